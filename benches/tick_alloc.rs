@@ -0,0 +1,129 @@
+//! Per-tick allocation benchmark for `WorldService` event collection.
+//!
+//! ```text
+//! cargo bench --bench tick_alloc
+//! ```
+//!
+//! Counts heap allocations with a wrapping global allocator while 64
+//! participants stand still (steady state: no cells stream, every
+//! participant gets a transform).  `tick()` builds fresh `TickEvents` each
+//! call; `tick_into()` reuses one buffer, so after warm-up it should report
+//! zero allocations per tick.  Proximity is disabled because its pair
+//! bookkeeping is measured separately from event collection.
+
+use janet_operations::physics::{
+    types::{
+        OntologyId, PhysicsRegistryConfig, Rapier2DConfig, SimulationMetadata, SimulationType, Tier,
+    },
+    PhysicsRegistry, Rapier2DSimulation,
+};
+use janet_world::{
+    service::{TickEvents, WorldService},
+    structure::World,
+    terrain::HeightmapTerrain,
+    types::{Vec3, WorldServiceConfig},
+};
+use parking_lot::RwLock;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+const PARTICIPANTS: usize = 64;
+const TICKS: usize = 1_000;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn make_service() -> WorldService {
+    let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+    let world = Arc::new(World::new(terrain));
+
+    let mut registry = PhysicsRegistry::new(PhysicsRegistryConfig::default());
+    let metadata = SimulationMetadata {
+        id: "bench".to_string(),
+        mandate_id: "_bench".to_string(),
+        ontology: OntologyId::Custom {
+            id: "Rapier2D".to_string(),
+        },
+        tier: Tier::Decidable,
+        overlays: vec![],
+        simulation_type: SimulationType::Rapier2D,
+        created_at_frame: 0,
+        name: "Bench Physics".to_string(),
+        description: None,
+        generator_id: None,
+    };
+    registry.set_default_simulation(Box::new(Rapier2DSimulation::new(
+        metadata,
+        Rapier2DConfig::default(),
+    )));
+
+    let config = WorldServiceConfig {
+        activation_radius: 2,
+        proximity_radius: 0.0,
+        ..Default::default()
+    };
+    let mut svc = WorldService::new(config, Arc::new(RwLock::new(registry)), world);
+    for i in 0..PARTICIPANTS {
+        let pos = Vec3::new((i % 8) as f32 * 25.0, (i / 8) as f32 * 25.0, 0.0);
+        svc.register_participant(format!("participant-{}", i), pos);
+    }
+    svc
+}
+
+/// Run `tick` after a warm-up and report allocations and time per tick.
+fn measure(name: &str, mut tick: impl FnMut()) {
+    for _ in 0..10 {
+        tick();
+    }
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..TICKS {
+        tick();
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{:<10} {:>8.2} allocations/tick {:>10.2?}/tick",
+        name,
+        allocations as f64 / TICKS as f64,
+        elapsed / TICKS as u32
+    );
+}
+
+fn main() {
+    println!("{} participants, {} ticks", PARTICIPANTS, TICKS);
+
+    let mut svc = make_service();
+    measure("tick", || {
+        std::hint::black_box(svc.tick().expect("tick"));
+    });
+
+    let mut svc = make_service();
+    let mut events = TickEvents::default();
+    measure("tick_into", || {
+        svc.tick_into(&mut events).expect("tick");
+        std::hint::black_box(&events);
+    });
+}
@@ -0,0 +1,126 @@
+//! Operator commands for live servers (`world.cmd.admin`, driven by the
+//! `janet-world-ctl` binary).
+//!
+//! Every action produces an [`AdminReply`]; failures are replies with
+//! `ok: false` rather than errors so the caller always gets a readable
+//! message.
+
+use crate::console;
+use crate::protocol::{AdminAction, AdminReply};
+use crate::service::WorldService;
+use crate::types::CellRegion;
+
+/// Checkpoint name used by [`AdminAction::Save`].
+pub const DEFAULT_CHECKPOINT: &str = "latest";
+
+/// `true` if a request carrying `token` may run on a server configured with
+/// `expected` (no configured token means admin is open).
+pub fn authorised(expected: Option<&str>, token: Option<&str>) -> bool {
+    match expected {
+        None => true,
+        Some(expected) => token == Some(expected),
+    }
+}
+
+/// Run one admin action against the service.
+pub fn execute(svc: &mut WorldService, action: AdminAction) -> AdminReply {
+    match action {
+        AdminAction::Stats => {
+            let stats = svc.stats();
+            AdminReply::success(format!(
+                "{} active cells, {} objects, {} participants, {} ticks",
+                stats.active_cells,
+                stats.total_objects,
+                stats.tracked_participants,
+                stats.total_ticks
+            ))
+            .with_data(serde_json::to_value(&stats).unwrap_or_default())
+        }
+        AdminAction::Kick { participant_id } => {
+            if svc.participant_position(&participant_id).is_none() {
+                return AdminReply::failure(format!("unknown participant '{}'", participant_id));
+            }
+            svc.unregister_participant(&participant_id);
+            AdminReply::success(format!("kicked {}", participant_id))
+        }
+        AdminAction::Save => checkpoint(svc, DEFAULT_CHECKPOINT),
+        AdminAction::Checkpoint { name } => checkpoint(svc, &name),
+        AdminAction::SetTickRate { hz } => match svc.set_tick_rate(hz) {
+            Ok(()) => AdminReply::success(format!("tick rate set to {} Hz", hz)),
+            Err(e) => AdminReply::failure(e.to_string()),
+        },
+        AdminAction::SetTimescale { scale } => match svc.set_timescale(scale) {
+            Ok(()) => AdminReply::success(format!("timescale set to {}", scale)),
+            Err(e) => AdminReply::failure(e.to_string()),
+        },
+        AdminAction::Pregenerate { cx, cy, radius } => {
+            let report = svc.pregenerate(CellRegion::around(cx, cy, radius.max(0)), &[0]);
+            AdminReply::success(format!(
+                "generated {} of {} chunks around ({}, {}) in {:.0} ms",
+                report.generated, report.chunks, cx, cy, report.elapsed_ms
+            ))
+            .with_data(serde_json::json!(report))
+        }
+        AdminAction::Console { line } => match console::run(svc, &line) {
+            Ok(output) => AdminReply::success(output),
+            Err(e) => AdminReply::failure(e.to_string()),
+        },
+        AdminAction::Events => {
+            let scheduler = svc.event_scheduler();
+            let events: Vec<_> = scheduler
+                .events()
+                .iter()
+                .map(|e| {
+                    serde_json::json!({
+                        "event_id": e.event.event_id,
+                        "kind": e.event.kind,
+                        "trigger": e.trigger,
+                        "enabled": e.enabled,
+                        "active": scheduler.is_active(&e.event.event_id),
+                    })
+                })
+                .collect();
+            AdminReply::success(format!(
+                "{} scheduled events, {} active",
+                events.len(),
+                scheduler.active().count()
+            ))
+            .with_data(serde_json::json!({ "events": events }))
+        }
+        AdminAction::TriggerEvent { event_id } => match svc.trigger_event(&event_id) {
+            Ok(event) => AdminReply::success(format!("started event '{}'", event_id))
+                .with_data(serde_json::to_value(&event).unwrap_or_default()),
+            Err(e) => AdminReply::failure(e.to_string()),
+        },
+        AdminAction::EndEvent { event_id } => match svc.end_event(&event_id) {
+            Ok(_) => AdminReply::success(format!("ended event '{}'", event_id)),
+            Err(e) => AdminReply::failure(e.to_string()),
+        },
+        AdminAction::SetEventEnabled { event_id, enabled } => {
+            match svc.set_event_enabled(&event_id, enabled) {
+                Ok(()) => AdminReply::success(format!(
+                    "{} event '{}'",
+                    if enabled { "enabled" } else { "disabled" },
+                    event_id
+                )),
+                Err(e) => AdminReply::failure(e.to_string()),
+            }
+        }
+        AdminAction::UpdateStructure {
+            structure_id,
+            update,
+        } => match svc.update_structure(&structure_id, &update) {
+            Ok(updated) => AdminReply::success(format!("updated structure '{}'", structure_id))
+                .with_data(serde_json::to_value(&updated).unwrap_or_default()),
+            Err(e) => AdminReply::failure(e.to_string()),
+        },
+    }
+}
+
+fn checkpoint(svc: &WorldService, name: &str) -> AdminReply {
+    match svc.write_checkpoint(name) {
+        Ok(path) => AdminReply::success(format!("wrote checkpoint {}", path.display()))
+            .with_data(serde_json::json!({ "path": path })),
+        Err(e) => AdminReply::failure(format!("checkpoint '{}' failed: {}", name, e)),
+    }
+}
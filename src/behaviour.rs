@@ -0,0 +1,140 @@
+//! Built-in steering for server entities: wander, flee and pursue.
+//!
+//! An entity with a [`Behaviour`] has its velocity set every tick, before
+//! entities move, from where it is and what is around it.  The service
+//! looks up the other party (the nearest participant within `range`, or a
+//! pursuit target) and [`Behaviour::steer`] turns that into a velocity, so
+//! the behaviours themselves stay pure and deterministic.
+//!
+//! Behaviours are tagged by `kind`, e.g. in a spawner:
+//!
+//! ```toml
+//! behaviour = { kind = "wander", radius = 12.0, speed = 1.5 }
+//! ```
+
+use crate::worldgen::unit;
+use serde::{Deserialize, Serialize};
+
+/// Distance from a wander waypoint that counts as having reached it.
+pub const WAYPOINT_REACHED: f32 = 0.5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Behaviour {
+    /// Stroll between random spots within `radius` of where the entity
+    /// spawned.
+    Wander { radius: f32, speed: f32 },
+    /// Run straight away from the nearest participant within `range`;
+    /// stand still while there is none.
+    Flee { range: f32, speed: f32 },
+    /// Close in on `target` (or, when unset, the nearest participant)
+    /// while it is within `range`, stopping `stop_distance` short of it.
+    Pursue {
+        #[serde(default)]
+        target: Option<String>,
+        range: f32,
+        speed: f32,
+        #[serde(default = "default_stop_distance")]
+        stop_distance: f32,
+    },
+}
+
+fn default_stop_distance() -> f32 {
+    1.0
+}
+
+/// Per-entity memory of a behaviour (the current wander waypoint).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Steering {
+    waypoint: Option<[f32; 2]>,
+    /// Waypoints picked so far, for deterministic picks.
+    picks: u64,
+}
+
+impl Behaviour {
+    /// How far the behaviour looks for the other party (`None` = it
+    /// doesn't).
+    pub fn range(&self) -> Option<f32> {
+        match self {
+            Self::Wander { .. } => None,
+            Self::Flee { range, .. } | Self::Pursue { range, .. } => Some(*range),
+        }
+    }
+
+    /// Whether every speed, range and distance is finite and not negative.
+    pub fn is_valid(&self) -> bool {
+        let values: &[f32] = match self {
+            Self::Wander { radius, speed } => &[*radius, *speed],
+            Self::Flee { range, speed } => &[*range, *speed],
+            Self::Pursue {
+                range,
+                speed,
+                stop_distance,
+                ..
+            } => &[*range, *speed, *stop_distance],
+        };
+        values.iter().all(|v| v.is_finite() && *v >= 0.0)
+    }
+
+    /// Velocity (metres per second along `x` and `y`) for an entity at
+    /// `pos` that spawned at `home`.  `other` is the party it flees from or
+    /// pursues, already limited to [`range`](Self::range).  `key` seeds the
+    /// wander waypoints.
+    pub fn steer(
+        &self,
+        steering: &mut Steering,
+        pos: [f32; 2],
+        home: [f32; 2],
+        other: Option<[f32; 2]>,
+        key: &str,
+    ) -> [f32; 2] {
+        match self {
+            Self::Wander { radius, speed } => {
+                let reached = |w: &[f32; 2]| distance(pos, *w) <= WAYPOINT_REACHED;
+                if steering.waypoint.as_ref().is_none_or(reached) {
+                    let key = format!("{}:wander:{}", key, steering.picks);
+                    steering.picks += 1;
+                    let angle = unit(&key, 0) * std::f32::consts::TAU;
+                    let dist = radius * unit(&key, 1).sqrt();
+                    steering.waypoint =
+                        Some([home[0] + dist * angle.cos(), home[1] + dist * angle.sin()]);
+                }
+                toward(
+                    pos,
+                    steering.waypoint.unwrap_or(pos),
+                    *speed,
+                    WAYPOINT_REACHED,
+                )
+            }
+            Self::Flee { speed, .. } => match other {
+                Some(threat) => {
+                    let [vx, vy] = toward(pos, threat, *speed, 0.0);
+                    [-vx, -vy]
+                }
+                None => [0.0, 0.0],
+            },
+            Self::Pursue {
+                speed,
+                stop_distance,
+                ..
+            } => match other {
+                Some(target) => toward(pos, target, *speed, *stop_distance),
+                None => [0.0, 0.0],
+            },
+        }
+    }
+}
+
+fn distance(a: [f32; 2], b: [f32; 2]) -> f32 {
+    (b[0] - a[0]).hypot(b[1] - a[1])
+}
+
+/// `speed` along the direction from `from` to `to`, or zero within `stop`
+/// of it (or on top of it).
+fn toward(from: [f32; 2], to: [f32; 2], speed: f32, stop: f32) -> [f32; 2] {
+    let d = distance(from, to);
+    if d <= stop || d == 0.0 {
+        return [0.0, 0.0];
+    }
+    [(to[0] - from[0]) / d * speed, (to[1] - from[1]) / d * speed]
+}
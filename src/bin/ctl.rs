@@ -0,0 +1,316 @@
+//! janet-world-ctl binary
+//!
+//! Admin CLI for live world servers.  Each subcommand becomes one
+//! `world.cmd.admin` request (see `janet_world::admin`):
+//!
+//! ```text
+//! janet-world-ctl stats
+//! janet-world-ctl kick bob
+//! janet-world-ctl checkpoint before-event
+//! janet-world-ctl set-tick-rate 20
+//! janet-world-ctl set-timescale 0.25
+//! janet-world-ctl pregenerate 0 0 8
+//! janet-world-ctl events
+//! janet-world-ctl trigger-event nightfall-meteors
+//! janet-world-ctl disable-event raid
+//! janet-world-ctl move-structure rock-7 12.5 -4 --yaw 1.57
+//! janet-world-ctl console tp alice 120 40
+//! janet-world-ctl console                    # interactive console prompt
+//! janet-world-ctl --json kick bob            # machine-readable output
+//! ```
+//!
+//! The tool joins the bus exactly like any other client (session,
+//! participant id, endpoint).  Servers started with `WORLD_ADMIN_TOKEN`
+//! additionally require the same value via `--token`.
+//!
+//! Requests are published fire-and-forget on `world.{session}.cmd.admin`:
+//! the server logs every action and broadcasts the resulting `AdminReply`
+//! on `world.{session}.admin.reply`.
+//!
+//! | Key                        | Default                 | Description          |
+//! |----------------------------|-------------------------|----------------------|
+//! | `WORLD_SESSION`            | `default`               | Janet session name   |
+//! | `WORLD_CTL_ID`             | `world-ctl`             | Bus participant ID   |
+//! | `WORLD_ENDPOINT`           | `nats://localhost:4222` | Transport endpoint   |
+//! | `WORLD_ADMIN_TOKEN`        | *(unset)*               | Admin shared secret  |
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use clap::{Parser, Subcommand};
+use janet_client::{ClientBuilder, JanetExecutor};
+use janet_world::console::{self, ConsoleCommand, HELP};
+use janet_world::protocol::{subjects, AdminAction, CmdAdmin, StructureUpdate};
+use std::io::{BufRead, Write};
+
+// ---------------------------------------------------------------------------
+// CLI
+// ---------------------------------------------------------------------------
+
+#[derive(Parser, Debug)]
+#[command(name = "janet-world-ctl", about = "Janet World admin CLI", version)]
+struct Args {
+    /// Janet session to join
+    #[arg(long, env = "WORLD_SESSION", default_value = "default")]
+    session: String,
+
+    /// Bus participant ID
+    #[arg(long, env = "WORLD_CTL_ID", default_value = "world-ctl")]
+    participant_id: String,
+
+    /// NATS endpoint
+    #[arg(long, env = "WORLD_ENDPOINT", default_value = "nats://localhost:4222")]
+    endpoint: String,
+
+    /// Admin shared secret (must match the server's WORLD_ADMIN_TOKEN)
+    #[arg(long, env = "WORLD_ADMIN_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+
+    /// Print machine-readable JSON instead of human-friendly text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Request world statistics
+    Stats,
+    /// Drop a participant from the world
+    Kick { participant_id: String },
+    /// Write the default checkpoint
+    Save,
+    /// Write a named checkpoint
+    Checkpoint { name: String },
+    /// Change the simulation tick rate (Hz)
+    SetTickRate { hz: f32 },
+    /// Slow down (< 1) or speed up (> 1) simulated time
+    SetTimescale { scale: f32 },
+    /// Generate terrain chunks around a chunk coordinate
+    Pregenerate {
+        #[arg(allow_hyphen_values = true)]
+        cx: i32,
+        #[arg(allow_hyphen_values = true)]
+        cy: i32,
+        /// Chebyshev radius in chunks
+        radius: i32,
+    },
+    /// Run a console line, or start an interactive prompt when omitted
+    Console {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        line: Vec<String>,
+    },
+    /// List scheduled world events
+    Events,
+    /// Start a scheduled world event now
+    TriggerEvent { event_id: String },
+    /// End an active world event early
+    EndEvent { event_id: String },
+    /// Resume a world event's schedule
+    EnableEvent { event_id: String },
+    /// Pause a world event's schedule
+    DisableEvent { event_id: String },
+    /// Move a structure, keeping its height unless --z is given
+    MoveStructure {
+        structure_id: String,
+        #[arg(allow_hyphen_values = true)]
+        x: f32,
+        #[arg(allow_hyphen_values = true)]
+        y: f32,
+        #[arg(long, allow_hyphen_values = true)]
+        z: Option<f32>,
+        /// New yaw in radians
+        #[arg(long, allow_hyphen_values = true)]
+        yaw: Option<f32>,
+    },
+}
+
+impl Command {
+    /// The admin action for every non-interactive subcommand.
+    fn into_action(self) -> Option<AdminAction> {
+        Some(match self {
+            Command::Stats => AdminAction::Stats,
+            Command::Kick { participant_id } => AdminAction::Kick { participant_id },
+            Command::Save => AdminAction::Save,
+            Command::Checkpoint { name } => AdminAction::Checkpoint { name },
+            Command::SetTickRate { hz } => AdminAction::SetTickRate { hz },
+            Command::SetTimescale { scale } => AdminAction::SetTimescale { scale },
+            Command::Pregenerate { cx, cy, radius } => AdminAction::Pregenerate { cx, cy, radius },
+            Command::Console { line } if line.is_empty() => return None,
+            Command::Console { line } => AdminAction::Console {
+                line: line.join(" "),
+            },
+            Command::Events => AdminAction::Events,
+            Command::TriggerEvent { event_id } => AdminAction::TriggerEvent { event_id },
+            Command::EndEvent { event_id } => AdminAction::EndEvent { event_id },
+            Command::EnableEvent { event_id } => AdminAction::SetEventEnabled {
+                event_id,
+                enabled: true,
+            },
+            Command::DisableEvent { event_id } => AdminAction::SetEventEnabled {
+                event_id,
+                enabled: false,
+            },
+            Command::MoveStructure {
+                structure_id,
+                x,
+                y,
+                z,
+                yaw,
+            } => AdminAction::UpdateStructure {
+                structure_id,
+                update: StructureUpdate {
+                    x: Some(x),
+                    y: Some(y),
+                    z,
+                    rotation_y: yaw,
+                    ..Default::default()
+                },
+            },
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Entry point
+// ---------------------------------------------------------------------------
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let client: JanetExecutor = ClientBuilder::new()
+        .session(&args.session)
+        .participant(&args.participant_id, vec!["admin".to_string()])
+        .coordinator_url(&args.endpoint)
+        .connect()
+        .await
+        .context("Failed to connect janet-world-ctl to janet bus")?;
+
+    let ctl = Ctl {
+        client,
+        session: args.session,
+        token: args.token,
+        json: args.json,
+    };
+
+    match args.command.into_action() {
+        Some(action) => ctl.send(action).await,
+        None => ctl.console_prompt().await,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Sending
+// ---------------------------------------------------------------------------
+
+struct Ctl {
+    client: JanetExecutor,
+    session: String,
+    token: Option<String>,
+    json: bool,
+}
+
+impl Ctl {
+    async fn send(&self, action: AdminAction) -> Result<()> {
+        // Catch console typos locally instead of round-tripping them.
+        if let AdminAction::Console { line } = &action {
+            if console::parse(line)? == ConsoleCommand::Help {
+                println!("{}", HELP);
+                return Ok(());
+            }
+        }
+
+        let request = CmdAdmin {
+            action,
+            token: self.token.clone(),
+        };
+        let payload = serde_json::to_vec(&request)?;
+        let subject = subjects::session(subjects::CMD_ADMIN, &self.session);
+        let reply_subject = subjects::session(subjects::ADMIN_REPLY, &self.session);
+        self.client
+            .publish(&subject, Bytes::from(payload))
+            .await
+            .with_context(|| format!("Failed to publish to {}", subject))?;
+
+        if self.json {
+            let shown = CmdAdmin {
+                token: None,
+                ..request
+            };
+            println!(
+                "{}",
+                serde_json::json!({
+                    "subject": subject,
+                    "session": self.session,
+                    "request": shown,
+                    "reply_subject": reply_subject,
+                })
+            );
+        } else {
+            println!(
+                "sent {} to session '{}' (result on {})",
+                describe(&request.action),
+                self.session,
+                reply_subject
+            );
+        }
+        Ok(())
+    }
+
+    async fn console_prompt(&self) -> Result<()> {
+        println!("connected to session '{}' — type 'help'", self.session);
+        let stdin = std::io::stdin();
+        loop {
+            print!("world> ");
+            std::io::stdout().flush()?;
+
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line)? == 0 {
+                break;
+            }
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if matches!(line, "quit" | "exit") {
+                break;
+            }
+            let action = AdminAction::Console {
+                line: line.to_string(),
+            };
+            if let Err(e) = self.send(action).await {
+                eprintln!("{:#}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One-line, human-friendly description of an action.
+fn describe(action: &AdminAction) -> String {
+    match action {
+        AdminAction::Stats => "stats request".to_string(),
+        AdminAction::Kick { participant_id } => format!("kick of '{}'", participant_id),
+        AdminAction::Save => "save".to_string(),
+        AdminAction::Checkpoint { name } => format!("checkpoint '{}'", name),
+        AdminAction::SetTickRate { hz } => format!("tick rate change to {} Hz", hz),
+        AdminAction::SetTimescale { scale } => format!("timescale change to {}", scale),
+        AdminAction::Pregenerate { cx, cy, radius } => {
+            format!("pregenerate of radius {} around ({}, {})", radius, cx, cy)
+        }
+        AdminAction::Console { line } => format!("console '{}'", line),
+        AdminAction::Events => "event list request".to_string(),
+        AdminAction::TriggerEvent { event_id } => format!("start of event '{}'", event_id),
+        AdminAction::EndEvent { event_id } => format!("end of event '{}'", event_id),
+        AdminAction::SetEventEnabled { event_id, enabled } => format!(
+            "{} of event '{}'",
+            if *enabled { "enable" } else { "disable" },
+            event_id
+        ),
+        AdminAction::UpdateStructure { structure_id, .. } => {
+            format!("update of structure '{}'", structure_id)
+        }
+    }
+}
@@ -10,13 +10,75 @@
 //! | `WORLD_SESSION`            | `default`           | Janet session name             |
 //! | `WORLD_PARTICIPANT_ID`     | `world-service`     | Bus participant ID             |
 //! | `WORLD_ENDPOINT`           | `nats://localhost:4222` | Transport endpoint         |
-//! | `WORLD_TICK_RATE_HZ`       | `30`                | Physics / streaming tick rate  |
+//! | `WORLD_TICK_RATE_HZ`       | `30`                | Physics / transform tick rate |
 //! | `WORLD_SEED`               | `42`                | Terrain seed                   |
 //! | `WORLD_CELL_SIZE`          | `10.0`              | Streaming cell size (world units) |
 //! | `WORLD_TILE_SIZE_M`        | `2.0`               | Terrain tile size in metres    |
+//! | `WORLD_TREE_DENSITY`       | `0.02`              | Chance per tile of a tree or rock (0 = bare terrain) |
 //! | `WORLD_ACTIVATION_RADIUS`  | `16`                | Chebyshev streaming radius     |
+//! | `WORLD_CHUNK_STORE_DIR`    | *(unset)*           | Persist generated terrain chunks here |
+//! | `WORLD_HEIGHT_OVERRIDES_DIR` | *(unset)*         | Persist designer height overrides here |
+//! | `WORLD_HIBERNATION_DIR`    | *(unset)*           | Persist changed objects of deactivated cells here |
+//! | `WORLD_NOISE_AMPLITUDE`    | `1.0`               | Multiplier on every noise layer's weight |
+//! | `WORLD_NOISE_FREQUENCY`    | `1.0`               | Multiplier on every noise layer's frequency |
+//! | `WORLD_NOISE_OCTAVES`      | `0`                 | Noise layers to sum (0 = as configured) |
+//! | `WORLD_VERTICAL_EXAGGERATION` | `1.0`            | World units per unit of generated height |
+//! | `WORLD_EROSION_ITERATIONS` | `0`                 | Erosion passes per chunk (0 = off) |
+//! | `WORLD_HYDROLOGY`          | `false`             | Carve rivers and fill lakes    |
+//! | `WORLD_WATER_TABLE`        | `0.18`              | Lake surface height (with hydrology) |
+//! | `WORLD_SEA_LEVEL`          | *(unset)*           | Global ocean height; blocks walking into the sea |
+//! | `WORLD_SEED_REGIONS`       | *(unset)*           | JSON file of `SeedRegion`s with their own seeds |
+//! | `WORLD_SPAWN_POINTS`       | *(unset)*           | JSON file of `[x, y]` spawn points |
+//! | `WORLD_BOUNDS`             | *(unset)*           | JSON `WorldBounds`: playable cells and edge fill (unbounded when unset) |
+//! | `WORLD_PREGENERATE`        | `false`             | Generate terrain around the spawn points before serving |
+//! | `WORLD_GEN`                | *(unset)*           | TOML `WorldGenConfig`: noise, biomes, sea level, structure density |
+//! | `WORLD_ELEVATION`          | *(unset)*           | JSON `ElevationConfig`: real-world GeoTIFF/SRTM terrain over the procedural one |
+//! | `WORLD_EVENTS_FILE`        | *(unset)*           | JSON schedule of scripted world events (`scheduler`) |
+//! | `WORLD_STRUCTURES_FILE`    | *(unset)*           | JSON structure file of authored props (`structure`) |
+//! | `WORLD_STATE_FILE`         | *(unset)*           | World state loaded on startup and saved on SIGINT (`persistence`) |
+//! | `WORLD_SCENE_FILE`         | *(unset)*           | glTF (`.gltf`) or JSON scene list imported as structures (`scene_import`) |
+//! | `WORLD_SHARD_ID`           | *(unset)*           | Run as this shard (requires `WORLD_SHARD_MAP`) |
+//! | `WORLD_SHARD_MAP`          | *(unset)*           | JSON file: `region_size` and `regions` owners |
+//! | `WORLD_SHARD_HANDOFF_TIMEOUT_S` | `2`            | Seconds to wait for a handoff ack before keeping the participant |
+//! | `WORLD_AFK_TIMEOUT_S`      | `0`                 | Idle seconds before a participant is downgraded (0 = off) |
+//! | `WORLD_AFK_RADIUS`         | `2`                 | Streaming radius (cells) while AFK |
+//! | `WORLD_AFK_TRANSFORM_INTERVAL_S` | `1.0`         | Seconds between an AFK participant's transforms |
+//! | `WORLD_EMOTE_RANGE`        | `30.0`              | Hearing distance for `world.entity.emote` |
+//! | `WORLD_EMOTE_INTERVAL_S`   | `0.5`               | Minimum seconds between one entity's emotes |
+//! | `WORLD_BUILDABLES`         | *(unset)*           | JSON file: `type_id` → `BuildableStructure` participants may place |
+//! | `WORLD_BUILD_REACH`        | `8.0`               | Furthest a participant may place a structure |
+//! | `WORLD_INTERACT_REACH`     | `3.0`               | Furthest a participant may operate a door or gate from |
+//! | `WORLD_STREAM_INTERVAL_S`  | `0`                 | Seconds between streaming passes (0 = every tick) |
+//! | `WORLD_SEND_CHUNK_HEIGHTS` | `false`             | Send `world.chunk.data` heights for chunks clients cannot regenerate |
+//! | `WORLD_VERTICAL_CELL_SIZE` | `0`                 | Height of a vertical streaming layer (0 = any altitude) |
+//! | `WORLD_VERTICAL_ACTIVATION_RADIUS` | `2`         | Vertical streaming radius in layers |
+//! | `WORLD_STRUCTURE_NEAR_RADIUS` | `4`              | Streaming radius (cells) of `near` tier structures |
+//! | `WORLD_STRUCTURE_DETAIL_RADIUS` | `1`            | Streaming radius (cells) of `detail` tier structures |
+//! | `WORLD_MAX_MOVE_SPEED`     | `5.0`               | Participant speed (m/s) at a full `intent.move` |
+//! | `WORLD_CELL_SUBJECTS`      | `false`             | Publish transforms, spawns and structure updates per interest cell |
+//! | `WORLD_INTEREST_CELL_SIZE` | `64.0`              | Side of an interest cell in metres |
+//! | `WORLD_TRANSFORM_POSITION_EPSILON` | `0.01`      | Movement (m) that makes an entity's transform due |
+//! | `WORLD_TRANSFORM_ROTATION_EPSILON` | `0.01`      | Turn (rad) that makes an entity's transform due |
+//! | `WORLD_TRANSFORM_KEEPALIVE_S` | `1.0`            | Seconds between transforms of unchanged entities (0 = every tick) |
+//! | `WORLD_CHUNK_CACHE_MB`     | `256`               | Terrain chunk cache budget, LRU (0 = unbounded) |
+//! | `WORLD_SLOW_CHUNK_MS`      | `50`                | Log terrain chunk builds slower than this (0 = off) |
+//! | `WORLD_GEN_WORKERS`        | `2`                 | Background chunk generation threads (0 = in tick) |
+//! | `WORLD_HANDLE_TRANSFORMS`  | `false`             | Batched, handle-addressed transforms (protocol v3) |
+//! | `WORLD_TRANSFORM_KEYFRAME_INTERVAL` | `0`        | Delta-encode batches, keyframe every N (protocol v4; 0 = off) |
+//! | `WORLD_POPULATION_INTERVAL_S` | `10`             | Seconds between ambient population passes (0 = off) |
+//! | `WORLD_RESUME_GRACE_S`     | `30`                | Seconds a leaving participant's entity waits for a resume (0 = off) |
+//! | `WORLD_CHECKPOINT_DIR`     | *(unset)*           | Where admin save/checkpoint write |
+//! | `WORLD_EXPORT_DIR`         | *(unset)*           | Where named `world.cmd.terrain.export` heightmaps go |
+//! | `WORLD_ADMIN_TOKEN`        | *(unset)*           | Secret required by `world.cmd.admin` |
+//! | `WORLD_STANDBY`            | `false`             | Wait as a warm standby and take over on failover |
+//! | `WORLD_HEARTBEAT_INTERVAL_S` | `1.0`             | Seconds between `world.heartbeat` broadcasts |
+//! | `WORLD_FAILOVER_TIMEOUT_S` | `5.0`               | Heartbeat silence before a standby takes over |
+//! | `WORLD_MIRROR_INTERVAL_S`  | `0`                 | Seconds between mirror checkpoints (0 = off) |
+//! | `WORLD_LEGACY_SUBJECTS`    | `false`             | Also serve unscoped `world.*` subjects next to `world.{session}.*` |
+//! | `WORLD_SNAPSHOT_CACHE_S`   | `0`                 | Seconds a snapshot reply is reused for later joiners (0 = same tick) |
+//! | `WORLD_SNAPSHOT_PAGE_BYTES` | `262144`           | Largest page of a paged (`paged: true`) snapshot reply |
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use janet_operations::physics::{
     types::{
@@ -26,12 +88,25 @@ use janet_operations::physics::{
 };
 use janet_world::{
     bus::{WorldBusAgent, WorldBusConfig},
+    elevation_terrain::{ElevationConfig, ElevationTerrain},
+    erosion::ErosionConfig,
+    hibernation::HibernationStore,
+    scene_import,
+    scheduler::EventScheduler,
     service::WorldService,
     structure::World,
-    terrain::HeightmapTerrain,
-    types::WorldServiceConfig,
+    terrain::{CacheBudget, HeightmapTerrain, TerrainSource},
+    types::{
+        AfkConfig, BuildableStructure, HydrologyConfig, NoiseParams, SeedRegion, ShardConfig,
+        ShardRegion, WorldBounds, WorldServiceConfig,
+    },
+    validation::ConfigValidator,
+    worldgen::WorldGenConfig,
 };
 use parking_lot::RwLock;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 // ---------------------------------------------------------------------------
@@ -69,9 +144,300 @@ struct Args {
     #[arg(long, env = "WORLD_TILE_SIZE_M", default_value_t = 2.0)]
     tile_size_m: f32,
 
+    /// Chance that each terrain tile of an activating cell holds a tree or
+    /// rock (0 disables vegetation)
+    #[arg(long, env = "WORLD_TREE_DENSITY", default_value_t = 0.02)]
+    tree_density: f32,
+
     /// Streaming activation radius (Chebyshev, in cells)
     #[arg(long, env = "WORLD_ACTIVATION_RADIUS", default_value_t = 16)]
     activation_radius: i32,
+
+    /// Directory for persisted terrain chunks (disabled when unset)
+    #[arg(long, env = "WORLD_CHUNK_STORE_DIR")]
+    chunk_store_dir: Option<PathBuf>,
+
+    /// Directory for designer height overrides (`world.cmd.terrain.set_heights`);
+    /// kept in memory only when unset
+    #[arg(long, env = "WORLD_HEIGHT_OVERRIDES_DIR")]
+    height_overrides_dir: Option<PathBuf>,
+
+    /// Directory for the changed objects of deactivated cells; kept in
+    /// memory only when unset
+    #[arg(long, env = "WORLD_HIBERNATION_DIR")]
+    hibernation_dir: Option<PathBuf>,
+
+    /// Erosion passes applied to generated chunks (0 disables erosion)
+    #[arg(long, env = "WORLD_EROSION_ITERATIONS", default_value_t = 0)]
+    erosion_iterations: u32,
+
+    /// Carve rivers and fill lakes in generated terrain
+    #[arg(long, env = "WORLD_HYDROLOGY", default_value_t = false)]
+    hydrology: bool,
+
+    /// Lake surface height in normalised elevation (used with --hydrology)
+    #[arg(long, env = "WORLD_WATER_TABLE", default_value_t = 0.18)]
+    water_table: f32,
+
+    /// Global ocean surface height (no sea when unset)
+    #[arg(long, env = "WORLD_SEA_LEVEL")]
+    sea_level: Option<f32>,
+
+    /// Multiplier on every noise layer's weight (overall relief)
+    #[arg(long, env = "WORLD_NOISE_AMPLITUDE", default_value_t = 1.0)]
+    noise_amplitude: f64,
+
+    /// Multiplier on every noise layer's frequency (higher = smaller hills)
+    #[arg(long, env = "WORLD_NOISE_FREQUENCY", default_value_t = 1.0)]
+    noise_frequency: f64,
+
+    /// Noise layers to sum; extra octaves double the frequency and halve the
+    /// weight (0 keeps the configured layers)
+    #[arg(long, env = "WORLD_NOISE_OCTAVES", default_value_t = 0)]
+    noise_octaves: u32,
+
+    /// World units per unit of generated height (flat plains below 1,
+    /// dramatic mountains above)
+    #[arg(long, env = "WORLD_VERTICAL_EXAGGERATION", default_value_t = 1.0)]
+    vertical_exaggeration: f32,
+
+    /// JSON file with a list of seed regions (rectangles generated from
+    /// their own seed)
+    #[arg(long, env = "WORLD_SEED_REGIONS")]
+    seed_regions: Option<PathBuf>,
+
+    /// JSON file with a list of `[x, y]` spawn points
+    #[arg(long, env = "WORLD_SPAWN_POINTS")]
+    spawn_points: Option<PathBuf>,
+
+    /// JSON file with the world bounds (playable cell columns, ocean or
+    /// cliff fill beyond them)
+    #[arg(long, env = "WORLD_BOUNDS")]
+    world_bounds: Option<PathBuf>,
+
+    /// Generate and cache the terrain within the activation radius of every
+    /// spawn point before joining the bus, so the first players don't hitch
+    #[arg(long, env = "WORLD_PREGENERATE", default_value_t = false)]
+    pregenerate: bool,
+
+    /// TOML world generation config (noise layers, biome bands, sea level,
+    /// structure density); seed and chunk layout still come from the CLI
+    #[arg(long, env = "WORLD_GEN")]
+    world_gen: Option<PathBuf>,
+
+    /// JSON file with an elevation config (GeoTIFFs / SRTM directory and
+    /// the world origin); procedural terrain fills in outside the data
+    #[arg(long, env = "WORLD_ELEVATION")]
+    elevation: Option<PathBuf>,
+
+    /// JSON schedule of scripted world events (meteor showers, invasions,
+    /// weather fronts)
+    #[arg(long, env = "WORLD_EVENTS_FILE")]
+    events_file: Option<PathBuf>,
+
+    /// JSON structure file of authored props, placed over the scattered
+    /// structures (same ids replace them)
+    #[arg(long, env = "WORLD_STRUCTURES_FILE")]
+    structures_file: Option<PathBuf>,
+
+    /// World state loaded on startup (when the file exists) and saved on
+    /// SIGINT: structures, server entities, terrain edits and participant
+    /// positions
+    #[arg(long, env = "WORLD_STATE_FILE")]
+    state_file: Option<PathBuf>,
+
+    /// glTF (`.gltf`) or JSON scene list whose mesh nodes are imported as
+    /// structures, after the structure file
+    #[arg(long, env = "WORLD_SCENE_FILE")]
+    scene_file: Option<PathBuf>,
+
+    /// Shard id of this instance (all subjects move into its namespace)
+    #[arg(long, env = "WORLD_SHARD_ID", requires = "shard_map")]
+    shard_id: Option<String>,
+
+    /// JSON file with the super-region size and owner of each super-region,
+    /// shared by every shard
+    #[arg(long, env = "WORLD_SHARD_MAP", requires = "shard_id")]
+    shard_map: Option<PathBuf>,
+
+    /// Seconds to wait for the target shard to acknowledge a handoff before
+    /// keeping the participant and offering it again
+    #[arg(long, env = "WORLD_SHARD_HANDOFF_TIMEOUT_S", default_value_t = 2.0)]
+    shard_handoff_timeout_s: f32,
+
+    /// Seconds without a movement intent before a participant's streaming
+    /// is downgraded (0 = never)
+    #[arg(long, env = "WORLD_AFK_TIMEOUT_S", default_value_t = 0.0)]
+    afk_timeout_s: f32,
+
+    /// Streaming activation radius for AFK participants
+    #[arg(long, env = "WORLD_AFK_RADIUS", default_value_t = 2)]
+    afk_radius: i32,
+
+    /// Seconds between transforms of an AFK participant
+    #[arg(long, env = "WORLD_AFK_TRANSFORM_INTERVAL_S", default_value_t = 1.0)]
+    afk_transform_interval_s: f32,
+
+    /// Distance within which participants receive an entity's emotes
+    #[arg(long, env = "WORLD_EMOTE_RANGE", default_value_t = 30.0)]
+    emote_range: f32,
+
+    /// Minimum seconds between two emotes of the same entity
+    #[arg(long, env = "WORLD_EMOTE_INTERVAL_S", default_value_t = 0.5)]
+    emote_interval_s: f32,
+
+    /// JSON file mapping each structure `type_id` participants may place to
+    /// its collider and steepest allowed slope (building is off when unset)
+    #[arg(long, env = "WORLD_BUILDABLES")]
+    buildables: Option<PathBuf>,
+
+    /// Furthest a participant may place a structure from itself
+    #[arg(long, env = "WORLD_BUILD_REACH", default_value_t = 8.0)]
+    build_reach: f32,
+
+    /// Furthest a participant may be from an interactive structure to
+    /// operate it
+    #[arg(long, env = "WORLD_INTERACT_REACH", default_value_t = 3.0)]
+    interact_reach: f32,
+
+    /// Seconds between streaming passes; physics and transforms keep the
+    /// tick rate (0 = stream every tick)
+    #[arg(long, env = "WORLD_STREAM_INTERVAL_S", default_value_t = 0.0)]
+    stream_interval_s: f32,
+
+    /// Send heights (world.chunk.data) for chunks clients can't regenerate
+    /// from their seed
+    #[arg(long, env = "WORLD_SEND_CHUNK_HEIGHTS", default_value_t = false)]
+    send_chunk_heights: bool,
+
+    /// Height of a vertical streaming layer (0 = stream columns at any
+    /// altitude)
+    #[arg(long, env = "WORLD_VERTICAL_CELL_SIZE", default_value_t = 0.0)]
+    vertical_cell_size: f32,
+
+    /// Vertical streaming radius in layers
+    #[arg(long, env = "WORLD_VERTICAL_ACTIVATION_RADIUS", default_value_t = 2)]
+    vertical_activation_radius: i32,
+
+    /// Streaming radius in cells of `near` tier structures (capped at the
+    /// activation radius)
+    #[arg(long, env = "WORLD_STRUCTURE_NEAR_RADIUS", default_value_t = 4)]
+    structure_near_radius: i32,
+
+    /// Streaming radius in cells of `detail` tier structures (capped at the
+    /// activation radius)
+    #[arg(long, env = "WORLD_STRUCTURE_DETAIL_RADIUS", default_value_t = 1)]
+    structure_detail_radius: i32,
+
+    /// Speed in metres per second of a participant moving at a full-length
+    /// `intent.move` direction
+    #[arg(long, env = "WORLD_MAX_MOVE_SPEED", default_value_t = 5.0)]
+    max_move_speed: f32,
+
+    /// Publish entity transforms and spawns and structure updates on
+    /// cell-scoped subjects, telling each client which cells to subscribe to
+    #[arg(long, env = "WORLD_CELL_SUBJECTS", default_value_t = false)]
+    cell_subjects: bool,
+
+    /// Side of an interest cell in metres (with --cell-subjects)
+    #[arg(long, env = "WORLD_INTEREST_CELL_SIZE", default_value_t = 64.0)]
+    interest_cell_size: f32,
+
+    /// Distance in metres an entity has to move before its transform is
+    /// published again
+    #[arg(long, env = "WORLD_TRANSFORM_POSITION_EPSILON", default_value_t = 0.01)]
+    transform_position_epsilon: f32,
+
+    /// Turn in radians an entity has to make before its transform is
+    /// published again
+    #[arg(long, env = "WORLD_TRANSFORM_ROTATION_EPSILON", default_value_t = 0.01)]
+    transform_rotation_epsilon: f32,
+
+    /// Seconds between transforms of entities that haven't changed
+    /// (0 = publish every tick)
+    #[arg(long, env = "WORLD_TRANSFORM_KEEPALIVE_S", default_value_t = 1.0)]
+    transform_keepalive_s: f32,
+
+    /// Terrain chunk cache budget in MiB, least recently used evicted first
+    /// (0 = unbounded)
+    #[arg(long, env = "WORLD_CHUNK_CACHE_MB", default_value_t = 256)]
+    chunk_cache_mb: usize,
+
+    /// Log terrain chunk builds slower than this many milliseconds (0 = off)
+    #[arg(long, env = "WORLD_SLOW_CHUNK_MS", default_value_t = 50.0)]
+    slow_chunk_ms: f32,
+
+    /// Background chunk generation threads (0 generates inside the tick)
+    #[arg(long, env = "WORLD_GEN_WORKERS", default_value_t = 2)]
+    gen_workers: usize,
+
+    /// Publish transforms as one handle-addressed batch per tick (requires
+    /// protocol version 3 clients)
+    #[arg(long, env = "WORLD_HANDLE_TRANSFORMS", default_value_t = false)]
+    handle_transforms: bool,
+
+    /// Delta-encode transform batches with a full keyframe every this many
+    /// batches (requires protocol version 4 clients; 0 disables)
+    #[arg(long, env = "WORLD_TRANSFORM_KEYFRAME_INTERVAL", default_value_t = 0)]
+    transform_keyframe_interval: u32,
+
+    /// Seconds between passes that stock active regions with the world
+    /// gen file's ambient population rules (0 disables)
+    #[arg(long, env = "WORLD_POPULATION_INTERVAL_S", default_value_t = 10.0)]
+    population_interval_s: f32,
+
+    /// Seconds a participant's entity is kept after it leaves, so a
+    /// reconnect with its resume token picks it up again (0 disables)
+    #[arg(long, env = "WORLD_RESUME_GRACE_S", default_value_t = 30.0)]
+    resume_grace_s: f32,
+
+    /// Directory for admin save/checkpoint snapshots (disabled when unset)
+    #[arg(long, env = "WORLD_CHECKPOINT_DIR")]
+    checkpoint_dir: Option<PathBuf>,
+
+    /// Directory for named heightmap exports (world.cmd.terrain.export
+    /// replies inline only when unset)
+    #[arg(long, env = "WORLD_EXPORT_DIR")]
+    export_dir: Option<PathBuf>,
+
+    /// Shared secret required on world.cmd.admin (open when unset)
+    #[arg(long, env = "WORLD_ADMIN_TOKEN", hide_env_values = true)]
+    admin_token: Option<String>,
+
+    /// Start as a warm standby for another instance sharing checkpoint_dir
+    #[arg(long, env = "WORLD_STANDBY", default_value_t = false)]
+    standby: bool,
+
+    /// Seconds between world.heartbeat broadcasts
+    #[arg(long, env = "WORLD_HEARTBEAT_INTERVAL_S", default_value_t = 1.0)]
+    heartbeat_interval_s: f32,
+
+    /// Seconds of heartbeat silence before a standby takes over
+    #[arg(long, env = "WORLD_FAILOVER_TIMEOUT_S", default_value_t = 5.0)]
+    failover_timeout_s: f32,
+
+    /// Seconds between mirror checkpoints for a standby (0 = off)
+    #[arg(long, env = "WORLD_MIRROR_INTERVAL_S", default_value_t = 0.0)]
+    mirror_interval_s: f32,
+
+    /// Also serve the unscoped pre-session subjects (`world.chunk.activated`)
+    #[arg(long, env = "WORLD_LEGACY_SUBJECTS", default_value_t = false)]
+    legacy_subjects: bool,
+
+    /// Seconds a snapshot reply is reused for later joiners (0 = same tick)
+    #[arg(long, env = "WORLD_SNAPSHOT_CACHE_S", default_value_t = 0.0)]
+    snapshot_cache_s: f32,
+
+    /// Largest page of a paged snapshot reply, in bytes
+    #[arg(long, env = "WORLD_SNAPSHOT_PAGE_BYTES", default_value_t = 262144)]
+    snapshot_page_bytes: usize,
+}
+
+/// Contents of `WORLD_SHARD_MAP` (the shard id comes from the CLI).
+#[derive(Deserialize)]
+struct ShardMapFile {
+    region_size: f32,
+    regions: Vec<ShardRegion>,
 }
 
 // ---------------------------------------------------------------------------
@@ -99,14 +465,269 @@ async fn main() -> Result<()> {
         args.activation_radius,
     );
 
-    // Build world data layer
-    let terrain = Arc::new(HeightmapTerrain::new(
-        args.seed,
+    let seed_regions: Vec<SeedRegion> = match &args.seed_regions {
+        Some(path) => {
+            let json = std::fs::read(path)
+                .with_context(|| format!("Failed to read seed regions {}", path.display()))?;
+            serde_json::from_slice(&json)
+                .with_context(|| format!("Invalid seed regions in {}", path.display()))?
+        }
+        None => Vec::new(),
+    };
+    let spawn_points: Vec<[f32; 2]> = match &args.spawn_points {
+        Some(path) => {
+            let json = std::fs::read(path)
+                .with_context(|| format!("Failed to read spawn points {}", path.display()))?;
+            serde_json::from_slice(&json)
+                .with_context(|| format!("Invalid spawn points in {}", path.display()))?
+        }
+        None => Vec::new(),
+    };
+    let buildables: HashMap<String, BuildableStructure> = match &args.buildables {
+        Some(path) => {
+            let json = std::fs::read(path)
+                .with_context(|| format!("Failed to read buildables {}", path.display()))?;
+            serde_json::from_slice(&json)
+                .with_context(|| format!("Invalid buildables in {}", path.display()))?
+        }
+        None => HashMap::new(),
+    };
+    let world_bounds: Option<WorldBounds> = match &args.world_bounds {
+        Some(path) => {
+            let json = std::fs::read(path)
+                .with_context(|| format!("Failed to read world bounds {}", path.display()))?;
+            Some(
+                serde_json::from_slice(&json)
+                    .with_context(|| format!("Invalid world bounds in {}", path.display()))?,
+            )
+        }
+        None => None,
+    };
+
+    let shard = match (&args.shard_id, &args.shard_map) {
+        (Some(shard_id), Some(path)) => {
+            let json = std::fs::read(path)
+                .with_context(|| format!("Failed to read shard map {}", path.display()))?;
+            let map: ShardMapFile = serde_json::from_slice(&json)
+                .with_context(|| format!("Invalid shard map in {}", path.display()))?;
+            Some(ShardConfig {
+                shard_id: shard_id.clone(),
+                region_size: map.region_size,
+                regions: map.regions,
+                handoff_timeout_s: args.shard_handoff_timeout_s,
+            })
+        }
+        _ => None,
+    };
+
+    let world_gen = match &args.world_gen {
+        Some(path) => WorldGenConfig::load(path).with_context(|| {
+            format!("Failed to load world generation config {}", path.display())
+        })?,
+        None => WorldGenConfig::default(),
+    };
+    let world_gen = WorldGenConfig {
+        seed: args.seed,
         // Use chunk_size = cell_size * activation_radius for sensible terrain chunks
-        args.cell_size * 4.0,
-        64, // base resolution at LOD 0
-    ));
-    let world = Arc::new(World::new(terrain));
+        chunk_size: args.cell_size * 4.0,
+        base_resolution: 64, // base resolution at LOD 0
+        ..world_gen
+    };
+
+    // Build world data layer
+    let noise = NoiseParams {
+        amplitude: args.noise_amplitude,
+        frequency: args.noise_frequency,
+        octaves: args.noise_octaves,
+        vertical_exaggeration: args.vertical_exaggeration,
+    };
+    let mut terrain = HeightmapTerrain::from_config(&world_gen)
+        .with_noise_params(noise)
+        .with_seed_regions(seed_regions.clone());
+    if args.erosion_iterations > 0 {
+        terrain = terrain.with_erosion(ErosionConfig {
+            iterations: args.erosion_iterations,
+            ..Default::default()
+        });
+    }
+    if args.chunk_cache_mb > 0 {
+        terrain = terrain.with_cache_budget(CacheBudget::Bytes(args.chunk_cache_mb << 20));
+    }
+    terrain = terrain.with_slow_generation_ms(args.slow_chunk_ms);
+    if args.hydrology {
+        terrain = terrain.with_hydrology(HydrologyConfig {
+            water_table: args.water_table,
+            ..Default::default()
+        });
+    }
+
+    // World service config
+    let service_config = WorldServiceConfig {
+        cell_size: args.cell_size,
+        activation_radius: args.activation_radius,
+        world_seed: args.seed,
+        tile_size_m: args.tile_size_m,
+        tree_density: args.tree_density,
+        physics_dt: 1.0 / args.tick_rate_hz,
+        checkpoint_dir: args.checkpoint_dir.clone(),
+        export_dir: args.export_dir.clone(),
+        generation_workers: args.gen_workers,
+        handle_transforms: args.handle_transforms,
+        transform_keyframe_interval: args.transform_keyframe_interval,
+        population_interval_s: args.population_interval_s,
+        resume_grace_s: args.resume_grace_s,
+        // The world gen file's sea level is in generated-height units.
+        sea_level: args
+            .sea_level
+            .or(world_gen.sea_level.map(|l| l * noise.vertical_exaggeration)),
+        noise,
+        seed_regions,
+        spawn_points,
+        world_bounds,
+        shard,
+        afk: (args.afk_timeout_s > 0.0).then_some(AfkConfig {
+            timeout_s: args.afk_timeout_s,
+            activation_radius: args.afk_radius,
+            transform_interval_s: args.afk_transform_interval_s,
+        }),
+        emote_range: args.emote_range,
+        emote_interval_s: args.emote_interval_s,
+        buildables,
+        build_reach: args.build_reach,
+        interact_reach: args.interact_reach,
+        stream_interval_s: args.stream_interval_s,
+        send_chunk_heights: args.send_chunk_heights,
+        vertical_cell_size: args.vertical_cell_size,
+        vertical_activation_radius: args.vertical_activation_radius,
+        structure_near_radius: args.structure_near_radius,
+        structure_detail_radius: args.structure_detail_radius,
+        max_move_speed: args.max_move_speed,
+        cell_subjects: args.cell_subjects,
+        interest_cell_size: args.interest_cell_size,
+        transform_position_epsilon: args.transform_position_epsilon,
+        transform_rotation_epsilon: args.transform_rotation_epsilon,
+        transform_keepalive_s: args.transform_keepalive_s,
+        ..Default::default()
+    };
+
+    // Bus agent config
+    let bus_config = WorldBusConfig {
+        session: args.session,
+        participant_id: args.participant_id,
+        endpoint: args.endpoint,
+        tick_rate_hz: args.tick_rate_hz,
+        admin_token: args.admin_token,
+        standby: args.standby,
+        heartbeat_interval_s: args.heartbeat_interval_s,
+        failover_timeout_s: args.failover_timeout_s,
+        mirror_interval_s: args.mirror_interval_s,
+        legacy_subjects: args.legacy_subjects,
+        snapshot_cache_s: args.snapshot_cache_s,
+        snapshot_page_bytes: args.snapshot_page_bytes,
+        state_file: args.state_file.clone(),
+    };
+
+    // Validate everything before touching the bus or the disk store.
+    let mut validator = ConfigValidator::new();
+    validator.check_service(&service_config);
+    validator.check_tick_rate(args.tick_rate_hz, service_config.physics_dt);
+    validator.check_terrain(&terrain, &service_config);
+    validator.check_worldgen(&world_gen);
+    validator.check_session(&bus_config);
+    validator.check_snapshot_cache(&bus_config);
+    validator.check_failover(&bus_config, args.checkpoint_dir.as_deref());
+    if let Some(dir) = &args.chunk_store_dir {
+        validator.check_writable_dir("chunk_store_dir", dir);
+    }
+    if let Some(dir) = &args.height_overrides_dir {
+        validator.check_writable_dir("height_overrides_dir", dir);
+    }
+    if let Some(dir) = &args.hibernation_dir {
+        validator.check_writable_dir("hibernation_dir", dir);
+    }
+    if let Some(dir) = &args.checkpoint_dir {
+        validator.check_writable_dir("checkpoint_dir", dir);
+    }
+    if let Some(dir) = &args.export_dir {
+        validator.check_writable_dir("export_dir", dir);
+    }
+    validator.finish()?;
+
+    if let Some(dir) = &args.chunk_store_dir {
+        log::info!("Persisting terrain chunks to {}", dir.display());
+        terrain = terrain
+            .with_chunk_store(dir)
+            .with_context(|| format!("Failed to open chunk store {}", dir.display()))?;
+    }
+    if let Some(dir) = &args.height_overrides_dir {
+        terrain = terrain
+            .with_height_overrides(dir)
+            .with_context(|| format!("Failed to load height overrides from {}", dir.display()))?;
+        log::info!(
+            "Loaded {} designer height overrides from {}",
+            terrain.height_override_count(),
+            dir.display()
+        );
+    }
+    let structures = world_gen.scatter_all(&terrain);
+    if !structures.is_empty() {
+        log::info!("Scattered {} structures", structures.len());
+    }
+    let terrain: Arc<dyn TerrainSource> = match &args.elevation {
+        Some(path) => {
+            let json = std::fs::read(path)
+                .with_context(|| format!("Failed to read elevation config {}", path.display()))?;
+            let config = ElevationConfig {
+                chunk_size: args.cell_size * 4.0,
+                ..serde_json::from_slice(&json)
+                    .with_context(|| format!("Invalid elevation config in {}", path.display()))?
+            };
+            log::info!(
+                "Real-world elevation around ({}, {}) from {} GeoTIFF(s){}",
+                config.origin_x,
+                config.origin_y,
+                config.geotiffs.len(),
+                if config.srtm_dir.is_some() {
+                    " and SRTM tiles"
+                } else {
+                    ""
+                },
+            );
+            Arc::new(
+                ElevationTerrain::open(config, Arc::new(terrain)).with_context(|| {
+                    format!("Failed to load elevation data from {}", path.display())
+                })?,
+            )
+        }
+        None => Arc::new(terrain),
+    };
+    let mut world = World::with_cell_size(terrain, args.cell_size);
+    for structure in structures {
+        world
+            .structures
+            .insert(structure)
+            .context("Invalid generated structure")?;
+    }
+    if let Some(path) = &args.structures_file {
+        let count = world
+            .structures
+            .load_from_file(path)
+            .with_context(|| format!("Failed to load structures from {}", path.display()))?;
+        log::info!("Loaded {} structures from {}", count, path.display());
+    }
+    if let Some(path) = &args.scene_file {
+        let structures = scene_import::import_scene(path)
+            .with_context(|| format!("Failed to import scene {}", path.display()))?;
+        let count = structures.len();
+        for structure in structures {
+            world
+                .structures
+                .insert(structure)
+                .context("Invalid imported structure")?;
+        }
+        log::info!("Imported {} structures from {}", count, path.display());
+    }
+    let world = Arc::new(world);
 
     // Physics registry (standalone – no coordinator owning it)
     let physics_registry = Arc::new(RwLock::new({
@@ -130,29 +751,50 @@ async fn main() -> Result<()> {
         reg
     }));
 
-    // World service config
-    let service_config = WorldServiceConfig {
-        cell_size: args.cell_size,
-        activation_radius: args.activation_radius,
-        world_seed: args.seed,
-        tile_size_m: args.tile_size_m,
-        physics_dt: 1.0 / args.tick_rate_hz,
-        ..Default::default()
-    };
-
-    let service = Arc::new(parking_lot::Mutex::new(WorldService::new(
-        service_config,
-        physics_registry,
-        world,
-    )));
-
-    // Bus agent config
-    let bus_config = WorldBusConfig {
-        session: args.session,
-        participant_id: args.participant_id,
-        endpoint: args.endpoint,
-        tick_rate_hz: args.tick_rate_hz,
-    };
+    let spawn_point_count = service_config.spawn_points.len();
+    let mut service = WorldService::new(service_config, physics_registry, world);
+    service.set_population_rules(world_gen.population.clone());
+    service.set_spawners(world_gen.spawners.clone());
+    if args.pregenerate {
+        if spawn_point_count == 0 {
+            log::warn!("--pregenerate has no spawn points to generate around");
+        } else {
+            let report = service.pregenerate_spawn_points();
+            log::info!(
+                "Pre-generated {} of {} terrain chunks around {} spawn points in {:.0} ms",
+                report.generated,
+                report.chunks,
+                spawn_point_count,
+                report.elapsed_ms
+            );
+        }
+    }
+    if let Some(path) = &args.events_file {
+        let scheduler = EventScheduler::load(path)
+            .with_context(|| format!("Failed to load world events from {}", path.display()))?;
+        log::info!(
+            "Scheduled {} world events from {}",
+            scheduler.events().len(),
+            path.display()
+        );
+        service.set_event_schedule(scheduler);
+    }
+    if let Some(dir) = &args.hibernation_dir {
+        let store = HibernationStore::open(dir)
+            .with_context(|| format!("Failed to open hibernation store {}", dir.display()))?;
+        log::info!(
+            "Loaded {} hibernated cells from {}",
+            store.len(),
+            dir.display()
+        );
+        service.set_hibernation_store(store);
+    }
+    if let Some(path) = args.state_file.as_deref().filter(|p| p.exists()) {
+        service
+            .load_state(path)
+            .with_context(|| format!("Failed to load world state from {}", path.display()))?;
+    }
+    let service = Arc::new(parking_lot::Mutex::new(service));
 
     // Run until shutdown
     WorldBusAgent::new(bus_config, service).run().await
@@ -11,29 +11,128 @@
 //!
 //! | Command                   | Payload keys              | Effect                        |
 //! |---------------------------|---------------------------|-------------------------------|
-//! | `world.participant.join`  | id, x, y, z              | `register_participant`        |
-//! | `world.participant.leave` | id                        | `unregister_participant`      |
-//! | `world.command.teleport`  | id, x, y, z              | forces position update        |
+//! | `world.participant.join`  | id, x, y, z, scale?, resume_token? | `join_participant`, reply with `ParticipantJoined` |
+//! | `world.participant.leave` | id                        | `participant_left`            |
+//! | `world.command.teleport`  | id, x, y, z              | `teleport` (moves the body), publishes its `EntityTransform` |
+//! | `intent.move`             | participant_id, dx, dy, dz, seq? | `set_move_intent` (held until the next one) |
+//! | `intent.interact`         | participant_id, target_id, verb? | `resolve_interaction`, reply with `InteractionResult` |
+//! | `intent.view_radius`      | participant_id, radius    | `set_view_radius`             |
+//! | `action.place_structure`  | participant_id, type_id, x, y, z, rotation_y? | `place_structure`, reply with `StructureSpawned` |
+//! | `action.interact`         | participant_id, target_id, verb? | `interact`, reply with `StructureStateChanged` |
 //! | `world.command.stats`     | *(empty)*                 | reply with `WorldStats`       |
+//! | `world.cmd.deform_terrain`| x, y, radius, delta, hole?, token? | `deform_terrain` / `cut_terrain_holes` |
+//! | `world.cmd.entity_meta`   | entity_id, name, health, status | `set_entity_meta`       |
+//! | `world.cmd.emote`         | entity_id, emote_id?, sound_id? | `emote`                 |
+//! | `world.cmd.raycast`       | x, y, z, dx, dy, dz, max_dist, exclude?, terrain_only? | reply with `RaycastHit` or null |
+//! | `world.cmd.pick`          | x, y, z, dx, dy, dz, max_dist, exclude? | reply with `PickHit` or null |
+//! | `world.cmd.heights`       | points, normals?          | reply with `HeightSamples`    |
+//! | `world.cmd.structures`    | tag, rect?, near?, limit? | reply with `StructureQuery`   |
+//! | `world.cmd.terrain.set_heights` | samples, token?     | `set_terrain_heights`         |
+//! | `world.cmd.terrain.export` | min/max x/y, spacing?, format?, name?, token? | reply with `TerrainExport` |
+//! | `world.cmd.console`       | line                      | run operator console command  |
+//! | `world.cmd.admin`         | action, …, token          | operator action (`admin`)     |
+//!
+//! Failed replies carry a JSON-encoded `WorldCmdError` as their message.
 //!
 //! ## Event contract (outbound)
 //!
 //! | Subject                      | Payload type                          |
 //! |------------------------------|---------------------------------------|
 //! | `world.chunk.activated`      | `WorldEvent<ChunkActivated>`          |
+//! | `world.chunk.voxels`         | `WorldEvent<ChunkVoxels>`             |
+//! | `world.chunk.data`           | `WorldEvent<ChunkData>`               |
 //! | `world.chunk.deactivated`    | `WorldEvent<ChunkDeactivated>`        |
+//! | `world.entity.spawned`       | `WorldEvent<EntitySpawned>`           |
+//! | `world.entity.removed`       | `WorldEvent<EntityRemoved>`           |
 //! | `world.entity.transform`     | `WorldEvent<EntityTransform>`         |
+//! | `world.structure.interest`   | `WorldEvent<StructureInterest>`       |
+//! | `world.structure.state_changed` | `WorldEvent<StructureStateChanged>` |
+//! | `world.structure.updated`    | `WorldEvent<StructureUpdated>`        |
+//! | `world.interaction.result`   | `WorldEvent<InteractionResult>`       |
+//! | `world.interest.subjects`    | `WorldEvent<InterestSubjects>`        |
+//! | `world.entity.transforms`    | `WorldEvent<EntityTransformBatch>`    |
+//! | `world.entity.handle`        | `WorldEvent<EntityHandle>`            |
+//! | `world.entity.meta`          | `WorldEvent<EntityMeta>`              |
+//! | `world.entity.emote`         | `WorldEvent<EntityEmote>`             |
+//! | `world.entity.collision`     | `WorldEvent<EntityCollision>`         |
+//! | `world.proximity.entered`    | `WorldEvent<ProximityEntered>`        |
+//! | `world.proximity.exited`     | `WorldEvent<ProximityExited>`         |
+//! | `world.environment`          | `WorldEvent<WorldEnvironment>`        |
+//! | `world.terrain.modified`     | `WorldEvent<TerrainModified>`         |
+//! | `world.nav.invalidated`      | `WorldEvent<NavInvalidated>`          |
+//! | `world.event.started`        | `WorldEvent<ScriptedEvent>`           |
+//! | `world.event.ended`          | `WorldEvent<ScriptedEventEnded>`      |
+//! | `world.admin.reply`          | `WorldEvent<AdminReply>`              |
+//! | `world.heartbeat`            | `WorldEvent<WorldHeartbeat>`          |
+//! | `world.failover`             | `WorldEvent<WorldFailover>`           |
+//! | `world.shard.handoff`        | `WorldEvent<ShardHandoff>`            |
+//! | `world.shard.handoff_ack`    | `WorldEvent<ShardHandoffAck>`         |
 //! | `world.snapshot` (cmd reply) | `WorldSnapshot` (via cmd response)    |
+//! | `world.snapshot.begin`       | `WorldEvent<SnapshotBegin>`           |
+//! | `world.snapshot.page`        | `WorldEvent<SnapshotPage>`            |
+//! | `world.snapshot.end`         | `WorldEvent<SnapshotEnd>`             |
+//!
+//! Snapshot replies use `CompactWorldSnapshot` when the request payload
+//! carries `protocol_version >= 2`, and come back as a gzip
+//! `EncodedSnapshot` when it carries `encoding: "gzip"`.  Replies are
+//! shared between requests for up to `snapshot_cache_s`.  A request with
+//! `paged: true` is answered with a `SnapshotBegin` instead, and the reply
+//! goes out as `world.snapshot.begin`, `world.snapshot.page`s of at most
+//! `snapshot_page_bytes` and `world.snapshot.end`, all keyed by the
+//! request's `command_id`.  With
+//! `handle_transforms` enabled the tick publishes one
+//! `world.entity.transforms` batch instead of the per-entity
+//! `world.entity.transform` messages; `transform_keyframe_interval` also
+//! delta-encodes those batches between keyframes.  With `cell_subjects`
+//! enabled, `world.entity.transform`, `world.entity.spawned` and
+//! `world.structure.updated` go out on the subject of the interest cell
+//! they happen in (`world.cell.{cx}.{cy}.entity.transform`) instead, and
+//! `world.interest.subjects` tells each client which cells to subscribe to.
+//!
+//! Commands that are rejected — malformed payloads, unknown entities,
+//! rate limits — are counted by reason, kind and sender in
+//! `WorldStats::drops`, and a sample of them is logged.
+//!
+//! The tables list base subjects.  On the wire each one carries the
+//! session after its first segment (`world.{session}.chunk.activated`,
+//! `action.{session}.move`; see [`subjects::Namespace`]), so several
+//! worlds can share one NATS cluster.  With `legacy_subjects` the agent
+//! also publishes and listens on the unscoped names.
+//!
+//! When the service is sharded (`WorldServiceConfig::shard`) every subject
+//! above except `world.shard.handoff` also lives in the shard's namespace
+//! ([`subjects::sharded`]), inside the session's
+//! (`world.{session}.shard.{id}.chunk.activated`), and the agent also
+//! accepts handoffs addressed to it on `world.{session}.shard.handoff`.
+//!
+//! A `standby` agent joins as `world-standby`, listens on `world.heartbeat`,
+//! and only runs the table above once it has taken over (see
+//! [`failover`](crate::failover)).
 
+use crate::failover::{HeartbeatMonitor, MIRROR_CHECKPOINT};
 use crate::protocol::subjects::mgmt;
-use crate::protocol::{subjects, WorldEvent};
-use crate::service::WorldService;
-use crate::types::{Vec3, WorldStats};
+use crate::protocol::{
+    subjects, AdminReply, CmdAdmin, CmdConsole, CmdDeformTerrain, CmdEmote, CmdEntityMeta,
+    CmdHeights, CmdPick, CmdRaycast, CmdSetHeights, CmdStructures, CmdTerrainExport, ConsoleReply,
+    HeightsSet, IntentInteract, IntentMove, IntentPlaceStructure, IntentViewRadius,
+    InterestSubjects, ShardHandoff, ShardHandoffAck, SnapshotEncoding, WorldCmdError,
+    WorldCmdErrorCode, WorldEvent, WorldFailover, WorldHeartbeat, COMPACT_SNAPSHOT_VERSION,
+    PROTOCOL_VERSION,
+};
+use crate::service::{
+    InteractError, PlacementError, TickEvents, WorldService, MAX_DEFORM_RADIUS,
+    MAX_HEIGHT_QUERY_POINTS, MAX_SET_HEIGHT_SAMPLES,
+};
+use crate::snapshot_cache::SnapshotCache;
+use crate::snapshot_paging::paginate;
+use crate::types::{DropReason, Vec3, WorldStats};
+use crate::{admin, console};
 use anyhow::{Context, Result};
 use bytes::Bytes;
 use log::info;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 // ---------------------------------------------------------------------------
@@ -46,6 +145,13 @@ pub struct ParticipantJoinMsg {
     pub x: f32,
     pub y: f32,
     pub z: f32,
+    /// Uniform entity scale (default 1).
+    #[serde(default)]
+    pub scale: Option<f32>,
+    /// Token from the participant's previous `ParticipantJoined`, to
+    /// reattach to its entity after a reconnect.
+    #[serde(default)]
+    pub resume_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,6 +179,49 @@ pub struct ActionMoveMsg {
     pub dy: f32,
     #[serde(default)]
     pub dz: f32,
+    /// Originating intent sequence number, acknowledged in transforms.
+    #[serde(default)]
+    pub seq: Option<u64>,
+}
+
+/// A client's `intent.view_radius`, tagged with its participant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentViewRadiusMsg {
+    pub participant_id: String,
+    #[serde(flatten)]
+    pub intent: IntentViewRadius,
+}
+
+/// A client's `intent.move`, tagged with the participant it steers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentMoveMsg {
+    pub participant_id: String,
+    #[serde(flatten)]
+    pub intent: IntentMove,
+}
+
+/// A client's `intent.interact`, tagged with the participant acting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentInteractMsg {
+    pub participant_id: String,
+    #[serde(flatten)]
+    pub intent: IntentInteract,
+}
+
+/// Coordinator-approved `intent.place_structure`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionPlaceStructureMsg {
+    pub participant_id: String,
+    #[serde(flatten)]
+    pub intent: IntentPlaceStructure,
+}
+
+/// Coordinator-approved `intent.interact`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionInteractMsg {
+    pub participant_id: String,
+    #[serde(flatten)]
+    pub intent: IntentInteract,
 }
 
 // ---------------------------------------------------------------------------
@@ -89,6 +238,30 @@ pub struct WorldBusConfig {
     pub endpoint: String,
     /// Tick rate in Hz.
     pub tick_rate_hz: f32,
+    /// Shared secret required on `world.cmd.admin` (open when `None`).
+    pub admin_token: Option<String>,
+    /// Start as a warm standby (see [`failover`](crate::failover)).
+    pub standby: bool,
+    /// Seconds between `world.heartbeat` broadcasts while active.
+    pub heartbeat_interval_s: f32,
+    /// Seconds without a heartbeat before a standby takes over.
+    pub failover_timeout_s: f32,
+    /// Seconds between mirror checkpoints while active (`0` = never).
+    pub mirror_interval_s: f32,
+    /// Also serve the unscoped pre-session subjects (`world.chunk.activated`
+    /// next to `world.{session}.chunk.activated`) for clients that don't
+    /// scope by session yet.
+    pub legacy_subjects: bool,
+    /// Seconds a snapshot reply is reused for later `world.cmd.snapshot`
+    /// requests (`0` = only within the tick it was built; see
+    /// [`snapshot_cache`](crate::snapshot_cache)).
+    pub snapshot_cache_s: f32,
+    /// Largest `data` of one `world.snapshot.page` when a client asks for
+    /// a paged snapshot (see [`snapshot_paging`](crate::snapshot_paging)).
+    pub snapshot_page_bytes: usize,
+    /// Where the world state is saved on SIGINT (see
+    /// [`persistence`](crate::persistence); not saved when unset).
+    pub state_file: Option<PathBuf>,
 }
 
 impl Default for WorldBusConfig {
@@ -98,6 +271,15 @@ impl Default for WorldBusConfig {
             participant_id: "world-service".into(),
             endpoint: "nats://localhost:4222".into(),
             tick_rate_hz: 30.0,
+            admin_token: None,
+            standby: false,
+            heartbeat_interval_s: 1.0,
+            failover_timeout_s: 5.0,
+            mirror_interval_s: 0.0,
+            legacy_subjects: false,
+            snapshot_cache_s: 0.0,
+            snapshot_page_bytes: 256 * 1024,
+            state_file: None,
         }
     }
 }
@@ -122,16 +304,34 @@ impl WorldBusAgent {
     /// Start the agent.  Connects to the bus, registers as an external
     /// physics participant, and runs the tick loop until the task is cancelled.
     ///
+    /// A standby agent first waits for the primary's heartbeat to disappear
+    /// and restores the mirrored state before doing any of that.
+    ///
     /// Uses `janet_client::JanetExecutor` — same pattern as the coordinator.
     pub async fn run(self) -> Result<()> {
         use janet_client::messages::CommandResponse;
         use janet_client::{ClientBuilder, JanetExecutor};
 
+        let failover = if self.config.standby {
+            let previous = self.wait_for_primary_loss().await?;
+            self.restore_mirror();
+            Some(previous)
+        } else {
+            None
+        };
+
         info!(
             "WorldBusAgent connecting as '{}' in session '{}'",
             self.config.participant_id, self.config.session
         );
 
+        let shard_id = self.service.lock().shard_id().map(str::to_string);
+        let shard = shard_id.as_deref();
+        if let Some(id) = shard {
+            info!("Serving shard '{}'", id);
+        }
+        let namespaces = Namespaces::new(&self.config, shard);
+
         let client: JanetExecutor = ClientBuilder::new()
             .session(&self.config.session)
             .participant(&self.config.participant_id, vec!["world".to_string()])
@@ -147,6 +347,21 @@ impl WorldBusAgent {
             self.config.tick_rate_hz
         );
 
+        if let Some(previous_instance_id) = failover {
+            let frame = self.service.lock().stats().total_ticks;
+            let announcement = WorldFailover {
+                instance_id: self.config.participant_id.clone(),
+                previous_instance_id,
+                frame,
+            };
+            publish_event(
+                &client,
+                &namespaces.subjects(subjects::FAILOVER),
+                WorldEvent::new(self.config.session.as_str(), frame, &announcement),
+            )
+            .await;
+        }
+
         // -----------------------------------------------------------------------
         // Register command handlers (synchronous registration)
         // -----------------------------------------------------------------------
@@ -154,7 +369,7 @@ impl WorldBusAgent {
         // world.command.stats
         {
             let svc = self.service.clone();
-            client.on_command(mgmt::STATS, move |cmd| {
+            on_each(&client, namespaces.subjects(mgmt::STATS), move |cmd| {
                 let stats: WorldStats = svc.lock().stats();
                 let result = serde_json::to_value(&stats).ok();
                 async move { Ok(CommandResponse::success(cmd.command_id, result)) }
@@ -165,120 +380,1085 @@ impl WorldBusAgent {
         {
             let svc = self.service.clone();
             let session = self.config.session.clone();
-            client.on_command(subjects::CMD_SNAPSHOT, move |cmd| {
-                let svc = svc.clone();
-                let session = session.clone();
-                async move {
-                    let snapshot = svc.lock().build_snapshot(&session);
-                    let result = serde_json::to_value(&snapshot).ok();
-                    Ok(CommandResponse::success(cmd.command_id, result))
-                }
-            });
+            let max_age_ticks =
+                (self.config.snapshot_cache_s * self.config.tick_rate_hz).round() as u64;
+            let cache = Arc::new(Mutex::new(SnapshotCache::new(max_age_ticks)));
+            let page_client = client.clone();
+            let page_bytes = self.config.snapshot_page_bytes;
+            let begin_subjects = namespaces.subjects(subjects::SNAPSHOT_BEGIN);
+            let page_subjects = namespaces.subjects(subjects::SNAPSHOT_PAGE);
+            let end_subjects = namespaces.subjects(subjects::SNAPSHOT_END);
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_SNAPSHOT),
+                move |cmd| {
+                    let svc = svc.clone();
+                    let session = session.clone();
+                    let cache = cache.clone();
+                    let page_client = page_client.clone();
+                    let begin_subjects = begin_subjects.clone();
+                    let page_subjects = page_subjects.clone();
+                    let end_subjects = end_subjects.clone();
+                    let compact =
+                        requested_protocol_version(&cmd.payload) >= COMPACT_SNAPSHOT_VERSION;
+                    let encoding = cmd
+                        .payload
+                        .get("encoding")
+                        .and_then(|v| serde_json::from_value::<SnapshotEncoding>(v.clone()).ok());
+                    let paged = cmd
+                        .payload
+                        .get("paged")
+                        .and_then(serde_json::Value::as_bool)
+                        .unwrap_or(false);
+                    async move {
+                        // Held across the build so a crowd of joiners waits
+                        // for one snapshot instead of each building its own.
+                        let cached = {
+                            let mut cache = cache.lock();
+                            let (frame, terrain_revision) = {
+                                let svc = svc.lock();
+                                (svc.tick_count(), svc.terrain_revision())
+                            };
+                            cache.get_or_build(frame, terrain_revision, compact, || {
+                                svc.lock().build_snapshot(&session)
+                            })
+                        };
+                        let result = match encoding {
+                            Some(SnapshotEncoding::Gzip) => cached.encoded(),
+                            None => cached.value.clone(),
+                        };
+                        if !paged {
+                            return Ok(CommandResponse::success(cmd.command_id, result));
+                        }
+
+                        let json = serde_json::to_string(&result).unwrap_or_default();
+                        let paged = paginate(
+                            &cmd.command_id,
+                            cached.frame,
+                            cached.compact,
+                            encoding,
+                            &json,
+                            page_bytes,
+                        );
+                        let frame = cached.frame;
+                        publish_event(
+                            &page_client,
+                            &begin_subjects,
+                            WorldEvent::new(session.as_str(), frame, &paged.begin),
+                        )
+                        .await;
+                        for page in &paged.pages {
+                            publish_event(
+                                &page_client,
+                                &page_subjects,
+                                WorldEvent::new(session.as_str(), frame, page),
+                            )
+                            .await;
+                        }
+                        publish_event(
+                            &page_client,
+                            &end_subjects,
+                            WorldEvent::new(session.as_str(), frame, &paged.end),
+                        )
+                        .await;
+                        Ok(CommandResponse::success(
+                            cmd.command_id,
+                            serde_json::to_value(&paged.begin).ok(),
+                        ))
+                    }
+                },
+            );
+        }
+
+        // world.cmd.deform_terrain – runtime crater / trench / flatten
+        {
+            let svc = self.service.clone();
+            let admin_token = self.config.admin_token.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_DEFORM_TERRAIN),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    let admin_token = admin_token.clone();
+                    async move {
+                        let m = match serde_json::from_value::<CmdDeformTerrain>(payload_val) {
+                            Ok(m) => m,
+                            Err(e) => {
+                                return Ok(reject_payload(
+                                    &svc,
+                                    subjects::CMD_DEFORM_TERRAIN,
+                                    &cmd,
+                                    e,
+                                ))
+                            }
+                        };
+                        if ![m.x, m.y, m.radius, m.delta].iter().all(|v| v.is_finite()) {
+                            return Ok(reject_payload(
+                                &svc,
+                                subjects::CMD_DEFORM_TERRAIN,
+                                &cmd,
+                                "x, y, radius and delta must be finite",
+                            ));
+                        }
+                        let result =
+                            if !admin::authorised(admin_token.as_deref(), m.token.as_deref()) {
+                                Err(WorldCmdError::new(
+                                    WorldCmdErrorCode::Unauthorized,
+                                    "admin token rejected",
+                                ))
+                            } else if m.radius > MAX_DEFORM_RADIUS {
+                                Err(WorldCmdError::new(
+                                    WorldCmdErrorCode::LimitExceeded,
+                                    format!(
+                                        "deform_terrain failed: radius {} is over the limit of {}",
+                                        m.radius, MAX_DEFORM_RADIUS
+                                    ),
+                                )
+                                .with_details(serde_json::json!({ "max": MAX_DEFORM_RADIUS })))
+                            } else {
+                                let center = Vec3::new(m.x, m.y, 0.0);
+                                let result = if m.hole {
+                                    svc.lock().cut_terrain_holes(center, m.radius)
+                                } else {
+                                    svc.lock().deform_terrain(center, m.radius, m.delta)
+                                };
+                                result.map_err(|e| {
+                                    WorldCmdError::new(
+                                        WorldCmdErrorCode::Rejected,
+                                        format!("deform_terrain failed: {}", e),
+                                    )
+                                })
+                            };
+                        match result {
+                            Ok(ev) => Ok(CommandResponse::success(
+                                cmd.command_id,
+                                serde_json::to_value(&ev).ok(),
+                            )),
+                            Err(error) => {
+                                svc.lock().record_drop(
+                                    subjects::CMD_DEFORM_TERRAIN,
+                                    None,
+                                    DropReason::Invalid,
+                                    &error.message,
+                                );
+                                Ok(cmd_failed(cmd.command_id, error))
+                            }
+                        }
+                    }
+                },
+            );
+        }
+
+        // world.cmd.raycast – first hit for click-to-move / targeting
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_RAYCAST),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<CmdRaycast>(payload_val) {
+                            Ok(m) => {
+                                let hit = svc.lock().raycast_world(
+                                    Vec3::new(m.x, m.y, m.z),
+                                    Vec3::new(m.dx, m.dy, m.dz),
+                                    m.max_dist,
+                                    m.exclude.as_deref(),
+                                    m.terrain_only,
+                                );
+                                Ok(CommandResponse::success(
+                                    cmd.command_id,
+                                    serde_json::to_value(hit).ok(),
+                                ))
+                            }
+                            Err(e) => Ok(reject_payload(&svc, subjects::CMD_RAYCAST, &cmd, e)),
+                        }
+                    }
+                },
+            );
+        }
+
+        // world.cmd.pick – click-to-interact target under a camera ray
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_PICK),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<CmdPick>(payload_val) {
+                            Ok(m) => {
+                                let hit = svc.lock().pick(
+                                    Vec3::new(m.x, m.y, m.z),
+                                    Vec3::new(m.dx, m.dy, m.dz),
+                                    m.max_dist,
+                                    m.exclude.as_deref(),
+                                );
+                                Ok(CommandResponse::success(
+                                    cmd.command_id,
+                                    serde_json::to_value(hit).ok(),
+                                ))
+                            }
+                            Err(e) => Ok(reject_payload(&svc, subjects::CMD_PICK, &cmd, e)),
+                        }
+                    }
+                },
+            );
+        }
+
+        // world.cmd.heights – batch ground height lookup
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_HEIGHTS),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<CmdHeights>(payload_val) {
+                            Ok(m) => {
+                                let result = svc.lock().sample_heights(&m.points, m.normals);
+                                match result {
+                                    Ok(samples) => Ok(CommandResponse::success(
+                                        cmd.command_id,
+                                        serde_json::to_value(&samples).ok(),
+                                    )),
+                                    Err(e) => {
+                                        let message = format!("heights failed: {}", e);
+                                        svc.lock().record_drop(
+                                            subjects::CMD_HEIGHTS,
+                                            None,
+                                            DropReason::Invalid,
+                                            &message,
+                                        );
+                                        let error = if m.points.len() > MAX_HEIGHT_QUERY_POINTS {
+                                            WorldCmdError::new(
+                                                WorldCmdErrorCode::LimitExceeded,
+                                                message,
+                                            )
+                                            .with_details(serde_json::json!({
+                                                "max": MAX_HEIGHT_QUERY_POINTS
+                                            }))
+                                        } else {
+                                            WorldCmdError::new(WorldCmdErrorCode::Rejected, message)
+                                        };
+                                        Ok(cmd_failed(cmd.command_id, error))
+                                    }
+                                }
+                            }
+                            Err(e) => Ok(reject_payload(&svc, subjects::CMD_HEIGHTS, &cmd, e)),
+                        }
+                    }
+                },
+            );
+        }
+
+        // world.cmd.structures – tagged structure lookup
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_STRUCTURES),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<CmdStructures>(payload_val) {
+                            Ok(m) => {
+                                let found = svc.lock().structures_by_tag(&m);
+                                Ok(CommandResponse::success(
+                                    cmd.command_id,
+                                    serde_json::to_value(&found).ok(),
+                                ))
+                            }
+                            Err(e) => Ok(reject_payload(&svc, subjects::CMD_STRUCTURES, &cmd, e)),
+                        }
+                    }
+                },
+            );
+        }
+
+        // world.cmd.terrain.set_heights – designer height overrides
+        {
+            let svc = self.service.clone();
+            let admin_token = self.config.admin_token.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_TERRAIN_SET_HEIGHTS),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    let admin_token = admin_token.clone();
+                    async move {
+                        let m = match serde_json::from_value::<CmdSetHeights>(payload_val) {
+                            Ok(m) => m,
+                            Err(e) => {
+                                return Ok(reject_payload(
+                                    &svc,
+                                    subjects::CMD_TERRAIN_SET_HEIGHTS,
+                                    &cmd,
+                                    e,
+                                ))
+                            }
+                        };
+                        let result =
+                            if admin::authorised(admin_token.as_deref(), m.token.as_deref()) {
+                                svc.lock().set_terrain_heights(&m.samples).map_err(|e| {
+                                    let message = format!("terrain.set_heights failed: {}", e);
+                                    if m.samples.len() > MAX_SET_HEIGHT_SAMPLES {
+                                        WorldCmdError::new(
+                                            WorldCmdErrorCode::LimitExceeded,
+                                            message,
+                                        )
+                                        .with_details(
+                                            serde_json::json!({
+                                                "max": MAX_SET_HEIGHT_SAMPLES
+                                            }),
+                                        )
+                                    } else {
+                                        WorldCmdError::new(WorldCmdErrorCode::Rejected, message)
+                                    }
+                                })
+                            } else {
+                                Err(WorldCmdError::new(
+                                    WorldCmdErrorCode::Unauthorized,
+                                    "admin token rejected",
+                                ))
+                            };
+                        match result {
+                            Ok(chunk_ids) => {
+                                info!("terrain.set_heights: {} chunks", chunk_ids.len());
+                                Ok(CommandResponse::success(
+                                    cmd.command_id,
+                                    serde_json::to_value(HeightsSet { chunk_ids }).ok(),
+                                ))
+                            }
+                            Err(error) => {
+                                svc.lock().record_drop(
+                                    subjects::CMD_TERRAIN_SET_HEIGHTS,
+                                    None,
+                                    DropReason::Invalid,
+                                    &error.message,
+                                );
+                                Ok(cmd_failed(cmd.command_id, error))
+                            }
+                        }
+                    }
+                },
+            );
+        }
+
+        // world.cmd.terrain.export – heightmap PNG/RAW render
+        {
+            let svc = self.service.clone();
+            let admin_token = self.config.admin_token.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_TERRAIN_EXPORT),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    let admin_token = admin_token.clone();
+                    async move {
+                        let m = match serde_json::from_value::<CmdTerrainExport>(payload_val) {
+                            Ok(m) => m,
+                            Err(e) => {
+                                return Ok(reject_payload(
+                                    &svc,
+                                    subjects::CMD_TERRAIN_EXPORT,
+                                    &cmd,
+                                    e,
+                                ))
+                            }
+                        };
+                        let result =
+                            if admin::authorised(admin_token.as_deref(), m.token.as_deref()) {
+                                svc.lock().export_terrain(&m).map_err(|e| {
+                                    WorldCmdError::new(
+                                        WorldCmdErrorCode::Rejected,
+                                        format!("terrain.export failed: {}", e),
+                                    )
+                                })
+                            } else {
+                                Err(WorldCmdError::new(
+                                    WorldCmdErrorCode::Unauthorized,
+                                    "admin token rejected",
+                                ))
+                            };
+                        match result {
+                            Ok(export) => {
+                                info!(
+                                    "terrain.export: {}x{} {:?}{}",
+                                    export.width,
+                                    export.height,
+                                    export.format,
+                                    export
+                                        .path
+                                        .as_deref()
+                                        .map(|p| format!(" to {}", p))
+                                        .unwrap_or_default()
+                                );
+                                Ok(CommandResponse::success(
+                                    cmd.command_id,
+                                    serde_json::to_value(&export).ok(),
+                                ))
+                            }
+                            Err(error) => {
+                                svc.lock().record_drop(
+                                    subjects::CMD_TERRAIN_EXPORT,
+                                    None,
+                                    DropReason::Invalid,
+                                    &error.message,
+                                );
+                                Ok(cmd_failed(cmd.command_id, error))
+                            }
+                        }
+                    }
+                },
+            );
+        }
+
+        // world.cmd.entity_meta – nameplate / HUD display data
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_ENTITY_META),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<CmdEntityMeta>(payload_val) {
+                            Ok(meta) => {
+                                let entity_id = meta.entity_id.clone();
+                                match svc.lock().set_entity_meta(meta) {
+                                    Ok(stored) => Ok(CommandResponse::success(
+                                        cmd.command_id,
+                                        serde_json::to_value(&stored).ok(),
+                                    )),
+                                    Err(e) => Ok(cmd_failed(
+                                        cmd.command_id,
+                                        unknown_entity(
+                                            &entity_id,
+                                            format!("entity_meta failed: {}", e),
+                                        ),
+                                    )),
+                                }
+                            }
+                            Err(e) => Ok(reject_payload(&svc, subjects::CMD_ENTITY_META, &cmd, e)),
+                        }
+                    }
+                },
+            );
+        }
+
+        // world.cmd.emote – entity animation / sound, heard nearby
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_EMOTE),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<CmdEmote>(payload_val) {
+                            Ok(m) => {
+                                let mut svc = svc.lock();
+                                let entity_id = m.entity_id.clone();
+                                let known = svc.participant_position(&entity_id).is_some();
+                                let cooldown = svc.emote_cooldown_s(&entity_id);
+                                let has_ids = m.emote_id.is_some() || m.sound_id.is_some();
+                                match svc.emote(m) {
+                                    Ok(emote) => Ok(CommandResponse::success(
+                                        cmd.command_id,
+                                        serde_json::to_value(&emote).ok(),
+                                    )),
+                                    Err(e) => {
+                                        let message = format!("emote failed: {}", e);
+                                        let error = if !known {
+                                            unknown_entity(&entity_id, message)
+                                        } else if has_ids && cooldown > 0.0 {
+                                            WorldCmdError::new(
+                                                WorldCmdErrorCode::RateLimited,
+                                                message,
+                                            )
+                                            .with_details(serde_json::json!({
+                                                "retry_after_s": cooldown
+                                            }))
+                                        } else {
+                                            WorldCmdError::new(
+                                                WorldCmdErrorCode::InvalidPayload,
+                                                message,
+                                            )
+                                        };
+                                        Ok(cmd_failed(cmd.command_id, error))
+                                    }
+                                }
+                            }
+                            Err(e) => Ok(reject_payload(&svc, subjects::CMD_EMOTE, &cmd, e)),
+                        }
+                    }
+                },
+            );
+        }
+
+        // world.cmd.console – operator REPL
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_CONSOLE),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<CmdConsole>(payload_val) {
+                            Ok(m) => {
+                                info!("console: {}", m.line);
+                                match console::run(&mut svc.lock(), &m.line) {
+                                    Ok(output) => {
+                                        info!("console> {}", output);
+                                        Ok(CommandResponse::success(
+                                            cmd.command_id,
+                                            serde_json::to_value(ConsoleReply { output }).ok(),
+                                        ))
+                                    }
+                                    Err(e) => {
+                                        info!("console error: {}", e);
+                                        Ok(cmd_failed(
+                                            cmd.command_id,
+                                            WorldCmdError::new(
+                                                WorldCmdErrorCode::Rejected,
+                                                e.to_string(),
+                                            ),
+                                        ))
+                                    }
+                                }
+                            }
+                            Err(e) => Ok(reject_payload(&svc, subjects::CMD_CONSOLE, &cmd, e)),
+                        }
+                    }
+                },
+            );
+        }
+
+        // world.cmd.admin – operator actions (janet-world-ctl)
+        {
+            let svc = self.service.clone();
+            let admin_client = client.clone();
+            let session = self.config.session.clone();
+            let admin_token = self.config.admin_token.clone();
+            let reply_subjects = namespaces.subjects(subjects::ADMIN_REPLY);
+            on_each(
+                &client,
+                namespaces.subjects(subjects::CMD_ADMIN),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    let admin_client = admin_client.clone();
+                    let session = session.clone();
+                    let admin_token = admin_token.clone();
+                    let reply_subjects = reply_subjects.clone();
+                    async move {
+                        let (reply, frame, code) =
+                            match serde_json::from_value::<CmdAdmin>(payload_val) {
+                                Ok(m)
+                                    if !admin::authorised(
+                                        admin_token.as_deref(),
+                                        m.token.as_deref(),
+                                    ) =>
+                                {
+                                    svc.lock().record_drop(
+                                        subjects::CMD_ADMIN,
+                                        None,
+                                        DropReason::Invalid,
+                                        "admin token rejected",
+                                    );
+                                    (
+                                        AdminReply::failure("admin token rejected"),
+                                        0,
+                                        WorldCmdErrorCode::Unauthorized,
+                                    )
+                                }
+                                Ok(m) => {
+                                    info!("admin: {:?}", m.action);
+                                    let mut svc = svc.lock();
+                                    let reply = admin::execute(&mut svc, m.action);
+                                    (reply, svc.stats().total_ticks, WorldCmdErrorCode::Failed)
+                                }
+                                Err(e) => {
+                                    let message = format!("Invalid payload: {}", e);
+                                    svc.lock().record_drop(
+                                        subjects::CMD_ADMIN,
+                                        None,
+                                        DropReason::Invalid,
+                                        &message,
+                                    );
+                                    (
+                                        AdminReply::failure(message),
+                                        0,
+                                        WorldCmdErrorCode::InvalidPayload,
+                                    )
+                                }
+                            };
+                        info!("admin> {}", reply.message);
+
+                        publish_event(
+                            &admin_client,
+                            &reply_subjects,
+                            WorldEvent::new(session.as_str(), frame, &reply),
+                        )
+                        .await;
+
+                        let result = serde_json::to_value(&reply).ok();
+                        if reply.ok {
+                            Ok(CommandResponse::success(cmd.command_id, result))
+                        } else {
+                            Ok(cmd_failed(
+                                cmd.command_id,
+                                WorldCmdError::new(code, reply.message),
+                            ))
+                        }
+                    }
+                },
+            );
         }
 
         // world.participant.join
         {
             let svc = self.service.clone();
-            client.on_command(mgmt::PARTICIPANT_JOIN, move |cmd| {
-                let payload_val =
-                    serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
-                let svc = svc.clone();
-                async move {
-                    match serde_json::from_value::<ParticipantJoinMsg>(payload_val) {
-                        Ok(m) => {
-                            svc.lock()
-                                .register_participant(m.id, Vec3::new(m.x, m.y, m.z));
-                            Ok(CommandResponse::success(cmd.command_id, None))
+            on_each(
+                &client,
+                namespaces.subjects(mgmt::PARTICIPANT_JOIN),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<ParticipantJoinMsg>(payload_val) {
+                            Ok(m) => {
+                                let mut svc = svc.lock();
+                                let joined = svc.join_participant(
+                                    m.id,
+                                    Vec3::new(m.x, m.y, m.z),
+                                    m.resume_token.as_deref(),
+                                );
+                                match m.scale.map(|s| svc.set_entity_scale(&joined.id, s)) {
+                                    Some(Err(e)) => Ok(cmd_failed(
+                                        cmd.command_id,
+                                        WorldCmdError::new(
+                                            WorldCmdErrorCode::Rejected,
+                                            format!("participant.join scale rejected: {}", e),
+                                        ),
+                                    )),
+                                    _ => Ok(CommandResponse::success(
+                                        cmd.command_id,
+                                        serde_json::to_value(&joined).ok(),
+                                    )),
+                                }
+                            }
+                            Err(e) => Ok(reject_payload(&svc, mgmt::PARTICIPANT_JOIN, &cmd, e)),
                         }
-                        Err(e) => Ok(CommandResponse::failed(
-                            cmd.command_id,
-                            format!("Invalid payload: {}", e),
-                        )),
                     }
-                }
-            });
+                },
+            );
         }
 
         // world.participant.leave
         {
             let svc = self.service.clone();
-            client.on_command(mgmt::PARTICIPANT_LEAVE, move |cmd| {
-                let payload_val =
-                    serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
-                let svc = svc.clone();
-                async move {
-                    match serde_json::from_value::<ParticipantLeaveMsg>(payload_val) {
-                        Ok(m) => {
-                            svc.lock().unregister_participant(&m.id);
-                            Ok(CommandResponse::success(cmd.command_id, None))
+            on_each(
+                &client,
+                namespaces.subjects(mgmt::PARTICIPANT_LEAVE),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<ParticipantLeaveMsg>(payload_val) {
+                            Ok(m) => {
+                                svc.lock().participant_left(&m.id);
+                                Ok(CommandResponse::success(cmd.command_id, None))
+                            }
+                            Err(e) => Ok(reject_payload(&svc, mgmt::PARTICIPANT_LEAVE, &cmd, e)),
                         }
-                        Err(e) => Ok(CommandResponse::failed(
-                            cmd.command_id,
-                            format!("Invalid payload: {}", e),
-                        )),
                     }
-                }
-            });
+                },
+            );
         }
 
         // world.command.teleport
         {
             let svc = self.service.clone();
-            client.on_command(mgmt::TELEPORT, move |cmd| {
+            let teleport_client = client.clone();
+            let session = self.config.session.clone();
+            let transform_subjects = namespaces.subjects(subjects::ENTITY_TRANSFORM);
+            on_each(&client, namespaces.subjects(mgmt::TELEPORT), move |cmd| {
                 let payload_val =
                     serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
                 let svc = svc.clone();
+                let teleport_client = teleport_client.clone();
+                let session = session.clone();
+                let transform_subjects = transform_subjects.clone();
                 async move {
                     match serde_json::from_value::<TeleportMsg>(payload_val) {
                         Ok(m) => {
-                            svc.lock()
-                                .register_participant(m.id, Vec3::new(m.x, m.y, m.z));
-                            Ok(CommandResponse::success(cmd.command_id, None))
+                            let position = Vec3::new(m.x, m.y, m.z);
+                            let (result, frame) = {
+                                let mut svc = svc.lock();
+                                // Unknown ids are placed as new participants, as
+                                // before teleports moved bodies.
+                                if svc.participant_position(&m.id).is_none() {
+                                    svc.register_participant(m.id.clone(), position);
+                                    return Ok(CommandResponse::success(cmd.command_id, None));
+                                }
+                                (svc.teleport(&m.id, position), svc.stats().total_ticks)
+                            };
+                            match result {
+                                Ok(transform) => {
+                                    publish_event(
+                                        &teleport_client,
+                                        &transform_subjects,
+                                        WorldEvent::new(session.as_str(), frame, &transform),
+                                    )
+                                    .await;
+                                    Ok(CommandResponse::success(
+                                        cmd.command_id,
+                                        serde_json::to_value(&transform).ok(),
+                                    ))
+                                }
+                                Err(e) => Ok(cmd_failed(
+                                    cmd.command_id,
+                                    WorldCmdError::new(
+                                        WorldCmdErrorCode::InvalidPayload,
+                                        format!("teleport failed: {}", e),
+                                    ),
+                                )),
+                            }
                         }
-                        Err(e) => Ok(CommandResponse::failed(
-                            cmd.command_id,
-                            format!("Invalid payload: {}", e),
-                        )),
+                        Err(e) => Ok(reject_payload(&svc, mgmt::TELEPORT, &cmd, e)),
                     }
                 }
             });
         }
 
-        // action.move (coordinator-approved movement)
+        // intent.move (client input, applied every tick until the next one)
         {
             let svc = self.service.clone();
-            client.on_command(subjects::ACTION_MOVE, move |cmd| {
-                let payload_val =
-                    serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
-                let svc = svc.clone();
-                async move {
-                    match serde_json::from_value::<ActionMoveMsg>(payload_val) {
-                        Ok(m) => {
-                            let actor_id =
-                                m.participant_id.or(m.entity_id).or(m.id).ok_or_else(|| {
-                                    "Missing participant_id/entity_id/id in action.move payload"
-                                        .to_string()
-                                });
-
-                            match actor_id {
-                                Ok(id) => match svc.lock().apply_move_action(&id, m.dx, m.dy, m.dz)
-                                {
+            on_each(
+                &client,
+                namespaces.subjects(subjects::INTENT_MOVE),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<IntentMoveMsg>(payload_val) {
+                            Ok(m) => {
+                                let mut svc = svc.lock();
+                                let known = svc.participant_position(&m.participant_id).is_some();
+                                match svc.set_move_intent(&m.participant_id, &m.intent) {
                                     Ok(()) => Ok(CommandResponse::success(cmd.command_id, None)),
-                                    Err(e) => Ok(CommandResponse::failed(
+                                    Err(e) if known => Ok(cmd_failed(
+                                        cmd.command_id,
+                                        WorldCmdError::new(
+                                            WorldCmdErrorCode::InvalidPayload,
+                                            format!("intent.move failed: {}", e),
+                                        ),
+                                    )),
+                                    Err(e) => Ok(cmd_failed(
                                         cmd.command_id,
-                                        format!("action.move failed: {}", e),
+                                        unknown_entity(
+                                            &m.participant_id,
+                                            format!("intent.move failed: {}", e),
+                                        ),
                                     )),
-                                },
-                                Err(msg) => Ok(CommandResponse::failed(cmd.command_id, msg)),
+                                }
                             }
+                            Err(e) => Ok(reject_payload(&svc, subjects::INTENT_MOVE, &cmd, e)),
                         }
-                        Err(e) => Ok(CommandResponse::failed(
-                            cmd.command_id,
-                            format!("Invalid payload: {}", e),
-                        )),
                     }
-                }
-            });
+                },
+            );
+        }
+
+        // intent.view_radius (narrows the client's cell subscriptions)
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::INTENT_VIEW_RADIUS),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<IntentViewRadiusMsg>(payload_val) {
+                            Ok(m) => {
+                                let result = svc
+                                    .lock()
+                                    .set_view_radius(&m.participant_id, m.intent.radius);
+                                match result {
+                                    Ok(()) => Ok(CommandResponse::success(cmd.command_id, None)),
+                                    Err(e) => Ok(cmd_failed(
+                                        cmd.command_id,
+                                        WorldCmdError::new(
+                                            WorldCmdErrorCode::InvalidPayload,
+                                            format!("intent.view_radius failed: {}", e),
+                                        ),
+                                    )),
+                                }
+                            }
+                            Err(e) => {
+                                Ok(reject_payload(&svc, subjects::INTENT_VIEW_RADIUS, &cmd, e))
+                            }
+                        }
+                    }
+                },
+            );
+        }
+
+        // intent.interact (client interaction, resolved by its handler)
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::INTENT_INTERACT),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<IntentInteractMsg>(payload_val) {
+                            Ok(m) => {
+                                let result =
+                                    svc.lock().resolve_interaction(&m.participant_id, &m.intent);
+                                Ok(CommandResponse::success(
+                                    cmd.command_id,
+                                    serde_json::to_value(&result).ok(),
+                                ))
+                            }
+                            Err(e) => Ok(reject_payload(&svc, subjects::INTENT_INTERACT, &cmd, e)),
+                        }
+                    }
+                },
+            );
+        }
+
+        // action.move (coordinator-approved movement)
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::ACTION_MOVE),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<ActionMoveMsg>(payload_val) {
+                            Ok(m) => {
+                                let actor_id =
+                                    m.participant_id.or(m.entity_id).or(m.id).ok_or_else(|| {
+                                        "Missing participant_id/entity_id/id in action.move payload"
+                                            .to_string()
+                                    });
+
+                                match actor_id {
+                                    Ok(id) => {
+                                        let mut svc = svc.lock();
+                                        match svc.apply_move_action(&id, m.dx, m.dy, m.dz) {
+                                            Ok(()) => {
+                                                if let Some(seq) = m.seq {
+                                                    svc.acknowledge_intent(&id, seq);
+                                                }
+                                                Ok(CommandResponse::success(cmd.command_id, None))
+                                            }
+                                            Err(e) => Ok(cmd_failed(
+                                                cmd.command_id,
+                                                unknown_entity(
+                                                    &id,
+                                                    format!("action.move failed: {}", e),
+                                                ),
+                                            )),
+                                        }
+                                    }
+                                    Err(msg) => {
+                                        svc.lock().record_drop(
+                                            subjects::ACTION_MOVE,
+                                            None,
+                                            DropReason::Invalid,
+                                            &msg,
+                                        );
+                                        Ok(cmd_failed(
+                                            cmd.command_id,
+                                            WorldCmdError::new(
+                                                WorldCmdErrorCode::InvalidPayload,
+                                                msg,
+                                            ),
+                                        ))
+                                    }
+                                }
+                            }
+                            Err(e) => Ok(reject_payload(&svc, subjects::ACTION_MOVE, &cmd, e)),
+                        }
+                    }
+                },
+            );
+        }
+
+        // action.place_structure (coordinator-approved building)
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::ACTION_PLACE_STRUCTURE),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<ActionPlaceStructureMsg>(payload_val) {
+                            Ok(m) => {
+                                let result =
+                                    svc.lock().place_structure(&m.participant_id, m.intent);
+                                match result {
+                                    Ok(spawned) => Ok(CommandResponse::success(
+                                        cmd.command_id,
+                                        serde_json::to_value(&spawned).ok(),
+                                    )),
+                                    Err(e) => Ok(cmd_failed(cmd.command_id, placement_failed(e))),
+                                }
+                            }
+                            Err(e) => Ok(reject_payload(
+                                &svc,
+                                subjects::ACTION_PLACE_STRUCTURE,
+                                &cmd,
+                                e,
+                            )),
+                        }
+                    }
+                },
+            );
+        }
+
+        // action.interact (coordinator-approved door / gate operation)
+        {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::ACTION_INTERACT),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let svc = svc.clone();
+                    async move {
+                        match serde_json::from_value::<ActionInteractMsg>(payload_val) {
+                            Ok(m) => {
+                                let result = svc.lock().interact(&m.participant_id, &m.intent);
+                                match result {
+                                    Ok(changed) => Ok(CommandResponse::success(
+                                        cmd.command_id,
+                                        serde_json::to_value(&changed).ok(),
+                                    )),
+                                    Err(e) => Ok(cmd_failed(cmd.command_id, interact_failed(e))),
+                                }
+                            }
+                            Err(e) => Ok(reject_payload(&svc, subjects::ACTION_INTERACT, &cmd, e)),
+                        }
+                    }
+                },
+            );
+        }
+
+        // world.shard.handoff – participants arriving from a neighbouring shard
+        if shard.is_some() {
+            let svc = self.service.clone();
+            let ack_client = client.clone();
+            let session = self.config.session.clone();
+            let ack_subjects = namespaces.shared(subjects::SHARD_HANDOFF_ACK);
+            on_each(
+                &client,
+                namespaces.shared(subjects::SHARD_HANDOFF),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    let ack = match serde_json::from_value::<WorldEvent<ShardHandoff>>(payload_val)
+                    {
+                        Ok(ev) => {
+                            let mut svc = svc.lock();
+                            svc.accept_handoff(&ev.payload).then(|| {
+                                info!(
+                                    "Took over '{}' from shard '{}'",
+                                    ev.payload.participant_id, ev.payload.from_shard
+                                );
+                                (ShardHandoffAck::new(&ev.payload), svc.tick_count())
+                            })
+                        }
+                        Err(e) => {
+                            log::warn!("world.shard.handoff bad payload: {}", e);
+                            None
+                        }
+                    };
+                    let ack_client = ack_client.clone();
+                    let session = session.clone();
+                    let ack_subjects = ack_subjects.clone();
+                    async move {
+                        if let Some((ack, frame)) = ack {
+                            publish_event(
+                                &ack_client,
+                                &ack_subjects,
+                                WorldEvent::new(session.as_str(), frame, &ack),
+                            )
+                            .await;
+                        }
+                        Ok(CommandResponse::success(cmd.command_id, None))
+                    }
+                },
+            );
+        }
+
+        // world.shard.handoff_ack – a neighbouring shard took over one of ours
+        if shard.is_some() {
+            let svc = self.service.clone();
+            on_each(
+                &client,
+                namespaces.shared(subjects::SHARD_HANDOFF_ACK),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    match serde_json::from_value::<WorldEvent<ShardHandoffAck>>(payload_val) {
+                        Ok(ev) => {
+                            if svc.lock().complete_handoff(&ev.payload) {
+                                info!(
+                                    "Shard '{}' took over '{}'",
+                                    ev.payload.to_shard, ev.payload.participant_id
+                                );
+                            }
+                        }
+                        Err(e) => log::warn!("world.shard.handoff_ack bad payload: {}", e),
+                    }
+                    async move { Ok(CommandResponse::success(cmd.command_id, None)) }
+                },
+            );
         }
 
         // -----------------------------------------------------------------------
@@ -289,56 +1469,361 @@ impl WorldBusAgent {
         let tick_hz = self.config.tick_rate_hz;
         let tick_client = client.clone();
         let tick_session = self.config.session.clone();
+        let tick_namespaces = namespaces.clone();
+        let cell_size = self.service.lock().interest_cell_size();
+        let instance_id = self.config.participant_id.clone();
+        let heartbeat_interval =
+            std::time::Duration::from_secs_f32(self.config.heartbeat_interval_s);
+        let mirror_interval = (self.config.mirror_interval_s > 0.0)
+            .then(|| std::time::Duration::from_secs_f32(self.config.mirror_interval_s));
 
         let tick_handle = tokio::spawn(async move {
+            let namespaces = tick_namespaces;
+            let mut tick_hz = tick_hz;
             let interval = std::time::Duration::from_secs_f32(1.0 / tick_hz);
             let mut timer = tokio::time::interval(interval);
+            // Reused every tick so steady-state event collection doesn't allocate.
+            let mut events = TickEvents::default();
+            let mut last_heartbeat: Option<std::time::Instant> = None;
+            let mut last_mirror = std::time::Instant::now();
             loop {
                 timer.tick().await;
 
                 // Hold the lock only long enough to tick, then release before publishing.
-                let tick_result = {
+                let (tick_result, wanted_hz, timescale) = {
                     let mut svc = service_tick.lock();
-                    svc.tick()
+                    (
+                        svc.tick_into(&mut events),
+                        svc.tick_rate_hz(),
+                        svc.timescale(),
+                    )
                 };
 
+                // Follow runtime tick-rate changes (admin `set_tick_rate`).
+                if (wanted_hz - tick_hz).abs() > tick_hz * 1e-3 {
+                    info!("Tick rate changed to {:.1}Hz", wanted_hz);
+                    tick_hz = wanted_hz;
+                    timer =
+                        tokio::time::interval(std::time::Duration::from_secs_f32(1.0 / tick_hz));
+                    timer.tick().await;
+                }
+
                 match tick_result {
-                    Ok(events) => {
+                    Ok(()) => {
                         let frame = events.tick;
                         let session = tick_session.as_str();
 
+                        // --- shard.handoff (shared subject, read by every shard) ---
+                        for handoff in &events.handoffs {
+                            info!(
+                                "Handing '{}' off to shard '{}'",
+                                handoff.participant_id, handoff.to_shard
+                            );
+                            publish_event(
+                                &tick_client,
+                                &namespaces.shared(subjects::SHARD_HANDOFF),
+                                WorldEvent::new(session, frame, handoff),
+                            )
+                            .await;
+                        }
+
                         // --- chunk.activated ---
                         for chunk in &events.activated {
                             publish_event(
                                 &tick_client,
-                                subjects::CHUNK_ACTIVATED,
+                                &namespaces.subjects(subjects::CHUNK_ACTIVATED),
                                 WorldEvent::new(session, frame, chunk),
                             )
                             .await;
                         }
 
+                        // --- chunk.voxels (after the activations they refine) ---
+                        for voxels in &events.chunk_voxels {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::CHUNK_VOXELS),
+                                WorldEvent::new(session, frame, voxels),
+                            )
+                            .await;
+                        }
+
+                        // --- chunk.data ---
+                        for data in &events.chunk_data {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::CHUNK_DATA),
+                                WorldEvent::new(session, frame, data),
+                            )
+                            .await;
+                        }
+
                         // --- chunk.deactivated ---
                         for chunk in &events.deactivated {
                             publish_event(
                                 &tick_client,
-                                subjects::CHUNK_DEACTIVATED,
+                                &namespaces.subjects(subjects::CHUNK_DEACTIVATED),
                                 WorldEvent::new(session, frame, chunk),
                             )
                             .await;
                         }
 
+                        // --- terrain.modified ---
+                        for ev in &events.terrain_modified {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::TERRAIN_MODIFIED),
+                                WorldEvent::new(session, frame, ev),
+                            )
+                            .await;
+                        }
+
+                        // --- nav.invalidated ---
+                        for ev in &events.nav_invalidated {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::NAV_INVALIDATED),
+                                WorldEvent::new(session, frame, ev),
+                            )
+                            .await;
+                        }
+
+                        // --- proximity.entered / proximity.exited ---
+                        for ev in &events.proximity_entered {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::PROXIMITY_ENTERED),
+                                WorldEvent::new(session, frame, ev),
+                            )
+                            .await;
+                        }
+                        for ev in &events.proximity_exited {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::PROXIMITY_EXITED),
+                                WorldEvent::new(session, frame, ev),
+                            )
+                            .await;
+                        }
+
+                        // --- structure.interest (per participant, on cell change) ---
+                        for interest in &events.structure_interest {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::STRUCTURE_INTEREST),
+                                WorldEvent::new(session, frame, interest),
+                            )
+                            .await;
+                        }
+
+                        // --- entity.meta (only on change) ---
+                        for meta in &events.entity_meta {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::ENTITY_META),
+                                WorldEvent::new(session, frame, meta),
+                            )
+                            .await;
+                        }
+
+                        // --- entity.emote (addressed to nearby listeners) ---
+                        for emote in &events.emotes {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::ENTITY_EMOTE),
+                                WorldEvent::new(session, frame, emote),
+                            )
+                            .await;
+                        }
+
+                        // --- entity.collision (at the contact point's cell) ---
+                        for collision in &events.collisions {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.scoped(
+                                    subjects::ENTITY_COLLISION,
+                                    cell_size,
+                                    collision.x,
+                                    collision.y,
+                                ),
+                                WorldEvent::new(session, frame, collision),
+                            )
+                            .await;
+                        }
+
+                        // --- scripted world events ---
+                        for ended in &events.events_ended {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::EVENT_ENDED),
+                                WorldEvent::new(session, frame, ended),
+                            )
+                            .await;
+                        }
+                        for started in &events.events_started {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::EVENT_STARTED),
+                                WorldEvent::new(session, frame, started),
+                            )
+                            .await;
+                        }
+
+                        // --- structure.removed / structure.spawned (vegetation) ---
+                        for removed in &events.structures_removed {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::STRUCTURE_REMOVED),
+                                WorldEvent::new(session, frame, removed),
+                            )
+                            .await;
+                        }
+                        for spawned in &events.structures_spawned {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::STRUCTURE_SPAWNED),
+                                WorldEvent::new(session, frame, spawned),
+                            )
+                            .await;
+                        }
+
+                        // --- structure.updated (moved props) ---
+                        for updated in &events.structures_updated {
+                            let s = &updated.structure;
+                            publish_event(
+                                &tick_client,
+                                &namespaces.scoped(
+                                    subjects::STRUCTURE_UPDATED,
+                                    cell_size,
+                                    s.x,
+                                    s.y,
+                                ),
+                                WorldEvent::new(session, frame, updated),
+                            )
+                            .await;
+                        }
+
+                        // --- structure.state_changed (doors, gates) ---
+                        for changed in &events.structure_states {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::STRUCTURE_STATE_CHANGED),
+                                WorldEvent::new(session, frame, changed),
+                            )
+                            .await;
+                        }
+
+                        // --- interest.subjects (cell subscriptions, before the events) ---
+                        for change in &events.interest_subjects {
+                            let change = namespaces.for_clients(change);
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::INTEREST_SUBJECTS),
+                                WorldEvent::new(session, frame, &change),
+                            )
+                            .await;
+                        }
+
+                        // --- interaction.result (intent.interact outcomes) ---
+                        for result in &events.interaction_results {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::INTERACTION_RESULT),
+                                WorldEvent::new(session, frame, result),
+                            )
+                            .await;
+                        }
+
+                        // --- entity.removed / entity.spawned (population, server entities) ---
+                        for removed in &events.entities_removed {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::ENTITY_REMOVED),
+                                WorldEvent::new(session, frame, removed),
+                            )
+                            .await;
+                        }
+                        for spawned in &events.entities_spawned {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.scoped(
+                                    subjects::ENTITY_SPAWNED,
+                                    cell_size,
+                                    spawned.x,
+                                    spawned.y,
+                                ),
+                                WorldEvent::new(session, frame, spawned),
+                            )
+                            .await;
+                        }
+
+                        // --- environment (low frequency) ---
+                        if let Some(env) = &events.environment {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::ENVIRONMENT),
+                                WorldEvent::new(session, frame, env),
+                            )
+                            .await;
+                        }
+
+                        // --- entity.handle (before any batch that uses it) ---
+                        for handle in &events.entity_handles {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::ENTITY_HANDLE),
+                                WorldEvent::new(session, frame, handle),
+                            )
+                            .await;
+                        }
+
                         // --- entity.transform (every participant, every tick) ---
                         for transform in &events.entity_transforms {
                             publish_event(
                                 &tick_client,
-                                subjects::ENTITY_TRANSFORM,
+                                &namespaces.scoped(
+                                    subjects::ENTITY_TRANSFORM,
+                                    cell_size,
+                                    transform.x,
+                                    transform.y,
+                                ),
                                 WorldEvent::new(session, frame, transform),
                             )
                             .await;
                         }
+                        if !events.transform_batch.is_empty() {
+                            publish_event(
+                                &tick_client,
+                                &namespaces.subjects(subjects::ENTITY_TRANSFORMS),
+                                WorldEvent::new(session, frame, &events.transform_batch),
+                            )
+                            .await;
+                        }
                     }
                     Err(e) => log::warn!("World tick error: {}", e),
                 }
+
+                // --- heartbeat / mirror (standby support) ---
+                let now = std::time::Instant::now();
+                if last_heartbeat.is_none_or(|t| now - t >= heartbeat_interval) {
+                    last_heartbeat = Some(now);
+                    let heartbeat = WorldHeartbeat {
+                        instance_id: instance_id.clone(),
+                        frame: events.tick,
+                        timescale,
+                    };
+                    publish_event(
+                        &tick_client,
+                        &namespaces.subjects(subjects::HEARTBEAT),
+                        WorldEvent::new(tick_session.as_str(), events.tick, &heartbeat),
+                    )
+                    .await;
+                }
+                if mirror_interval.is_some_and(|i| now - last_mirror >= i) {
+                    last_mirror = now;
+                    if let Err(e) = service_tick.lock().write_checkpoint(MIRROR_CHECKPOINT) {
+                        log::warn!("Mirror checkpoint failed: {}", e);
+                    }
+                }
             }
         });
 
@@ -355,31 +1840,298 @@ impl WorldBusAgent {
             }
         }
 
+        if let Some(path) = &self.config.state_file {
+            match self.service.lock().save_state(path) {
+                Ok(()) => info!("Saved world state to {}", path.display()),
+                Err(e) => log::error!("Failed to save world state to {}: {}", path.display(), e),
+            }
+        }
+
         // Drop client to gracefully close the connection.
         drop(client);
         Ok(())
     }
+
+    /// Join as `world-standby` and block until the primary's heartbeat has
+    /// been silent for `failover_timeout_s`.  Returns the primary's instance
+    /// id, if one was ever heard.
+    async fn wait_for_primary_loss(&self) -> Result<Option<String>> {
+        use janet_client::messages::CommandResponse;
+        use janet_client::{ClientBuilder, JanetExecutor};
+
+        info!(
+            "WorldBusAgent standing by as '{}' in session '{}'",
+            self.config.participant_id, self.config.session
+        );
+
+        let client: JanetExecutor = ClientBuilder::new()
+            .session(&self.config.session)
+            .participant(
+                &self.config.participant_id,
+                vec!["world-standby".to_string()],
+            )
+            .coordinator_url(&self.config.endpoint)
+            .connect()
+            .await
+            .context("Failed to connect standby world service to janet bus")?;
+
+        let shard_id = self.service.lock().shard_id().map(str::to_string);
+        let namespaces = Namespaces::new(&self.config, shard_id.as_deref());
+        let timeout = std::time::Duration::from_secs_f32(self.config.failover_timeout_s);
+        let monitor = Arc::new(Mutex::new(HeartbeatMonitor::new(
+            timeout,
+            std::time::Instant::now(),
+        )));
+        {
+            let monitor = monitor.clone();
+            on_each(
+                &client,
+                namespaces.subjects(subjects::HEARTBEAT),
+                move |cmd| {
+                    let payload_val =
+                        serde_json::Value::Object(cmd.payload.clone().into_iter().collect());
+                    match serde_json::from_value::<WorldEvent<WorldHeartbeat>>(payload_val) {
+                        Ok(ev) => monitor
+                            .lock()
+                            .observe(&ev.payload, std::time::Instant::now()),
+                        Err(e) => log::warn!("world.heartbeat bad payload: {}", e),
+                    }
+                    async move { Ok(CommandResponse::success(cmd.command_id, None)) }
+                },
+            );
+        }
+
+        let mut poll = tokio::time::interval(std::time::Duration::from_millis(250));
+        loop {
+            poll.tick().await;
+            let monitor = monitor.lock();
+            if monitor.primary_lost(std::time::Instant::now()) {
+                let primary = monitor.primary().map(str::to_string);
+                log::warn!(
+                    "Primary world service {} silent for {:.1}s – taking over",
+                    primary.as_deref().unwrap_or("(never seen)"),
+                    self.config.failover_timeout_s
+                );
+                drop(client);
+                return Ok(primary);
+            }
+        }
+    }
+
+    /// Load the primary's last mirror checkpoint into the service.
+    fn restore_mirror(&self) {
+        let mut svc = self.service.lock();
+        match svc.read_checkpoint(MIRROR_CHECKPOINT) {
+            Ok(snapshot) => {
+                svc.restore_snapshot(&snapshot);
+                info!(
+                    "Restored mirror checkpoint ({} entities, {} terrain edits)",
+                    snapshot.entities.len(),
+                    snapshot.terrain_modifications.len()
+                );
+            }
+            Err(e) => log::warn!("No usable mirror checkpoint ({}) – starting fresh", e),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Payload helpers
+// ---------------------------------------------------------------------------
+
+/// Protocol version advertised in a command payload (default `1`), capped at
+/// what this server speaks.
+fn requested_protocol_version<'a>(
+    payload: impl IntoIterator<Item = (&'a String, &'a serde_json::Value)>,
+) -> u32 {
+    payload
+        .into_iter()
+        .find(|(k, _)| k.as_str() == "protocol_version")
+        .and_then(|(_, v)| v.as_u64())
+        .map(|v| (v as u32).min(PROTOCOL_VERSION))
+        .unwrap_or(1)
+}
+
+/// Count a command whose payload didn't parse as a dropped `kind`
+/// (attributed to its `participant_id` / `entity_id` / `id`, if any) and
+/// build the failure reply.
+fn reject_payload(
+    svc: &Mutex<WorldService>,
+    kind: &str,
+    cmd: &janet_client::messages::Command,
+    error: impl std::fmt::Display,
+) -> janet_client::messages::CommandResponse {
+    let message = format!("Invalid payload: {}", error);
+    let sender = ["participant_id", "entity_id", "id"]
+        .iter()
+        .find_map(|key| cmd.payload.get(*key)?.as_str());
+    svc.lock()
+        .record_drop(kind, sender, DropReason::Invalid, &message);
+    cmd_failed(
+        cmd.command_id.clone(),
+        WorldCmdError::new(WorldCmdErrorCode::InvalidPayload, message),
+    )
+}
+
+/// Failed reply whose message is `error` (see [`WorldCmdError`]).
+fn cmd_failed(command_id: String, error: WorldCmdError) -> janet_client::messages::CommandResponse {
+    janet_client::messages::CommandResponse::failed(command_id, error.encode())
+}
+
+/// [`WorldCmdErrorCode::UnknownEntity`] for `entity_id`.
+fn unknown_entity(entity_id: &str, message: String) -> WorldCmdError {
+    WorldCmdError::new(WorldCmdErrorCode::UnknownEntity, message)
+        .with_details(serde_json::json!({ "entity_id": entity_id }))
+}
+
+/// Error reply for a refused placement: reach and buildable types are
+/// permissions, non-finite numbers a bad payload, the rest is about the
+/// spot or the structure.
+fn placement_failed(e: PlacementError) -> WorldCmdError {
+    let message = e.to_string();
+    match e {
+        PlacementError::UnknownEntity(id) => unknown_entity(&id, message),
+        PlacementError::NotBuildable(type_id) => {
+            WorldCmdError::new(WorldCmdErrorCode::Unauthorized, message)
+                .with_details(serde_json::json!({ "type_id": type_id }))
+        }
+        PlacementError::OutOfReach { reach, .. } => {
+            WorldCmdError::new(WorldCmdErrorCode::Unauthorized, message)
+                .with_details(serde_json::json!({ "reach": reach }))
+        }
+        PlacementError::NonFinite => WorldCmdError::new(WorldCmdErrorCode::InvalidPayload, message),
+        PlacementError::OutOfBounds | PlacementError::Ground | PlacementError::MissingTypeId => {
+            WorldCmdError::new(WorldCmdErrorCode::Rejected, message)
+        }
+        PlacementError::Overlaps(id) => WorldCmdError::new(WorldCmdErrorCode::Rejected, message)
+            .with_details(serde_json::json!({ "overlaps": id })),
+        PlacementError::InvalidMetadata { key, .. } => {
+            WorldCmdError::new(WorldCmdErrorCode::Rejected, message)
+                .with_details(serde_json::json!({ "key": key }))
+        }
+        PlacementError::UnknownState(state) => {
+            WorldCmdError::new(WorldCmdErrorCode::Rejected, message)
+                .with_details(serde_json::json!({ "state": state }))
+        }
+        PlacementError::Physics(_) => WorldCmdError::new(WorldCmdErrorCode::Failed, message),
+    }
+}
+
+/// Error reply for a refused interaction: reach is a permission, a target
+/// that can't do it is a rejection.
+fn interact_failed(e: InteractError) -> WorldCmdError {
+    let message = e.to_string();
+    match e {
+        InteractError::UnknownEntity(id) => unknown_entity(&id, message),
+        InteractError::NotInteractive(target) => {
+            WorldCmdError::new(WorldCmdErrorCode::Rejected, message)
+                .with_details(serde_json::json!({ "target_id": target }))
+        }
+        InteractError::OutOfReach { reach, .. } => {
+            WorldCmdError::new(WorldCmdErrorCode::Unauthorized, message)
+                .with_details(serde_json::json!({ "reach": reach }))
+        }
+        InteractError::NoTransition { state, verb, .. } => {
+            WorldCmdError::new(WorldCmdErrorCode::Rejected, message)
+                .with_details(serde_json::json!({ "state": state, "verb": verb }))
+        }
+        InteractError::NoHandler { target, verb } => {
+            WorldCmdError::new(WorldCmdErrorCode::Rejected, message)
+                .with_details(serde_json::json!({ "target_id": target, "verb": verb }))
+        }
+        InteractError::Refused(_) => WorldCmdError::new(WorldCmdErrorCode::Rejected, message),
+        InteractError::Physics(_) => WorldCmdError::new(WorldCmdErrorCode::Failed, message),
+    }
+}
+
+/// Subject namespaces the agent serves: its session's own, plus the
+/// unscoped pre-session one when `legacy_subjects` is set.
+#[derive(Debug, Clone)]
+struct Namespaces(Vec<subjects::Namespace>);
+
+impl Namespaces {
+    fn new(config: &WorldBusConfig, shard: Option<&str>) -> Self {
+        let mut all = vec![subjects::Namespace::new(Some(&config.session), shard)];
+        if config.legacy_subjects {
+            all.push(subjects::Namespace::new(None, shard));
+        }
+        Self(all)
+    }
+
+    /// `base` in every namespace.
+    fn subjects(&self, base: &str) -> Vec<String> {
+        self.0.iter().map(|ns| ns.subject(base)).collect()
+    }
+
+    /// `base` in every namespace, shared by all shards of the session.
+    fn shared(&self, base: &str) -> Vec<String> {
+        self.0.iter().map(|ns| ns.shared(base)).collect()
+    }
+
+    /// Where an event at `(x, y)` goes: the subject of its interest cell
+    /// (shared by all shards, as cells are) when `cell_size` is set, else
+    /// `base` world-wide.
+    fn scoped(&self, base: &str, cell_size: Option<f32>, x: f32, y: f32) -> Vec<String> {
+        match cell_size {
+            Some(size) => {
+                let (cx, cy) = subjects::cell_of(x, y, size);
+                self.shared(&subjects::cell(cx, cy, base))
+            }
+            None => self.subjects(base),
+        }
+    }
+
+    /// `change` with its base subjects in the session's namespace, as
+    /// clients subscribe to them.
+    fn for_clients(&self, change: &InterestSubjects) -> InterestSubjects {
+        let session = |base: &String| self.0[0].shared(base);
+        InterestSubjects {
+            subscribe: change.subscribe.iter().map(session).collect(),
+            unsubscribe: change.unsubscribe.iter().map(session).collect(),
+            ..change.clone()
+        }
+    }
+}
+
+/// Register `handler` on each of `subjects`.
+fn on_each<F, Fut>(client: &janet_client::JanetExecutor, subjects: Vec<String>, handler: F)
+where
+    F: Fn(janet_client::messages::Command) -> Fut + Clone + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<janet_client::messages::CommandResponse>>
+        + Send
+        + 'static,
+{
+    for subject in subjects {
+        client.on_command(&subject, handler.clone());
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Publish helper
 // ---------------------------------------------------------------------------
 
-/// Serialise `event` and publish it on `subject`.
+/// Serialise `event` once and publish it on each of `subjects`.
 ///
 /// Errors are logged and swallowed — a single failed publish should not crash
 /// the tick loop.
 async fn publish_event<T: serde::Serialize>(
     client: &janet_client::JanetExecutor,
-    subject: &str,
+    subjects: &[String],
     event: WorldEvent<T>,
 ) {
     match serde_json::to_vec(&event) {
         Ok(payload) => {
-            if let Err(e) = client.publish(subject, Bytes::from(payload)).await {
-                log::warn!("Failed to publish to {}: {}", subject, e);
+            let payload = Bytes::from(payload);
+            for subject in subjects {
+                if let Err(e) = client.publish(subject, payload.clone()).await {
+                    log::warn!("Failed to publish to {}: {}", subject, e);
+                }
             }
         }
-        Err(e) => log::warn!("Failed to serialise event for {}: {}", subject, e),
+        Err(e) => log::warn!(
+            "Failed to serialise event for {}: {}",
+            subjects.join(", "),
+            e
+        ),
     }
 }
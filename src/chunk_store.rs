@@ -0,0 +1,131 @@
+//! On-disk chunk store: persist generated [`HeightChunk`]s so expensive
+//! generation only happens once per world.
+//!
+//! Each chunk lives in its own file `{cx}_{cy}_{lod}.chunk` inside the store
+//! directory.  Files are little-endian:
+//!
+//! ```text
+//! magic     [u8; 4]  = b"JWHC"
+//! version   u8       = 2
+//! seed      u64
+//! resolution u32
+//! origin_x  f32
+//! origin_y  f32
+//! cell_size f32
+//! heights   [f32; resolution * resolution]
+//! ```
+//!
+//! The `seed` field holds the caller's generation key — the world seed, or a
+//! hash of the seed and post-process settings (see `HeightmapTerrain`).  A
+//! file whose key or layout does not match what the caller expects is
+//! treated as a miss and overwritten on the next save.  `version` follows
+//! `TERRAIN_VERSION`, so chunks generated by an older noise are rebuilt.
+
+use crate::terrain::HeightChunk;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const MAGIC: &[u8; 4] = b"JWHC";
+const VERSION: u8 = 2;
+const HEADER_LEN: usize = 4 + 1 + 8 + 4 + 4 + 4 + 4;
+
+/// Distinguishes temp files of concurrent saves (chunk workers).
+static TMP_SEQ: AtomicU64 = AtomicU64::new(0);
+
+pub struct ChunkStore {
+    dir: PathBuf,
+}
+
+impl ChunkStore {
+    /// Open (and create if needed) a store rooted at `dir`.
+    pub fn open(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, cx: i32, cy: i32, lod: u8) -> PathBuf {
+        self.dir.join(format!("{}_{}_{}.chunk", cx, cy, lod))
+    }
+
+    /// Read a chunk generated with `seed`, or `None` on miss / mismatch.
+    pub fn load(&self, seed: u64, cx: i32, cy: i32, lod: u8) -> Option<HeightChunk> {
+        let bytes = fs::read(self.path(cx, cy, lod)).ok()?;
+        decode(&bytes, seed)
+    }
+
+    /// Write a chunk; overwrites any existing file for the same key.
+    pub fn save(
+        &self,
+        seed: u64,
+        cx: i32,
+        cy: i32,
+        lod: u8,
+        chunk: &HeightChunk,
+    ) -> io::Result<()> {
+        // Write to a temp file first so a crash never leaves a torn chunk.
+        let path = self.path(cx, cy, lod);
+        let seq = TMP_SEQ.fetch_add(1, Ordering::Relaxed);
+        let tmp = path.with_extension(format!("chunk.{}.tmp", seq));
+        fs::write(&tmp, encode(seed, chunk))?;
+        fs::rename(tmp, path)
+    }
+}
+
+fn encode(seed: u64, chunk: &HeightChunk) -> Vec<u8> {
+    let mut out = Vec::with_capacity(HEADER_LEN + chunk.heights.len() * 4);
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.extend_from_slice(&seed.to_le_bytes());
+    out.extend_from_slice(&(chunk.resolution as u32).to_le_bytes());
+    out.extend_from_slice(&chunk.world_origin_x.to_le_bytes());
+    out.extend_from_slice(&chunk.world_origin_y.to_le_bytes());
+    out.extend_from_slice(&chunk.cell_size.to_le_bytes());
+    for h in &chunk.heights {
+        out.extend_from_slice(&h.to_le_bytes());
+    }
+    out
+}
+
+fn decode(bytes: &[u8], expected_seed: u64) -> Option<HeightChunk> {
+    if bytes.len() < HEADER_LEN || &bytes[0..4] != MAGIC || bytes[4] != VERSION {
+        return None;
+    }
+
+    let u32_at = |o: usize| u32::from_le_bytes(bytes[o..o + 4].try_into().unwrap());
+    let f32_at = |o: usize| f32::from_le_bytes(bytes[o..o + 4].try_into().unwrap());
+
+    let seed = u64::from_le_bytes(bytes[5..13].try_into().unwrap());
+    if seed != expected_seed {
+        return None;
+    }
+
+    let resolution = u32_at(13) as usize;
+    let world_origin_x = f32_at(17);
+    let world_origin_y = f32_at(21);
+    let cell_size = f32_at(25);
+
+    let body = &bytes[HEADER_LEN..];
+    if body.len() != resolution * resolution * 4 {
+        return None;
+    }
+    let heights = body
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect();
+
+    Some(HeightChunk {
+        heights,
+        resolution,
+        world_origin_x,
+        world_origin_y,
+        cell_size,
+        materials: Vec::new(),
+    })
+}
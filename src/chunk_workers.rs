@@ -0,0 +1,87 @@
+//! Background chunk generation.
+//!
+//! A fixed pool of OS threads runs terrain generation off the tick.  The
+//! pool is deliberately dumb: it takes cell coordinates (with the LOD they
+//! are wanted at) in submission order (callers submit nearest-first), runs
+//! the job for each, and reports the coordinate back once the job is done.
+//! The job itself decides what "generate" means — for [`HeightmapTerrain`]
+//! it warms the chunk cache, so the tick that later activates the cell
+//! finds the chunk ready.
+//!
+//! A job that panics is logged and reported done like any other, so its
+//! worker keeps running and the cell can be asked for again.
+//!
+//! Dropping the pool closes the queue; workers finish their current job and
+//! exit.
+//!
+//! [`HeightmapTerrain`]: crate::terrain::HeightmapTerrain
+
+use crate::types::CellCoord;
+use log::warn;
+use parking_lot::Mutex;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+type Job = dyn Fn(CellCoord, u8) + Send + Sync;
+
+pub struct ChunkWorkers {
+    jobs: Sender<(CellCoord, u8)>,
+    done: Receiver<CellCoord>,
+}
+
+impl ChunkWorkers {
+    /// Start `threads` workers (at least one) that run `job` per coordinate.
+    pub fn new(threads: usize, job: impl Fn(CellCoord, u8) + Send + Sync + 'static) -> Self {
+        let (jobs, queue) = mpsc::channel::<(CellCoord, u8)>();
+        let (finished, done) = mpsc::channel();
+        let queue = Arc::new(Mutex::new(queue));
+        let job: Arc<Job> = Arc::new(job);
+
+        for i in 0..threads.max(1) {
+            let queue = queue.clone();
+            let finished = finished.clone();
+            let job = job.clone();
+            let spawned = thread::Builder::new()
+                .name(format!("chunk-worker-{}", i))
+                .spawn(move || loop {
+                    // Hold the queue lock only while waiting for the next job.
+                    let next = queue.lock().recv();
+                    let Ok((coord, lod)) = next else {
+                        break;
+                    };
+                    if let Err(panic) = panic::catch_unwind(AssertUnwindSafe(|| job(coord, lod))) {
+                        let message = panic
+                            .downcast_ref::<&str>()
+                            .copied()
+                            .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                            .unwrap_or("unknown panic");
+                        warn!(
+                            "Chunk job for {} at LOD {} panicked: {}",
+                            coord, lod, message
+                        );
+                    }
+                    if finished.send(coord).is_err() {
+                        break;
+                    }
+                });
+            if let Err(e) = spawned {
+                warn!("Failed to start chunk worker {}: {}", i, e);
+            }
+        }
+
+        Self { jobs, done }
+    }
+
+    /// Queue a coordinate at `lod`; jobs start in submission order.
+    pub fn submit(&self, coord: CellCoord, lod: u8) {
+        // Only fails when every worker is gone; the cell then stays pending.
+        let _ = self.jobs.send((coord, lod));
+    }
+
+    /// Coordinates whose job finished since the last call (never blocks).
+    pub fn completed(&self) -> Vec<CellCoord> {
+        self.done.try_iter().collect()
+    }
+}
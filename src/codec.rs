@@ -0,0 +1,87 @@
+//! Compact text encodings for bulk payloads carried inside JSON messages.
+//!
+//! Bus payloads are JSON, so binary data (compressed snapshots, raw height
+//! grids) travels as gzip wrapped in standard padded base64.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{self, Read, Write};
+
+/// Gzip `bytes` and base64 the result.
+pub fn encode(bytes: &[u8]) -> io::Result<String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder.write_all(bytes)?;
+    Ok(base64_encode(&encoder.finish()?))
+}
+
+/// Inverse of [`encode`].
+pub fn decode(text: &str) -> io::Result<Vec<u8>> {
+    let compressed = base64_decode(text)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "bad base64"))?;
+    let mut bytes = Vec::new();
+    GzDecoder::new(compressed.as_slice()).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// [`encode`] a height grid as little-endian `f32`s.
+pub fn encode_heights(heights: &[f32]) -> io::Result<String> {
+    let bytes: Vec<u8> = heights.iter().flat_map(|h| h.to_le_bytes()).collect();
+    encode(&bytes)
+}
+
+/// Inverse of [`encode_heights`].
+pub fn decode_heights(text: &str) -> io::Result<Vec<f32>> {
+    let bytes = decode(text)?;
+    if !bytes.len().is_multiple_of(4) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "height data is not a whole number of f32s",
+        ));
+    }
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard (RFC 4648, padded) base64.
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Inverse of [`base64_encode`]; `None` for malformed input.
+pub fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=').as_bytes();
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let v = BASE64.iter().position(|&b| b == c)? as u32;
+            n |= v << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
@@ -0,0 +1,47 @@
+//! Entity collision events from the physics simulation.
+//!
+//! Once every tracked entity has moved for the tick, the service steps the
+//! default simulation by the tick's `dt` and drains the contacts that
+//! started during the step.  Those involving at least one tracked entity
+//! (participant or server entity) become [`EntityCollision`]s carrying the
+//! solver's impulse; contacts between static bodies (terrain, structures,
+//! vegetation) are dropped.
+
+use crate::protocol::EntityCollision;
+use crate::types::Vec3;
+use janet_operations::physics::types::ContactEvent;
+
+/// Turn the simulation's new `contacts` into collision events (sorted by
+/// pair).  `position` is the tracked position of an entity, `None` for
+/// bodies that aren't entities.
+///
+/// Two entities are ordered lexically; an entity that hit a static body is
+/// always `entity_a`, with the body id as `entity_b`.  The contact point
+/// takes the height of `entity_a`.
+pub fn entity_collisions(
+    contacts: Vec<ContactEvent>,
+    position: impl Fn(&str) -> Option<Vec3>,
+) -> Vec<EntityCollision> {
+    let mut collisions: Vec<EntityCollision> = contacts
+        .into_iter()
+        .filter_map(|c| {
+            let (a, b) = match (position(&c.body_a), position(&c.body_b)) {
+                (None, None) => return None,
+                (Some(_), Some(_)) if c.body_b < c.body_a => (c.body_b, c.body_a),
+                (None, Some(_)) => (c.body_b, c.body_a),
+                _ => (c.body_a, c.body_b),
+            };
+            let z = position(&a)?.z;
+            Some(EntityCollision {
+                entity_a: a,
+                entity_b: b,
+                impulse: c.impulse,
+                x: c.point.0,
+                y: c.point.1,
+                z,
+            })
+        })
+        .collect();
+    collisions.sort_by(|x, y| (&x.entity_a, &x.entity_b).cmp(&(&y.entity_a, &y.entity_b)));
+    collisions
+}
@@ -0,0 +1,168 @@
+//! Composite terrain: several sources layered into one surface.
+//!
+//! Layers are applied in order on top of a running height that starts at
+//! zero.  An [`LayerBlend::Add`] layer adds `weight × its height` (base
+//! noise at weight 1, detail noise at a small weight); a
+//! [`LayerBlend::Mix`] layer pulls the running height towards its own by
+//! `weight` (`1` replaces it outright, which is how manual overrides such as
+//! a flattened town square are expressed).  A layer limited to
+//! [`TerrainLayer::within`] bounds leaves everything outside untouched.
+//!
+//! The result is not reproducible from a seed, so chunks advertise seed `0`
+//! and clients must take heights from the server.
+
+use crate::terrain::{footprint_collider, ChunkDescriptor, TerrainSource};
+use crate::types::Vec3;
+use janet_operations::physics::types::ColliderShape;
+use std::any::Any;
+use std::sync::Arc;
+
+/// How a layer combines with the layers below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerBlend {
+    /// `height += weight * layer`
+    Add,
+    /// `height += weight * (layer - height)` (weight clamped to `0..=1`)
+    Mix,
+}
+
+/// Axis-aligned rectangle a layer is limited to (`min` inclusive, `max`
+/// exclusive).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayerBounds {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+}
+
+impl LayerBounds {
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        (self.min_x..self.max_x).contains(&x) && (self.min_y..self.max_y).contains(&y)
+    }
+}
+
+pub struct TerrainLayer {
+    pub source: Arc<dyn TerrainSource>,
+    pub weight: f32,
+    pub blend: LayerBlend,
+    /// `None` = the layer covers the whole world.
+    pub bounds: Option<LayerBounds>,
+}
+
+impl TerrainLayer {
+    pub fn add(source: Arc<dyn TerrainSource>, weight: f32) -> Self {
+        Self {
+            source,
+            weight,
+            blend: LayerBlend::Add,
+            bounds: None,
+        }
+    }
+
+    pub fn mix(source: Arc<dyn TerrainSource>, weight: f32) -> Self {
+        Self {
+            source,
+            weight,
+            blend: LayerBlend::Mix,
+            bounds: None,
+        }
+    }
+
+    /// Limit the layer to `min..max`.
+    pub fn within(mut self, min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Self {
+        self.bounds = Some(LayerBounds {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        });
+        self
+    }
+
+    fn covers(&self, x: f32, y: f32) -> bool {
+        self.bounds.is_none_or(|b| b.contains(x, y))
+    }
+
+    fn apply(&self, below: f32, x: f32, y: f32) -> f32 {
+        if !self.covers(x, y) {
+            return below;
+        }
+        let h = self.source.height_at(x, y);
+        match self.blend {
+            LayerBlend::Add => below + self.weight * h,
+            LayerBlend::Mix => below + self.weight.clamp(0.0, 1.0) * (h - below),
+        }
+    }
+}
+
+pub struct CompositeTerrain {
+    pub chunk_size: f32,
+    layers: Vec<TerrainLayer>,
+}
+
+impl CompositeTerrain {
+    /// Empty composite (height `0` everywhere until layers are added).
+    pub fn new(chunk_size: f32) -> Self {
+        Self {
+            chunk_size,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Append a layer on top of the existing ones.
+    pub fn with_layer(mut self, layer: TerrainLayer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    pub fn layers(&self) -> &[TerrainLayer] {
+        &self.layers
+    }
+}
+
+impl TerrainSource for CompositeTerrain {
+    fn height_at(&self, x: f32, y: f32) -> f32 {
+        self.layers
+            .iter()
+            .fold(0.0, |below, layer| layer.apply(below, x, y))
+    }
+
+    fn normal_at(&self, x: f32, y: f32) -> Vec3 {
+        let eps = 0.5;
+        let h_l = self.height_at(x - eps, y);
+        let h_r = self.height_at(x + eps, y);
+        let h_d = self.height_at(x, y - eps);
+        let h_u = self.height_at(x, y + eps);
+        Vec3::new(h_l - h_r, h_d - h_u, 2.0 * eps)
+    }
+
+    /// Highest water surface any covering layer reports, if the composite
+    /// ground lies below it.
+    fn water_level_at(&self, x: f32, y: f32) -> Option<f32> {
+        let level = self
+            .layers
+            .iter()
+            .filter(|l| l.covers(x, y))
+            .filter_map(|l| l.source.water_level_at(x, y))
+            .reduce(f32::max)?;
+        (self.height_at(x, y) < level).then_some(level)
+    }
+
+    fn chunk_descriptor(&self, _cx: i32, _cy: i32, _lod: u8) -> Option<ChunkDescriptor> {
+        Some(ChunkDescriptor {
+            seed: 0,
+            chunk_size: self.chunk_size,
+            hydrology: None,
+            materials: Default::default(),
+        })
+    }
+
+    fn collider_for_chunk(&self, _cx: i32, _cy: i32, _lod: u8) -> Option<ColliderShape> {
+        Some(footprint_collider(self.chunk_size))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
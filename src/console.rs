@@ -0,0 +1,233 @@
+//! Operator console: a tiny line-oriented command language for poking the
+//! live world over `world.cmd.console`.
+//!
+//! | Command                        | Effect                                  |
+//! |--------------------------------|-----------------------------------------|
+//! | `help`                         | list commands                           |
+//! | `spawn <id> <x> <y> [z]`       | start tracking a new entity             |
+//! | `tp <id> <x> <y> [z]`          | move an existing participant/entity     |
+//! | `time`                         | print the world clock                   |
+//! | `time <seconds> \| <HH:MM>`    | set the world clock                     |
+//! | `dump <cx> <cy> [cz]`          | describe a streaming cell (layer `cz`)  |
+//!
+//! `HH:MM` is read on a 24-hour dial and scaled onto the configured day
+//! length.  Parsing is kept separate from execution so the admin CLI can
+//! reject typos before anything reaches the bus.
+
+use crate::service::WorldService;
+use crate::types::{CellCoord, Vec3};
+use std::fmt::Write;
+use thiserror::Error;
+
+pub const HELP: &str = "\
+commands:
+  help                      list commands
+  spawn <id> <x> <y> [z]    start tracking a new entity
+  tp <id> <x> <y> [z]       move an existing participant/entity
+  time                      print the world clock
+  time <seconds>|<HH:MM>    set the world clock
+  dump <cx> <cy> [cz]       describe a streaming cell (layer cz)";
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ConsoleError {
+    #[error("empty command (try 'help')")]
+    Empty,
+    #[error("unknown command '{0}' (try 'help')")]
+    UnknownCommand(String),
+    #[error("usage: {0}")]
+    Usage(&'static str),
+    #[error("'{0}' is not a number")]
+    BadNumber(String),
+    #[error("'{0}' is not a time (expected seconds or HH:MM)")]
+    BadTime(String),
+    #[error("entity '{0}' already exists")]
+    AlreadyExists(String),
+    #[error("unknown participant/entity '{0}'")]
+    UnknownEntity(String),
+    /// The service refused the command.
+    #[error("{0}")]
+    Failed(String),
+}
+
+// ---------------------------------------------------------------------------
+// Commands
+// ---------------------------------------------------------------------------
+
+/// Clock argument to `time`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeSpec {
+    /// Seconds past midnight in world time.
+    Seconds(f32),
+    /// Hours and minutes on a 24-hour dial.
+    Clock { hours: u32, minutes: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsoleCommand {
+    Help,
+    Spawn { id: String, position: Vec3 },
+    Teleport { id: String, position: Vec3 },
+    Time(Option<TimeSpec>),
+    Dump(CellCoord),
+}
+
+/// Parse a single console line.
+pub fn parse(line: &str) -> Result<ConsoleCommand, ConsoleError> {
+    let mut words = line.split_whitespace();
+    let Some(verb) = words.next() else {
+        return Err(ConsoleError::Empty);
+    };
+    let args: Vec<&str> = words.collect();
+
+    match verb.to_ascii_lowercase().as_str() {
+        "help" | "?" => Ok(ConsoleCommand::Help),
+        "spawn" => {
+            let (id, position) = parse_id_and_position(&args, "spawn <id> <x> <y> [z]")?;
+            Ok(ConsoleCommand::Spawn { id, position })
+        }
+        "tp" | "teleport" => {
+            let (id, position) = parse_id_and_position(&args, "tp <id> <x> <y> [z]")?;
+            Ok(ConsoleCommand::Teleport { id, position })
+        }
+        "time" => match args.as_slice() {
+            [] => Ok(ConsoleCommand::Time(None)),
+            [t] => Ok(ConsoleCommand::Time(Some(parse_time(t)?))),
+            _ => Err(ConsoleError::Usage("time [<seconds>|<HH:MM>]")),
+        },
+        "dump" => match args.as_slice() {
+            [cx, cy] => Ok(ConsoleCommand::Dump(CellCoord::new(
+                parse_num(cx)?,
+                parse_num(cy)?,
+                0,
+            ))),
+            [cx, cy, cz] => Ok(ConsoleCommand::Dump(CellCoord::new(
+                parse_num(cx)?,
+                parse_num(cy)?,
+                parse_num(cz)?,
+            ))),
+            _ => Err(ConsoleError::Usage("dump <cx> <cy> [cz]")),
+        },
+        other => Err(ConsoleError::UnknownCommand(other.to_string())),
+    }
+}
+
+fn parse_num<T: std::str::FromStr>(s: &str) -> Result<T, ConsoleError> {
+    s.parse()
+        .map_err(|_| ConsoleError::BadNumber(s.to_string()))
+}
+
+fn parse_id_and_position(
+    args: &[&str],
+    usage: &'static str,
+) -> Result<(String, Vec3), ConsoleError> {
+    let (id, x, y, z) = match args {
+        [id, x, y] => (id, x, y, "0"),
+        [id, x, y, z] => (id, x, y, *z),
+        _ => return Err(ConsoleError::Usage(usage)),
+    };
+    Ok((
+        id.to_string(),
+        Vec3::new(parse_num(x)?, parse_num(y)?, parse_num(z)?),
+    ))
+}
+
+fn parse_time(s: &str) -> Result<TimeSpec, ConsoleError> {
+    let bad = || ConsoleError::BadTime(s.to_string());
+    match s.split_once(':') {
+        Some((h, m)) => {
+            let hours: u32 = h.parse().map_err(|_| bad())?;
+            let minutes: u32 = m.parse().map_err(|_| bad())?;
+            if hours >= 24 || minutes >= 60 {
+                return Err(bad());
+            }
+            Ok(TimeSpec::Clock { hours, minutes })
+        }
+        None => s
+            .parse::<f32>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .map(TimeSpec::Seconds)
+            .ok_or_else(bad),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Execution
+// ---------------------------------------------------------------------------
+
+/// Run a parsed command against the service and return its text output.
+pub fn execute(svc: &mut WorldService, cmd: ConsoleCommand) -> Result<String, ConsoleError> {
+    match cmd {
+        ConsoleCommand::Help => Ok(HELP.to_string()),
+        ConsoleCommand::Spawn { id, position } => {
+            if svc.participant_position(&id).is_some() {
+                return Err(ConsoleError::AlreadyExists(id));
+            }
+            svc.register_participant(id.clone(), position);
+            Ok(format!("spawned {} at {}", id, position))
+        }
+        ConsoleCommand::Teleport { id, position } => {
+            if svc.participant_position(&id).is_none() {
+                return Err(ConsoleError::UnknownEntity(id));
+            }
+            svc.teleport(&id, position)
+                .map_err(|e| ConsoleError::Failed(e.to_string()))?;
+            Ok(format!("teleported {} to {}", id, position))
+        }
+        ConsoleCommand::Time(spec) => {
+            if let Some(spec) = spec {
+                let day = svc.environment().day_length_s();
+                let seconds = match spec {
+                    TimeSpec::Seconds(s) => s,
+                    TimeSpec::Clock { hours, minutes } => {
+                        (hours * 60 + minutes) as f32 / (24.0 * 60.0) * day
+                    }
+                };
+                svc.set_time_of_day(seconds);
+            }
+            let env = svc.environment();
+            Ok(format!(
+                "time {:.1}s of {:.0}s (sun {:.1}°, {:?})",
+                env.time_of_day_s(),
+                env.day_length_s(),
+                env.sun_angle_deg(),
+                env.weather()
+            ))
+        }
+        ConsoleCommand::Dump(coord) => {
+            let info = svc.cell_info(coord);
+            let mut out = format!("cell {}: {}", coord, state(info.active));
+            let _ = write!(out, "\n  terrain bodies: {}", list(&info.terrain_bodies));
+            let _ = write!(out, "\n  objects: {}", list(&info.objects));
+            let _ = write!(out, "\n  structures: {}", list(&info.structures));
+            let _ = write!(out, "\n  participants: {}", list(&info.participants));
+            let _ = write!(out, "\n  height at centre: {:.3}", info.centre_height);
+            Ok(out)
+        }
+    }
+}
+
+/// Parse and execute in one step (what the bus handler does).
+pub fn run(svc: &mut WorldService, line: &str) -> Result<String, ConsoleError> {
+    execute(svc, parse(line)?)
+}
+
+fn state(active: bool) -> &'static str {
+    if active {
+        "active"
+    } else {
+        "inactive"
+    }
+}
+
+fn list(items: &[String]) -> String {
+    if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.join(", ")
+    }
+}
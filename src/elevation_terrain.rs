@@ -0,0 +1,696 @@
+//! Real-world elevation terrain: GeoTIFF rasters and SRTM `.hgt` tiles.
+//!
+//! The world origin is pinned to a point of the dataset's coordinate system
+//! (`origin_x`/`origin_y`: longitude/latitude for [`GeoCrs::Geographic`],
+//! easting/northing for [`GeoCrs::Projected`]); world `+x` is east, `+y`
+//! north, and one world unit is `metres_per_unit` metres.  Geographic data
+//! is reprojected with an equirectangular approximation around the origin,
+//! which is accurate to well under a percent across a few tens of
+//! kilometres.
+//!
+//! Elevations (metres) become heights as `vertical_offset + vertical_scale
+//! × elevation`.  Anything outside every tile, and no-data pixels (SRTM
+//! voids), comes from the `fallback` source instead, typically procedural
+//! noise.
+//!
+//! GeoTIFFs are read by a small built-in decoder: single-band, uncompressed,
+//! strip-organised, 16/32-bit integer or 32/64-bit float samples (convert
+//! other files with `gdal_translate -co COMPRESS=NONE -co TILED=NO`).
+//! SRTM tiles are loaded lazily from `srtm_dir` the first time a point
+//! inside them is sampled, and kept until more than `srtm_cache_tiles`
+//! squares have been used since.
+
+use crate::terrain::{footprint_collider, ChunkDescriptor, TerrainSource};
+use crate::types::Vec3;
+use janet_operations::physics::types::ColliderShape;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Metres per degree of latitude (and of longitude at the equator).
+const METRES_PER_DEGREE: f64 = 111_320.0;
+
+/// SRTM void marker.
+const SRTM_VOID: i16 = -32768;
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Error)]
+pub enum ElevationError {
+    #[error("failed to read elevation data: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid GeoTIFF: {0}")]
+    Tiff(String),
+    #[error("unsupported GeoTIFF: {0}")]
+    Unsupported(String),
+    #[error("SRTM tile name '{0}' is not like N37W122.hgt")]
+    SrtmName(String),
+    #[error("SRTM tile of {0} bytes is not a square grid of i16 samples")]
+    SrtmSize(usize),
+    #[error("{tile} is {found:?} but the terrain is configured as {expected:?}")]
+    CrsMismatch {
+        tile: String,
+        found: GeoCrs,
+        expected: GeoCrs,
+    },
+    #[error("elevation grid has {len} samples, expected {width}x{height}")]
+    Dimensions {
+        len: usize,
+        width: usize,
+        height: usize,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// Config
+// ---------------------------------------------------------------------------
+
+/// Coordinate system of the elevation data (and of the world origin).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeoCrs {
+    /// Longitude / latitude in degrees (SRTM, most global DEMs).
+    Geographic,
+    /// Metric easting / northing (UTM and other projected grids).
+    Projected,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ElevationConfig {
+    pub crs: GeoCrs,
+    /// Dataset X (longitude or easting) at world `(0, 0)`.
+    pub origin_x: f64,
+    /// Dataset Y (latitude or northing) at world `(0, 0)`.
+    pub origin_y: f64,
+    pub metres_per_unit: f32,
+    pub vertical_scale: f32,
+    pub vertical_offset: f32,
+    /// World-space width/height of a single terrain chunk.
+    pub chunk_size: f32,
+    /// GeoTIFF rasters loaded at startup (earlier files win where they
+    /// overlap).
+    pub geotiffs: Vec<PathBuf>,
+    /// Directory of SRTM `.hgt` tiles, loaded on demand (geographic only).
+    pub srtm_dir: Option<PathBuf>,
+    /// Most SRTM squares kept in memory (known-missing ones included); the
+    /// least recently used goes first.
+    pub srtm_cache_tiles: usize,
+}
+
+impl Default for ElevationConfig {
+    fn default() -> Self {
+        Self {
+            crs: GeoCrs::Geographic,
+            origin_x: 0.0,
+            origin_y: 0.0,
+            metres_per_unit: 1.0,
+            vertical_scale: 1.0,
+            vertical_offset: 0.0,
+            chunk_size: 40.0,
+            geotiffs: Vec::new(),
+            srtm_dir: None,
+            srtm_cache_tiles: 8,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tiles
+// ---------------------------------------------------------------------------
+
+/// One georeferenced elevation grid.
+#[derive(Debug, Clone)]
+pub struct ElevationTile {
+    pub crs: GeoCrs,
+    pub width: usize,
+    pub height: usize,
+    /// Dataset coordinates of the centre of pixel `(0, 0)` (north-west).
+    pub origin_x: f64,
+    pub origin_y: f64,
+    /// Pixel spacing; rows run south, so Y decreases by `pixel_height`.
+    pub pixel_width: f64,
+    pub pixel_height: f64,
+    /// Elevations in metres, row-major; `NaN` marks no data.
+    samples: Vec<f32>,
+}
+
+impl ElevationTile {
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_samples(
+        crs: GeoCrs,
+        width: usize,
+        height: usize,
+        origin_x: f64,
+        origin_y: f64,
+        pixel_width: f64,
+        pixel_height: f64,
+        samples: Vec<f32>,
+    ) -> Result<Self, ElevationError> {
+        if width < 2 || height < 2 || samples.len() != width * height {
+            return Err(ElevationError::Dimensions {
+                len: samples.len(),
+                width,
+                height,
+            });
+        }
+        Ok(Self {
+            crs,
+            width,
+            height,
+            origin_x,
+            origin_y,
+            pixel_width,
+            pixel_height,
+            samples,
+        })
+    }
+
+    /// Read an SRTM `.hgt` tile; the south-west corner comes from the file
+    /// name (`N37W122.hgt`), the resolution from the file size.
+    pub fn read_hgt(path: impl AsRef<Path>) -> Result<Self, ElevationError> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let (lat, lon) = parse_srtm_name(&name).ok_or(ElevationError::SrtmName(name))?;
+
+        let bytes = std::fs::read(path)?;
+        let side = ((bytes.len() / 2) as f64).sqrt() as usize;
+        if side < 2 || side * side * 2 != bytes.len() {
+            return Err(ElevationError::SrtmSize(bytes.len()));
+        }
+        let samples = bytes
+            .chunks_exact(2)
+            .map(|b| match i16::from_be_bytes([b[0], b[1]]) {
+                SRTM_VOID => f32::NAN,
+                v => v as f32,
+            })
+            .collect();
+        // Samples sit on the grid lines, first row on the northern edge.
+        let spacing = 1.0 / (side - 1) as f64;
+        Self::from_samples(
+            GeoCrs::Geographic,
+            side,
+            side,
+            lon as f64,
+            lat as f64 + 1.0,
+            spacing,
+            spacing,
+            samples,
+        )
+    }
+
+    /// Read a single-band, uncompressed, strip-organised GeoTIFF.
+    pub fn read_geotiff(path: impl AsRef<Path>) -> Result<Self, ElevationError> {
+        decode_geotiff(&std::fs::read(path)?)
+    }
+
+    /// Elevation at a dataset point, bilinear between pixel centres; `None`
+    /// outside the grid or next to a no-data pixel.
+    pub fn sample(&self, x: f64, y: f64) -> Option<f32> {
+        let gx = (x - self.origin_x) / self.pixel_width;
+        let gy = (self.origin_y - y) / self.pixel_height;
+        let max_x = (self.width - 1) as f64;
+        let max_y = (self.height - 1) as f64;
+        if !(0.0..=max_x).contains(&gx) || !(0.0..=max_y).contains(&gy) {
+            return None;
+        }
+        let ix = (gx.floor() as usize).min(self.width - 2);
+        let iy = (gy.floor() as usize).min(self.height - 2);
+        let fx = (gx - ix as f64) as f32;
+        let fy = (gy - iy as f64) as f32;
+        let corners = [
+            (ix, iy, (1.0 - fx) * (1.0 - fy)),
+            (ix + 1, iy, fx * (1.0 - fy)),
+            (ix, iy + 1, (1.0 - fx) * fy),
+            (ix + 1, iy + 1, fx * fy),
+        ];
+        let mut v = 0.0;
+        for (c, r, w) in corners {
+            if w > 0.0 {
+                let s = self.samples[r * self.width + c];
+                if !s.is_finite() {
+                    return None;
+                }
+                v += s * w;
+            }
+        }
+        Some(v)
+    }
+}
+
+/// `N37W122` → `(37, -122)`.
+fn parse_srtm_name(name: &str) -> Option<(i32, i32)> {
+    let name = name.to_ascii_uppercase();
+    let (lat_part, lon_part) = name.split_at(name.find(['E', 'W'])?);
+    let lat: i32 = lat_part.get(1..)?.parse().ok()?;
+    let lon: i32 = lon_part.get(1..)?.parse().ok()?;
+    let lat = match lat_part.as_bytes().first()? {
+        b'N' => lat,
+        b'S' => -lat,
+        _ => return None,
+    };
+    let lon = if lon_part.starts_with('W') { -lon } else { lon };
+    Some((lat, lon))
+}
+
+/// `(37, -122)` → `N37W122.hgt`.
+fn srtm_file_name(lat: i32, lon: i32) -> String {
+    format!(
+        "{}{:02}{}{:03}.hgt",
+        if lat < 0 { 'S' } else { 'N' },
+        lat.abs(),
+        if lon < 0 { 'W' } else { 'E' },
+        lon.abs()
+    )
+}
+
+// ---------------------------------------------------------------------------
+// GeoTIFF decoding
+// ---------------------------------------------------------------------------
+
+const TAG_IMAGE_WIDTH: u16 = 256;
+const TAG_IMAGE_LENGTH: u16 = 257;
+const TAG_BITS_PER_SAMPLE: u16 = 258;
+const TAG_COMPRESSION: u16 = 259;
+const TAG_STRIP_OFFSETS: u16 = 273;
+const TAG_SAMPLES_PER_PIXEL: u16 = 277;
+const TAG_STRIP_BYTE_COUNTS: u16 = 279;
+const TAG_TILE_WIDTH: u16 = 322;
+const TAG_SAMPLE_FORMAT: u16 = 339;
+const TAG_MODEL_PIXEL_SCALE: u16 = 33550;
+const TAG_MODEL_TIEPOINT: u16 = 33922;
+const TAG_GEO_KEY_DIRECTORY: u16 = 34735;
+const TAG_GDAL_NODATA: u16 = 42113;
+
+const GEO_KEY_MODEL_TYPE: f64 = 1024.0;
+const GEO_KEY_RASTER_TYPE: f64 = 1025.0;
+const MODEL_TYPE_GEOGRAPHIC: f64 = 2.0;
+const RASTER_PIXEL_IS_POINT: f64 = 2.0;
+
+/// First IFD of a classic TIFF file.
+struct Tiff<'a> {
+    bytes: &'a [u8],
+    big_endian: bool,
+    /// Tag → (field type, count, file offset of the entry's value field).
+    entries: HashMap<u16, (u16, usize, usize)>,
+}
+
+impl<'a> Tiff<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self, ElevationError> {
+        let big_endian = match bytes.get(..2) {
+            Some(b"II") => false,
+            Some(b"MM") => true,
+            _ => return Err(ElevationError::Tiff("missing byte-order mark".into())),
+        };
+        let mut tiff = Self {
+            bytes,
+            big_endian,
+            entries: HashMap::new(),
+        };
+        match tiff.uint(2, 2)? {
+            42 => {}
+            43 => return Err(ElevationError::Unsupported("BigTIFF".into())),
+            magic => return Err(ElevationError::Tiff(format!("bad magic {}", magic))),
+        }
+        let ifd = tiff.uint(4, 4)? as usize;
+        for i in 0..tiff.uint(ifd, 2)? as usize {
+            let at = ifd + 2 + i * 12;
+            let tag = tiff.uint(at, 2)? as u16;
+            let field_type = tiff.uint(at + 2, 2)? as u16;
+            let count = tiff.uint(at + 4, 4)? as usize;
+            tiff.entries.insert(tag, (field_type, count, at + 8));
+        }
+        Ok(tiff)
+    }
+
+    fn slice(&self, at: usize, len: usize) -> Result<&'a [u8], ElevationError> {
+        self.bytes
+            .get(at..at.saturating_add(len))
+            .ok_or_else(|| ElevationError::Tiff(format!("offset {} out of bounds", at)))
+    }
+
+    /// Unsigned integer of `size` bytes (2, 4 or 8) in file byte order.
+    fn uint(&self, at: usize, size: usize) -> Result<u64, ElevationError> {
+        Ok(read_uint(self.slice(at, size)?, self.big_endian))
+    }
+
+    /// Field type and raw bytes of a tag's values.
+    fn values(&self, tag: u16) -> Result<Option<(u16, &'a [u8])>, ElevationError> {
+        let Some(&(field_type, count, at)) = self.entries.get(&tag) else {
+            return Ok(None);
+        };
+        let len = field_size(field_type)? * count;
+        // Values that fit in four bytes are stored in the entry itself.
+        let at = if len <= 4 {
+            at
+        } else {
+            self.uint(at, 4)? as usize
+        };
+        Ok(Some((field_type, self.slice(at, len)?)))
+    }
+
+    /// Numeric values of a tag as `f64`.
+    fn numbers(&self, tag: u16) -> Result<Option<Vec<f64>>, ElevationError> {
+        let Some((field_type, bytes)) = self.values(tag)? else {
+            return Ok(None);
+        };
+        let size = field_size(field_type)?;
+        bytes
+            .chunks_exact(size)
+            .map(|b| {
+                let v = read_uint(b, self.big_endian);
+                Ok(match field_type {
+                    1 | 3 | 4 => v as f64,
+                    6 => v as u8 as i8 as f64,
+                    8 => v as u16 as i16 as f64,
+                    9 => v as u32 as i32 as f64,
+                    11 => f32::from_bits(v as u32) as f64,
+                    12 => f64::from_bits(v),
+                    other => {
+                        return Err(ElevationError::Unsupported(format!(
+                            "field type {} on tag {}",
+                            other, tag
+                        )))
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    }
+
+    fn number(&self, tag: u16) -> Result<Option<f64>, ElevationError> {
+        Ok(self.numbers(tag)?.and_then(|v| v.first().copied()))
+    }
+
+    fn required(&self, tag: u16, name: &str) -> Result<Vec<f64>, ElevationError> {
+        self.numbers(tag)?
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| ElevationError::Tiff(format!("missing {}", name)))
+    }
+
+    fn ascii(&self, tag: u16) -> Result<Option<String>, ElevationError> {
+        Ok(self.values(tag)?.map(|(_, bytes)| {
+            String::from_utf8_lossy(bytes)
+                .trim_end_matches('\0')
+                .trim()
+                .to_string()
+        }))
+    }
+}
+
+fn field_size(field_type: u16) -> Result<usize, ElevationError> {
+    match field_type {
+        1 | 2 | 6 | 7 => Ok(1),
+        3 | 8 => Ok(2),
+        4 | 9 | 11 => Ok(4),
+        12 => Ok(8),
+        other => Err(ElevationError::Unsupported(format!("field type {}", other))),
+    }
+}
+
+fn read_uint(bytes: &[u8], big_endian: bool) -> u64 {
+    let fold = |acc: u64, &b: &u8| (acc << 8) | b as u64;
+    if big_endian {
+        bytes.iter().fold(0, fold)
+    } else {
+        bytes.iter().rev().fold(0, fold)
+    }
+}
+
+fn decode_geotiff(bytes: &[u8]) -> Result<ElevationTile, ElevationError> {
+    let tiff = Tiff::parse(bytes)?;
+    if tiff.entries.contains_key(&TAG_TILE_WIDTH) {
+        return Err(ElevationError::Unsupported("tiled layout".into()));
+    }
+    let compression = tiff.number(TAG_COMPRESSION)?.unwrap_or(1.0);
+    if compression != 1.0 {
+        return Err(ElevationError::Unsupported(format!(
+            "compression {}",
+            compression
+        )));
+    }
+    if tiff.number(TAG_SAMPLES_PER_PIXEL)?.unwrap_or(1.0) != 1.0 {
+        return Err(ElevationError::Unsupported("more than one band".into()));
+    }
+    let width = tiff.required(TAG_IMAGE_WIDTH, "ImageWidth")?[0] as usize;
+    let height = tiff.required(TAG_IMAGE_LENGTH, "ImageLength")?[0] as usize;
+    let bits = tiff.required(TAG_BITS_PER_SAMPLE, "BitsPerSample")?[0] as usize;
+    let format = tiff.number(TAG_SAMPLE_FORMAT)?.unwrap_or(1.0) as u16;
+    let decode: fn(u64) -> f32 = match (format, bits) {
+        (1, 16) | (1, 32) => |v| v as f32,
+        (2, 16) => |v| v as u16 as i16 as f32,
+        (2, 32) => |v| v as u32 as i32 as f32,
+        (3, 32) => |v| f32::from_bits(v as u32),
+        (3, 64) => |v| f64::from_bits(v) as f32,
+        _ => {
+            return Err(ElevationError::Unsupported(format!(
+                "{}-bit samples of format {}",
+                bits, format
+            )))
+        }
+    };
+
+    let offsets = tiff.required(TAG_STRIP_OFFSETS, "StripOffsets")?;
+    let counts = tiff.required(TAG_STRIP_BYTE_COUNTS, "StripByteCounts")?;
+    let mut data = Vec::with_capacity(width * height * bits / 8);
+    for (&offset, &count) in offsets.iter().zip(&counts) {
+        data.extend_from_slice(tiff.slice(offset as usize, count as usize)?);
+    }
+    if data.len() < width * height * bits / 8 {
+        return Err(ElevationError::Tiff("strips shorter than the image".into()));
+    }
+
+    let nodata = tiff
+        .ascii(TAG_GDAL_NODATA)?
+        .and_then(|s| s.parse::<f32>().ok());
+    let samples = data
+        .chunks_exact(bits / 8)
+        .take(width * height)
+        .map(|b| decode(read_uint(b, tiff.big_endian)))
+        .map(|v| if Some(v) == nodata { f32::NAN } else { v })
+        .collect();
+
+    let scale = tiff.required(TAG_MODEL_PIXEL_SCALE, "ModelPixelScale")?;
+    let tie = tiff.required(TAG_MODEL_TIEPOINT, "ModelTiepoint")?;
+    if scale.len() < 2 || tie.len() < 6 {
+        return Err(ElevationError::Tiff("short georeferencing tags".into()));
+    }
+    // GeoKeyDirectory: 4-value header, then (id, location, count, value)
+    // entries; only inline (location 0) keys are needed here.
+    let keys = tiff.numbers(TAG_GEO_KEY_DIRECTORY)?.unwrap_or_default();
+    let geo_key = |id: f64| {
+        keys.get(4..)?
+            .chunks_exact(4)
+            .find(|k| k[0] == id && k[1] == 0.0)
+            .map(|k| k[3])
+    };
+    let crs = if geo_key(GEO_KEY_MODEL_TYPE) == Some(MODEL_TYPE_GEOGRAPHIC) {
+        GeoCrs::Geographic
+    } else {
+        GeoCrs::Projected
+    };
+    // The tiepoint names a pixel's corner for area rasters and its centre
+    // for point rasters; tiles store centres.
+    let half = if geo_key(GEO_KEY_RASTER_TYPE) == Some(RASTER_PIXEL_IS_POINT) {
+        0.0
+    } else {
+        0.5
+    };
+    let (sx, sy) = (scale[0], scale[1]);
+    ElevationTile::from_samples(
+        crs,
+        width,
+        height,
+        tie[3] + (half - tie[0]) * sx,
+        tie[4] - (half - tie[1]) * sy,
+        sx,
+        sy,
+        samples,
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Elevation terrain
+// ---------------------------------------------------------------------------
+
+/// SRTM tiles by south-west corner (`None` = no file for that square), with
+/// the lookup clock at their last use.
+type SrtmCache = HashMap<(i32, i32), (Option<Arc<ElevationTile>>, AtomicU64)>;
+
+pub struct ElevationTerrain {
+    pub config: ElevationConfig,
+    tiles: Vec<Arc<ElevationTile>>,
+    srtm: RwLock<SrtmCache>,
+    /// Monotonic lookup counter used as the SRTM LRU timestamp.
+    srtm_clock: AtomicU64,
+    fallback: Arc<dyn TerrainSource>,
+}
+
+impl ElevationTerrain {
+    /// Terrain from `config`, loading its GeoTIFFs now.
+    pub fn open(
+        config: ElevationConfig,
+        fallback: Arc<dyn TerrainSource>,
+    ) -> Result<Self, ElevationError> {
+        let mut terrain = Self {
+            config,
+            tiles: Vec::new(),
+            srtm: RwLock::new(HashMap::new()),
+            srtm_clock: AtomicU64::new(0),
+            fallback,
+        };
+        for path in terrain.config.geotiffs.clone() {
+            let tile = ElevationTile::read_geotiff(&path)?;
+            terrain.add_tile_named(tile, &path.display().to_string())?;
+        }
+        Ok(terrain)
+    }
+
+    /// Add an in-memory tile (checked after every tile already added).
+    pub fn with_tile(mut self, tile: ElevationTile) -> Result<Self, ElevationError> {
+        self.add_tile_named(tile, "tile")?;
+        Ok(self)
+    }
+
+    fn add_tile_named(&mut self, tile: ElevationTile, name: &str) -> Result<(), ElevationError> {
+        if tile.crs != self.config.crs {
+            return Err(ElevationError::CrsMismatch {
+                tile: name.to_string(),
+                found: tile.crs,
+                expected: self.config.crs,
+            });
+        }
+        self.tiles.push(Arc::new(tile));
+        Ok(())
+    }
+
+    /// Dataset coordinates of a world point.
+    pub fn world_to_dataset(&self, x: f32, y: f32) -> (f64, f64) {
+        let c = &self.config;
+        let (east, north) = (
+            x as f64 * c.metres_per_unit as f64,
+            y as f64 * c.metres_per_unit as f64,
+        );
+        match c.crs {
+            GeoCrs::Projected => (c.origin_x + east, c.origin_y + north),
+            GeoCrs::Geographic => {
+                let lon_scale = METRES_PER_DEGREE * c.origin_y.to_radians().cos().max(1e-6);
+                (
+                    c.origin_x + east / lon_scale,
+                    c.origin_y + north / METRES_PER_DEGREE,
+                )
+            }
+        }
+    }
+
+    /// Elevation in metres at a world point, if any dataset covers it.
+    pub fn elevation_at(&self, x: f32, y: f32) -> Option<f32> {
+        let (dx, dy) = self.world_to_dataset(x, y);
+        self.tiles
+            .iter()
+            .find_map(|t| t.sample(dx, dy))
+            .or_else(|| self.srtm_tile(dx, dy)?.sample(dx, dy))
+    }
+
+    /// SRTM tile covering a geographic point, loading it on first use (and
+    /// dropping the least recently used one past `srtm_cache_tiles`).
+    fn srtm_tile(&self, lon: f64, lat: f64) -> Option<Arc<ElevationTile>> {
+        let dir = self.config.srtm_dir.as_ref()?;
+        if self.config.crs != GeoCrs::Geographic {
+            return None;
+        }
+        let key = (lat.floor() as i32, lon.floor() as i32);
+        let now = self.srtm_clock.fetch_add(1, Ordering::Relaxed);
+        if let Some((tile, last_used)) = self.srtm.read().get(&key) {
+            last_used.store(now, Ordering::Relaxed);
+            return tile.clone();
+        }
+        let path = dir.join(srtm_file_name(key.0, key.1));
+        let tile = match ElevationTile::read_hgt(&path) {
+            Ok(tile) => Some(Arc::new(tile)),
+            Err(ElevationError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                log::warn!("Skipping SRTM tile {}: {}", path.display(), e);
+                None
+            }
+        };
+        let mut srtm = self.srtm.write();
+        let tile = srtm
+            .entry(key)
+            .or_insert_with(|| (tile, AtomicU64::new(now)))
+            .0
+            .clone();
+        while srtm.len() > self.config.srtm_cache_tiles.max(1) {
+            let oldest = srtm
+                .iter()
+                .filter(|(k, _)| **k != key)
+                .min_by_key(|(_, (_, last_used))| last_used.load(Ordering::Relaxed))
+                .map(|(k, _)| *k);
+            match oldest {
+                Some(oldest) => srtm.remove(&oldest),
+                None => break,
+            };
+        }
+        tile
+    }
+
+    /// SRTM tiles loaded so far (including known-missing squares).
+    pub fn srtm_tiles_cached(&self) -> usize {
+        self.srtm.read().len()
+    }
+}
+
+impl TerrainSource for ElevationTerrain {
+    fn height_at(&self, x: f32, y: f32) -> f32 {
+        match self.elevation_at(x, y) {
+            Some(metres) => self.config.vertical_offset + self.config.vertical_scale * metres,
+            None => self.fallback.height_at(x, y),
+        }
+    }
+
+    fn normal_at(&self, x: f32, y: f32) -> Vec3 {
+        let eps = 0.5;
+        let h_l = self.height_at(x - eps, y);
+        let h_r = self.height_at(x + eps, y);
+        let h_d = self.height_at(x, y - eps);
+        let h_u = self.height_at(x, y + eps);
+        Vec3::new(h_l - h_r, h_d - h_u, 2.0 * eps)
+    }
+
+    fn chunk_descriptor(&self, _cx: i32, _cy: i32, _lod: u8) -> Option<ChunkDescriptor> {
+        Some(ChunkDescriptor {
+            seed: 0,
+            chunk_size: self.config.chunk_size,
+            hydrology: None,
+            materials: Default::default(),
+        })
+    }
+
+    fn collider_for_chunk(&self, _cx: i32, _cy: i32, _lod: u8) -> Option<ColliderShape> {
+        Some(footprint_collider(self.config.chunk_size))
+    }
+
+    /// Load the SRTM tiles under the chunk's corners ahead of streaming.
+    fn warm_chunk(&self, cx: i32, cy: i32, _lod: u8) {
+        let size = self.config.chunk_size;
+        for (i, j) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let (lon, lat) = self.world_to_dataset((cx + i) as f32 * size, (cy + j) as f32 * size);
+            self.srtm_tile(lon, lat);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,171 @@
+//! Server-owned entities: NPCs, carts, projectiles and anything else the
+//! world spawns itself rather than a participant joining over the bus.
+//!
+//! The [`EntityStore`] keeps what only these entities have: archetype,
+//! heading, velocity and free-form components.  Positions live with the
+//! participants' in [`WorldService`](crate::service::WorldService), so
+//! server entities get handles, transforms and proximity like everyone
+//! else.  Like ambient entities they don't stream terrain.
+//!
+//! Spawns and despawns go through
+//! [`WorldService::spawn_entity`](crate::service::WorldService::spawn_entity)
+//! and [`despawn_entity`](crate::service::WorldService::despawn_entity),
+//! which queue `world.entity.spawned` / `world.entity.removed` for the
+//! next tick.  Every tick entities with a [`Behaviour`] pick a velocity,
+//! then each entity moves by its velocity.
+
+use crate::behaviour::{Behaviour, Steering};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum EntityError {
+    #[error("Entity '{0}' already exists")]
+    Exists(String),
+    #[error("Unknown entity '{0}'")]
+    Unknown(String),
+    #[error("Invalid entity '{id}': {reason}")]
+    Invalid { id: String, reason: String },
+}
+
+/// What to spawn.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntitySpec {
+    pub entity_id: String,
+    /// Game-defined archetype (`"npc/merchant"`, `"vehicle/cart"`).
+    pub archetype: String,
+    pub x: f32,
+    pub y: f32,
+    /// Height; `None` places the entity on the ground.
+    #[serde(default)]
+    pub z: Option<f32>,
+    #[serde(default)]
+    pub rotation_y: f32,
+    /// Metres per second along `x` and `y`.
+    #[serde(default)]
+    pub velocity: [f32; 2],
+    /// Game state carried along and sent as the spawn's `metadata`.
+    #[serde(default)]
+    pub components: BTreeMap<String, serde_json::Value>,
+    /// Steering that sets `velocity` every tick.
+    #[serde(default)]
+    pub behaviour: Option<Behaviour>,
+}
+
+/// A live server entity (its position is the service's).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entity {
+    pub archetype: String,
+    pub rotation_y: f32,
+    /// Metres per second along `x` and `y`.
+    pub velocity: [f32; 2],
+    pub components: BTreeMap<String, serde_json::Value>,
+    pub behaviour: Option<Behaviour>,
+    /// Where it spawned (the centre of its wandering).
+    pub home: [f32; 2],
+    pub steering: Steering,
+    /// Velocity moved with on the last tick.
+    applied: [f32; 2],
+}
+
+impl Entity {
+    pub fn new(archetype: impl Into<String>, home: [f32; 2]) -> Self {
+        Self {
+            archetype: archetype.into(),
+            rotation_y: 0.0,
+            velocity: [0.0, 0.0],
+            components: BTreeMap::new(),
+            behaviour: None,
+            home,
+            steering: Steering::default(),
+            applied: [0.0, 0.0],
+        }
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.velocity != [0.0, 0.0]
+    }
+
+    /// Components as the `metadata` of entity events (`null` when empty).
+    pub fn metadata(&self) -> serde_json::Value {
+        if self.components.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::json!(self.components)
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EntityStore {
+    entities: HashMap<String, Entity>,
+}
+
+impl EntityStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn contains(&self, entity_id: &str) -> bool {
+        self.entities.contains_key(entity_id)
+    }
+
+    /// Every entity (unordered).
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Entity)> {
+        self.entities.iter().map(|(id, e)| (id.as_str(), e))
+    }
+
+    pub fn get(&self, entity_id: &str) -> Option<&Entity> {
+        self.entities.get(entity_id)
+    }
+
+    pub fn get_mut(&mut self, entity_id: &str) -> Option<&mut Entity> {
+        self.entities.get_mut(entity_id)
+    }
+
+    /// Ids and velocities of the entities to move this tick, sorted by id:
+    /// those with a velocity, and those that just stopped (so their
+    /// physics bodies stop too).
+    pub fn take_moves(&mut self) -> Vec<(String, [f32; 2])> {
+        let mut moves: Vec<_> = self
+            .entities
+            .iter_mut()
+            .filter(|(_, e)| e.is_moving() || e.applied != [0.0, 0.0])
+            .map(|(id, e)| {
+                e.applied = e.velocity;
+                (id.clone(), e.velocity)
+            })
+            .collect();
+        moves.sort_by(|a, b| a.0.cmp(&b.0));
+        moves
+    }
+
+    /// Ids of the entities with a behaviour, sorted.
+    pub fn steered(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .entities
+            .iter()
+            .filter(|(_, e)| e.behaviour.is_some())
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    pub fn insert(&mut self, entity_id: String, entity: Entity) {
+        self.entities.insert(entity_id, entity);
+    }
+
+    pub fn remove(&mut self, entity_id: &str) -> Option<Entity> {
+        self.entities.remove(entity_id)
+    }
+}
@@ -0,0 +1,138 @@
+//! Environment subsystem: world clock, weather, and per-biome ambient tint.
+//!
+//! The service owns a single [`Environment`] and advances it every tick.
+//! Snapshots of it are published as [`WorldEnvironment`] on
+//! `world.environment` at low frequency, and immediately whenever weather or
+//! time is changed explicitly.
+
+use crate::protocol::{Weather, WorldEnvironment};
+use std::collections::BTreeMap;
+
+/// Canonical terrain classes (see `terrain::classify_terrain`) with their
+/// linear RGB ambient tint.
+const BIOME_TINTS: &[(&str, [f32; 3])] = &[
+    ("water", [0.80, 0.90, 1.00]),
+    ("sand", [1.00, 0.96, 0.86]),
+    ("swamp", [0.82, 0.90, 0.78]),
+    ("grass", [0.95, 1.00, 0.92]),
+    ("forest", [0.85, 0.95, 0.85]),
+    ("rock", [0.92, 0.92, 0.95]),
+    ("snow", [0.95, 0.97, 1.00]),
+    ("desert", [1.00, 0.92, 0.80]),
+];
+
+/// Ambient tint for a canonical terrain class (white if unknown).
+pub fn ambient_tint(biome: &str) -> [f32; 3] {
+    BIOME_TINTS
+        .iter()
+        .find(|(name, _)| *name == biome)
+        .map(|(_, tint)| *tint)
+        .unwrap_or([1.0, 1.0, 1.0])
+}
+
+/// Base exponential fog density for a weather state.
+pub fn fog_density(weather: Weather) -> f32 {
+    match weather {
+        Weather::Clear => 0.0,
+        Weather::Overcast => 0.02,
+        Weather::Rain => 0.08,
+        Weather::Storm => 0.15,
+        Weather::Fog => 0.35,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Environment state
+// ---------------------------------------------------------------------------
+
+pub struct Environment {
+    day_length_s: f32,
+    time_of_day_s: f32,
+    weather: Weather,
+    /// Set when state changed outside of normal clock progression.
+    dirty: bool,
+}
+
+impl Environment {
+    /// Start a new clock at `start_time_s` seconds past midnight.
+    pub fn new(day_length_s: f32, start_time_s: f32) -> Self {
+        let day_length_s = day_length_s.max(1.0);
+        Self {
+            day_length_s,
+            time_of_day_s: start_time_s.rem_euclid(day_length_s),
+            weather: Weather::Clear,
+            dirty: true,
+        }
+    }
+
+    /// Advance the world clock by `dt` seconds, wrapping at midnight.
+    pub fn advance(&mut self, dt: f32) {
+        self.time_of_day_s = (self.time_of_day_s + dt).rem_euclid(self.day_length_s);
+    }
+
+    pub fn day_length_s(&self) -> f32 {
+        self.day_length_s
+    }
+
+    pub fn time_of_day_s(&self) -> f32 {
+        self.time_of_day_s
+    }
+
+    pub fn set_time_of_day(&mut self, seconds: f32) {
+        self.time_of_day_s = seconds.rem_euclid(self.day_length_s);
+        self.dirty = true;
+    }
+
+    pub fn weather(&self) -> Weather {
+        self.weather
+    }
+
+    pub fn set_weather(&mut self, weather: Weather) {
+        if self.weather != weather {
+            self.weather = weather;
+            self.dirty = true;
+        }
+    }
+
+    /// Sun elevation in degrees: -90 at midnight, 0 at sunrise, 90 at noon.
+    pub fn sun_angle_deg(&self) -> f32 {
+        let phase = self.time_of_day_s / self.day_length_s;
+        let angle = phase * 360.0 - 90.0;
+        // Fold the afternoon back down so the sun sets instead of circling.
+        if angle > 90.0 {
+            180.0 - angle
+        } else {
+            angle
+        }
+    }
+
+    /// Fog density for the current weather, thickened slightly at night.
+    pub fn fog_density(&self) -> f32 {
+        let night = if self.sun_angle_deg() < 0.0 {
+            1.25
+        } else {
+            1.0
+        };
+        fog_density(self.weather) * night
+    }
+
+    /// Returns `true` (once) if state changed since the last call.
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// Protocol view of the current state.
+    pub fn to_event(&self) -> WorldEnvironment {
+        WorldEnvironment {
+            time_of_day_s: self.time_of_day_s,
+            day_length_s: self.day_length_s,
+            sun_angle_deg: self.sun_angle_deg(),
+            weather: self.weather,
+            fog_density: self.fog_density(),
+            ambient_tints: BIOME_TINTS
+                .iter()
+                .map(|(name, tint)| (name.to_string(), *tint))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Erosion post-process for generated height grids.
+//!
+//! Two cheap, fully local passes run once per iteration:
+//!
+//! * **Thermal** — wherever the height difference to a 4-neighbour exceeds
+//!   the talus slope, a fraction of the excess slides downhill.
+//! * **Hydraulic** — every sample loses material in proportion to the slope
+//!   towards its lowest neighbour; part of it is deposited there and the rest
+//!   is washed away, which cuts gullies into steep ground.
+//!
+//! Both passes read the previous iteration's grid and only touch samples at
+//! most two steps away, so a sample's final value depends only on samples
+//! within `2 * iterations`.  Eroding a chunk padded by
+//! [`ErosionConfig::padding`] therefore gives exactly the same interior as
+//! eroding the whole world — no seams at chunk edges.
+
+/// The four axis neighbours, in a fixed order (results must not depend on
+/// where a grid starts).
+const NEIGHBOURS: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErosionConfig {
+    /// Number of passes; `0` disables erosion.
+    pub iterations: u32,
+    /// Steepest stable slope (height per world unit) before thermal
+    /// slumping kicks in.
+    pub talus: f32,
+    /// Fraction of the excess over `talus` moved per pass (`0..=0.25`).
+    pub thermal_rate: f32,
+    /// Height removed per unit of downhill slope per pass.
+    pub hydraulic_rate: f32,
+    /// Fraction of hydraulically eroded material deposited in the lowest
+    /// neighbour (`0..=1`); the remainder is carried away.
+    pub deposition: f32,
+}
+
+impl Default for ErosionConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 8,
+            talus: 0.05,
+            thermal_rate: 0.2,
+            hydraulic_rate: 0.02,
+            deposition: 0.5,
+        }
+    }
+}
+
+impl ErosionConfig {
+    /// Extra samples needed on every side of a chunk for a seamless result.
+    pub fn padding(&self) -> usize {
+        2 * self.iterations as usize
+    }
+
+    /// Erode a `size × size` row-major grid whose samples are `cell_size`
+    /// world units apart.
+    pub fn apply(&self, heights: &mut [f32], size: usize, cell_size: f32) {
+        debug_assert_eq!(heights.len(), size * size);
+        let talus = self.talus * cell_size;
+        let thermal_rate = self.thermal_rate.clamp(0.0, 0.25);
+        let deposition = self.deposition.clamp(0.0, 1.0);
+
+        let neighbour = |i: usize, (dx, dy): (isize, isize)| {
+            let x = (i % size) as isize + dx;
+            let y = (i / size) as isize + dy;
+            (x >= 0 && y >= 0 && (x as usize) < size && (y as usize) < size)
+                .then(|| y as usize * size + x as usize)
+        };
+
+        let mut delta = vec![0.0f32; heights.len()];
+        for _ in 0..self.iterations {
+            delta.iter_mut().for_each(|d| *d = 0.0);
+
+            for i in 0..heights.len() {
+                let h = heights[i];
+                let mut lowest: Option<(usize, f32)> = None;
+
+                for offset in NEIGHBOURS {
+                    let Some(j) = neighbour(i, offset) else {
+                        continue;
+                    };
+                    let drop = h - heights[j];
+
+                    // Thermal: each pair is handled once, by its higher side.
+                    if drop > talus {
+                        let moved = thermal_rate * (drop - talus);
+                        delta[i] -= moved;
+                        delta[j] += moved;
+                    }
+
+                    if drop > 0.0 && lowest.is_none_or(|(_, d)| drop > d) {
+                        lowest = Some((j, drop));
+                    }
+                }
+
+                // Hydraulic: slope-proportional removal, partial deposition.
+                if let Some((j, drop)) = lowest {
+                    let eroded = (self.hydraulic_rate * drop / cell_size).min(drop * 0.5);
+                    delta[i] -= eroded;
+                    delta[j] += eroded * deposition;
+                }
+            }
+
+            for (h, d) in heights.iter_mut().zip(&delta) {
+                *h += d;
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Warm standby support.
+//!
+//! The active world service publishes a [`WorldHeartbeat`] every
+//! `heartbeat_interval_s` and mirrors its durable state to the
+//! [`MIRROR_CHECKPOINT`] checkpoint every `mirror_interval_s`.  A standby
+//! instance (same checkpoint directory, `standby` set) joins the session
+//! without the `world` role and watches the heartbeats.  When they stop for
+//! `failover_timeout_s` it restores the mirror, connects as `world`, and
+//! announces a [`WorldFailover`](crate::protocol::WorldFailover) so clients
+//! resync from a fresh snapshot.
+//!
+//! State newer than the last mirror is lost on takeover; keep the mirror
+//! interval well below the failover timeout.
+
+use crate::protocol::WorldHeartbeat;
+use std::time::{Duration, Instant};
+
+/// Checkpoint name the active instance mirrors its state to.
+pub const MIRROR_CHECKPOINT: &str = "mirror";
+
+/// Decides when a standby should take over.
+pub struct HeartbeatMonitor {
+    timeout: Duration,
+    last_seen: Instant,
+    primary: Option<String>,
+}
+
+impl HeartbeatMonitor {
+    /// Start watching at `now`.  A primary that is never heard from counts
+    /// as lost `timeout` after start.
+    pub fn new(timeout: Duration, now: Instant) -> Self {
+        Self {
+            timeout,
+            last_seen: now,
+            primary: None,
+        }
+    }
+
+    /// Record a heartbeat received at `now`.
+    pub fn observe(&mut self, heartbeat: &WorldHeartbeat, now: Instant) {
+        if self.primary.as_deref() != Some(heartbeat.instance_id.as_str()) {
+            self.primary = Some(heartbeat.instance_id.clone());
+        }
+        self.last_seen = self.last_seen.max(now);
+    }
+
+    /// The instance that sent the latest heartbeat.
+    pub fn primary(&self) -> Option<&str> {
+        self.primary.as_deref()
+    }
+
+    /// `true` once no heartbeat has arrived for the full timeout.
+    pub fn primary_lost(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_seen) >= self.timeout
+    }
+}
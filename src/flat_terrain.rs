@@ -0,0 +1,62 @@
+//! Flat terrain: a level plane at a fixed height.
+//!
+//! For arena-style games and tests that want streaming, colliders and
+//! raycasts without any terrain generation cost.
+
+use crate::terrain::{ChunkDescriptor, TerrainSource};
+use crate::types::Vec3;
+use janet_operations::physics::types::ColliderShape;
+use std::any::Any;
+
+/// Chunk side used unless [`FlatTerrain::with_chunk_size`] overrides it.
+pub const DEFAULT_FLAT_CHUNK_SIZE: f32 = 40.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlatTerrain {
+    pub height: f32,
+    pub chunk_size: f32,
+}
+
+impl FlatTerrain {
+    pub fn new(height: f32) -> Self {
+        Self {
+            height,
+            chunk_size: DEFAULT_FLAT_CHUNK_SIZE,
+        }
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: f32) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+}
+
+impl TerrainSource for FlatTerrain {
+    fn height_at(&self, _x: f32, _y: f32) -> f32 {
+        self.height
+    }
+
+    fn normal_at(&self, _x: f32, _y: f32) -> Vec3 {
+        Vec3::new(0.0, 0.0, 1.0)
+    }
+
+    fn chunk_descriptor(&self, _cx: i32, _cy: i32, _lod: u8) -> Option<ChunkDescriptor> {
+        Some(ChunkDescriptor {
+            seed: 0,
+            chunk_size: self.chunk_size,
+            hydrology: None,
+            materials: Default::default(),
+        })
+    }
+
+    fn collider_for_chunk(&self, _cx: i32, _cy: i32, _lod: u8) -> Option<ColliderShape> {
+        Some(ColliderShape::Box {
+            width: self.chunk_size,
+            height: self.chunk_size,
+        })
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,173 @@
+//! Designer height overrides: sparse per-chunk height deltas applied on
+//! top of generated terrain and persisted so touch-ups survive restarts.
+//!
+//! Unlike runtime deformation (replayed from `terrain_modifications`),
+//! overrides belong to the world itself.  Each chunk with any lives in its
+//! own file `{cx}_{cy}.hov` inside the override directory.  Files are
+//! little-endian:
+//!
+//! ```text
+//! magic    [u8; 4]  = b"JWHO"
+//! version  u8       = 1
+//! count    u32
+//! entries  [(lx u32, ly u32, delta f32); count]
+//! ```
+//!
+//! `(lx, ly)` index the chunk's LOD 0 grid.  A chunk whose last delta is
+//! cleared has its file removed.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 4] = b"JWHO";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 4 + 1 + 4;
+const ENTRY_LEN: usize = 4 + 4 + 4;
+
+/// LOD 0 grid deltas of one chunk, by local `(lx, ly)`.
+pub type ChunkOverrides = BTreeMap<(u32, u32), f32>;
+
+#[derive(Debug, Default)]
+pub struct HeightOverrides {
+    dir: Option<PathBuf>,
+    chunks: HashMap<(i32, i32), ChunkOverrides>,
+}
+
+impl HeightOverrides {
+    /// Overrides kept in memory only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open (and create if needed) an override directory and load every
+    /// chunk file in it.  Unreadable or malformed files are an error rather
+    /// than silently dropped designer work.
+    pub fn open(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        let mut chunks = HashMap::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let Some(key) = chunk_key(&path) else {
+                continue;
+            };
+            let deltas = decode(&fs::read(&path)?).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("malformed height override file {}", path.display()),
+                )
+            })?;
+            if !deltas.is_empty() {
+                chunks.insert(key, deltas);
+            }
+        }
+        Ok(Self {
+            dir: Some(dir),
+            chunks,
+        })
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Total number of overridden samples.
+    pub fn len(&self) -> usize {
+        self.chunks.values().map(BTreeMap::len).sum()
+    }
+
+    pub fn chunk(&self, cx: i32, cy: i32) -> Option<&ChunkOverrides> {
+        self.chunks.get(&(cx, cy))
+    }
+
+    /// Delta at local sample `(lx, ly)` of chunk `(cx, cy)`.
+    pub fn get(&self, cx: i32, cy: i32, lx: u32, ly: u32) -> Option<f32> {
+        self.chunks.get(&(cx, cy))?.get(&(lx, ly)).copied()
+    }
+
+    /// Replace deltas in chunk `(cx, cy)` (`0` clears a sample) and write
+    /// the chunk's file.  Memory is only updated once the write succeeded.
+    pub fn set(
+        &mut self,
+        cx: i32,
+        cy: i32,
+        deltas: impl IntoIterator<Item = ((u32, u32), f32)>,
+    ) -> io::Result<()> {
+        let mut chunk = self.chunks.get(&(cx, cy)).cloned().unwrap_or_default();
+        for (local, delta) in deltas {
+            if delta == 0.0 {
+                chunk.remove(&local);
+            } else {
+                chunk.insert(local, delta);
+            }
+        }
+        if let Some(dir) = &self.dir {
+            let path = dir.join(format!("{}_{}.hov", cx, cy));
+            if chunk.is_empty() {
+                match fs::remove_file(&path) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                    _ => {}
+                }
+            } else {
+                // Write to a temp file first so a crash never leaves a torn file.
+                let tmp = path.with_extension("hov.tmp");
+                fs::write(&tmp, encode(&chunk))?;
+                fs::rename(tmp, path)?;
+            }
+        }
+        if chunk.is_empty() {
+            self.chunks.remove(&(cx, cy));
+        } else {
+            self.chunks.insert((cx, cy), chunk);
+        }
+        Ok(())
+    }
+}
+
+/// `(cx, cy)` from a `{cx}_{cy}.hov` file name.
+fn chunk_key(path: &Path) -> Option<(i32, i32)> {
+    if path.extension()? != "hov" {
+        return None;
+    }
+    let (cx, cy) = path.file_stem()?.to_str()?.split_once('_')?;
+    Some((cx.parse().ok()?, cy.parse().ok()?))
+}
+
+fn encode(chunk: &ChunkOverrides) -> Vec<u8> {
+    let mut out = Vec::with_capacity(HEADER_LEN + chunk.len() * ENTRY_LEN);
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+    for (&(lx, ly), delta) in chunk {
+        out.extend_from_slice(&lx.to_le_bytes());
+        out.extend_from_slice(&ly.to_le_bytes());
+        out.extend_from_slice(&delta.to_le_bytes());
+    }
+    out
+}
+
+fn decode(bytes: &[u8]) -> Option<ChunkOverrides> {
+    if bytes.len() < HEADER_LEN || &bytes[0..4] != MAGIC || bytes[4] != VERSION {
+        return None;
+    }
+    let count = u32::from_le_bytes(bytes[5..9].try_into().unwrap()) as usize;
+    let body = &bytes[HEADER_LEN..];
+    if body.len() != count * ENTRY_LEN {
+        return None;
+    }
+    let u32_at = |b: &[u8], o: usize| u32::from_le_bytes(b[o..o + 4].try_into().unwrap());
+    Some(
+        body.chunks_exact(ENTRY_LEN)
+            .map(|e| {
+                let delta = f32::from_le_bytes(e[8..12].try_into().unwrap());
+                ((u32_at(e, 0), u32_at(e, 4)), delta)
+            })
+            .collect(),
+    )
+}
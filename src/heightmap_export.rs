@@ -0,0 +1,102 @@
+//! Rendering terrain heights to heightmap files (`world.cmd.terrain.export`).
+//!
+//! Exports use the same layout [`ImageTerrain`](crate::image_terrain::ImageTerrain)
+//! loads: 16-bit greyscale, row 0 at the region's minimum `y`, sample `0`
+//! at the lowest height in the region and `65535` at the highest.  The
+//! export's origin, spacing and height range therefore plug straight into
+//! an `ImageTerrainConfig`.
+
+use crate::protocol::HeightmapFormat;
+use crate::terrain::TerrainSource;
+use image::{ImageBuffer, ImageFormat, Luma};
+use std::io::{self, Cursor};
+
+/// Heights of a `width` × `height` grid starting at `origin`, `spacing`
+/// metres apart, row-major with rows along `+y`.
+pub fn sample(
+    terrain: &dyn TerrainSource,
+    origin: [f32; 2],
+    spacing: f32,
+    width: usize,
+    height: usize,
+) -> Vec<f32> {
+    (0..height)
+        .flat_map(|row| {
+            let y = origin[1] + row as f32 * spacing;
+            (0..width).map(move |col| terrain.height_at(origin[0] + col as f32 * spacing, y))
+        })
+        .collect()
+}
+
+/// Heights scaled to the full `u16` range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalisedHeights {
+    /// Height of sample `0`.
+    pub min_height: f32,
+    /// Height of sample `65535` (equal to `min_height` for flat regions,
+    /// whose samples are all `0`).
+    pub max_height: f32,
+    pub samples: Vec<u16>,
+}
+
+pub fn normalise(heights: &[f32]) -> NormalisedHeights {
+    let (min, max) = heights
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &h| {
+            (lo.min(h), hi.max(h))
+        });
+    if heights.is_empty() {
+        return NormalisedHeights {
+            min_height: 0.0,
+            max_height: 0.0,
+            samples: Vec::new(),
+        };
+    }
+    let range = max - min;
+    let samples = heights
+        .iter()
+        .map(|&h| {
+            if range > 0.0 {
+                ((h - min) / range * u16::MAX as f32).round() as u16
+            } else {
+                0
+            }
+        })
+        .collect();
+    NormalisedHeights {
+        min_height: min,
+        max_height: max,
+        samples,
+    }
+}
+
+/// File contents of a `width` × `height` heightmap: a 16-bit greyscale
+/// PNG, or headerless little-endian `u16`s for [`HeightmapFormat::Raw`].
+pub fn encode(
+    width: u32,
+    height: u32,
+    samples: &[u16],
+    format: HeightmapFormat,
+) -> io::Result<Vec<u8>> {
+    match format {
+        HeightmapFormat::Png => {
+            let img = ImageBuffer::<Luma<u16>, _>::from_raw(width, height, samples.to_vec())
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "{} samples don't fill a {}x{} image",
+                            samples.len(),
+                            width,
+                            height
+                        ),
+                    )
+                })?;
+            let mut out = Cursor::new(Vec::new());
+            img.write_to(&mut out, ImageFormat::Png)
+                .map_err(io::Error::other)?;
+            Ok(out.into_inner())
+        }
+        HeightmapFormat::Raw => Ok(samples.iter().flat_map(|s| s.to_le_bytes()).collect()),
+    }
+}
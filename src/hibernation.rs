@@ -0,0 +1,128 @@
+//! Hibernation of deactivated cells' object state.
+//!
+//! World objects (vegetation and the like) are regenerated from the seed
+//! whenever their cell activates, so a change to one (moved, properties
+//! edited) would be lost once its cell deactivates.  Instead the service
+//! serialises every changed object of a cell into the [`HibernationStore`]
+//! as soon as it changes and, whenever the cell activates, restores them
+//! over the regenerated ones by id.  A cell's entry is only ever replaced,
+//! never dropped on activation, so nothing is lost whether the service
+//! stops while the cell is active or not.
+//!
+//! A store opened on a directory also keeps each hibernated cell in its own
+//! file `{x}_{y}_{z}.json`, so the state survives restarts.
+
+use crate::types::{CellCoord, WorldObject};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default)]
+pub struct HibernationStore {
+    dir: Option<PathBuf>,
+    /// Serialised objects per cell.
+    cells: HashMap<CellCoord, Vec<u8>>,
+}
+
+impl HibernationStore {
+    /// Hibernated cells kept in memory only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open (and create if needed) a hibernation directory and load every
+    /// cell file in it.
+    pub fn open(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        let mut cells = HashMap::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if let Some(coord) = cell_key(&path) {
+                cells.insert(coord, fs::read(&path)?);
+            }
+        }
+        Ok(Self {
+            dir: Some(dir),
+            cells,
+        })
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// Number of hibernated cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, coord: CellCoord) -> bool {
+        self.cells.contains_key(&coord)
+    }
+
+    /// Hibernate `objects` of cell `coord`, replacing what it held.
+    pub fn put(&mut self, coord: CellCoord, objects: &[WorldObject]) -> io::Result<()> {
+        let bytes = serde_json::to_vec(objects).map_err(io::Error::other)?;
+        if let Some(dir) = &self.dir {
+            let path = cell_path(dir, coord);
+            // Write to a temp file first so a crash never leaves a torn file.
+            let tmp = path.with_extension("json.tmp");
+            fs::write(&tmp, &bytes)?;
+            fs::rename(tmp, path)?;
+        }
+        self.cells.insert(coord, bytes);
+        Ok(())
+    }
+
+    /// Objects hibernated for `coord`, if any (reading doesn't remove them).
+    pub fn get(&self, coord: CellCoord) -> io::Result<Option<Vec<WorldObject>>> {
+        self.cells
+            .get(&coord)
+            .map(|bytes| {
+                serde_json::from_slice(bytes).map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("malformed hibernated cell {}: {}", coord, e),
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    /// Forget what cell `coord` held.
+    pub fn remove(&mut self, coord: CellCoord) -> io::Result<()> {
+        if self.cells.remove(&coord).is_some() {
+            if let Some(dir) = &self.dir {
+                match fs::remove_file(cell_path(dir, coord)) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn cell_path(dir: &Path, coord: CellCoord) -> PathBuf {
+    dir.join(format!("{}_{}_{}.json", coord.x, coord.y, coord.z))
+}
+
+/// Cell of an `{x}_{y}_{z}.json` file name.
+fn cell_key(path: &Path) -> Option<CellCoord> {
+    if path.extension()? != "json" {
+        return None;
+    }
+    let mut parts = path.file_stem()?.to_str()?.split('_');
+    let coord = CellCoord::new(
+        parts.next()?.parse().ok()?,
+        parts.next()?.parse().ok()?,
+        parts.next()?.parse().ok()?,
+    );
+    parts.next().is_none().then_some(coord)
+}
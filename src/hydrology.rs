@@ -0,0 +1,52 @@
+//! Rivers and lakes for procedural terrain.
+//!
+//! Rivers follow the `0.5` contour of a low-frequency noise field: wherever
+//! the noise is within `river_width` of the contour, the terrain is cut down
+//! by up to `river_depth` with a smooth bank profile.  Everything here is a
+//! pure function of world position and seed, so carving is seamless across
+//! chunks and clients can reproduce it from the `HydrologyConfig` carried in
+//! `ChunkActivated`.
+//!
+//! Water surfaces:
+//!
+//! * **Lakes** — flat planes at `water_table` over any terrain below it.
+//! * **Rivers** — the uncarved terrain height minus half the river depth, so
+//!   water fills the middle of each channel and leaves the banks dry.
+
+use crate::terrain::unit_noise;
+use crate::types::HydrologyConfig;
+
+/// Salt for the river network noise (distinct from the elevation octaves).
+const RIVER_SALT: u64 = 0x7777;
+
+/// How strongly `(x, y)` lies inside a river channel: `1` on the centre
+/// line, falling smoothly to `0` at the banks and beyond.
+pub fn river_factor(cfg: &HydrologyConfig, seed: u64, x: f32, y: f32) -> f32 {
+    if cfg.river_width <= 0.0 {
+        return 0.0;
+    }
+    let n = unit_noise(
+        x as f64,
+        y as f64,
+        cfg.river_scale as f64,
+        seed ^ RIVER_SALT,
+    );
+    let t = ((n - 0.5).abs() / cfg.river_width as f64).min(1.0);
+    (1.0 - t * t * (3.0 - 2.0 * t)) as f32
+}
+
+/// Terrain height after cutting river channels into `height`.
+pub fn carve(cfg: &HydrologyConfig, seed: u64, x: f32, y: f32, height: f32) -> f32 {
+    height - cfg.river_depth * river_factor(cfg, seed, x, y)
+}
+
+/// Water surface height at `(x, y)` given the *uncarved* terrain height
+/// there.  Whether the surface is actually above the ground is up to the
+/// caller (the final ground may also include erosion and runtime edits).
+pub fn surface(cfg: &HydrologyConfig, seed: u64, x: f32, y: f32, base_height: f32) -> f32 {
+    if river_factor(cfg, seed, x, y) > 0.0 {
+        cfg.water_table.max(base_height - 0.5 * cfg.river_depth)
+    } else {
+        cfg.water_table
+    }
+}
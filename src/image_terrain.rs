@@ -0,0 +1,326 @@
+//! Image-backed terrain: serve hand-authored heightmaps (PNG / RAW / EXR)
+//! through the same chunked [`TerrainSource`] interface as procedural noise.
+//!
+//! Pixel `(0, 0)` sits at `(origin_x, origin_y)` in world space and each
+//! pixel covers `metres_per_pixel` metres.  Samples are normalised to `[0, 1]`
+//! and mapped linearly onto `[min_height, max_height]`.  Queries outside the
+//! image clamp to the nearest edge pixel.
+
+use crate::terrain::{
+    footprint_collider, CacheBudget, ChunkCache, ChunkDescriptor, HeightChunk, TerrainSource,
+};
+use crate::types::Vec3;
+use janet_operations::physics::types::ColliderShape;
+use parking_lot::RwLock;
+use std::any::Any;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Error)]
+pub enum ImageTerrainError {
+    #[error("failed to read heightmap: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to decode heightmap image: {0}")]
+    Decode(#[from] image::ImageError),
+    #[error("RAW heightmap has {len} bytes, expected {expected} for {width}x{height} u16 samples")]
+    RawSize {
+        len: usize,
+        expected: usize,
+        width: usize,
+        height: usize,
+    },
+    #[error("RAW heightmap of {0} bytes is not square; use ImageTerrain::load_raw with explicit dimensions")]
+    RawNotSquare(usize),
+    #[error("unsupported heightmap format '{0}' (expected png, exr, raw or r16)")]
+    UnsupportedFormat(String),
+    #[error("heightmap has {len} samples, expected {width}x{height}")]
+    Dimensions {
+        len: usize,
+        width: usize,
+        height: usize,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// Config
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct ImageTerrainConfig {
+    /// World-space metres covered by a single pixel.
+    pub metres_per_pixel: f32,
+    /// Height assigned to a sample of `0.0` (black).
+    pub min_height: f32,
+    /// Height assigned to a sample of `1.0` (white).
+    pub max_height: f32,
+    /// World-space X of pixel `(0, 0)`.
+    pub origin_x: f32,
+    /// World-space Y of pixel `(0, 0)`.
+    pub origin_y: f32,
+    /// World-space width/height of a single terrain chunk.
+    pub chunk_size: f32,
+    /// Sample resolution at LOD 0 (halved per LOD level).
+    pub base_resolution: usize,
+}
+
+impl Default for ImageTerrainConfig {
+    fn default() -> Self {
+        Self {
+            metres_per_pixel: 2.0,
+            min_height: 0.0,
+            max_height: 1.0,
+            origin_x: 0.0,
+            origin_y: 0.0,
+            chunk_size: 40.0,
+            base_resolution: 64,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Image terrain
+// ---------------------------------------------------------------------------
+
+/// Chunk cache budget of a new [`ImageTerrain`], the server's default
+/// `chunk_cache_mb`.
+pub const DEFAULT_CACHE_BUDGET: CacheBudget = CacheBudget::Bytes(256 << 20);
+
+pub struct ImageTerrain {
+    pub config: ImageTerrainConfig,
+    width: usize,
+    height: usize,
+    /// Normalised `[0, 1]` samples, row-major (`y * width + x`).
+    samples: Vec<f32>,
+    /// LRU limit for `cache` (unbounded when `None`); defaults to
+    /// [`DEFAULT_CACHE_BUDGET`].
+    pub cache_budget: Option<CacheBudget>,
+    cache: RwLock<ChunkCache>,
+    /// Monotonic lookup counter used as the LRU timestamp.
+    cache_clock: AtomicU64,
+}
+
+impl ImageTerrain {
+    /// Build from an in-memory grid of normalised samples (row-major).
+    pub fn from_samples(
+        width: usize,
+        height: usize,
+        samples: Vec<f32>,
+        config: ImageTerrainConfig,
+    ) -> Result<Self, ImageTerrainError> {
+        if width == 0 || height == 0 || samples.len() != width * height {
+            return Err(ImageTerrainError::Dimensions {
+                len: samples.len(),
+                width,
+                height,
+            });
+        }
+
+        Ok(Self {
+            config,
+            width,
+            height,
+            samples,
+            cache_budget: Some(DEFAULT_CACHE_BUDGET),
+            cache: RwLock::new(ChunkCache::default()),
+            cache_clock: AtomicU64::new(0),
+        })
+    }
+
+    /// Bound the chunk cache; least recently used chunks are evicted
+    /// beyond `budget`.
+    pub fn with_cache_budget(mut self, budget: CacheBudget) -> Self {
+        self.cache_budget = Some(budget);
+        self
+    }
+
+    /// Load a heightmap from disk, choosing the decoder by file extension.
+    ///
+    /// * `png` – 8- or 16-bit greyscale (colour images use luminance).
+    /// * `exr` – first channel as 32-bit float, already normalised.
+    /// * `raw` / `r16` – square, headerless, little-endian `u16` samples.
+    pub fn load(
+        path: impl AsRef<Path>,
+        config: ImageTerrainConfig,
+    ) -> Result<Self, ImageTerrainError> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        match ext.as_str() {
+            "png" => {
+                let img = image::open(path)?.into_luma16();
+                let (w, h) = img.dimensions();
+                let samples = img
+                    .into_raw()
+                    .into_iter()
+                    .map(|v| v as f32 / u16::MAX as f32)
+                    .collect();
+                Self::from_samples(w as usize, h as usize, samples, config)
+            }
+            "exr" => {
+                let img = image::open(path)?.into_rgb32f();
+                let (w, h) = img.dimensions();
+                let samples = img.pixels().map(|p| p.0[0]).collect();
+                Self::from_samples(w as usize, h as usize, samples, config)
+            }
+            "raw" | "r16" => {
+                let len = std::fs::metadata(path)?.len() as usize;
+                let side = ((len / 2) as f64).sqrt() as usize;
+                if side * side * 2 != len {
+                    return Err(ImageTerrainError::RawNotSquare(len));
+                }
+                Self::load_raw(path, side, side, config)
+            }
+            other => Err(ImageTerrainError::UnsupportedFormat(other.to_string())),
+        }
+    }
+
+    /// Load a headerless little-endian `u16` RAW heightmap of known size.
+    pub fn load_raw(
+        path: impl AsRef<Path>,
+        width: usize,
+        height: usize,
+        config: ImageTerrainConfig,
+    ) -> Result<Self, ImageTerrainError> {
+        let bytes = std::fs::read(path)?;
+        let expected = width * height * 2;
+        if bytes.len() != expected {
+            return Err(ImageTerrainError::RawSize {
+                len: bytes.len(),
+                expected,
+                width,
+                height,
+            });
+        }
+
+        let samples = bytes
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as f32 / u16::MAX as f32)
+            .collect();
+        Self::from_samples(width, height, samples, config)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn chunk_size(&self) -> f32 {
+        self.config.chunk_size
+    }
+
+    /// Build a collider for a chunk at the given LOD.
+    ///
+    /// Mirrors [`HeightmapTerrain::heightfield_collider_for_chunk`] so the
+    /// service can stream either backend identically.
+    ///
+    /// [`HeightmapTerrain::heightfield_collider_for_chunk`]: crate::terrain::HeightmapTerrain::heightfield_collider_for_chunk
+    pub fn heightfield_collider_for_chunk(&self, cx: i32, cy: i32, lod: u8) -> ColliderShape {
+        let chunk = self.get_or_generate_chunk(cx, cy, lod);
+        footprint_collider(chunk.resolution as f32 * chunk.cell_size)
+    }
+
+    /// Cached chunk, sampling it from the image on a miss.
+    pub fn get_or_generate_chunk(&self, cx: i32, cy: i32, lod: u8) -> Arc<HeightChunk> {
+        let key = (cx, cy, lod);
+        let now = self.cache_clock.fetch_add(1, Ordering::Relaxed);
+        if let Some(chunk) = self.cache.read().get(&key, now) {
+            return chunk;
+        }
+        let chunk = Arc::new(HeightChunk::sample(
+            cx,
+            cy,
+            lod,
+            self.config.chunk_size,
+            self.config.base_resolution,
+            |wx, wy| self.height_at(wx, wy),
+        ));
+
+        let mut cache = self.cache.write();
+        if let Some(cached) = cache.get(&key, now) {
+            return cached;
+        }
+        cache.insert(key, chunk.clone(), now);
+        if let Some(budget) = self.cache_budget {
+            cache.enforce(budget);
+        }
+        chunk
+    }
+
+    /// Normalised sample at integer pixel coordinates, clamped to the image.
+    fn pixel(&self, px: i64, py: i64) -> f32 {
+        let x = px.clamp(0, self.width as i64 - 1) as usize;
+        let y = py.clamp(0, self.height as i64 - 1) as usize;
+        self.samples[y * self.width + x]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TerrainSource impl
+// ---------------------------------------------------------------------------
+
+impl TerrainSource for ImageTerrain {
+    fn height_at(&self, x: f32, y: f32) -> f32 {
+        let c = &self.config;
+        let gx = (x - c.origin_x) / c.metres_per_pixel;
+        let gy = (y - c.origin_y) / c.metres_per_pixel;
+
+        // Bilinear between the four surrounding pixels.
+        let ix = gx.floor() as i64;
+        let iy = gy.floor() as i64;
+        let fx = gx - ix as f32;
+        let fy = gy - iy as f32;
+
+        let top = self.pixel(ix, iy) * (1.0 - fx) + self.pixel(ix + 1, iy) * fx;
+        let bottom = self.pixel(ix, iy + 1) * (1.0 - fx) + self.pixel(ix + 1, iy + 1) * fx;
+        let v = top * (1.0 - fy) + bottom * fy;
+
+        c.min_height + v * (c.max_height - c.min_height)
+    }
+
+    fn normal_at(&self, x: f32, y: f32) -> Vec3 {
+        let eps = self.config.metres_per_pixel * 0.5;
+        let h_l = self.height_at(x - eps, y);
+        let h_r = self.height_at(x + eps, y);
+        let h_d = self.height_at(x, y - eps);
+        let h_u = self.height_at(x, y + eps);
+        Vec3::new(h_l - h_r, h_d - h_u, 2.0 * eps)
+    }
+
+    fn chunk_descriptor(&self, _cx: i32, _cy: i32, _lod: u8) -> Option<ChunkDescriptor> {
+        Some(ChunkDescriptor {
+            seed: 0,
+            chunk_size: self.chunk_size(),
+            hydrology: None,
+            materials: Default::default(),
+        })
+    }
+
+    fn collider_for_chunk(&self, cx: i32, cy: i32, lod: u8) -> Option<ColliderShape> {
+        Some(self.heightfield_collider_for_chunk(cx, cy, lod))
+    }
+
+    fn warm_chunk(&self, cx: i32, cy: i32, lod: u8) {
+        self.get_or_generate_chunk(cx, cy, lod);
+    }
+
+    fn is_chunk_cached(&self, cx: i32, cy: i32, lod: u8) -> bool {
+        self.cache.read().contains(&(cx, cy, lod))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
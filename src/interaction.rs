@@ -0,0 +1,104 @@
+//! Interaction handlers: the game logic behind `intent.interact`.
+//!
+//! [`WorldService::resolve_interaction`](crate::service::WorldService::resolve_interaction)
+//! finds the target (a structure or a tracked entity), checks the actor is
+//! within `interact_reach` of it, then hands the [`Interaction`] to the
+//! handler registered for its verb and target kind.  Interactive
+//! structures without a handler for the verb fall back to their state
+//! transitions (doors, gates).  Either way the outcome is published as
+//! `world.interaction.result`.
+//!
+//! Handlers are anything implementing [`InteractionHandler`], closures
+//! included:
+//!
+//! ```ignore
+//! service.register_interaction_handler("talk", InteractionTarget::Entity, |i: &Interaction| {
+//!     Ok(serde_json::json!({ "line": format!("{} greets you", i.target_id) }))
+//! });
+//! ```
+
+use crate::protocol::InteractionTarget;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Verb of an `intent.interact` that names none.
+pub const DEFAULT_VERB: &str = "use";
+
+/// One in-reach interaction, as handed to its handler.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interaction<'a> {
+    pub actor_id: &'a str,
+    pub target_id: &'a str,
+    pub target: InteractionTarget,
+    /// Structure `type_id` or entity archetype.
+    pub target_type: &'a str,
+    pub verb: &'a str,
+    /// Gap between the actor's and the target's bounding circles.
+    pub distance: f32,
+}
+
+pub trait InteractionHandler: Send + Sync {
+    /// Carry out `interaction`.  `Ok` data goes out with the result; an
+    /// `Err` is the failure shown to the player.
+    fn interact(&self, interaction: &Interaction) -> Result<serde_json::Value, String>;
+}
+
+impl<F> InteractionHandler for F
+where
+    F: Fn(&Interaction) -> Result<serde_json::Value, String> + Send + Sync,
+{
+    fn interact(&self, interaction: &Interaction) -> Result<serde_json::Value, String> {
+        self(interaction)
+    }
+}
+
+/// Handlers by verb and target kind.
+#[derive(Default)]
+pub struct InteractionHandlers {
+    handlers: HashMap<(String, InteractionTarget), Arc<dyn InteractionHandler>>,
+}
+
+impl InteractionHandlers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Handle `verb` on targets of kind `target` with `handler`, replacing
+    /// any handler registered for them before.
+    pub fn register(
+        &mut self,
+        verb: impl Into<String>,
+        target: InteractionTarget,
+        handler: impl InteractionHandler + 'static,
+    ) {
+        self.handlers
+            .insert((verb.into(), target), Arc::new(handler));
+    }
+
+    pub fn get(
+        &self,
+        verb: &str,
+        target: InteractionTarget,
+    ) -> Option<Arc<dyn InteractionHandler>> {
+        self.handlers.get(&(verb.to_string(), target)).cloned()
+    }
+}
+
+impl fmt::Debug for InteractionHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut keys: Vec<_> = self.handlers.keys().collect();
+        keys.sort();
+        f.debug_struct("InteractionHandlers")
+            .field("handlers", &keys)
+            .finish()
+    }
+}
@@ -0,0 +1,232 @@
+//! Per-participant structure interest scopes.
+//!
+//! Each participant's scope is the set of structures overlapping its
+//! streaming window.  The tracker remembers what it has loaded for every
+//! participant and, whenever the participant changes cell, diffs the new
+//! window against it to produce a [`StructureInterest`] message.  Structures
+//! are static, so a participant that stays inside one cell costs a single
+//! map lookup per tick.
+//!
+//! `Near` and `Detail` [`StreamTier`] structures use a narrower window (see
+//! [`InterestTracker::with_tier_radii`]), so small props don't load for the
+//! whole view distance.
+//!
+//! [`CellInterest`] does the same for cell-scoped event subjects: it tracks
+//! which interest cells each participant's client listens to and produces
+//! an [`InterestSubjects`] message when that set changes.
+
+use crate::protocol::{subjects, InterestSubjects, StructureInterest};
+use crate::structure::{StreamTier, StructureInstance, StructureRegistry};
+use crate::types::Vec3;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+struct Scope {
+    cell: (i32, i32),
+    structures: HashSet<String>,
+}
+
+pub struct InterestTracker {
+    scopes: HashMap<String, Scope>,
+    near_radius: i32,
+    detail_radius: i32,
+}
+
+impl Default for InterestTracker {
+    fn default() -> Self {
+        Self::with_tier_radii(i32::MAX, i32::MAX)
+    }
+}
+
+impl InterestTracker {
+    /// Every tier streams over the whole window.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `Near` and `Detail` structures stream within these many cells of the
+    /// participant (capped at the window radius).
+    pub fn with_tier_radii(near_radius: i32, detail_radius: i32) -> Self {
+        Self {
+            scopes: HashMap::new(),
+            near_radius,
+            detail_radius,
+        }
+    }
+
+    fn tier_radius(&self, tier: StreamTier, radius: i32) -> i32 {
+        match tier {
+            StreamTier::Always => radius,
+            StreamTier::Near => self.near_radius.min(radius),
+            StreamTier::Detail => self.detail_radius.min(radius),
+        }
+    }
+
+    /// Move `participant_id` to `pos`; returns the scope change, if any.
+    ///
+    /// The window covers `radius` cells of `cell_size` around the
+    /// participant's cell, matching terrain streaming; `Near` and `Detail`
+    /// structures must overlap their tier's narrower window.
+    pub fn update(
+        &mut self,
+        participant_id: &str,
+        pos: Vec3,
+        cell_size: f32,
+        radius: i32,
+        registry: &StructureRegistry,
+    ) -> Option<StructureInterest> {
+        let cell = (
+            (pos.x / cell_size).floor() as i32,
+            (pos.y / cell_size).floor() as i32,
+        );
+        if self
+            .scopes
+            .get(participant_id)
+            .is_some_and(|s| s.cell == cell)
+        {
+            return None;
+        }
+
+        let mut in_window = registry.query_rect(
+            (cell.0 - radius) as f32 * cell_size,
+            (cell.1 - radius) as f32 * cell_size,
+            (cell.0 + radius + 1) as f32 * cell_size,
+            (cell.1 + radius + 1) as f32 * cell_size,
+        );
+        in_window.retain(|s| {
+            s.tier.is_always()
+                || overlaps_window(s, cell, self.tier_radius(s.tier, radius), cell_size)
+        });
+        let scope = self
+            .scopes
+            .entry(participant_id.to_string())
+            .or_insert_with(|| Scope {
+                cell,
+                structures: HashSet::new(),
+            });
+        scope.cell = cell;
+
+        let mut loaded: Vec<_> = in_window
+            .iter()
+            .filter(|s| !scope.structures.contains(&s.id))
+            .map(|s| s.to_spawned())
+            .collect();
+        let current: HashSet<&str> = in_window.iter().map(|s| s.id.as_str()).collect();
+        let mut unloaded: Vec<_> = scope
+            .structures
+            .iter()
+            .filter(|id| !current.contains(id.as_str()))
+            .cloned()
+            .collect();
+
+        if loaded.is_empty() && unloaded.is_empty() {
+            return None;
+        }
+        for id in &unloaded {
+            scope.structures.remove(id);
+        }
+        scope
+            .structures
+            .extend(loaded.iter().map(|s| s.structure_id.clone()));
+        loaded.sort_by(|a, b| a.structure_id.cmp(&b.structure_id));
+        unloaded.sort();
+
+        Some(StructureInterest {
+            participant_id: participant_id.to_string(),
+            loaded,
+            unloaded,
+        })
+    }
+
+    /// Forget a participant (it left; nothing needs to be unloaded).
+    pub fn remove(&mut self, participant_id: &str) {
+        self.scopes.remove(participant_id);
+    }
+
+    /// `true` if `structure_id` is currently loaded for `participant_id`.
+    pub fn in_scope(&self, participant_id: &str, structure_id: &str) -> bool {
+        self.scopes
+            .get(participant_id)
+            .is_some_and(|s| s.structures.contains(structure_id))
+    }
+}
+
+/// `true` if `s`'s bounding square overlaps the `radius` cells around
+/// `cell`.
+fn overlaps_window(s: &StructureInstance, cell: (i32, i32), radius: i32, cell_size: f32) -> bool {
+    let r = s.bounds_radius;
+    let (min_x, min_y) = (
+        (cell.0 - radius) as f32 * cell_size,
+        (cell.1 - radius) as f32 * cell_size,
+    );
+    let (max_x, max_y) = (
+        (cell.0 + radius + 1) as f32 * cell_size,
+        (cell.1 + radius + 1) as f32 * cell_size,
+    );
+    s.position.x + r >= min_x
+        && s.position.x - r <= max_x
+        && s.position.y + r >= min_y
+        && s.position.y - r <= max_y
+}
+
+/// Interest cells each participant's client is subscribed to.
+#[derive(Debug)]
+pub struct CellInterest {
+    cell_size: f32,
+    cells: HashMap<String, BTreeSet<(i32, i32)>>,
+}
+
+impl CellInterest {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Subscribe `participant_id` to the cells overlapping the square of
+    /// half-side `radius` around `pos`; returns the change, if any.
+    /// Subjects are base subjects (see [`subjects::cell`]).
+    pub fn update(
+        &mut self,
+        participant_id: &str,
+        pos: Vec3,
+        radius: f32,
+    ) -> Option<InterestSubjects> {
+        let (min_x, min_y) = subjects::cell_of(pos.x - radius, pos.y - radius, self.cell_size);
+        let (max_x, max_y) = subjects::cell_of(pos.x + radius, pos.y + radius, self.cell_size);
+        let window: BTreeSet<(i32, i32)> = (min_x..=max_x)
+            .flat_map(|cx| (min_y..=max_y).map(move |cy| (cx, cy)))
+            .collect();
+        let current = self.cells.entry(participant_id.to_string()).or_default();
+        if *current == window {
+            return None;
+        }
+
+        let subject = |&(cx, cy): &(i32, i32)| subjects::cell(cx, cy, "world.>");
+        let subscribe = window.difference(current).map(subject).collect();
+        let unsubscribe = current.difference(&window).map(subject).collect();
+        *current = window;
+        Some(InterestSubjects {
+            participant_id: participant_id.to_string(),
+            cell_size: self.cell_size,
+            subscribe,
+            unsubscribe,
+        })
+    }
+
+    /// Forget a participant (it left; nothing needs to be unsubscribed).
+    pub fn remove(&mut self, participant_id: &str) {
+        self.cells.remove(participant_id);
+    }
+
+    /// `true` if `participant_id` listens to cell `(cx, cy)`.
+    pub fn subscribed(&self, participant_id: &str, cell: (i32, i32)) -> bool {
+        self.cells
+            .get(participant_id)
+            .is_some_and(|cells| cells.contains(&cell))
+    }
+}
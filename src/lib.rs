@@ -9,6 +9,10 @@
 //!   └── WorldService  (service.rs)  ← streaming, cell lifecycle
 //!         └── World  (structure.rs) ← data layer
 //!               ├── HeightmapTerrain  (terrain.rs)
+//!               ├── ImageTerrain      (image_terrain.rs)
+//!               ├── FlatTerrain       (flat_terrain.rs)
+//!               ├── CompositeTerrain  (composite_terrain.rs)
+//!               ├── ElevationTerrain  (elevation_terrain.rs)
 //!               └── StructureRegistry (structure.rs)
 //! ```
 //!
@@ -18,25 +22,109 @@
 
 // Protocol types are always available (no server feature needed).
 pub mod protocol;
+pub mod snapshot_paging;
+pub mod transform_delta;
 pub mod types;
 
 // Server-side modules require the `server` feature.
 #[cfg(feature = "server")]
+pub mod admin;
+#[cfg(feature = "server")]
+pub mod behaviour;
+#[cfg(feature = "server")]
 pub mod bus;
 #[cfg(feature = "server")]
+pub mod chunk_store;
+#[cfg(feature = "server")]
+pub mod chunk_workers;
+#[cfg(feature = "server")]
+pub mod codec;
+#[cfg(feature = "server")]
+pub mod collision;
+#[cfg(feature = "server")]
+pub mod composite_terrain;
+#[cfg(feature = "server")]
+pub mod console;
+#[cfg(feature = "server")]
+pub mod elevation_terrain;
+#[cfg(feature = "server")]
+pub mod entity;
+#[cfg(feature = "server")]
+pub mod environment;
+#[cfg(feature = "server")]
+pub mod erosion;
+#[cfg(feature = "server")]
+pub mod failover;
+#[cfg(feature = "server")]
+pub mod flat_terrain;
+#[cfg(feature = "server")]
+pub mod height_overrides;
+#[cfg(feature = "server")]
+pub mod heightmap_export;
+#[cfg(feature = "server")]
+pub mod hibernation;
+#[cfg(feature = "server")]
+pub mod hydrology;
+#[cfg(feature = "server")]
+pub mod image_terrain;
+#[cfg(feature = "server")]
+pub mod interaction;
+#[cfg(feature = "server")]
+pub mod interest;
+#[cfg(feature = "server")]
+pub mod metrics;
+#[cfg(feature = "server")]
+pub mod persistence;
+#[cfg(feature = "server")]
+pub mod population;
+#[cfg(feature = "server")]
+pub mod proximity;
+#[cfg(feature = "server")]
+pub mod scene_import;
+#[cfg(feature = "server")]
+pub mod scheduler;
+#[cfg(feature = "server")]
 pub mod service;
 #[cfg(feature = "server")]
+pub mod shard;
+#[cfg(feature = "server")]
+pub mod shoreline;
+#[cfg(feature = "server")]
+pub mod snapshot_cache;
+#[cfg(feature = "server")]
+pub mod spatial;
+#[cfg(feature = "server")]
+pub mod spawner;
+#[cfg(feature = "server")]
 pub mod structure;
 #[cfg(feature = "server")]
 pub mod terrain;
+#[cfg(feature = "server")]
+pub mod validation;
+#[cfg(feature = "server")]
+pub mod vegetation;
+#[cfg(feature = "server")]
+pub mod voxel;
+#[cfg(feature = "server")]
+pub mod worldgen;
 
 // Convenience re-exports (server only)
 #[cfg(feature = "server")]
 pub use bus::{WorldBusAgent, WorldBusConfig};
 #[cfg(feature = "server")]
+pub use composite_terrain::{CompositeTerrain, LayerBlend, TerrainLayer};
+#[cfg(feature = "server")]
+pub use elevation_terrain::{ElevationConfig, ElevationTerrain, ElevationTile, GeoCrs};
+#[cfg(feature = "server")]
+pub use flat_terrain::FlatTerrain;
+#[cfg(feature = "server")]
+pub use image_terrain::{ImageTerrain, ImageTerrainConfig};
+#[cfg(feature = "server")]
 pub use service::WorldService;
 #[cfg(feature = "server")]
-pub use structure::{StructureInstance, StructureRegistry, World};
+pub use structure::{StructureCatalog, StructureInstance, StructureRegistry, World};
 #[cfg(feature = "server")]
-pub use terrain::{HeightChunk, HeightmapTerrain, TerrainSource};
+pub use terrain::{ChunkDescriptor, HeightChunk, HeightmapTerrain, Interpolation, TerrainSource};
 pub use types::{CellCoord, Vec3, WorldObject, WorldServiceConfig, WorldStats};
+#[cfg(feature = "server")]
+pub use worldgen::WorldGenConfig;
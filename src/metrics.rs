@@ -0,0 +1,244 @@
+//! Rolling per-tick event fan-out metrics and dropped-intent counters.
+//!
+//! [`FanoutMetrics`] records how many events of each kind every tick
+//! produced and keeps the last [`FANOUT_WINDOW_TICKS`] counts per kind;
+//! [`WorldService::stats`](crate::service::WorldService::stats) summarises
+//! them into [`FanoutStats`].  Recording is a handful of array writes, so it
+//! runs on every tick.
+//!
+//! [`DropCounters`] counts intents and commands that were rejected, by
+//! reason, kind and sender ([`DropStats`]), and logs a sample of them.
+
+use crate::service::TickEvents;
+use crate::types::{DropReason, DropStats, FanoutStats, HistogramSummary};
+use log::warn;
+use std::collections::HashMap;
+
+/// Ticks kept per histogram (10 s at 30 Hz).
+pub const FANOUT_WINDOW_TICKS: usize = 300;
+
+/// Ring buffer of the most recent per-tick counts.
+#[derive(Debug, Clone)]
+pub struct RollingHistogram {
+    samples: Vec<u32>,
+    /// Next slot to overwrite once the buffer is full.
+    next: usize,
+    total: u64,
+}
+
+impl RollingHistogram {
+    pub fn new(window: usize) -> Self {
+        Self {
+            samples: Vec::with_capacity(window.max(1)),
+            next: 0,
+            total: 0,
+        }
+    }
+
+    pub fn record(&mut self, count: u32) {
+        if self.samples.len() < self.samples.capacity() {
+            self.samples.push(count);
+        } else {
+            self.samples[self.next] = count;
+        }
+        self.next = (self.next + 1) % self.samples.capacity();
+        self.total += count as u64;
+    }
+
+    /// Samples currently in the window.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn summary(&self) -> HistogramSummary {
+        if self.samples.is_empty() {
+            return HistogramSummary::default();
+        }
+        let last = self.next.checked_sub(1).unwrap_or(self.samples.len() - 1);
+        let mut sorted = self.samples.clone();
+        sorted.sort_unstable();
+        // Nearest-rank percentile.
+        let rank =
+            |p: f32| sorted[((p * sorted.len() as f32).ceil() as usize).clamp(1, sorted.len()) - 1];
+        HistogramSummary {
+            last: self.samples[last],
+            mean: sorted.iter().map(|&c| c as f32).sum::<f32>() / sorted.len() as f32,
+            p50: rank(0.50),
+            p95: rank(0.95),
+            max: *sorted.last().unwrap(),
+            total: self.total,
+        }
+    }
+}
+
+/// One histogram per event kind in [`TickEvents`].
+#[derive(Debug, Clone)]
+pub struct FanoutMetrics {
+    activated: RollingHistogram,
+    deactivated: RollingHistogram,
+    chunk_voxels: RollingHistogram,
+    chunk_data: RollingHistogram,
+    transforms: RollingHistogram,
+    spawns: RollingHistogram,
+    environment: RollingHistogram,
+    proximity: RollingHistogram,
+    terrain_modified: RollingHistogram,
+    nav_invalidated: RollingHistogram,
+    emotes: RollingHistogram,
+    collisions: RollingHistogram,
+    entity_meta: RollingHistogram,
+    structure_interest: RollingHistogram,
+    handoffs: RollingHistogram,
+    total: RollingHistogram,
+}
+
+impl Default for FanoutMetrics {
+    fn default() -> Self {
+        Self::new(FANOUT_WINDOW_TICKS)
+    }
+}
+
+impl FanoutMetrics {
+    pub fn new(window: usize) -> Self {
+        let h = || RollingHistogram::new(window);
+        Self {
+            activated: h(),
+            deactivated: h(),
+            chunk_voxels: h(),
+            chunk_data: h(),
+            transforms: h(),
+            spawns: h(),
+            environment: h(),
+            proximity: h(),
+            terrain_modified: h(),
+            nav_invalidated: h(),
+            emotes: h(),
+            collisions: h(),
+            entity_meta: h(),
+            structure_interest: h(),
+            handoffs: h(),
+            total: h(),
+        }
+    }
+
+    /// Count one tick's events.
+    pub fn record(&mut self, events: &TickEvents) {
+        let counts = [
+            (&mut self.activated, events.activated.len()),
+            (&mut self.deactivated, events.deactivated.len()),
+            (&mut self.chunk_voxels, events.chunk_voxels.len()),
+            (&mut self.chunk_data, events.chunk_data.len()),
+            (
+                &mut self.transforms,
+                events.entity_transforms.len()
+                    + events.transform_batch.transforms.len()
+                    + events.transform_batch.deltas.len(),
+            ),
+            (&mut self.spawns, events.entity_handles.len()),
+            (&mut self.environment, events.environment.is_some() as usize),
+            (
+                &mut self.proximity,
+                events.proximity_entered.len() + events.proximity_exited.len(),
+            ),
+            (&mut self.terrain_modified, events.terrain_modified.len()),
+            (&mut self.nav_invalidated, events.nav_invalidated.len()),
+            (&mut self.emotes, events.emotes.len()),
+            (&mut self.collisions, events.collisions.len()),
+            (&mut self.entity_meta, events.entity_meta.len()),
+            (
+                &mut self.structure_interest,
+                events.structure_interest.len(),
+            ),
+            (&mut self.handoffs, events.handoffs.len()),
+        ];
+        let mut total = 0;
+        for (histogram, count) in counts {
+            histogram.record(count as u32);
+            total += count;
+        }
+        self.total.record(total as u32);
+    }
+
+    pub fn summary(&self) -> FanoutStats {
+        FanoutStats {
+            window_ticks: self.total.len(),
+            activated: self.activated.summary(),
+            deactivated: self.deactivated.summary(),
+            chunk_voxels: self.chunk_voxels.summary(),
+            chunk_data: self.chunk_data.summary(),
+            transforms: self.transforms.summary(),
+            spawns: self.spawns.summary(),
+            environment: self.environment.summary(),
+            proximity: self.proximity.summary(),
+            terrain_modified: self.terrain_modified.summary(),
+            nav_invalidated: self.nav_invalidated.summary(),
+            emotes: self.emotes.summary(),
+            collisions: self.collisions.summary(),
+            entity_meta: self.entity_meta.summary(),
+            structure_interest: self.structure_interest.summary(),
+            handoffs: self.handoffs.summary(),
+            total: self.total.summary(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Dropped intents
+// ---------------------------------------------------------------------------
+
+/// Senders counted individually before the rest share the `"*"` bucket.
+pub const DROP_TRACKED_PARTICIPANTS: usize = 256;
+
+/// Drops logged per kind and reason: the first, then every this many.
+pub const DROP_LOG_EVERY: u64 = 100;
+
+#[derive(Debug, Clone, Default)]
+pub struct DropCounters {
+    stats: DropStats,
+    /// Drops per (kind, reason), for log sampling.
+    seen: HashMap<(String, DropReason), u64>,
+}
+
+impl DropCounters {
+    /// Count one dropped `kind` intent from `participant`; `detail` goes to
+    /// the log when this drop is sampled.
+    pub fn record(
+        &mut self,
+        kind: &str,
+        participant: Option<&str>,
+        reason: DropReason,
+        detail: &str,
+    ) {
+        let stats = &mut self.stats;
+        stats.total += 1;
+        *stats.by_reason.entry(reason).or_default() += 1;
+        *stats.by_kind.entry(kind.to_string()).or_default() += 1;
+        if let Some(id) = participant {
+            let tracked = stats.by_participant.contains_key(id)
+                || stats.by_participant.len() < DROP_TRACKED_PARTICIPANTS;
+            let key = if tracked { id } else { "*" };
+            *stats.by_participant.entry(key.to_string()).or_default() += 1;
+        }
+
+        let seen = self.seen.entry((kind.to_string(), reason)).or_default();
+        *seen += 1;
+        if (*seen - 1).is_multiple_of(DROP_LOG_EVERY) {
+            warn!(
+                "Dropped {} from {} ({}, {} so far): {}",
+                kind,
+                participant.unwrap_or("?"),
+                reason.as_str(),
+                seen,
+                detail
+            );
+        }
+    }
+
+    pub fn summary(&self) -> DropStats {
+        self.stats.clone()
+    }
+}
@@ -0,0 +1,49 @@
+//! World state that survives a restart.
+//!
+//! [`WorldService::save_state`](crate::service::WorldService::save_state)
+//! writes a [`WorldState`] (the bus agent does so on SIGINT) and
+//! [`load_state`](crate::service::WorldService::load_state) reads it back
+//! into a freshly started service (`WORLD_STATE_FILE` on startup):
+//!
+//! * every structure, as a [`StructureRecord`], replacing the generated or
+//!   imported one with the same id (so moves, state changes and placed
+//!   structures survive);
+//! * server entities, respawned from their current transform (entities of
+//!   spawners and ambient population are left to those to refill);
+//! * runtime terrain edits, replayed in `revision` order;
+//! * where each participant was last seen, which a fresh join under the
+//!   same id spawns at instead of its requested position.
+//!
+//! Designer height overrides persist in their own directory and terrain
+//! regenerates from the seed, so neither is part of the state.
+
+use crate::entity::EntitySpec;
+use crate::protocol::TerrainModified;
+use crate::structure::StructureRecord;
+use crate::types::Vec3;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Version written by `WorldService::save_state`.
+pub const STATE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldState {
+    pub version: u32,
+    /// Service tick it was saved at.
+    pub frame: u64,
+    /// [`TERRAIN_VERSION`](crate::protocol::TERRAIN_VERSION) of the saving
+    /// server; terrain edits only replay faithfully on the same one.
+    pub terrain_version: u32,
+    /// Sorted by id.
+    #[serde(default)]
+    pub structures: Vec<StructureRecord>,
+    /// Sorted by id.
+    #[serde(default)]
+    pub entities: Vec<EntitySpec>,
+    #[serde(default)]
+    pub terrain_modifications: Vec<TerrainModified>,
+    /// Last-known position of each participant, by id.
+    #[serde(default)]
+    pub participants: BTreeMap<String, Vec3>,
+}
@@ -0,0 +1,185 @@
+//! Ambient entity population: wildlife and other background entities kept
+//! stocked in the active region according to per-biome
+//! [`PopulationRule`]s.
+//!
+//! Every `population_interval_s` the service hands [`AmbientPopulation`]
+//! the active chunk columns with the biome at their centre.  For each rule
+//! the target count is `per_km2` times the active area of the rule's
+//! biomes; entities are spawned at random spots of those columns until the
+//! rounded target is reached and removed (newest first) once the count
+//! exceeds its ceiling, so a target hovering around a fraction doesn't
+//! flap.  Entities whose column left the active region, or whose rule is
+//! outside its `hours`, are removed.
+//!
+//! Ambient entities are tracked like participants (transforms, handles,
+//! proximity) but don't stream terrain, so they never widen the region
+//! that keeps them alive.
+
+use crate::types::PopulationStats;
+use crate::worldgen::{unit, PopulationRule};
+use std::collections::{BTreeMap, HashMap};
+
+/// An active chunk column and the biome at its centre.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PopulationCell {
+    pub cx: i32,
+    pub cy: i32,
+    pub biome: String,
+}
+
+/// An entity to start tracking; the service places it on the ground.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmbientSpawn {
+    pub entity_id: String,
+    pub archetype: String,
+    pub biome: String,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Result of one [`AmbientPopulation::evaluate`] pass.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PopulationChange {
+    pub spawned: Vec<AmbientSpawn>,
+    /// Ids to stop tracking.
+    pub despawned: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Ambient {
+    rule: usize,
+    /// Spawn order, for newest-first removal.
+    serial: u64,
+    biome: String,
+    column: (i32, i32),
+}
+
+#[derive(Debug, Default)]
+pub struct AmbientPopulation {
+    rules: Vec<PopulationRule>,
+    cell_size: f32,
+    seed: u64,
+    entities: HashMap<String, Ambient>,
+    next_serial: u64,
+}
+
+impl AmbientPopulation {
+    pub fn new(rules: Vec<PopulationRule>, cell_size: f32, seed: u64) -> Self {
+        Self {
+            rules,
+            cell_size,
+            seed,
+            ..Self::default()
+        }
+    }
+
+    pub fn rules(&self) -> &[PopulationRule] {
+        &self.rules
+    }
+
+    /// Ids of every ambient entity (unordered).
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.entities.keys().map(String::as_str)
+    }
+
+    pub fn contains(&self, entity_id: &str) -> bool {
+        self.entities.contains_key(entity_id)
+    }
+
+    /// Archetype of an ambient entity.
+    pub fn archetype(&self, entity_id: &str) -> Option<&str> {
+        let e = self.entities.get(entity_id)?;
+        Some(self.rules[e.rule].archetype.as_str())
+    }
+
+    /// Forget an entity removed by something else (kick, handoff).
+    pub fn remove(&mut self, entity_id: &str) {
+        self.entities.remove(entity_id);
+    }
+
+    /// Bring every rule towards its target over `cells` at world-clock
+    /// `hour` (`0..24`).
+    pub fn evaluate(&mut self, cells: &[PopulationCell], hour: f32) -> PopulationChange {
+        let Self {
+            rules,
+            cell_size,
+            seed,
+            entities,
+            next_serial,
+        } = self;
+        let mut change = PopulationChange::default();
+        let km2_per_cell = (*cell_size as f64 * *cell_size as f64) / 1e6;
+
+        for (index, rule) in rules.iter().enumerate() {
+            let habitat: Vec<&PopulationCell> = if rule.active_at(hour) {
+                cells.iter().filter(|c| rule.allows(&c.biome)).collect()
+            } else {
+                Vec::new()
+            };
+
+            // Entities whose column is no longer habitat go first.
+            let mut alive: Vec<(u64, String)> = Vec::new();
+            let mut gone: Vec<String> = Vec::new();
+            for (id, e) in entities.iter().filter(|(_, e)| e.rule == index) {
+                if habitat.iter().any(|c| (c.cx, c.cy) == e.column) {
+                    alive.push((e.serial, id.clone()));
+                } else {
+                    gone.push(id.clone());
+                }
+            }
+
+            let target = rule.per_km2 as f64 * km2_per_cell * habitat.len() as f64;
+            let (low, high) = (target.round() as usize, target.ceil() as usize);
+            if alive.len() > high {
+                alive.sort_unstable();
+                gone.extend(alive.drain(high..).map(|(_, id)| id));
+            }
+            gone.sort_unstable();
+            for id in &gone {
+                entities.remove(id);
+            }
+            change.despawned.append(&mut gone);
+
+            for _ in alive.len()..low {
+                let serial = *next_serial;
+                *next_serial += 1;
+                let key = format!("{}:{}:{}", seed, rule.archetype, serial);
+                let cell = habitat[(unit(&key, 0) * habitat.len() as f32) as usize % habitat.len()];
+                let spawn = AmbientSpawn {
+                    entity_id: format!("ambient:{}:{}", rule.archetype, serial),
+                    archetype: rule.archetype.clone(),
+                    biome: cell.biome.clone(),
+                    x: (cell.cx as f32 + unit(&key, 1)) * *cell_size,
+                    y: (cell.cy as f32 + unit(&key, 2)) * *cell_size,
+                };
+                entities.insert(
+                    spawn.entity_id.clone(),
+                    Ambient {
+                        rule: index,
+                        serial,
+                        biome: cell.biome.clone(),
+                        column: (cell.cx, cell.cy),
+                    },
+                );
+                change.spawned.push(spawn);
+            }
+        }
+        change
+    }
+
+    pub fn stats(&self) -> PopulationStats {
+        let mut by_biome = BTreeMap::new();
+        let mut by_archetype = BTreeMap::new();
+        for e in self.entities.values() {
+            *by_biome.entry(e.biome.clone()).or_default() += 1;
+            *by_archetype
+                .entry(self.rules[e.rule].archetype.clone())
+                .or_default() += 1;
+        }
+        PopulationStats {
+            total: self.entities.len(),
+            by_biome,
+            by_archetype,
+        }
+    }
+}
@@ -18,17 +18,102 @@
 //! 3. Terrain is **never** sent as raw height arrays — only `(cx, cy, seed, lod)`.
 //! 4. Every outbound event includes `frame: u64` and `session: String`.
 //! 5. Transforms include `dt: f32` to support client-side interpolation.
+//!
+//! ## Versioning
+//!
+//! Clients advertise the highest protocol version they understand (field
+//! `protocol_version` on `world.cmd.*` requests, default `1`).  The server
+//! only uses encodings the client has opted into.
+//!
+//! | Version | Adds                                                     |
+//! |---------|----------------------------------------------------------|
+//! | 1       | Baseline protocol                                        |
+//! | 2       | [`CompactWorldSnapshot`] (string-table snapshot replies) |
+//! | 3       | [`EntityHandle`] / [`EntityTransformBatch`] transforms   |
+//! | 4       | Delta-encoded batches ([`TransformDelta`], keyframes)    |
+//!
+//! Transforms are broadcast, so version 3 transforms are a server-wide
+//! choice (`handle_transforms`) rather than per request: only enable it once
+//! every client in the session speaks version 3.  The same goes for version
+//! 4 delta encoding (`transform_keyframe_interval`).
 
+use crate::types::{EdgeFill, HydrologyConfig, MaterialRules, Vec3};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Highest protocol version this server speaks.
+pub const PROTOCOL_VERSION: u32 = 4;
+
+/// First protocol version that accepts [`CompactWorldSnapshot`] replies.
+pub const COMPACT_SNAPSHOT_VERSION: u32 = 2;
+
+/// First protocol version that understands handle-addressed transforms.
+pub const ENTITY_HANDLE_VERSION: u32 = 3;
+
+/// First protocol version that understands delta-encoded transform batches.
+pub const TRANSFORM_DELTA_VERSION: u32 = 4;
+
+/// Unit of every [`TransformDelta`] field: metres for positions, radians
+/// for rotation, metres per second for velocities.
+pub const TRANSFORM_DELTA_QUANTUM: f32 = 0.001;
+
+/// Version of the procedural terrain clients rebuild from `(seed, cx, cy)`.
+///
+/// Bump it with any change to the generated heights.  Clients compare it
+/// with the version they implement and report a mismatch instead of
+/// meshing geometry that no longer matches the server's colliders.
+///
+/// Version 2 moved the value noise to fixed-point arithmetic
+/// (`terrain::value_noise`).
+pub const TERRAIN_VERSION: u32 = 2;
+
+/// `ChunkActivated::terrain_algo_version` of this server.
+pub const TERRAIN_ALGO_VERSION: &str = "md5_value_noise_q20_v2";
+
+fn default_protocol_version() -> u32 {
+    1
+}
 
 fn default_tile_resolution() -> f32 {
     2.0
 }
 
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn is_zero(v: &i32) -> bool {
+    *v == 0
+}
+
+pub(crate) fn is_zero_angle(v: &f32) -> bool {
+    *v == 0.0
+}
+
+/// Per-axis scale of an unscaled structure.
+pub const UNIT_SCALE: [f32; 3] = [1.0, 1.0, 1.0];
+
+pub(crate) fn unit_scale() -> [f32; 3] {
+    UNIT_SCALE
+}
+
+pub(crate) fn is_unit_scale(v: &[f32; 3]) -> bool {
+    *v == UNIT_SCALE
+}
+
+fn is_false(v: &bool) -> bool {
+    !*v
+}
+
 fn default_terrain_algo_version() -> String {
     "md5_value_noise_v1".to_string()
 }
 
+fn default_terrain_version() -> u32 {
+    1
+}
+
 // ---------------------------------------------------------------------------
 // Common envelope
 // ---------------------------------------------------------------------------
@@ -62,13 +147,20 @@ impl<T> WorldEvent<T> {
 ///
 /// Client generates terrain locally using `seed` and the chunk coordinate —
 /// raw height data is **never** sent over the wire.
+///
+/// **Ordering guarantee:** within a single frame, activations are published
+/// in ascending `priority` (ties broken by `cx`, then `cy`), and snapshot
+/// `active_chunks` use the same order.  Clients that mesh in arrival order
+/// therefore build the nearest chunks first.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChunkActivated {
     /// Globally unique chunk identifier (deterministic: `{session}:{cx}:{cy}`).
     pub chunk_id: String,
     pub cx: i32,
     pub cy: i32,
-    /// Terrain seed — sufficient for deterministic local generation.
+    /// Terrain seed — sufficient for deterministic local generation.  This
+    /// is the chunk's effective seed, which differs from the world seed
+    /// inside a seed region.
     pub seed: u64,
     /// Canonical terrain seed (explicit field for cross-language alignment).
     pub terrain_seed: u64,
@@ -78,10 +170,162 @@ pub struct ChunkActivated {
     /// Terrain algorithm version, used to detect cross-client divergence.
     #[serde(default = "default_terrain_algo_version")]
     pub terrain_algo_version: String,
-    /// 0 = full detail, 1 = half, 2 = quarter.
+    /// [`TERRAIN_VERSION`] of the server (payloads without it are version 1).
+    #[serde(default = "default_terrain_version")]
+    pub terrain_version: u32,
+    /// 0 = full detail, 1 = half, 2 = quarter.  Picked from the distance
+    /// to the nearest participant; when that changes band the chunk is
+    /// announced again with the new `lod` (replace the existing mesh).
     pub lod: u8,
     /// World-space size of one chunk side.
     pub chunk_size: f32,
+    /// Scheduling hint: Chebyshev distance in cells to the nearest
+    /// participant (`0` = the participant's own cell).  Lower is more urgent.
+    #[serde(default)]
+    pub priority: u32,
+    /// River/lake parameters when the terrain has hydrology: lakes are flat
+    /// planes at `water_table`; rivers are reproduced from the rest.
+    #[serde(default)]
+    pub hydrology: Option<HydrologyConfig>,
+    /// Splat rules for texturing: per-vertex weights come from
+    /// `MaterialRules::weights(height, slope)`.
+    #[serde(default)]
+    pub materials: MaterialRules,
+    /// Coastline segments `[x0, y0, x1, y1]` (world units) where the terrain
+    /// crosses the sea level; empty without a sea or away from the coast.
+    #[serde(default)]
+    pub shoreline: Vec<[f32; 4]>,
+    /// Cells cut out of the heightfield (cave entrances, basements);
+    /// `None` when the chunk has none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holes: Option<ChunkHoles>,
+    /// FNV-1a 64 of the server's heights for this chunk at `lod` (see
+    /// `terrain::heights_checksum`), terrain edits included.  Clients that
+    /// generate locally hash their own heights the same way and report a
+    /// mismatch.  `None` for backends clients can't regenerate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<u64>,
+    /// Set on the ring of chunks just outside finite world bounds: draw the
+    /// fill instead of (or over) terrain.  The server blocks the whole
+    /// chunk with a barrier collider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edge: Option<ChunkEdge>,
+}
+
+/// World-edge rendering hints for a chunk outside the bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChunkEdge {
+    pub fill: EdgeFill,
+    /// Height of the ocean surface or the cliff top.
+    pub height: f32,
+    /// Unit step `[dx, dy]` (each `-1`, `0` or `1`) towards the playable
+    /// world; both non-zero on corner chunks.  Cliff faces point this way.
+    pub inward: [i32; 2],
+}
+
+/// Heightfield cells cut out of one chunk.
+///
+/// Cell `[i, j]` is the square starting at `(cx * chunk_size + i *
+/// cell_size, cy * chunk_size + j * cell_size)` with side `cell_size` (the
+/// LOD 0 sample spacing).  Clients drop the mesh triangles over it; the
+/// server has no ground there for raycasts and solidity queries, so the
+/// opening matches what players can fall or shoot into.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkHoles {
+    pub cell_size: f32,
+    /// `[i, j]` cell indices, sorted.
+    pub cells: Vec<[u32; 2]>,
+}
+
+/// Raw heights for a chunk clients can't regenerate from its seed
+/// (`world.chunk.data`), sent right after its `ChunkActivated` when the
+/// server runs with `send_chunk_heights` — image, elevation or composite
+/// terrain, for example.
+///
+/// The grid is `resolution × resolution` samples, row-major, starting at
+/// `(cx * chunk_size, cy * chunk_size)` with `cell_size` spacing.  `data` is
+/// the heights as little-endian `f32`s, gzip-compressed and base64-encoded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkData {
+    pub chunk_id: String,
+    pub cx: i32,
+    pub cy: i32,
+    pub lod: u8,
+    pub resolution: u32,
+    pub cell_size: f32,
+    pub data: String,
+}
+
+/// Sparse voxel overrides for one chunk (`world.chunk.voxels`), sent right
+/// after its `ChunkActivated` when the chunk has any.
+///
+/// The grid starts at `(cx * chunk_size, cy * chunk_size, origin_z)` and has
+/// `dims = [nx, ny, nz]` cubes of `voxel_size`, indexed
+/// `(iz * ny + iy) * nx + ix`.  Cell values: [`VOXEL_INHERIT`] leaves the
+/// heightmap in charge, [`VOXEL_AIR`] carves (caves), anything higher is
+/// solid with that material id (overhangs).  Clients without voxel support
+/// can ignore the message and keep rendering the plain heightmap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkVoxels {
+    pub chunk_id: String,
+    pub cx: i32,
+    pub cy: i32,
+    pub origin_z: f32,
+    pub voxel_size: f32,
+    pub dims: [u32; 3],
+    pub cells: VoxelCells,
+}
+
+/// Voxel cell value: the heightmap decides.
+pub const VOXEL_INHERIT: u8 = 0;
+/// Voxel cell value: empty, even below the heightmap surface.
+pub const VOXEL_AIR: u8 = 1;
+
+/// Voxel cell payload, dense or run-length encoded (whichever is smaller).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "encoding", content = "data", rename_all = "snake_case")]
+pub enum VoxelCells {
+    Dense(Vec<u8>),
+    /// `[value, run_length]` pairs in index order.
+    Rle(Vec<[u32; 2]>),
+}
+
+impl VoxelCells {
+    /// Encode `cells`, choosing RLE when it is smaller than the dense form.
+    pub fn encode(cells: &[u8]) -> Self {
+        let mut runs: Vec<[u32; 2]> = Vec::new();
+        for &c in cells {
+            match runs.last_mut() {
+                Some([value, len]) if *value == c as u32 => *len += 1,
+                _ => runs.push([c as u32, 1]),
+            }
+        }
+        // Rough JSON cost: a run is about four times a dense cell.
+        if runs.len() * 4 < cells.len() {
+            VoxelCells::Rle(runs)
+        } else {
+            VoxelCells::Dense(cells.to_vec())
+        }
+    }
+
+    /// Dense cells, or `None` if the payload doesn't hold exactly `len`.
+    pub fn decode(&self, len: usize) -> Option<Vec<u8>> {
+        let cells = match self {
+            VoxelCells::Dense(cells) => cells.clone(),
+            VoxelCells::Rle(runs) => {
+                let mut cells = Vec::with_capacity(len);
+                for &[value, run] in runs {
+                    let value = u8::try_from(value).ok()?;
+                    if cells.len() + run as usize > len {
+                        return None;
+                    }
+                    cells.extend(std::iter::repeat_n(value, run as usize));
+                }
+                cells
+            }
+        };
+        (cells.len() == len).then_some(cells)
+    }
 }
 
 /// Server instructs client to free a chunk.
@@ -90,12 +334,83 @@ pub struct ChunkDeactivated {
     pub chunk_id: String,
 }
 
+// ---------------------------------------------------------------------------
+// Terrain edits  (subject: world.terrain.modified)
+// ---------------------------------------------------------------------------
+
+/// Runtime terrain deformation (crater, trench, flattened build site…).
+///
+/// Clients keep generating base terrain from the seed and replay every
+/// modification in `revision` order on top of it, using the same falloff as
+/// the server: at distance `d < radius` from the centre the height changes
+/// by `delta * smoothstep(1 - d / radius)`.  Chunks listed in `chunk_ids`
+/// should be re-meshed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerrainModified {
+    /// Monotonic per-world edit counter, starting at 1.
+    pub revision: u64,
+    pub center_x: f32,
+    pub center_y: f32,
+    pub radius: f32,
+    /// Peak height change at the centre (negative digs, positive raises).
+    pub delta: f32,
+    /// `"cx:cy"` ids of every chunk whose samples changed.
+    pub chunk_ids: Vec<String>,
+    /// The edit cut holes (every cell whose centre is within `radius`)
+    /// instead of moving the surface; `delta` is `0`.  Active chunks in
+    /// `chunk_ids` are announced again with their new
+    /// [`ChunkActivated::holes`].
+    #[serde(default)]
+    pub hole: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Navigation  (subject: world.nav.invalidated)
+// ---------------------------------------------------------------------------
+
+/// What changed the walkable geometry behind a [`NavInvalidated`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NavChangeCause {
+    TerrainDeformed,
+    TerrainHole,
+    StructurePlaced,
+    StructureRemoved,
+}
+
+/// Path data over an area is stale.
+///
+/// Agents following a path that crosses the rectangle should re-plan;
+/// clients with their own navigation (a Godot navmesh) rebake the tiles
+/// it overlaps.  The rectangle is the world-space bounding box of the
+/// change, `chunk_ids` the chunks it touches.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NavInvalidated {
+    /// Monotonic per-world counter, starting at 1.
+    pub revision: u64,
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+    pub chunk_ids: Vec<String>,
+    pub cause: NavChangeCause,
+}
+
 // ---------------------------------------------------------------------------
 // Structure events  (subjects: world.structure.*)
 // ---------------------------------------------------------------------------
 
 /// A static structure appeared in the world (building, rock, obstacle…).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Orientation is yaw (`rotation_y`, about the vertical), then `pitch`
+/// (about the structure's own sideways axis), then `roll` (about its
+/// forward axis), in radians.  `pitch`, `roll` and `scale` are omitted
+/// when they're the identity, so yaw-only payloads are unchanged and older
+/// senders read as yaw-only.
+///
+/// Interactive structures (doors, gates…) carry their current `state`;
+/// later changes arrive as [`StructureStateChanged`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StructureSpawned {
     pub structure_id: String,
     /// Asset / scene path the client uses to instantiate.
@@ -104,22 +419,154 @@ pub struct StructureSpawned {
     pub y: f32,
     pub z: f32,
     pub rotation_y: f32,
+    #[serde(default, skip_serializing_if = "is_zero_angle")]
+    pub pitch: f32,
+    #[serde(default, skip_serializing_if = "is_zero_angle")]
+    pub roll: f32,
+    /// Scale along the structure's own axes, before rotation.
+    #[serde(default = "unit_scale", skip_serializing_if = "is_unit_scale")]
+    pub scale: [f32; 3],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
     #[serde(default)]
     pub metadata: serde_json::Value,
 }
 
+/// A static structure's transform or metadata changed
+/// (`world.structure.updated`), e.g. an operator nudged a prop.
+///
+/// Carries the structure's whole current state in the [`StructureSpawned`]
+/// layout.  Clients replace their cached copy, and ignore updates for
+/// structures they don't have.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructureUpdated {
+    #[serde(flatten)]
+    pub structure: StructureSpawned,
+}
+
+/// Changes to a structure (see [`AdminAction::UpdateStructure`]).  Omitted
+/// fields keep their value; `metadata` keys are merged into the existing
+/// metadata, a `null` value removing the key.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructureUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub y: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation_y: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pitch: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roll: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<[f32; 3]>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+}
+
 /// A static structure was removed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructureRemoved {
     pub structure_id: String,
 }
 
+/// An interactive structure moved to another state
+/// (`world.structure.state_changed`), e.g. a door from `closed` to `open`.
+/// Its collider has already been swapped for the new state's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructureStateChanged {
+    pub structure_id: String,
+    pub previous: String,
+    pub state: String,
+    /// Interaction verb that caused the change.
+    pub verb: String,
+    /// Entity that interacted with it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor_id: Option<String>,
+}
+
+/// What an `intent.interact` was aimed at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionTarget {
+    Structure,
+    /// A participant or server entity.
+    Entity,
+}
+
+/// Outcome of a participant's `intent.interact`
+/// (`world.interaction.result`), so its client can show success or
+/// failure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InteractionResult {
+    pub actor_id: String,
+    pub target_id: String,
+    /// `None` when no such target exists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_kind: Option<InteractionTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verb: Option<String>,
+    pub success: bool,
+    /// Why it failed, for display.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// What the handler returned (a [`StructureStateChanged`] for doors
+    /// and gates).
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub data: serde_json::Value,
+}
+
+/// Cell-scoped subjects a participant's client should subscribe to
+/// (`world.interest.subjects`), sent when they change.
+///
+/// With `cell_subjects` on, entity transforms and spawns and structure
+/// updates are published on the subject of the interest cell they happen
+/// in ([`subjects::cell`]) instead of world-wide.  Each subject here is a
+/// wildcard covering every event of one cell, in the session's namespace.
+/// Clients act only on messages whose `participant_id` is their own.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InterestSubjects {
+    pub participant_id: String,
+    /// Side of an interest cell in metres.
+    pub cell_size: f32,
+    #[serde(default)]
+    pub subscribe: Vec<String>,
+    #[serde(default)]
+    pub unsubscribe: Vec<String>,
+}
+
+/// Structures entering and leaving one participant's interest scope
+/// (`world.structure.interest`).
+///
+/// A participant's scope is every structure overlapping its streaming
+/// window (`activation_radius` cells around it, or fewer for small props
+/// on a `near` / `detail` streaming tier).  The contract:
+///
+/// * Clients act only on messages whose `participant_id` is their own.
+/// * `loaded` structures are upserted into the cache; `unloaded` ids are
+///   evicted — clients don't garbage-collect structures by distance.
+/// * Every structure the server loads for a participant is unloaded for it
+///   once it leaves the scope, so following the messages keeps the cache
+///   exactly equal to the scope.  Nothing is sent on disconnect.
+/// * A structure never appears in both lists of one message.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StructureInterest {
+    pub participant_id: String,
+    #[serde(default)]
+    pub loaded: Vec<StructureSpawned>,
+    #[serde(default)]
+    pub unloaded: Vec<String>,
+}
+
 // ---------------------------------------------------------------------------
 // Entity events  (subjects: world.entity.*)
 // ---------------------------------------------------------------------------
 
 /// An entity (creature, vehicle, projectile…) entered the active region.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EntitySpawned {
     pub entity_id: String,
     /// Game-defined archetype string (e.g. "creature/wolf", "vehicle/cart").
@@ -128,6 +575,13 @@ pub struct EntitySpawned {
     pub y: f32,
     pub z: f32,
     pub rotation_y: f32,
+    /// Uniform scale relative to the archetype's authored size.
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    /// Radius of a circle enclosing the scaled archetype collider, for
+    /// selection and client-side collision (`0` = unknown).
+    #[serde(default)]
+    pub bounding_radius: f32,
     #[serde(default)]
     pub metadata: serde_json::Value,
 }
@@ -144,17 +598,328 @@ pub struct EntityRemoved {
 /// Clients interpolate between received frames at their render rate.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntityTransform {
-    pub entity_id: String,
+    /// Shared with the server's id table so per-tick transforms don't copy
+    /// the string (serialises as a plain string).
+    pub entity_id: Arc<str>,
     pub x: f32,
     pub y: f32,
     pub z: f32,
     pub rotation_y: f32,
-    /// Velocity (m/s) for dead-reckoning / extrapolation.
+    /// Velocity (m/s of simulated time) for dead-reckoning / extrapolation.
     pub vx: f32,
     pub vy: f32,
     pub vz: f32,
-    /// Integration step that produced this transform.
+    /// Integration step that produced this transform, in simulated
+    /// seconds (the tick interval times `WorldHeartbeat::timescale`).
     pub dt: f32,
+    /// Highest movement intent `seq` from this entity's own client that the
+    /// transform already includes.  Predicting clients drop inputs up to
+    /// this number and replay the rest on top of the transform.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_intent_seq: Option<u64>,
+}
+
+/// Reply to `world.participant.join`.
+///
+/// `id` is the entity the participant now controls: its previous one when
+/// the join carried a valid resume token (`resumed`), with the position and
+/// state it had, or the requested id otherwise.  Every join issues a fresh
+/// `resume_token` and retires the one it used; clients keep the latest and
+/// send it when they reconnect.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParticipantJoined {
+    pub id: String,
+    pub resume_token: String,
+    #[serde(default)]
+    pub resumed: bool,
+}
+
+/// Binds a compact numeric handle to an entity id (`world.entity.handle`).
+///
+/// Sent once when the entity is first tracked, before any
+/// [`EntityTransformBatch`] that uses the handle; late joiners get the full
+/// table in [`WorldSnapshot::entity_handles`].  Handles are never reused
+/// while the server runs, so a stale handle can't alias a newer entity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityHandle {
+    pub entity_id: Arc<str>,
+    pub handle: u32,
+}
+
+/// [`EntityTransform`] addressed by handle instead of entity id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandleTransform {
+    pub handle: u32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub rotation_y: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub vz: f32,
+    pub dt: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_intent_seq: Option<u64>,
+}
+
+/// Change of one entity's transform since the previous batch, in units of
+/// [`TRANSFORM_DELTA_QUANTUM`] (protocol version ≥ 4).
+///
+/// Zero fields are omitted, so a resting entity costs only its handle.
+/// `dt` is unchanged from the entity's last full transform.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransformDelta {
+    pub handle: u32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub dx: i32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub dy: i32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub dz: i32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub drot: i32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub dvx: i32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub dvy: i32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub dvz: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_intent_seq: Option<u64>,
+}
+
+/// Every entity transform of one tick in a single message
+/// (`world.entity.transforms`, protocol version ≥ 3).
+///
+/// Transforms whose handle the client hasn't seen bound yet should be
+/// dropped; the next `world.entity.handle` or snapshot fills the gap.
+///
+/// With delta encoding (`transform_keyframe_interval`, version 4) batches
+/// are numbered by `seq`.  A `keyframe` batch carries every transform in
+/// full; in between, entities with a previous transform come as `deltas`
+/// against the position the client reconstructed for them, and only new
+/// entities come in full.  A client that sees a gap in `seq` (a lost
+/// batch, or a restarted server) must drop its baselines and ignore deltas
+/// for an entity until its next full transform.
+/// [`TransformDecoder`](crate::transform_delta::TransformDecoder) implements
+/// the reassembly.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntityTransformBatch {
+    pub transforms: Vec<HandleTransform>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deltas: Vec<TransformDelta>,
+    /// Batch number, from 1 (only with delta encoding).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub keyframe: bool,
+}
+
+impl EntityTransformBatch {
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty() && self.deltas.is_empty()
+    }
+
+    /// Reset to an empty batch, keeping capacity.
+    pub fn clear(&mut self) {
+        self.transforms.clear();
+        self.deltas.clear();
+        self.seq = None;
+        self.keyframe = false;
+    }
+}
+
+impl EntityTransform {
+    /// The same transform addressed by `handle`.
+    pub fn with_handle(&self, handle: u32) -> HandleTransform {
+        HandleTransform {
+            handle,
+            x: self.x,
+            y: self.y,
+            z: self.z,
+            rotation_y: self.rotation_y,
+            vx: self.vx,
+            vy: self.vy,
+            vz: self.vz,
+            dt: self.dt,
+            last_intent_seq: self.last_intent_seq,
+        }
+    }
+}
+
+/// Mutable display data for an entity (nameplates, HUD).
+///
+/// Low frequency: sent on `world.entity.meta` only when a value changes, at
+/// most once per entity per tick.  Every update carries the complete current
+/// value, so clients replace their cached copy rather than merging.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct EntityMeta {
+    pub entity_id: String,
+    /// Display name; `None` hides the nameplate.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Remaining health as a fraction (`0.0..=1.0`); `None` hides the bar.
+    #[serde(default)]
+    pub health: Option<f32>,
+    /// Game-defined status icon keys (e.g. "poisoned", "afk").
+    #[serde(default)]
+    pub status: Vec<String>,
+    /// Set by the server while the participant is idle and its streaming is
+    /// downgraded; cleared by its next intent.  Ignored on input.
+    #[serde(default)]
+    pub afk: bool,
+}
+
+/// A one-shot sound or animation played by an entity (wave, laugh, door
+/// knock…), sent by game logic through `world.cmd.emote`.
+///
+/// Published on `world.entity.emote` once per emote.  Only participants
+/// within hearing range of `(x, y, z)` are listed in `listeners`; clients
+/// act only on messages that list their own id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityEmote {
+    pub entity_id: String,
+    /// Animation to play on the entity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emote_id: Option<String>,
+    /// Sound to play at the position.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sound_id: Option<String>,
+    /// Where the entity was when it emoted.
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    /// Participants within hearing range (sorted, the emitter included).
+    #[serde(default)]
+    pub listeners: Vec<String>,
+}
+
+/// A tracked entity started touching another one, or a static body, in the
+/// physics simulation (see [`collision`](crate::collision)).
+///
+/// Published on `world.entity.collision` once per contact, scoped to the
+/// interest cell of the contact point.  Two entities are unordered, with
+/// `entity_a` the lexically smaller id; against a static body `entity_a` is
+/// the entity and `entity_b` the body id (e.g. `structure.hut`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityCollision {
+    pub entity_a: String,
+    pub entity_b: String,
+    /// Impulse the solver applied to separate the two (`0` when they
+    /// merely touched).
+    pub impulse: f32,
+    /// Contact point, at `entity_a`'s height.
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+// ---------------------------------------------------------------------------
+// Proximity events  (subjects: world.proximity.*)
+// ---------------------------------------------------------------------------
+
+/// Two participants came within the configured proximity radius.
+///
+/// Pairs are unordered; `participant_a` is always the lexically smaller id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProximityEntered {
+    pub participant_a: String,
+    pub participant_b: String,
+    /// Gap between the participants' bounding circles when the event fired
+    /// (centre distance minus both bounding radii).
+    pub distance: f32,
+}
+
+/// Two participants moved apart (or one of them left the world).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProximityExited {
+    pub participant_a: String,
+    pub participant_b: String,
+}
+
+// ---------------------------------------------------------------------------
+// Environment  (subject: world.environment)
+// ---------------------------------------------------------------------------
+
+/// Weather conditions owned by the server.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Weather {
+    #[default]
+    Clear,
+    Overcast,
+    Fog,
+    Rain,
+    Storm,
+}
+
+/// Server-owned atmosphere state so every client renders the same sky.
+///
+/// Published at low frequency (and immediately on change).  Clients should
+/// keep only the latest value and interpolate lighting locally between
+/// updates using `time_of_day_s` / `day_length_s`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldEnvironment {
+    /// Seconds since midnight of the current in-game day.
+    pub time_of_day_s: f32,
+    /// Length of a full in-game day in seconds.
+    pub day_length_s: f32,
+    /// Sun elevation in degrees: -90 at midnight, 0 at sunrise, 90 at noon.
+    pub sun_angle_deg: f32,
+    pub weather: Weather,
+    /// Exponential fog density (0 = perfectly clear).
+    pub fog_density: f32,
+    /// Linear RGB ambient tint keyed by canonical terrain class
+    /// (`"grass"`, `"snow"`, …).  Clients apply the tint of the biome the
+    /// camera is in.
+    #[serde(default)]
+    pub ambient_tints: BTreeMap<String, [f32; 3]>,
+}
+
+// ---------------------------------------------------------------------------
+// Scripted events  (subjects: world.event.*)
+// ---------------------------------------------------------------------------
+
+/// A scheduled world event started (`world.event.started`).
+///
+/// Announced once when the event fires (on schedule or by an operator);
+/// active events are also listed in [`WorldSnapshot::active_events`].  The
+/// server has already applied `weather` and registered `entities` as
+/// tracked entities; `structures` are transient scenery for clients to
+/// place for the event's duration (they have no server-side colliders).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptedEvent {
+    pub event_id: String,
+    /// Game-defined kind (e.g. "meteor_shower", "invasion", "weather_front").
+    pub kind: String,
+    /// Centre of the affected area.
+    pub x: f32,
+    pub y: f32,
+    /// Radius of the affected area; `0` = world-wide.
+    #[serde(default)]
+    pub radius: f32,
+    /// Seconds until `world.event.ended`; `0` = instantaneous.
+    #[serde(default)]
+    pub duration_s: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weather: Option<Weather>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub structures: Vec<StructureSpawned>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entities: Vec<EntitySpawned>,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+}
+
+/// A scripted event ran its course or was cancelled (`world.event.ended`).
+/// Clients remove its `structures`; its `entities` have been despawned and
+/// weather set by it has been restored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptedEventEnded {
+    pub event_id: String,
+    /// Ended by an operator rather than by running out.
+    #[serde(default)]
+    pub cancelled: bool,
 }
 
 // ---------------------------------------------------------------------------
@@ -170,6 +935,306 @@ pub struct WorldSnapshot {
     pub active_chunks: Vec<ChunkActivated>,
     pub structures: Vec<StructureSpawned>,
     pub entities: Vec<EntitySpawned>,
+    /// Current atmosphere so late joiners don't wait for the next broadcast.
+    #[serde(default)]
+    pub environment: Option<WorldEnvironment>,
+    /// Global ocean surface height; `None` when the world has no sea.
+    #[serde(default)]
+    pub sea_level: Option<f32>,
+    /// [`TERRAIN_VERSION`] of the server; check it before hydrating.
+    #[serde(default = "default_terrain_version")]
+    pub terrain_version: u32,
+    /// Every terrain edit so far, in `revision` order.
+    #[serde(default)]
+    pub terrain_modifications: Vec<TerrainModified>,
+    /// Voxel overrides for active chunks that have any.
+    #[serde(default)]
+    pub chunk_voxels: Vec<ChunkVoxels>,
+    /// Heights of active chunks clients can't regenerate (only with
+    /// `send_chunk_heights`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunk_data: Vec<ChunkData>,
+    /// Current display data for every entity that has any, by `entity_id`.
+    #[serde(default)]
+    pub entity_meta: Vec<EntityMeta>,
+    /// Every live entity handle, by `handle`.
+    #[serde(default)]
+    pub entity_handles: Vec<EntityHandle>,
+    /// Scripted events in progress, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub active_events: Vec<ScriptedEvent>,
+}
+
+/// [`WorldSnapshot`] with repeated strings replaced by indices into
+/// `string_table` (protocol version ≥ 2).
+///
+/// Entity/structure ids, structure `type_id`s and entity archetypes are
+/// interned; everything else is carried verbatim.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactWorldSnapshot {
+    pub string_table: Vec<String>,
+    pub active_chunks: Vec<ChunkActivated>,
+    pub structures: Vec<CompactStructure>,
+    pub entities: Vec<CompactEntity>,
+    #[serde(default)]
+    pub environment: Option<WorldEnvironment>,
+    #[serde(default)]
+    pub sea_level: Option<f32>,
+    #[serde(default = "default_terrain_version")]
+    pub terrain_version: u32,
+    #[serde(default)]
+    pub terrain_modifications: Vec<TerrainModified>,
+    #[serde(default)]
+    pub chunk_voxels: Vec<ChunkVoxels>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunk_data: Vec<ChunkData>,
+    #[serde(default)]
+    pub entity_meta: Vec<EntityMeta>,
+    #[serde(default)]
+    pub entity_handles: Vec<EntityHandle>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub active_events: Vec<ScriptedEvent>,
+}
+
+/// Transfer encoding a client can ask for on `world.cmd.snapshot`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotEncoding {
+    /// Gzip-compressed JSON, base64-encoded into [`EncodedSnapshot::data`].
+    Gzip,
+}
+
+/// Snapshot reply for a request that set `encoding`.
+///
+/// `data` decodes to the JSON of the snapshot the request would otherwise
+/// have received: a [`CompactWorldSnapshot`] when `compact`, else a
+/// [`WorldSnapshot`].  `frame` is the server tick it was taken at; the
+/// server may answer from a snapshot a few ticks old, so apply incremental
+/// events from `frame` on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncodedSnapshot {
+    pub frame: u64,
+    pub encoding: SnapshotEncoding,
+    pub compact: bool,
+    pub data: String,
+}
+
+/// Start of a paged snapshot (`world.snapshot.begin`), also the reply to a
+/// `world.cmd.snapshot` that set `paged: true`.
+///
+/// The reply the request would otherwise have received (a
+/// [`WorldSnapshot`], [`CompactWorldSnapshot`] or [`EncodedSnapshot`]) is
+/// serialised to JSON and split into `total_pages` [`SnapshotPage`]s, then
+/// closed by a [`SnapshotEnd`].  `snapshot_id` is the request's
+/// `command_id`, so a client can pick its own pages out of the broadcast;
+/// pages may arrive before the reply.  See
+/// [`SnapshotAssembler`](crate::snapshot_paging::SnapshotAssembler).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotBegin {
+    pub snapshot_id: String,
+    pub frame: u64,
+    pub total_pages: u32,
+    /// Length in bytes of the assembled JSON.
+    pub total_bytes: usize,
+    /// The assembled reply is a [`CompactWorldSnapshot`].
+    pub compact: bool,
+    /// Set when the assembled reply is an [`EncodedSnapshot`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<SnapshotEncoding>,
+}
+
+/// One slice of a paged snapshot's JSON (`world.snapshot.page`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotPage {
+    pub snapshot_id: String,
+    /// Position of this page, from `0`.
+    pub index: u32,
+    pub total: u32,
+    pub data: String,
+}
+
+/// Last message of a paged snapshot (`world.snapshot.end`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEnd {
+    pub snapshot_id: String,
+    pub total_pages: u32,
+}
+
+/// [`StructureSpawned`] with interned strings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactStructure {
+    pub structure_id: u32,
+    pub type_id: u32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub rotation_y: f32,
+    #[serde(default, skip_serializing_if = "is_zero_angle")]
+    pub pitch: f32,
+    #[serde(default, skip_serializing_if = "is_zero_angle")]
+    pub roll: f32,
+    #[serde(default = "unit_scale", skip_serializing_if = "is_unit_scale")]
+    pub scale: [f32; 3],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+}
+
+/// [`EntitySpawned`] with interned strings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactEntity {
+    pub entity_id: u32,
+    pub archetype: u32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub rotation_y: f32,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    #[serde(default)]
+    pub bounding_radius: f32,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+}
+
+/// Builds a string table, handing out one index per distinct string.
+#[derive(Default)]
+struct StringInterner {
+    table: Vec<String>,
+    index: std::collections::HashMap<String, u32>,
+}
+
+impl StringInterner {
+    fn intern(&mut self, s: String) -> u32 {
+        if let Some(&i) = self.index.get(&s) {
+            return i;
+        }
+        let i = self.table.len() as u32;
+        self.index.insert(s.clone(), i);
+        self.table.push(s);
+        i
+    }
+}
+
+impl WorldSnapshot {
+    /// Intern repeated strings for a version ≥ 2 client.
+    pub fn compact(self) -> CompactWorldSnapshot {
+        let mut strings = StringInterner::default();
+
+        let structures = self
+            .structures
+            .into_iter()
+            .map(|s| CompactStructure {
+                structure_id: strings.intern(s.structure_id),
+                type_id: strings.intern(s.type_id),
+                x: s.x,
+                y: s.y,
+                z: s.z,
+                rotation_y: s.rotation_y,
+                pitch: s.pitch,
+                roll: s.roll,
+                scale: s.scale,
+                state: s.state,
+                metadata: s.metadata,
+            })
+            .collect();
+
+        let entities = self
+            .entities
+            .into_iter()
+            .map(|e| CompactEntity {
+                entity_id: strings.intern(e.entity_id),
+                archetype: strings.intern(e.archetype),
+                x: e.x,
+                y: e.y,
+                z: e.z,
+                rotation_y: e.rotation_y,
+                scale: e.scale,
+                bounding_radius: e.bounding_radius,
+                metadata: e.metadata,
+            })
+            .collect();
+
+        CompactWorldSnapshot {
+            string_table: strings.table,
+            active_chunks: self.active_chunks,
+            structures,
+            entities,
+            environment: self.environment,
+            sea_level: self.sea_level,
+            terrain_version: self.terrain_version,
+            terrain_modifications: self.terrain_modifications,
+            chunk_voxels: self.chunk_voxels,
+            chunk_data: self.chunk_data,
+            entity_meta: self.entity_meta,
+            entity_handles: self.entity_handles,
+            active_events: self.active_events,
+        }
+    }
+}
+
+impl CompactWorldSnapshot {
+    /// Resolve every string index back into a plain [`WorldSnapshot`].
+    ///
+    /// Returns `None` if any index is out of range for `string_table`.
+    pub fn expand(self) -> Option<WorldSnapshot> {
+        let table = self.string_table;
+        let lookup = |i: u32| table.get(i as usize).cloned();
+
+        let structures = self
+            .structures
+            .into_iter()
+            .map(|s| {
+                Some(StructureSpawned {
+                    structure_id: lookup(s.structure_id)?,
+                    type_id: lookup(s.type_id)?,
+                    x: s.x,
+                    y: s.y,
+                    z: s.z,
+                    rotation_y: s.rotation_y,
+                    pitch: s.pitch,
+                    roll: s.roll,
+                    scale: s.scale,
+                    state: s.state,
+                    metadata: s.metadata,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let entities = self
+            .entities
+            .into_iter()
+            .map(|e| {
+                Some(EntitySpawned {
+                    entity_id: lookup(e.entity_id)?,
+                    archetype: lookup(e.archetype)?,
+                    x: e.x,
+                    y: e.y,
+                    z: e.z,
+                    rotation_y: e.rotation_y,
+                    scale: e.scale,
+                    bounding_radius: e.bounding_radius,
+                    metadata: e.metadata,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(WorldSnapshot {
+            active_chunks: self.active_chunks,
+            structures,
+            entities,
+            environment: self.environment,
+            sea_level: self.sea_level,
+            terrain_version: self.terrain_version,
+            terrain_modifications: self.terrain_modifications,
+            chunk_voxels: self.chunk_voxels,
+            chunk_data: self.chunk_data,
+            entity_meta: self.entity_meta,
+            entity_handles: self.entity_handles,
+            active_events: self.active_events,
+        })
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -198,6 +1263,90 @@ pub struct ConnectionStatus {
     pub frame: u64,
 }
 
+/// Liveness beacon from the active world service (`world.heartbeat`).
+///
+/// Standby instances take over once heartbeats stop for longer than their
+/// failover timeout.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldHeartbeat {
+    /// Bus participant id of the sender.
+    pub instance_id: String,
+    pub frame: u64,
+    /// Simulated seconds per wall-clock second: transforms arrive every
+    /// `dt / timescale` seconds.
+    #[serde(default = "default_timescale")]
+    pub timescale: f32,
+}
+
+fn default_timescale() -> f32 {
+    1.0
+}
+
+/// A standby instance has taken over the `world` role (`world.failover`).
+///
+/// Its state was restored from the last mirrored checkpoint, so anything
+/// newer is gone: clients drop their world cache and request a fresh
+/// `world.cmd.snapshot`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldFailover {
+    pub instance_id: String,
+    /// The primary whose heartbeat disappeared, if one was ever seen.
+    #[serde(default)]
+    pub previous_instance_id: Option<String>,
+    pub frame: u64,
+}
+
+// ---------------------------------------------------------------------------
+// Sharding  (subjects: world.shard.handoff, world.shard.handoff_ack)
+// ---------------------------------------------------------------------------
+
+/// A participant crossed into a super-region owned by another shard.
+///
+/// Published on the shared (un-namespaced) `world.shard.handoff` subject.
+/// The shard named by `to_shard` registers the participant at the given
+/// position and answers with a [`ShardHandoffAck`]; the old shard keeps
+/// simulating it until that ack arrives, and retries the handoff if none
+/// comes within `ShardConfig::handoff_timeout_s`.  The participant's client
+/// switches to `to_shard`'s subject namespace (see [`subjects::sharded`])
+/// and requests a snapshot there.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShardHandoff {
+    pub participant_id: String,
+    pub from_shard: String,
+    pub to_shard: String,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    /// Display data, carried over so nameplates survive the move.
+    #[serde(default)]
+    pub meta: Option<EntityMeta>,
+    /// Last acknowledged movement intent, for client reconciliation.
+    #[serde(default)]
+    pub last_intent_seq: Option<u64>,
+}
+
+/// `to_shard` has taken over the participant of a [`ShardHandoff`]; the
+/// shard named by `from_shard` drops it on receipt.
+///
+/// Published on the shared `world.shard.handoff_ack` subject.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShardHandoffAck {
+    pub participant_id: String,
+    pub from_shard: String,
+    pub to_shard: String,
+}
+
+impl ShardHandoffAck {
+    /// The ack answering `handoff`.
+    pub fn new(handoff: &ShardHandoff) -> Self {
+        Self {
+            participant_id: handoff.participant_id.clone(),
+            from_shard: handoff.from_shard.clone(),
+            to_shard: handoff.to_shard.clone(),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Intent messages  (client → server, via intent.* commands)
 // ---------------------------------------------------------------------------
@@ -208,14 +1357,20 @@ pub struct IntentMove {
     pub dx: f32,
     pub dy: f32,
     pub dz: f32,
+    /// Client-assigned, increasing input sequence number (for prediction);
+    /// echoed back as `EntityTransform::last_intent_seq`.
+    #[serde(default)]
+    pub seq: Option<u64>,
 }
 
 /// Client requests interaction with a specific entity or structure.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IntentInteract {
     pub target_id: String,
-    /// Optional interaction verb (e.g. "open", "attack", "talk").
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Optional interaction verb (e.g. "open", "attack", "talk").  Without
+    /// one, an interactive structure takes the only transition out of its
+    /// current state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub verb: Option<String>,
 }
 
@@ -227,6 +1382,20 @@ pub struct IntentTeleport {
     pub z: f32,
 }
 
+/// Client asks to build a structure of a buildable `type_id`.  The server
+/// snaps it to the ground and checks reach, slope and overlaps before
+/// broadcasting `world.structure.spawned`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntentPlaceStructure {
+    pub type_id: String,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    /// Yaw in radians.
+    #[serde(default)]
+    pub rotation_y: f32,
+}
+
 /// Client advertises its view radius so the server can tune the activation
 /// window and census resolution.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -249,37 +1418,603 @@ pub struct CmdRequestSnapshot {
     pub y: f32,
     pub z: f32,
     pub radius: f32,
+    /// Highest protocol version the client understands (see module docs).
+    #[serde(default = "default_protocol_version")]
+    pub protocol_version: u32,
+    /// Ask for an [`EncodedSnapshot`] reply instead of plain JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<SnapshotEncoding>,
+}
+
+/// Run one operator console line (see `console` module for the language).
+///
+/// Reply: [`ConsoleReply`] on success; parse and execution errors come back
+/// as a failed command response carrying the message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdConsole {
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleReply {
+    pub output: String,
+}
+
+/// Operator command for live servers (`janet-world-ctl`).
+///
+/// Encoded with an `action` tag, e.g. `{"action": "kick", "participant_id":
+/// "bob"}`.  When the server is configured with an admin token the request
+/// must carry it in `token`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AdminAction {
+    /// Reply with `WorldStats`.
+    Stats,
+    /// Drop a participant from the world.
+    Kick { participant_id: String },
+    /// Write the current world state to the default checkpoint.
+    Save,
+    /// Write the current world state to a named checkpoint.
+    Checkpoint { name: String },
+    /// Change the simulation tick rate.
+    SetTickRate { hz: f32 },
+    /// Slow down (`scale < 1`) or speed up simulated time.
+    SetTimescale { scale: f32 },
+    /// Generate (and persist, if a chunk store is configured) every terrain
+    /// chunk within `radius` chunks of `(cx, cy)`.
+    Pregenerate { cx: i32, cy: i32, radius: i32 },
+    /// Run one operator console line.
+    Console { line: String },
+    /// List scheduled events and which are active.
+    Events,
+    /// Start a scheduled event now, regardless of its trigger.
+    TriggerEvent { event_id: String },
+    /// End an active event early.
+    EndEvent { event_id: String },
+    /// Pause (`enabled: false`) or resume an event's schedule.
+    SetEventEnabled { event_id: String, enabled: bool },
+    /// Move, turn, rescale or re-tag a structure's metadata.
+    UpdateStructure {
+        structure_id: String,
+        update: StructureUpdate,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdAdmin {
+    #[serde(flatten)]
+    pub action: AdminAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// Result of a [`CmdAdmin`], returned as the command reply and broadcast on
+/// `world.admin.reply`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminReply {
+    pub ok: bool,
+    /// Human-readable summary.
+    pub message: String,
+    /// Structured result (e.g. `WorldStats`), `null` when there is none.
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+impl AdminReply {
+    pub fn success(message: impl Into<String>) -> Self {
+        Self {
+            ok: true,
+            message: message.into(),
+            data: serde_json::Value::Null,
+        }
+    }
+
+    pub fn failure(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            message: message.into(),
+            data: serde_json::Value::Null,
+        }
+    }
+
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = data;
+        self
+    }
+}
+
+/// Set an entity's display data (payload: an [`EntityMeta`]).
+///
+/// Reply: the stored [`EntityMeta`] (health clamped to `0..=1`); changes are
+/// broadcast on `world.entity.meta` at the next tick.
+pub type CmdEntityMeta = EntityMeta;
+
+/// Play an emote and/or sound on an entity.  At least one id is required;
+/// each entity may emote once per `emote_interval_s`.
+///
+/// Reply: the resulting [`EntityEmote`], which is also published on
+/// `world.entity.emote`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdEmote {
+    pub entity_id: String,
+    #[serde(default)]
+    pub emote_id: Option<String>,
+    #[serde(default)]
+    pub sound_id: Option<String>,
+}
+
+/// Deform terrain around `(x, y)` (server-authorised tooling / gameplay).
+///
+/// Every number must be finite, and radii over the server's cap
+/// (`MAX_DEFORM_RADIUS`, 64) fail.  When the server has an admin token the
+/// request must carry it in `token`.
+///
+/// Reply: the resulting [`TerrainModified`], which is also broadcast on
+/// `world.terrain.modified`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdDeformTerrain {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    #[serde(default)]
+    pub delta: f32,
+    /// Cut holes in the heightfield instead (`delta` is ignored).
+    #[serde(default)]
+    pub hole: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// Cast a ray through the world (click-to-move, targeting, projectiles).
+///
+/// Reply: the first [`RaycastHit`] among terrain, structures (vegetation
+/// included) and entities, or `null` when nothing lies within `max_dist`
+/// (capped server-side).  Shapes are hit as for [`CmdPick`]; `exclude`
+/// skips one entity, usually the caster, and `terrain_only` ignores
+/// everything but the ground.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdRaycast {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub dx: f32,
+    pub dy: f32,
+    pub dz: f32,
+    pub max_dist: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<String>,
+    #[serde(default)]
+    pub terrain_only: bool,
+}
+
+/// Reply to [`CmdRaycast`]: a [`RayHit`](crate::types::RayHit) tagged
+/// with what was hit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaycastHit {
+    #[serde(flatten)]
+    pub target: PickTarget,
+    /// Where the ray met the target.
+    pub position: Vec3,
+    /// Unit surface normal at the hit (`z` up).
+    pub normal: Vec3,
+    /// Distance travelled along the ray.
+    pub distance: f32,
+}
+
+/// Find what a ray from the camera points at, so clients can implement
+/// click-to-interact without a collision world of their own.
+///
+/// Reply: the nearest [`PickHit`] among entities, structures and terrain,
+/// or `null` when nothing lies within `max_dist` (capped as for
+/// [`CmdRaycast`]).  Entities are hit at their bounding spheres,
+/// structures and vegetation at their collider footprints extruded by
+/// their bounding radius; `exclude` skips one entity, usually the caller's
+/// own avatar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdPick {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub dx: f32,
+    pub dy: f32,
+    pub dz: f32,
+    pub max_dist: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<String>,
+}
+
+/// What a [`CmdPick`] or [`CmdRaycast`] ray hit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PickTarget {
+    Entity { entity_id: String },
+    Structure { structure_id: String },
+    Terrain,
+}
+
+/// Reply to [`CmdPick`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PickHit {
+    #[serde(flatten)]
+    pub target: PickTarget,
+    /// Where the ray met the target.
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    /// Distance travelled along the ray.
+    pub distance: f32,
+}
+
+/// Structures carrying a tag (`world.cmd.structures`): every spawn beacon
+/// in a region, the campfire nearest a point.
+///
+/// Reply: [`StructureQuery`] with the tagged structures overlapping
+/// `rect` (`[min_x, min_y, max_x, max_y]`, the whole world when absent),
+/// nearest to `near` first if given (by id otherwise), at most `limit`
+/// of them (capped at the server's `MAX_STRUCTURE_QUERY_RESULTS`, 256).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CmdStructures {
+    pub tag: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rect: Option<[f32; 4]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub near: Option<[f32; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Reply to [`CmdStructures`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructureQuery {
+    pub structures: Vec<StructureSpawned>,
+    /// More structures matched than were returned.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Ground heights for a batch of `[x, y]` points (UI markers, prop
+/// placement) without replicating terrain generation on the client.
+///
+/// Reply: [`HeightSamples`] in request order.  Requests over the server's
+/// point cap (`MAX_HEIGHT_QUERY_POINTS`, 1024) fail; split them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdHeights {
+    pub points: Vec<[f32; 2]>,
+    /// Also return surface normals.
+    #[serde(default)]
+    pub normals: bool,
+}
+
+/// Reply to [`CmdHeights`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeightSamples {
+    /// Terrain height at each point.
+    pub heights: Vec<f32>,
+    /// Unit surface normal at each point (`z` up); empty unless requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub normals: Vec<[f32; 3]>,
+}
+
+/// Designer height touch-up (`world.cmd.terrain.set_heights`).
+///
+/// Each sample is `[x, y, height]`; it pins the nearest terrain grid
+/// sample to `height`, and a height equal to the generated one clears the
+/// override.  Overrides are persisted with the world.  Requests over the
+/// server's sample cap (`MAX_SET_HEIGHT_SAMPLES`, 4096) fail.  When the
+/// server has an admin token the request must carry it in `token`.
+///
+/// Reply: [`HeightsSet`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdSetHeights {
+    pub samples: Vec<[f32; 3]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// Reply to [`CmdSetHeights`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeightsSet {
+    /// `"cx:cy"` of every chunk whose heights changed.
+    pub chunk_ids: Vec<String>,
+}
+
+/// File format of a [`CmdTerrainExport`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeightmapFormat {
+    /// 16-bit greyscale PNG.
+    #[default]
+    Png,
+    /// Headerless little-endian `u16` samples (`.raw` / `.r16`).
+    Raw,
+}
+
+impl HeightmapFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Raw => "raw",
+        }
+    }
+}
+
+/// Render a region's terrain heights to a heightmap
+/// (`world.cmd.terrain.export`).
+///
+/// Samples cover `min_x..=max_x` × `min_y..=max_y`, `spacing` metres apart
+/// (default: the server's tile size).  Without `name` the file comes back
+/// base64-encoded in the reply, which caps the region at
+/// `MAX_EXPORT_SAMPLES` (512 × 512); with one it's written server-side to
+/// `<export_dir>/<name>.<png|raw>`, up to `MAX_EXPORT_FILE_SAMPLES`.  When
+/// the server has an admin token the request must carry it in `token`.
+///
+/// Reply: [`TerrainExport`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdTerrainExport {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+    #[serde(default)]
+    pub spacing: Option<f32>,
+    #[serde(default)]
+    pub format: HeightmapFormat,
+    /// File name (`[A-Za-z0-9_-]`) to write on the server.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// Reply to [`CmdTerrainExport`].
+///
+/// Pixel `(col, row)` is the height at `(min_x + col * spacing, min_y +
+/// row * spacing)`, scaled from `min_height..=max_height` to `0..=65535`;
+/// these fields match `ImageTerrainConfig`, so an export loads back as an
+/// image terrain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerrainExport {
+    pub format: HeightmapFormat,
+    pub width: u32,
+    pub height: u32,
+    pub min_x: f32,
+    pub min_y: f32,
+    pub spacing: f32,
+    pub min_height: f32,
+    pub max_height: f32,
+    /// Base64 file contents (exports without `name`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    /// Where the file was written (exports with `name`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Command errors  (failed replies to world.cmd.*, world.participant.*, action.*)
+// ---------------------------------------------------------------------------
+
+/// Why a command failed.
+///
+/// | Code              | Meaning                                               |
+/// |-------------------|-------------------------------------------------------|
+/// | `invalid_payload` | The payload didn't parse or lacks a required key      |
+/// | `unauthorized`    | Admin token missing or wrong                          |
+/// | `unknown_entity`  | No tracked entity has the id (`details.entity_id`)    |
+/// | `rate_limited`    | Too soon after the last one (`details.retry_after_s`) |
+/// | `limit_exceeded`  | Over a per-request cap (`details.max`)                |
+/// | `rejected`        | Valid but refused: out-of-range value, unsupported by the terrain backend, … |
+/// | `failed`          | Accepted but couldn't be carried out (I/O, operator action error) |
+///
+/// Clients should branch on the code and show `message` only to humans;
+/// unknown codes are to be treated like `failed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorldCmdErrorCode {
+    InvalidPayload,
+    Unauthorized,
+    UnknownEntity,
+    RateLimited,
+    LimitExceeded,
+    Rejected,
+    Failed,
+}
+
+/// Body of every failed command reply: the reply's error message is this,
+/// encoded as JSON text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldCmdError {
+    pub code: WorldCmdErrorCode,
+    /// Human-readable explanation.
+    pub message: String,
+    /// Code-specific data (see [`WorldCmdErrorCode`]), `null` when none.
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub details: serde_json::Value,
+}
+
+impl WorldCmdError {
+    pub fn new(code: WorldCmdErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: serde_json::Value::Null,
+        }
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// The reply message text.
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| self.message.clone())
+    }
+
+    /// Read a failed reply's message.  Free text (servers from before
+    /// error codes) becomes a `failed` error carrying it.
+    pub fn decode(text: &str) -> Self {
+        serde_json::from_str(text).unwrap_or_else(|_| Self::new(WorldCmdErrorCode::Failed, text))
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Subject helpers
 // ---------------------------------------------------------------------------
 
-/// All NATS/bus subjects used by the world protocol, as constants.
+/// All NATS/bus subjects used by the world protocol.
+///
+/// The constants are base subjects; what goes on the wire is built from
+/// them by [`session`](subjects::session), [`sharded`](subjects::sharded)
+/// or a [`Namespace`](subjects::Namespace), so several worlds can share one
+/// NATS cluster (`world.chunk.activated` →
+/// `world.{session}.chunk.activated`).
 pub mod subjects {
     pub const CHUNK_ACTIVATED: &str = "world.chunk.activated";
     pub const CHUNK_DEACTIVATED: &str = "world.chunk.deactivated";
+    pub const CHUNK_VOXELS: &str = "world.chunk.voxels";
+    pub const CHUNK_DATA: &str = "world.chunk.data";
 
     pub const STRUCTURE_SPAWNED: &str = "world.structure.spawned";
     pub const STRUCTURE_REMOVED: &str = "world.structure.removed";
+    pub const STRUCTURE_INTEREST: &str = "world.structure.interest";
+    pub const STRUCTURE_STATE_CHANGED: &str = "world.structure.state_changed";
+    pub const STRUCTURE_UPDATED: &str = "world.structure.updated";
+
+    pub const INTERACTION_RESULT: &str = "world.interaction.result";
+
+    pub const INTEREST_SUBJECTS: &str = "world.interest.subjects";
 
     pub const ENTITY_SPAWNED: &str = "world.entity.spawned";
     pub const ENTITY_REMOVED: &str = "world.entity.removed";
     pub const ENTITY_TRANSFORM: &str = "world.entity.transform";
+    pub const ENTITY_META: &str = "world.entity.meta";
+    pub const ENTITY_HANDLE: &str = "world.entity.handle";
+    pub const ENTITY_TRANSFORMS: &str = "world.entity.transforms";
+    pub const ENTITY_EMOTE: &str = "world.entity.emote";
+    pub const ENTITY_COLLISION: &str = "world.entity.collision";
+
+    pub const PROXIMITY_ENTERED: &str = "world.proximity.entered";
+    pub const PROXIMITY_EXITED: &str = "world.proximity.exited";
+
+    pub const ENVIRONMENT: &str = "world.environment";
+
+    pub const TERRAIN_MODIFIED: &str = "world.terrain.modified";
+
+    pub const NAV_INVALIDATED: &str = "world.nav.invalidated";
+
+    pub const EVENT_STARTED: &str = "world.event.started";
+    pub const EVENT_ENDED: &str = "world.event.ended";
 
     pub const SNAPSHOT: &str = "world.snapshot";
+    pub const SNAPSHOT_BEGIN: &str = "world.snapshot.begin";
+    pub const SNAPSHOT_PAGE: &str = "world.snapshot.page";
+    pub const SNAPSHOT_END: &str = "world.snapshot.end";
     pub const CONNECTION_STATUS: &str = "world.connection.status";
+    pub const HEARTBEAT: &str = "world.heartbeat";
+    pub const FAILOVER: &str = "world.failover";
+    pub const SHARD_HANDOFF: &str = "world.shard.handoff";
+    pub const SHARD_HANDOFF_ACK: &str = "world.shard.handoff_ack";
 
     pub const INTENT_MOVE: &str = "intent.move";
     pub const INTENT_INTERACT: &str = "intent.interact";
     pub const INTENT_TELEPORT: &str = "intent.teleport";
     pub const INTENT_VIEW_RADIUS: &str = "intent.view_radius";
+    pub const INTENT_PLACE_STRUCTURE: &str = "intent.place_structure";
 
     pub const ACTION_MOVE: &str = "action.move";
     pub const ACTION_INTERACT: &str = "action.interact";
+    pub const ACTION_PLACE_STRUCTURE: &str = "action.place_structure";
 
     pub const CMD_STATS: &str = "world.cmd.stats";
     pub const CMD_SNAPSHOT: &str = "world.cmd.snapshot";
+    pub const CMD_DEFORM_TERRAIN: &str = "world.cmd.deform_terrain";
+    pub const CMD_ENTITY_META: &str = "world.cmd.entity_meta";
+    pub const CMD_EMOTE: &str = "world.cmd.emote";
+    pub const CMD_RAYCAST: &str = "world.cmd.raycast";
+    pub const CMD_PICK: &str = "world.cmd.pick";
+    pub const CMD_HEIGHTS: &str = "world.cmd.heights";
+    pub const CMD_STRUCTURES: &str = "world.cmd.structures";
+    pub const CMD_TERRAIN_SET_HEIGHTS: &str = "world.cmd.terrain.set_heights";
+    pub const CMD_TERRAIN_EXPORT: &str = "world.cmd.terrain.export";
+    pub const CMD_CONSOLE: &str = "world.cmd.console";
+    pub const CMD_ADMIN: &str = "world.cmd.admin";
+    pub const ADMIN_REPLY: &str = "world.admin.reply";
+
+    /// `subject` in `shard_id`'s namespace: `shard.{shard_id}` is inserted
+    /// after the first segment (`world.chunk.activated` →
+    /// `world.shard.east.chunk.activated`, `action.move` →
+    /// `action.shard.east.move`).
+    pub fn sharded(subject: &str, shard_id: &str) -> String {
+        insert_after_head(subject, &format!("shard.{}", shard_id))
+    }
+
+    /// `subject` scoped to interest cell `(cx, cy)`: `cell.{cx}.{cy}` is
+    /// inserted after the first segment (`world.entity.transform` →
+    /// `world.cell.3.-1.entity.transform`).  `cell(cx, cy, "world.>")` is
+    /// the wildcard over every event of the cell.
+    pub fn cell(cx: i32, cy: i32, subject: &str) -> String {
+        insert_after_head(subject, &format!("cell.{}.{}", cx, cy))
+    }
+
+    /// Interest cell of world position `(x, y)`.
+    pub fn cell_of(x: f32, y: f32, cell_size: f32) -> (i32, i32) {
+        (
+            (x / cell_size).floor() as i32,
+            (y / cell_size).floor() as i32,
+        )
+    }
+
+    /// `subject` in `session`'s namespace: the session is inserted after
+    /// the first segment (`world.chunk.activated` →
+    /// `world.{session}.chunk.activated`, `intent.move` →
+    /// `intent.{session}.move`).
+    pub fn session(subject: &str, session: &str) -> String {
+        insert_after_head(subject, session)
+    }
+
+    fn insert_after_head(subject: &str, segment: &str) -> String {
+        match subject.split_once('.') {
+            Some((head, rest)) => format!("{}.{}.{}", head, segment, rest),
+            None => format!("{}.{}", subject, segment),
+        }
+    }
+
+    /// Where one world instance publishes and listens: its session (if
+    /// scoped) and shard (if sharded).  The session is the outer segment:
+    /// `world.{session}.shard.{shard_id}.chunk.activated`.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct Namespace {
+        session: Option<String>,
+        shard: Option<String>,
+    }
+
+    impl Namespace {
+        pub fn new(session: Option<&str>, shard: Option<&str>) -> Self {
+            Self {
+                session: session.map(str::to_string),
+                shard: shard.map(str::to_string),
+            }
+        }
+
+        /// `base` in this namespace.
+        pub fn subject(&self, base: &str) -> String {
+            let subject = match &self.shard {
+                Some(id) => sharded(base, id),
+                None => base.to_string(),
+            };
+            self.shared(&subject)
+        }
+
+        /// `base` in this session, shared by all of its shards
+        /// (e.g. [`SHARD_HANDOFF`]).
+        pub fn shared(&self, base: &str) -> String {
+            match &self.session {
+                Some(name) => session(base, name),
+                None => base.to_string(),
+            }
+        }
+    }
 
     /// Management commands sent by the coordinator → world service.
     /// (Not used directly by clients.)
@@ -0,0 +1,180 @@
+//! Participant-to-participant proximity tracking.
+//!
+//! Participants are bucketed into a uniform grid whose cell size equals the
+//! proximity radius, so each participant only needs to be compared against
+//! the 3×3 block of buckets around it.  Pairs transition between "in range"
+//! and "out of range" with hysteresis (exit at `radius * EXIT_FACTOR`) and a
+//! per-pair cooldown so jittering players don't spam the bus.
+//!
+//! With [`update_sized`](ProximityTracker::update_sized) distances are
+//! measured between bounding circles (centre distance minus both radii), so
+//! large entities come into range earlier than small ones.
+
+use crate::protocol::{ProximityEntered, ProximityExited};
+use crate::types::Vec3;
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Pairs leave range only once they are this much further apart than
+/// `radius`, preventing enter/exit flapping at the boundary.
+const EXIT_FACTOR: f32 = 1.1;
+
+/// Unordered participant pair, stored with the lexically smaller id first.
+type Pair = (String, String);
+
+fn pair(a: &str, b: &str) -> Pair {
+    if a <= b {
+        (a.to_string(), b.to_string())
+    } else {
+        (b.to_string(), a.to_string())
+    }
+}
+
+fn distance(a: &Vec3, b: &Vec3) -> f32 {
+    let dx = a.x - b.x;
+    let dy = a.y - b.y;
+    let dz = a.z - b.z;
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+pub struct ProximityTracker {
+    radius: f32,
+    cooldown_ticks: u64,
+    in_range: HashSet<Pair>,
+    last_change: HashMap<Pair, u64>,
+}
+
+impl ProximityTracker {
+    /// `radius <= 0` disables tracking entirely.
+    pub fn new(radius: f32, cooldown_ticks: u64) -> Self {
+        Self {
+            radius,
+            cooldown_ticks,
+            in_range: HashSet::new(),
+            last_change: HashMap::new(),
+        }
+    }
+
+    pub fn set_cooldown_ticks(&mut self, cooldown_ticks: u64) {
+        self.cooldown_ticks = cooldown_ticks;
+    }
+
+    pub fn in_range_count(&self) -> usize {
+        self.in_range.len()
+    }
+
+    /// Diff the current positions against the last known pair set
+    /// (participants treated as points).
+    pub fn update<K: Borrow<str> + Hash + Eq>(
+        &mut self,
+        tick: u64,
+        positions: &HashMap<K, Vec3>,
+    ) -> (Vec<ProximityEntered>, Vec<ProximityExited>) {
+        self.update_sized(tick, positions, |_| 0.0)
+    }
+
+    /// [`update`](Self::update) with each participant's bounding radius;
+    /// the reported distance is the gap between the two circles.
+    pub fn update_sized<K: Borrow<str> + Hash + Eq>(
+        &mut self,
+        tick: u64,
+        positions: &HashMap<K, Vec3>,
+        bounding_radius: impl Fn(&str) -> f32,
+    ) -> (Vec<ProximityEntered>, Vec<ProximityExited>) {
+        let mut entered = Vec::new();
+        let mut exited = Vec::new();
+        if self.radius <= 0.0 {
+            return (entered, exited);
+        }
+
+        let exit_radius = self.radius * EXIT_FACTOR;
+        let radii: HashMap<&str, f32> = positions
+            .keys()
+            .map(|id| (id.borrow(), bounding_radius(id.borrow()).max(0.0)))
+            .collect();
+        let largest = radii.values().copied().fold(0.0, f32::max);
+        // Any pair within reach has centres at most this far apart.
+        let bucket_size = exit_radius + 2.0 * largest;
+
+        // Bucket by grid cell so candidate pairs are local.
+        let mut buckets: HashMap<(i32, i32), Vec<&str>> = HashMap::new();
+        for (id, pos) in positions {
+            let key = (
+                (pos.x / bucket_size).floor() as i32,
+                (pos.y / bucket_size).floor() as i32,
+            );
+            buckets.entry(key).or_default().push(id.borrow());
+        }
+
+        let mut close: HashMap<Pair, f32> = HashMap::new();
+        for (&(bx, by), ids) in &buckets {
+            for a in ids {
+                for dx in -1..=1 {
+                    for dy in -1..=1 {
+                        let Some(others) = buckets.get(&(bx + dx, by + dy)) else {
+                            continue;
+                        };
+                        for b in others {
+                            if a >= b {
+                                continue;
+                            }
+                            let d =
+                                (distance(&positions[*a], &positions[*b]) - radii[a] - radii[b])
+                                    .max(0.0);
+                            if d <= exit_radius {
+                                close.insert(pair(a, b), d);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Exits: pairs that drifted beyond the exit radius or whose
+        // participant left.  Departures bypass the cooldown.
+        let current: Vec<Pair> = self.in_range.iter().cloned().collect();
+        for p in current {
+            let gone =
+                !positions.contains_key(p.0.as_str()) || !positions.contains_key(p.1.as_str());
+            if close.contains_key(&p) {
+                continue;
+            }
+            if !gone && !self.cooled_down(&p, tick) {
+                continue;
+            }
+            self.in_range.remove(&p);
+            if gone {
+                self.last_change.remove(&p);
+            } else {
+                self.last_change.insert(p.clone(), tick);
+            }
+            exited.push(ProximityExited {
+                participant_a: p.0,
+                participant_b: p.1,
+            });
+        }
+
+        // Entries: pairs newly within the (inner) radius.
+        for (p, d) in close {
+            if d > self.radius || self.in_range.contains(&p) || !self.cooled_down(&p, tick) {
+                continue;
+            }
+            self.in_range.insert(p.clone());
+            self.last_change.insert(p.clone(), tick);
+            entered.push(ProximityEntered {
+                participant_a: p.0,
+                participant_b: p.1,
+                distance: d,
+            });
+        }
+
+        (entered, exited)
+    }
+
+    fn cooled_down(&self, p: &Pair, tick: u64) -> bool {
+        self.last_change
+            .get(p)
+            .is_none_or(|&t| tick.saturating_sub(t) >= self.cooldown_ticks)
+    }
+}
@@ -0,0 +1,412 @@
+//! Importing authored scenes as structures (`WORLD_SCENE_FILE`).
+//!
+//! Two inputs are understood:
+//!
+//! * glTF 2.0 scenes in their JSON form (`.gltf`, as Blender and Godot
+//!   export them).  Every node with a mesh becomes a structure; the mesh's
+//!   extent comes from the `min`/`max` of its `POSITION` accessors, so the
+//!   buffers themselves are never read.  Binary `.glb` files are rejected.
+//! * Scene lists (any other extension): the same node transforms without
+//!   the rest of glTF, each node giving its local extent as `size`:
+//!
+//! ```json
+//! {
+//!   "nodes": [
+//!     {
+//!       "name": "hut-1",
+//!       "type_id": "buildings/hut",
+//!       "translation": [12.0, 0.0, 4.5],
+//!       "rotation": [0.0, 0.7071, 0.0, 0.7071],
+//!       "size": [4.0, 3.0, 3.0],
+//!       "tags": ["shelter"],
+//!       "metadata": { "owner": "alice" }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Both use glTF's conventions: `+y` up, rotations as `[x, y, z, w]`
+//! quaternions, transforms composed down the node hierarchy.  World `x`
+//! is glTF `x`, world `y` is glTF `-z` and world `z` (height) is glTF `y`.
+//!
+//! A glTF node's `type_id` is `extras.type_id`, else its mesh's name;
+//! other `extras` keys become metadata (`tags` becomes the structure's
+//! tags).  Structure ids are node names, or `node-{index}` for unnamed
+//! nodes.
+//!
+//! 2D physics only has circles and boxes, so the collider is the smallest
+//! yaw-aligned box, centred on the node's origin, that holds the convex
+//! hull of the node's extent projected onto the ground.  Meshes modelled
+//! around the centre of their footprint get a tight box.
+
+use crate::protocol::UNIT_SCALE;
+use crate::structure::{collider_bounding_radius, StructureInstance, StructureMetaError};
+use crate::types::Vec3;
+use janet_operations::physics::types::ColliderShape;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SceneImportError {
+    #[error("failed to read scene: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid scene: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("unsupported scene: {0}")]
+    Unsupported(String),
+    #[error("invalid glTF: {0}")]
+    Gltf(String),
+    #[error("structure '{0}' is defined more than once")]
+    Duplicate(String),
+    #[error("invalid structure metadata: {0}")]
+    Metadata(#[from] StructureMetaError),
+}
+
+/// Structures of the scene at `path`: glTF for `.gltf`, a scene list
+/// otherwise.  Every structure is validated, so they insert cleanly.
+pub fn import_scene(path: impl AsRef<Path>) -> Result<Vec<StructureInstance>, SceneImportError> {
+    let path = path.as_ref();
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        Some("gltf") => from_gltf(&fs::read(path)?),
+        Some("glb") => Err(SceneImportError::Unsupported(
+            "binary glTF (.glb); export as .gltf instead".into(),
+        )),
+        _ => from_scene_list(&fs::read(path)?),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// glTF
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Default, Deserialize)]
+struct Gltf {
+    #[serde(default)]
+    scene: Option<usize>,
+    #[serde(default)]
+    scenes: Vec<GltfScene>,
+    #[serde(default)]
+    nodes: Vec<GltfNode>,
+    #[serde(default)]
+    meshes: Vec<GltfMesh>,
+    #[serde(default)]
+    accessors: Vec<GltfAccessor>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GltfScene {
+    #[serde(default)]
+    nodes: Vec<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GltfNode {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    mesh: Option<usize>,
+    #[serde(default)]
+    children: Vec<usize>,
+    #[serde(default)]
+    matrix: Option<[f32; 16]>,
+    #[serde(flatten)]
+    trs: Trs,
+    #[serde(default)]
+    extras: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GltfMesh {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    primitives: Vec<GltfPrimitive>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GltfPrimitive {
+    #[serde(default)]
+    attributes: HashMap<String, usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GltfAccessor {
+    #[serde(default)]
+    min: Option<Vec<f32>>,
+    #[serde(default)]
+    max: Option<Vec<f32>>,
+}
+
+/// Structures of the mesh nodes of a glTF document's default scene (every
+/// root node when it names none).
+pub fn from_gltf(json: &[u8]) -> Result<Vec<StructureInstance>, SceneImportError> {
+    let gltf: Gltf = serde_json::from_slice(json)?;
+    let bad = |msg: String| SceneImportError::Gltf(msg);
+    let roots: Vec<usize> = match gltf.scenes.get(gltf.scene.unwrap_or(0)) {
+        Some(scene) => scene.nodes.clone(),
+        None => {
+            let children: HashSet<usize> =
+                gltf.nodes.iter().flat_map(|n| n.children.clone()).collect();
+            (0..gltf.nodes.len())
+                .filter(|i| !children.contains(i))
+                .collect()
+        }
+    };
+
+    let mut out = Vec::new();
+    let mut ids = HashSet::new();
+    // (node, parent's world matrix); a node seen twice is a cycle or shared
+    // instance, both of which glTF forbids.
+    let mut stack: Vec<(usize, Mat4)> = roots.into_iter().rev().map(|i| (i, IDENTITY)).collect();
+    let mut visited = HashSet::new();
+    while let Some((index, parent)) = stack.pop() {
+        let node = gltf
+            .nodes
+            .get(index)
+            .ok_or_else(|| bad(format!("node {} doesn't exist", index)))?;
+        if !visited.insert(index) {
+            return Err(bad(format!("node {} has more than one parent", index)));
+        }
+        let local = match node.matrix {
+            Some(m) => m,
+            None => node.trs.matrix(),
+        };
+        let world = mul(&parent, &local);
+        stack.extend(node.children.iter().rev().map(|&c| (c, world)));
+
+        let Some(mesh_index) = node.mesh else {
+            continue;
+        };
+        let mesh = gltf
+            .meshes
+            .get(mesh_index)
+            .ok_or_else(|| bad(format!("mesh {} doesn't exist", mesh_index)))?;
+        let (min, max) = mesh_bounds(&gltf, mesh)
+            .ok_or_else(|| bad(format!("mesh {} has no POSITION bounds", mesh_index)))?;
+
+        let mut metadata = node.extras.clone();
+        let type_id = metadata
+            .remove("type_id")
+            .or_else(|| mesh.name.clone().map(serde_json::Value::String));
+        let tags = metadata.remove("tags");
+        let id = node
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("node-{}", index));
+        if !ids.insert(id.clone()) {
+            return Err(SceneImportError::Duplicate(id));
+        }
+        let mut s = structure(id, &world, min, max);
+        if let Some(type_id) = type_id {
+            metadata.insert("type_id".into(), type_id);
+        }
+        if let Some(tags) = tags {
+            s.tags = serde_json::from_value(tags).map_err(|_| StructureMetaError::Invalid {
+                id: s.id.clone(),
+                key: "tags",
+                expected: "a list of strings",
+            })?;
+        }
+        s.metadata = metadata;
+        s.validate()?;
+        out.push(s);
+    }
+    Ok(out)
+}
+
+/// Local bounding box of every `POSITION` accessor of `mesh`.
+fn mesh_bounds(gltf: &Gltf, mesh: &GltfMesh) -> Option<([f32; 3], [f32; 3])> {
+    let mut bounds: Option<([f32; 3], [f32; 3])> = None;
+    for primitive in &mesh.primitives {
+        let accessor = gltf.accessors.get(*primitive.attributes.get("POSITION")?)?;
+        let (lo, hi) = (accessor.min.as_deref()?, accessor.max.as_deref()?);
+        if lo.len() != 3 || hi.len() != 3 {
+            return None;
+        }
+        let (min, max) = bounds.get_or_insert(([lo[0], lo[1], lo[2]], [hi[0], hi[1], hi[2]]));
+        for axis in 0..3 {
+            min[axis] = min[axis].min(lo[axis]);
+            max[axis] = max[axis].max(hi[axis]);
+        }
+    }
+    bounds
+}
+
+// ---------------------------------------------------------------------------
+// Scene lists
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+struct SceneList {
+    nodes: Vec<SceneNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SceneNode {
+    name: String,
+    type_id: String,
+    #[serde(flatten)]
+    trs: Trs,
+    /// Local extent along glTF `x`, `y` (up) and `z`, centred on the origin.
+    size: [f32; 3],
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    metadata: HashMap<String, serde_json::Value>,
+}
+
+/// Structures of a scene list's nodes, in order.
+pub fn from_scene_list(json: &[u8]) -> Result<Vec<StructureInstance>, SceneImportError> {
+    let list: SceneList = serde_json::from_slice(json)?;
+    let mut ids = HashSet::new();
+    let mut out = Vec::with_capacity(list.nodes.len());
+    for node in list.nodes {
+        if !ids.insert(node.name.clone()) {
+            return Err(SceneImportError::Duplicate(node.name));
+        }
+        let half = node.size.map(|v| 0.5 * v.abs());
+        let mut s = structure(node.name, &node.trs.matrix(), half.map(|v| -v), half);
+        s.tags = node.tags;
+        s.metadata = node.metadata;
+        s.metadata
+            .insert("type_id".into(), serde_json::json!(node.type_id));
+        s.validate()?;
+        out.push(s);
+    }
+    Ok(out)
+}
+
+// ---------------------------------------------------------------------------
+// Transforms
+// ---------------------------------------------------------------------------
+
+/// Column-major 4×4 matrix, as glTF stores them.
+type Mat4 = [f32; 16];
+
+const IDENTITY: Mat4 = [
+    1.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, //
+    0.0, 0.0, 0.0, 1.0,
+];
+
+/// A node's translation, rotation and scale.
+#[derive(Debug, Default, Deserialize)]
+struct Trs {
+    #[serde(default)]
+    translation: Option<[f32; 3]>,
+    #[serde(default)]
+    rotation: Option<[f32; 4]>,
+    #[serde(default)]
+    scale: Option<[f32; 3]>,
+}
+
+impl Trs {
+    fn matrix(&self) -> Mat4 {
+        let [tx, ty, tz] = self.translation.unwrap_or([0.0; 3]);
+        let [x, y, z, w] = self.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+        let [sx, sy, sz] = self.scale.unwrap_or(UNIT_SCALE);
+        [
+            (1.0 - 2.0 * (y * y + z * z)) * sx,
+            2.0 * (x * y + z * w) * sx,
+            2.0 * (x * z - y * w) * sx,
+            0.0,
+            2.0 * (x * y - z * w) * sy,
+            (1.0 - 2.0 * (x * x + z * z)) * sy,
+            2.0 * (y * z + x * w) * sy,
+            0.0,
+            2.0 * (x * z + y * w) * sz,
+            2.0 * (y * z - x * w) * sz,
+            (1.0 - 2.0 * (x * x + y * y)) * sz,
+            0.0,
+            tx,
+            ty,
+            tz,
+            1.0,
+        ]
+    }
+}
+
+fn mul(a: &Mat4, b: &Mat4) -> Mat4 {
+    let mut out = [0.0; 16];
+    for col in 0..4 {
+        for row in 0..4 {
+            out[col * 4 + row] = (0..4).map(|k| a[k * 4 + row] * b[col * 4 + k]).sum();
+        }
+    }
+    out
+}
+
+/// The glTF axis, and its sign, along each world axis.
+const AXES: [(usize, f32); 3] = [(0, 1.0), (2, -1.0), (1, 1.0)];
+
+/// Element `(row, col)` of `m` in world axes (`+z` up).
+fn world_axes(m: &Mat4, row: usize, col: usize) -> f32 {
+    let (r, rs) = AXES[row];
+    let (c, cs) = AXES[col];
+    rs * cs * m[c * 4 + r]
+}
+
+/// A structure at `world`'s origin whose collider holds the local box
+/// `min..max`.
+fn structure(id: String, world: &Mat4, min: [f32; 3], max: [f32; 3]) -> StructureInstance {
+    let position = Vec3::new(world[12], -world[14], world[13]);
+    // Columns of the world-axes basis: their lengths are the scale, the
+    // normalised columns the rotation.
+    let scale: [f32; 3] = std::array::from_fn(|col| {
+        (0..3)
+            .map(|row| world_axes(world, row, col).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    });
+    let r = |row: usize, col: usize| {
+        if scale[col] > 0.0 {
+            world_axes(world, row, col) / scale[col]
+        } else {
+            0.0
+        }
+    };
+    // R = Rz(yaw) · Ry(pitch) · Rx(roll).
+    let yaw = r(1, 0).atan2(r(0, 0));
+    let pitch = (-r(2, 0)).clamp(-1.0, 1.0).asin();
+    let roll = r(2, 1).atan2(r(2, 2));
+
+    // Project the box's corners onto the ground in the yaw frame.
+    let (sin, cos) = yaw.sin_cos();
+    let (mut half_w, mut half_h) = (0.0f32, 0.0f32);
+    for corner in 0..8 {
+        let p: [f32; 3] = std::array::from_fn(|i| {
+            if corner & (1 << i) == 0 {
+                min[i]
+            } else {
+                max[i]
+            }
+        });
+        let offset = |row: usize| -> f32 {
+            let (r, rs) = AXES[row];
+            rs * (0..3).map(|c| world[c * 4 + r] * p[c]).sum::<f32>()
+        };
+        let (dx, dy) = (offset(0), offset(1));
+        half_w = half_w.max((dx * cos + dy * sin).abs());
+        half_h = half_h.max((-dx * sin + dy * cos).abs());
+    }
+
+    let collider = ColliderShape::Box {
+        width: 2.0 * half_w,
+        height: 2.0 * half_h,
+    };
+    let mut s = StructureInstance::new(id, position, collider);
+    s.bounds_radius = collider_bounding_radius(&s.collider);
+    s.rotation_y = yaw;
+    s.pitch = pitch;
+    s.roll = roll;
+    s.scale = scale;
+    s
+}
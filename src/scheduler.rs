@@ -0,0 +1,361 @@
+//! Scripted world events: a schedule of [`ScriptedEvent`]s (meteor showers,
+//! invasions, weather fronts …) fired at world-clock or wall-clock times.
+//!
+//! The schedule is a JSON data file (`WORLD_EVENTS_FILE`):
+//!
+//! ```json
+//! { "events": [
+//!     { "event_id": "nightfall-meteors", "kind": "meteor_shower",
+//!       "trigger": { "game_time": "22:00" }, "duration_s": 120,
+//!       "x": 300, "y": -40, "radius": 150, "weather": "storm",
+//!       "structures": [ … ], "metadata": { "intensity": 3 } },
+//!     { "event_id": "raid", "kind": "invasion",
+//!       "trigger": { "wall_clock": "19:30" }, "entities": [ … ] },
+//!     { "event_id": "drizzle", "kind": "weather_front",
+//!       "trigger": { "every_s": 1800 }, "duration_s": 300, "weather": "rain" }
+//! ] }
+//! ```
+//!
+//! | Trigger                 | Fires                                           |
+//! |-------------------------|-------------------------------------------------|
+//! | `game_time: "HH:MM"`    | daily when the world clock passes it (24-hour dial scaled to the day length) |
+//! | `wall_clock: "HH:MM"`   | daily at that UTC time                          |
+//! | `every_s: n`            | every `n` seconds of simulated time             |
+//! | *(none)*                | only when an operator triggers it               |
+//!
+//! [`EventScheduler`] only decides *when*; the service applies each event's
+//! effects and announces it on `world.event.started` / `world.event.ended`.
+
+use crate::protocol::{ScriptedEvent, ScriptedEventEnded};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use thiserror::Error;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    #[error("failed to read event schedule: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid event schedule: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("event '{0}' is defined more than once")]
+    Duplicate(String),
+    #[error("event '{id}': {message}")]
+    Invalid { id: String, message: String },
+    #[error("unknown event '{0}'")]
+    Unknown(String),
+    #[error("event '{0}' is not active")]
+    NotActive(String),
+}
+
+// ---------------------------------------------------------------------------
+// Definitions
+// ---------------------------------------------------------------------------
+
+/// Time of day on a 24-hour dial, written `HH:MM` or `HH:MM:SS`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ClockTime {
+    /// Seconds past midnight, `0..86400`.
+    seconds: u32,
+}
+
+impl ClockTime {
+    /// Fraction of the day, `0.0..1.0`.
+    pub fn day_fraction(self) -> f64 {
+        self.seconds as f64 / SECONDS_PER_DAY
+    }
+}
+
+impl TryFrom<String> for ClockTime {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        let parts: Vec<u32> = text
+            .split(':')
+            .map(|p| p.parse().map_err(|_| ()))
+            .collect::<Result<_, ()>>()
+            .map_err(|()| format!("'{}' is not HH:MM[:SS]", text))?;
+        let (h, m, s) = match parts[..] {
+            [h, m] => (h, m, 0),
+            [h, m, s] => (h, m, s),
+            _ => return Err(format!("'{}' is not HH:MM[:SS]", text)),
+        };
+        if h >= 24 || m >= 60 || s >= 60 {
+            return Err(format!("'{}' is not a time of day", text));
+        }
+        Ok(Self {
+            seconds: h * 3600 + m * 60 + s,
+        })
+    }
+}
+
+impl From<ClockTime> for String {
+    fn from(t: ClockTime) -> String {
+        t.to_string()
+    }
+}
+
+impl fmt::Display for ClockTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (h, m, s) = (
+            self.seconds / 3600,
+            self.seconds / 60 % 60,
+            self.seconds % 60,
+        );
+        if s == 0 {
+            write!(f, "{:02}:{:02}", h, m)
+        } else {
+            write!(f, "{:02}:{:02}:{:02}", h, m, s)
+        }
+    }
+}
+
+/// When a scheduled event fires.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventTrigger {
+    GameTime(ClockTime),
+    WallClock(ClockTime),
+    #[serde(rename = "every_s")]
+    Every(f32),
+}
+
+/// One entry of the schedule: the announcement plus when to make it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledEvent {
+    #[serde(flatten)]
+    pub event: ScriptedEvent,
+    #[serde(default)]
+    pub trigger: Option<EventTrigger>,
+    /// Disabled events only start when triggered by hand.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Contents of a schedule file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventSchedule {
+    pub events: Vec<ScheduledEvent>,
+}
+
+// ---------------------------------------------------------------------------
+// Scheduler
+// ---------------------------------------------------------------------------
+
+/// The clocks triggers are evaluated against, sampled once per tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SchedulerClock {
+    /// Simulated seconds since the service started.
+    pub elapsed_s: f64,
+    pub time_of_day_s: f32,
+    pub day_length_s: f32,
+    /// Seconds since the Unix epoch.
+    pub wall_clock_s: f64,
+}
+
+/// A start or end decided by [`EventScheduler::poll`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventChange {
+    Started(ScriptedEvent),
+    Ended(ScriptedEventEnded),
+}
+
+#[derive(Debug, Clone)]
+struct ActiveEvent {
+    index: usize,
+    ends_at_s: f64,
+}
+
+/// Decides when scheduled events start and end.
+#[derive(Debug, Default)]
+pub struct EventScheduler {
+    events: Vec<ScheduledEvent>,
+    /// Oldest first.
+    active: Vec<ActiveEvent>,
+    /// Clock at the previous poll; triggers fire when it is passed.
+    last: Option<SchedulerClock>,
+    /// `elapsed_s` each `every_s` event is next due, by index.
+    next_due: Vec<f64>,
+}
+
+impl EventScheduler {
+    pub fn new(schedule: EventSchedule) -> Result<Self, ScheduleError> {
+        for (i, e) in schedule.events.iter().enumerate() {
+            let id = &e.event.event_id;
+            let invalid = |message: &str| ScheduleError::Invalid {
+                id: id.clone(),
+                message: message.to_string(),
+            };
+            if id.is_empty() {
+                return Err(invalid("event_id must not be empty"));
+            }
+            if schedule.events[..i]
+                .iter()
+                .any(|other| other.event.event_id == *id)
+            {
+                return Err(ScheduleError::Duplicate(id.clone()));
+            }
+            if !(e.event.x.is_finite() && e.event.y.is_finite()) {
+                return Err(invalid("x and y must be finite"));
+            }
+            if !(e.event.radius >= 0.0 && e.event.duration_s >= 0.0) {
+                return Err(invalid("radius and duration_s must not be negative"));
+            }
+            if let Some(EventTrigger::Every(period)) = e.trigger {
+                if !(period.is_finite() && period > 0.0) {
+                    return Err(invalid("every_s must be positive"));
+                }
+            }
+        }
+        Ok(Self {
+            next_due: vec![f64::INFINITY; schedule.events.len()],
+            events: schedule.events,
+            ..Self::default()
+        })
+    }
+
+    /// Read a JSON schedule file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScheduleError> {
+        let json = std::fs::read(path)?;
+        Self::new(serde_json::from_slice(&json)?)
+    }
+
+    pub fn events(&self) -> &[ScheduledEvent] {
+        &self.events
+    }
+
+    pub fn is_active(&self, event_id: &str) -> bool {
+        self.active
+            .iter()
+            .any(|a| self.events[a.index].event.event_id == event_id)
+    }
+
+    /// Events in progress, oldest first.
+    pub fn active(&self) -> impl Iterator<Item = &ScriptedEvent> {
+        self.active.iter().map(|a| &self.events[a.index].event)
+    }
+
+    /// End events that ran their course and start those whose trigger time
+    /// passed since the previous poll (ends first).  The first poll only
+    /// starts the clocks, so a restart doesn't replay today's events.
+    pub fn poll(&mut self, clock: SchedulerClock) -> Vec<EventChange> {
+        let mut changes = Vec::new();
+        let (ended, running): (Vec<_>, Vec<_>) = std::mem::take(&mut self.active)
+            .into_iter()
+            .partition(|a| clock.elapsed_s >= a.ends_at_s);
+        self.active = running;
+        changes.extend(ended.into_iter().map(|a| {
+            EventChange::Ended(ScriptedEventEnded {
+                event_id: self.events[a.index].event.event_id.clone(),
+                cancelled: false,
+            })
+        }));
+
+        let Some(last) = self.last.replace(clock) else {
+            for (due, e) in self.next_due.iter_mut().zip(&self.events) {
+                if let Some(EventTrigger::Every(period)) = e.trigger {
+                    *due = clock.elapsed_s + period as f64;
+                }
+            }
+            return changes;
+        };
+        for index in 0..self.events.len() {
+            let fired = match self.events[index].trigger {
+                Some(EventTrigger::GameTime(at)) => {
+                    let day = clock.day_length_s as f64;
+                    passed(
+                        last.time_of_day_s as f64 / day,
+                        clock.time_of_day_s as f64 / day,
+                        at.day_fraction(),
+                    )
+                }
+                Some(EventTrigger::WallClock(at)) => passed(
+                    last.wall_clock_s.rem_euclid(SECONDS_PER_DAY) / SECONDS_PER_DAY,
+                    clock.wall_clock_s.rem_euclid(SECONDS_PER_DAY) / SECONDS_PER_DAY,
+                    at.day_fraction(),
+                ),
+                Some(EventTrigger::Every(period)) => {
+                    let due = clock.elapsed_s >= self.next_due[index];
+                    if due {
+                        self.next_due[index] = clock.elapsed_s + period as f64;
+                    }
+                    due
+                }
+                None => false,
+            };
+            if fired && self.events[index].enabled {
+                changes.push(EventChange::Started(self.start(index, clock.elapsed_s)));
+            }
+        }
+        changes
+    }
+
+    /// Start an event now, whatever its trigger or `enabled` flag.  An
+    /// event that is already active restarts its duration.
+    pub fn trigger(
+        &mut self,
+        event_id: &str,
+        elapsed_s: f64,
+    ) -> Result<ScriptedEvent, ScheduleError> {
+        let index = self.index_of(event_id)?;
+        Ok(self.start(index, elapsed_s))
+    }
+
+    /// End an active event early.
+    pub fn end(&mut self, event_id: &str) -> Result<ScriptedEventEnded, ScheduleError> {
+        let index = self.index_of(event_id)?;
+        let before = self.active.len();
+        self.active.retain(|a| a.index != index);
+        if self.active.len() == before {
+            return Err(ScheduleError::NotActive(event_id.to_string()));
+        }
+        Ok(ScriptedEventEnded {
+            event_id: event_id.to_string(),
+            cancelled: true,
+        })
+    }
+
+    /// Pause or resume an event's trigger; active events keep running.
+    pub fn set_enabled(&mut self, event_id: &str, enabled: bool) -> Result<(), ScheduleError> {
+        let index = self.index_of(event_id)?;
+        self.events[index].enabled = enabled;
+        Ok(())
+    }
+
+    fn index_of(&self, event_id: &str) -> Result<usize, ScheduleError> {
+        self.events
+            .iter()
+            .position(|e| e.event.event_id == event_id)
+            .ok_or_else(|| ScheduleError::Unknown(event_id.to_string()))
+    }
+
+    fn start(&mut self, index: usize, elapsed_s: f64) -> ScriptedEvent {
+        self.active.retain(|a| a.index != index);
+        self.active.push(ActiveEvent {
+            index,
+            ends_at_s: elapsed_s + self.events[index].event.duration_s as f64,
+        });
+        self.events[index].event.clone()
+    }
+}
+
+/// `true` if a daily clock moving from `prev` to `now` (fractions of a day)
+/// passed `at`, wrapping at midnight.
+fn passed(prev: f64, now: f64, at: f64) -> bool {
+    if prev <= now {
+        prev < at && at <= now
+    } else {
+        at > prev || at <= now
+    }
+}
@@ -1,17 +1,176 @@
 //! WorldService – streaming, cell activation/deactivation, terrain physics bodies.
 
+use crate::behaviour::Behaviour;
+use crate::chunk_workers::ChunkWorkers;
+use crate::codec;
+use crate::collision;
+use crate::entity::{Entity, EntityError, EntitySpec, EntityStore};
+use crate::environment::Environment;
+use crate::heightmap_export;
+use crate::hibernation::HibernationStore;
+use crate::interaction::{Interaction, InteractionHandler, InteractionHandlers, DEFAULT_VERB};
+use crate::interest::{CellInterest, InterestTracker};
+use crate::metrics::{DropCounters, FanoutMetrics};
+use crate::persistence::{WorldState, STATE_VERSION};
+use crate::population::{AmbientPopulation, PopulationCell};
 use crate::protocol::{
-    ChunkActivated, ChunkDeactivated, EntitySpawned, EntityTransform, StructureSpawned,
-    WorldSnapshot,
+    subjects, ChunkActivated, ChunkData, ChunkDeactivated, ChunkEdge, ChunkVoxels, CmdEmote,
+    CmdStructures, CmdTerrainExport, EntityCollision, EntityEmote, EntityHandle, EntityMeta,
+    EntityRemoved, EntitySpawned, EntityTransform, EntityTransformBatch, HandleTransform,
+    HeightSamples, IntentInteract, IntentMove, IntentPlaceStructure, InteractionResult,
+    InteractionTarget, InterestSubjects, NavChangeCause, NavInvalidated, ParticipantJoined,
+    PickHit, PickTarget, ProximityEntered, ProximityExited, RaycastHit, ScriptedEvent,
+    ScriptedEventEnded, ShardHandoff, ShardHandoffAck, StructureInterest, StructureQuery,
+    StructureRemoved, StructureSpawned, StructureStateChanged, StructureUpdate, StructureUpdated,
+    TerrainExport, TerrainModified, Weather, WorldEnvironment, WorldSnapshot, TERRAIN_ALGO_VERSION,
+    TERRAIN_VERSION,
 };
-use crate::structure::World;
-use crate::terrain::HeightmapTerrain;
-use crate::types::{CellCoord, Vec3, WorldObject, WorldServiceConfig, WorldStats};
-use janet_operations::physics::{types::BodyParams, PhysicsRegistry};
-use log::{debug, warn};
+use crate::proximity::ProximityTracker;
+use crate::scheduler::{EventChange, EventScheduler, ScheduleError, SchedulerClock};
+use crate::shard::ShardMap;
+use crate::shoreline;
+use crate::spatial::SpatialGrid;
+use crate::spawner::Spawners;
+use crate::structure::{
+    collider_bounding_radius, StructureInstance, StructureMetaError, StructureRecord, World,
+};
+use crate::terrain::{solid_footprint, HeightChunk};
+use crate::transform_delta::TransformEncoder;
+use crate::types::{
+    CellCoord, CellRegion, DropReason, PregenerateReport, RayHit, Vec3, WorldObject,
+    WorldServiceConfig, WorldStats, PARTICIPANT_ARCHETYPE,
+};
+use crate::vegetation;
+use crate::worldgen::{PopulationRule, SpawnerDef};
+use janet_operations::physics::{
+    types::{BodyParams, ColliderShape, Transform},
+    PhysicsRegistry,
+};
+use log::{debug, info, warn};
 use parking_lot::RwLock;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Longest ray [`WorldService::raycast`] will march (world units); longer
+/// requests are clamped so one query can't generate far-away chunks.
+pub const MAX_RAYCAST_DISTANCE: f32 = 512.0;
+
+/// Smallest bounding sphere [`WorldService::pick`] tests an entity against,
+/// so point-sized archetypes can still be clicked.
+pub const MIN_PICK_RADIUS: f32 = 0.25;
+
+/// Most points one [`WorldService::sample_heights`] call accepts.
+pub const MAX_HEIGHT_QUERY_POINTS: usize = 1024;
+
+/// Most structures one [`WorldService::structures_by_tag`] reply carries.
+pub const MAX_STRUCTURE_QUERY_RESULTS: usize = 256;
+
+/// Most samples one [`WorldService::set_terrain_heights`] call accepts.
+pub const MAX_SET_HEIGHT_SAMPLES: usize = 4096;
+
+/// Largest radius one [`WorldService::deform_terrain`] or
+/// [`WorldService::cut_terrain_holes`] call accepts.
+pub const MAX_DEFORM_RADIUS: f32 = 64.0;
+
+/// Most samples in a [`WorldService::export_terrain`] returned in the reply.
+pub const MAX_EXPORT_SAMPLES: usize = 512 * 512;
+
+/// Most samples in a [`WorldService::export_terrain`] written to disk.
+pub const MAX_EXPORT_FILE_SAMPLES: usize = 4096 * 4096;
+
+/// Slowest [`WorldService::set_timescale`] accepts.
+pub const MIN_TIMESCALE: f32 = 0.1;
+
+/// Fastest [`WorldService::set_timescale`] accepts.
+pub const MAX_TIMESCALE: f32 = 10.0;
+
+/// Height above the ground within which solid voxels block 2D movement.
+pub const VOXEL_COLLIDER_CLEARANCE: f32 = 2.0;
+
+/// Why [`WorldService::place_structure`] refused a placement.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum PlacementError {
+    #[error("Unknown entity '{0}'")]
+    UnknownEntity(String),
+    #[error("'{0}' is not a buildable structure type")]
+    NotBuildable(String),
+    #[error("Placement position and rotation must be finite")]
+    NonFinite,
+    #[error("Placement is {distance:.1} m away, reach is {reach:.1} m")]
+    OutOfReach { distance: f32, reach: f32 },
+    #[error("Placement is outside the world bounds")]
+    OutOfBounds,
+    #[error("Ground is too steep, flooded or below the sea")]
+    Ground,
+    #[error("Placement overlaps '{0}'")]
+    Overlaps(String),
+    #[error("Placed structure has no type_id")]
+    MissingTypeId,
+    #[error("Placed structure metadata '{key}' must be {expected}")]
+    InvalidMetadata {
+        key: &'static str,
+        expected: &'static str,
+    },
+    #[error("Placed structure has no state '{0}'")]
+    UnknownState(String),
+    #[error("Placement failed: {0}")]
+    Physics(String),
+}
+
+impl From<StructureMetaError> for PlacementError {
+    fn from(e: StructureMetaError) -> Self {
+        match e {
+            StructureMetaError::MissingTypeId(_) => Self::MissingTypeId,
+            StructureMetaError::Invalid { key, expected, .. } => {
+                Self::InvalidMetadata { key, expected }
+            }
+            StructureMetaError::UnknownState { state, .. } => Self::UnknownState(state),
+        }
+    }
+}
+
+/// Why [`WorldService::interact`] refused an interaction.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum InteractError {
+    #[error("Unknown entity '{0}'")]
+    UnknownEntity(String),
+    #[error("'{0}' is not an interactive structure")]
+    NotInteractive(String),
+    #[error("Target is {distance:.1} m away, reach is {reach:.1} m")]
+    OutOfReach { distance: f32, reach: f32 },
+    #[error("'{target}' has no such transition from '{state}'")]
+    NoTransition {
+        target: String,
+        state: String,
+        verb: Option<String>,
+    },
+    #[error("Interaction failed: {0}")]
+    Physics(String),
+    #[error("Nothing happens when you {verb} '{target}'")]
+    NoHandler { target: String, verb: String },
+    /// An interaction handler turned it down.
+    #[error("{0}")]
+    Refused(String),
+}
+
+/// Why [`WorldService::update_structure`] refused an update.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum StructureUpdateError {
+    #[error("Unknown structure '{0}'")]
+    UnknownStructure(String),
+    #[error(transparent)]
+    Metadata(#[from] StructureMetaError),
+    #[error("Update failed: {0}")]
+    Physics(String),
+    #[error("World object '{0}' only takes position and metadata updates")]
+    ObjectTransform(String),
+}
 
 // ---------------------------------------------------------------------------
 // Tick result
@@ -19,27 +178,242 @@ use std::sync::Arc;
 
 /// Events produced by a single [`WorldService::tick`] call.
 ///
-/// Callers (typically [`WorldBusAgent`]) publish these to the bus.
+/// Callers (typically [`WorldBusAgent`]) publish these to the bus.  Long
+/// running loops should keep one value and pass it to
+/// [`WorldService::tick_into`] so the vectors are reused between ticks.
+#[derive(Default)]
 pub struct TickEvents {
     /// The tick counter that produced this set of events.
     pub tick: u64,
-    /// Chunks that were activated this tick.
+    /// Chunks that were activated this tick, plus active chunks announced
+    /// again because their LOD or holes changed.
     pub activated: Vec<ChunkActivated>,
     /// Chunks that were deactivated this tick.
     pub deactivated: Vec<ChunkDeactivated>,
-    /// Authoritative transforms for every tracked participant/entity.
+    /// Voxel overrides for chunks activated this tick (only those with any).
+    pub chunk_voxels: Vec<ChunkVoxels>,
+    /// Heights for chunks announced this tick that clients can't
+    /// regenerate (only with `send_chunk_heights`).
+    pub chunk_data: Vec<ChunkData>,
+    /// Authoritative transforms for every tracked participant/entity
+    /// (empty when `handle_transforms` is enabled).
     pub entity_transforms: Vec<EntityTransform>,
+    /// The same transforms addressed by handle (only filled when
+    /// `handle_transforms` is enabled).
+    pub transform_batch: EntityTransformBatch,
+    /// Handles bound since the previous tick; publish before the batch.
+    pub entity_handles: Vec<EntityHandle>,
+    /// Atmosphere update, present when due or after an explicit change.
+    pub environment: Option<WorldEnvironment>,
+    /// Participant pairs that came within proximity range this tick.
+    pub proximity_entered: Vec<ProximityEntered>,
+    /// Participant pairs that left proximity range this tick.
+    pub proximity_exited: Vec<ProximityExited>,
+    /// Terrain edits applied since the previous tick.
+    pub terrain_modified: Vec<TerrainModified>,
+    /// Areas whose path data went stale since the previous tick.
+    pub nav_invalidated: Vec<NavInvalidated>,
+    /// Display data that changed since the previous tick (sorted by id).
+    pub entity_meta: Vec<EntityMeta>,
+    /// Emotes played since the previous tick, in order.
+    pub emotes: Vec<EntityEmote>,
+    /// Contacts the physics step started this tick (sorted by pair).
+    pub collisions: Vec<EntityCollision>,
+    /// Structure scope changes, one entry per participant that moved cell.
+    pub structure_interest: Vec<StructureInterest>,
+    /// Participants that walked into another shard's super-region; they
+    /// are no longer tracked here (sorted by id).
+    pub handoffs: Vec<ShardHandoff>,
+    /// Scripted events that started since the previous tick.
+    pub events_started: Vec<ScriptedEvent>,
+    /// Scripted events that ended since the previous tick.
+    pub events_ended: Vec<ScriptedEventEnded>,
+    /// Ambient entities spawned by the population pass this tick.
+    pub entities_spawned: Vec<EntitySpawned>,
+    /// Ambient entities removed by the population pass this tick.
+    pub entities_removed: Vec<EntityRemoved>,
+    /// Structures and vegetation that entered the active region this tick.
+    pub structures_spawned: Vec<StructureSpawned>,
+    /// Structures and vegetation that left it this tick.
+    pub structures_removed: Vec<StructureRemoved>,
+    /// Interactive structures that changed state since the previous tick.
+    pub structure_states: Vec<StructureStateChanged>,
+    /// Active structures moved or re-described since the previous tick.
+    pub structures_updated: Vec<StructureUpdated>,
+    /// `intent.interact` outcomes (`world.interaction.result`).
+    pub interaction_results: Vec<InteractionResult>,
+    /// Changed cell subscriptions, with `cell_subjects` on.
+    pub interest_subjects: Vec<InterestSubjects>,
+}
+
+/// Diagnostic view of a single streaming cell (see [`WorldService::cell_info`]).
+#[derive(Debug, Clone)]
+pub struct CellInfo {
+    pub active: bool,
+    /// Physics body ids of the cell's terrain collider (several when holes
+    /// split it), if registered.
+    pub terrain_bodies: Vec<String>,
+    /// Object body ids registered in this cell.
+    pub objects: Vec<String>,
+    /// Registry structures overlapping this cell (sorted).
+    pub structures: Vec<String>,
+    /// Participants currently standing in this cell (sorted).
+    pub participants: Vec<String>,
+    /// Terrain height at the cell centre.
+    pub centre_height: f32,
 }
 
 pub struct WorldService {
     config: WorldServiceConfig,
     active_cells: HashSet<CellCoord>,
-    terrain_bodies: HashMap<CellCoord, String>,
+    /// LOD each active cell's terrain was streamed at.
+    cell_lods: HashMap<CellCoord, u8>,
+    /// Participant cells the LODs were last computed for (sorted).
+    lod_anchors: Vec<(i32, i32, i32)>,
+    /// Scratch buffer for this tick's anchors (kept for its capacity).
+    lod_anchors_scratch: Vec<(i32, i32, i32)>,
+    /// A LOD change is waiting for its chunk to be generated.
+    lod_change_pending: bool,
+    /// Cells to announce again next tick (their holes changed); ignored
+    /// unless still active by then.
+    pending_chunk_updates: HashSet<CellCoord>,
+    /// Terrain body ids of the active cells (several when holes split the
+    /// chunk's collider).
+    terrain_bodies: HashMap<CellCoord, Vec<String>>,
     cell_objects: HashMap<CellCoord, Vec<String>>,
+    /// Vegetation of the active cells, by id.
     world_objects: HashMap<String, WorldObject>,
-    participant_positions: HashMap<String, Vec3>,
+    /// The same objects bucketed by cell, for ray queries.
+    object_grid: SpatialGrid,
+    /// Largest collider bounding radius among the objects ever indexed in
+    /// `object_grid` (how far past a query an object can reach).
+    object_reach: f32,
+    /// World objects of the active cells changed since they were generated.
+    /// Each change is hibernated right away, so it survives the cell
+    /// deactivating as well as a restart while the cell is active.
+    modified_objects: HashSet<String>,
+    hibernation: HibernationStore,
+    /// Active cells overlapping each registry structure with a collider.
+    structure_cells: HashMap<String, usize>,
+    /// Structures and vegetation activated / deactivated since the last
+    /// tick.
+    pending_structures_spawned: Vec<StructureSpawned>,
+    pending_structures_removed: Vec<StructureRemoved>,
+    pending_structure_states: Vec<StructureStateChanged>,
+    pending_structures_updated: Vec<StructureUpdated>,
+    interaction_handlers: InteractionHandlers,
+    /// `intent.interact` outcomes since the last tick.
+    pending_interaction_results: Vec<InteractionResult>,
+    /// Keyed by interned ids, shared with every outgoing transform.
+    participant_positions: HashMap<Arc<str>, Vec3>,
+    /// The same positions bucketed by cell, for range queries.
+    participant_grid: SpatialGrid,
+    /// Positions as of the previous physics tick (transform velocities).
+    previous_positions: HashMap<Arc<str>, Vec3>,
     physics_registry: Arc<RwLock<PhysicsRegistry>>,
     world: Arc<World>,
+    environment: Environment,
+    last_environment_tick: u64,
+    proximity: ProximityTracker,
+    interest: InterestTracker,
+    /// Cell-scoped subscriptions (`None` unless `config.cell_subjects`).
+    cell_interest: Option<CellInterest>,
+    /// View radius each participant advertised (`intent.view_radius`).
+    view_radii: HashMap<String, f32>,
+    /// Super-region ownership (`None` = not sharded).
+    shards: Option<ShardMap>,
+    /// Tick each participant was handed off to another shard; it stays
+    /// here until that shard acknowledges (see `complete_handoff`).
+    pending_handoffs: HashMap<String, u64>,
+    /// Per-tick event counts, reported through `stats`.
+    fanout: FanoutMetrics,
+    /// Rejected intents and commands, reported through `stats`.
+    drops: DropCounters,
+    /// Every terrain edit so far (replayed to late joiners via snapshot).
+    terrain_modifications: Vec<TerrainModified>,
+    /// Edits not yet handed out through [`TickEvents`].
+    pending_terrain_modified: Vec<TerrainModified>,
+    /// Designer height override batches applied since startup.
+    height_override_edits: u64,
+    /// Last navigation invalidation revision handed out.
+    nav_revision: u64,
+    /// Invalidations not yet handed out through [`TickEvents`].
+    pending_nav_invalidated: Vec<NavInvalidated>,
+    /// Current display data per entity.
+    entity_meta: HashMap<String, EntityMeta>,
+    /// Ids whose display data changed since the last tick.
+    pending_entity_meta: BTreeSet<String>,
+    /// Tick of each entity's latest emote (rate limit).
+    last_emote: HashMap<String, u64>,
+    /// Emotes not yet handed out through [`TickEvents`].
+    pending_emotes: Vec<EntityEmote>,
+    /// Scripted world event schedule (empty unless one is loaded).
+    scheduler: EventScheduler,
+    /// Event starts/ends not yet handed out through [`TickEvents`].
+    pending_events_started: Vec<ScriptedEvent>,
+    pending_events_ended: Vec<ScriptedEventEnded>,
+    /// Weather before the first active event with one overrode it.
+    weather_before_events: Option<Weather>,
+    /// Ambient entities kept stocked per biome (no rules = none).
+    population: AmbientPopulation,
+    /// Tick of the latest population pass.
+    last_population_tick: u64,
+    /// Server-owned entities (NPCs, carts …); see [`entity`](crate::entity).
+    entities: EntityStore,
+    /// Server entities kept alive at fixed spots.
+    spawners: Spawners,
+    /// Server entities spawned since the last tick.
+    pending_entities_spawned: Vec<EntitySpawned>,
+    /// Ambient entities dropped with their rules and despawned server
+    /// entities, not yet announced.
+    pending_entities_removed: Vec<EntityRemoved>,
+    /// Background generation pool (`None` = generate inside the tick).
+    chunk_workers: Option<ChunkWorkers>,
+    /// Cells whose chunk has been queued but not reported done yet.
+    pending_cells: HashSet<CellCoord>,
+    /// Numeric wire handle per tracked entity.
+    entity_handles: HashMap<Arc<str>, u32>,
+    /// Handles assigned since the last tick.
+    pending_entity_handles: Vec<EntityHandle>,
+    /// Keyframe/delta state of published transform batches.
+    transform_encoder: TransformEncoder,
+    /// Last transform published per entity, for change tracking.
+    published_transforms: HashMap<Arc<str>, PublishedTransform>,
+    next_entity_handle: u32,
+    /// Highest acknowledged movement intent per participant.
+    last_intent_seq: HashMap<String, u64>,
+    /// Direction (length up to 1) each participant last asked to move in
+    /// with `intent.move`, applied every tick.
+    move_intents: HashMap<String, [f32; 2]>,
+    /// Tick of each participant's latest intent (or join).
+    last_activity: HashMap<String, u64>,
+    /// Participants idle past `config.afk.timeout_s`.
+    afk: HashSet<String>,
+    /// Resume token → entity id, one live token per joined participant.
+    resume_tokens: HashMap<String, String>,
+    /// Tick each departed participant left; its entity is kept for
+    /// `config.resume_grace_s` in case it reconnects.
+    departed: HashMap<String, u64>,
+    /// Where participants of a loaded world state were last seen; a fresh
+    /// join under one of these ids spawns there.
+    last_known_positions: HashMap<String, Vec3>,
+    /// Resume tokens issued so far (mixed into the next one).
+    resume_serial: u64,
+    /// Structures placed by participants so far (numbers their ids).
+    placed_serial: u64,
+    /// Simulated seconds per wall-clock second (see `set_timescale`).
+    timescale: f32,
+    /// Simulated seconds since startup.
+    sim_elapsed_s: f64,
+    /// Per-entity scale (absent = 1).
+    entity_scales: HashMap<String, f32>,
+    /// Scratch set for the cells wanted this tick (kept for its capacity).
+    desired_cells: HashSet<CellCoord>,
+    /// Tick of the latest streaming pass.
+    last_stream_tick: u64,
+    /// A participant joined; stream on the next tick instead of waiting
+    /// for the interval.
+    stream_pending: bool,
     tick_count: u64,
 }
 
@@ -49,41 +423,324 @@ impl WorldService {
         physics_registry: Arc<RwLock<PhysicsRegistry>>,
         world: Arc<World>,
     ) -> Self {
+        let environment = Environment::new(config.day_length_s, config.day_length_s * 0.25);
+        let proximity = ProximityTracker::new(
+            config.proximity_radius,
+            (config.proximity_cooldown_s / config.physics_dt).ceil() as u64,
+        );
+        let chunk_workers = Self::start_chunk_workers(&config, &world);
+        let shards = config.shard.as_ref().map(ShardMap::new);
+        let participant_grid = SpatialGrid::new(config.cell_size);
+        let object_grid = SpatialGrid::new(config.cell_size);
+        let transform_encoder = TransformEncoder::new(config.transform_keyframe_interval);
+        let interest = InterestTracker::with_tier_radii(
+            config.structure_near_radius,
+            config.structure_detail_radius,
+        );
+        let cell_interest = config
+            .cell_subjects
+            .then(|| CellInterest::new(config.interest_cell_size));
         Self {
             config,
             active_cells: HashSet::new(),
+            cell_lods: HashMap::new(),
+            lod_anchors: Vec::new(),
+            lod_anchors_scratch: Vec::new(),
+            lod_change_pending: false,
+            pending_chunk_updates: HashSet::new(),
             terrain_bodies: HashMap::new(),
             cell_objects: HashMap::new(),
             world_objects: HashMap::new(),
+            object_grid,
+            object_reach: 0.0,
+            modified_objects: HashSet::new(),
+            hibernation: HibernationStore::new(),
+            structure_cells: HashMap::new(),
+            pending_structures_spawned: Vec::new(),
+            pending_structure_states: Vec::new(),
+            pending_structures_updated: Vec::new(),
+            pending_structures_removed: Vec::new(),
+            interaction_handlers: InteractionHandlers::new(),
+            pending_interaction_results: Vec::new(),
             participant_positions: HashMap::new(),
+            participant_grid,
+            previous_positions: HashMap::new(),
             physics_registry,
             world,
+            environment,
+            last_environment_tick: 0,
+            proximity,
+            interest,
+            cell_interest,
+            view_radii: HashMap::new(),
+            shards,
+            pending_handoffs: HashMap::new(),
+            fanout: FanoutMetrics::default(),
+            drops: DropCounters::default(),
+            terrain_modifications: Vec::new(),
+            pending_terrain_modified: Vec::new(),
+            height_override_edits: 0,
+            nav_revision: 0,
+            pending_nav_invalidated: Vec::new(),
+            entity_meta: HashMap::new(),
+            pending_entity_meta: BTreeSet::new(),
+            last_emote: HashMap::new(),
+            pending_emotes: Vec::new(),
+            scheduler: EventScheduler::default(),
+            pending_events_started: Vec::new(),
+            pending_events_ended: Vec::new(),
+            weather_before_events: None,
+            population: AmbientPopulation::default(),
+            last_population_tick: 0,
+            entities: EntityStore::new(),
+            spawners: Spawners::default(),
+            pending_entities_spawned: Vec::new(),
+            pending_entities_removed: Vec::new(),
+            chunk_workers,
+            pending_cells: HashSet::new(),
+            entity_handles: HashMap::new(),
+            pending_entity_handles: Vec::new(),
+            transform_encoder,
+            published_transforms: HashMap::new(),
+            next_entity_handle: 0,
+            last_intent_seq: HashMap::new(),
+            move_intents: HashMap::new(),
+            last_activity: HashMap::new(),
+            afk: HashSet::new(),
+            resume_tokens: HashMap::new(),
+            departed: HashMap::new(),
+            last_known_positions: HashMap::new(),
+            resume_serial: 0,
+            placed_serial: 0,
+            timescale: 1.0,
+            sim_elapsed_s: 0.0,
+            entity_scales: HashMap::new(),
+            desired_cells: HashSet::new(),
+            last_stream_tick: 0,
+            stream_pending: false,
             tick_count: 0,
         }
     }
 
+    /// Worker pool that warms the terrain chunk cache off-tick.  Unchunked
+    /// backends report every chunk as cached, so nothing is ever queued.
+    fn start_chunk_workers(
+        config: &WorldServiceConfig,
+        world: &Arc<World>,
+    ) -> Option<ChunkWorkers> {
+        if config.generation_workers == 0 {
+            return None;
+        }
+        // Only the terrain: the service must stay the world's sole owner so
+        // placing structures doesn't copy it.
+        let terrain = Arc::clone(&world.terrain);
+        Some(ChunkWorkers::new(
+            config.generation_workers,
+            move |coord, lod| {
+                terrain.warm_chunk(coord.x, coord.y, lod);
+            },
+        ))
+    }
+
     // -----------------------------------------------------------------------
     // Participant management
     // -----------------------------------------------------------------------
 
+    /// Track participant or entity `id` at `position`, with a kinematic
+    /// body in the default simulation (when there is one) that
+    /// [`move_tracked`](Self::move_tracked) moves every tick.  Registering
+    /// a tracked id again moves it.
     pub fn register_participant(&mut self, id: String, position: Vec3) {
-        self.participant_positions.insert(id, position);
+        match self.participant_positions.get_key_value(id.as_str()) {
+            Some((id, _)) => {
+                let id = id.clone();
+                self.set_body_position(&id, position);
+                self.participant_grid.update(&id, position);
+                self.participant_positions.insert(id, position);
+            }
+            None => {
+                let id: Arc<str> = id.into();
+                let handle = self.next_entity_handle;
+                self.next_entity_handle += 1;
+                self.entity_handles.insert(id.clone(), handle);
+                self.pending_entity_handles.push(EntityHandle {
+                    entity_id: id.clone(),
+                    handle,
+                });
+                self.last_activity.insert(id.to_string(), self.tick_count);
+                self.participant_grid.update(&id, position);
+                self.participant_positions.insert(id.clone(), position);
+                self.stream_pending = true;
+
+                let mut registry = self.physics_registry.write();
+                if let Some(sim) = registry.default_simulation_mut() {
+                    let body = BodyParams::Kinematic {
+                        shape: ColliderShape::Circle {
+                            radius: self.bounding_radius(&id),
+                        },
+                        position: (position.x, position.y),
+                        rotation: 0.0,
+                    };
+                    if let Err(e) = sim.register_body(id.to_string(), body) {
+                        warn!("Failed to register body for {}: {}", id, e);
+                    }
+                }
+            }
+        }
     }
 
     pub fn unregister_participant(&mut self, id: &str) {
+        if self.participant_positions.contains_key(id) {
+            let mut registry = self.physics_registry.write();
+            if let Some(sim) = registry.default_simulation_mut() {
+                if let Err(e) = sim.unregister_body(id) {
+                    debug!("No body to unregister for {}: {}", id, e);
+                }
+            }
+        }
         self.participant_positions.remove(id);
+        self.previous_positions.remove(id);
+        self.pending_handoffs.remove(id);
+        self.published_transforms.remove(id);
+        self.participant_grid.remove(id);
+        self.interest.remove(id);
+        if let Some(cells) = &mut self.cell_interest {
+            cells.remove(id);
+        }
+        self.view_radii.remove(id);
+        if let Some(handle) = self.entity_handles.remove(id) {
+            self.pending_entity_handles.retain(|h| h.handle != handle);
+        }
+        self.entity_meta.remove(id);
+        self.pending_entity_meta.remove(id);
+        self.last_intent_seq.remove(id);
+        self.move_intents.remove(id);
+        self.last_activity.remove(id);
+        self.afk.remove(id);
+        self.entity_scales.remove(id);
+        self.last_emote.remove(id);
+        self.population.remove(id);
+        self.entities.remove(id);
+        self.spawners.forget(id, self.sim_elapsed_s);
+        self.departed.remove(id);
+        self.resume_tokens.retain(|_, entity| entity != id);
+    }
+
+    /// Register a joining participant (`world.participant.join`), or
+    /// reattach it to the entity `resume_token` was issued for.
+    ///
+    /// A resumed participant keeps that entity's id, body, position and
+    /// state; the join `position` is ignored.  This also covers reconnects
+    /// that arrive before the old connection's leave.  Unknown, used and
+    /// expired tokens fall back to a fresh join under `id`.
+    pub fn join_participant(
+        &mut self,
+        id: String,
+        position: Vec3,
+        resume_token: Option<&str>,
+    ) -> ParticipantJoined {
+        let previous = resume_token.and_then(|token| self.resume_tokens.remove(token));
+        let (id, resumed) = match previous {
+            Some(previous) if self.participant_positions.contains_key(previous.as_str()) => {
+                self.departed.remove(&previous);
+                self.mark_active(&previous);
+                (previous, true)
+            }
+            _ => {
+                let position = self.last_known_positions.remove(&id).unwrap_or(position);
+                self.register_participant(id.clone(), position);
+                (id, false)
+            }
+        };
+        self.resume_tokens.retain(|_, entity| *entity != id);
+        let resume_token = self.issue_resume_token(&id);
+        ParticipantJoined {
+            id,
+            resume_token,
+            resumed,
+        }
+    }
+
+    /// A participant disconnected (`world.participant.leave`).  Its entity
+    /// stays for `config.resume_grace_s` when it holds a resume token, and
+    /// is removed right away otherwise.
+    pub fn participant_left(&mut self, id: &str) {
+        let resumable = self.config.resume_grace_s > 0.0
+            && self.participant_positions.contains_key(id)
+            && self.resume_tokens.values().any(|entity| entity == id);
+        if resumable {
+            self.departed.insert(id.to_string(), self.tick_count);
+        } else {
+            self.unregister_participant(id);
+        }
+    }
+
+    /// `true` while a departed participant's entity waits for it to resume.
+    pub fn is_departed(&self, id: &str) -> bool {
+        self.departed.contains_key(id)
+    }
+
+    fn issue_resume_token(&mut self, id: &str) -> String {
+        self.resume_serial += 1;
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let key = format!("{}:{}:{}", id, self.resume_serial, nanos);
+        let token = format!("{:x}", md5::compute(key.as_bytes()));
+        self.resume_tokens.insert(token.clone(), id.to_string());
+        token
+    }
+
+    /// Remove departed participants whose grace window has run out.
+    fn expire_departed(&mut self) {
+        let grace = (self.config.resume_grace_s / self.config.physics_dt).ceil() as u64;
+        let expired: Vec<String> = self
+            .departed
+            .iter()
+            .filter(|(_, &since)| self.tick_count.saturating_sub(since) >= grace)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in expired {
+            debug!("Participant {} did not resume; removing its entity", id);
+            self.unregister_participant(&id);
+        }
+    }
+
+    /// Wire handle bound to a tracked entity.
+    pub fn entity_handle(&self, id: &str) -> Option<u32> {
+        self.entity_handles.get(id).copied()
+    }
+
+    /// `true` if `structure_id` is in the participant's interest scope (as
+    /// of the last tick).
+    pub fn structure_in_scope(&self, participant_id: &str, structure_id: &str) -> bool {
+        self.interest.in_scope(participant_id, structure_id)
+    }
+
+    /// The terrain and structures being served.
+    pub fn world(&self) -> &World {
+        &self.world
     }
 
     pub fn participant_count(&self) -> usize {
         self.participant_positions.len()
     }
 
+    pub fn participant_position(&self, id: &str) -> Option<Vec3> {
+        self.participant_positions.get(id).copied()
+    }
+
     /// Apply a coordinator-approved movement action for a participant.
     ///
     /// Preferred path: apply velocity to the participant's physics body.
     /// Fallback path: integrate in tracked participant positions when no
     /// simulation/body is available (keeps deterministic progress in minimal
     /// test and degraded runtime environments).
+    ///
+    /// With a sea level configured, each axis of the velocity that would
+    /// carry the participant into the sea this step is dropped, so walkers
+    /// slide along the coast instead of wading out.
     pub fn apply_move_action(
         &mut self,
         participant_id: &str,
@@ -91,37 +748,452 @@ impl WorldService {
         dy: f32,
         _dz: f32,
     ) -> janet::Result<()> {
-        if !self.participant_positions.contains_key(participant_id) {
-            return Err(janet::JanetError::Other(format!(
-                "Unknown participant_id '{}'",
-                participant_id
-            )));
+        let Some(&pos) = self.participant_positions.get(participant_id) else {
+            return Err(self.reject(
+                subjects::ACTION_MOVE,
+                participant_id,
+                DropReason::Invalid,
+                format!("Unknown participant_id '{}'", participant_id),
+            ));
+        };
+        self.mark_active(participant_id);
+        self.move_tracked(participant_id, pos, dx, dy);
+        Ok(())
+    }
+
+    /// Move participant or entity `id` to `position` at once
+    /// (`world.command.teleport`).
+    ///
+    /// Its physics body is moved and stopped too, so the next sync from the
+    /// registry doesn't pull it back.  The returned transform (zero
+    /// velocity, so clients snap rather than interpolate) is meant to be
+    /// published right away; streaming catches up on the next tick.
+    pub fn teleport(&mut self, id: &str, position: Vec3) -> janet::Result<EntityTransform> {
+        let Some(key) = self
+            .participant_positions
+            .get_key_value(id)
+            .map(|(key, _)| key.clone())
+        else {
+            return Err(self.reject(
+                subjects::mgmt::TELEPORT,
+                id,
+                DropReason::Invalid,
+                format!("Unknown entity '{}'", id),
+            ));
+        };
+        if ![position.x, position.y, position.z]
+            .iter()
+            .all(|v| v.is_finite())
+        {
+            return Err(self.reject(
+                subjects::mgmt::TELEPORT,
+                id,
+                DropReason::Invalid,
+                format!("Non-finite position {}", position),
+            ));
         }
 
-        // Try authoritative physics velocity first.
-        let mut applied_in_physics = false;
         {
             let mut registry = self.physics_registry.write();
             if let Some(sim) = registry.default_simulation_mut() {
-                if sim.set_velocity(participant_id, (dx, dy)).is_ok() {
-                    applied_in_physics = true;
+                let rotation = sim.get_transform(id).map_or(0.0, |t| t.rotation);
+                let moved = sim.set_transform(
+                    id,
+                    Transform {
+                        position: (position.x, position.y),
+                        rotation,
+                    },
+                );
+                match moved {
+                    Ok(()) => {
+                        if let Err(e) = sim.set_velocity(id, (0.0, 0.0)) {
+                            debug!("Could not stop teleported body {}: {}", id, e);
+                        }
+                    }
+                    // Entities without a body only live in the tracked positions.
+                    Err(e) => debug!("No physics body to teleport for {}: {}", id, e),
                 }
             }
         }
 
-        if applied_in_physics {
-            return Ok(());
+        self.participant_grid.update(&key, position);
+        self.participant_positions.insert(key.clone(), position);
+        self.previous_positions.insert(key.clone(), position);
+        self.stream_pending = true;
+        info!("Teleported {} to {}", id, position);
+        Ok(self.entity_transform(&key, position))
+    }
+
+    /// Record the view radius (metres) a participant's client advertised
+    /// (`intent.view_radius`).  With `cell_subjects` on it narrows the
+    /// interest cells the client is told to subscribe to; it never widens
+    /// them past the streaming window.
+    pub fn set_view_radius(&mut self, participant_id: &str, radius: f32) -> janet::Result<()> {
+        if !self.participant_positions.contains_key(participant_id) {
+            return Err(self.reject(
+                subjects::INTENT_VIEW_RADIUS,
+                participant_id,
+                DropReason::Invalid,
+                format!("Unknown participant_id '{}'", participant_id),
+            ));
+        }
+        if !(radius.is_finite() && radius >= 0.0) {
+            return Err(self.reject(
+                subjects::INTENT_VIEW_RADIUS,
+                participant_id,
+                DropReason::Invalid,
+                format!("View radius must be zero or positive, got {}", radius),
+            ));
+        }
+        self.view_radii.insert(participant_id.to_string(), radius);
+        self.stream_pending = true;
+        Ok(())
+    }
+
+    pub fn view_radius(&self, participant_id: &str) -> Option<f32> {
+        self.view_radii.get(participant_id).copied()
+    }
+
+    /// Side of the interest cells events are scoped to (`None` when
+    /// `cell_subjects` is off).
+    pub fn interest_cell_size(&self) -> Option<f32> {
+        self.cell_interest.as_ref().map(CellInterest::cell_size)
+    }
+
+    /// Hold a client's `intent.move` until its next one: every tick the
+    /// participant moves along `(dx, dy)` at up to `max_move_speed`
+    /// (directions longer than 1 are shortened to 1, a zero one stops it).
+    /// `dz` is ignored; the ground decides height.
+    pub fn set_move_intent(
+        &mut self,
+        participant_id: &str,
+        intent: &IntentMove,
+    ) -> janet::Result<()> {
+        if !self.participant_positions.contains_key(participant_id)
+            || self.is_server_entity(participant_id)
+        {
+            return Err(self.reject(
+                subjects::INTENT_MOVE,
+                participant_id,
+                DropReason::Invalid,
+                format!("Unknown participant_id '{}'", participant_id),
+            ));
+        }
+        if !(intent.dx.is_finite() && intent.dy.is_finite()) {
+            return Err(self.reject(
+                subjects::INTENT_MOVE,
+                participant_id,
+                DropReason::Invalid,
+                format!("Non-finite direction ({}, {})", intent.dx, intent.dy),
+            ));
+        }
+        let length = intent.dx.hypot(intent.dy);
+        let scale = if length > 1.0 { 1.0 / length } else { 1.0 };
+        self.move_intents.insert(
+            participant_id.to_string(),
+            [intent.dx * scale, intent.dy * scale],
+        );
+        self.mark_active(participant_id);
+        if let Some(seq) = intent.seq {
+            self.acknowledge_intent(participant_id, seq);
+        }
+        Ok(())
+    }
+
+    /// Move every participant along its held `intent.move` for one tick.
+    /// Stopped participants are moved (at zero velocity) once more, then
+    /// forgotten.
+    fn apply_move_intents(&mut self) {
+        if self.move_intents.is_empty() {
+            return;
+        }
+        let speed = self.config.max_move_speed;
+        let mut intents: Vec<_> = self
+            .move_intents
+            .iter()
+            .map(|(id, &direction)| (id.clone(), direction))
+            .collect();
+        intents.sort_by(|a, b| a.0.cmp(&b.0));
+        for (id, [dx, dy]) in intents {
+            if let Some(&pos) = self.participant_positions.get(id.as_str()) {
+                self.move_tracked(&id, pos, dx * speed, dy * speed);
+            }
+        }
+        self.move_intents
+            .retain(|_, direction| *direction != [0.0, 0.0]);
+    }
+
+    /// Move tracked entity `id` (at `pos`) with velocity `(dx, dy)` for one
+    /// tick, kept out of the sea and inside the world bounds.  Walking
+    /// follows the ground: the height is the terrain's where the step ends.
+    ///
+    /// Nothing steps the simulation in this process, so kinematic bodies are
+    /// moved to where the step ends, with the velocity set to match for
+    /// whatever reads it from the simulation.
+    fn move_tracked(&mut self, id: &str, pos: Vec3, dx: f32, dy: f32) {
+        let (dx, dy) = self.clamp_to_shore(pos, dx, dy);
+        let (dx, dy) = self.clamp_to_bounds(pos, dx, dy);
+        let Some(key) = self
+            .participant_positions
+            .get_key_value(id)
+            .map(|(key, _)| key.clone())
+        else {
+            return;
+        };
+        let dt = self.sim_dt();
+        let (x, y) = (pos.x + dx * dt, pos.y + dy * dt);
+        let pos = Vec3::new(x, y, self.world.terrain.height_at(x, y));
+
+        {
+            let mut registry = self.physics_registry.write();
+            if let Some(sim) = registry.default_simulation_mut() {
+                // The simulation runs in wall-clock time.
+                let velocity = (dx * self.timescale, dy * self.timescale);
+                // Entities without a body only live in the tracked positions.
+                let _ = sim.set_velocity(id, velocity);
+            }
+        }
+        self.set_body_position(id, pos);
+        self.participant_grid.update(&key, pos);
+        self.participant_positions.insert(key, pos);
+    }
+
+    /// Move the body of `id`, if it has one, to `pos` (keeping its
+    /// rotation).
+    fn set_body_position(&self, id: &str, pos: Vec3) {
+        let mut registry = self.physics_registry.write();
+        if let Some(sim) = registry.default_simulation_mut() {
+            if let Ok(t) = sim.get_transform(id) {
+                let moved = sim.set_transform(
+                    id,
+                    Transform {
+                        position: (pos.x, pos.y),
+                        rotation: t.rotation,
+                    },
+                );
+                if let Err(e) = moved {
+                    warn!("Failed to move body {}: {}", id, e);
+                }
+            }
         }
+    }
+
+    /// Zero the velocity components whose step ends below the sea level and
+    /// deeper than the ground at `pos` (climbing out is always allowed).
+    fn clamp_to_shore(&self, pos: Vec3, dx: f32, dy: f32) -> (f32, f32) {
+        let Some(sea) = self.config.sea_level else {
+            return (dx, dy);
+        };
+        let terrain = &self.world.terrain;
+        let here = terrain.height_at(pos.x, pos.y);
+        let dt = self.sim_dt();
+        let blocked = |x: f32, y: f32| {
+            let h = terrain.height_at(x, y);
+            h < sea && h < here
+        };
+        let dx = if blocked(pos.x + dx * dt, pos.y) {
+            0.0
+        } else {
+            dx
+        };
+        let dy = if blocked(pos.x, pos.y + dy * dt) {
+            0.0
+        } else {
+            dy
+        };
+        (dx, dy)
+    }
+
+    /// Zero the velocity components whose step ends outside the world
+    /// bounds (stepping back in is always allowed).
+    fn clamp_to_bounds(&self, pos: Vec3, dx: f32, dy: f32) -> (f32, f32) {
+        let Some(bounds) = &self.config.world_bounds else {
+            return (dx, dy);
+        };
+        let dt = self.sim_dt();
+        let cell_size = self.config.cell_size;
+        let outside = |x: f32, y: f32| {
+            bounds.cells.distance_outside(
+                (x / cell_size).floor() as i32,
+                (y / cell_size).floor() as i32,
+            )
+        };
+        let here = outside(pos.x, pos.y);
+        let dx = if outside(pos.x + dx * dt, pos.y) > here {
+            0.0
+        } else {
+            dx
+        };
+        let dy = if outside(pos.x, pos.y + dy * dt) > here {
+            0.0
+        } else {
+            dy
+        };
+        (dx, dy)
+    }
 
-        // Fallback integration path when no body/simulation is available.
-        if let Some(pos) = self.participant_positions.get_mut(participant_id) {
-            pos.x += dx * self.config.physics_dt;
-            pos.y += dy * self.config.physics_dt;
+    /// Record that movement intent `seq` from `participant_id` has been
+    /// applied; reported back in that participant's transforms.  Sequence
+    /// numbers never go backwards (late or duplicate intents are ignored).
+    pub fn acknowledge_intent(&mut self, participant_id: &str, seq: u64) {
+        if !self.participant_positions.contains_key(participant_id) {
+            return;
         }
+        let last = self
+            .last_intent_seq
+            .entry(participant_id.to_string())
+            .or_insert(seq);
+        *last = (*last).max(seq);
+    }
+
+    pub fn last_intent_seq(&self, participant_id: &str) -> Option<u64> {
+        self.last_intent_seq.get(participant_id).copied()
+    }
+
+    // -----------------------------------------------------------------------
+    // Entity size
+    // -----------------------------------------------------------------------
 
+    /// Set a tracked entity's uniform scale (reported in spawn messages and
+    /// applied to its bounding radius).
+    pub fn set_entity_scale(&mut self, id: &str, scale: f32) -> janet::Result<()> {
+        if !self.participant_positions.contains_key(id) {
+            return Err(janet::JanetError::Other(format!("Unknown entity '{}'", id)));
+        }
+        if !(scale.is_finite() && scale > 0.0) {
+            return Err(janet::JanetError::Other(format!(
+                "Scale must be positive, got {}",
+                scale
+            )));
+        }
+        self.entity_scales.insert(id.to_string(), scale);
         Ok(())
     }
 
+    pub fn entity_scale(&self, id: &str) -> f32 {
+        self.entity_scales.get(id).copied().unwrap_or(1.0)
+    }
+
+    /// Bounding radius of an archetype's collider at scale 1 (`0` when the
+    /// archetype has no collider configured).
+    fn archetype_radius(&self, archetype: &str) -> f32 {
+        self.config
+            .archetype_colliders
+            .get(archetype)
+            .map_or(0.0, collider_bounding_radius)
+    }
+
+    /// Archetype of a tracked entity: its server entity's or ambient
+    /// rule's, else [`PARTICIPANT_ARCHETYPE`].
+    fn archetype_of(&self, id: &str) -> &str {
+        match self.entities.get(id) {
+            Some(e) => &e.archetype,
+            None => self
+                .population
+                .archetype(id)
+                .unwrap_or(PARTICIPANT_ARCHETYPE),
+        }
+    }
+
+    /// Scaled bounding radius of a tracked entity.
+    pub fn bounding_radius(&self, id: &str) -> f32 {
+        self.archetype_radius(self.archetype_of(id)) * self.entity_scale(id)
+    }
+
+    /// Gap between two entities' bounding circles (`0` when they overlap),
+    /// or `None` if either is unknown.
+    pub fn surface_distance(&self, a: &str, b: &str) -> Option<f32> {
+        let pa = self.participant_positions.get(a)?;
+        let pb = self.participant_positions.get(b)?;
+        let (dx, dy, dz) = (pa.x - pb.x, pa.y - pb.y, pa.z - pb.z);
+        let centres = (dx * dx + dy * dy + dz * dz).sqrt();
+        Some((centres - self.bounding_radius(a) - self.bounding_radius(b)).max(0.0))
+    }
+
+    /// `true` if `actor` can reach `target` with an interaction of `reach`
+    /// world units (measured between bounding circles, so big targets are
+    /// easier to reach).
+    pub fn in_interaction_range(&self, actor: &str, target: &str, reach: f32) -> bool {
+        self.surface_distance(actor, target)
+            .is_some_and(|d| d <= reach)
+    }
+
+    // -----------------------------------------------------------------------
+    // AFK
+    // -----------------------------------------------------------------------
+
+    /// `true` while the participant's streaming is downgraded for idleness.
+    pub fn is_afk(&self, participant_id: &str) -> bool {
+        self.afk.contains(participant_id)
+    }
+
+    /// Restart the idle timer; an AFK participant gets full streaming back.
+    fn mark_active(&mut self, participant_id: &str) {
+        self.last_activity
+            .insert(participant_id.to_string(), self.tick_count);
+        if self.afk.remove(participant_id) {
+            self.set_afk_flag(participant_id, false);
+        }
+    }
+
+    /// Flag every participant that has been idle for the AFK timeout.
+    fn update_afk(&mut self) {
+        let Some(afk) = self.config.afk else {
+            return;
+        };
+        let timeout = (afk.timeout_s / self.config.physics_dt).ceil() as u64;
+        let idle: Vec<String> = self
+            .last_activity
+            .iter()
+            .filter(|(id, &since)| {
+                self.tick_count.saturating_sub(since) >= timeout && !self.afk.contains(*id)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in idle {
+            self.set_afk_flag(&id, true);
+            self.afk.insert(id);
+        }
+    }
+
+    /// Publish the `afk` flag through the participant's entity meta.
+    fn set_afk_flag(&mut self, participant_id: &str, afk: bool) {
+        let meta = self
+            .entity_meta
+            .entry(participant_id.to_string())
+            .or_insert_with(|| EntityMeta {
+                entity_id: participant_id.to_string(),
+                ..Default::default()
+            });
+        if meta.afk != afk {
+            meta.afk = afk;
+            self.pending_entity_meta.insert(participant_id.to_string());
+        }
+    }
+
+    /// Streaming radius (cells) for one participant.
+    fn activation_radius_for(&self, participant_id: &str) -> i32 {
+        match &self.config.afk {
+            Some(afk) if self.afk.contains(participant_id) => {
+                afk.activation_radius.min(self.config.activation_radius)
+            }
+            _ => self.config.activation_radius,
+        }
+    }
+
+    /// `false` on the ticks an AFK participant's transform is skipped.
+    fn transform_due(&self, participant_id: &str) -> bool {
+        match &self.config.afk {
+            Some(afk) if self.afk.contains(participant_id) => {
+                let every = (afk.transform_interval_s / self.config.physics_dt)
+                    .round()
+                    .max(1.0) as u64;
+                self.tick_count.is_multiple_of(every)
+            }
+            _ => true,
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Main tick
     // -----------------------------------------------------------------------
@@ -131,70 +1203,2449 @@ impl WorldService {
     /// Returns [`TickEvents`] describing every state change that occurred so
     /// the bus agent can publish the corresponding protocol messages.
     pub fn tick(&mut self) -> janet::Result<TickEvents> {
+        let mut events = TickEvents::default();
+        self.tick_into(&mut events)?;
+        Ok(events)
+    }
+
+    /// [`tick`](Self::tick) into a caller-owned buffer.
+    ///
+    /// `events` is cleared first; its vectors keep their capacity, so a
+    /// steady-state tick allocates nothing for event collection.
+    pub fn tick_into(&mut self, events: &mut TickEvents) -> janet::Result<()> {
+        events.activated.clear();
+        events.deactivated.clear();
+        events.chunk_voxels.clear();
+        events.chunk_data.clear();
+        events.entity_transforms.clear();
+        events.transform_batch.clear();
+        events.entity_handles.clear();
+        events.proximity_entered.clear();
+        events.proximity_exited.clear();
+        events.terrain_modified.clear();
+        events.nav_invalidated.clear();
+        events.entity_meta.clear();
+        events.emotes.clear();
+        events.collisions.clear();
+        events.structure_interest.clear();
+        events.interest_subjects.clear();
+        events.handoffs.clear();
+        events.events_started.clear();
+        events.events_ended.clear();
+        events.entities_spawned.clear();
+        events.entities_removed.clear();
+        events.structures_spawned.clear();
+        events.structures_removed.clear();
+        events.structure_states.clear();
+        events.structures_updated.clear();
+        events.interaction_results.clear();
+
         self.tick_count += 1;
+        self.sim_elapsed_s += self.sim_dt() as f64;
+        events.tick = self.tick_count;
         self.sync_positions_from_registry();
+        self.steer_entities();
+        self.move_entities();
+        self.apply_move_intents();
+        self.step_physics(&mut events.collisions);
+        self.collect_handoffs(&mut events.handoffs);
+        self.update_afk();
+        self.expire_departed();
+        self.poll_scheduled_events();
+
+        let streaming = self.stream_due();
+        if streaming {
+            self.last_stream_tick = self.tick_count;
+            self.stream_pending = false;
+            let mut desired = std::mem::take(&mut self.desired_cells);
+            self.compute_active_cells(&mut desired);
+            let result = self.stream_cells(&desired, events);
+            self.desired_cells = desired;
+            result?;
+            if self.config.send_chunk_heights {
+                for c in &events.activated {
+                    events
+                        .chunk_data
+                        .extend(self.chunk_data_event(c.cx, c.cy, c.lod));
+                }
+            }
+        }
+        events
+            .structures_removed
+            .append(&mut self.pending_structures_removed);
+        events
+            .structures_spawned
+            .append(&mut self.pending_structures_spawned);
+        events
+            .structure_states
+            .append(&mut self.pending_structure_states);
+        events
+            .structures_updated
+            .append(&mut self.pending_structures_updated);
+        events
+            .interaction_results
+            .append(&mut self.pending_interaction_results);
+        self.run_spawners();
+        events
+            .entities_removed
+            .append(&mut self.pending_entities_removed);
+        events
+            .entities_spawned
+            .append(&mut self.pending_entities_spawned);
+        if self.population_due() {
+            self.last_population_tick = self.tick_count;
+            self.populate(&mut events.entities_spawned, &mut events.entities_removed);
+        }
+
+        events
+            .entity_handles
+            .append(&mut self.pending_entity_handles);
+        if self.config.handle_transforms {
+            self.collect_handle_transforms(&mut events.transform_batch.transforms);
+            self.transform_encoder.encode(&mut events.transform_batch);
+        } else {
+            self.collect_entity_transforms(&mut events.entity_transforms);
+        }
+        events.environment = self.tick_environment();
+        let base_radius = self.archetype_radius(PARTICIPANT_ARCHETYPE);
+        let scales = &self.entity_scales;
+        let (entered, exited) =
+            self.proximity
+                .update_sized(self.tick_count, &self.participant_positions, |id| {
+                    base_radius * scales.get(id).copied().unwrap_or(1.0)
+                });
+        events.proximity_entered.extend(entered);
+        events.proximity_exited.extend(exited);
+        events
+            .terrain_modified
+            .append(&mut self.pending_terrain_modified);
+        events
+            .nav_invalidated
+            .append(&mut self.pending_nav_invalidated);
+        self.drain_entity_meta(&mut events.entity_meta);
+        events.emotes.append(&mut self.pending_emotes);
+        events
+            .events_started
+            .append(&mut self.pending_events_started);
+        events.events_ended.append(&mut self.pending_events_ended);
+        if streaming {
+            for (id, pos) in &self.participant_positions {
+                if self.is_server_entity(id) {
+                    continue;
+                }
+                let radius = self.activation_radius_for(id);
+                let change = self.interest.update(
+                    id,
+                    *pos,
+                    self.config.cell_size,
+                    radius,
+                    &self.world.structures,
+                );
+                events.structure_interest.extend(change);
+                if let Some(cells) = &mut self.cell_interest {
+                    let window = radius as f32 * self.config.cell_size;
+                    let view = self.view_radii.get(&**id).map_or(window, |r| r.min(window));
+                    events
+                        .interest_subjects
+                        .extend(cells.update(id, *pos, view));
+                }
+            }
+        }
+        self.previous_positions.clear();
+        self.previous_positions.extend(
+            self.participant_positions
+                .iter()
+                .map(|(id, pos)| (id.clone(), *pos)),
+        );
+        self.fanout.record(events);
+        Ok(())
+    }
+
+    /// Physics ticks between streaming passes.
+    pub fn stream_interval_ticks(&self) -> u64 {
+        (self.config.stream_interval_s / self.config.physics_dt)
+            .round()
+            .max(1.0) as u64
+    }
+
+    /// `true` on the ticks that diff cells: every `stream_interval_s`, or
+    /// right away after a join or a chunk change so nobody waits for
+    /// terrain.
+    fn stream_due(&self) -> bool {
+        self.stream_pending
+            || !self.pending_chunk_updates.is_empty()
+            || self.tick_count - self.last_stream_tick >= self.stream_interval_ticks()
+    }
+
+    // -----------------------------------------------------------------------
+    // Sharding
+    // -----------------------------------------------------------------------
+
+    /// This instance's shard id, when sharded.
+    pub fn shard_id(&self) -> Option<&str> {
+        self.shards.as_ref().map(ShardMap::shard_id)
+    }
+
+    /// Hand off every participant standing in another shard's super-region,
+    /// describing each in `out` (sorted by id).  Server entities stay with
+    /// the shard that spawned them.
+    ///
+    /// A handed-off participant keeps being simulated here until the target
+    /// shard acknowledges (`complete_handoff`); one still unacknowledged
+    /// after `handoff_timeout_s` falls back to this shard and is offered
+    /// again.
+    pub fn collect_handoffs(&mut self, out: &mut Vec<ShardHandoff>) {
+        let Some(shards) = &self.shards else {
+            return;
+        };
+        let timeout = (shards.handoff_timeout_s() / self.config.physics_dt).ceil() as u64;
+        let tick = self.tick_count;
+        self.pending_handoffs.retain(|id, &mut since| {
+            let waiting = tick.saturating_sub(since) < timeout;
+            if !waiting {
+                warn!("Handoff of '{}' was not acknowledged; keeping it", id);
+            }
+            waiting
+        });
+        let start = out.len();
+        for (id, pos) in &self.participant_positions {
+            if self.pending_handoffs.contains_key(id.as_ref()) || self.is_server_entity(id) {
+                continue;
+            }
+            if let Some(target) = shards.handoff_target(*pos) {
+                out.push(ShardHandoff {
+                    participant_id: id.to_string(),
+                    from_shard: shards.shard_id().to_string(),
+                    to_shard: target.to_string(),
+                    x: pos.x,
+                    y: pos.y,
+                    z: pos.z,
+                    meta: self.entity_meta.get(id.as_ref()).cloned(),
+                    last_intent_seq: self.last_intent_seq.get(id.as_ref()).copied(),
+                });
+            }
+        }
+        out[start..].sort_by(|a, b| a.participant_id.cmp(&b.participant_id));
+        for handoff in &out[start..] {
+            self.pending_handoffs
+                .insert(handoff.participant_id.clone(), tick);
+        }
+    }
+
+    /// Drop a participant the target shard has taken over.  Returns `false`
+    /// (and does nothing) unless the ack answers a handoff from this shard
+    /// that is still pending.
+    pub fn complete_handoff(&mut self, ack: &ShardHandoffAck) -> bool {
+        if self.shard_id() != Some(ack.from_shard.as_str())
+            || self.pending_handoffs.remove(&ack.participant_id).is_none()
+        {
+            return false;
+        }
+        self.unregister_participant(&ack.participant_id);
+        true
+    }
+
+    /// Participants handed off and awaiting the target shard's ack.
+    pub fn pending_handoff_count(&self) -> usize {
+        self.pending_handoffs.len()
+    }
+
+    /// Take over a participant handed off by another shard.  Returns
+    /// `false` (and does nothing) unless the handoff targets this shard.
+    pub fn accept_handoff(&mut self, handoff: &ShardHandoff) -> bool {
+        if self.shard_id() != Some(handoff.to_shard.as_str()) {
+            return false;
+        }
+        self.register_participant(
+            handoff.participant_id.clone(),
+            Vec3::new(handoff.x, handoff.y, handoff.z),
+        );
+        if let Some(meta) = &handoff.meta {
+            if let Err(e) = self.set_entity_meta(meta.clone()) {
+                warn!("Dropping handed-off entity meta: {}", e);
+            }
+        }
+        if let Some(seq) = handoff.last_intent_seq {
+            self.acknowledge_intent(&handoff.participant_id, seq);
+        }
+        true
+    }
+
+    /// Activate / deactivate cells so the active set matches `desired`.
+    fn stream_cells(
+        &mut self,
+        desired: &HashSet<CellCoord>,
+        events: &mut TickEvents,
+    ) -> janet::Result<()> {
+        let to_deactivate: Vec<_> = self.active_cells.difference(desired).cloned().collect();
+        for c in to_deactivate {
+            events.deactivated.push(self.deactivate_cell(&c)?);
+        }
+
+        // Pending -> ready: forget finished jobs and cells nobody wants now.
+        if let Some(workers) = &self.chunk_workers {
+            for c in workers.completed() {
+                self.pending_cells.remove(&c);
+            }
+        }
+        self.pending_cells.retain(|c| desired.contains(c));
 
-        let desired = self.compute_active_cells();
+        // Nearest chunks first so clients can mesh what the player sees soonest.
+        let mut to_activate: Vec<_> = desired.difference(&self.active_cells).cloned().collect();
+        to_activate.sort_by_key(|c| (self.cell_priority(c), c.x, c.y));
+        for c in to_activate {
+            let lod = self.cell_lod(&c);
+            if !self.chunk_ready(&c, lod) {
+                self.request_chunk(c, lod);
+                continue;
+            }
+            if let Some(ev) = self.activate_cell(c, lod)? {
+                events.activated.push(ev);
+                events.chunk_voxels.extend(self.chunk_voxels_event(&c));
+            }
+        }
+        self.update_cell_lods(events)?;
+
+        if !self.pending_chunk_updates.is_empty() {
+            let mut updated: Vec<_> = self
+                .pending_chunk_updates
+                .drain()
+                .filter(|c| self.active_cells.contains(c))
+                .collect();
+            updated.sort_by_key(|c| (self.cell_priority(c), c.x, c.y));
+            for c in updated {
+                events.activated.push(self.chunk_activated_event(&c));
+            }
+        }
+        Ok(())
+    }
+
+    /// Move active cells whose nearest participant crossed into another LOD
+    /// band to that LOD: swap in the matching terrain collider and announce
+    /// the chunk again.  Only recomputed when some participant changed cell
+    /// (or a previous change is still waiting for generation).
+    fn update_cell_lods(&mut self, events: &mut TickEvents) -> janet::Result<()> {
+        let mut anchors = std::mem::take(&mut self.lod_anchors_scratch);
+        anchors.clear();
+        anchors.extend(
+            self.participant_positions
+                .iter()
+                .filter(|(id, _)| !self.is_server_entity(id))
+                .map(|(_, pos)| {
+                    (
+                        (pos.x / self.config.cell_size).floor() as i32,
+                        (pos.y / self.config.cell_size).floor() as i32,
+                        self.layer_of(pos.z),
+                    )
+                }),
+        );
+        anchors.sort_unstable();
+        anchors.dedup();
+        if anchors == self.lod_anchors && !self.lod_change_pending {
+            self.lod_anchors_scratch = anchors;
+            return Ok(());
+        }
+        self.lod_anchors_scratch = std::mem::replace(&mut self.lod_anchors, anchors);
+        self.lod_change_pending = false;
+
+        let mut changed: Vec<_> = self
+            .active_cells
+            .iter()
+            .filter_map(|c| {
+                let lod = self.cell_lod(c);
+                (self.cell_lods.get(c) != Some(&lod)).then_some((*c, lod))
+            })
+            .collect();
+        changed.sort_by_key(|(c, _)| (self.cell_priority(c), c.x, c.y));
+        let mut ready = Vec::with_capacity(changed.len());
+        for (c, lod) in changed {
+            if !self.chunk_ready(&c, lod) {
+                self.request_chunk(c, lod);
+                self.lod_change_pending = true;
+                continue;
+            }
+            self.cell_lods.insert(c, lod);
+            ready.push(c);
+        }
+        self.refresh_terrain_bodies(&ready)?;
+        for c in &ready {
+            debug!("Cell {} now streams at LOD {}", c, self.cell_lods[c]);
+            events.activated.push(self.chunk_activated_event(c));
+        }
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Entity display data
+    // -----------------------------------------------------------------------
+
+    /// Replace an entity's nameplate/HUD data.
+    ///
+    /// Health is clamped to `0..=1`.  Changes are coalesced and handed out
+    /// once through the next [`TickEvents::entity_meta`]; setting the same
+    /// value again queues nothing.
+    pub fn set_entity_meta(&mut self, mut meta: EntityMeta) -> janet::Result<EntityMeta> {
+        if !self
+            .participant_positions
+            .contains_key(meta.entity_id.as_str())
+        {
+            let message = format!("Unknown entity '{}'", meta.entity_id);
+            return Err(self.reject(
+                subjects::CMD_ENTITY_META,
+                &meta.entity_id,
+                DropReason::Invalid,
+                message,
+            ));
+        }
+        meta.health = meta
+            .health
+            .filter(|h| h.is_finite())
+            .map(|h| h.clamp(0.0, 1.0));
+        meta.afk = self.afk.contains(&meta.entity_id);
+
+        if self.entity_meta.get(&meta.entity_id) != Some(&meta) {
+            self.pending_entity_meta.insert(meta.entity_id.clone());
+            self.entity_meta
+                .insert(meta.entity_id.clone(), meta.clone());
+        }
+        Ok(meta)
+    }
+
+    pub fn entity_meta(&self, id: &str) -> Option<&EntityMeta> {
+        self.entity_meta.get(id)
+    }
+
+    fn drain_entity_meta(&mut self, out: &mut Vec<EntityMeta>) {
+        let pending = std::mem::take(&mut self.pending_entity_meta);
+        out.extend(
+            pending
+                .into_iter()
+                .filter_map(|id| self.entity_meta.get(&id).cloned()),
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Emotes
+    // -----------------------------------------------------------------------
+
+    /// Seconds until `entity_id` may emote again (`0` = now).
+    pub fn emote_cooldown_s(&self, entity_id: &str) -> f32 {
+        let interval = (self.config.emote_interval_s / self.config.physics_dt).ceil() as u64;
+        self.last_emote.get(entity_id).map_or(0.0, |&last| {
+            (last + interval).saturating_sub(self.tick_count) as f32 * self.config.physics_dt
+        })
+    }
+
+    /// Play an emote and/or sound on a tracked entity.
+    ///
+    /// Listeners are the participants within `emote_range` of the entity,
+    /// found through the position grid.  Each entity may emote once per
+    /// `emote_interval_s`; faster requests are rejected.  The event is
+    /// queued for the next tick's `world.entity.emote` broadcast.
+    pub fn emote(&mut self, cmd: CmdEmote) -> janet::Result<EntityEmote> {
+        let Some(&pos) = self.participant_positions.get(cmd.entity_id.as_str()) else {
+            let message = format!("Unknown entity '{}'", cmd.entity_id);
+            return Err(self.reject(
+                subjects::CMD_EMOTE,
+                &cmd.entity_id,
+                DropReason::Invalid,
+                message,
+            ));
+        };
+        if cmd.emote_id.is_none() && cmd.sound_id.is_none() {
+            return Err(self.reject(
+                subjects::CMD_EMOTE,
+                &cmd.entity_id,
+                DropReason::Invalid,
+                "Emote needs an emote_id or a sound_id".into(),
+            ));
+        }
+        let interval = (self.config.emote_interval_s / self.config.physics_dt).ceil() as u64;
+        if let Some(&last) = self.last_emote.get(&cmd.entity_id) {
+            if self.tick_count < last + interval {
+                let message = format!("Entity '{}' is emoting too fast", cmd.entity_id);
+                return Err(self.reject(
+                    subjects::CMD_EMOTE,
+                    &cmd.entity_id,
+                    DropReason::RateLimited,
+                    message,
+                ));
+            }
+        }
+
+        let range = self.config.emote_range;
+        let mut listeners: Vec<String> = self
+            .participant_grid
+            .candidates(pos.x, pos.y, range)
+            .into_iter()
+            .filter(|id| {
+                self.participant_positions.get(*id).is_some_and(|p| {
+                    let (dx, dy, dz) = (p.x - pos.x, p.y - pos.y, p.z - pos.z);
+                    dx * dx + dy * dy + dz * dz <= range * range
+                })
+            })
+            .map(|id| id.to_string())
+            .collect();
+        listeners.sort();
+
+        self.last_emote
+            .insert(cmd.entity_id.clone(), self.tick_count);
+        let event = EntityEmote {
+            entity_id: cmd.entity_id,
+            emote_id: cmd.emote_id,
+            sound_id: cmd.sound_id,
+            x: pos.x,
+            y: pos.y,
+            z: pos.z,
+            listeners,
+        };
+        self.pending_emotes.push(event.clone());
+        Ok(event)
+    }
+
+    // -----------------------------------------------------------------------
+    // Building
+    // -----------------------------------------------------------------------
+
+    /// Place a structure for `placer` (`action.place_structure`).
+    ///
+    /// The structure is snapped to the ground at `(x, y)` and must be a
+    /// configured buildable, within `build_reach` of the placer, inside the
+    /// world bounds, on ground its `max_slope` allows, and clear of other
+    /// structures, vegetation and entities.  It joins the world's
+    /// structure registry (with `type_id` and `owner` metadata); if it
+    /// overlaps an active cell its collider is registered and its
+    /// `world.structure.spawned` queued for the next tick, otherwise it
+    /// streams in with its cells.
+    pub fn place_structure(
+        &mut self,
+        placer: &str,
+        intent: IntentPlaceStructure,
+    ) -> Result<StructureSpawned, PlacementError> {
+        let result = self.check_placement(placer, &intent);
+        let buildable = match result {
+            Ok(buildable) => buildable,
+            Err(e) => {
+                self.record_drop(
+                    subjects::ACTION_PLACE_STRUCTURE,
+                    Some(placer),
+                    DropReason::Invalid,
+                    &e.to_string(),
+                );
+                return Err(e);
+            }
+        };
+
+        let id = loop {
+            self.placed_serial += 1;
+            let id = format!("placed:{}:{}", placer, self.placed_serial);
+            if self.world.structures.get(&id).is_none() {
+                break id;
+            }
+        };
+        let z = self.world.terrain.height_at(intent.x, intent.y);
+        let mut structure =
+            StructureInstance::new(id.clone(), Vec3::new(intent.x, intent.y, z), buildable);
+        structure.rotation_y = intent.rotation_y;
+        structure.bounds_radius = collider_bounding_radius(&structure.collider);
+        structure
+            .metadata
+            .insert("type_id".into(), serde_json::json!(intent.type_id));
+        structure
+            .metadata
+            .insert("owner".into(), serde_json::json!(placer));
+
+        let active = self
+            .structure_cell_span(&structure)
+            .filter(|c| self.active_cells.contains(c))
+            .count();
+        let spawned = structure.to_spawned();
+        let body = structure_body(&structure);
+        Arc::make_mut(&mut self.world)
+            .structures
+            .insert(structure)?;
+        if active > 0 {
+            let registered = match body {
+                None => Ok(()),
+                Some(body) => {
+                    let mut registry = self.physics_registry.write();
+                    match registry.default_simulation_mut() {
+                        Some(sim) => sim
+                            .register_body(structure_body_id(&id), body)
+                            .map_err(|e| PlacementError::Physics(e.to_string())),
+                        None => Err(PlacementError::Physics(
+                            "No default physics simulation".into(),
+                        )),
+                    }
+                }
+            };
+            if let Err(e) = registered {
+                Arc::make_mut(&mut self.world).structures.remove(&id);
+                return Err(e);
+            }
+            self.structure_cells.insert(id.clone(), active);
+            self.pending_structures_spawned.push(spawned.clone());
+        }
+        info!("{} placed {} ({})", placer, id, intent.type_id);
+        Ok(spawned)
+    }
+
+    /// Handle `verb` on targets of kind `target` with `handler` (see
+    /// [`interaction`](crate::interaction)).
+    pub fn register_interaction_handler(
+        &mut self,
+        verb: impl Into<String>,
+        target: InteractionTarget,
+        handler: impl InteractionHandler + 'static,
+    ) {
+        self.interaction_handlers.register(verb, target, handler);
+    }
+
+    /// Resolve a participant's `intent.interact`: check the target is within
+    /// `interact_reach`, then run the handler for its verb and kind, or an
+    /// interactive structure's state transition.  The result, success or
+    /// not, is also queued as `world.interaction.result` for the next tick.
+    pub fn resolve_interaction(
+        &mut self,
+        actor: &str,
+        intent: &IntentInteract,
+    ) -> InteractionResult {
+        let target_kind = if self.world.structures.get(&intent.target_id).is_some() {
+            Some(InteractionTarget::Structure)
+        } else if intent.target_id != actor
+            && self
+                .participant_positions
+                .contains_key(intent.target_id.as_str())
+        {
+            Some(InteractionTarget::Entity)
+        } else {
+            None
+        };
+        let mut result = InteractionResult {
+            actor_id: actor.to_string(),
+            target_id: intent.target_id.clone(),
+            target_kind,
+            verb: intent.verb.clone(),
+            success: false,
+            error: None,
+            data: serde_json::Value::Null,
+        };
+        match self.dispatch_interaction(actor, intent, target_kind) {
+            Ok(data) => {
+                result.success = true;
+                result.data = data;
+            }
+            Err(e) => {
+                if !matches!(e, InteractError::Physics(_) | InteractError::Refused(_)) {
+                    self.record_drop(
+                        subjects::INTENT_INTERACT,
+                        Some(actor),
+                        DropReason::Invalid,
+                        &e.to_string(),
+                    );
+                }
+                result.error = Some(e.to_string());
+            }
+        }
+        self.pending_interaction_results.push(result.clone());
+        result
+    }
+
+    fn dispatch_interaction(
+        &mut self,
+        actor: &str,
+        intent: &IntentInteract,
+        target_kind: Option<InteractionTarget>,
+    ) -> Result<serde_json::Value, InteractError> {
+        let target_id = intent.target_id.as_str();
+        let pos = *self
+            .participant_positions
+            .get(actor)
+            .ok_or_else(|| InteractError::UnknownEntity(actor.to_string()))?;
+        let kind =
+            target_kind.ok_or_else(|| InteractError::UnknownEntity(target_id.to_string()))?;
+        let (target_pos, target_radius, target_type) = match kind {
+            InteractionTarget::Structure => {
+                let s = self
+                    .world
+                    .structures
+                    .get(target_id)
+                    .ok_or_else(|| InteractError::UnknownEntity(target_id.to_string()))?;
+                let type_id = s.metadata.get("type_id").and_then(|v| v.as_str());
+                (s.position, s.bounds_radius, type_id.unwrap_or("unknown"))
+            }
+            InteractionTarget::Entity => (
+                self.participant_positions[target_id],
+                self.bounding_radius(target_id),
+                self.archetype_of(target_id),
+            ),
+        };
+        let (dx, dy, dz) = (
+            target_pos.x - pos.x,
+            target_pos.y - pos.y,
+            target_pos.z - pos.z,
+        );
+        let distance =
+            ((dx * dx + dy * dy + dz * dz).sqrt() - target_radius - self.bounding_radius(actor))
+                .max(0.0);
+        let reach = self.config.interact_reach;
+        if distance > reach {
+            return Err(InteractError::OutOfReach { distance, reach });
+        }
+
+        let verb = intent.verb.as_deref().unwrap_or(DEFAULT_VERB);
+        if let Some(handler) = self.interaction_handlers.get(verb, kind) {
+            let interaction = Interaction {
+                actor_id: actor,
+                target_id,
+                target: kind,
+                target_type,
+                verb,
+                distance,
+            };
+            return handler
+                .interact(&interaction)
+                .map_err(InteractError::Refused);
+        }
+        match kind {
+            InteractionTarget::Structure => {
+                let changed = self.apply_interaction(actor, intent)?;
+                Ok(serde_json::json!(changed))
+            }
+            InteractionTarget::Entity => Err(InteractError::NoHandler {
+                target: target_id.to_string(),
+                verb: verb.to_string(),
+            }),
+        }
+    }
+
+    /// Interact with a structure for `actor` (`action.interact`).
+    ///
+    /// An interactive structure within `interact_reach` of the actor moves
+    /// along the transition `intent.verb` names (or its only one).  If its
+    /// collider is registered it is swapped for the new state's, and the
+    /// change is queued as `world.structure.state_changed` for the next
+    /// tick.
+    pub fn interact(
+        &mut self,
+        actor: &str,
+        intent: &IntentInteract,
+    ) -> Result<StructureStateChanged, InteractError> {
+        let result = self.apply_interaction(actor, intent);
+        if let Err(e) = &result {
+            if !matches!(e, InteractError::Physics(_)) {
+                self.record_drop(
+                    subjects::ACTION_INTERACT,
+                    Some(actor),
+                    DropReason::Invalid,
+                    &e.to_string(),
+                );
+            }
+        }
+        result
+    }
+
+    fn apply_interaction(
+        &mut self,
+        actor: &str,
+        intent: &IntentInteract,
+    ) -> Result<StructureStateChanged, InteractError> {
+        let target = &intent.target_id;
+        let pos = *self
+            .participant_positions
+            .get(actor)
+            .ok_or_else(|| InteractError::UnknownEntity(actor.to_string()))?;
+        let (verb, next, old_body, new_body) = {
+            let s = self
+                .world
+                .structures
+                .get(target)
+                .ok_or_else(|| InteractError::NotInteractive(target.clone()))?;
+            let Some(states) = &s.states else {
+                return Err(InteractError::NotInteractive(target.clone()));
+            };
+            let (dx, dy, dz) = (
+                s.position.x - pos.x,
+                s.position.y - pos.y,
+                s.position.z - pos.z,
+            );
+            let distance = ((dx * dx + dy * dy + dz * dz).sqrt()
+                - s.bounds_radius
+                - self.bounding_radius(actor))
+            .max(0.0);
+            let reach = self.config.interact_reach;
+            if distance > reach {
+                return Err(InteractError::OutOfReach { distance, reach });
+            }
+            let (verb, next) =
+                states
+                    .next(intent.verb.as_deref())
+                    .ok_or_else(|| InteractError::NoTransition {
+                        target: target.clone(),
+                        state: states.state.clone(),
+                        verb: intent.verb.clone(),
+                    })?;
+            let new_body = states.states[next]
+                .collider
+                .clone()
+                .map(|shape| BodyParams::Static {
+                    shape,
+                    position: (s.position.x, s.position.y),
+                    rotation: s.rotation_y,
+                });
+            (
+                verb.to_string(),
+                next.to_string(),
+                structure_body(s),
+                new_body,
+            )
+        };
+
+        if self.structure_cells.contains_key(target) {
+            let mut registry = self.physics_registry.write();
+            let sim = registry
+                .default_simulation_mut()
+                .ok_or_else(|| InteractError::Physics("No default physics simulation".into()))?;
+            let body_id = structure_body_id(target);
+            if old_body.is_some() {
+                sim.unregister_body(&body_id)
+                    .map_err(|e| InteractError::Physics(e.to_string()))?;
+            }
+            if let Some(body) = new_body {
+                if let Err(e) = sim.register_body(body_id.clone(), body) {
+                    // Put the old collider back: the state stays as it was.
+                    if let Some(old) = old_body {
+                        if let Err(e) = sim.register_body(body_id, old) {
+                            warn!("Failed to restore structure body {}: {}", target, e);
+                        }
+                    }
+                    return Err(InteractError::Physics(e.to_string()));
+                }
+            }
+        }
+
+        let previous = Arc::make_mut(&mut self.world)
+            .structures
+            .set_state(target, &next)
+            .map_err(|_| InteractError::NotInteractive(target.clone()))?;
+        let changed = StructureStateChanged {
+            structure_id: target.clone(),
+            previous,
+            state: next,
+            verb,
+            actor_id: Some(actor.to_string()),
+        };
+        debug!(
+            "{} turned {} from {} to {}",
+            actor, target, changed.previous, changed.state
+        );
+        self.pending_structure_states.push(changed.clone());
+        Ok(changed)
+    }
+
+    /// Apply `update` to structure `id` (an operator moving a prop).
+    ///
+    /// A structure overlapping active cells has its body moved with it and
+    /// the change queued as `world.structure.updated` for the next tick; an
+    /// always-streamed one that moves into or out of the active region is
+    /// queued as spawned or removed instead.  World objects of active cells
+    /// (vegetation) take position and metadata updates, which are hibernated
+    /// straight away and restored whenever their cell activates.
+    pub fn update_structure(
+        &mut self,
+        id: &str,
+        update: &StructureUpdate,
+    ) -> Result<StructureUpdated, StructureUpdateError> {
+        if self.world.structures.get(id).is_none() && self.world_objects.contains_key(id) {
+            return self.update_world_object(id, update);
+        }
+        let old = self
+            .world
+            .structures
+            .get(id)
+            .ok_or_else(|| StructureUpdateError::UnknownStructure(id.to_string()))?
+            .clone();
+        let mut s = old.clone();
+        s.position.x = update.x.unwrap_or(s.position.x);
+        s.position.y = update.y.unwrap_or(s.position.y);
+        s.position.z = update.z.unwrap_or(s.position.z);
+        s.rotation_y = update.rotation_y.unwrap_or(s.rotation_y);
+        s.pitch = update.pitch.unwrap_or(s.pitch);
+        s.roll = update.roll.unwrap_or(s.roll);
+        s.scale = update.scale.unwrap_or(s.scale);
+        for (key, value) in &update.metadata {
+            if value.is_null() {
+                s.metadata.remove(key);
+            } else {
+                s.metadata.insert(key.clone(), value.clone());
+            }
+        }
+        s.validate()?;
+
+        let was = self.structure_cells.get(id).copied().unwrap_or(0);
+        let now = self
+            .structure_cell_span(&s)
+            .filter(|c| self.active_cells.contains(c))
+            .count();
+        let moved = (s.position.x, s.position.y, s.rotation_y)
+            != (old.position.x, old.position.y, old.rotation_y);
+        let old_body = structure_body(&old).filter(|_| was > 0);
+        let new_body = structure_body(&s).filter(|_| now > 0);
+        if (moved || (was > 0) != (now > 0)) && (old_body.is_some() || new_body.is_some()) {
+            let mut registry = self.physics_registry.write();
+            let sim = registry.default_simulation_mut().ok_or_else(|| {
+                StructureUpdateError::Physics("No default physics simulation".into())
+            })?;
+            let body_id = structure_body_id(id);
+            if old_body.is_some() {
+                sim.unregister_body(&body_id)
+                    .map_err(|e| StructureUpdateError::Physics(e.to_string()))?;
+            }
+            if let Some(body) = new_body {
+                if let Err(e) = sim.register_body(body_id.clone(), body) {
+                    // Put the old body back: the structure stays where it was.
+                    if let Some(old) = old_body {
+                        if let Err(e) = sim.register_body(body_id, old) {
+                            warn!("Failed to restore structure body {}: {}", id, e);
+                        }
+                    }
+                    return Err(StructureUpdateError::Physics(e.to_string()));
+                }
+            }
+        }
+
+        let updated = StructureUpdated {
+            structure: s.to_spawned(),
+        };
+        let always = s.tier.is_always();
+        Arc::make_mut(&mut self.world).structures.insert(s)?;
+        if now > 0 {
+            self.structure_cells.insert(id.to_string(), now);
+        } else {
+            self.structure_cells.remove(id);
+        }
+        match (was > 0, now > 0) {
+            (true, true) => self.pending_structures_updated.push(updated.clone()),
+            (false, true) if always => self
+                .pending_structures_spawned
+                .push(updated.structure.clone()),
+            (true, false) if always => self.pending_structures_removed.push(StructureRemoved {
+                structure_id: id.to_string(),
+            }),
+            _ => {}
+        }
+        info!("Updated structure {}", id);
+        Ok(updated)
+    }
+
+    fn update_world_object(
+        &mut self,
+        id: &str,
+        update: &StructureUpdate,
+    ) -> Result<StructureUpdated, StructureUpdateError> {
+        if update.rotation_y.is_some()
+            || update.pitch.is_some()
+            || update.roll.is_some()
+            || update.scale.is_some()
+        {
+            return Err(StructureUpdateError::ObjectTransform(id.to_string()));
+        }
+        let old = &self.world_objects[id];
+        let mut object = old.clone();
+        object.position.x = update.x.unwrap_or(object.position.x);
+        object.position.y = update.y.unwrap_or(object.position.y);
+        object.position.z = update.z.unwrap_or(object.position.z);
+        for (key, value) in &update.metadata {
+            if value.is_null() {
+                object.properties.remove(key);
+            } else {
+                object.properties.insert(key.clone(), value.clone());
+            }
+        }
+
+        if (object.position.x, object.position.y) != (old.position.x, old.position.y) {
+            let body = |o: &WorldObject| BodyParams::Static {
+                shape: o.collider.clone(),
+                position: (o.position.x, o.position.y),
+                rotation: 0.0,
+            };
+            let mut registry = self.physics_registry.write();
+            let sim = registry.default_simulation_mut().ok_or_else(|| {
+                StructureUpdateError::Physics("No default physics simulation".into())
+            })?;
+            sim.unregister_body(id)
+                .map_err(|e| StructureUpdateError::Physics(e.to_string()))?;
+            if let Err(e) = sim.register_body(id.to_string(), body(&object)) {
+                if let Err(e) = sim.register_body(id.to_string(), body(old)) {
+                    warn!("Failed to restore object body {}: {}", id, e);
+                }
+                return Err(StructureUpdateError::Physics(e.to_string()));
+            }
+        }
+
+        let updated = StructureUpdated {
+            structure: object.to_spawned(),
+        };
+        self.index_object(&object);
+        self.world_objects.insert(id.to_string(), object);
+        self.modified_objects.insert(id.to_string());
+        self.hibernate_object_cell(id);
+        self.pending_structures_updated.push(updated.clone());
+        info!("Updated world object {}", id);
+        Ok(updated)
+    }
+
+    /// Put `object` into (or move it within) the ray query index.
+    fn index_object(&mut self, object: &WorldObject) {
+        self.object_grid
+            .update(&Arc::from(object.id.as_str()), object.position);
+        self.object_reach = self
+            .object_reach
+            .max(collider_bounding_radius(&object.collider));
+    }
+
+    /// Rewrite the hibernated state of the active cell holding object `id`
+    /// with every changed object of that cell.
+    fn hibernate_object_cell(&mut self, id: &str) {
+        let Some((coord, ids)) = self
+            .cell_objects
+            .iter()
+            .find(|(_, ids)| ids.iter().any(|o| o == id))
+        else {
+            return;
+        };
+        let modified: Vec<WorldObject> = ids
+            .iter()
+            .filter(|o| self.modified_objects.contains(*o))
+            .filter_map(|o| self.world_objects.get(o).cloned())
+            .collect();
+        if let Err(e) = self.hibernation.put(*coord, &modified) {
+            warn!("Failed to hibernate cell {}: {}", coord, e);
+        }
+    }
+
+    /// Hibernate changed world objects in `store` (see
+    /// [`hibernation`](crate::hibernation)) instead of the in-memory one.
+    pub fn set_hibernation_store(&mut self, store: HibernationStore) {
+        self.hibernation = store;
+    }
+
+    pub fn hibernation_store(&self) -> &HibernationStore {
+        &self.hibernation
+    }
+
+    /// The collider of a valid placement.
+    fn check_placement(
+        &self,
+        placer: &str,
+        intent: &IntentPlaceStructure,
+    ) -> Result<ColliderShape, PlacementError> {
+        let pos = self
+            .participant_positions
+            .get(placer)
+            .filter(|_| !self.is_server_entity(placer))
+            .ok_or_else(|| PlacementError::UnknownEntity(placer.to_string()))?;
+        let buildable = self
+            .config
+            .buildables
+            .get(&intent.type_id)
+            .ok_or_else(|| PlacementError::NotBuildable(intent.type_id.clone()))?;
+        if !(intent.x.is_finite() && intent.y.is_finite() && intent.rotation_y.is_finite()) {
+            return Err(PlacementError::NonFinite);
+        }
+        let (x, y) = (intent.x, intent.y);
+        let distance = (x - pos.x).hypot(y - pos.y);
+        if distance.is_nan() || distance > self.config.build_reach {
+            return Err(PlacementError::OutOfReach {
+                distance,
+                reach: self.config.build_reach,
+            });
+        }
+        if let Some(bounds) = &self.config.world_bounds {
+            let cell_size = self.config.cell_size;
+            let (cx, cy) = (
+                (x / cell_size).floor() as i32,
+                (y / cell_size).floor() as i32,
+            );
+            if !bounds.cells.contains(cx, cy) {
+                return Err(PlacementError::OutOfBounds);
+            }
+        }
+        if !self.is_buildable(x, y, buildable.max_slope.unwrap_or(f32::INFINITY)) {
+            return Err(PlacementError::Ground);
+        }
+
+        let radius = collider_bounding_radius(&buildable.collider);
+        let clear = |id: &str, at: Vec3, r: f32| {
+            if (at.x - x).hypot(at.y - y) < radius + r {
+                Err(PlacementError::Overlaps(id.to_string()))
+            } else {
+                Ok(())
+            }
+        };
+        for s in self.world.structures.query_radius(x, y, radius) {
+            clear(&s.id, s.position, collider_bounding_radius(&s.collider))?;
+        }
+        let objects = self
+            .object_grid
+            .candidates(x, y, radius + self.object_reach)
+            .into_iter()
+            .filter_map(|id| self.world_objects.get(&**id));
+        for o in objects {
+            clear(&o.id, o.position, collider_bounding_radius(&o.collider))?;
+        }
+        for (id, &at) in &self.participant_positions {
+            clear(id, at, self.bounding_radius(id))?;
+        }
+        Ok(buildable.collider.clone())
+    }
+
+    // -----------------------------------------------------------------------
+    // Scripted events
+    // -----------------------------------------------------------------------
+
+    /// Replace the event schedule (see [`scheduler`](crate::scheduler)).
+    pub fn set_event_schedule(&mut self, scheduler: EventScheduler) {
+        self.scheduler = scheduler;
+    }
+
+    pub fn event_scheduler(&self) -> &EventScheduler {
+        &self.scheduler
+    }
+
+    /// Start a scheduled event now; announced on the next tick.
+    pub fn trigger_event(&mut self, event_id: &str) -> Result<ScriptedEvent, ScheduleError> {
+        let event = self.scheduler.trigger(event_id, self.elapsed_s())?;
+        self.apply_event_change(EventChange::Started(event.clone()));
+        Ok(event)
+    }
+
+    /// End an active event early; announced on the next tick.
+    pub fn end_event(&mut self, event_id: &str) -> Result<ScriptedEventEnded, ScheduleError> {
+        let ended = self.scheduler.end(event_id)?;
+        self.apply_event_change(EventChange::Ended(ended.clone()));
+        Ok(ended)
+    }
+
+    pub fn set_event_enabled(
+        &mut self,
+        event_id: &str,
+        enabled: bool,
+    ) -> Result<(), ScheduleError> {
+        self.scheduler.set_enabled(event_id, enabled)
+    }
+
+    /// Simulated seconds since startup.
+    fn elapsed_s(&self) -> f64 {
+        self.sim_elapsed_s
+    }
+
+    fn poll_scheduled_events(&mut self) {
+        if self.scheduler.events().is_empty() {
+            return;
+        }
+        let wall_clock_s = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |d| d.as_secs_f64());
+        let clock = SchedulerClock {
+            elapsed_s: self.elapsed_s(),
+            time_of_day_s: self.environment.time_of_day_s(),
+            day_length_s: self.environment.day_length_s(),
+            wall_clock_s,
+        };
+        for change in self.scheduler.poll(clock) {
+            self.apply_event_change(change);
+        }
+    }
+
+    /// Register a started event's entities (or remove an ended one's),
+    /// update the weather, and queue the announcement.
+    fn apply_event_change(&mut self, change: EventChange) {
+        match change {
+            EventChange::Started(event) => {
+                info!("World event '{}' ({}) started", event.event_id, event.kind);
+                for e in &event.entities {
+                    self.register_participant(e.entity_id.clone(), Vec3::new(e.x, e.y, e.z));
+                    if e.scale != 1.0 {
+                        if let Err(err) = self.set_entity_scale(&e.entity_id, e.scale) {
+                            warn!("Event '{}' entity {}: {}", event.event_id, e.entity_id, err);
+                        }
+                    }
+                }
+                self.pending_events_started.push(event);
+            }
+            EventChange::Ended(ended) => {
+                info!("World event '{}' ended", ended.event_id);
+                let entities: Vec<String> = self
+                    .scheduler
+                    .events()
+                    .iter()
+                    .filter(|e| e.event.event_id == ended.event_id)
+                    .flat_map(|e| e.event.entities.iter().map(|x| x.entity_id.clone()))
+                    .collect();
+                for id in entities {
+                    self.unregister_participant(&id);
+                }
+                self.pending_events_ended.push(ended);
+            }
+        }
+
+        // The newest active event with weather sets it; once none is left
+        // the weather from before the first one comes back.
+        match self.scheduler.active().filter_map(|e| e.weather).last() {
+            Some(weather) => {
+                self.weather_before_events
+                    .get_or_insert(self.environment.weather());
+                self.set_weather(weather);
+            }
+            None => {
+                if let Some(weather) = self.weather_before_events.take() {
+                    self.set_weather(weather);
+                }
+            }
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Environment
+    // -----------------------------------------------------------------------
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    /// Change the weather; broadcast on the next tick.
+    pub fn set_weather(&mut self, weather: Weather) {
+        self.environment.set_weather(weather);
+    }
+
+    /// Jump the world clock; broadcast on the next tick.
+    pub fn set_time_of_day(&mut self, seconds: f32) {
+        self.environment.set_time_of_day(seconds);
+    }
+
+    /// Advance the world clock and decide whether an update is due.
+    fn tick_environment(&mut self) -> Option<WorldEnvironment> {
+        self.environment.advance(self.sim_dt());
+
+        let elapsed_s =
+            (self.tick_count - self.last_environment_tick) as f32 * self.config.physics_dt;
+        if self.environment.take_dirty() || elapsed_s >= self.config.environment_interval_s {
+            self.last_environment_tick = self.tick_count;
+            Some(self.environment.to_event())
+        } else {
+            None
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Terrain queries
+    // -----------------------------------------------------------------------
+
+    /// First terrain hit along a ray, with `max_dist` clamped to
+    /// [`MAX_RAYCAST_DISTANCE`].
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_dist: f32) -> Option<RayHit> {
+        self.world
+            .terrain
+            .raycast(origin, direction, max_dist.min(MAX_RAYCAST_DISTANCE))
+    }
+
+    /// Nearest entity, structure or terrain hit along a ray
+    /// (`world.cmd.pick`), with `max_dist` clamped to
+    /// [`MAX_RAYCAST_DISTANCE`].
+    ///
+    /// Entities are tested against their scaled bounding spheres (at least
+    /// [`MIN_PICK_RADIUS`]).  Structures and vegetation are tested against
+    /// their physics colliders (circle or yawed box footprints) extruded up
+    /// and down by their bounding radius; a structure whose current state
+    /// has no collider lets the ray through.  Shapes around the origin are
+    /// skipped, as is `exclude`.
+    pub fn pick(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_dist: f32,
+        exclude: Option<&str>,
+    ) -> Option<PickHit> {
+        let (target, hit) = self.cast_ray(origin, direction, max_dist, exclude)?;
+        Some(PickHit {
+            target,
+            x: hit.position.x,
+            y: hit.position.y,
+            z: hit.position.z,
+            distance: hit.distance,
+        })
+    }
+
+    /// First hit along a ray (`world.cmd.raycast`): what [`pick`](Self::pick)
+    /// finds, with the surface normal there.  `terrain_only` casts against
+    /// the ground alone.
+    pub fn raycast_world(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_dist: f32,
+        exclude: Option<&str>,
+        terrain_only: bool,
+    ) -> Option<RaycastHit> {
+        let (target, hit) = if terrain_only {
+            (
+                PickTarget::Terrain,
+                self.raycast(origin, direction, max_dist)?,
+            )
+        } else {
+            self.cast_ray(origin, direction, max_dist, exclude)?
+        };
+        Some(RaycastHit {
+            target,
+            position: hit.position,
+            normal: hit.normal,
+            distance: hit.distance,
+        })
+    }
+
+    /// Nearest hit for [`pick`](Self::pick) and
+    /// [`raycast_world`](Self::raycast_world).
+    fn cast_ray(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_dist: f32,
+        exclude: Option<&str>,
+    ) -> Option<(PickTarget, RayHit)> {
+        let len =
+            (direction.x * direction.x + direction.y * direction.y + direction.z * direction.z)
+                .sqrt();
+        let max_dist = max_dist.min(MAX_RAYCAST_DISTANCE);
+        if !(len > 0.0 && max_dist > 0.0) {
+            return None;
+        }
+        let dir = Vec3::new(direction.x / len, direction.y / len, direction.z / len);
+
+        let mut best: Option<(PickTarget, RayHit)> = self
+            .raycast(origin, dir, max_dist)
+            .map(|hit| (PickTarget::Terrain, hit));
+        let mut consider = |hit: Option<(f32, Vec3)>, target: PickTarget| {
+            let Some((t, normal)) =
+                hit.filter(|&(t, _)| best.as_ref().is_none_or(|(_, hit)| t < hit.distance))
+            else {
+                return;
+            };
+            let position = Vec3::new(
+                origin.x + dir.x * t,
+                origin.y + dir.y * t,
+                origin.z + dir.z * t,
+            );
+            best = Some((
+                target,
+                RayHit {
+                    position,
+                    normal,
+                    distance: t,
+                },
+            ));
+        };
+
+        for (id, &centre) in &self.participant_positions {
+            if exclude == Some(&**id) {
+                continue;
+            }
+            let radius = self.bounding_radius(id).max(MIN_PICK_RADIUS);
+            consider(
+                ray_sphere(origin, dir, max_dist, centre, radius),
+                PickTarget::Entity {
+                    entity_id: id.to_string(),
+                },
+            );
+        }
+
+        let end = Vec3::new(
+            origin.x + dir.x * max_dist,
+            origin.y + dir.y * max_dist,
+            origin.z + dir.z * max_dist,
+        );
+        let (min_x, min_y) = (origin.x.min(end.x), origin.y.min(end.y));
+        let (max_x, max_y) = (origin.x.max(end.x), origin.y.max(end.y));
+        for s in self.world.structures.query_rect(min_x, min_y, max_x, max_y) {
+            let Some(shape) = s.body_collider() else {
+                continue;
+            };
+            consider(
+                ray_prism(
+                    origin,
+                    dir,
+                    max_dist,
+                    shape,
+                    s.position,
+                    s.rotation_y,
+                    s.bounds_radius,
+                ),
+                PickTarget::Structure {
+                    structure_id: s.id.clone(),
+                },
+            );
+        }
+        let reach = self.object_reach;
+        let objects = self.object_grid.candidates_in_rect(
+            min_x - reach,
+            min_y - reach,
+            max_x + reach,
+            max_y + reach,
+        );
+        for o in objects
+            .into_iter()
+            .filter_map(|id| self.world_objects.get(&**id))
+        {
+            let radius = collider_bounding_radius(&o.collider);
+            consider(
+                ray_prism(origin, dir, max_dist, &o.collider, o.position, 0.0, radius),
+                PickTarget::Structure {
+                    structure_id: o.id.clone(),
+                },
+            );
+        }
+
+        best
+    }
+
+    /// Registry structures tagged `cmd.tag` (`world.cmd.structures`), at
+    /// most `cmd.limit` and [`MAX_STRUCTURE_QUERY_RESULTS`].
+    pub fn structures_by_tag(&self, cmd: &CmdStructures) -> StructureQuery {
+        let [min_x, min_y, max_x, max_y] = cmd.rect.unwrap_or([
+            f32::NEG_INFINITY,
+            f32::NEG_INFINITY,
+            f32::INFINITY,
+            f32::INFINITY,
+        ]);
+        let mut found = self
+            .world
+            .structures
+            .query_by_tag(&cmd.tag, min_x, min_y, max_x, max_y);
+        if let Some([x, y]) = cmd.near {
+            let d2 =
+                |s: &StructureInstance| (s.position.x - x).powi(2) + (s.position.y - y).powi(2);
+            found.sort_by(|a, b| d2(a).total_cmp(&d2(b)));
+        }
+        let limit = cmd
+            .limit
+            .unwrap_or(MAX_STRUCTURE_QUERY_RESULTS)
+            .min(MAX_STRUCTURE_QUERY_RESULTS);
+        StructureQuery {
+            truncated: found.len() > limit,
+            structures: found
+                .into_iter()
+                .take(limit)
+                .map(StructureInstance::to_spawned)
+                .collect(),
+        }
+    }
+
+    /// Terrain heights (and optionally unit normals) at `[x, y]` points, in
+    /// order.  Fails for more than [`MAX_HEIGHT_QUERY_POINTS`] points.
+    pub fn sample_heights(
+        &self,
+        points: &[[f32; 2]],
+        normals: bool,
+    ) -> janet::Result<HeightSamples> {
+        if points.len() > MAX_HEIGHT_QUERY_POINTS {
+            return Err(janet::JanetError::Other(format!(
+                "{} points requested, at most {} per query",
+                points.len(),
+                MAX_HEIGHT_QUERY_POINTS
+            )));
+        }
+        let terrain = &self.world.terrain;
+        Ok(HeightSamples {
+            heights: points
+                .iter()
+                .map(|&[x, y]| terrain.height_at(x, y))
+                .collect(),
+            normals: if normals {
+                points
+                    .iter()
+                    .map(|&[x, y]| {
+                        let n = terrain.normal_at(x, y);
+                        let len = (n.x * n.x + n.y * n.y + n.z * n.z).sqrt().max(f32::EPSILON);
+                        [n.x / len, n.y / len, n.z / len]
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            },
+        })
+    }
+
+    /// [`TerrainSource::is_buildable`] that also rejects ground below the
+    /// sea level.
+    pub fn is_buildable(&self, x: f32, y: f32, max_slope: f32) -> bool {
+        let terrain = &self.world.terrain;
+        self.config
+            .sea_level
+            .is_none_or(|sea| terrain.height_at(x, y) >= sea)
+            && terrain.is_buildable(x, y, max_slope)
+    }
+
+    // -----------------------------------------------------------------------
+    // Terrain deformation
+    // -----------------------------------------------------------------------
+
+    /// Raise or dig terrain around `center` (smoothstep falloff to `radius`).
+    ///
+    /// Active terrain bodies in affected chunks are re-registered with fresh
+    /// colliders.  The returned event is also queued for the next tick's
+    /// `world.terrain.modified` broadcast.  Fails for a radius over
+    /// [`MAX_DEFORM_RADIUS`], non-finite values, or terrain backends that
+    /// can't be deformed.
+    pub fn deform_terrain(
+        &mut self,
+        center: Vec3,
+        radius: f32,
+        delta: f32,
+    ) -> janet::Result<TerrainModified> {
+        check_terrain_edit(center, radius, delta)?;
+        let chunks = self
+            .world
+            .terrain
+            .deform_region(center.x, center.y, radius, delta)
+            .ok_or_else(|| {
+                janet::JanetError::Other("Terrain backend does not support deformation".into())
+            })?;
+
+        let stale = self.active_cells_in_columns(&chunks);
+        self.refresh_terrain_bodies(&stale)?;
+
+        debug!(
+            "Terrain deformed at ({}, {}) r={} delta={} ({} chunks)",
+            center.x,
+            center.y,
+            radius,
+            delta,
+            chunks.len()
+        );
+        Ok(self.record_terrain_edit(center, radius, delta, false, &chunks))
+    }
+
+    /// Cut holes (cave entrances, basements) in every heightfield cell whose
+    /// centre is within `radius` of `center`.
+    ///
+    /// There is no ground in a hole for raycasts and solidity queries.
+    /// Affected active chunks get fresh terrain bodies and are announced
+    /// again next tick with their new [`ChunkActivated::holes`].  The
+    /// returned event is queued like a deformation (`hole` set).  Fails for
+    /// a radius over [`MAX_DEFORM_RADIUS`], non-finite values, or terrain
+    /// backends without hole support.
+    pub fn cut_terrain_holes(
+        &mut self,
+        center: Vec3,
+        radius: f32,
+    ) -> janet::Result<TerrainModified> {
+        check_terrain_edit(center, radius, 0.0)?;
+        let chunks = self
+            .world
+            .terrain
+            .cut_holes(center.x, center.y, radius)
+            .ok_or_else(|| {
+                janet::JanetError::Other("Terrain backend does not support holes".into())
+            })?;
+
+        let cells = self.active_cells_in_columns(&chunks);
+        self.refresh_terrain_bodies(&cells)?;
+        self.pending_chunk_updates.extend(cells);
+
+        debug!(
+            "Terrain holes cut at ({}, {}) r={} ({} chunks)",
+            center.x,
+            center.y,
+            radius,
+            chunks.len()
+        );
+        Ok(self.record_terrain_edit(center, radius, 0.0, true, &chunks))
+    }
+
+    /// Pin terrain samples to designer heights (`[x, y, height]`, see
+    /// [`HeightmapTerrain::set_heights`](crate::terrain::HeightmapTerrain::set_heights)).
+    ///
+    /// Overrides persist with the terrain rather than in snapshots, and
+    /// clients can't replay them: affected active chunks get fresh terrain
+    /// bodies and are announced again next tick with a new checksum (and
+    /// their heights, with `send_chunk_heights`).  Returns the ids of the
+    /// changed chunks.  Fails for more than [`MAX_SET_HEIGHT_SAMPLES`]
+    /// samples, non-finite values, or backends without an override layer.
+    pub fn set_terrain_heights(&mut self, samples: &[[f32; 3]]) -> janet::Result<Vec<String>> {
+        if samples.len() > MAX_SET_HEIGHT_SAMPLES {
+            return Err(janet::JanetError::Other(format!(
+                "{} samples sent, at most {} per call",
+                samples.len(),
+                MAX_SET_HEIGHT_SAMPLES
+            )));
+        }
+        if samples.iter().flatten().any(|v| !v.is_finite()) {
+            return Err(janet::JanetError::Other(
+                "samples must be finite [x, y, height] triples".into(),
+            ));
+        }
+        let chunks = self
+            .world
+            .terrain
+            .set_heights(samples)
+            .ok_or_else(|| {
+                janet::JanetError::Other("Terrain backend does not support height overrides".into())
+            })?
+            .map_err(|e| {
+                janet::JanetError::Other(format!("Could not persist height overrides: {}", e))
+            })?;
+
+        let cells = self.active_cells_in_columns(&chunks);
+        self.refresh_terrain_bodies(&cells)?;
+        self.pending_chunk_updates.extend(cells);
+        self.height_override_edits += 1;
+
+        let (min, max) = samples.iter().fold(
+            ([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]),
+            |(min, max), &[x, y, _]| {
+                (
+                    [min[0].min(x), min[1].min(y)],
+                    [max[0].max(x), max[1].max(y)],
+                )
+            },
+        );
+        if !samples.is_empty() {
+            self.invalidate_nav(min, max, NavChangeCause::TerrainDeformed);
+        }
+        debug!(
+            "Height overrides set for {} samples ({} chunks)",
+            samples.len(),
+            chunks.len()
+        );
+        Ok(chunks
+            .iter()
+            .map(|(cx, cy)| format!("{}:{}", cx, cy))
+            .collect())
+    }
+
+    /// Log an applied terrain edit for snapshots and the next tick.
+    fn record_terrain_edit(
+        &mut self,
+        center: Vec3,
+        radius: f32,
+        delta: f32,
+        hole: bool,
+        chunks: &[(i32, i32)],
+    ) -> TerrainModified {
+        let event = TerrainModified {
+            revision: self.terrain_modifications.len() as u64 + 1,
+            center_x: center.x,
+            center_y: center.y,
+            radius,
+            delta,
+            chunk_ids: chunks
+                .iter()
+                .map(|(cx, cy)| format!("{}:{}", cx, cy))
+                .collect(),
+            hole,
+        };
+        self.terrain_modifications.push(event.clone());
+        self.pending_terrain_modified.push(event.clone());
+        let cause = if hole {
+            NavChangeCause::TerrainHole
+        } else {
+            NavChangeCause::TerrainDeformed
+        };
+        self.invalidate_nav(
+            [center.x - radius, center.y - radius],
+            [center.x + radius, center.y + radius],
+            cause,
+        );
+        event
+    }
+
+    // -----------------------------------------------------------------------
+    // Navigation invalidation
+    // -----------------------------------------------------------------------
+
+    /// Mark path data inside the rectangle `min..max` stale.
+    ///
+    /// Terrain edits call this themselves; game code that places or removes
+    /// obstacles calls it with a structure cause.  The event is queued for
+    /// the next tick's `world.nav.invalidated` broadcast so agents re-plan
+    /// and clients rebake the overlapping navmesh tiles.
+    pub fn invalidate_nav(
+        &mut self,
+        min: [f32; 2],
+        max: [f32; 2],
+        cause: NavChangeCause,
+    ) -> NavInvalidated {
+        let chunk_size = self
+            .world
+            .terrain
+            .chunk_descriptor(0, 0, 0)
+            .map_or(self.config.cell_size, |d| d.chunk_size);
+        let chunk = |v: f32| (v / chunk_size).floor() as i32;
+        let mut chunk_ids = Vec::new();
+        for cy in chunk(min[1])..=chunk(max[1]) {
+            for cx in chunk(min[0])..=chunk(max[0]) {
+                chunk_ids.push(format!("{}:{}", cx, cy));
+            }
+        }
+
+        self.nav_revision += 1;
+        let event = NavInvalidated {
+            revision: self.nav_revision,
+            min_x: min[0],
+            min_y: min[1],
+            max_x: max[0],
+            max_y: max[1],
+            chunk_ids,
+            cause,
+        };
+        self.pending_nav_invalidated.push(event.clone());
+        event
+    }
+
+    // -----------------------------------------------------------------------
+    // Operations
+    // -----------------------------------------------------------------------
+
+    /// Ticks per second implied by `physics_dt`.
+    pub fn tick_rate_hz(&self) -> f32 {
+        1.0 / self.config.physics_dt
+    }
+
+    /// Change the simulation rate; the bus agent picks it up on its next
+    /// tick.  Time-based settings (proximity cooldown) are rescaled.
+    pub fn set_tick_rate(&mut self, hz: f32) -> janet::Result<()> {
+        if !(hz.is_finite() && hz > 0.0) {
+            return Err(janet::JanetError::Other(format!(
+                "Tick rate must be positive, got {}",
+                hz
+            )));
+        }
+        self.config.physics_dt = 1.0 / hz;
+        self.proximity.set_cooldown_ticks(
+            (self.config.proximity_cooldown_s / self.config.physics_dt).ceil() as u64,
+        );
+        Ok(())
+    }
+
+    /// Simulated seconds per wall-clock second.
+    pub fn timescale(&self) -> f32 {
+        self.timescale
+    }
+
+    /// Run simulated time `scale` times as fast as wall-clock time (slow
+    /// motion below 1, fast forward above), within [`MIN_TIMESCALE`] and
+    /// [`MAX_TIMESCALE`].  The tick rate is unchanged; each tick advances
+    /// movement, the world clock, scheduled events and the population pass
+    /// by `physics_dt * scale`, which transforms report as their `dt`.
+    /// Cooldowns and timeouts aimed at players (AFK, emotes, resume grace,
+    /// proximity) stay in wall-clock time.
+    pub fn set_timescale(&mut self, scale: f32) -> janet::Result<()> {
+        if !(MIN_TIMESCALE..=MAX_TIMESCALE).contains(&scale) {
+            return Err(janet::JanetError::Other(format!(
+                "Timescale must be between {} and {}, got {}",
+                MIN_TIMESCALE, MAX_TIMESCALE, scale
+            )));
+        }
+        self.timescale = scale;
+        Ok(())
+    }
+
+    /// Simulated seconds per tick.
+    fn sim_dt(&self) -> f32 {
+        self.config.physics_dt * self.timescale
+    }
+
+    /// Generate and cache every chunk of `region` at each of `lods` ahead of
+    /// time, so the first participant to stream there doesn't wait on
+    /// terrain generation.  Blocks until done, on `generation_workers`
+    /// threads (at least one), warming the cache and any disk store.
+    /// Already cached chunks are skipped (unchunked backends count every
+    /// chunk as cached).
+    pub fn pregenerate(&self, region: CellRegion, lods: &[u8]) -> PregenerateReport {
+        let chunks: Vec<(i32, i32, u8)> = region
+            .columns()
+            .flat_map(|(cx, cy)| lods.iter().map(move |&lod| (cx, cy, lod)))
+            .collect();
+        self.warm_chunks(&chunks)
+    }
+
+    /// [`pregenerate`](Self::pregenerate) the streaming radius around every
+    /// `config.spawn_points` entry, each chunk at the LOD it would stream at
+    /// for a participant standing on the spawn point.
+    pub fn pregenerate_spawn_points(&self) -> PregenerateReport {
+        let cell_size = self.config.cell_size;
+        let mut chunks = BTreeSet::new();
+        for &[x, y] in &self.config.spawn_points {
+            let cx = (x / cell_size).floor() as i32;
+            let cy = (y / cell_size).floor() as i32;
+            let region = CellRegion::around(cx, cy, self.config.activation_radius);
+            for (col_x, col_y) in region.columns() {
+                let cells = (col_x - cx).abs().max((col_y - cy).abs());
+                let lod = self
+                    .world
+                    .terrain
+                    .lod_for_distance(cells as f32 * cell_size);
+                chunks.insert((col_x, col_y, lod));
+            }
+        }
+        self.warm_chunks(&chunks.into_iter().collect::<Vec<_>>())
+    }
+
+    fn warm_chunks(&self, chunks: &[(i32, i32, u8)]) -> PregenerateReport {
+        let start = Instant::now();
+        let evictions = self.world.terrain.chunk_cache_stats().evictions;
+        let terrain = &*self.world.terrain;
+        let next = AtomicUsize::new(0);
+        let generated = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..self.config.generation_workers.max(1).min(chunks.len()) {
+                scope.spawn(|| {
+                    while let Some(&(cx, cy, lod)) =
+                        chunks.get(next.fetch_add(1, Ordering::Relaxed))
+                    {
+                        if !terrain.is_chunk_cached(cx, cy, lod) {
+                            terrain.warm_chunk(cx, cy, lod);
+                            generated.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+
+        let evicted = self.world.terrain.chunk_cache_stats().evictions - evictions;
+        if evicted > 0 {
+            warn!(
+                "Pre-generation evicted {} chunks; the chunk cache budget is smaller than the region",
+                evicted
+            );
+        }
+        PregenerateReport {
+            chunks: chunks.len(),
+            generated: generated.into_inner(),
+            elapsed_ms: start.elapsed().as_secs_f32() * 1000.0,
+        }
+    }
+
+    /// Render a region's heights to a heightmap (see [`CmdTerrainExport`]).
+    ///
+    /// Sampling runs inside the caller's lock on the service, which is
+    /// what the sample caps bound.
+    pub fn export_terrain(&self, cmd: &CmdTerrainExport) -> janet::Result<TerrainExport> {
+        let invalid = |msg: String| janet::JanetError::Other(msg);
+        let spacing = cmd.spacing.unwrap_or(self.config.tile_size_m);
+        let bounds = [cmd.min_x, cmd.min_y, cmd.max_x, cmd.max_y];
+        if !bounds.iter().all(|v| v.is_finite()) || cmd.min_x > cmd.max_x || cmd.min_y > cmd.max_y {
+            return Err(invalid(format!(
+                "region must be finite with min <= max, got ({}, {})..({}, {})",
+                cmd.min_x, cmd.min_y, cmd.max_x, cmd.max_y
+            )));
+        }
+        if !(spacing.is_finite() && spacing > 0.0) {
+            return Err(invalid(format!(
+                "spacing must be positive, got {}",
+                spacing
+            )));
+        }
+        let columns = ((cmd.max_x - cmd.min_x) / spacing).floor() as u64 + 1;
+        let rows = ((cmd.max_y - cmd.min_y) / spacing).floor() as u64 + 1;
+        let cap = if cmd.name.is_some() {
+            MAX_EXPORT_FILE_SAMPLES
+        } else {
+            MAX_EXPORT_SAMPLES
+        };
+        if columns.saturating_mul(rows) > cap as u64 {
+            return Err(invalid(format!(
+                "{}x{} samples requested, at most {} per {} export",
+                columns,
+                rows,
+                cap,
+                if cmd.name.is_some() { "file" } else { "inline" }
+            )));
+        }
+        let (width, height) = (columns as u32, rows as u32);
+        let path = cmd
+            .name
+            .as_deref()
+            .map(|name| {
+                let dir = self.config.export_dir.as_ref().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "no export_dir configured")
+                })?;
+                check_file_name("export", name)?;
+                Ok::<_, io::Error>(dir.join(format!("{}.{}", name, cmd.format.extension())))
+            })
+            .transpose()
+            .map_err(|e| invalid(e.to_string()))?;
+
+        let heights = heightmap_export::sample(
+            &*self.world.terrain,
+            [cmd.min_x, cmd.min_y],
+            spacing,
+            width as usize,
+            height as usize,
+        );
+        let normalised = heightmap_export::normalise(&heights);
+        let bytes = heightmap_export::encode(width, height, &normalised.samples, cmd.format)
+            .map_err(|e| invalid(format!("Could not encode heightmap: {}", e)))?;
+        let mut export = TerrainExport {
+            format: cmd.format,
+            width,
+            height,
+            min_x: cmd.min_x,
+            min_y: cmd.min_y,
+            spacing,
+            min_height: normalised.min_height,
+            max_height: normalised.max_height,
+            data: None,
+            path: None,
+        };
+        match path {
+            Some(path) => {
+                let write = || {
+                    std::fs::create_dir_all(path.parent().unwrap_or(&path))?;
+                    let tmp = path.with_extension("tmp");
+                    std::fs::write(&tmp, &bytes)?;
+                    std::fs::rename(&tmp, &path)
+                };
+                write()
+                    .map_err(|e| invalid(format!("Could not write {}: {}", path.display(), e)))?;
+                export.path = Some(path.display().to_string());
+            }
+            None => export.data = Some(codec::base64_encode(&bytes)),
+        }
+        Ok(export)
+    }
+
+    /// Write the current snapshot to `<checkpoint_dir>/<name>.json`.
+    pub fn write_checkpoint(&self, name: &str) -> io::Result<PathBuf> {
+        let dir = self.config.checkpoint_dir.as_ref().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no checkpoint_dir configured")
+        })?;
+        check_file_name("checkpoint", name)?;
+
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.json", name));
+        let tmp = path.with_extension("json.tmp");
+        let json = serde_json::to_vec_pretty(&self.build_snapshot("checkpoint"))
+            .map_err(io::Error::other)?;
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(path)
+    }
+
+    /// Read a checkpoint written by [`write_checkpoint`](Self::write_checkpoint).
+    pub fn read_checkpoint(&self, name: &str) -> io::Result<WorldSnapshot> {
+        let dir = self.config.checkpoint_dir.as_ref().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no checkpoint_dir configured")
+        })?;
+        let json = std::fs::read(dir.join(format!("{}.json", name)))?;
+        serde_json::from_slice(&json).map_err(io::Error::other)
+    }
+
+    /// Adopt the durable state of a checkpointed snapshot: replay its
+    /// terrain edits and re-register its participants and display data.
+    ///
+    /// Meant for a freshly started service (standby takeover).  Edits are
+    /// recorded as history, not queued for broadcast, because clients
+    /// resync from a snapshot afterwards anyway.
+    pub fn restore_snapshot(&mut self, snapshot: &WorldSnapshot) {
+        self.replay_terrain_modifications(&snapshot.terrain_modifications);
+        for e in &snapshot.entities {
+            if e.archetype == PARTICIPANT_ARCHETYPE {
+                self.register_participant(e.entity_id.clone(), Vec3::new(e.x, e.y, e.z));
+                if e.scale != 1.0 {
+                    if let Err(err) = self.set_entity_scale(&e.entity_id, e.scale) {
+                        warn!("Dropping restored entity scale: {}", err);
+                    }
+                }
+            }
+        }
+        for meta in &snapshot.entity_meta {
+            if let Err(e) = self.set_entity_meta(meta.clone()) {
+                warn!("Dropping restored entity meta: {}", e);
+            }
+        }
+    }
+
+    /// Apply `modifications` to the terrain as history (stops at the first
+    /// the terrain can't apply).
+    fn replay_terrain_modifications(&mut self, modifications: &[TerrainModified]) {
+        for m in modifications {
+            let terrain = &self.world.terrain;
+            let applied = if m.hole {
+                terrain.cut_holes(m.center_x, m.center_y, m.radius)
+            } else {
+                terrain.deform_region(m.center_x, m.center_y, m.radius, m.delta)
+            };
+            if applied.is_none() {
+                break;
+            }
+            self.terrain_modifications.push(m.clone());
+        }
+    }
+
+    /// What should survive a restart (see [`persistence`](crate::persistence)).
+    pub fn world_state(&self) -> WorldState {
+        let mut structures: Vec<StructureRecord> = self
+            .world
+            .structures
+            .iter()
+            .map(StructureRecord::from)
+            .collect();
+        structures.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut entities: Vec<EntitySpec> = self
+            .entities
+            .iter()
+            .filter(|(id, _)| !self.spawners.contains(id))
+            .filter_map(|(id, e)| {
+                let pos = self.participant_positions.get(id)?;
+                Some(EntitySpec {
+                    entity_id: id.to_string(),
+                    archetype: e.archetype.clone(),
+                    x: pos.x,
+                    y: pos.y,
+                    z: Some(pos.z),
+                    rotation_y: e.rotation_y,
+                    velocity: e.velocity,
+                    components: e.components.clone(),
+                    behaviour: e.behaviour.clone(),
+                })
+            })
+            .collect();
+        entities.sort_by(|a, b| a.entity_id.cmp(&b.entity_id));
+
+        let mut participants: BTreeMap<String, Vec3> = self
+            .last_known_positions
+            .iter()
+            .map(|(id, pos)| (id.clone(), *pos))
+            .collect();
+        participants.extend(
+            self.participant_positions
+                .iter()
+                .filter(|(id, _)| !self.is_server_entity(id))
+                .map(|(id, pos)| (id.to_string(), *pos)),
+        );
+
+        WorldState {
+            version: STATE_VERSION,
+            frame: self.tick_count,
+            terrain_version: TERRAIN_VERSION,
+            structures,
+            entities,
+            terrain_modifications: self.terrain_modifications.clone(),
+            participants,
+        }
+    }
+
+    /// Write [`world_state`](Self::world_state) to `path`.  The file is
+    /// replaced atomically, so a crash mid-save keeps the previous state.
+    pub fn save_state(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(&self.world_state()).map_err(io::Error::other)?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Adopt a state written by [`save_state`](Self::save_state).
+    ///
+    /// Meant for a freshly started service, before its first tick: saved
+    /// structures replace registered ones with the same id, entities are
+    /// spawned (announced on the first tick) and terrain edits are replayed
+    /// as history.  Entries that no longer fit the world are skipped with a
+    /// warning.
+    pub fn load_state(&mut self, path: &Path) -> io::Result<()> {
+        let state: WorldState =
+            serde_json::from_slice(&std::fs::read(path)?).map_err(io::Error::other)?;
+        if state.version != STATE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported world state version {}", state.version),
+            ));
+        }
+        if state.terrain_version != TERRAIN_VERSION {
+            warn!(
+                "World state was saved with terrain version {} (running {}); terrain edits may land differently",
+                state.terrain_version, TERRAIN_VERSION
+            );
+        }
+
+        self.replay_terrain_modifications(&state.terrain_modifications);
+        let structures = &mut Arc::make_mut(&mut self.world).structures;
+        for record in state.structures {
+            let id = record.id.clone();
+            if let Err(e) = structures.insert(record.into()) {
+                warn!("Dropping saved structure {}: {}", id, e);
+            }
+        }
+        for spec in state.entities {
+            if let Err(e) = self.spawn_entity(spec) {
+                warn!("Dropping saved entity: {}", e);
+            }
+        }
+        for (id, pos) in state.participants {
+            if !self.participant_positions.contains_key(id.as_str()) {
+                self.last_known_positions.insert(id, pos);
+            }
+        }
+        info!(
+            "Loaded world state from frame {} ({} structures, {} entities, {} terrain edits, {} participants)",
+            state.frame,
+            self.world.structures.len(),
+            self.entities.len(),
+            self.terrain_modifications.len(),
+            self.last_known_positions.len()
+        );
+        Ok(())
+    }
+
+    /// Where a participant of a loaded world state will spawn when it
+    /// next joins.
+    pub fn last_known_position(&self, id: &str) -> Option<Vec3> {
+        self.last_known_positions.get(id).copied()
+    }
+
+    // -----------------------------------------------------------------------
+    // Server entities
+    // -----------------------------------------------------------------------
+
+    /// Start tracking a server entity (see [`entity`](crate::entity));
+    /// announced on the next tick.  Fails if the id is taken by any tracked
+    /// entity or participant.
+    pub fn spawn_entity(&mut self, spec: EntitySpec) -> Result<EntitySpawned, EntityError> {
+        let id = spec.entity_id;
+        let invalid = |reason: &str| EntityError::Invalid {
+            id: id.clone(),
+            reason: reason.to_string(),
+        };
+        if self.participant_positions.contains_key(id.as_str()) {
+            return Err(EntityError::Exists(id));
+        }
+        if id.is_empty() {
+            return Err(invalid("empty id"));
+        }
+        if spec.archetype.is_empty() || spec.archetype == PARTICIPANT_ARCHETYPE {
+            return Err(invalid("archetype must be set and not 'participant'"));
+        }
+        let finite = [spec.x, spec.y, spec.z.unwrap_or(0.0), spec.rotation_y]
+            .iter()
+            .chain(&spec.velocity)
+            .all(|v| v.is_finite());
+        if !finite {
+            return Err(invalid("non-finite transform or velocity"));
+        }
+        if !spec.behaviour.as_ref().is_none_or(Behaviour::is_valid) {
+            return Err(invalid("behaviour values must be finite and not negative"));
+        }
+
+        let z = spec
+            .z
+            .unwrap_or_else(|| self.world.terrain.height_at(spec.x, spec.y));
+        let mut entity = Entity::new(spec.archetype, [spec.x, spec.y]);
+        entity.rotation_y = spec.rotation_y;
+        entity.velocity = spec.velocity;
+        entity.components = spec.components;
+        entity.behaviour = spec.behaviour;
+        self.register_participant(id.clone(), Vec3::new(spec.x, spec.y, z));
+        // Server entities never idle into AFK.
+        self.last_activity.remove(&id);
+        let mut spawned = EntitySpawned {
+            entity_id: id.clone(),
+            archetype: entity.archetype.clone(),
+            x: spec.x,
+            y: spec.y,
+            z,
+            rotation_y: entity.rotation_y,
+            scale: 1.0,
+            bounding_radius: 0.0,
+            metadata: entity.metadata(),
+        };
+        self.entities.insert(id.clone(), entity);
+        spawned.bounding_radius = self.bounding_radius(&id);
+        debug!("Spawned entity {} ({})", id, spawned.archetype);
+        self.pending_entities_spawned.push(spawned.clone());
+        Ok(spawned)
+    }
+
+    /// Stop tracking a server entity; announced on the next tick.
+    pub fn despawn_entity(&mut self, entity_id: &str) -> Result<EntityRemoved, EntityError> {
+        if !self.entities.contains(entity_id) {
+            return Err(EntityError::Unknown(entity_id.to_string()));
+        }
+        self.unregister_participant(entity_id);
+        self.pending_entities_spawned
+            .retain(|e| e.entity_id != entity_id);
+        let removed = EntityRemoved {
+            entity_id: entity_id.to_string(),
+        };
+        debug!("Despawned entity {}", entity_id);
+        self.pending_entities_removed.push(removed.clone());
+        Ok(removed)
+    }
+
+    pub fn entity(&self, entity_id: &str) -> Option<&Entity> {
+        self.entities.get(entity_id)
+    }
+
+    /// Change a server entity's heading, velocity or components.
+    pub fn entity_mut(&mut self, entity_id: &str) -> Option<&mut Entity> {
+        self.entities.get_mut(entity_id)
+    }
+
+    pub fn entity_count(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Ambient and server entities, as opposed to participants: they
+    /// neither stream terrain nor build.
+    fn is_server_entity(&self, id: &str) -> bool {
+        self.population.contains(id) || self.entities.contains(id)
+    }
+
+    /// Step the default simulation over the tick and report the contacts
+    /// it started (see [`collision`](crate::collision)).
+    fn step_physics(&mut self, out: &mut Vec<EntityCollision>) {
+        let contacts = {
+            let mut registry = self.physics_registry.write();
+            let Some(sim) = registry.default_simulation_mut() else {
+                return;
+            };
+            if let Err(e) = sim.step(self.sim_dt()) {
+                warn!("Physics step failed: {}", e);
+                return;
+            }
+            sim.drain_contact_events()
+        };
+        out.extend(collision::entity_collisions(contacts, |id| {
+            self.participant_positions.get(id).copied()
+        }));
+    }
+
+    /// Set the velocity of every server entity with a behaviour.
+    fn steer_entities(&mut self) {
+        for id in self.entities.steered() {
+            let Some(&pos) = self.participant_positions.get(id.as_str()) else {
+                continue;
+            };
+            let Some(behaviour) = self.entities.get(&id).and_then(|e| e.behaviour.clone()) else {
+                continue;
+            };
+            let other = match (&behaviour, behaviour.range()) {
+                (_, None) => None,
+                (
+                    Behaviour::Pursue {
+                        target: Some(target),
+                        ..
+                    },
+                    Some(range),
+                ) => self
+                    .participant_positions
+                    .get(target.as_str())
+                    .filter(|p| (p.x - pos.x).hypot(p.y - pos.y) <= range)
+                    .copied(),
+                (_, Some(range)) => self.nearest_participant(pos, range),
+            };
+            let key = format!("{}:{}", self.config.world_seed, id);
+            if let Some(entity) = self.entities.get_mut(&id) {
+                entity.velocity = behaviour.steer(
+                    &mut entity.steering,
+                    [pos.x, pos.y],
+                    entity.home,
+                    other.map(|p| [p.x, p.y]),
+                    &key,
+                );
+            }
+        }
+    }
+
+    /// Position of the participant (not server entity) nearest `pos`
+    /// within `range`.
+    fn nearest_participant(&self, pos: Vec3, range: f32) -> Option<Vec3> {
+        self.participant_grid
+            .candidates(pos.x, pos.y, range)
+            .into_iter()
+            .filter(|id| !self.is_server_entity(id))
+            .filter_map(|id| self.participant_positions.get(id).copied())
+            .map(|p| ((p.x - pos.x).hypot(p.y - pos.y), p))
+            .filter(|(d, _)| *d <= range)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, p)| p)
+    }
+
+    /// Move every server entity with a velocity by one tick.
+    fn move_entities(&mut self) {
+        for (id, [vx, vy]) in self.entities.take_moves() {
+            if let Some(&pos) = self.participant_positions.get(id.as_str()) {
+                self.move_tracked(&id, pos, vx, vy);
+            }
+        }
+    }
+
+    /// Replace the spawners (see [`spawner`](crate::spawner)).  Entities
+    /// of the old ones are despawned, announced on the next tick.
+    pub fn set_spawners(&mut self, defs: Vec<SpawnerDef>) {
+        let old = std::mem::replace(
+            &mut self.spawners,
+            Spawners::new(defs, self.config.world_seed),
+        );
+        for id in old.ids() {
+            if let Err(e) = self.despawn_entity(id) {
+                debug!("Spawner entity already gone: {}", e);
+            }
+        }
+    }
+
+    pub fn spawners(&self) -> &Spawners {
+        &self.spawners
+    }
+
+    /// Fill spawners in active cells and empty the others.
+    fn run_spawners(&mut self) {
+        if self.spawners.defs().is_empty() {
+            return;
+        }
+        let columns: HashSet<(i32, i32)> = self.active_cells.iter().map(|c| (c.x, c.y)).collect();
+        let size = self.config.cell_size;
+        let change = self.spawners.evaluate(self.sim_elapsed_s, |x, y| {
+            columns.contains(&((x / size).floor() as i32, (y / size).floor() as i32))
+        });
+        for id in change.despawned {
+            if let Err(e) = self.despawn_entity(&id) {
+                debug!("Spawner entity already gone: {}", e);
+            }
+        }
+        for spec in change.spawned {
+            let id = spec.entity_id.clone();
+            if let Err(e) = self.spawn_entity(spec) {
+                warn!("Spawner failed to spawn {}: {}", id, e);
+                self.spawners.forget(&id, self.sim_elapsed_s);
+            }
+        }
+    }
 
-        let mut activated = Vec::new();
-        let mut deactivated = Vec::new();
+    // -----------------------------------------------------------------------
+    // Ambient population
+    // -----------------------------------------------------------------------
 
-        let to_deactivate: Vec<_> = self.active_cells.difference(&desired).cloned().collect();
-        for c in to_deactivate {
-            deactivated.push(self.deactivate_cell(&c)?);
+    /// Replace the ambient population rules (see
+    /// [`population`](crate::population)).  Entities spawned under the old
+    /// rules are removed, announced on the next tick.
+    pub fn set_population_rules(&mut self, rules: Vec<PopulationRule>) {
+        let old = std::mem::replace(
+            &mut self.population,
+            AmbientPopulation::new(rules, self.config.cell_size, self.config.world_seed),
+        );
+        for id in old.ids() {
+            self.unregister_participant(id);
+            self.pending_entities_removed.push(EntityRemoved {
+                entity_id: id.to_string(),
+            });
         }
+        self.last_population_tick = 0;
+    }
 
-        let to_activate: Vec<_> = desired.difference(&self.active_cells).cloned().collect();
-        for c in to_activate {
-            if let Some(ev) = self.activate_cell(c)? {
-                activated.push(ev);
-            }
+    pub fn population_rules(&self) -> &[PopulationRule] {
+        self.population.rules()
+    }
+
+    fn population_due(&self) -> bool {
+        if self.config.population_interval_s <= 0.0 || self.population.rules().is_empty() {
+            return false;
         }
+        let every = (self.config.population_interval_s / self.sim_dt())
+            .round()
+            .max(1.0) as u64;
+        self.last_population_tick == 0 || self.tick_count - self.last_population_tick >= every
+    }
 
-        let entity_transforms = self.collect_entity_transforms();
+    /// One population pass over the active columns: place new ambient
+    /// entities on the ground and drop the ones no longer wanted.
+    fn populate(&mut self, spawned: &mut Vec<EntitySpawned>, removed: &mut Vec<EntityRemoved>) {
+        let mut columns: Vec<(i32, i32)> = self.active_cells.iter().map(|c| (c.x, c.y)).collect();
+        columns.sort_unstable();
+        columns.dedup();
+        let half = self.config.cell_size * 0.5;
+        let cells: Vec<PopulationCell> = columns
+            .into_iter()
+            .map(|(cx, cy)| {
+                let (ox, oy) = self.cell_origin(CellCoord::new(cx, cy, 0));
+                PopulationCell {
+                    cx,
+                    cy,
+                    biome: self
+                        .world
+                        .terrain
+                        .biome(ox + half, oy + half)
+                        .unwrap_or("")
+                        .to_string(),
+                }
+            })
+            .collect();
+        let hour = self.environment.time_of_day_s() / self.environment.day_length_s() * 24.0;
+        let change = self.population.evaluate(&cells, hour);
 
-        Ok(TickEvents {
-            tick: self.tick_count,
-            activated,
-            deactivated,
-            entity_transforms,
-        })
+        for id in change.despawned {
+            self.unregister_participant(&id);
+            removed.push(EntityRemoved { entity_id: id });
+        }
+        for s in change.spawned {
+            let z = self.world.terrain.height_at(s.x, s.y);
+            self.register_participant(s.entity_id.clone(), Vec3::new(s.x, s.y, z));
+            // Ambient entities never idle into AFK.
+            self.last_activity.remove(&s.entity_id);
+            debug!("Spawned {} ({}) in {}", s.entity_id, s.archetype, s.biome);
+            spawned.push(EntitySpawned {
+                bounding_radius: self.bounding_radius(&s.entity_id),
+                entity_id: s.entity_id,
+                archetype: s.archetype,
+                x: s.x,
+                y: s.y,
+                z,
+                rotation_y: 0.0,
+                scale: 1.0,
+                metadata: serde_json::Value::Null,
+            });
+        }
     }
 
     // -----------------------------------------------------------------------
     // Snapshot
     // -----------------------------------------------------------------------
 
+    /// Ticks run so far (the `frame` of events published after them).
+    pub fn tick_count(&self) -> u64 {
+        self.tick_count
+    }
+
+    /// Number of terrain edits (runtime deformations and designer height
+    /// override batches) applied so far.
+    pub fn terrain_revision(&self) -> u64 {
+        self.terrain_modifications.len() as u64 + self.height_override_edits
+    }
+
     /// Build a full-state [`WorldSnapshot`] for a reconnecting client.
     pub fn build_snapshot(&self, _session: &str) -> WorldSnapshot {
         // Active chunks
-        let active_chunks = self
+        let mut active_chunks: Vec<_> = self
             .active_cells
             .iter()
-            .map(|coord| {
-                let (seed, chunk_size) = self
-                    .world
-                    .terrain
-                    .as_any()
-                    .downcast_ref::<HeightmapTerrain>()
-                    .map(|hm| (hm.seed, hm.chunk_size))
-                    .unwrap_or((0, self.config.cell_size));
-
-                ChunkActivated {
-                    chunk_id: format!("{}:{}", coord.x, coord.y),
-                    cx: coord.x,
-                    cy: coord.y,
-                    seed,
-                    terrain_seed: seed,
-                    tile_resolution: self.config.tile_size_m,
-                    terrain_algo_version: "md5_value_noise_v1".to_string(),
-                    lod: 0,
-                    chunk_size,
-                }
-            })
+            .map(|coord| self.chunk_activated_event(coord))
+            .collect();
+        active_chunks.sort_by_key(|c| (c.priority, c.cx, c.cy));
+        let chunk_voxels = active_chunks
+            .iter()
+            .filter_map(|c| self.chunk_voxels_event(&CellCoord::new(c.cx, c.cy, 0)))
             .collect();
+        let chunk_data = if self.config.send_chunk_heights {
+            active_chunks
+                .iter()
+                .filter_map(|c| self.chunk_data_event(c.cx, c.cy, c.lod))
+                .collect()
+        } else {
+            Vec::new()
+        };
 
-        // Structures (all; a real impl might page by view radius)
+        // Structures (all; a real impl might page by view radius) and the
+        // vegetation of active cells
         let structures = self
             .world
             .structures
@@ -205,46 +3656,56 @@ impl WorldService {
                 f32::INFINITY,
             )
             .into_iter()
-            .map(|s| StructureSpawned {
-                structure_id: s.id.clone(),
-                type_id: s
-                    .metadata
-                    .get("type_id")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown")
-                    .to_string(),
-                x: s.position.x,
-                y: s.position.y,
-                z: s.position.z,
-                rotation_y: 0.0,
-                metadata: serde_json::Value::Object(
-                    s.metadata
-                        .iter()
-                        .map(|(k, v)| (k.clone(), v.clone()))
-                        .collect(),
-                ),
-            })
+            .map(StructureInstance::to_spawned)
+            .chain(self.world_objects.values().map(WorldObject::to_spawned))
             .collect();
 
         // Participants as entity stubs
         let entities = self
             .participant_positions
             .iter()
-            .map(|(id, pos)| EntitySpawned {
+            .map(|(id, pos)| {
+                let entity = self.entities.get(id);
+                EntitySpawned {
+                    entity_id: id.to_string(),
+                    archetype: self.archetype_of(id).into(),
+                    x: pos.x,
+                    y: pos.y,
+                    z: pos.z,
+                    rotation_y: entity.map_or(0.0, |e| e.rotation_y),
+                    scale: self.entity_scale(id),
+                    bounding_radius: self.bounding_radius(id),
+                    metadata: entity.map_or(serde_json::Value::Null, Entity::metadata),
+                }
+            })
+            .collect();
+
+        let mut entity_meta: Vec<_> = self.entity_meta.values().cloned().collect();
+        entity_meta.sort_by(|a, b| a.entity_id.cmp(&b.entity_id));
+
+        let mut entity_handles: Vec<_> = self
+            .entity_handles
+            .iter()
+            .map(|(id, &handle)| EntityHandle {
                 entity_id: id.clone(),
-                archetype: "participant".into(),
-                x: pos.x,
-                y: pos.y,
-                z: pos.z,
-                rotation_y: 0.0,
-                metadata: serde_json::Value::Null,
+                handle,
             })
             .collect();
+        entity_handles.sort_by_key(|h| h.handle);
 
         WorldSnapshot {
             active_chunks,
             structures,
             entities,
+            environment: Some(self.environment.to_event()),
+            sea_level: self.config.sea_level,
+            terrain_version: TERRAIN_VERSION,
+            terrain_modifications: self.terrain_modifications.clone(),
+            chunk_voxels,
+            chunk_data,
+            entity_meta,
+            entity_handles,
+            active_events: self.scheduler.active().cloned().collect(),
         }
     }
 
@@ -258,6 +3719,61 @@ impl WorldService {
             total_objects: self.world_objects.len(),
             tracked_participants: self.participant_positions.len(),
             total_ticks: self.tick_count,
+            pending_chunks: self.pending_cells.len(),
+            chunk_cache: self.world.terrain.chunk_cache_stats(),
+            afk_participants: self.afk.len(),
+            departed_participants: self.departed.len(),
+            fanout: self.fanout.summary(),
+            drops: self.drops.summary(),
+            population: self.population.stats(),
+        }
+    }
+
+    /// Count a `kind` intent or command from `participant` that was not
+    /// applied (see [`DropStats`](crate::types::DropStats)).
+    pub fn record_drop(
+        &mut self,
+        kind: &str,
+        participant: Option<&str>,
+        reason: DropReason,
+        detail: &str,
+    ) {
+        self.drops.record(kind, participant, reason, detail);
+    }
+
+    /// [`record_drop`](Self::record_drop) and the error to return for it.
+    fn reject(
+        &mut self,
+        kind: &str,
+        participant: &str,
+        reason: DropReason,
+        message: String,
+    ) -> janet::JanetError {
+        self.record_drop(kind, Some(participant), reason, &message);
+        janet::JanetError::Other(message)
+    }
+
+    /// Everything the service knows about one cell (operator diagnostics).
+    pub fn cell_info(&self, coord: CellCoord) -> CellInfo {
+        let mut participants: Vec<_> = self
+            .participant_positions
+            .iter()
+            .filter(|(_, pos)| self.cell_priority_from(pos, &coord) == 0)
+            .map(|(id, _)| id.to_string())
+            .collect();
+        participants.sort();
+        let structures = self.cell_structure_ids(coord);
+
+        let (ox, oy) = self.cell_origin(coord);
+        let half = self.config.cell_size * 0.5;
+
+        CellInfo {
+            active: self.active_cells.contains(&coord),
+            terrain_bodies: self.terrain_bodies.get(&coord).cloned().unwrap_or_default(),
+            objects: self.cell_objects.get(&coord).cloned().unwrap_or_default(),
+            structures,
+            participants,
+            centre_height: self.world.terrain.height_at(ox + half, oy + half),
         }
     }
 
@@ -265,91 +3781,545 @@ impl WorldService {
     // Cell computation
     // -----------------------------------------------------------------------
 
-    fn compute_active_cells(&self) -> HashSet<CellCoord> {
-        let mut set = HashSet::new();
-        let r = self.config.activation_radius;
+    fn compute_active_cells(&self, set: &mut HashSet<CellCoord>) {
+        set.clear();
 
-        for pos in self.participant_positions.values() {
+        let vertical_radius = self.config.vertical_activation_radius;
+        for (id, pos) in &self.participant_positions {
+            // Ambient entities live inside the region; letting them widen
+            // it would stock the new edge, and so on.
+            if self.is_server_entity(id) {
+                continue;
+            }
+            let r = self.activation_radius_for(id);
             let cx = (pos.x / self.config.cell_size).floor() as i32;
             let cy = (pos.y / self.config.cell_size).floor() as i32;
+            let layer = self.layer_of(pos.z);
 
             for dx in -r..=r {
                 for dy in -r..=r {
-                    set.insert(CellCoord::new(cx + dx, cy + dy, 0));
+                    // Beyond the world bounds only the edge ring streams.
+                    if let Some(bounds) = &self.config.world_bounds {
+                        if bounds.cells.distance_outside(cx + dx, cy + dy) > 1 {
+                            continue;
+                        }
+                    }
+                    let cell = self.column_cell(cx + dx, cy + dy);
+                    if (cell.z - layer).abs() <= vertical_radius {
+                        set.insert(cell);
+                    }
                 }
             }
         }
+    }
+
+    /// Vertical streaming layer of a height (`0` unless
+    /// `vertical_cell_size` is set).
+    fn layer_of(&self, z: f32) -> i32 {
+        if self.config.vertical_cell_size > 0.0 {
+            (z / self.config.vertical_cell_size).floor() as i32
+        } else {
+            0
+        }
+    }
+
+    /// The cell that streams column `(cx, cy)`'s terrain: the one in the
+    /// layer of the column's centre height.  Samples the terrain when
+    /// vertical streaming is on.
+    pub fn column_cell(&self, cx: i32, cy: i32) -> CellCoord {
+        if self.config.vertical_cell_size <= 0.0 {
+            return CellCoord::new(cx, cy, 0);
+        }
+        let (ox, oy) = self.cell_origin(CellCoord::new(cx, cy, 0));
+        let half = self.config.cell_size * 0.5;
+        let height = self.world.terrain.height_at(ox + half, oy + half);
+        CellCoord::new(cx, cy, self.layer_of(height))
+    }
+
+    /// Active cells of the given chunk columns, whatever their layer.  An
+    /// edit can move a column to another layer; the next streaming pass
+    /// then re-activates it there.
+    fn active_cells_in_columns(&self, columns: &[(i32, i32)]) -> Vec<CellCoord> {
+        if self.config.vertical_cell_size <= 0.0 {
+            return columns
+                .iter()
+                .map(|&(cx, cy)| CellCoord::new(cx, cy, 0))
+                .collect();
+        }
+        let columns: HashSet<_> = columns.iter().copied().collect();
+        self.active_cells
+            .iter()
+            .filter(|c| columns.contains(&(c.x, c.y)))
+            .copied()
+            .collect()
+    }
+
+    /// `true` if activating `coord` will not have to generate terrain.
+    fn chunk_ready(&self, coord: &CellCoord, lod: u8) -> bool {
+        self.chunk_workers.is_none() || self.world.terrain.is_chunk_cached(coord.x, coord.y, lod)
+    }
+
+    /// Queue background generation for `coord` unless already queued.
+    fn request_chunk(&mut self, coord: CellCoord, lod: u8) {
+        if let Some(workers) = &self.chunk_workers {
+            if self.pending_cells.insert(coord) {
+                workers.submit(coord, lod);
+            }
+        }
+    }
 
-        set
+    /// LOD for a cell from the distance to the nearest participant's cell,
+    /// so it only changes when someone crosses a cell border.
+    fn cell_lod(&self, coord: &CellCoord) -> u8 {
+        match self.cell_priority(coord) {
+            u32::MAX => 0,
+            cells => self
+                .world
+                .terrain
+                .lod_for_distance(cells as f32 * self.config.cell_size),
+        }
     }
 
-    fn activate_cell(&mut self, coord: CellCoord) -> janet::Result<Option<ChunkActivated>> {
+    /// Register every body of a newly active cell.  Registration is all or
+    /// nothing: if the simulation refuses one body, the ones already
+    /// registered are taken out again and the cell stays inactive, so a
+    /// retry on the next tick doesn't find orphans under the same ids.
+    fn activate_cell(
+        &mut self,
+        coord: CellCoord,
+        lod: u8,
+    ) -> janet::Result<Option<ChunkActivated>> {
         if self.active_cells.contains(&coord) {
             return Ok(None);
         }
 
-        let mut registry = self.physics_registry.write();
-        let sim = registry
-            .default_simulation_mut()
-            .ok_or_else(|| janet::JanetError::Other("No default physics simulation".into()))?;
-
-        // Terrain streaming – downcast to HeightmapTerrain for heightfield support.
-        if let Some(hm) = self
-            .world
-            .terrain
-            .as_any()
-            .downcast_ref::<HeightmapTerrain>()
-        {
-            let body_id = format!("terrain.{}.{}", coord.x, coord.y);
-            let collider = hm.heightfield_collider_for_chunk(coord.x, coord.y, 0);
+        let mut bodies: Vec<(String, BodyParams)> = Vec::new();
 
-            sim.register_body(
+        // Terrain streaming – only backends with heightfield support get a body.
+        let terrain_colliders = self.terrain_colliders(coord);
+        let terrain_ids = terrain_body_ids(coord, terrain_colliders.len());
+        for (body_id, (shape, position)) in terrain_ids.iter().zip(terrain_colliders) {
+            bodies.push((
                 body_id.clone(),
                 BodyParams::Static {
-                    shape: collider,
-                    position: (
-                        coord.x as f32 * self.config.cell_size,
-                        coord.y as f32 * self.config.cell_size,
-                    ),
+                    shape,
+                    position,
+                    rotation: 0.0,
+                },
+            ));
+        }
+
+        // Solid voxel overrides the 2D simulation can bump into.
+        let mut object_ids = Vec::new();
+        for (i, (shape, position)) in self.voxel_colliders(coord).into_iter().enumerate() {
+            let body_id = format!("voxel.{}.{}.{}", coord.x, coord.y, i);
+            object_ids.push(body_id.clone());
+            bodies.push((
+                body_id,
+                BodyParams::Static {
+                    shape,
+                    position,
+                    rotation: 0.0,
+                },
+            ));
+        }
+
+        // Vegetation (`tree_density`) standing in this cell's layer.
+        let mut vegetation: Vec<WorldObject> = vegetation::scatter(
+            &self.config,
+            self.world.terrain.as_ref(),
+            coord.x,
+            coord.y,
+            |x, y, max_slope| self.is_buildable(x, y, max_slope),
+        )
+        .into_iter()
+        .filter(|o| self.layer_of(o.position.z) == coord.z)
+        .collect();
+        // Objects changed before the cell last deactivated come back as they
+        // were left.
+        let hibernated = match self.hibernation.get(coord) {
+            Ok(objects) => objects.unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to restore hibernated cell {}: {}", coord, e);
+                Vec::new()
+            }
+        };
+        let restored: Vec<String> = hibernated.iter().map(|o| o.id.clone()).collect();
+        for object in hibernated {
+            match vegetation.iter_mut().find(|o| o.id == object.id) {
+                Some(generated) => *generated = object,
+                None => vegetation.push(object),
+            }
+        }
+        for object in &vegetation {
+            object_ids.push(object.id.clone());
+            bodies.push((
+                object.id.clone(),
+                BodyParams::Static {
+                    shape: object.collider.clone(),
+                    position: (object.position.x, object.position.y),
                     rotation: 0.0,
                 },
-            )?;
+            ));
+        }
+
+        // Registry structures share one collider between every active cell
+        // they overlap; the first of those registers them and announces the
+        // `Always` tier ones (the rest stream through interest scopes).
+        let world = Arc::clone(&self.world);
+        let structures: Vec<&StructureInstance> = self
+            .cell_structure_ids(coord)
+            .iter()
+            .filter_map(|id| world.structures.get(id))
+            .collect();
+        let first_seen: Vec<&StructureInstance> = structures
+            .iter()
+            .copied()
+            .filter(|s| !self.structure_cells.contains_key(&s.id))
+            .collect();
+        for s in &first_seen {
+            if let Some(body) = structure_body(s) {
+                bodies.push((structure_body_id(&s.id), body));
+            }
+        }
+
+        {
+            let mut registry = self.physics_registry.write();
+            let sim = registry
+                .default_simulation_mut()
+                .ok_or_else(|| janet::JanetError::Other("No default physics simulation".into()))?;
+            let mut registered: Vec<String> = Vec::with_capacity(bodies.len());
+            for (body_id, params) in bodies {
+                if let Err(e) = sim.register_body(body_id.clone(), params) {
+                    for id in registered.iter().rev() {
+                        if let Err(e) = sim.unregister_body(id) {
+                            warn!("Failed to roll back body {}: {}", id, e);
+                        }
+                    }
+                    return Err(e);
+                }
+                registered.push(body_id);
+            }
+        }
 
+        if !terrain_ids.is_empty() {
             debug!("Activated terrain cell {}", coord);
-            self.terrain_bodies.insert(coord, body_id);
+            self.terrain_bodies.insert(coord, terrain_ids);
+        }
+        if !object_ids.is_empty() {
+            self.cell_objects.insert(coord, object_ids);
+        }
+        if !restored.is_empty() {
+            // The cell's file stays: it is rewritten with the next change.
+            debug!(
+                "Restored {} hibernated objects in cell {}",
+                restored.len(),
+                coord
+            );
+            self.modified_objects.extend(restored);
+        }
+        for object in vegetation {
+            self.pending_structures_spawned.push(object.to_spawned());
+            self.index_object(&object);
+            self.world_objects.insert(object.id.clone(), object);
+        }
+        for s in structures {
+            *self.structure_cells.entry(s.id.clone()).or_default() += 1;
         }
+        self.pending_structures_spawned.extend(
+            first_seen
+                .into_iter()
+                .filter(|s| s.tier.is_always())
+                .map(StructureInstance::to_spawned),
+        );
 
+        self.cell_lods.insert(coord, lod);
         self.active_cells.insert(coord);
 
-        // Build protocol event — grab seed from terrain if HeightmapTerrain.
-        let (seed, chunk_size) = self
+        Ok(Some(self.chunk_activated_event(&coord)))
+    }
+
+    /// Registry structures whose bounding circle overlaps the cell, in the
+    /// cell's vertical layer.
+    pub fn cell_structures(&self, coord: CellCoord) -> Vec<&StructureInstance> {
+        let (ox, oy) = self.cell_origin(coord);
+        let size = self.config.cell_size;
+        let mut found = self
             .world
-            .terrain
-            .as_any()
-            .downcast_ref::<HeightmapTerrain>()
-            .map(|hm| (hm.seed, hm.chunk_size))
-            .unwrap_or((0, self.config.cell_size));
+            .structures
+            .query_rect(ox, oy, ox + size, oy + size);
+        found.retain(|s| self.layer_of(s.position.z) == coord.z);
+        found
+    }
 
-        let chunk_id = format!("{}:{}", coord.x, coord.y);
-        Ok(Some(ChunkActivated {
-            chunk_id,
+    /// Cells whose [`cell_structures`](Self::cell_structures) include `s`.
+    fn structure_cell_span(&self, s: &StructureInstance) -> impl Iterator<Item = CellCoord> {
+        let size = self.config.cell_size;
+        let r = s.bounds_radius;
+        // Cell edges are inclusive, so a bound exactly on an edge is in
+        // both cells.
+        let lo = |v: f32| (v / size).ceil() as i32 - 1;
+        let hi = |v: f32| (v / size).floor() as i32;
+        let (x0, x1) = (lo(s.position.x - r), hi(s.position.x + r));
+        let (y0, y1) = (lo(s.position.y - r), hi(s.position.y + r));
+        let z = self.layer_of(s.position.z);
+        (y0..=y1).flat_map(move |y| (x0..=x1).map(move |x| CellCoord::new(x, y, z)))
+    }
+
+    /// Sorted ids of [`cell_structures`](Self::cell_structures).
+    fn cell_structure_ids(&self, coord: CellCoord) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .cell_structures(coord)
+            .into_iter()
+            .map(|s| s.id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Protocol event for an active cell (shared by live and snapshot paths).
+    fn chunk_activated_event(&self, coord: &CellCoord) -> ChunkActivated {
+        let lod = self.cell_lods.get(coord).copied().unwrap_or(0);
+        let descriptor = self.world.terrain.chunk_descriptor(coord.x, coord.y, lod);
+        let seed = descriptor.map_or(0, |d| d.seed);
+        let chunk_size = descriptor.map_or(self.config.cell_size, |d| d.chunk_size);
+
+        ChunkActivated {
+            chunk_id: format!("{}:{}", coord.x, coord.y),
             cx: coord.x,
             cy: coord.y,
             seed,
             terrain_seed: seed,
             tile_resolution: self.config.tile_size_m,
-            terrain_algo_version: "md5_value_noise_v1".to_string(),
-            lod: 0,
+            terrain_algo_version: TERRAIN_ALGO_VERSION.to_string(),
+            terrain_version: TERRAIN_VERSION,
+            lod,
             chunk_size,
-        }))
+            priority: self.cell_priority(coord),
+            hydrology: descriptor.and_then(|d| d.hydrology),
+            materials: descriptor.map(|d| d.materials).unwrap_or_default(),
+            shoreline: self
+                .config
+                .sea_level
+                .map(|level| {
+                    shoreline::segments(
+                        self.world.terrain.as_ref(),
+                        coord.x as f32 * chunk_size,
+                        coord.y as f32 * chunk_size,
+                        chunk_size,
+                        level,
+                    )
+                })
+                .unwrap_or_default(),
+            holes: self.world.terrain.chunk_holes(coord.x, coord.y),
+            checksum: self.world.terrain.chunk_checksum(coord.x, coord.y, lod),
+            edge: self.chunk_edge(coord.x, coord.y),
+        }
+    }
+
+    /// Edge hints for a column outside the world bounds (`None` inside, or
+    /// in an unbounded world).
+    fn chunk_edge(&self, cx: i32, cy: i32) -> Option<ChunkEdge> {
+        let bounds = self.config.world_bounds?;
+        let r = bounds.cells;
+        if r.contains(cx, cy) {
+            return None;
+        }
+        Some(ChunkEdge {
+            fill: bounds.fill,
+            height: bounds.height,
+            inward: [
+                (cx.max(r.min_cx).min(r.max_cx) - cx).signum(),
+                (cy.max(r.min_cy).min(r.max_cy) - cy).signum(),
+            ],
+        })
+    }
+
+    /// `world.chunk.data` payload for a chunk clients can't regenerate.
+    /// The grid follows [`HeightChunk::sample`] with one sample per
+    /// `tile_size_m` at LOD 0.
+    fn chunk_data_event(&self, cx: i32, cy: i32, lod: u8) -> Option<ChunkData> {
+        let terrain = self.world.terrain.as_ref();
+        if terrain.client_regenerable(cx, cy) {
+            return None;
+        }
+        let chunk_size = terrain
+            .chunk_descriptor(cx, cy, lod)
+            .map_or(self.config.cell_size, |d| d.chunk_size);
+        let base_resolution = (chunk_size / self.config.tile_size_m).round().max(1.0) as usize;
+        let chunk = HeightChunk::sample(cx, cy, lod, chunk_size, base_resolution, |x, y| {
+            terrain.height_at(x, y)
+        });
+        let data = match codec::encode_heights(&chunk.heights) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Could not encode heights of chunk {}:{}: {}", cx, cy, e);
+                return None;
+            }
+        };
+        Some(ChunkData {
+            chunk_id: format!("{}:{}", cx, cy),
+            cx,
+            cy,
+            lod,
+            resolution: chunk.resolution as u32,
+            cell_size: chunk.cell_size,
+            data,
+        })
+    }
+
+    /// `world.chunk.voxels` payload for a cell, if its chunk has overrides.
+    fn chunk_voxels_event(&self, coord: &CellCoord) -> Option<ChunkVoxels> {
+        self.world
+            .terrain
+            .voxel_layer()?
+            .chunk_event(coord.x, coord.y)
+    }
+
+    /// Footprint colliders for a cell's solid voxel boxes.
+    ///
+    /// Physics is 2D, so only boxes reaching into the band a walker
+    /// occupies (ground to [`VOXEL_COLLIDER_CLEARANCE`] above it) become
+    /// obstacles; buried cells and high overhangs are left out.
+    fn voxel_colliders(&self, coord: CellCoord) -> Vec<(ColliderShape, (f32, f32))> {
+        let Some(voxels) = self.world.terrain.voxel_layer() else {
+            return Vec::new();
+        };
+        voxels
+            .solid_boxes(coord.x, coord.y)
+            .into_iter()
+            .filter_map(|b| {
+                let centre = (0.5 * (b.min.x + b.max.x), 0.5 * (b.min.y + b.max.y));
+                let ground = self.world.terrain.height_at(centre.0, centre.1);
+                let reaches_walker =
+                    b.max.z > ground && b.min.z < ground + VOXEL_COLLIDER_CLEARANCE;
+                reaches_walker.then_some((
+                    ColliderShape::Box {
+                        width: b.max.x - b.min.x,
+                        height: b.max.y - b.min.y,
+                    },
+                    centre,
+                ))
+            })
+            .collect()
+    }
+
+    /// Chebyshev distance (in cells) from `coord` to the nearest participant;
+    /// `0` is the participant's own cell.  `u32::MAX` when nobody is around.
+    fn cell_priority(&self, coord: &CellCoord) -> u32 {
+        self.participant_positions
+            .values()
+            .map(|pos| self.cell_priority_from(pos, coord))
+            .min()
+            .unwrap_or(u32::MAX)
+    }
+
+    /// Chebyshev distance (in cells, and layers with vertical streaming)
+    /// between `pos`'s cell and `coord`.
+    fn cell_priority_from(&self, pos: &Vec3, coord: &CellCoord) -> u32 {
+        let cx = (pos.x / self.config.cell_size).floor() as i32;
+        let cy = (pos.y / self.config.cell_size).floor() as i32;
+        (coord.x - cx)
+            .unsigned_abs()
+            .max((coord.y - cy).unsigned_abs())
+            .max((coord.z - self.layer_of(pos.z)).unsigned_abs())
+    }
+
+    /// World-space origin of a cell (its lowest x/y corner).
+    fn cell_origin(&self, coord: CellCoord) -> (f32, f32) {
+        (
+            coord.x as f32 * self.config.cell_size,
+            coord.y as f32 * self.config.cell_size,
+        )
+    }
+
+    /// Colliders for a cell's terrain chunk at the cell's LOD, as `(shape,
+    /// centre)`; empty if the backend can't provide one.  A chunk with holes
+    /// gets boxes around its hole cells instead (see [`solid_footprint`]).
+    fn terrain_colliders(&self, coord: CellCoord) -> Vec<(ColliderShape, (f32, f32))> {
+        let (ox, oy) = self.cell_origin(coord);
+        // Edge chunks are solid all over, whatever the terrain there.
+        let collider = if self.chunk_edge(coord.x, coord.y).is_some() {
+            ColliderShape::Box {
+                width: self.config.cell_size,
+                height: self.config.cell_size,
+            }
+        } else {
+            let lod = self.cell_lods.get(&coord).copied().unwrap_or(0);
+            let Some(collider) = self.world.terrain.collider_for_chunk(coord.x, coord.y, lod)
+            else {
+                return Vec::new();
+            };
+            if let Some(holes) = self.world.terrain.chunk_holes(coord.x, coord.y) {
+                let size = match collider {
+                    ColliderShape::Box { width, .. } => width,
+                    _ => self.config.cell_size,
+                };
+                return solid_footprint(size, &holes)
+                    .into_iter()
+                    .map(|(shape, (x, y))| (shape, (ox + x, oy + y)))
+                    .collect();
+            }
+            collider
+        };
+        let half = match collider {
+            ColliderShape::Box { width, height } => (0.5 * width, 0.5 * height),
+            _ => (0.5 * self.config.cell_size, 0.5 * self.config.cell_size),
+        };
+        vec![(collider, (ox + half.0, oy + half.1))]
+    }
+
+    /// Re-register the terrain bodies of `coords` (those that have some)
+    /// with their current colliders, after an edit or a LOD change.
+    fn refresh_terrain_bodies(&mut self, coords: &[CellCoord]) -> janet::Result<()> {
+        if !coords.iter().any(|c| self.terrain_bodies.contains_key(c)) {
+            return Ok(());
+        }
+        let physics = Arc::clone(&self.physics_registry);
+        let mut registry = physics.write();
+        let sim = registry
+            .default_simulation_mut()
+            .ok_or_else(|| janet::JanetError::Other("No default physics simulation".into()))?;
+        for &coord in coords {
+            if !self.terrain_bodies.contains_key(&coord) {
+                continue;
+            }
+            let colliders = self.terrain_colliders(coord);
+            if colliders.is_empty() {
+                continue;
+            }
+            for body_id in self.terrain_bodies.remove(&coord).unwrap_or_default() {
+                if let Err(e) = sim.unregister_body(&body_id) {
+                    warn!("Failed to unregister terrain body {}: {}", body_id, e);
+                }
+            }
+            let ids = terrain_body_ids(coord, colliders.len());
+            let mut registered = Vec::with_capacity(ids.len());
+            for (body_id, (shape, position)) in ids.into_iter().zip(colliders) {
+                let params = BodyParams::Static {
+                    shape,
+                    position,
+                    rotation: 0.0,
+                };
+                if let Err(e) = sim.register_body(body_id.clone(), params) {
+                    self.terrain_bodies.insert(coord, registered);
+                    return Err(e);
+                }
+                registered.push(body_id);
+            }
+            self.terrain_bodies.insert(coord, registered);
+        }
+        Ok(())
     }
 
     fn deactivate_cell(&mut self, coord: &CellCoord) -> janet::Result<ChunkDeactivated> {
-        if let Some(id) = self.terrain_bodies.remove(coord) {
+        if let Some(ids) = self.terrain_bodies.remove(coord) {
             let mut registry = self.physics_registry.write();
             if let Some(sim) = registry.default_simulation_mut() {
-                if let Err(e) = sim.unregister_body(&id) {
-                    warn!("Failed to unregister terrain body {}: {}", id, e);
+                for id in &ids {
+                    if let Err(e) = sim.unregister_body(id) {
+                        warn!("Failed to unregister terrain body {}: {}", id, e);
+                    }
                 }
             }
         }
@@ -363,10 +4333,54 @@ impl WorldService {
                     }
                 }
             }
+            // Changed objects were hibernated as they changed.
+            for id in object_ids {
+                self.modified_objects.remove(&id);
+                self.object_grid.remove(&id);
+                if self.world_objects.remove(&id).is_some() {
+                    self.pending_structures_removed
+                        .push(StructureRemoved { structure_id: id });
+                }
+            }
+        }
+
+        let mut released = Vec::new();
+        for id in self.cell_structure_ids(*coord) {
+            if let Some(cells) = self.structure_cells.get_mut(&id) {
+                *cells -= 1;
+                if *cells == 0 {
+                    self.structure_cells.remove(&id);
+                    released.push(id);
+                }
+            }
+        }
+        if !released.is_empty() {
+            let world = Arc::clone(&self.world);
+            let mut registry = self.physics_registry.write();
+            if let Some(sim) = registry.default_simulation_mut() {
+                let with_body = released.iter().filter(|id| {
+                    world
+                        .structures
+                        .get(id)
+                        .is_some_and(|s| s.body_collider().is_some())
+                });
+                for id in with_body {
+                    if let Err(e) = sim.unregister_body(&structure_body_id(id)) {
+                        warn!("Failed to unregister structure body {}: {}", id, e);
+                    }
+                }
+            }
+            self.pending_structures_removed.extend(
+                released
+                    .into_iter()
+                    .filter(|id| world.structures.get(id).is_some_and(|s| s.tier.is_always()))
+                    .map(|structure_id| StructureRemoved { structure_id }),
+            );
         }
 
         debug!("Deactivated cell {}", coord);
         self.active_cells.remove(coord);
+        self.cell_lods.remove(coord);
 
         let chunk_id = format!("{}:{}", coord.x, coord.y);
         Ok(ChunkDeactivated { chunk_id })
@@ -379,21 +4393,87 @@ impl WorldService {
     /// Collect authoritative transforms for every tracked participant.
     ///
     /// These are published each tick so clients can interpolate movement.
-    fn collect_entity_transforms(&self) -> Vec<EntityTransform> {
-        self.participant_positions
-            .iter()
-            .map(|(id, pos)| EntityTransform {
-                entity_id: id.clone(),
-                x: pos.x,
-                y: pos.y,
-                z: pos.z,
-                rotation_y: 0.0,
-                vx: 0.0,
-                vy: 0.0,
-                vz: 0.0,
-                dt: 0.0,
-            })
-            .collect()
+    /// Transforms of the entities that changed since their last published
+    /// one (see [`PublishedTransform::changed`]), and of those due a
+    /// keep-alive.
+    fn collect_entity_transforms(&mut self, out: &mut Vec<EntityTransform>) {
+        let mut published = std::mem::take(&mut self.published_transforms);
+        out.extend(self.participant_positions.iter().filter_map(|(id, pos)| {
+            if !self.transform_due(id) {
+                return None;
+            }
+            let transform = self.entity_transform(id, *pos);
+            self.track_transform(&mut published, id, &transform)
+                .then_some(transform)
+        }));
+        self.published_transforms = published;
+    }
+
+    /// [`collect_entity_transforms`](Self::collect_entity_transforms) for
+    /// handle-addressed batches.
+    fn collect_handle_transforms(&mut self, out: &mut Vec<HandleTransform>) {
+        let mut published = std::mem::take(&mut self.published_transforms);
+        out.extend(self.participant_positions.iter().filter_map(|(id, pos)| {
+            if !self.transform_due(id) {
+                return None;
+            }
+            let handle = *self.entity_handles.get(id)?;
+            let transform = self.entity_transform(id, *pos);
+            self.track_transform(&mut published, id, &transform)
+                .then(|| transform.with_handle(handle))
+        }));
+        self.published_transforms = published;
+    }
+
+    /// `true` (recording it as published) if `transform` should go out
+    /// this tick.  Always `true` with a zero `transform_keepalive_s`.
+    fn track_transform(
+        &self,
+        published: &mut HashMap<Arc<str>, PublishedTransform>,
+        id: &Arc<str>,
+        transform: &EntityTransform,
+    ) -> bool {
+        let keepalive = self.config.transform_keepalive_s;
+        if keepalive <= 0.0 {
+            return true;
+        }
+        let every = (keepalive / self.config.physics_dt).round().max(1.0) as u64;
+        if let Some(last) = published.get(&**id) {
+            let stale = self.tick_count.saturating_sub(last.tick) >= every;
+            if !stale
+                && !last.changed(
+                    transform,
+                    self.config.transform_position_epsilon,
+                    self.config.transform_rotation_epsilon,
+                )
+            {
+                return false;
+            }
+        }
+        published.insert(
+            id.clone(),
+            PublishedTransform::new(transform, self.tick_count),
+        );
+        true
+    }
+
+    /// Velocities span the last physics step, so clients can interpolate
+    /// between consecutive transforms (or extrapolate past the newest).
+    fn entity_transform(&self, id: &Arc<str>, pos: Vec3) -> EntityTransform {
+        let dt = self.sim_dt();
+        let prev = self.previous_positions.get(id).copied().unwrap_or(pos);
+        EntityTransform {
+            entity_id: id.clone(),
+            x: pos.x,
+            y: pos.y,
+            z: pos.z,
+            rotation_y: 0.0,
+            vx: (pos.x - prev.x) / dt,
+            vy: (pos.y - prev.y) / dt,
+            vz: (pos.z - prev.z) / dt,
+            dt,
+            last_intent_seq: self.last_intent_seq.get(&**id).copied(),
+        }
     }
 
     // -----------------------------------------------------------------------
@@ -406,13 +4486,224 @@ impl WorldService {
             return;
         };
 
-        let ids: Vec<_> = self.participant_positions.keys().cloned().collect();
-        for id in ids {
-            if let Ok(transform) = sim.get_transform(&id) {
+        // The simulation is 2D: a body pushed elsewhere takes the ground's
+        // height there, one where it was keeps its own (flying, teleported
+        // aloft, on an upper layer).
+        let terrain = &self.world.terrain;
+        for (id, pos) in self.participant_positions.iter_mut() {
+            if let Ok(transform) = sim.get_transform(id) {
                 let (px, py) = transform.position;
-                self.participant_positions
-                    .insert(id, Vec3::new(px, py, 0.0));
+                if (px, py) != (pos.x, pos.y) {
+                    *pos = Vec3::new(px, py, terrain.height_at(px, py));
+                    self.participant_grid.update(id, *pos);
+                }
+            }
+        }
+    }
+}
+
+/// What was last published of an entity's transform.
+#[derive(Debug, Clone, Copy)]
+struct PublishedTransform {
+    position: [f32; 3],
+    rotation_y: f32,
+    velocity: [f32; 3],
+    tick: u64,
+}
+
+impl PublishedTransform {
+    fn new(t: &EntityTransform, tick: u64) -> Self {
+        Self {
+            position: [t.x, t.y, t.z],
+            rotation_y: t.rotation_y,
+            velocity: [t.vx, t.vy, t.vz],
+            tick,
+        }
+    }
+
+    /// Whether `t` moved more than `position_epsilon` or turned more than
+    /// `rotation_epsilon` from this, or changed velocity enough to carry a
+    /// client's extrapolation more than `position_epsilon` off within one
+    /// step (so an entity that stops is published once more).
+    fn changed(&self, t: &EntityTransform, position_epsilon: f32, rotation_epsilon: f32) -> bool {
+        let distance = |a: [f32; 3], b: [f32; 3]| {
+            ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+        };
+        let turn = (t.rotation_y - self.rotation_y + std::f32::consts::PI)
+            .rem_euclid(std::f32::consts::TAU)
+            - std::f32::consts::PI;
+        distance([t.x, t.y, t.z], self.position) > position_epsilon
+            || turn.abs() > rotation_epsilon
+            || distance([t.vx, t.vy, t.vz], self.velocity) * t.dt > position_epsilon
+    }
+}
+
+/// Reject file names outside `[A-Za-z0-9_-]`, so requests can't escape
+/// the configured directory.
+fn check_file_name(kind: &str, name: &str) -> io::Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid {} name '{}' (use [A-Za-z0-9_-])", kind, name),
+        ))
+    }
+}
+
+/// Distance along the unit ray `dir` to where it enters the sphere, and the
+/// surface normal there, if within `max_dist`.  `None` when the origin is
+/// inside the sphere.
+fn ray_sphere(
+    origin: Vec3,
+    dir: Vec3,
+    max_dist: f32,
+    centre: Vec3,
+    radius: f32,
+) -> Option<(f32, Vec3)> {
+    let (ox, oy, oz) = (
+        centre.x - origin.x,
+        centre.y - origin.y,
+        centre.z - origin.z,
+    );
+    let along = ox * dir.x + oy * dir.y + oz * dir.z;
+    let off_axis = ox * ox + oy * oy + oz * oz - along * along;
+    if off_axis > radius * radius {
+        return None;
+    }
+    let t = along - (radius * radius - off_axis).sqrt();
+    let normal = Vec3::new(
+        (dir.x * t - ox) / radius,
+        (dir.y * t - oy) / radius,
+        (dir.z * t - oz) / radius,
+    );
+    (t >= 0.0 && t <= max_dist).then_some((t, normal))
+}
+
+/// Distance along the unit ray `dir` to where it enters a collider
+/// footprint standing at `base` (turned by `rotation` about the vertical)
+/// and extruded from `base.z - half_height` to `base.z + half_height`, and
+/// the surface normal there, if within `max_dist`.  Shapes other than
+/// circles and boxes use their bounding circle.  `None` when the origin is
+/// inside.
+fn ray_prism(
+    origin: Vec3,
+    dir: Vec3,
+    max_dist: f32,
+    shape: &ColliderShape,
+    base: Vec3,
+    rotation: f32,
+    half_height: f32,
+) -> Option<(f32, Vec3)> {
+    // Into the footprint's own frame.
+    let (sin, cos) = rotation.sin_cos();
+    let (dx, dy) = (origin.x - base.x, origin.y - base.y);
+    let (px, py) = (cos * dx + sin * dy, cos * dy - sin * dx);
+    let (vx, vy) = (cos * dir.x + sin * dir.y, cos * dir.y - sin * dir.x);
+
+    // Where the ray is over the footprint, with the side normal on entry.
+    let (side_in, side_out, (nx, ny)) = if let ColliderShape::Box { width, height } = shape {
+        let (x_in, x_out) = ray_slab(px, vx, -width * 0.5, width * 0.5)?;
+        let (y_in, y_out) = ray_slab(py, vy, -height * 0.5, height * 0.5)?;
+        let normal = if x_in > y_in {
+            (-vx.signum(), 0.0)
+        } else {
+            (0.0, -vy.signum())
+        };
+        (x_in.max(y_in), x_out.min(y_out), normal)
+    } else {
+        let radius = collider_bounding_radius(shape);
+        let a = vx * vx + vy * vy;
+        let b = px * vx + py * vy;
+        let c = px * px + py * py - radius * radius;
+        if a <= f32::EPSILON {
+            // Vertical ray: over the disc or not at all.
+            if c > 0.0 {
+                return None;
             }
+            (f32::NEG_INFINITY, f32::INFINITY, (0.0, 0.0))
+        } else {
+            let disc = b * b - a * c;
+            if disc < 0.0 || radius <= 0.0 {
+                return None;
+            }
+            let root = disc.sqrt();
+            let (t_in, t_out) = ((-b - root) / a, (-b + root) / a);
+            let normal = ((px + vx * t_in) / radius, (py + vy * t_in) / radius);
+            (t_in, t_out, normal)
         }
+    };
+    let (z_in, z_out) = ray_slab(origin.z, dir.z, base.z - half_height, base.z + half_height)?;
+
+    let t = side_in.max(z_in);
+    if t > side_out.min(z_out) || t < 0.0 || t > max_dist {
+        return None;
+    }
+    let normal = if z_in >= side_in {
+        Vec3::new(0.0, 0.0, -dir.z.signum())
+    } else {
+        Vec3::new(cos * nx - sin * ny, sin * nx + cos * ny, 0.0)
+    };
+    Some((t, normal))
+}
+
+/// Ray parameters over which `o + d * t` lies within `[lo, hi]`.
+fn ray_slab(o: f32, d: f32, lo: f32, hi: f32) -> Option<(f32, f32)> {
+    if d.abs() <= f32::EPSILON {
+        return (lo..=hi)
+            .contains(&o)
+            .then_some((f32::NEG_INFINITY, f32::INFINITY));
+    }
+    let (a, b) = ((lo - o) / d, (hi - o) / d);
+    Some((a.min(b), a.max(b)))
+}
+
+/// Refuse a terrain edit with non-finite values or a radius over
+/// [`MAX_DEFORM_RADIUS`].
+fn check_terrain_edit(center: Vec3, radius: f32, delta: f32) -> janet::Result<()> {
+    if radius > MAX_DEFORM_RADIUS {
+        return Err(janet::JanetError::Other(format!(
+            "radius {} is over the limit of {}",
+            radius, MAX_DEFORM_RADIUS
+        )));
     }
+    if ![center.x, center.y, radius, delta]
+        .iter()
+        .all(|v| v.is_finite())
+    {
+        return Err(janet::JanetError::Other(
+            "x, y, radius and delta must be finite".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Physics body ids of a cell's `count` terrain colliders: the chunk's own
+/// id, numbered when holes split it.
+fn terrain_body_ids(coord: CellCoord, count: usize) -> Vec<String> {
+    let id = format!("terrain.{}.{}", coord.x, coord.y);
+    match count {
+        1 => vec![id],
+        _ => (0..count).map(|i| format!("{}.{}", id, i)).collect(),
+    }
+}
+
+/// Physics body id of a registry structure.
+fn structure_body_id(structure_id: &str) -> String {
+    format!("structure.{}", structure_id)
+}
+
+/// Static body of a registry structure: its current collider at its
+/// ground position, turned by its yaw (`None` while it has no collider).
+/// Pitch, roll and scale are visual only.
+fn structure_body(s: &StructureInstance) -> Option<BodyParams> {
+    s.body_collider().map(|shape| BodyParams::Static {
+        shape: shape.clone(),
+        position: (s.position.x, s.position.y),
+        rotation: s.rotation_y,
+    })
 }
@@ -0,0 +1,66 @@
+//! Region-based sharding.
+//!
+//! The world is divided into square super-regions of `region_size` world
+//! units, each optionally assigned to a shard (one world-service process).
+//! A shard simulates only the participants standing in its own or in
+//! unassigned super-regions; when one walks into another shard's
+//! super-region it is handed off (see
+//! [`ShardHandoff`](crate::protocol::ShardHandoff)).
+//!
+//! Cells near a border may be streamed by both neighbouring shards; each
+//! participant is authoritative on exactly one of them.
+
+use crate::types::{ShardConfig, Vec3};
+use std::collections::HashMap;
+
+pub struct ShardMap {
+    shard_id: String,
+    region_size: f32,
+    handoff_timeout_s: f32,
+    owners: HashMap<(i32, i32), String>,
+}
+
+impl ShardMap {
+    /// Later entries for the same super-region win (validation rejects
+    /// duplicates).
+    pub fn new(config: &ShardConfig) -> Self {
+        Self {
+            shard_id: config.shard_id.clone(),
+            region_size: config.region_size,
+            handoff_timeout_s: config.handoff_timeout_s,
+            owners: config
+                .regions
+                .iter()
+                .map(|r| ((r.rx, r.ry), r.shard_id.clone()))
+                .collect(),
+        }
+    }
+
+    /// This instance's shard id.
+    pub fn shard_id(&self) -> &str {
+        &self.shard_id
+    }
+
+    /// How long a handoff may go unacknowledged before it is retried.
+    pub fn handoff_timeout_s(&self) -> f32 {
+        self.handoff_timeout_s
+    }
+
+    /// Super-region containing `pos`.
+    pub fn region_of(&self, pos: Vec3) -> (i32, i32) {
+        (
+            (pos.x / self.region_size).floor() as i32,
+            (pos.y / self.region_size).floor() as i32,
+        )
+    }
+
+    /// Shard assigned to the super-region containing `pos`, if any.
+    pub fn owner_at(&self, pos: Vec3) -> Option<&str> {
+        self.owners.get(&self.region_of(pos)).map(String::as_str)
+    }
+
+    /// The shard `pos` must be handed off to, or `None` if it stays here.
+    pub fn handoff_target(&self, pos: Vec3) -> Option<&str> {
+        self.owner_at(pos).filter(|owner| *owner != self.shard_id)
+    }
+}
@@ -0,0 +1,89 @@
+//! Coastlines where the terrain crosses a global sea level.
+//!
+//! Each chunk's shoreline is the `sea_level` contour of the terrain, traced
+//! with marching squares over a `(resolution + 1)²` grid of
+//! [`TerrainSource::height_at`] samples spanning the whole chunk.  Samples on
+//! a chunk edge are shared with the neighbour, so segments meet exactly
+//! across chunk boundaries.
+//!
+//! Segments are unordered `[x0, y0, x1, y1]` pairs in world units; clients
+//! use them for foam, shore decals, or AI coast-following.
+
+use crate::terrain::TerrainSource;
+
+/// Grid cells per chunk side used to trace the shoreline.
+pub const SHORELINE_RESOLUTION: usize = 16;
+
+/// Shoreline segments inside the chunk whose lower corner is `(x0, y0)`.
+pub fn segments(
+    terrain: &dyn TerrainSource,
+    x0: f32,
+    y0: f32,
+    chunk_size: f32,
+    sea_level: f32,
+) -> Vec<[f32; 4]> {
+    let n = SHORELINE_RESOLUTION;
+    let step = chunk_size / n as f32;
+    let pos = |i: usize| i as f32 * step;
+
+    // Heights relative to the sea: negative is underwater.
+    let mut depth = Vec::with_capacity((n + 1) * (n + 1));
+    for row in 0..=n {
+        for col in 0..=n {
+            depth.push(terrain.height_at(x0 + pos(col), y0 + pos(row)) - sea_level);
+        }
+    }
+    let d = |col: usize, row: usize| depth[row * (n + 1) + col];
+
+    let mut out = Vec::new();
+    for row in 0..n {
+        for col in 0..n {
+            // Corners counter-clockwise from the lower left.
+            let corners = [
+                (pos(col), pos(row), d(col, row)),
+                (pos(col + 1), pos(row), d(col + 1, row)),
+                (pos(col + 1), pos(row + 1), d(col + 1, row + 1)),
+                (pos(col), pos(row + 1), d(col, row + 1)),
+            ];
+
+            // Crossing point on every edge whose ends straddle the sea.
+            let mut crossings = Vec::with_capacity(4);
+            for k in 0..4 {
+                let (ax, ay, da) = corners[k];
+                let (bx, by, db) = corners[(k + 1) % 4];
+                if (da < 0.0) != (db < 0.0) {
+                    let t = da / (da - db);
+                    crossings.push([x0 + ax + t * (bx - ax), y0 + ay + t * (by - ay)]);
+                }
+            }
+
+            match crossings.len() {
+                2 => out.push([
+                    crossings[0][0],
+                    crossings[0][1],
+                    crossings[1][0],
+                    crossings[1][1],
+                ]),
+                4 => {
+                    // Saddle: the cell centre decides which diagonal is land.
+                    let centre = corners.iter().map(|c| c.2).sum::<f32>() * 0.25;
+                    let pairs = if (centre < 0.0) == (corners[0].2 < 0.0) {
+                        [(0, 1), (2, 3)]
+                    } else {
+                        [(3, 0), (1, 2)]
+                    };
+                    for (a, b) in pairs {
+                        out.push([
+                            crossings[a][0],
+                            crossings[a][1],
+                            crossings[b][0],
+                            crossings[b][1],
+                        ]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    out
+}
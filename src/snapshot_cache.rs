@@ -0,0 +1,129 @@
+//! Shared snapshot replies for late joiners.
+//!
+//! Building and serialising a [`WorldSnapshot`] walks every active chunk,
+//! structure and entity, so a crowd joining at once (an event start) would
+//! pay that cost once per request.  [`SnapshotCache`] keeps the last
+//! serialised reply per form (full / compact) and hands it to every request
+//! made within `max_age_ticks` of the frame it was taken at.  A terrain edit
+//! always forces a rebuild, since a joiner that subscribed after the
+//! `world.terrain.modified` went out could never catch up on it.
+//!
+//! Gzip replies ([`SnapshotEncoding::Gzip`]) are compressed once per cached
+//! snapshot, on first use.
+
+use crate::codec;
+use crate::protocol::{EncodedSnapshot, SnapshotEncoding, WorldSnapshot};
+use std::io;
+use std::sync::{Arc, OnceLock};
+
+/// One serialised snapshot reply.
+#[derive(Debug)]
+pub struct CachedSnapshot {
+    /// Service tick the snapshot was built at.
+    pub frame: u64,
+    /// Terrain edits included ([`WorldService::terrain_revision`](crate::service::WorldService::terrain_revision)).
+    pub terrain_revision: u64,
+    /// `value` is a `CompactWorldSnapshot` rather than a `WorldSnapshot`.
+    pub compact: bool,
+    /// Plain JSON reply.
+    pub value: Option<serde_json::Value>,
+    encoded: OnceLock<Option<serde_json::Value>>,
+}
+
+impl CachedSnapshot {
+    pub fn new(frame: u64, terrain_revision: u64, compact: bool, snapshot: WorldSnapshot) -> Self {
+        let value = if compact {
+            serde_json::to_value(snapshot.compact()).ok()
+        } else {
+            serde_json::to_value(&snapshot).ok()
+        };
+        Self {
+            frame,
+            terrain_revision,
+            compact,
+            value,
+            encoded: OnceLock::new(),
+        }
+    }
+
+    /// [`EncodedSnapshot`] reply, compressed on the first call.
+    pub fn encoded(&self) -> Option<serde_json::Value> {
+        self.encoded
+            .get_or_init(|| {
+                let json = serde_json::to_vec(&self.value).ok()?;
+                let data = codec::encode(&json).ok()?;
+                serde_json::to_value(EncodedSnapshot {
+                    frame: self.frame,
+                    encoding: SnapshotEncoding::Gzip,
+                    compact: self.compact,
+                    data,
+                })
+                .ok()
+            })
+            .clone()
+    }
+}
+
+/// Last snapshot reply of each form, reused while fresh.
+#[derive(Debug, Default)]
+pub struct SnapshotCache {
+    max_age_ticks: u64,
+    full: Option<Arc<CachedSnapshot>>,
+    compact: Option<Arc<CachedSnapshot>>,
+}
+
+impl SnapshotCache {
+    /// `max_age_ticks = 0` only shares snapshots between requests in the
+    /// same tick.
+    pub fn new(max_age_ticks: u64) -> Self {
+        Self {
+            max_age_ticks,
+            ..Self::default()
+        }
+    }
+
+    /// Cached reply if it was built at most `max_age_ticks` before `frame`
+    /// with the same terrain; otherwise `build` a new one and keep it.
+    pub fn get_or_build(
+        &mut self,
+        frame: u64,
+        terrain_revision: u64,
+        compact: bool,
+        build: impl FnOnce() -> WorldSnapshot,
+    ) -> Arc<CachedSnapshot> {
+        let max_age = self.max_age_ticks;
+        let slot = if compact {
+            &mut self.compact
+        } else {
+            &mut self.full
+        };
+        if let Some(cached) = slot.as_ref().filter(|c| {
+            c.terrain_revision == terrain_revision
+                && frame.checked_sub(c.frame).is_some_and(|age| age <= max_age)
+        }) {
+            return cached.clone();
+        }
+        let cached = Arc::new(CachedSnapshot::new(
+            frame,
+            terrain_revision,
+            compact,
+            build(),
+        ));
+        *slot = Some(cached.clone());
+        cached
+    }
+
+    /// Drop both cached replies.
+    pub fn clear(&mut self) {
+        self.full = None;
+        self.compact = None;
+    }
+}
+
+/// Inverse of [`CachedSnapshot::encoded`]: the snapshot JSON in `data`.
+pub fn decode(encoded: &EncodedSnapshot) -> io::Result<serde_json::Value> {
+    let json = match encoded.encoding {
+        SnapshotEncoding::Gzip => codec::decode(&encoded.data)?,
+    };
+    serde_json::from_slice(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
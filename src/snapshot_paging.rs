@@ -0,0 +1,197 @@
+//! Paged delivery of snapshot replies.
+//!
+//! A snapshot of a dense region can outgrow the bus's payload limit, so a
+//! `world.cmd.snapshot` that sets `paged: true` gets its reply in pieces:
+//! [`paginate`] serialises it once and cuts the JSON into pages of at most
+//! `page_bytes`, sent as `world.snapshot.begin`, one `world.snapshot.page`
+//! per page and `world.snapshot.end`.
+//!
+//! [`SnapshotAssembler`] is the client side: it collects the pages of the
+//! snapshots it asked for, in whatever order they arrive, and parses the
+//! reply once the end marker shows every page is there.
+
+use crate::protocol::{SnapshotBegin, SnapshotEncoding, SnapshotEnd, SnapshotPage};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum SnapshotPageError {
+    #[error("Snapshot '{0}' ended before it began")]
+    NotBegun(String),
+    #[error("Snapshot '{snapshot_id}' is missing {missing} of {total} pages")]
+    Incomplete {
+        snapshot_id: String,
+        missing: u32,
+        total: u32,
+    },
+    #[error("Snapshot '{snapshot_id}' does not parse: {reason}")]
+    Malformed { snapshot_id: String, reason: String },
+}
+
+/// One reply cut into pages, in the order they are sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PagedSnapshot {
+    pub begin: SnapshotBegin,
+    pub pages: Vec<SnapshotPage>,
+    pub end: SnapshotEnd,
+}
+
+/// Cut the reply `json` into pages whose `data` takes at most
+/// `page_bytes` once escaped into a JSON string (pages never split a
+/// character, and hold at least one).  `compact` and `encoding` describe
+/// what `json` is.
+pub fn paginate(
+    snapshot_id: &str,
+    frame: u64,
+    compact: bool,
+    encoding: Option<SnapshotEncoding>,
+    json: &str,
+    page_bytes: usize,
+) -> PagedSnapshot {
+    let mut slices = Vec::new();
+    let (mut start, mut size) = (0, 0);
+    for (i, c) in json.char_indices() {
+        let len = escaped_len(c);
+        if size + len > page_bytes && i > start {
+            slices.push(&json[start..i]);
+            (start, size) = (i, 0);
+        }
+        size += len;
+    }
+    if start < json.len() {
+        slices.push(&json[start..]);
+    }
+
+    let total = slices.len() as u32;
+    let pages = slices
+        .into_iter()
+        .enumerate()
+        .map(|(index, data)| SnapshotPage {
+            snapshot_id: snapshot_id.to_string(),
+            index: index as u32,
+            total,
+            data: data.to_string(),
+        })
+        .collect();
+    PagedSnapshot {
+        begin: SnapshotBegin {
+            snapshot_id: snapshot_id.to_string(),
+            frame,
+            total_pages: total,
+            total_bytes: json.len(),
+            compact,
+            encoding,
+        },
+        pages,
+        end: SnapshotEnd {
+            snapshot_id: snapshot_id.to_string(),
+            total_pages: total,
+        },
+    }
+}
+
+/// Bytes `c` takes inside a JSON string.
+fn escaped_len(c: char) -> usize {
+    match c {
+        '"' | '\\' | '\n' | '\r' | '\t' | '\u{8}' | '\u{c}' => 2,
+        c if (c as u32) < 0x20 => 6,
+        c => c.len_utf8(),
+    }
+}
+
+/// A reassembled paged snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssembledSnapshot {
+    pub begin: SnapshotBegin,
+    /// The reply, to decode as `begin.compact` / `begin.encoding` say.
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Default)]
+struct Pending {
+    begin: Option<SnapshotBegin>,
+    pages: BTreeMap<u32, String>,
+}
+
+/// Client-side reassembly of paged snapshots.
+#[derive(Debug, Default)]
+pub struct SnapshotAssembler {
+    pending: HashMap<String, Pending>,
+}
+
+impl SnapshotAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect the pages of `snapshot_id` (the `command_id` of a paged
+    /// `world.cmd.snapshot`).  Call before sending the request; pages of
+    /// snapshots nobody expects are ignored.
+    pub fn expect(&mut self, snapshot_id: impl Into<String>) {
+        self.pending.entry(snapshot_id.into()).or_default();
+    }
+
+    /// Stop collecting `snapshot_id` (the request failed or timed out).
+    pub fn discard(&mut self, snapshot_id: &str) {
+        self.pending.remove(snapshot_id);
+    }
+
+    pub fn is_pending(&self, snapshot_id: &str) -> bool {
+        self.pending.contains_key(snapshot_id)
+    }
+
+    /// Record the begin marker (or the request's reply).
+    pub fn begin(&mut self, begin: &SnapshotBegin) {
+        if let Some(pending) = self.pending.get_mut(&begin.snapshot_id) {
+            pending.begin = Some(begin.clone());
+        }
+    }
+
+    pub fn page(&mut self, page: &SnapshotPage) {
+        if let Some(pending) = self.pending.get_mut(&page.snapshot_id) {
+            pending.pages.insert(page.index, page.data.clone());
+        }
+    }
+
+    /// Finish `end`'s snapshot: `Ok(None)` for one that isn't expected,
+    /// otherwise the reply or why it can't be had.  Either way the snapshot
+    /// is no longer pending.
+    pub fn end(
+        &mut self,
+        end: &SnapshotEnd,
+    ) -> Result<Option<AssembledSnapshot>, SnapshotPageError> {
+        let Some(pending) = self.pending.remove(&end.snapshot_id) else {
+            return Ok(None);
+        };
+        let Some(begin) = pending.begin else {
+            return Err(SnapshotPageError::NotBegun(end.snapshot_id.clone()));
+        };
+        let total = end.total_pages;
+        let present = (0..total).filter(|i| pending.pages.contains_key(i)).count() as u32;
+        if present < total {
+            return Err(SnapshotPageError::Incomplete {
+                snapshot_id: end.snapshot_id.clone(),
+                missing: total - present,
+                total,
+            });
+        }
+
+        let mut json = String::with_capacity(begin.total_bytes);
+        for data in pending.pages.range(0..total).map(|(_, data)| data) {
+            json.push_str(data);
+        }
+        let malformed = |reason: String| SnapshotPageError::Malformed {
+            snapshot_id: end.snapshot_id.clone(),
+            reason,
+        };
+        if json.len() != begin.total_bytes {
+            return Err(malformed(format!(
+                "{} bytes, expected {}",
+                json.len(),
+                begin.total_bytes
+            )));
+        }
+        let value = serde_json::from_str(&json).map_err(|e| malformed(e.to_string()))?;
+        Ok(Some(AssembledSnapshot { begin, value }))
+    }
+}
@@ -0,0 +1,106 @@
+//! Uniform-grid index of participant (and world object) positions.
+//!
+//! Range queries (who is within hearing distance of an emote, what a ray
+//! may pass) only look at the buckets the query area overlaps instead of
+//! every entry.  The service keeps the grid in step with its positions; a
+//! move that stays inside its bucket costs one map lookup.
+
+use crate::types::Vec3;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub struct SpatialGrid {
+    bucket_size: f32,
+    buckets: HashMap<(i32, i32), Vec<Arc<str>>>,
+    bucket_of: HashMap<Arc<str>, (i32, i32)>,
+}
+
+impl SpatialGrid {
+    pub fn new(bucket_size: f32) -> Self {
+        Self {
+            bucket_size,
+            buckets: HashMap::new(),
+            bucket_of: HashMap::new(),
+        }
+    }
+
+    fn key(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            (x / self.bucket_size).floor() as i32,
+            (y / self.bucket_size).floor() as i32,
+        )
+    }
+
+    /// Insert `id` at `pos`, or move it there.
+    pub fn update(&mut self, id: &Arc<str>, pos: Vec3) {
+        let key = self.key(pos.x, pos.y);
+        match self.bucket_of.insert(id.clone(), key) {
+            Some(old) if old == key => return,
+            Some(old) => self.remove_from(old, id),
+            None => {}
+        }
+        self.buckets.entry(key).or_default().push(id.clone());
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        if let Some(old) = self.bucket_of.remove(id) {
+            self.remove_from(old, id);
+        }
+    }
+
+    fn remove_from(&mut self, key: (i32, i32), id: &str) {
+        if let Some(bucket) = self.buckets.get_mut(&key) {
+            bucket.retain(|other| other.as_ref() != id);
+            if bucket.is_empty() {
+                self.buckets.remove(&key);
+            }
+        }
+    }
+
+    /// Ids in every bucket the circle around `(x, y)` overlaps: a superset
+    /// of those within `radius`, so callers still check the distance.
+    pub fn candidates(&self, x: f32, y: f32, radius: f32) -> Vec<&Arc<str>> {
+        self.candidates_in_rect(x - radius, y - radius, x + radius, y + radius)
+    }
+
+    /// Ids in every bucket the rectangle overlaps: a superset of those
+    /// inside it.
+    pub fn candidates_in_rect(
+        &self,
+        min_x: f32,
+        min_y: f32,
+        max_x: f32,
+        max_y: f32,
+    ) -> Vec<&Arc<str>> {
+        let (x0, y0) = self.key(min_x, min_y);
+        let (x1, y1) = self.key(max_x, max_y);
+        let span = (x1 as i64 - x0 as i64 + 1) * (y1 as i64 - y0 as i64 + 1);
+
+        // A query wider than the populated area walks the buckets instead.
+        if span > self.buckets.len() as i64 {
+            return self
+                .buckets
+                .iter()
+                .filter(|(&(bx, by), _)| (x0..=x1).contains(&bx) && (y0..=y1).contains(&by))
+                .flat_map(|(_, ids)| ids)
+                .collect();
+        }
+        let mut out = Vec::new();
+        for by in y0..=y1 {
+            for bx in x0..=x1 {
+                if let Some(ids) = self.buckets.get(&(bx, by)) {
+                    out.extend(ids);
+                }
+            }
+        }
+        out
+    }
+
+    pub fn len(&self) -> usize {
+        self.bucket_of.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bucket_of.is_empty()
+    }
+}
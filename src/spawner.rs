@@ -0,0 +1,142 @@
+//! Spawners: server entities of one archetype kept alive around a fixed
+//! spot while its cell is active (a wolf den, a deer meadow, a cart depot).
+//!
+//! Spawners come from the world gen file's `[[spawners]]` table (see
+//! [`SpawnerDef`]).  Every tick the service hands [`Spawners::evaluate`]
+//! the simulated clock and which spawners sit in an active cell:
+//!
+//! * A spawner whose cell activates fills up to `max_population` at once.
+//! * While it stays active, an entity that goes away (despawned, kicked)
+//!   is replaced `respawn_s` later.
+//! * Once its cell deactivates, every entity it spawned is despawned,
+//!   wherever the entity has wandered to.
+//!
+//! Entities spawn at spots jittered within `radius` of the spawner, a pure
+//! function of the world seed, the spawner and the spawn serial.
+
+use crate::entity::EntitySpec;
+use crate::worldgen::{unit, SpawnerDef};
+use std::collections::{BTreeSet, HashMap};
+
+/// Result of one [`Spawners::evaluate`] pass.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpawnerChange {
+    pub spawned: Vec<EntitySpec>,
+    /// Ids to despawn.
+    pub despawned: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct SpawnerState {
+    live: BTreeSet<String>,
+    /// Simulated times lost entities may be replaced at.
+    respawn_at: Vec<f64>,
+    active: bool,
+    next_serial: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Spawners {
+    defs: Vec<SpawnerDef>,
+    seed: u64,
+    states: Vec<SpawnerState>,
+    /// Spawner index of every live entity.
+    owners: HashMap<String, usize>,
+}
+
+impl Spawners {
+    pub fn new(defs: Vec<SpawnerDef>, seed: u64) -> Self {
+        let states = defs.iter().map(|_| SpawnerState::default()).collect();
+        Self {
+            defs,
+            seed,
+            states,
+            owners: HashMap::new(),
+        }
+    }
+
+    pub fn defs(&self) -> &[SpawnerDef] {
+        &self.defs
+    }
+
+    /// Ids of every live spawned entity (unordered).
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.owners.keys().map(String::as_str)
+    }
+
+    pub fn contains(&self, entity_id: &str) -> bool {
+        self.owners.contains_key(entity_id)
+    }
+
+    /// Live entities of spawner `id`.
+    pub fn population(&self, id: &str) -> usize {
+        self.defs
+            .iter()
+            .position(|d| d.id == id)
+            .map_or(0, |i| self.states[i].live.len())
+    }
+
+    /// An entity went away by other means at simulated time `now_s`: its
+    /// spawner replaces it `respawn_s` later.
+    pub fn forget(&mut self, entity_id: &str, now_s: f64) {
+        if let Some(index) = self.owners.remove(entity_id) {
+            let state = &mut self.states[index];
+            state.live.remove(entity_id);
+            if state.active {
+                state
+                    .respawn_at
+                    .push(now_s + self.defs[index].respawn_s as f64);
+            }
+        }
+    }
+
+    /// Bring every spawner in line at simulated time `now_s`;
+    /// `active(x, y)` tells whether the spawner at `(x, y)` is in an active
+    /// cell.
+    pub fn evaluate(&mut self, now_s: f64, active: impl Fn(f32, f32) -> bool) -> SpawnerChange {
+        let Self {
+            defs,
+            seed,
+            states,
+            owners,
+        } = self;
+        let mut change = SpawnerChange::default();
+        for (index, (def, state)) in defs.iter().zip(states.iter_mut()).enumerate() {
+            state.active = active(def.x, def.y);
+            if !state.active {
+                state.respawn_at.clear();
+                for id in std::mem::take(&mut state.live) {
+                    owners.remove(&id);
+                    change.despawned.push(id);
+                }
+                continue;
+            }
+
+            state.respawn_at.retain(|&t| t > now_s);
+            let missing = def
+                .max_population
+                .saturating_sub(state.live.len() + state.respawn_at.len());
+            for _ in 0..missing {
+                let serial = state.next_serial;
+                state.next_serial += 1;
+                let key = format!("{}:spawner:{}:{}", seed, def.id, serial);
+                let angle = unit(&key, 0) * std::f32::consts::TAU;
+                let dist = def.radius * unit(&key, 1).sqrt();
+                let entity_id = format!("spawner:{}:{}", def.id, serial);
+                state.live.insert(entity_id.clone());
+                owners.insert(entity_id.clone(), index);
+                change.spawned.push(EntitySpec {
+                    entity_id,
+                    archetype: def.archetype.clone(),
+                    x: def.x + dist * angle.cos(),
+                    y: def.y + dist * angle.sin(),
+                    rotation_y: unit(&key, 2) * std::f32::consts::TAU,
+                    components: def.components.clone(),
+                    behaviour: def.behaviour.clone(),
+                    ..Default::default()
+                });
+            }
+        }
+        change
+    }
+}
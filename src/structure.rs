@@ -1,28 +1,270 @@
 //! Structure subsystem: static mesh instances and their registry,
 //! plus the top-level `World` data container.
+//!
+//! Registries load and save structure files: JSON of the form
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "structures": [
+//!     {
+//!       "id": "hut-1",
+//!       "type_id": "buildings/hut",
+//!       "position": { "x": 12.0, "y": -4.5, "z": 3.2 },
+//!       "rotation_y": 1.57,
+//!       "pitch": 0.2,
+//!       "scale": [1.0, 2.0, 1.0],
+//!       "collider": { "Box": { "width": 4.0, "height": 3.0 } },
+//!       "bounds_radius": 2.5,
+//!       "tier": "near",
+//!       "tags": ["shelter"],
+//!       "metadata": { "owner": "alice" }
+//!     },
+//!     {
+//!       "id": "gate-1",
+//!       "type_id": "props/gate",
+//!       "position": { "x": 20.0, "y": 0.0, "z": 3.0 },
+//!       "collider": { "Box": { "width": 4.0, "height": 0.5 } },
+//!       "states": {
+//!         "state": "closed",
+//!         "states": {
+//!           "closed": {
+//!             "collider": { "Box": { "width": 4.0, "height": 0.5 } },
+//!             "transitions": { "open": "open" }
+//!           },
+//!           "open": { "transitions": { "close": "closed" } }
+//!         }
+//!       }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Everything but `id`, `position`, `collider` and `type_id` is optional.
+//! Saved files list structures by id with sorted metadata keys, so a layout
+//! under version control only diffs where it changed.
+//!
+//! Per-type defaults live in a [`StructureCatalog`], loaded from TOML:
+//!
+//! ```toml
+//! [prefabs."buildings/hut"]
+//! collider = { Box = { width = 4.0, height = 3.0 } }
+//! tags = ["shelter"]
+//! tier = "near"
+//! metadata = { asset = "res://buildings/hut.tscn", verbs = ["enter"] }
+//! ```
+//!
+//! Metadata is free-form apart from the keys of [`StructureMeta`], which
+//! registries check on insert.
 
+use crate::protocol::{is_unit_scale, is_zero_angle, unit_scale, StructureSpawned, UNIT_SCALE};
 use crate::terrain::TerrainSource;
 use crate::types::Vec3;
 use janet_operations::physics::types::ColliderShape;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
 use std::sync::Arc;
+use thiserror::Error;
+
+/// Radius of the smallest circle around the origin that encloses `shape`.
+pub fn collider_bounding_radius(shape: &ColliderShape) -> f32 {
+    if let ColliderShape::Circle { radius } = shape {
+        return *radius;
+    }
+    if let ColliderShape::Box { width, height } = shape {
+        return 0.5 * width.hypot(*height);
+    }
+    0.0
+}
+
+// ---------------------------------------------------------------------------
+// Streaming tier
+// ---------------------------------------------------------------------------
+
+/// How close a participant must be for a structure to stream to it.
+///
+/// `Always` structures load with the terrain, over the participant's whole
+/// activation radius.  `Near` and `Detail` ones load within the shorter
+/// `structure_near_radius` / `structure_detail_radius` (see
+/// [`InterestTracker`](crate::interest::InterestTracker)) and reach
+/// clients only through `world.structure.interest`: cell activation
+/// doesn't broadcast them.  Colliders are registered with the cells
+/// whatever the tier.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamTier {
+    #[default]
+    Always,
+    Near,
+    Detail,
+}
+
+impl StreamTier {
+    pub fn is_always(&self) -> bool {
+        *self == Self::Always
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Interactive states
+// ---------------------------------------------------------------------------
+
+/// One named state of an interactive structure.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructureState {
+    /// Collider while in this state; `None` leaves the structure passable
+    /// (an open door).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collider: Option<ColliderShape>,
+    /// Next state by interaction verb.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub transitions: BTreeMap<String, String>,
+}
+
+/// State machine of an interactive structure (door, gate, elevator):
+/// `intent.interact` verbs move it between named states, each with its own
+/// collider.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructureStates {
+    /// Current state.
+    pub state: String,
+    pub states: BTreeMap<String, StructureState>,
+}
+
+impl StructureStates {
+    pub fn current(&self) -> Option<&StructureState> {
+        self.states.get(&self.state)
+    }
+
+    /// The state `verb` leads to from the current one.  Without a verb, the
+    /// only transition out of the current state, if there is exactly one.
+    pub fn next(&self, verb: Option<&str>) -> Option<(&str, &str)> {
+        let transitions = &self.current()?.transitions;
+        match verb {
+            Some(verb) => transitions
+                .get_key_value(verb)
+                .map(|(v, s)| (v.as_str(), s.as_str())),
+            None if transitions.len() == 1 => transitions
+                .iter()
+                .next()
+                .map(|(v, s)| (v.as_str(), s.as_str())),
+            None => None,
+        }
+    }
+
+    /// The first state named (as current or a transition target) without
+    /// a definition.
+    fn undefined_state(&self) -> Option<&str> {
+        std::iter::once(&self.state)
+            .chain(self.states.values().flat_map(|s| s.transitions.values()))
+            .find(|s| !self.states.contains_key(*s))
+            .map(String::as_str)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Metadata
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum StructureMetaError {
+    #[error("structure '{0}' has no type_id")]
+    MissingTypeId(String),
+    #[error("structure '{id}' metadata '{key}' must be {expected}")]
+    Invalid {
+        id: String,
+        key: &'static str,
+        expected: &'static str,
+    },
+    #[error("structure '{id}' has no state '{state}'")]
+    UnknownState { id: String, state: String },
+}
+
+/// The metadata keys the server and clients interpret.  Any other key is
+/// carried along untouched.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructureMeta {
+    /// Prefab / asset family clients instantiate (`"buildings/hut"`).
+    pub type_id: String,
+    /// Client asset path, when it isn't derived from `type_id`.
+    #[serde(default)]
+    pub asset: Option<String>,
+    /// Interaction verbs the structure answers to (`"open"`, `"enter"`).
+    #[serde(default)]
+    pub verbs: Vec<String>,
+}
+
+impl StructureMeta {
+    /// Parse and check the typed keys of `metadata`.  `type_id` is a
+    /// required non-empty string; `verbs` is a string list and `asset` a
+    /// string when present.
+    pub fn from_metadata(
+        id: &str,
+        metadata: &HashMap<String, serde_json::Value>,
+    ) -> Result<Self, StructureMetaError> {
+        let invalid = |key, expected| StructureMetaError::Invalid {
+            id: id.to_string(),
+            key,
+            expected,
+        };
+        let type_id = match metadata.get("type_id") {
+            None => return Err(StructureMetaError::MissingTypeId(id.to_string())),
+            Some(serde_json::Value::String(t)) if !t.is_empty() => t.clone(),
+            Some(_) => return Err(invalid("type_id", "a non-empty string")),
+        };
+        let asset = match metadata.get("asset") {
+            None => None,
+            Some(serde_json::Value::String(a)) => Some(a.clone()),
+            Some(_) => return Err(invalid("asset", "a string")),
+        };
+        let verbs = match metadata.get("verbs") {
+            None => Vec::new(),
+            Some(v) => serde_json::from_value(v.clone())
+                .map_err(|_| invalid("verbs", "a list of strings"))?,
+        };
+        Ok(Self {
+            type_id,
+            asset,
+            verbs,
+        })
+    }
+}
 
 // ---------------------------------------------------------------------------
 // Structure instance
 // ---------------------------------------------------------------------------
 
 /// A single static structure placed in the world (building, rock, barrier …).
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StructureInstance {
     /// Globally unique identifier for the structure.
     pub id: String,
     /// World-space origin of the structure.
     pub position: Vec3,
+    /// Yaw in radians; also the rotation of the physics body.
+    pub rotation_y: f32,
+    /// Pitch and roll in radians, applied after yaw (see
+    /// [`StructureSpawned`]).  Visual only: the 2D collider ignores them.
+    pub pitch: f32,
+    pub roll: f32,
+    /// Scale along the structure's own axes.  Visual only: `collider` is
+    /// already in world units.
+    pub scale: [f32; 3],
     /// Approximate bounding half-extents used for per-chunk bucketing.
     pub bounds_radius: f32,
-    /// Physics collider shape (mesh or convex hull).
+    /// Distance it streams to participants from.
+    pub tier: StreamTier,
+    /// Free-form labels (`"campfire"`, `"spawn_beacon"`) registries index
+    /// for [`StructureRegistry::query_by_tag`].
+    pub tags: Vec<String>,
+    /// Physics collider shape (mesh or convex hull).  Interactive
+    /// structures use their current state's instead.
     pub collider: ColliderShape,
-    /// Arbitrary metadata (asset path, tags, …).
+    /// Named states, for interactive structures.
+    pub states: Option<StructureStates>,
+    /// Arbitrary metadata; the keys of [`StructureMeta`] are typed.
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
@@ -31,43 +273,406 @@ impl StructureInstance {
         Self {
             id: id.into(),
             position,
+            rotation_y: 0.0,
+            pitch: 0.0,
+            roll: 0.0,
+            scale: UNIT_SCALE,
             bounds_radius: 5.0,
+            tier: StreamTier::Always,
+            tags: Vec::new(),
             collider,
+            states: None,
             metadata: HashMap::new(),
         }
     }
+
+    /// An instance of `type_id`'s prefab, or `None` if the catalog doesn't
+    /// have it.  Metadata is the prefab's plus `type_id`.
+    pub fn from_prefab(
+        catalog: &StructureCatalog,
+        type_id: &str,
+        id: impl Into<String>,
+        position: Vec3,
+    ) -> Option<Self> {
+        let prefab = catalog.get(type_id)?;
+        let mut s = Self::new(id, position, prefab.collider.clone());
+        s.bounds_radius = prefab
+            .bounds_radius
+            .unwrap_or_else(|| collider_bounding_radius(&prefab.collider));
+        s.tier = prefab.tier;
+        s.states = prefab.states.clone();
+        s.tags = prefab.tags.clone();
+        s.metadata = prefab.metadata.clone();
+        s.metadata
+            .insert("type_id".into(), serde_json::json!(type_id));
+        Some(s)
+    }
+
+    /// Typed view of [`metadata`](Self::metadata).
+    pub fn meta(&self) -> Result<StructureMeta, StructureMetaError> {
+        StructureMeta::from_metadata(&self.id, &self.metadata)
+    }
+
+    /// Checks registries run on insert: typed metadata, and states that
+    /// only name defined states.
+    pub fn validate(&self) -> Result<(), StructureMetaError> {
+        self.meta()?;
+        if let Some(state) = self.states.as_ref().and_then(|s| s.undefined_state()) {
+            return Err(StructureMetaError::UnknownState {
+                id: self.id.clone(),
+                state: state.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The collider its physics body has right now (`None` = no body).
+    pub fn body_collider(&self) -> Option<&ColliderShape> {
+        match &self.states {
+            Some(states) => states.current()?.collider.as_ref(),
+            None => Some(&self.collider),
+        }
+    }
+
+    /// The spawn event clients instantiate this structure from
+    /// (`type_id` comes from the `type_id` metadata key, which registries
+    /// guarantee; `tags` join the metadata).
+    pub fn to_spawned(&self) -> StructureSpawned {
+        let mut metadata: serde_json::Map<_, _> = self
+            .metadata
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        if !self.tags.is_empty() {
+            metadata.insert("tags".into(), serde_json::json!(self.tags));
+        }
+        StructureSpawned {
+            structure_id: self.id.clone(),
+            type_id: self
+                .metadata
+                .get("type_id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            x: self.position.x,
+            y: self.position.y,
+            z: self.position.z,
+            rotation_y: self.rotation_y,
+            pitch: self.pitch,
+            roll: self.roll,
+            scale: self.scale,
+            state: self.states.as_ref().map(|s| s.state.clone()),
+            metadata: serde_json::Value::Object(metadata),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// File format
+// ---------------------------------------------------------------------------
+
+/// Version written by [`StructureRegistry::save_to_file`].
+pub const STRUCTURE_FILE_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum StructureFileError {
+    #[error("failed to access structure file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid structure file: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("unsupported structure file version {0}")]
+    Version(u32),
+    #[error("structure '{0}' is defined more than once")]
+    Duplicate(String),
+    #[error("invalid structure metadata: {0}")]
+    Metadata(#[from] StructureMetaError),
+}
+
+/// Top level of a structure file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructureFile {
+    pub version: u32,
+    #[serde(default)]
+    pub structures: Vec<StructureRecord>,
+}
+
+/// One structure as stored on disk.  `type_id` lives in the instance's
+/// metadata under the `type_id` key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructureRecord {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_id: Option<String>,
+    pub position: Vec3,
+    #[serde(default)]
+    pub rotation_y: f32,
+    #[serde(default, skip_serializing_if = "is_zero_angle")]
+    pub pitch: f32,
+    #[serde(default, skip_serializing_if = "is_zero_angle")]
+    pub roll: f32,
+    #[serde(default = "unit_scale", skip_serializing_if = "is_unit_scale")]
+    pub scale: [f32; 3],
+    pub collider: ColliderShape,
+    /// Defaults to the bounds of [`StructureInstance::new`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bounds_radius: Option<f32>,
+    #[serde(default, skip_serializing_if = "StreamTier::is_always")]
+    pub tier: StreamTier,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub states: Option<StructureStates>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, serde_json::Value>,
+}
+
+impl From<&StructureInstance> for StructureRecord {
+    fn from(s: &StructureInstance) -> Self {
+        let mut metadata: BTreeMap<_, _> = s
+            .metadata
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        let type_id = match metadata.remove("type_id") {
+            Some(serde_json::Value::String(t)) => Some(t),
+            // Not a string: keep it as plain metadata.
+            Some(other) => {
+                metadata.insert("type_id".into(), other);
+                None
+            }
+            None => None,
+        };
+        Self {
+            id: s.id.clone(),
+            type_id,
+            position: s.position,
+            rotation_y: s.rotation_y,
+            pitch: s.pitch,
+            roll: s.roll,
+            scale: s.scale,
+            collider: s.collider.clone(),
+            bounds_radius: Some(s.bounds_radius),
+            tier: s.tier,
+            tags: s.tags.clone(),
+            states: s.states.clone(),
+            metadata,
+        }
+    }
+}
+
+impl From<StructureRecord> for StructureInstance {
+    fn from(r: StructureRecord) -> Self {
+        let mut s = StructureInstance::new(r.id, r.position, r.collider);
+        s.rotation_y = r.rotation_y;
+        s.pitch = r.pitch;
+        s.roll = r.roll;
+        s.scale = r.scale;
+        s.tier = r.tier;
+        s.tags = r.tags;
+        s.states = r.states;
+        if let Some(radius) = r.bounds_radius {
+            s.bounds_radius = radius;
+        }
+        s.metadata.extend(r.metadata);
+        // Files saved before tags had a field kept them in the metadata.
+        if s.tags.is_empty() {
+            let legacy = s.metadata.get("tags").cloned();
+            if let Some(Ok(tags)) = legacy.map(serde_json::from_value::<Vec<String>>) {
+                s.tags = tags;
+                s.metadata.remove("tags");
+            }
+        }
+        if let Some(type_id) = r.type_id {
+            s.metadata
+                .insert("type_id".into(), serde_json::Value::String(type_id));
+        }
+        s
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Prefab catalog
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Error)]
+pub enum StructureCatalogError {
+    #[error("failed to load structure catalog: {0}")]
+    Load(#[from] config::ConfigError),
+}
+
+/// Defaults for every structure of one `type_id`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructurePrefab {
+    pub collider: ColliderShape,
+    /// Defaults to the collider's bounding radius.
+    #[serde(default)]
+    pub bounds_radius: Option<f32>,
+    /// Free-form labels of every instance.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub tier: StreamTier,
+    /// Initial states of interactive instances.
+    #[serde(default)]
+    pub states: Option<StructureStates>,
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+/// Structure prefabs by `type_id`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructureCatalog {
+    #[serde(default)]
+    pub prefabs: HashMap<String, StructurePrefab>,
+}
+
+impl StructureCatalog {
+    /// Read a TOML file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, StructureCatalogError> {
+        let source = config::File::from(path.as_ref()).format(config::FileFormat::Toml);
+        Ok(config::Config::builder()
+            .add_source(source)
+            .build()?
+            .try_deserialize()?)
+    }
+
+    /// Parse TOML text.
+    pub fn from_toml(text: &str) -> Result<Self, StructureCatalogError> {
+        let source = config::File::from_str(text, config::FileFormat::Toml);
+        Ok(config::Config::builder()
+            .add_source(source)
+            .build()?
+            .try_deserialize()?)
+    }
+
+    pub fn get(&self, type_id: &str) -> Option<&StructurePrefab> {
+        self.prefabs.get(type_id)
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Registry
 // ---------------------------------------------------------------------------
 
+/// Bucket size of [`StructureRegistry::new`]: the default streaming cell.
+pub const DEFAULT_STRUCTURE_BUCKET_SIZE: f32 = 10.0;
+
 /// Holds all static structures placed in the world.
 ///
-/// Future: bucket by spatial grid so streaming can query per-chunk.
+/// Structures are bucketed on a uniform grid (normally the streaming cell
+/// size): each one is listed in every bucket its bounding square overlaps,
+/// so rect and radius queries only visit the buckets they cover.
+#[derive(Clone)]
 pub struct StructureRegistry {
     instances: HashMap<String, StructureInstance>,
+    bucket_size: f32,
+    buckets: HashMap<(i32, i32), Vec<String>>,
+    /// Ids of the structures carrying each tag.
+    tagged: HashMap<String, HashSet<String>>,
 }
 
 impl StructureRegistry {
     pub fn new() -> Self {
+        Self::with_bucket_size(DEFAULT_STRUCTURE_BUCKET_SIZE)
+    }
+
+    pub fn with_bucket_size(bucket_size: f32) -> Self {
         Self {
             instances: HashMap::new(),
+            bucket_size,
+            buckets: HashMap::new(),
+            tagged: HashMap::new(),
         }
     }
 
-    pub fn insert(&mut self, structure: StructureInstance) {
+    fn key(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            (x / self.bucket_size).floor() as i32,
+            (y / self.bucket_size).floor() as i32,
+        )
+    }
+
+    /// Buckets overlapped by a structure's bounding square.
+    fn span(&self, s: &StructureInstance) -> ((i32, i32), (i32, i32)) {
+        let r = s.bounds_radius;
+        (
+            self.key(s.position.x - r, s.position.y - r),
+            self.key(s.position.x + r, s.position.y + r),
+        )
+    }
+
+    /// Add `structure`, replacing any with the same id.  Structures that
+    /// fail [`StructureInstance::validate`] are refused.
+    pub fn insert(&mut self, structure: StructureInstance) -> Result<(), StructureMetaError> {
+        structure.validate()?;
+        self.insert_checked(structure);
+        Ok(())
+    }
+
+    fn insert_checked(&mut self, structure: StructureInstance) {
+        self.remove(&structure.id);
+        let ((x0, y0), (x1, y1)) = self.span(&structure);
+        for by in y0..=y1 {
+            for bx in x0..=x1 {
+                self.buckets
+                    .entry((bx, by))
+                    .or_default()
+                    .push(structure.id.clone());
+            }
+        }
+        for tag in &structure.tags {
+            self.tagged
+                .entry(tag.clone())
+                .or_default()
+                .insert(structure.id.clone());
+        }
         self.instances.insert(structure.id.clone(), structure);
     }
 
     pub fn remove(&mut self, id: &str) -> Option<StructureInstance> {
-        self.instances.remove(id)
+        let structure = self.instances.remove(id)?;
+        let ((x0, y0), (x1, y1)) = self.span(&structure);
+        for by in y0..=y1 {
+            for bx in x0..=x1 {
+                if let Some(bucket) = self.buckets.get_mut(&(bx, by)) {
+                    bucket.retain(|other| other != id);
+                    if bucket.is_empty() {
+                        self.buckets.remove(&(bx, by));
+                    }
+                }
+            }
+        }
+        for tag in &structure.tags {
+            if let Some(ids) = self.tagged.get_mut(tag) {
+                ids.remove(id);
+                if ids.is_empty() {
+                    self.tagged.remove(tag);
+                }
+            }
+        }
+        Some(structure)
     }
 
     pub fn get(&self, id: &str) -> Option<&StructureInstance> {
         self.instances.get(id)
     }
 
+    /// Move an interactive structure to `state` (which its states must
+    /// define).  Returns the previous state.
+    pub fn set_state(&mut self, id: &str, state: &str) -> Result<String, StructureMetaError> {
+        let unknown = || StructureMetaError::UnknownState {
+            id: id.to_string(),
+            state: state.to_string(),
+        };
+        let states = self
+            .instances
+            .get_mut(id)
+            .and_then(|s| s.states.as_mut())
+            .filter(|s| s.states.contains_key(state))
+            .ok_or_else(unknown)?;
+        Ok(std::mem::replace(&mut states.state, state.to_string()))
+    }
+
     pub fn len(&self) -> usize {
         self.instances.len()
     }
@@ -76,6 +681,52 @@ impl StructureRegistry {
         self.instances.is_empty()
     }
 
+    /// Every structure (unordered).
+    pub fn iter(&self) -> impl Iterator<Item = &StructureInstance> {
+        self.instances.values()
+    }
+
+    /// Insert every structure of a structure file, replacing registered
+    /// ones with the same id.  Nothing is inserted if the file is invalid.
+    /// Returns the number of structures read.
+    pub fn load_from_file(&mut self, path: impl AsRef<Path>) -> Result<usize, StructureFileError> {
+        let file: StructureFile = serde_json::from_slice(&fs::read(path)?)?;
+        if file.version != STRUCTURE_FILE_VERSION {
+            return Err(StructureFileError::Version(file.version));
+        }
+        let mut seen = HashSet::new();
+        if let Some(dup) = file.structures.iter().find(|r| !seen.insert(&r.id)) {
+            return Err(StructureFileError::Duplicate(dup.id.clone()));
+        }
+        let structures: Vec<StructureInstance> =
+            file.structures.into_iter().map(Into::into).collect();
+        for s in &structures {
+            s.validate()?;
+        }
+        let count = structures.len();
+        for s in structures {
+            self.insert_checked(s);
+        }
+        Ok(count)
+    }
+
+    /// Write every structure to `path`, sorted by id.  The file is replaced
+    /// atomically, so a crash mid-save keeps the previous layout.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), StructureFileError> {
+        let mut structures: Vec<StructureRecord> =
+            self.instances.values().map(StructureRecord::from).collect();
+        structures.sort_by(|a, b| a.id.cmp(&b.id));
+        let file = StructureFile {
+            version: STRUCTURE_FILE_VERSION,
+            structures,
+        };
+        let path = path.as_ref();
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(&file)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
     /// Return all structures whose bounding circle overlaps the given world
     /// rectangle (used during chunk activation for selective streaming).
     pub fn query_rect(
@@ -85,8 +736,64 @@ impl StructureRegistry {
         max_x: f32,
         max_y: f32,
     ) -> Vec<&StructureInstance> {
-        self.instances
-            .values()
+        let (x0, y0) = self.key(min_x, min_y);
+        let (x1, y1) = self.key(max_x, max_y);
+        let mut out = Vec::new();
+        let mut visit = |bucket: (i32, i32), ids: &Vec<String>| {
+            for s in ids.iter().filter_map(|id| self.instances.get(id)) {
+                let r = s.bounds_radius;
+                let overlaps = s.position.x + r >= min_x
+                    && s.position.x - r <= max_x
+                    && s.position.y + r >= min_y
+                    && s.position.y - r <= max_y;
+                // A structure in several buckets is reported only from the
+                // one holding the low corner of its overlap with the query.
+                if overlaps
+                    && self.key((s.position.x - r).max(min_x), (s.position.y - r).max(min_y))
+                        == bucket
+                {
+                    out.push(s);
+                }
+            }
+        };
+
+        let span = (x1 as i64 - x0 as i64 + 1).saturating_mul(y1 as i64 - y0 as i64 + 1);
+        if span > self.buckets.len() as i64 {
+            // A query wider than the populated area walks the buckets instead.
+            for (&(bx, by), ids) in &self.buckets {
+                if (x0..=x1).contains(&bx) && (y0..=y1).contains(&by) {
+                    visit((bx, by), ids);
+                }
+            }
+        } else {
+            for by in y0..=y1 {
+                for bx in x0..=x1 {
+                    if let Some(ids) = self.buckets.get(&(bx, by)) {
+                        visit((bx, by), ids);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Structures tagged `tag` whose bounding circle overlaps the given
+    /// world rectangle, sorted by id.  Pass infinite bounds to search the
+    /// whole world.
+    pub fn query_by_tag(
+        &self,
+        tag: &str,
+        min_x: f32,
+        min_y: f32,
+        max_x: f32,
+        max_y: f32,
+    ) -> Vec<&StructureInstance> {
+        let Some(ids) = self.tagged.get(tag) else {
+            return Vec::new();
+        };
+        let mut out: Vec<&StructureInstance> = ids
+            .iter()
+            .filter_map(|id| self.instances.get(id))
             .filter(|s| {
                 let r = s.bounds_radius;
                 s.position.x + r >= min_x
@@ -94,7 +801,20 @@ impl StructureRegistry {
                     && s.position.y + r >= min_y
                     && s.position.y - r <= max_y
             })
-            .collect()
+            .collect();
+        out.sort_by(|a, b| a.id.cmp(&b.id));
+        out
+    }
+
+    /// Return all structures whose bounding circle overlaps the circle of
+    /// `radius` around `(x, y)`.
+    pub fn query_radius(&self, x: f32, y: f32, radius: f32) -> Vec<&StructureInstance> {
+        let mut out = self.query_rect(x - radius, y - radius, x + radius, y + radius);
+        out.retain(|s| {
+            let reach = radius + s.bounds_radius;
+            (s.position.x - x).powi(2) + (s.position.y - y).powi(2) <= reach * reach
+        });
+        out
     }
 }
 
@@ -108,7 +828,9 @@ impl Default for StructureRegistry {
 // World (data container)
 // ---------------------------------------------------------------------------
 
-/// The immutable world data layer.  `WorldService` streams it into physics.
+/// The world data layer.  `WorldService` streams it into physics and owns
+/// it; structures players place are inserted copy-on-write.
+#[derive(Clone)]
 pub struct World {
     pub terrain: Arc<dyn TerrainSource>,
     pub structures: StructureRegistry,
@@ -121,4 +843,13 @@ impl World {
             structures: StructureRegistry::new(),
         }
     }
+
+    /// A world whose structures are bucketed by `cell_size` (the streaming
+    /// cell size), so per-cell queries touch few buckets.
+    pub fn with_cell_size(terrain: Arc<dyn TerrainSource>, cell_size: f32) -> Self {
+        Self {
+            terrain,
+            structures: StructureRegistry::with_bucket_size(cell_size),
+        }
+    }
 }
@@ -1,17 +1,36 @@
 //! Terrain subsystem: TerrainSource trait, HeightmapTerrain implementation,
 //! chunk cache, LOD generation, and heightfield collider construction.
 
-use crate::types::Vec3;
+use crate::chunk_store::ChunkStore;
+use crate::erosion::ErosionConfig;
+use crate::height_overrides::{ChunkOverrides, HeightOverrides};
+use crate::hydrology;
+use crate::protocol::{ChunkHoles, VOXEL_AIR, VOXEL_INHERIT};
+use crate::types::{
+    ChunkCacheStats, HydrologyConfig, MaterialRules, MaterialWeights, NoiseParams, RayHit,
+    RegionStats, SeedRegion, Vec3,
+};
+use crate::voxel::VoxelLayer;
+use crate::worldgen::{
+    apply_noise_params, default_biomes, default_noise_layers, BiomeBand, NoiseLayer, WorldGenConfig,
+};
 use janet_operations::physics::types::ColliderShape;
+use log::warn;
 use md5;
 use parking_lot::RwLock;
 use std::any::Any;
-use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 const CANONICAL_TILE_SIZE: i32 = 16;
 
+/// Default [`HeightmapTerrain::slow_generation_ms`].
+pub const DEFAULT_SLOW_GENERATION_MS: f32 = 50.0;
+
 #[derive(Debug, Clone)]
 pub struct CanonicalTileSample {
     pub terrain: String,
@@ -40,19 +59,81 @@ fn classify_terrain(elevation: f64) -> &'static str {
     }
 }
 
-fn hash_float(ix: i32, iy: i32, salt: u64) -> f64 {
+// ---------------------------------------------------------------------------
+// Value noise
+// ---------------------------------------------------------------------------
+//
+// Clients rebuild terrain from the seed, so the server, the Godot client
+// and the WASM client must produce bit-identical heights.  Only the lattice
+// position is floating point (one IEEE multiply, exact on every platform);
+// the rest is 64-bit integer arithmetic with `>>` as an arithmetic shift:
+//
+// 1. `s = floor((w * frequency) * 2^20)`: lattice index `s >> 20` and
+//    fraction `f = s & (2^20 - 1)` for each axis.
+// 2. Fade `f' = (((f * f) >> 20) * (3 * 2^20 - 2 * f)) >> 20`.
+// 3. Corner values: the last two bytes of `md5("{ix}:{iy}:{salt}")`,
+//    big-endian (`0..=65535`).
+// 4. `x0 = h00 * 2^20 + (h10 - h00) * fx'` and `x1` likewise from `h01`,
+//    `h11`; the noise is `x0 + (((x1 - x0) * fy') >> 20)`, in units of
+//    [`NOISE_ONE`].
+// 5. Layers sum `round(weight * 2^16) * noise` and divide once, in `f64`,
+//    by `NOISE_ONE * 2^16` before clamping to `0..=1`.
+//
+// `tests/noise_golden_vectors.json` pins the results for client ports.
+
+/// Fractional bits of lattice positions and noise values.
+const NOISE_FRAC_BITS: u32 = 20;
+const NOISE_FRAC_ONE: i64 = 1 << NOISE_FRAC_BITS;
+/// Fractional bits of layer weights.
+const NOISE_WEIGHT_BITS: u32 = 16;
+/// Noise value of a lattice corner hashing to `65535`.
+pub const NOISE_ONE: i64 = 65535 << NOISE_FRAC_BITS;
+
+fn hash_corner(ix: i32, iy: i32, salt: u64) -> i64 {
     let key = format!("{}:{}:{}", ix, iy, salt);
     let digest = md5::compute(key.as_bytes());
-    let low = ((digest.0[14] as u16) << 8) | digest.0[15] as u16;
-    low as f64 / 65535.0
+    (((digest.0[14] as u16) << 8) | digest.0[15] as u16) as i64
 }
 
-fn lerp(a: f64, b: f64, t: f64) -> f64 {
-    a + (b - a) * t
+/// Lattice index and Q20 fraction of world coordinate `w`.
+fn lattice(w: f64, frequency: f64) -> (i32, i64) {
+    let s = ((w * frequency) * NOISE_FRAC_ONE as f64).floor() as i64;
+    ((s >> NOISE_FRAC_BITS) as i32, s & (NOISE_FRAC_ONE - 1))
 }
 
-fn smooth_step(t: f64) -> f64 {
-    t * t * (3.0 - 2.0 * t)
+/// Smoothstep of a Q20 fraction.
+fn fade(f: i64) -> i64 {
+    (((f * f) >> NOISE_FRAC_BITS) * (3 * NOISE_FRAC_ONE - 2 * f)) >> NOISE_FRAC_BITS
+}
+
+/// Value noise at a world point, `0..=NOISE_ONE`.
+pub fn value_noise(wx: f64, wy: f64, frequency: f64, salt: u64) -> i64 {
+    let (ix, fx) = lattice(wx, frequency);
+    let (iy, fy) = lattice(wy, frequency);
+    let (fx, fy) = (fade(fx), fade(fy));
+    let h00 = hash_corner(ix, iy, salt);
+    let h10 = hash_corner(ix + 1, iy, salt);
+    let h01 = hash_corner(ix, iy + 1, salt);
+    let h11 = hash_corner(ix + 1, iy + 1, salt);
+    let x0 = h00 * NOISE_FRAC_ONE + (h10 - h00) * fx;
+    let x1 = h01 * NOISE_FRAC_ONE + (h11 - h01) * fx;
+    x0 + (((x1 - x0) * fy) >> NOISE_FRAC_BITS)
+}
+
+/// [`value_noise`] scaled to `0..=1`.
+pub(crate) fn unit_noise(wx: f64, wy: f64, frequency: f64, salt: u64) -> f64 {
+    value_noise(wx, wy, frequency, salt) as f64 / NOISE_ONE as f64
+}
+
+// Canonical tiles keep the floating-point value noise of the Python world
+// generator they are pinned to (`tests/terrain_alignment_vectors.json`).
+
+fn hash_float(ix: i32, iy: i32, salt: u64) -> f64 {
+    hash_corner(ix, iy, salt) as f64 / 65535.0
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
 }
 
 fn smooth_noise(wx: f64, wy: f64, scale: f64, salt: u64) -> f64 {
@@ -69,6 +150,10 @@ fn smooth_noise(wx: f64, wy: f64, scale: f64, salt: u64) -> f64 {
     lerp(lerp(v00, v10, fx), lerp(v01, v11, fx), fy)
 }
 
+fn smooth_step(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
 fn clamp01(v: f64) -> f64 {
     v.clamp(0.0, 1.0)
 }
@@ -92,6 +177,21 @@ fn hazard(wx: f64, wy: f64, seed: u64) -> f64 {
     smooth_noise(wx, wy, 0.15, seed ^ 0x6666)
 }
 
+/// Weighted sum of [`value_noise`] layers, clamped to `0..=1`.  The
+/// default layers match the canonical tile elevation to within the
+/// fixed-point rounding.
+pub fn layered_noise(layers: &[NoiseLayer], seed: u64, x: f32, y: f32) -> f32 {
+    let (wx, wy) = (x as f64, y as f64);
+    let sum: i64 = layers
+        .iter()
+        .map(|l| {
+            let weight = (l.weight * (1 << NOISE_WEIGHT_BITS) as f64).round() as i64;
+            weight * value_noise(wx, wy, l.frequency, seed ^ l.salt)
+        })
+        .sum();
+    clamp01(sum as f64 / (NOISE_ONE << NOISE_WEIGHT_BITS) as f64) as f32
+}
+
 fn round4(v: f64) -> f64 {
     (v * 10_000.0).round() / 10_000.0
 }
@@ -123,15 +223,329 @@ pub fn sample_canonical_tile(seed: u64, cx: i32, cy: i32, lx: i32, ly: i32) -> C
 // Trait
 // ---------------------------------------------------------------------------
 
+/// March increment for [`TerrainSource::raycast`] (world units).
+pub const RAYCAST_STEP: f32 = 0.5;
+
+/// Bisection passes once a raycast step crosses the ground (step / 2^n).
+const RAYCAST_REFINE_STEPS: usize = 12;
+
+/// FNV-1a 64 over the little-endian IEEE-754 bytes of `heights` in
+/// row-major order — simple enough to port to any client that generates
+/// terrain itself.
+pub fn heights_checksum(heights: &[f32]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    heights
+        .iter()
+        .flat_map(|h| h.to_le_bytes())
+        .fold(OFFSET, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(PRIME)
+        })
+}
+
+/// Boxes covering a `size`-wide chunk footprint except its `holes` cells,
+/// as `(shape, centre)` with the centre relative to the chunk corner.
+///
+/// Each row's runs of solid cells merge with identical runs in the rows
+/// after it, so a single hole splits the footprint into at most four boxes.
+pub fn solid_footprint(size: f32, holes: &ChunkHoles) -> Vec<(ColliderShape, (f32, f32))> {
+    let cell = holes.cell_size;
+    let n = (size / cell).round().max(1.0) as u32;
+    let hole: BTreeSet<(u32, u32)> = holes.cells.iter().map(|&[i, j]| (i, j)).collect();
+
+    let mut boxes = Vec::new();
+    // Runs `[i0, i1)` still growing downwards, with the row they started in.
+    let mut open: BTreeMap<(u32, u32), u32> = BTreeMap::new();
+    for j in 0..=n {
+        let mut runs = Vec::new();
+        let mut i = 0;
+        while j < n && i < n {
+            let i0 = i;
+            while i < n && !hole.contains(&(i, j)) {
+                i += 1;
+            }
+            if i > i0 {
+                runs.push((i0, i));
+            }
+            i += 1;
+        }
+        open.retain(|&(i0, i1), &mut j0| {
+            if runs.contains(&(i0, i1)) {
+                return true;
+            }
+            boxes.push((
+                ColliderShape::Box {
+                    width: (i1 - i0) as f32 * cell,
+                    height: (j - j0) as f32 * cell,
+                },
+                (0.5 * (i0 + i1) as f32 * cell, 0.5 * (j0 + j) as f32 * cell),
+            ));
+            false
+        });
+        for run in runs {
+            open.entry(run).or_insert(j);
+        }
+    }
+    boxes
+}
+
+/// Collider of a chunk `size` wide, for backends without a physics
+/// heightfield.
+pub fn footprint_collider(size: f32) -> ColliderShape {
+    // TODO(Phase 1): Replace with ColliderShape::Heightfield once the physics
+    // engine exposes a 3D heightfield variant.  For now use a flat Box that
+    // covers the chunk footprint; the service splits it around the
+    // `chunk_holes` cells (see `solid_footprint`).
+    ColliderShape::Box {
+        width: size,
+        height: size,
+    }
+}
+
+/// Grid points per side sampled by [`TerrainSource::sample_region_stats`].
+pub const REGION_STATS_SAMPLES: usize = 16;
+
+/// Streaming parameters of one terrain chunk, advertised in
+/// `ChunkActivated`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkDescriptor {
+    /// Effective generation seed; `0` for backends that can't be
+    /// regenerated from a seed (e.g. image terrain).
+    pub seed: u64,
+    pub chunk_size: f32,
+    pub hydrology: Option<HydrologyConfig>,
+    pub materials: MaterialRules,
+}
+
 /// Anything that can provide a terrain height and surface normal.
 ///
-/// The `as_any` method enables downcasting from `Arc<dyn TerrainSource>` to a
-/// concrete type (e.g. [`HeightmapTerrain`]) when heightfield colliders are
-/// needed.
+/// Chunked backends also override the streaming hooks
+/// ([`chunk_descriptor`](Self::chunk_descriptor),
+/// [`collider_for_chunk`](Self::collider_for_chunk), …) so the service can
+/// stream them without knowing the concrete type; the defaults describe an
+/// unchunked, immutable surface.
 pub trait TerrainSource: Send + Sync {
     fn height_at(&self, x: f32, y: f32) -> f32;
     fn normal_at(&self, x: f32, y: f32) -> Vec3;
 
+    /// Height of the water surface at `(x, y)`, or `None` if the ground
+    /// there is dry.  Sources without hydrology never report water.
+    fn water_level_at(&self, _x: f32, _y: f32) -> Option<f32> {
+        None
+    }
+
+    /// First point where the ray from `origin` along `direction` meets the
+    /// ground within `max_dist`, or `None` if it stays above the terrain.
+    ///
+    /// Marches in [`RAYCAST_STEP`] increments, then bisects the last step;
+    /// features thinner than a step can be skipped.  A ray starting inside
+    /// solid ground hits at distance `0`.  Solidity comes from
+    /// [`solid_at`](Self::solid_at); the normal is always the heightfield's.
+    fn raycast(&self, origin: Vec3, direction: Vec3, max_dist: f32) -> Option<RayHit> {
+        let len =
+            (direction.x * direction.x + direction.y * direction.y + direction.z * direction.z)
+                .sqrt();
+        if !(len > 0.0 && max_dist.is_finite()) {
+            return None;
+        }
+        let dir = Vec3::new(direction.x / len, direction.y / len, direction.z / len);
+        let point = |t: f32| {
+            Vec3::new(
+                origin.x + dir.x * t,
+                origin.y + dir.y * t,
+                origin.z + dir.z * t,
+            )
+        };
+        let solid = |p: Vec3| self.solid_at(p.x, p.y, p.z);
+
+        let hit = |t: f32| {
+            let position = point(t);
+            let n = self.normal_at(position.x, position.y);
+            let n_len = (n.x * n.x + n.y * n.y + n.z * n.z).sqrt().max(f32::EPSILON);
+            RayHit {
+                position,
+                normal: Vec3::new(n.x / n_len, n.y / n_len, n.z / n_len),
+                distance: t,
+            }
+        };
+
+        if solid(origin) {
+            return Some(hit(0.0));
+        }
+        let mut prev = 0.0;
+        while prev < max_dist {
+            let t = (prev + RAYCAST_STEP).min(max_dist);
+            if solid(point(t)) {
+                let (mut lo, mut hi) = (prev, t);
+                for _ in 0..RAYCAST_REFINE_STEPS {
+                    let mid = 0.5 * (lo + hi);
+                    if solid(point(mid)) {
+                        hi = mid;
+                    } else {
+                        lo = mid;
+                    }
+                }
+                return Some(hit(hi));
+            }
+            prev = t;
+        }
+        None
+    }
+
+    /// `true` if nothing but air lies between `from` and `to`.
+    fn line_of_sight(&self, from: Vec3, to: Vec3) -> bool {
+        let d = Vec3::new(to.x - from.x, to.y - from.y, to.z - from.z);
+        let dist = (d.x * d.x + d.y * d.y + d.z * d.z).sqrt();
+        dist == 0.0 || self.raycast(from, d, dist).is_none()
+    }
+
+    /// Gradient magnitude (rise over run) of the surface at `(x, y)`.
+    fn slope_at(&self, x: f32, y: f32) -> f32 {
+        let n = self.normal_at(x, y);
+        (n.x * n.x + n.y * n.y).sqrt() / n.z.abs().max(f32::EPSILON)
+    }
+
+    /// `true` if a structure may stand at `(x, y)`: solid ground (no hole),
+    /// not under water, and no steeper than `max_slope` (rise over run).
+    fn is_buildable(&self, x: f32, y: f32, max_slope: f32) -> bool {
+        !self.is_hole(x, y)
+            && self.water_level_at(x, y).is_none()
+            && self.slope_at(x, y) <= max_slope
+    }
+
+    /// Height, slope, water and hole statistics over the rectangle
+    /// `min..=max`, from a [`REGION_STATS_SAMPLES`]² grid spanning it
+    /// (corners included).
+    fn sample_region_stats(&self, min: [f32; 2], max: [f32; 2]) -> RegionStats {
+        let n = REGION_STATS_SAMPLES;
+        let at = |lo: f32, hi: f32, i: usize| lo + (hi - lo) * i as f32 / (n - 1) as f32;
+        let mut stats = RegionStats {
+            min_height: f32::INFINITY,
+            max_height: f32::NEG_INFINITY,
+            ..Default::default()
+        };
+        let (mut water, mut holes) = (0usize, 0usize);
+        for j in 0..n {
+            for i in 0..n {
+                let (x, y) = (at(min[0], max[0], i), at(min[1], max[1], j));
+                let h = self.height_at(x, y);
+                let slope = self.slope_at(x, y);
+                stats.min_height = stats.min_height.min(h);
+                stats.max_height = stats.max_height.max(h);
+                stats.mean_height += h;
+                stats.max_slope = stats.max_slope.max(slope);
+                stats.mean_slope += slope;
+                water += self.water_level_at(x, y).is_some() as usize;
+                holes += self.is_hole(x, y) as usize;
+            }
+        }
+        let samples = n * n;
+        stats.samples = samples;
+        stats.mean_height /= samples as f32;
+        stats.mean_slope /= samples as f32;
+        stats.water_fraction = water as f32 / samples as f32;
+        stats.hole_fraction = holes as f32 / samples as f32;
+        stats
+    }
+
+    /// [`heights_checksum`] of chunk `(cx, cy)` at `lod`, or `None` if
+    /// clients don't generate this backend's chunks themselves.
+    fn chunk_checksum(&self, _cx: i32, _cy: i32, _lod: u8) -> Option<u64> {
+        None
+    }
+
+    /// `true` if the point is inside solid ground.  Plain heightfields are
+    /// solid everywhere at or below the surface, except under holes.
+    fn solid_at(&self, x: f32, y: f32, z: f32) -> bool {
+        z <= self.height_at(x, y) && !self.is_hole(x, y)
+    }
+
+    /// `true` if `(x, y)` lies in a cell cut out of the heightfield.
+    fn is_hole(&self, _x: f32, _y: f32) -> bool {
+        false
+    }
+
+    /// Cut every heightfield cell whose centre is within `radius` of
+    /// `(center_x, center_y)`.  Returns the chunks that gained a hole, or
+    /// `None` if the backend doesn't support holes.
+    fn cut_holes(&self, _center_x: f32, _center_y: f32, _radius: f32) -> Option<Vec<(i32, i32)>> {
+        None
+    }
+
+    /// Hole cells of chunk `(cx, cy)`, if it has any.
+    fn chunk_holes(&self, _cx: i32, _cy: i32) -> Option<ChunkHoles> {
+        None
+    }
+
+    /// `true` if clients rebuild chunk `(cx, cy)` from its `ChunkActivated`
+    /// seed (plus replayed terrain edits) alone.  Otherwise the service can
+    /// send the heights (`send_chunk_heights`).
+    fn client_regenerable(&self, _cx: i32, _cy: i32) -> bool {
+        false
+    }
+
+    /// Layout of chunk `(cx, cy)` at `lod`, or `None` if the backend is not
+    /// chunked (the service then uses its cell size and seed `0`).
+    fn chunk_descriptor(&self, _cx: i32, _cy: i32, _lod: u8) -> Option<ChunkDescriptor> {
+        None
+    }
+
+    /// LOD to stream a chunk at when the nearest participant is `distance`
+    /// world units away.  Single-resolution backends always use `0`.
+    fn lod_for_distance(&self, _distance: f32) -> u8 {
+        0
+    }
+
+    /// Static collider for chunk `(cx, cy)`; `None` registers no terrain
+    /// body for the cell.
+    fn collider_for_chunk(&self, _cx: i32, _cy: i32, _lod: u8) -> Option<ColliderShape> {
+        None
+    }
+
+    /// Generate (or load) and cache chunk `(cx, cy)` so a later lookup is
+    /// cheap.  Called from the chunk worker pool.
+    fn warm_chunk(&self, _cx: i32, _cy: i32, _lod: u8) {}
+
+    /// `true` if chunk `(cx, cy)` can be served without generating it.
+    fn is_chunk_cached(&self, _cx: i32, _cy: i32, _lod: u8) -> bool {
+        true
+    }
+
+    /// Raise or dig the surface around `(center_x, center_y)`.  Returns the
+    /// chunks whose heights changed, or `None` if the backend can't be
+    /// deformed.
+    fn deform_region(
+        &self,
+        _center_x: f32,
+        _center_y: f32,
+        _radius: f32,
+        _delta: f32,
+    ) -> Option<Vec<(i32, i32)>> {
+        None
+    }
+
+    /// Pin LOD 0 samples to designer heights (`[x, y, height]`).  Returns
+    /// the chunks whose heights changed, or `None` if the backend has no
+    /// override layer.
+    fn set_heights(&self, _samples: &[[f32; 3]]) -> Option<io::Result<Vec<(i32, i32)>>> {
+        None
+    }
+
+    /// Chunk cache counters (zero for backends without a cache).
+    fn chunk_cache_stats(&self) -> ChunkCacheStats {
+        ChunkCacheStats::default()
+    }
+
+    /// Biome name at a world point, for backends that classify terrain.
+    fn biome(&self, _x: f32, _y: f32) -> Option<&str> {
+        None
+    }
+
+    /// Sparse voxel overrides on top of the heightfield, if any.
+    fn voxel_layer(&self) -> Option<&VoxelLayer> {
+        None
+    }
+
     /// Downcast support (implement by returning `self`).
     fn as_any(&self) -> &dyn Any;
 }
@@ -146,31 +560,443 @@ pub struct HeightChunk {
     pub world_origin_x: f32,
     pub world_origin_y: f32,
     pub cell_size: f32,
+    /// Splat weights per sample, same layout as `heights`.  Empty until
+    /// [`HeightChunk::compute_materials`] runs (never persisted).
+    pub materials: Vec<MaterialWeights>,
+}
+
+impl HeightChunk {
+    /// `true` if this chunk has the grid layout [`HeightChunk::sample`] would
+    /// produce for the given parameters (used to reject stale stored chunks).
+    pub fn has_layout(
+        &self,
+        cx: i32,
+        cy: i32,
+        lod: u8,
+        chunk_size: f32,
+        base_resolution: usize,
+    ) -> bool {
+        let resolution = (base_resolution >> lod).max(4);
+        self.resolution == resolution
+            && self.heights.len() == resolution * resolution
+            && self.cell_size == chunk_size / resolution as f32
+            && self.world_origin_x == cx as f32 * chunk_size
+            && self.world_origin_y == cy as f32 * chunk_size
+    }
+
+    /// Sample a `resolution × resolution` grid for chunk `(cx, cy)` at `lod`.
+    ///
+    /// Resolution is `base_resolution` halved per LOD level (minimum 4).
+    /// Shared by every chunked terrain backend so they agree on grid layout.
+    pub fn sample(
+        cx: i32,
+        cy: i32,
+        lod: u8,
+        chunk_size: f32,
+        base_resolution: usize,
+        height: impl Fn(f32, f32) -> f32,
+    ) -> Self {
+        let resolution = (base_resolution >> lod).max(4);
+        let cell_size = chunk_size / resolution as f32;
+        let world_origin_x = cx as f32 * chunk_size;
+        let world_origin_y = cy as f32 * chunk_size;
+
+        let mut heights = Vec::with_capacity(resolution * resolution);
+        for row in 0..resolution {
+            for col in 0..resolution {
+                let wx = world_origin_x + col as f32 * cell_size;
+                let wy = world_origin_y + row as f32 * cell_size;
+                heights.push(height(wx, wy));
+            }
+        }
+
+        Self {
+            heights,
+            resolution,
+            world_origin_x,
+            world_origin_y,
+            cell_size,
+            materials: Vec::new(),
+        }
+    }
+
+    /// Estimated memory held by this chunk (used for cache budgets).
+    pub fn memory_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.heights.capacity() * std::mem::size_of::<f32>()
+            + self.materials.capacity() * std::mem::size_of::<MaterialWeights>()
+    }
+
+    /// Derive `materials` from the current heights.
+    ///
+    /// Slope is the gradient magnitude from central differences inside the
+    /// chunk (one-sided on the border samples), in height per world unit.
+    pub fn compute_materials(&mut self, rules: &MaterialRules) {
+        let res = self.resolution;
+        let h = |col: usize, row: usize| self.heights[row * res + col];
+        let diff = |i: usize| (i.saturating_sub(1), (i + 1).min(res - 1));
+
+        let mut materials = Vec::with_capacity(self.heights.len());
+        for row in 0..res {
+            for col in 0..res {
+                let (l, r) = diff(col);
+                let (d, u) = diff(row);
+                let gx = (h(r, row) - h(l, row)) / ((r - l) as f32 * self.cell_size);
+                let gy = (h(col, u) - h(col, d)) / ((u - d) as f32 * self.cell_size);
+                materials.push(rules.weights(h(col, row), gx.hypot(gy)));
+            }
+        }
+        self.materials = materials;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Interpolation
+// ---------------------------------------------------------------------------
+
+/// How height queries blend between grid samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Snap to the sample at or below the query point (legacy behaviour).
+    Nearest,
+    /// Blend the four surrounding samples.
+    #[default]
+    Bilinear,
+    /// Catmull-Rom over the surrounding 4×4 samples; smooth first derivative.
+    Bicubic,
+}
+
+fn lerp32(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Catmull-Rom spline through `p1`..`p2` with outer control points.
+fn catmull_rom(p0: f32, p1: f32, p2: f32, p3: f32, t: f32) -> f32 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * ((2.0 * p1)
+        + (-p0 + p2) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (-p0 + 3.0 * p1 - 3.0 * p2 + p3) * t3)
+}
+
+/// Derivative of [`catmull_rom`] with respect to `t`.
+fn catmull_rom_slope(p0: f32, p1: f32, p2: f32, p3: f32, t: f32) -> f32 {
+    0.5 * ((-p0 + p2)
+        + 2.0 * (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t
+        + 3.0 * (-p0 + 3.0 * p1 - 3.0 * p2 + p3) * t * t)
+}
+
+// ---------------------------------------------------------------------------
+// Chunk cache
+// ---------------------------------------------------------------------------
+
+/// Upper bound on the chunk cache; least recently used chunks are evicted
+/// once it is exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheBudget {
+    /// Maximum number of cached chunks (all LODs together).
+    Chunks(usize),
+    /// Maximum estimated bytes ([`HeightChunk::memory_bytes`]).
+    Bytes(usize),
+}
+
+struct CacheEntry {
+    chunk: Arc<HeightChunk>,
+    /// Value of the cache clock at the last lookup.
+    last_used: AtomicU64,
+}
+
+/// Chunks by `(cx, cy, lod)` with their LRU timestamps; shared by the
+/// chunked backends that build [`HeightChunk`]s.
+#[derive(Default)]
+pub(crate) struct ChunkCache {
+    entries: HashMap<(i32, i32, u8), CacheEntry>,
+    bytes: usize,
+}
+
+impl ChunkCache {
+    /// Cached chunk at `key`, marked as used at `now`.
+    pub(crate) fn get(&self, key: &(i32, i32, u8), now: u64) -> Option<Arc<HeightChunk>> {
+        let entry = self.entries.get(key)?;
+        entry.last_used.store(now, Ordering::Relaxed);
+        Some(entry.chunk.clone())
+    }
+
+    pub(crate) fn contains(&self, key: &(i32, i32, u8)) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub(crate) fn insert(&mut self, key: (i32, i32, u8), chunk: Arc<HeightChunk>, now: u64) {
+        self.bytes += chunk.memory_bytes();
+        let old = self.entries.insert(
+            key,
+            CacheEntry {
+                chunk,
+                last_used: AtomicU64::new(now),
+            },
+        );
+        if let Some(old) = old {
+            self.bytes -= old.chunk.memory_bytes();
+        }
+    }
+
+    /// Drop every entry for which `keep` is false; returns how many went.
+    fn retain(&mut self, mut keep: impl FnMut(&(i32, i32, u8)) -> bool) -> usize {
+        let before = self.entries.len();
+        let mut freed = 0;
+        self.entries.retain(|key, entry| {
+            let kept = keep(key);
+            if !kept {
+                freed += entry.chunk.memory_bytes();
+            }
+            kept
+        });
+        self.bytes -= freed;
+        before - self.entries.len()
+    }
+
+    fn over(&self, budget: CacheBudget) -> bool {
+        match budget {
+            CacheBudget::Chunks(max) => self.entries.len() > max,
+            CacheBudget::Bytes(max) => self.bytes > max,
+        }
+    }
+
+    /// Evict least recently used entries until within `budget`, always
+    /// keeping the most recent one.  Returns the number evicted.
+    pub(crate) fn enforce(&mut self, budget: CacheBudget) -> usize {
+        if !self.over(budget) {
+            return 0;
+        }
+        let mut by_age: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(key, e)| (e.last_used.load(Ordering::Relaxed), *key))
+            .collect();
+        by_age.sort_unstable();
+
+        let mut evicted = 0;
+        for (_, key) in &by_age[..by_age.len() - 1] {
+            if !self.over(budget) {
+                break;
+            }
+            if let Some(e) = self.entries.remove(key) {
+                self.bytes -= e.chunk.memory_bytes();
+                evicted += 1;
+            }
+        }
+        evicted
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Heightmap terrain
 // ---------------------------------------------------------------------------
 
+/// Local LOD 0 cell indices cut out of each chunk.
+type HoleCells = HashMap<(i32, i32), BTreeSet<(u32, u32)>>;
+
 pub struct HeightmapTerrain {
     pub seed: u64,
+    /// Areas generated from their own seed instead of `seed`.
+    pub seed_regions: Vec<SeedRegion>,
     /// World-space width/height of a single terrain chunk.
     pub chunk_size: f32,
     /// Sample resolution at LOD 0 (halved per LOD level).
     pub base_resolution: usize,
-    cache: RwLock<HashMap<(i32, i32, u8), Arc<HeightChunk>>>,
+    /// How `height_at` blends between grid samples.
+    pub interpolation: Interpolation,
+    /// Octaves summed into the generated height.
+    pub noise: Vec<NoiseLayer>,
+    /// World units per unit of generated height (see
+    /// [`with_noise_params`](Self::with_noise_params)).
+    pub height_scale: f32,
+    /// Biome bands by generated height (see [`biome_at`](Self::biome_at)).
+    pub biomes: Vec<BiomeBand>,
+    /// Distances (world units) at which the next LOD level starts; must be
+    /// strictly increasing.  `[100, 300]` → LOD 0 below 100, LOD 2 from 300.
+    pub lod_bands: Vec<f32>,
+    /// Pin every chunk's shared borders to the coarsest LOD so neighbours
+    /// at different LODs mesh without cracks (see `stitch_borders`).
+    pub stitch_borders: bool,
+    /// Optional erosion post-process applied to every generated chunk.
+    pub erosion: Option<ErosionConfig>,
+    /// Optional river carving and lake water table.
+    pub hydrology: Option<HydrologyConfig>,
+    /// Splat rules applied to every cached chunk.
+    pub materials: MaterialRules,
+    /// Cave / overhang overrides on top of the heightmap.
+    pub voxels: Option<VoxelLayer>,
+    /// LRU limit for `cache` (unbounded when `None`).
+    pub cache_budget: Option<CacheBudget>,
+    cache: RwLock<ChunkCache>,
+    /// Monotonic lookup counter used as the LRU timestamp.
+    cache_clock: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    cache_evictions: AtomicU64,
+    /// Builds on a miss that completed, their total and longest time (µs).
+    generations: AtomicU64,
+    generation_us_total: AtomicU64,
+    generation_us_max: AtomicU64,
+    slow_generations: AtomicU64,
+    /// Builds slower than this are logged and counted (`0` disables).
+    pub slow_generation_ms: f32,
+    /// Optional write-back / read-through disk store (base terrain only).
+    store: Option<ChunkStore>,
+    /// Runtime edits: height offset per global LOD 0 grid index, applied on
+    /// top of generated chunks.
+    overrides: RwLock<HashMap<(i64, i64), f32>>,
+    /// Cells cut out of the heightfield.
+    holes: RwLock<HoleCells>,
+    /// Designer touch-ups, applied like `overrides` but persisted with the
+    /// world rather than replayed from edits.
+    height_overrides: RwLock<HeightOverrides>,
+    /// Bumped by every edit; a chunk generated before an edit is not cached.
+    edit_revision: AtomicU64,
 }
 
 impl HeightmapTerrain {
     pub fn new(seed: u64, chunk_size: f32, base_resolution: usize) -> Self {
         Self {
             seed,
+            seed_regions: Vec::new(),
             chunk_size,
             base_resolution,
-            cache: RwLock::new(HashMap::new()),
+            interpolation: Interpolation::default(),
+            noise: default_noise_layers(),
+            height_scale: 1.0,
+            biomes: default_biomes(),
+            lod_bands: vec![100.0, 300.0],
+            stitch_borders: true,
+            erosion: None,
+            hydrology: None,
+            voxels: None,
+            materials: MaterialRules::default(),
+            cache_budget: None,
+            cache: RwLock::new(ChunkCache::default()),
+            cache_clock: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+            cache_evictions: AtomicU64::new(0),
+            generations: AtomicU64::new(0),
+            generation_us_total: AtomicU64::new(0),
+            generation_us_max: AtomicU64::new(0),
+            slow_generations: AtomicU64::new(0),
+            slow_generation_ms: DEFAULT_SLOW_GENERATION_MS,
+            store: None,
+            overrides: RwLock::new(HashMap::new()),
+            holes: RwLock::new(HashMap::new()),
+            height_overrides: RwLock::new(HeightOverrides::new()),
+            edit_revision: AtomicU64::new(0),
         }
     }
 
+    /// Terrain shaped by a designer's [`WorldGenConfig`] (noise, biomes,
+    /// splat rules and hydrology).
+    pub fn from_config(config: &WorldGenConfig) -> Self {
+        let mut terrain = Self::new(config.seed, config.chunk_size, config.base_resolution)
+            .with_noise_layers(config.noise.clone())
+            .with_biomes(config.biomes.clone())
+            .with_material_rules(config.materials);
+        if let Some(hydrology) = config.hydrology {
+            terrain = terrain.with_hydrology(hydrology);
+        }
+        terrain
+    }
+
+    pub fn with_noise_layers(mut self, noise: Vec<NoiseLayer>) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    /// Reshape the noise layers and scale generated heights by
+    /// `vertical_exaggeration`.  Splat rules are scaled along, so sand and
+    /// snow stay on the same parts of the terrain; biome bands and the
+    /// hydrology water table are in generated-height units and need no
+    /// change.
+    pub fn with_noise_params(mut self, params: NoiseParams) -> Self {
+        self.noise = apply_noise_params(&self.noise, &params);
+        let scale = params.vertical_exaggeration / self.height_scale;
+        self.height_scale = params.vertical_exaggeration;
+        let m = &mut self.materials;
+        m.sand_max *= scale;
+        m.snow_min *= scale;
+        m.blend *= scale;
+        m.rock_slope_start *= scale;
+        m.rock_slope_full *= scale;
+        self
+    }
+
+    pub fn with_biomes(mut self, biomes: Vec<BiomeBand>) -> Self {
+        self.biomes = biomes;
+        self
+    }
+
+    pub fn with_seed_regions(mut self, regions: Vec<SeedRegion>) -> Self {
+        self.seed_regions = regions;
+        self
+    }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn with_lod_bands(mut self, lod_bands: Vec<f32>) -> Self {
+        self.lod_bands = lod_bands;
+        self
+    }
+
+    pub fn with_border_stitching(mut self, stitch_borders: bool) -> Self {
+        self.stitch_borders = stitch_borders;
+        self
+    }
+
+    pub fn with_erosion(mut self, erosion: ErosionConfig) -> Self {
+        self.erosion = Some(erosion);
+        self
+    }
+
+    pub fn with_hydrology(mut self, hydrology: HydrologyConfig) -> Self {
+        self.hydrology = Some(hydrology);
+        self
+    }
+
+    pub fn with_voxels(mut self, voxels: VoxelLayer) -> Self {
+        self.voxels = Some(voxels);
+        self
+    }
+
+    pub fn with_material_rules(mut self, materials: MaterialRules) -> Self {
+        self.materials = materials;
+        self
+    }
+
+    pub fn with_cache_budget(mut self, budget: CacheBudget) -> Self {
+        self.cache_budget = Some(budget);
+        self
+    }
+
+    /// Log chunk builds slower than `ms` (`0` disables).
+    pub fn with_slow_generation_ms(mut self, ms: f32) -> Self {
+        self.slow_generation_ms = ms;
+        self
+    }
+
+    /// Persist generated chunks under `dir` and reuse them across restarts.
+    pub fn with_chunk_store(mut self, dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        self.store = Some(ChunkStore::open(dir)?);
+        Ok(self)
+    }
+
+    /// Load designer height overrides from `dir` and persist new ones there.
+    pub fn with_height_overrides(mut self, dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        self.height_overrides = RwLock::new(HeightOverrides::open(dir)?);
+        Ok(self)
+    }
+
     pub fn chunk_coord(&self, x: f32, y: f32) -> (i32, i32) {
         (
             (x / self.chunk_size).floor() as i32,
@@ -178,27 +1004,259 @@ impl HeightmapTerrain {
         )
     }
 
+    /// Effective seed of chunk `(cx, cy)`: the first seed region containing
+    /// the chunk centre, else the world seed.
+    pub fn chunk_seed(&self, cx: i32, cy: i32) -> u64 {
+        let x = (cx as f32 + 0.5) * self.chunk_size;
+        let y = (cy as f32 + 0.5) * self.chunk_size;
+        self.seed_regions
+            .iter()
+            .find(|r| r.contains(x, y))
+            .map_or(self.seed, |r| r.seed)
+    }
+
+    /// Effective seed at a world point (that of its chunk).
+    fn seed_at(&self, x: f32, y: f32) -> u64 {
+        let (cx, cy) = self.chunk_coord(x, y);
+        self.chunk_seed(cx, cy)
+    }
+
     pub fn lod_for_distance(&self, distance: f32) -> u8 {
-        if distance < 100.0 {
-            0
-        } else if distance < 300.0 {
-            1
-        } else {
-            2
-        }
+        self.lod_bands
+            .iter()
+            .take_while(|&&band| distance >= band)
+            .count() as u8
     }
 
-    /// Build a `ColliderShape::Heightfield` for a chunk at the given LOD.
+    /// Collider for a chunk at the given LOD (see [`footprint_collider`]).
     pub fn heightfield_collider_for_chunk(&self, cx: i32, cy: i32, lod: u8) -> ColliderShape {
         let chunk = self.get_or_generate_chunk(cx, cy, lod);
+        footprint_collider(chunk.resolution as f32 * chunk.cell_size)
+    }
+
+    // -----------------------------------------------------------------------
+    // Grid sampling
+    // -----------------------------------------------------------------------
+
+    /// Spacing between LOD 0 samples (uniform across all chunks).
+    fn lod0_cell_size(&self) -> f32 {
+        self.chunk_size / (self.base_resolution.max(4)) as f32
+    }
+
+    /// Samples per chunk side at the coarsest LOD `lod_for_distance` hands
+    /// out; chunk borders are stitched to this spacing.
+    pub fn border_resolution(&self) -> usize {
+        (self.base_resolution >> self.lod_bands.len()).max(4)
+    }
 
-        // TODO(Phase 1): Replace with ColliderShape::Heightfield once the physics
-        // engine exposes a 3D heightfield variant.  For now use a flat Box that
-        // covers the chunk footprint.
-        let _ = lod; // suppress unused-variable lint until variant is added
-        ColliderShape::Box {
-            width: chunk.resolution as f32 * chunk.cell_size,
-            height: chunk.resolution as f32 * chunk.cell_size,
+    /// Height of a border vertex: the generated base before erosion, plus
+    /// any edit.  Erosion output depends on the LOD, so borders skip it.
+    fn border_sample(&self, x: f32, y: f32) -> f32 {
+        let cell = self.lod0_cell_size();
+        let key = ((x / cell).round() as i64, (y / cell).round() as i64);
+        let offset = self.overrides.read().get(&key).copied().unwrap_or(0.0)
+            + self.designer_offset(&self.height_overrides.read(), key);
+        self.sample_noise(self.seed_at(x, y), x, y) + offset
+    }
+
+    /// LOD 0 sample at global grid index `(gx, gy)`.
+    ///
+    /// Indices are chunk-independent: sample `(gx, gy)` sits at world
+    /// `(gx * cell, gy * cell)`, so neighbours across a chunk edge come from
+    /// the adjacent chunk and interpolation is continuous everywhere.
+    fn grid_sample(&self, gx: i64, gy: i64) -> f32 {
+        let res = self.base_resolution.max(4) as i64;
+        let chunk =
+            self.get_or_generate_chunk(gx.div_euclid(res) as i32, gy.div_euclid(res) as i32, 0);
+        let lx = gx.rem_euclid(res) as usize;
+        let ly = gy.rem_euclid(res) as usize;
+        chunk.heights[ly * chunk.resolution + lx]
+    }
+
+    // -----------------------------------------------------------------------
+    // Deformation
+    // -----------------------------------------------------------------------
+
+    /// Raise (`delta > 0`) or dig (`delta < 0`) the terrain around
+    /// `(center_x, center_y)` with a smoothstep falloff to zero at `radius`.
+    ///
+    /// Edits accumulate in an override layer; the procedural base and the
+    /// disk store are untouched.  Returns every chunk whose mesh changed,
+    /// sorted by `(cx, cy)`.
+    pub fn deform(&self, center_x: f32, center_y: f32, radius: f32, delta: f32) -> Vec<(i32, i32)> {
+        if radius <= 0.0 || delta == 0.0 {
+            return Vec::new();
+        }
+
+        let cell = self.lod0_cell_size();
+        let gx0 = ((center_x - radius) / cell).floor() as i64;
+        let gx1 = ((center_x + radius) / cell).ceil() as i64;
+        let gy0 = ((center_y - radius) / cell).floor() as i64;
+        let gy1 = ((center_y + radius) / cell).ceil() as i64;
+
+        let mut touched = BTreeSet::new();
+        {
+            let mut overrides = self.overrides.write();
+            for gy in gy0..=gy1 {
+                for gx in gx0..=gx1 {
+                    let dx = gx as f32 * cell - center_x;
+                    let dy = gy as f32 * cell - center_y;
+                    let d = (dx * dx + dy * dy).sqrt();
+                    if d >= radius {
+                        continue;
+                    }
+                    let w = smooth_step(1.0 - (d / radius) as f64) as f32;
+                    *overrides.entry((gx, gy)).or_insert(0.0) += delta * w;
+                    self.touch_sample(gx, gy, &mut touched);
+                }
+            }
+        }
+
+        self.invalidate_chunks(&touched);
+        touched.into_iter().collect()
+    }
+
+    /// Pin LOD 0 samples to designer heights.
+    ///
+    /// Each `[x, y, height]` snaps to the nearest grid sample, whose
+    /// override becomes `height` minus the generated height there, so the
+    /// sample reads `height` until a runtime deformation adds to it (chunk
+    /// border samples are still stitched to the coarse LOD).  Later samples
+    /// win.  Overrides are written to the override directory, if
+    /// any, before they take effect.  Returns every chunk whose mesh
+    /// changed, sorted by `(cx, cy)`.
+    pub fn set_heights(&self, samples: &[[f32; 3]]) -> io::Result<Vec<(i32, i32)>> {
+        let cell = self.lod0_cell_size();
+        let mut generated: HashMap<(i32, i32), HeightChunk> = HashMap::new();
+        let mut deltas: BTreeMap<(i32, i32), ChunkOverrides> = BTreeMap::new();
+        let mut touched = BTreeSet::new();
+        for &[x, y, height] in samples {
+            let (gx, gy) = ((x / cell).round() as i64, (y / cell).round() as i64);
+            let (chunk, (lx, ly)) = self.grid_key(gx, gy);
+            let base = generated
+                .entry(chunk)
+                .or_insert_with(|| self.load_or_generate_chunk(chunk.0, chunk.1, 0));
+            let base_height = base.heights[ly as usize * base.resolution + lx as usize];
+            deltas
+                .entry(chunk)
+                .or_default()
+                .insert((lx, ly), height - base_height);
+            self.touch_sample(gx, gy, &mut touched);
+        }
+
+        let result = {
+            let mut overrides = self.height_overrides.write();
+            deltas
+                .into_iter()
+                .try_for_each(|((cx, cy), d)| overrides.set(cx, cy, d))
+        };
+        // Chunks persisted before a failed write still changed.
+        self.invalidate_chunks(&touched);
+        result.map(|()| touched.into_iter().collect())
+    }
+
+    /// Add every chunk whose mesh uses LOD 0 sample `(gx, gy)` to `touched`.
+    /// A sample on a chunk's first row/column is also the closing edge of
+    /// the neighbour's mesh, so both chunks need re-meshing.
+    fn touch_sample(&self, gx: i64, gy: i64, touched: &mut BTreeSet<(i32, i32)>) {
+        let res = self.base_resolution.max(4) as i64;
+        let owners = |g: i64| {
+            let c = g.div_euclid(res) as i32;
+            if g.rem_euclid(res) == 0 {
+                vec![c - 1, c]
+            } else {
+                vec![c]
+            }
+        };
+        for cx in owners(gx) {
+            for cy in owners(gy) {
+                touched.insert((cx, cy));
+            }
+        }
+    }
+
+    /// Drop stale cached chunks at every LOD; they regenerate with the new
+    /// offsets on next access.  Chunks generated concurrently with the edit
+    /// see the new revision and skip the cache.
+    fn invalidate_chunks(&self, touched: &BTreeSet<(i32, i32)>) {
+        let mut cache = self.cache.write();
+        self.edit_revision.fetch_add(1, Ordering::SeqCst);
+        cache.retain(|(cx, cy, _lod)| !touched.contains(&(*cx, *cy)));
+    }
+
+    /// Number of LOD 0 samples with a designer override.
+    pub fn height_override_count(&self) -> usize {
+        self.height_overrides.read().len()
+    }
+
+    /// Chunk and local LOD 0 index of global grid index `(gx, gy)`.
+    fn grid_key(&self, gx: i64, gy: i64) -> ((i32, i32), (u32, u32)) {
+        let res = self.base_resolution.max(4) as i64;
+        (
+            (gx.div_euclid(res) as i32, gy.div_euclid(res) as i32),
+            (gx.rem_euclid(res) as u32, gy.rem_euclid(res) as u32),
+        )
+    }
+
+    /// Cut every LOD 0 cell whose centre is within `radius` of the centre.
+    /// Heights are untouched (the rim keeps its shape); returns the chunks
+    /// that gained a hole.
+    pub fn cut_holes(&self, center_x: f32, center_y: f32, radius: f32) -> Vec<(i32, i32)> {
+        if radius <= 0.0 {
+            return Vec::new();
+        }
+        let cell = self.lod0_cell_size();
+        let gx0 = ((center_x - radius) / cell).floor() as i64;
+        let gx1 = ((center_x + radius) / cell).ceil() as i64;
+        let gy0 = ((center_y - radius) / cell).floor() as i64;
+        let gy1 = ((center_y + radius) / cell).ceil() as i64;
+
+        let mut touched = BTreeSet::new();
+        let mut holes = self.holes.write();
+        for gy in gy0..=gy1 {
+            for gx in gx0..=gx1 {
+                let dx = (gx as f32 + 0.5) * cell - center_x;
+                let dy = (gy as f32 + 0.5) * cell - center_y;
+                if dx * dx + dy * dy >= radius * radius {
+                    continue;
+                }
+                let (chunk, local) = self.grid_key(gx, gy);
+                if holes.entry(chunk).or_default().insert(local) {
+                    touched.insert(chunk);
+                }
+            }
+        }
+        touched.into_iter().collect()
+    }
+
+    /// Designer override at global LOD 0 grid index `key`.
+    fn designer_offset(&self, designer: &HeightOverrides, key: (i64, i64)) -> f32 {
+        if designer.is_empty() {
+            return 0.0;
+        }
+        let ((cx, cy), (lx, ly)) = self.grid_key(key.0, key.1);
+        designer.get(cx, cy, lx, ly).unwrap_or(0.0)
+    }
+
+    /// Add override-layer offsets (runtime edits and designer touch-ups) to
+    /// a freshly generated/loaded chunk.
+    fn apply_overrides(&self, chunk: &mut HeightChunk) {
+        let overrides = self.overrides.read();
+        let designer = self.height_overrides.read();
+        if overrides.is_empty() && designer.is_empty() {
+            return;
+        }
+
+        let cell = self.lod0_cell_size();
+        for row in 0..chunk.resolution {
+            for col in 0..chunk.resolution {
+                let wx = chunk.world_origin_x + col as f32 * chunk.cell_size;
+                let wy = chunk.world_origin_y + row as f32 * chunk.cell_size;
+                let key = ((wx / cell).round() as i64, (wy / cell).round() as i64);
+                let offset = overrides.get(&key).copied().unwrap_or(0.0)
+                    + self.designer_offset(&designer, key);
+                chunk.heights[row * chunk.resolution + col] += offset;
+            }
         }
     }
 
@@ -206,27 +1264,134 @@ impl HeightmapTerrain {
     // Cache helpers
     // -----------------------------------------------------------------------
 
+    /// Cached chunk, generating (or loading) it on a miss.
+    ///
+    /// Generation runs without holding the cache lock, so several threads
+    /// (e.g. the chunk worker pool) can build different chunks at once; if
+    /// two race on the same chunk the first one cached wins.
     pub fn get_or_generate_chunk(&self, cx: i32, cy: i32, lod: u8) -> Arc<HeightChunk> {
+        let key = (cx, cy, lod);
+        let now = self.cache_clock.fetch_add(1, Ordering::Relaxed);
+        if let Some(entry) = self.cache.read().entries.get(&key) {
+            entry.last_used.store(now, Ordering::Relaxed);
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return entry.chunk.clone();
+        }
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+
+        let revision = self.edit_revision.load(Ordering::SeqCst);
+        let started = Instant::now();
+        let mut chunk = self.load_or_generate_chunk(cx, cy, lod);
+        self.apply_overrides(&mut chunk);
+        if self.stitch_borders {
+            self.stitch_borders(&mut chunk);
+        }
+        chunk.compute_materials(&self.materials);
+        self.record_generation(cx, cy, lod, started.elapsed());
+        let chunk = Arc::new(chunk);
+
         let mut cache = self.cache.write();
-        match cache.entry((cx, cy, lod)) {
-            Entry::Occupied(e) => e.get().clone(),
-            Entry::Vacant(v) => {
-                let chunk = Arc::new(self.generate_chunk(cx, cy, lod));
-                v.insert(chunk.clone());
-                chunk
+        if self.edit_revision.load(Ordering::SeqCst) != revision {
+            return chunk;
+        }
+        if let Some(entry) = cache.entries.get(&key) {
+            return entry.chunk.clone();
+        }
+        cache.insert(key, chunk.clone(), now);
+        if let Some(budget) = self.cache_budget {
+            let evicted = cache.enforce(budget);
+            self.cache_evictions
+                .fetch_add(evicted as u64, Ordering::Relaxed);
+        }
+        chunk
+    }
+
+    /// `true` if the chunk is cached (a lookup will not generate).
+    pub fn is_cached(&self, cx: i32, cy: i32, lod: u8) -> bool {
+        self.cache.read().entries.contains_key(&(cx, cy, lod))
+    }
+
+    /// Cache counters since construction and current occupancy.
+    pub fn cache_stats(&self) -> ChunkCacheStats {
+        let cache = self.cache.read();
+        let hits = self.cache_hits.load(Ordering::Relaxed);
+        let misses = self.cache_misses.load(Ordering::Relaxed);
+        let generations = self.generations.load(Ordering::Relaxed);
+        let total_us = self.generation_us_total.load(Ordering::Relaxed);
+        ChunkCacheStats {
+            hits,
+            misses,
+            hit_rate: if hits + misses > 0 {
+                hits as f32 / (hits + misses) as f32
+            } else {
+                0.0
+            },
+            evictions: self.cache_evictions.load(Ordering::Relaxed),
+            chunks: cache.entries.len(),
+            bytes: cache.bytes,
+            generation_ms_mean: if generations > 0 {
+                total_us as f32 / generations as f32 / 1000.0
+            } else {
+                0.0
+            },
+            generation_ms_max: self.generation_us_max.load(Ordering::Relaxed) as f32 / 1000.0,
+            slow_generations: self.slow_generations.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record_generation(&self, cx: i32, cy: i32, lod: u8, elapsed: Duration) {
+        let us = elapsed.as_micros().min(u64::MAX as u128) as u64;
+        self.generations.fetch_add(1, Ordering::Relaxed);
+        self.generation_us_total.fetch_add(us, Ordering::Relaxed);
+        self.generation_us_max.fetch_max(us, Ordering::Relaxed);
+        let ms = us as f32 / 1000.0;
+        if self.slow_generation_ms > 0.0 && ms > self.slow_generation_ms {
+            self.slow_generations.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "Slow terrain chunk ({}, {}, lod {}): {:.1} ms (base_resolution {})",
+                cx, cy, lod, ms, self.base_resolution
+            );
+        }
+    }
+
+    /// Cache-miss path: read through the disk store, else generate and
+    /// write back.
+    fn load_or_generate_chunk(&self, cx: i32, cy: i32, lod: u8) -> HeightChunk {
+        let Some(store) = &self.store else {
+            return self.generate_chunk(cx, cy, lod);
+        };
+
+        let key = self.store_key();
+        if let Some(chunk) = store.load(key, cx, cy, lod) {
+            if chunk.has_layout(cx, cy, lod, self.chunk_size, self.base_resolution) {
+                return chunk;
             }
         }
+
+        let chunk = self.generate_chunk(cx, cy, lod);
+        if let Err(e) = store.save(key, cx, cy, lod, &chunk) {
+            warn!(
+                "Failed to persist terrain chunk ({}, {}, lod {}) to {}: {}",
+                cx,
+                cy,
+                lod,
+                store.dir().display(),
+                e
+            );
+        }
+        chunk
     }
 
     /// Evict every chunk whose (cx, cy) chunk-centre is further than
     /// `max_chunks` cells from `origin` in Chebyshev distance.
     pub fn evict_distant_chunks(&self, origin_cx: i32, origin_cy: i32, max_chunks: i32) {
-        let mut cache = self.cache.write();
-        cache.retain(|(cx, cy, _lod), _| {
+        let evicted = self.cache.write().retain(|(cx, cy, _lod)| {
             let dx = (cx - origin_cx).abs();
             let dy = (cy - origin_cy).abs();
             dx <= max_chunks && dy <= max_chunks
         });
+        self.cache_evictions
+            .fetch_add(evicted as u64, Ordering::Relaxed);
     }
 
     // -----------------------------------------------------------------------
@@ -234,32 +1399,155 @@ impl HeightmapTerrain {
     // -----------------------------------------------------------------------
 
     fn generate_chunk(&self, cx: i32, cy: i32, lod: u8) -> HeightChunk {
-        let resolution = (self.base_resolution >> lod).max(4);
-        let cell_size = self.chunk_size / resolution as f32;
-        let world_origin_x = cx as f32 * self.chunk_size;
-        let world_origin_y = cy as f32 * self.chunk_size;
+        let seed = self.chunk_seed(cx, cy);
+        let mut chunk = HeightChunk::sample(
+            cx,
+            cy,
+            lod,
+            self.chunk_size,
+            self.base_resolution,
+            |wx, wy| self.sample_noise(seed, wx, wy),
+        );
+        if let Some(erosion) = self.erosion.as_ref().filter(|e| e.iterations > 0) {
+            self.erode_chunk(&mut chunk, erosion);
+        }
+        chunk
+    }
 
-        let mut heights = Vec::with_capacity(resolution * resolution);
-        for row in 0..resolution {
-            for col in 0..resolution {
-                let wx = world_origin_x + col as f32 * cell_size;
-                let wy = world_origin_y + row as f32 * cell_size;
-                heights.push(self.sample_noise(wx, wy));
+    /// Rewrite the chunk's first row and column — the borders it shares with
+    /// its `-x` / `-y` neighbours, which close their meshes against them —
+    /// as seen from the coarsest LOD: [`border_sample`](Self::border_sample)
+    /// at every `border_resolution` vertex (including the far corner, owned
+    /// by the next chunk), linearly interpolated in between.
+    ///
+    /// Every LOD of every chunk then produces the same border polyline, so
+    /// finer vertices lie exactly on the coarser neighbour's edge and no
+    /// T-junction cracks open.  Runs after edits are applied so deformed
+    /// borders stay stitched too.
+    fn stitch_borders(&self, chunk: &mut HeightChunk) {
+        let res = chunk.resolution;
+        let step = (res / self.border_resolution()).max(1);
+        let span = step as f32 * chunk.cell_size;
+        let (ox, oy) = (chunk.world_origin_x, chunk.world_origin_y);
+
+        // Coarse vertices along the row (`y = oy`) and the column (`x = ox`).
+        let coarse = res.div_ceil(step);
+        let row: Vec<f32> = (0..=coarse)
+            .map(|k| self.border_sample(ox + k as f32 * span, oy))
+            .collect();
+        let col: Vec<f32> = (0..=coarse)
+            .map(|k| self.border_sample(ox, oy + k as f32 * span))
+            .collect();
+
+        for i in 0..res {
+            let (k, t) = (i / step, (i % step) as f32 / step as f32);
+            chunk.heights[i] = lerp32(row[k], row[k + 1], t);
+            chunk.heights[i * res] = lerp32(col[k], col[k + 1], t);
+        }
+    }
+
+    /// Erode `chunk` inside a padded grid so the result is seamless with its
+    /// neighbours, then crop back to the chunk footprint.
+    fn erode_chunk(&self, chunk: &mut HeightChunk, erosion: &ErosionConfig) {
+        let res = chunk.resolution;
+        let pad = erosion.padding();
+        let size = res + 2 * pad;
+
+        let mut grid = Vec::with_capacity(size * size);
+        for row in 0..size {
+            for col in 0..size {
+                let inside = (pad..pad + res).contains(&row) && (pad..pad + res).contains(&col);
+                grid.push(if inside {
+                    chunk.heights[(row - pad) * res + (col - pad)]
+                } else {
+                    let wx = chunk.world_origin_x + (col as f32 - pad as f32) * chunk.cell_size;
+                    let wy = chunk.world_origin_y + (row as f32 - pad as f32) * chunk.cell_size;
+                    self.sample_noise(self.seed_at(wx, wy), wx, wy)
+                });
             }
         }
 
-        HeightChunk {
-            heights,
-            resolution,
-            world_origin_x,
-            world_origin_y,
-            cell_size,
+        erosion.apply(&mut grid, size, chunk.cell_size);
+
+        for row in 0..res {
+            let src = (row + pad) * size + pad;
+            chunk.heights[row * res..(row + 1) * res].copy_from_slice(&grid[src..src + res]);
+        }
+    }
+
+    /// Disk store key: the seed, salted with any post-process parameters so
+    /// chunks generated with different settings are never mixed.
+    fn store_key(&self) -> u64 {
+        let erosion = self.erosion.as_ref().filter(|e| e.iterations > 0);
+        let custom_noise = self.noise != default_noise_layers();
+        if erosion.is_none()
+            && self.hydrology.is_none()
+            && self.seed_regions.is_empty()
+            && !custom_noise
+            && self.height_scale == 1.0
+        {
+            return self.seed;
+        }
+        let mut key = self.seed.to_string();
+        if let Some(erosion) = erosion {
+            key += &format!(
+                ":erosion:{}:{}:{}:{}:{}",
+                erosion.iterations,
+                erosion.talus,
+                erosion.thermal_rate,
+                erosion.hydraulic_rate,
+                erosion.deposition
+            );
+        }
+        if let Some(h) = &self.hydrology {
+            key += &format!(
+                ":hydrology:{}:{}:{}:{}",
+                h.water_table, h.river_scale, h.river_width, h.river_depth
+            );
+        }
+        for r in &self.seed_regions {
+            key += &format!(
+                ":region:{}:{}:{}:{}:{}",
+                r.min_x, r.min_y, r.max_x, r.max_y, r.seed
+            );
+        }
+        if custom_noise {
+            for l in &self.noise {
+                key += &format!(":noise:{}:{}:{}", l.weight, l.frequency, l.salt);
+            }
+        }
+        if self.height_scale != 1.0 {
+            key += &format!(":scale:{}", self.height_scale);
         }
+        let digest = md5::compute(key.as_bytes());
+        u64::from_le_bytes(digest.0[..8].try_into().unwrap())
     }
 
-    /// Canonical deterministic elevation noise aligned with Python world generator.
-    fn sample_noise(&self, x: f32, y: f32) -> f32 {
-        elevation(x as f64, y as f64, self.seed) as f32
+    /// Noise height before any carving or post-processing.
+    fn base_noise(&self, seed: u64, x: f32, y: f32) -> f32 {
+        layered_noise(&self.noise, seed, x, y)
+    }
+
+    /// Biome at a world point: the first band whose `below` exceeds the
+    /// generated height there (edits don't change biomes).
+    pub fn biome_at(&self, x: f32, y: f32) -> &str {
+        let height = self.base_noise(self.seed_at(x, y), x, y);
+        self.biomes
+            .iter()
+            .find(|b| height < b.below)
+            .or(self.biomes.last())
+            .map_or("", |b| b.name.as_str())
+    }
+
+    /// Generated height before post-processing: the layered noise with
+    /// river channels carved in when hydrology is enabled, in world units.
+    fn sample_noise(&self, seed: u64, x: f32, y: f32) -> f32 {
+        let height = self.base_noise(seed, x, y);
+        let height = match &self.hydrology {
+            Some(h) => hydrology::carve(h, seed, x, y, height),
+            None => height,
+        };
+        height * self.height_scale
     }
 }
 
@@ -267,31 +1555,189 @@ impl HeightmapTerrain {
 // TerrainSource impl
 // ---------------------------------------------------------------------------
 
+impl HeightmapTerrain {
+    /// Interpolated height at `(x, y)` with its analytical gradient
+    /// `(dh/dx, dh/dy)`, differentiated from the same interpolant.
+    ///
+    /// `Nearest` surfaces are flat within a cell, so their gradient comes
+    /// from the bilinear surface through the same samples instead.
+    pub fn surface_at(&self, x: f32, y: f32) -> (f32, f32, f32) {
+        let cell = self.lod0_cell_size();
+        let gx = x / cell;
+        let gy = y / cell;
+        let ix = gx.floor() as i64;
+        let iy = gy.floor() as i64;
+        let fx = gx - ix as f32;
+        let fy = gy - iy as f32;
+
+        match self.interpolation {
+            Interpolation::Nearest | Interpolation::Bilinear => {
+                let s00 = self.grid_sample(ix, iy);
+                let s10 = self.grid_sample(ix + 1, iy);
+                let s01 = self.grid_sample(ix, iy + 1);
+                let s11 = self.grid_sample(ix + 1, iy + 1);
+                let height = match self.interpolation {
+                    Interpolation::Nearest => s00,
+                    _ => lerp32(lerp32(s00, s10, fx), lerp32(s01, s11, fx), fy),
+                };
+                let dx = lerp32(s10 - s00, s11 - s01, fy) / cell;
+                let dy = lerp32(s01 - s00, s11 - s10, fx) / cell;
+                (height, dx, dy)
+            }
+            Interpolation::Bicubic => {
+                let mut rows = [0.0f32; 4];
+                let mut row_slopes = [0.0f32; 4];
+                for r in 0..4 {
+                    let sy = iy - 1 + r as i64;
+                    let p = [
+                        self.grid_sample(ix - 1, sy),
+                        self.grid_sample(ix, sy),
+                        self.grid_sample(ix + 1, sy),
+                        self.grid_sample(ix + 2, sy),
+                    ];
+                    rows[r] = catmull_rom(p[0], p[1], p[2], p[3], fx);
+                    row_slopes[r] = catmull_rom_slope(p[0], p[1], p[2], p[3], fx);
+                }
+                let [r0, r1, r2, r3] = rows;
+                let [d0, d1, d2, d3] = row_slopes;
+                (
+                    catmull_rom(r0, r1, r2, r3, fy),
+                    catmull_rom(d0, d1, d2, d3, fy) / cell,
+                    catmull_rom_slope(r0, r1, r2, r3, fy) / cell,
+                )
+            }
+        }
+    }
+}
+
 impl TerrainSource for HeightmapTerrain {
     fn height_at(&self, x: f32, y: f32) -> f32 {
-        let (cx, cy) = self.chunk_coord(x, y);
-        let chunk = self.get_or_generate_chunk(cx, cy, 0);
+        self.surface_at(x, y).0
+    }
+
+    /// Unit normal from the analytical gradient of the interpolated surface.
+    fn normal_at(&self, x: f32, y: f32) -> Vec3 {
+        let (_, dx, dy) = self.surface_at(x, y);
+        let len = (dx * dx + dy * dy + 1.0).sqrt();
+        Vec3::new(-dx / len, -dy / len, 1.0 / len)
+    }
+
+    fn solid_at(&self, x: f32, y: f32, z: f32) -> bool {
+        let value = self
+            .voxels
+            .as_ref()
+            .map_or(VOXEL_INHERIT, |v| v.get(x, y, z));
+        match value {
+            VOXEL_INHERIT => z <= self.height_at(x, y) && !self.is_hole(x, y),
+            VOXEL_AIR => false,
+            _ => true,
+        }
+    }
 
-        let local_x = x - chunk.world_origin_x;
-        let local_y = y - chunk.world_origin_y;
+    fn water_level_at(&self, x: f32, y: f32) -> Option<f32> {
+        let h = self.hydrology.as_ref()?;
+        let seed = self.seed_at(x, y);
+        let level =
+            hydrology::surface(h, seed, x, y, self.base_noise(seed, x, y)) * self.height_scale;
+        (self.height_at(x, y) < level).then_some(level)
+    }
 
-        let gx = (local_x / chunk.cell_size).clamp(0.0, (chunk.resolution - 1) as f32);
-        let gy = (local_y / chunk.cell_size).clamp(0.0, (chunk.resolution - 1) as f32);
+    fn chunk_descriptor(&self, cx: i32, cy: i32, _lod: u8) -> Option<ChunkDescriptor> {
+        Some(ChunkDescriptor {
+            seed: self.chunk_seed(cx, cy),
+            chunk_size: self.chunk_size,
+            // Water heights in the world units clients see.
+            hydrology: self.hydrology.map(|h| HydrologyConfig {
+                water_table: h.water_table * self.height_scale,
+                river_depth: h.river_depth * self.height_scale,
+                ..h
+            }),
+            materials: self.materials,
+        })
+    }
 
-        let ix = gx.floor() as usize;
-        let iy = gy.floor() as usize;
+    fn lod_for_distance(&self, distance: f32) -> u8 {
+        HeightmapTerrain::lod_for_distance(self, distance)
+    }
 
-        chunk.heights[iy * chunk.resolution + ix]
+    fn collider_for_chunk(&self, cx: i32, cy: i32, lod: u8) -> Option<ColliderShape> {
+        Some(self.heightfield_collider_for_chunk(cx, cy, lod))
     }
 
-    fn normal_at(&self, x: f32, y: f32) -> Vec3 {
-        // Finite-difference gradient (replace with analytical in Phase 1.3)
-        let eps = 0.5;
-        let h_l = self.height_at(x - eps, y);
-        let h_r = self.height_at(x + eps, y);
-        let h_d = self.height_at(x, y - eps);
-        let h_u = self.height_at(x, y + eps);
-        Vec3::new(h_l - h_r, h_d - h_u, 2.0 * eps)
+    fn warm_chunk(&self, cx: i32, cy: i32, lod: u8) {
+        self.get_or_generate_chunk(cx, cy, lod);
+    }
+
+    fn is_chunk_cached(&self, cx: i32, cy: i32, lod: u8) -> bool {
+        self.is_cached(cx, cy, lod)
+    }
+
+    /// Clients run the canonical noise pipeline, but not the erosion pass,
+    /// and have no copy of designer overrides or reshaped noise.
+    fn client_regenerable(&self, cx: i32, cy: i32) -> bool {
+        self.erosion.is_none()
+            && self.noise == default_noise_layers()
+            && self.height_scale == 1.0
+            && self.height_overrides.read().chunk(cx, cy).is_none()
+    }
+
+    fn chunk_checksum(&self, cx: i32, cy: i32, lod: u8) -> Option<u64> {
+        Some(heights_checksum(
+            &self.get_or_generate_chunk(cx, cy, lod).heights,
+        ))
+    }
+
+    fn deform_region(
+        &self,
+        center_x: f32,
+        center_y: f32,
+        radius: f32,
+        delta: f32,
+    ) -> Option<Vec<(i32, i32)>> {
+        Some(self.deform(center_x, center_y, radius, delta))
+    }
+
+    fn set_heights(&self, samples: &[[f32; 3]]) -> Option<io::Result<Vec<(i32, i32)>>> {
+        Some(HeightmapTerrain::set_heights(self, samples))
+    }
+
+    fn is_hole(&self, x: f32, y: f32) -> bool {
+        let holes = self.holes.read();
+        if holes.is_empty() {
+            return false;
+        }
+        let cell = self.lod0_cell_size();
+        let (chunk, local) = self.grid_key((x / cell).floor() as i64, (y / cell).floor() as i64);
+        holes
+            .get(&chunk)
+            .is_some_and(|cells| cells.contains(&local))
+    }
+
+    fn cut_holes(&self, center_x: f32, center_y: f32, radius: f32) -> Option<Vec<(i32, i32)>> {
+        Some(HeightmapTerrain::cut_holes(
+            self, center_x, center_y, radius,
+        ))
+    }
+
+    fn chunk_holes(&self, cx: i32, cy: i32) -> Option<ChunkHoles> {
+        let holes = self.holes.read();
+        let cells = holes.get(&(cx, cy)).filter(|c| !c.is_empty())?;
+        Some(ChunkHoles {
+            cell_size: self.lod0_cell_size(),
+            cells: cells.iter().map(|&(i, j)| [i, j]).collect(),
+        })
+    }
+
+    fn chunk_cache_stats(&self) -> ChunkCacheStats {
+        self.cache_stats()
+    }
+
+    fn biome(&self, x: f32, y: f32) -> Option<&str> {
+        Some(self.biome_at(x, y))
+    }
+
+    fn voxel_layer(&self) -> Option<&VoxelLayer> {
+        self.voxels.as_ref()
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -0,0 +1,203 @@
+//! Delta encoding of [`EntityTransformBatch`]es (protocol version 4).
+//!
+//! [`TransformEncoder`] rewrites each tick's batch on the server: every
+//! `keyframe_interval` batches it sends a keyframe with every transform in
+//! full, and in between it replaces transforms with quantised
+//! [`TransformDelta`]s against the last transform published for the same
+//! handle.  Baselines are the values the client *reconstructs*, not the
+//! exact ones, so rounding never accumulates; keyframes bound any drift
+//! from clients that do the arithmetic in `f64`.
+//!
+//! [`TransformDecoder`] is the client side: it tracks `seq`, drops its
+//! baselines on a gap and skips deltas until the handle's next full
+//! transform.
+
+use crate::protocol::{
+    EntityTransformBatch, HandleTransform, TransformDelta, TRANSFORM_DELTA_QUANTUM,
+};
+use std::collections::HashMap;
+
+/// Last transform published (server) or reconstructed (client) for one
+/// handle.
+#[derive(Debug, Clone)]
+struct Baseline {
+    /// `x, y, z, rotation_y, vx, vy, vz`.
+    values: [f32; 7],
+    dt: f32,
+    last_intent_seq: Option<u64>,
+}
+
+impl Baseline {
+    fn new(t: &HandleTransform) -> Self {
+        Self {
+            values: [t.x, t.y, t.z, t.rotation_y, t.vx, t.vy, t.vz],
+            dt: t.dt,
+            last_intent_seq: t.last_intent_seq,
+        }
+    }
+
+    /// Delta from this baseline to `t`, moving the baseline to what the
+    /// client will reconstruct.  `None` (baseline untouched) when `t` can't
+    /// be expressed as a delta and has to go out in full.
+    fn delta(&mut self, t: &HandleTransform) -> Option<TransformDelta> {
+        if t.dt != self.dt || (t.last_intent_seq.is_none() && self.last_intent_seq.is_some()) {
+            return None;
+        }
+        let target = [t.x, t.y, t.z, t.rotation_y, t.vx, t.vy, t.vz];
+        let mut q = [0i32; 7];
+        for i in 0..7 {
+            let steps = ((target[i] - self.values[i]) / TRANSFORM_DELTA_QUANTUM).round();
+            if !steps.is_finite() || steps.abs() > i32::MAX as f32 {
+                return None;
+            }
+            q[i] = steps as i32;
+        }
+        for (value, steps) in self.values.iter_mut().zip(q) {
+            *value = apply_steps(*value, steps);
+        }
+        let last_intent_seq = t
+            .last_intent_seq
+            .filter(|seq| self.last_intent_seq != Some(*seq));
+        self.last_intent_seq = t.last_intent_seq;
+        let [dx, dy, dz, drot, dvx, dvy, dvz] = q;
+        Some(TransformDelta {
+            handle: t.handle,
+            dx,
+            dy,
+            dz,
+            drot,
+            dvx,
+            dvy,
+            dvz,
+            last_intent_seq,
+        })
+    }
+
+    fn transform(&self, handle: u32) -> HandleTransform {
+        let [x, y, z, rotation_y, vx, vy, vz] = self.values;
+        HandleTransform {
+            handle,
+            x,
+            y,
+            z,
+            rotation_y,
+            vx,
+            vy,
+            vz,
+            dt: self.dt,
+            last_intent_seq: self.last_intent_seq,
+        }
+    }
+}
+
+/// The reconstruction both sides agree on.
+fn apply_steps(value: f32, steps: i32) -> f32 {
+    value + steps as f32 * TRANSFORM_DELTA_QUANTUM
+}
+
+/// Server-side batch encoder.
+#[derive(Debug, Default)]
+pub struct TransformEncoder {
+    keyframe_interval: u64,
+    seq: u64,
+    baselines: HashMap<u32, Baseline>,
+}
+
+impl TransformEncoder {
+    /// `keyframe_interval = 0` leaves batches untouched (no `seq`, no
+    /// deltas); `1` numbers them but sends every one as a keyframe.
+    pub fn new(keyframe_interval: u32) -> Self {
+        Self {
+            keyframe_interval: keyframe_interval as u64,
+            ..Self::default()
+        }
+    }
+
+    /// `seq` of the last encoded batch (0 before the first).
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Number and delta-encode a batch of full transforms in place.  Empty
+    /// batches aren't published, so they don't use up a `seq`.
+    pub fn encode(&mut self, batch: &mut EntityTransformBatch) {
+        if self.keyframe_interval == 0 || batch.is_empty() {
+            return;
+        }
+        self.seq += 1;
+        batch.seq = Some(self.seq);
+        batch.keyframe = (self.seq - 1).is_multiple_of(self.keyframe_interval);
+        if batch.keyframe {
+            // Also forgets handles that have left since the last keyframe.
+            self.baselines.clear();
+            self.baselines.extend(
+                batch
+                    .transforms
+                    .iter()
+                    .map(|t| (t.handle, Baseline::new(t))),
+            );
+            return;
+        }
+
+        let baselines = &mut self.baselines;
+        let deltas = &mut batch.deltas;
+        batch.transforms.retain(
+            |t| match baselines.get_mut(&t.handle).and_then(|b| b.delta(t)) {
+                Some(delta) => {
+                    deltas.push(delta);
+                    false
+                }
+                None => {
+                    baselines.insert(t.handle, Baseline::new(t));
+                    true
+                }
+            },
+        );
+    }
+}
+
+/// Client-side reassembly of encoded batches.
+#[derive(Debug, Default)]
+pub struct TransformDecoder {
+    last_seq: Option<u64>,
+    baselines: HashMap<u32, Baseline>,
+}
+
+impl TransformDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Full transforms of every entity in `batch` that can be
+    /// reconstructed: deltas for handles without a baseline (after a
+    /// `seq` gap, or before the first keyframe) are skipped.
+    pub fn apply(&mut self, batch: &EntityTransformBatch) -> Vec<HandleTransform> {
+        let Some(seq) = batch.seq else {
+            return batch.transforms.clone();
+        };
+        if batch.keyframe || self.last_seq.map(|last| last + 1) != Some(seq) {
+            self.baselines.clear();
+        }
+        self.last_seq = Some(seq);
+
+        let mut out = Vec::with_capacity(batch.transforms.len() + batch.deltas.len());
+        for t in &batch.transforms {
+            self.baselines.insert(t.handle, Baseline::new(t));
+            out.push(t.clone());
+        }
+        for d in &batch.deltas {
+            let Some(base) = self.baselines.get_mut(&d.handle) else {
+                continue;
+            };
+            let steps = [d.dx, d.dy, d.dz, d.drot, d.dvx, d.dvy, d.dvz];
+            for (value, steps) in base.values.iter_mut().zip(steps) {
+                *value = apply_steps(*value, steps);
+            }
+            if d.last_intent_seq.is_some() {
+                base.last_intent_seq = d.last_intent_seq;
+            }
+            out.push(base.transform(d.handle));
+        }
+        out
+    }
+}
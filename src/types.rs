@@ -1,8 +1,10 @@
 //! Core world types shared across all modules.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 
+use crate::protocol::{StructureSpawned, UNIT_SCALE};
 use janet_operations::physics::types::ColliderShape;
 
 // ---------------------------------------------------------------------------
@@ -26,6 +28,34 @@ impl Vec3 {
     }
 }
 
+/// Where a ray first meets the terrain (see `TerrainSource::raycast`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RayHit {
+    pub position: Vec3,
+    /// Unit surface normal at the hit (`z` up).
+    pub normal: Vec3,
+    /// Distance travelled along the ray.
+    pub distance: f32,
+}
+
+/// Terrain summary over a rectangle (see
+/// `TerrainSource::sample_region_stats`).  Slopes are gradient magnitudes
+/// (rise over run), as in [`MaterialRules`].
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct RegionStats {
+    /// Grid points sampled.
+    pub samples: usize,
+    pub min_height: f32,
+    pub max_height: f32,
+    pub mean_height: f32,
+    pub max_slope: f32,
+    pub mean_slope: f32,
+    /// Share of samples (`0..=1`) under standing water.
+    pub water_fraction: f32,
+    /// Share of samples (`0..=1`) in a terrain hole.
+    pub hole_fraction: f32,
+}
+
 impl std::fmt::Display for Vec3 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({:.2}, {:.2}, {:.2})", self.x, self.y, self.z)
@@ -55,6 +85,114 @@ impl std::fmt::Display for CellCoord {
     }
 }
 
+/// Rectangle of cell columns, bounds inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellRegion {
+    pub min_cx: i32,
+    pub min_cy: i32,
+    pub max_cx: i32,
+    pub max_cy: i32,
+}
+
+impl CellRegion {
+    /// The square of columns within Chebyshev `radius` of `(cx, cy)`.
+    pub fn around(cx: i32, cy: i32, radius: i32) -> Self {
+        Self {
+            min_cx: cx - radius,
+            min_cy: cy - radius,
+            max_cx: cx + radius,
+            max_cy: cy + radius,
+        }
+    }
+
+    pub fn contains(&self, cx: i32, cy: i32) -> bool {
+        (self.min_cx..=self.max_cx).contains(&cx) && (self.min_cy..=self.max_cy).contains(&cy)
+    }
+
+    /// Chebyshev distance in cells from `(cx, cy)` to the nearest column
+    /// of the region (`0` inside).
+    pub fn distance_outside(&self, cx: i32, cy: i32) -> i32 {
+        (self.min_cx - cx)
+            .max(cx - self.max_cx)
+            .max(self.min_cy - cy)
+            .max(cy - self.max_cy)
+            .max(0)
+    }
+
+    /// Every column, row by row (none if min > max).
+    pub fn columns(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (min_cx, max_cx) = (self.min_cx, self.max_cx);
+        (self.min_cy..=self.max_cy).flat_map(move |cy| (min_cx..=max_cx).map(move |cx| (cx, cy)))
+    }
+}
+
+/// Session-wide reshaping of the procedural terrain's noise layers, so
+/// one world file can give gentle plains or dramatic mountains.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoiseParams {
+    /// Multiplier on every layer's weight: relief before generated heights
+    /// are clamped to `0..=1`.
+    pub amplitude: f64,
+    /// Multiplier on every layer's frequency (higher = smaller hills).
+    pub frequency: f64,
+    /// Layers to sum.  Fewer drops the finest ones; more adds octaves at
+    /// twice the frequency and half the weight of the one before (`0` =
+    /// as configured).
+    pub octaves: u32,
+    /// World units per unit of generated height.
+    pub vertical_exaggeration: f32,
+}
+
+impl Default for NoiseParams {
+    fn default() -> Self {
+        Self {
+            amplitude: 1.0,
+            frequency: 1.0,
+            octaves: 0,
+            vertical_exaggeration: 1.0,
+        }
+    }
+}
+
+/// What clients show beyond the [`WorldBounds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeFill {
+    /// Open water out to the horizon.
+    #[default]
+    Ocean,
+    /// A sheer wall.
+    Cliff,
+}
+
+/// Extent of a finite world.
+///
+/// Columns outside `cells` are not streamed, except the ring right around
+/// them: those are announced as edge chunks (`ChunkActivated::edge`) so
+/// clients can draw the fill, and get a solid barrier collider covering
+/// the whole cell.  Moves that would cross the bounds are blocked.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorldBounds {
+    /// Playable cell columns.
+    pub cells: CellRegion,
+    #[serde(default)]
+    pub fill: EdgeFill,
+    /// Height of the ocean surface or the cliff top.
+    #[serde(default)]
+    pub height: f32,
+}
+
+/// Outcome of `WorldService::pregenerate`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PregenerateReport {
+    /// Chunks (column and LOD) asked for.
+    pub chunks: usize,
+    /// Of those, chunks that weren't cached yet and were generated.
+    pub generated: usize,
+    pub elapsed_ms: f32,
+}
+
 // ---------------------------------------------------------------------------
 // World objects
 // ---------------------------------------------------------------------------
@@ -68,6 +206,31 @@ pub struct WorldObject {
     pub properties: HashMap<String, serde_json::Value>,
 }
 
+impl WorldObject {
+    /// The spawn event clients instantiate this object from (`kind` is the
+    /// `type_id`, `properties` the metadata).
+    pub fn to_spawned(&self) -> StructureSpawned {
+        StructureSpawned {
+            structure_id: self.id.clone(),
+            type_id: self.kind.clone(),
+            x: self.position.x,
+            y: self.position.y,
+            z: self.position.z,
+            rotation_y: 0.0,
+            pitch: 0.0,
+            roll: 0.0,
+            scale: UNIT_SCALE,
+            state: None,
+            metadata: serde_json::Value::Object(
+                self.properties
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Stats & config
 // ---------------------------------------------------------------------------
@@ -78,6 +241,342 @@ pub struct WorldStats {
     pub total_objects: usize,
     pub tracked_participants: usize,
     pub total_ticks: u64,
+    /// Cells waiting for background chunk generation.
+    #[serde(default)]
+    pub pending_chunks: usize,
+    /// Terrain chunk cache counters (zero for backends without one).
+    #[serde(default)]
+    pub chunk_cache: ChunkCacheStats,
+    /// Participants currently flagged AFK (streaming downgraded).
+    #[serde(default)]
+    pub afk_participants: usize,
+    /// Participants that left and can still resume their entity.
+    #[serde(default)]
+    pub departed_participants: usize,
+    /// Events produced per tick over the recent window, by kind.
+    #[serde(default)]
+    pub fanout: FanoutStats,
+    /// Intents and commands rejected since startup.
+    #[serde(default)]
+    pub drops: DropStats,
+    /// Ambient entities currently spawned.
+    #[serde(default)]
+    pub population: PopulationStats,
+}
+
+/// Why an intent or command was dropped instead of applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DropReason {
+    /// A queue between the transport and the service was full.
+    ChannelFull,
+    /// The sender exceeded a per-entity rate limit.
+    RateLimited,
+    /// Malformed payload, unknown entity or out-of-range value.
+    Invalid,
+    /// Sent to a subject the service doesn't handle.
+    UnknownSubject,
+}
+
+impl DropReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DropReason::ChannelFull => "channel_full",
+            DropReason::RateLimited => "rate_limited",
+            DropReason::Invalid => "invalid",
+            DropReason::UnknownSubject => "unknown_subject",
+        }
+    }
+}
+
+/// Dropped intents and commands, so "my clicks do nothing" reports can be
+/// traced to a cause.  Counts are totals since startup.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DropStats {
+    pub total: u64,
+    pub by_reason: BTreeMap<DropReason, u64>,
+    /// By intent / command kind (`action.move`, `emote`, …).
+    pub by_kind: BTreeMap<String, u64>,
+    /// By sending participant, for the first
+    /// [`DROP_TRACKED_PARTICIPANTS`](crate::metrics::DROP_TRACKED_PARTICIPANTS)
+    /// senders; later ones are counted under `"*"`.
+    pub by_participant: BTreeMap<String, u64>,
+}
+
+/// Distribution of one per-tick count over the rolling window.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct HistogramSummary {
+    /// Count in the most recent tick.
+    pub last: u32,
+    pub mean: f32,
+    pub p50: u32,
+    pub p95: u32,
+    pub max: u32,
+    /// Sum since startup (not just the window).
+    pub total: u64,
+}
+
+/// Per-tick event fan-out: how many of each event a tick produced.
+///
+/// Streaming churn regressions (cells flapping in and out, transforms for
+/// idle entities) show up here as a jump in `p95` / `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct FanoutStats {
+    /// Ticks covered by the histograms (up to the window size).
+    pub window_ticks: usize,
+    pub activated: HistogramSummary,
+    pub deactivated: HistogramSummary,
+    pub chunk_voxels: HistogramSummary,
+    pub chunk_data: HistogramSummary,
+    /// Entity transforms, per-entity or batched.
+    pub transforms: HistogramSummary,
+    /// Newly tracked entities (handle bindings).
+    pub spawns: HistogramSummary,
+    pub environment: HistogramSummary,
+    /// Proximity enters plus exits.
+    pub proximity: HistogramSummary,
+    pub terrain_modified: HistogramSummary,
+    pub emotes: HistogramSummary,
+    pub collisions: HistogramSummary,
+    pub nav_invalidated: HistogramSummary,
+    pub entity_meta: HistogramSummary,
+    pub structure_interest: HistogramSummary,
+    pub handoffs: HistogramSummary,
+    /// Every event above, summed.
+    pub total: HistogramSummary,
+}
+
+/// Ambient entities alive now (see [`population`](crate::population)).
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PopulationStats {
+    pub total: usize,
+    pub by_biome: BTreeMap<String, usize>,
+    pub by_archetype: BTreeMap<String, usize>,
+}
+
+/// Terrain chunk cache counters since startup, plus current occupancy.
+///
+/// The generation figures cover the whole miss path (disk load or noise,
+/// erosion, edits, stitching and splat weights), which is what
+/// `base_resolution` and the activation radius trade against.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ChunkCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// `hits / (hits + misses)`, `0` before the first lookup.
+    #[serde(default)]
+    pub hit_rate: f32,
+    /// Chunks dropped to stay within the budget or by distance eviction.
+    pub evictions: u64,
+    /// Chunks resident in the cache.
+    pub chunks: usize,
+    /// Estimated heap size of the resident chunks.
+    pub bytes: usize,
+    /// Mean time to build one chunk on a miss.
+    #[serde(default)]
+    pub generation_ms_mean: f32,
+    /// Slowest chunk build since startup.
+    #[serde(default)]
+    pub generation_ms_max: f32,
+    /// Builds that took longer than the terrain's slow-generation threshold
+    /// (each one is logged).
+    #[serde(default)]
+    pub slow_generations: u64,
+}
+
+/// Parameters of the terrain hydrology pass (rivers and lakes).
+///
+/// Shared with clients in `ChunkActivated` so they can carve the same
+/// channels and place the same water surfaces locally.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HydrologyConfig {
+    /// Lake surface height: terrain below it is under water.
+    pub water_table: f32,
+    /// Frequency of the river network noise (lower = longer rivers).
+    pub river_scale: f32,
+    /// River half-width as a fraction of the noise range (`0..0.5`).
+    pub river_width: f32,
+    /// How far river beds are cut below the surrounding terrain.
+    pub river_depth: f32,
+}
+
+impl Default for HydrologyConfig {
+    fn default() -> Self {
+        Self {
+            water_table: 0.18,
+            river_scale: 0.01,
+            river_width: 0.03,
+            river_depth: 0.12,
+        }
+    }
+}
+
+/// Rectangle of world space generated from its own seed (e.g. a
+/// hand-tuned starting island inside a procedural ocean).
+///
+/// Regions apply per terrain chunk: a chunk uses the first region that
+/// contains its centre, so every chunk has exactly one effective seed and
+/// clients can regenerate it from `ChunkActivated.seed` alone.  Terrain is
+/// not blended across a region border.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SeedRegion {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+    pub seed: u64,
+}
+
+impl SeedRegion {
+    /// `true` if `(x, y)` lies inside (min inclusive, max exclusive).
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        (self.min_x..self.max_x).contains(&x) && (self.min_y..self.max_y).contains(&y)
+    }
+}
+
+/// One super-region assignment in a [`ShardConfig`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardRegion {
+    /// Super-region coordinate (`floor(x / region_size)`, same for y).
+    pub rx: i32,
+    pub ry: i32,
+    pub shard_id: String,
+}
+
+/// Region-based sharding across several world-service processes.
+///
+/// Every shard is started with the same `region_size` and `regions` and its
+/// own `shard_id`.  Participants standing in a super-region assigned to
+/// another shard are handed off to it; unassigned super-regions never
+/// trigger a handoff.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShardConfig {
+    /// This instance's shard id (also its subject namespace).
+    pub shard_id: String,
+    /// Side length of a super-region in world units.
+    pub region_size: f32,
+    pub regions: Vec<ShardRegion>,
+    /// Seconds to wait for the target shard to acknowledge a handoff before
+    /// keeping the participant and offering it again.
+    #[serde(default = "default_handoff_timeout_s")]
+    pub handoff_timeout_s: f32,
+}
+
+/// Streaming downgrade for idle participants.
+///
+/// A participant that sends no movement intent for `timeout_s` is flagged
+/// `afk` (in `world.entity.meta`), streamed with the smaller
+/// `activation_radius`, and sent one transform every `transform_interval_s`
+/// instead of every tick.  Its next intent restores full streaming.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AfkConfig {
+    pub timeout_s: f32,
+    /// Streaming radius (cells) while idle; capped at the normal radius.
+    pub activation_radius: i32,
+    pub transform_interval_s: f32,
+}
+
+impl Default for AfkConfig {
+    fn default() -> Self {
+        Self {
+            timeout_s: 300.0,
+            activation_radius: 2,
+            transform_interval_s: 1.0,
+        }
+    }
+}
+
+/// A structure type participants may place (`action.place_structure`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildableStructure {
+    pub collider: ColliderShape,
+    /// Steepest ground (rise over run) it may stand on; flooded ground,
+    /// holes and ground below the sea are refused regardless.  `None` =
+    /// any slope.
+    #[serde(default)]
+    pub max_slope: Option<f32>,
+}
+
+/// Rules turning height and slope into terrain material weights.
+///
+/// Shared with clients in `ChunkActivated`; clients call
+/// [`MaterialRules::weights`] per vertex (slope from the same finite
+/// differences as `HeightChunk::compute_materials`) instead of inventing
+/// their own texturing rules.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaterialRules {
+    /// Sand below this height (beaches, lake beds).
+    pub sand_max: f32,
+    /// Snow above this height.
+    pub snow_min: f32,
+    /// Half-width of the height band over which sand/snow blend into grass.
+    pub blend: f32,
+    /// Slope (height per world unit) at which rock starts to show.
+    pub rock_slope_start: f32,
+    /// Slope at which rock fully covers the ground.
+    pub rock_slope_full: f32,
+}
+
+impl Default for MaterialRules {
+    fn default() -> Self {
+        Self {
+            sand_max: 0.28,
+            snow_min: 0.84,
+            blend: 0.03,
+            rock_slope_start: 0.03,
+            rock_slope_full: 0.06,
+        }
+    }
+}
+
+/// Per-vertex splat weights; the four always sum to `1`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct MaterialWeights {
+    pub grass: f32,
+    pub rock: f32,
+    pub sand: f32,
+    pub snow: f32,
+}
+
+impl MaterialRules {
+    /// Weights for a vertex at `height` with gradient magnitude `slope`.
+    ///
+    /// Height picks sand / grass / snow (smooth bands of `±blend`), then rock
+    /// takes over the steep share and the rest is scaled down to match.
+    pub fn weights(&self, height: f32, slope: f32) -> MaterialWeights {
+        let sand = 1.0
+            - smooth_band(
+                self.sand_max - self.blend,
+                self.sand_max + self.blend,
+                height,
+            );
+        let snow = smooth_band(
+            self.snow_min - self.blend,
+            self.snow_min + self.blend,
+            height,
+        );
+        let grass = (1.0 - sand - snow).max(0.0);
+        let rock = smooth_band(self.rock_slope_start, self.rock_slope_full, slope);
+
+        let scale = (1.0 - rock) / (grass + sand + snow).max(f32::EPSILON);
+        MaterialWeights {
+            grass: grass * scale,
+            rock,
+            sand: sand * scale,
+            snow: snow * scale,
+        }
+    }
+}
+
+/// Smoothstep from `0` at `lo` to `1` at `hi` (a step when `lo >= hi`).
+fn smooth_band(lo: f32, hi: f32, v: f32) -> f32 {
+    if hi <= lo {
+        return if v >= hi { 1.0 } else { 0.0 };
+    }
+    let t = ((v - lo) / (hi - lo)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,10 +589,227 @@ pub struct WorldServiceConfig {
     pub world_seed: u64,
     /// Terrain tile size in world-space metres.
     pub tile_size_m: f32,
-    /// Density of tree objects per cell (future use).
+    /// Chance (`0..=1`) that each `tile_size_m` tile of an activating cell
+    /// holds a tree or rock (see `vegetation`).  `0` = bare terrain.
     pub tree_density: f32,
     /// Physics integration step size in seconds.
     pub physics_dt: f32,
+    /// Length of a full in-game day in seconds.
+    pub day_length_s: f32,
+    /// Minimum seconds between periodic `world.environment` broadcasts.
+    pub environment_interval_s: f32,
+    /// Distance at which two participants are reported as near each other
+    /// (`world.proximity.*`).  `0` disables proximity events.
+    pub proximity_radius: f32,
+    /// Minimum seconds between enter/exit transitions for the same pair.
+    pub proximity_cooldown_s: f32,
+    /// Where admin `save` / `checkpoint` write world snapshots (disabled
+    /// when unset).
+    #[serde(default)]
+    pub checkpoint_dir: Option<PathBuf>,
+    /// Where named `world.cmd.terrain.export` heightmaps are written
+    /// (disabled when unset).
+    #[serde(default)]
+    pub export_dir: Option<PathBuf>,
+    /// Background chunk generation threads.  `0` generates synchronously
+    /// inside the tick (deterministic; used by tests and tools).
+    #[serde(default)]
+    pub generation_workers: usize,
+    /// Publish one `world.entity.transforms` batch per tick (entities by
+    /// numeric handle, protocol version 3) instead of one
+    /// `world.entity.transform` per entity.
+    #[serde(default)]
+    pub handle_transforms: bool,
+    /// Delta-encode transform batches (protocol version 4), sending every
+    /// transform in full once per this many batches.  `0` = full
+    /// transforms only.  Needs `handle_transforms`.
+    #[serde(default)]
+    pub transform_keyframe_interval: u32,
+    /// Seconds between ambient population passes (`0` = never).
+    #[serde(default = "default_population_interval_s")]
+    pub population_interval_s: f32,
+    /// Seconds a participant's entity outlives `world.participant.leave`
+    /// so a reconnect carrying its resume token can reattach to it (`0` =
+    /// leaving removes the entity immediately).
+    #[serde(default = "default_resume_grace_s")]
+    pub resume_grace_s: f32,
+    /// Global ocean surface height.  Participants can't walk into ground
+    /// below it, and chunks carry the coastline where terrain crosses it.
+    /// `None` = no sea.
+    #[serde(default)]
+    pub sea_level: Option<f32>,
+    /// Rectangles generated from their own seed instead of `world_seed`
+    /// (first match wins).  Must equal the terrain's `seed_regions`.
+    #[serde(default)]
+    pub seed_regions: Vec<SeedRegion>,
+    /// `[x, y]` where participants usually enter the world; terrain around
+    /// them can be generated at startup (see
+    /// `WorldService::pregenerate_spawn_points`).
+    #[serde(default)]
+    pub spawn_points: Vec<[f32; 2]>,
+    /// Finite world extent (`None` = unbounded).
+    #[serde(default)]
+    pub world_bounds: Option<WorldBounds>,
+    /// Noise reshaping the terrain was built with; must match the
+    /// terrain's.
+    #[serde(default)]
+    pub noise: NoiseParams,
+    /// Super-region ownership when running as one of several shards
+    /// (`None` = this process owns the whole world).
+    #[serde(default)]
+    pub shard: Option<ShardConfig>,
+    /// Idle-participant downgrade (`None` = every participant streams at
+    /// full rate regardless of activity).
+    #[serde(default)]
+    pub afk: Option<AfkConfig>,
+    /// Collider per entity archetype, at scale 1.  Bounding radii for
+    /// spawn messages, proximity and interaction range derive from it;
+    /// unlisted archetypes are treated as points.
+    #[serde(default = "default_archetype_colliders")]
+    pub archetype_colliders: HashMap<String, ColliderShape>,
+    /// Distance within which participants receive an entity's
+    /// `world.entity.emote`.
+    #[serde(default = "default_emote_range")]
+    pub emote_range: f32,
+    /// Minimum seconds between two emotes of the same entity.
+    #[serde(default = "default_emote_interval_s")]
+    pub emote_interval_s: f32,
+    /// Structure types participants may place, by `type_id` (empty =
+    /// building disabled).
+    #[serde(default)]
+    pub buildables: HashMap<String, BuildableStructure>,
+    /// Furthest a participant may place a structure from itself.
+    #[serde(default = "default_build_reach")]
+    pub build_reach: f32,
+    /// Furthest a participant may be from an interactive structure
+    /// (between bounding circles) to operate it.
+    #[serde(default = "default_interact_reach")]
+    pub interact_reach: f32,
+    /// Seconds between streaming passes (cell diffing, chunk activation,
+    /// structure interest).  Physics sync and transforms still run every
+    /// `physics_dt`; `0` streams on every tick.
+    #[serde(default)]
+    pub stream_interval_s: f32,
+    /// Follow each `world.chunk.activated` of a chunk clients can't
+    /// regenerate from its seed (see `TerrainSource::client_regenerable`)
+    /// with a `world.chunk.data` carrying its heights.
+    #[serde(default)]
+    pub send_chunk_heights: bool,
+    /// Height of a vertical streaming layer.  A column's chunk lives in the
+    /// layer of its centre height (`CellCoord::z`) and only streams while a
+    /// participant is within `vertical_activation_radius` layers of it.
+    /// `0` streams columns at any altitude (every cell has `z = 0`).
+    #[serde(default)]
+    pub vertical_cell_size: f32,
+    /// Vertical streaming radius in layers (with `vertical_cell_size`).
+    #[serde(default = "default_vertical_activation_radius")]
+    pub vertical_activation_radius: i32,
+    /// Cells around a participant that `Near` tier structures stream
+    /// within (capped at its activation radius).
+    #[serde(default = "default_structure_near_radius")]
+    pub structure_near_radius: i32,
+    /// Cells around a participant that `Detail` tier structures stream
+    /// within (capped at its activation radius).
+    #[serde(default = "default_structure_detail_radius")]
+    pub structure_detail_radius: i32,
+    /// Speed (metres per second) of a participant holding a full-length
+    /// `intent.move` direction.
+    #[serde(default = "default_max_move_speed")]
+    pub max_move_speed: f32,
+    /// Publish entity transforms and spawns and structure updates on the
+    /// subject of the interest cell they happen in
+    /// (`world.cell.{cx}.{cy}.…`) instead of world-wide, and tell each
+    /// client which cells to subscribe to (`world.interest.subjects`).
+    /// Can't be combined with `handle_transforms`.
+    #[serde(default)]
+    pub cell_subjects: bool,
+    /// Side of an interest cell in metres (with `cell_subjects`).
+    #[serde(default = "default_interest_cell_size")]
+    pub interest_cell_size: f32,
+    /// Distance an entity has to move from its last published transform
+    /// before the next one goes out.
+    #[serde(default = "default_transform_position_epsilon")]
+    pub transform_position_epsilon: f32,
+    /// Turn (radians) an entity has to make from its last published
+    /// transform before the next one goes out.
+    #[serde(default = "default_transform_rotation_epsilon")]
+    pub transform_rotation_epsilon: f32,
+    /// Seconds after which an entity that hasn't changed gets its
+    /// transform published again anyway (`0` = every tick, no change
+    /// tracking).
+    #[serde(default = "default_transform_keepalive_s")]
+    pub transform_keepalive_s: f32,
+}
+
+/// Archetype of every participant entity.
+pub const PARTICIPANT_ARCHETYPE: &str = "participant";
+
+/// Participants default to a half-metre circle.
+fn default_archetype_colliders() -> HashMap<String, ColliderShape> {
+    HashMap::from([(
+        PARTICIPANT_ARCHETYPE.to_string(),
+        ColliderShape::Circle { radius: 0.5 },
+    )])
+}
+
+fn default_population_interval_s() -> f32 {
+    10.0
+}
+
+fn default_resume_grace_s() -> f32 {
+    30.0
+}
+
+fn default_handoff_timeout_s() -> f32 {
+    2.0
+}
+
+fn default_emote_range() -> f32 {
+    30.0
+}
+
+fn default_emote_interval_s() -> f32 {
+    0.5
+}
+
+fn default_build_reach() -> f32 {
+    8.0
+}
+
+fn default_interact_reach() -> f32 {
+    3.0
+}
+
+fn default_vertical_activation_radius() -> i32 {
+    2
+}
+
+fn default_structure_near_radius() -> i32 {
+    4
+}
+
+fn default_structure_detail_radius() -> i32 {
+    1
+}
+
+fn default_max_move_speed() -> f32 {
+    5.0
+}
+
+fn default_interest_cell_size() -> f32 {
+    64.0
+}
+
+fn default_transform_position_epsilon() -> f32 {
+    0.01
+}
+
+fn default_transform_rotation_epsilon() -> f32 {
+    0.01
+}
+
+fn default_transform_keepalive_s() -> f32 {
+    1.0
 }
 
 impl Default for WorldServiceConfig {
@@ -105,6 +821,42 @@ impl Default for WorldServiceConfig {
             tile_size_m: 2.0,
             tree_density: 0.02,
             physics_dt: 1.0 / 30.0,
+            day_length_s: 1200.0,
+            environment_interval_s: 5.0,
+            proximity_radius: 10.0,
+            proximity_cooldown_s: 1.0,
+            checkpoint_dir: None,
+            export_dir: None,
+            generation_workers: 0,
+            handle_transforms: false,
+            transform_keyframe_interval: 0,
+            population_interval_s: default_population_interval_s(),
+            resume_grace_s: default_resume_grace_s(),
+            sea_level: None,
+            seed_regions: Vec::new(),
+            spawn_points: Vec::new(),
+            world_bounds: None,
+            noise: NoiseParams::default(),
+            shard: None,
+            afk: None,
+            archetype_colliders: default_archetype_colliders(),
+            emote_range: default_emote_range(),
+            emote_interval_s: default_emote_interval_s(),
+            buildables: HashMap::new(),
+            build_reach: default_build_reach(),
+            interact_reach: default_interact_reach(),
+            stream_interval_s: 0.0,
+            send_chunk_heights: false,
+            vertical_cell_size: 0.0,
+            vertical_activation_radius: default_vertical_activation_radius(),
+            structure_near_radius: default_structure_near_radius(),
+            structure_detail_radius: default_structure_detail_radius(),
+            max_move_speed: default_max_move_speed(),
+            cell_subjects: false,
+            interest_cell_size: default_interest_cell_size(),
+            transform_position_epsilon: default_transform_position_epsilon(),
+            transform_rotation_epsilon: default_transform_rotation_epsilon(),
+            transform_keepalive_s: default_transform_keepalive_s(),
         }
     }
 }
@@ -0,0 +1,642 @@
+//! Startup configuration validation.
+//!
+//! Every check runs up front and all problems are reported together, so a
+//! misconfigured server refuses to start with one actionable message instead
+//! of panicking (or silently misbehaving) the first time a bad value is used.
+//!
+//! ```ignore
+//! let mut v = ConfigValidator::new();
+//! v.check_service(&service_config);
+//! v.check_tick_rate(tick_rate_hz, service_config.physics_dt);
+//! v.check_terrain(&terrain, &service_config);
+//! v.check_failover(&bus_config, checkpoint_dir);
+//! v.check_writable_dir("chunk_store_dir", &dir);
+//! v.finish()?;
+//! ```
+
+use crate::behaviour::Behaviour;
+use crate::bus::WorldBusConfig;
+use crate::terrain::{CacheBudget, HeightmapTerrain};
+use crate::types::{
+    AfkConfig, NoiseParams, ShardConfig, WorldServiceConfig, PARTICIPANT_ARCHETYPE,
+};
+use crate::worldgen::WorldGenConfig;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+/// Largest accepted activation radius; `(2r + 1)²` cells are streamed per
+/// participant, so anything beyond this is almost certainly a unit mix-up.
+pub const MAX_ACTIVATION_RADIUS: i32 = 64;
+
+/// Allowed relative mismatch between `physics_dt` and `1 / tick_rate_hz`.
+const TICK_RATE_TOLERANCE: f32 = 0.01;
+
+/// Beyond this the finest octaves are far below a sample spacing and only
+/// cost generation time.
+const MAX_NOISE_OCTAVES: u32 = 12;
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+/// A single invalid setting.
+#[derive(Debug, Clone, Error)]
+#[error("{key}: {message}")]
+pub struct ConfigError {
+    /// Config key the problem is attributed to (e.g. `physics_dt`).
+    pub key: String,
+    pub message: String,
+}
+
+/// Every problem found during validation.
+#[derive(Debug, Clone)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration ({} problem(s)):", self.0.len())?;
+        for e in &self.0 {
+            write!(f, "\n  - {}", e)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+// ---------------------------------------------------------------------------
+// Validator
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+pub struct ConfigValidator {
+    errors: Vec<ConfigError>,
+}
+
+impl ConfigValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn errors(&self) -> &[ConfigError] {
+        &self.errors
+    }
+
+    /// `Ok` if no check failed, otherwise every collected error.
+    pub fn finish(self) -> Result<(), ConfigErrors> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigErrors(self.errors))
+        }
+    }
+
+    fn fail(&mut self, key: &str, message: impl Into<String>) {
+        self.errors.push(ConfigError {
+            key: key.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn require_positive(&mut self, key: &str, value: f32) -> bool {
+        if value.is_finite() && value > 0.0 {
+            return true;
+        }
+        self.fail(key, format!("must be a positive number, got {}", value));
+        false
+    }
+
+    /// `value` is embedded in bus subjects, so it must be one token.
+    fn require_subject_token(&mut self, key: &str, value: &str) {
+        if value.is_empty() || value.contains(['.', '*', '>', ' ']) {
+            self.fail(
+                key,
+                format!(
+                    "must be a non-empty subject token (no '.', '*', '>' or spaces), got {:?}",
+                    value
+                ),
+            );
+        }
+    }
+
+    fn require_non_negative(&mut self, key: &str, value: f32) {
+        if !(value.is_finite() && value >= 0.0) {
+            self.fail(key, format!("must be zero or positive, got {}", value));
+        }
+    }
+
+    /// Range checks on every [`WorldServiceConfig`] field.
+    pub fn check_service(&mut self, cfg: &WorldServiceConfig) {
+        self.require_positive("cell_size", cfg.cell_size);
+        self.require_positive("tile_size_m", cfg.tile_size_m);
+        self.require_positive("physics_dt", cfg.physics_dt);
+        self.require_positive("day_length_s", cfg.day_length_s);
+        self.require_non_negative("environment_interval_s", cfg.environment_interval_s);
+        self.require_non_negative("proximity_radius", cfg.proximity_radius);
+        self.require_non_negative("proximity_cooldown_s", cfg.proximity_cooldown_s);
+        self.require_non_negative("emote_range", cfg.emote_range);
+        self.require_non_negative("emote_interval_s", cfg.emote_interval_s);
+        self.require_non_negative("build_reach", cfg.build_reach);
+        self.require_non_negative("interact_reach", cfg.interact_reach);
+        self.require_non_negative("stream_interval_s", cfg.stream_interval_s);
+        self.require_non_negative("population_interval_s", cfg.population_interval_s);
+        self.require_non_negative("resume_grace_s", cfg.resume_grace_s);
+        self.require_non_negative("vertical_cell_size", cfg.vertical_cell_size);
+        self.require_non_negative("max_move_speed", cfg.max_move_speed);
+        self.require_positive("interest_cell_size", cfg.interest_cell_size);
+        self.require_non_negative("transform_position_epsilon", cfg.transform_position_epsilon);
+        self.require_non_negative("transform_rotation_epsilon", cfg.transform_rotation_epsilon);
+        self.require_non_negative("transform_keepalive_s", cfg.transform_keepalive_s);
+        if cfg.cell_subjects && cfg.handle_transforms {
+            self.fail(
+                "cell_subjects",
+                "cell-scoped transforms can't be batched; turn off handle_transforms",
+            );
+        }
+
+        if let Some(level) = cfg.sea_level {
+            if !level.is_finite() {
+                self.fail(
+                    "sea_level",
+                    format!("must be a finite number, got {}", level),
+                );
+            }
+        }
+        for (i, r) in cfg.seed_regions.iter().enumerate() {
+            let finite = [r.min_x, r.min_y, r.max_x, r.max_y]
+                .iter()
+                .all(|v| v.is_finite());
+            if !finite || r.min_x >= r.max_x || r.min_y >= r.max_y {
+                self.fail(
+                    "seed_regions",
+                    format!(
+                        "region {} must have finite min < max, got ({}, {})..({}, {})",
+                        i, r.min_x, r.min_y, r.max_x, r.max_y
+                    ),
+                );
+            }
+        }
+        if let Some(p) = cfg
+            .spawn_points
+            .iter()
+            .find(|p| !p.iter().all(|v| v.is_finite()))
+        {
+            self.fail(
+                "spawn_points",
+                format!("must be finite, got [{}, {}]", p[0], p[1]),
+            );
+        }
+        self.check_noise(&cfg.noise);
+        if let Some(bounds) = &cfg.world_bounds {
+            let r = bounds.cells;
+            if r.min_cx > r.max_cx || r.min_cy > r.max_cy {
+                self.fail(
+                    "world_bounds",
+                    format!(
+                        "cells must have min <= max, got ({}, {})..({}, {})",
+                        r.min_cx, r.min_cy, r.max_cx, r.max_cy
+                    ),
+                );
+            }
+            if !bounds.height.is_finite() {
+                self.fail(
+                    "world_bounds.height",
+                    format!("must be a finite number, got {}", bounds.height),
+                );
+            }
+        }
+        if let Some(shard) = &cfg.shard {
+            self.check_shard(shard);
+        }
+        if let Some(afk) = &cfg.afk {
+            self.check_afk(afk);
+        }
+        for (type_id, b) in &cfg.buildables {
+            if type_id.is_empty() {
+                self.fail("buildables", "type ids must not be empty");
+            }
+            if let Some(slope) = b.max_slope {
+                if !(slope.is_finite() && slope >= 0.0) {
+                    self.fail(
+                        "buildables",
+                        format!(
+                            "'{}' max_slope must be zero or positive, got {}",
+                            type_id, slope
+                        ),
+                    );
+                }
+            }
+        }
+        if !(0.0..=1.0).contains(&cfg.tree_density) {
+            self.fail(
+                "tree_density",
+                format!("must be between 0 and 1, got {}", cfg.tree_density),
+            );
+        }
+        if !(0..=MAX_ACTIVATION_RADIUS).contains(&cfg.activation_radius) {
+            self.fail(
+                "activation_radius",
+                format!(
+                    "must be between 0 and {} cells, got {} (it is a cell count, not world units)",
+                    MAX_ACTIVATION_RADIUS, cfg.activation_radius
+                ),
+            );
+        }
+        if cfg.vertical_cell_size > 0.0
+            && !(0..=MAX_ACTIVATION_RADIUS).contains(&cfg.vertical_activation_radius)
+        {
+            self.fail(
+                "vertical_activation_radius",
+                format!(
+                    "must be between 0 and {} layers, got {}",
+                    MAX_ACTIVATION_RADIUS, cfg.vertical_activation_radius
+                ),
+            );
+        }
+        for (key, radius) in [
+            ("structure_near_radius", cfg.structure_near_radius),
+            ("structure_detail_radius", cfg.structure_detail_radius),
+        ] {
+            if radius < 0 {
+                self.fail(key, format!("must be zero or more cells, got {}", radius));
+            }
+        }
+        if cfg.transform_keyframe_interval > 0 && !cfg.handle_transforms {
+            self.fail(
+                "transform_keyframe_interval",
+                "delta encoding needs handle_transforms (batched transforms)",
+            );
+        }
+    }
+
+    fn check_noise(&mut self, noise: &NoiseParams) {
+        self.require_positive("noise.amplitude", noise.amplitude as f32);
+        self.require_positive("noise.frequency", noise.frequency as f32);
+        self.require_positive("noise.vertical_exaggeration", noise.vertical_exaggeration);
+        if noise.octaves > MAX_NOISE_OCTAVES {
+            self.fail(
+                "noise.octaves",
+                format!(
+                    "must be at most {}, got {}",
+                    MAX_NOISE_OCTAVES, noise.octaves
+                ),
+            );
+        }
+    }
+
+    fn check_afk(&mut self, afk: &AfkConfig) {
+        self.require_positive("afk.timeout_s", afk.timeout_s);
+        self.require_positive("afk.transform_interval_s", afk.transform_interval_s);
+        if !(0..=MAX_ACTIVATION_RADIUS).contains(&afk.activation_radius) {
+            self.fail(
+                "afk.activation_radius",
+                format!(
+                    "must be between 0 and {} cells, got {}",
+                    MAX_ACTIVATION_RADIUS, afk.activation_radius
+                ),
+            );
+        }
+    }
+
+    fn check_shard(&mut self, shard: &ShardConfig) {
+        self.require_subject_token("shard.shard_id", &shard.shard_id);
+        self.require_positive("shard.region_size", shard.region_size);
+        self.require_positive("shard.handoff_timeout_s", shard.handoff_timeout_s);
+        let mut seen = HashSet::new();
+        for r in &shard.regions {
+            if !seen.insert((r.rx, r.ry)) {
+                self.fail(
+                    "shard.regions",
+                    format!(
+                        "super-region ({}, {}) is assigned more than once",
+                        r.rx, r.ry
+                    ),
+                );
+            }
+        }
+    }
+
+    /// The tick loop and the physics step must agree, otherwise simulated
+    /// time drifts from wall-clock time.
+    pub fn check_tick_rate(&mut self, tick_rate_hz: f32, physics_dt: f32) {
+        if !self.require_positive("tick_rate_hz", tick_rate_hz) || !physics_dt.is_finite() {
+            return;
+        }
+        let expected = 1.0 / tick_rate_hz;
+        if ((physics_dt - expected) / expected).abs() > TICK_RATE_TOLERANCE {
+            self.fail(
+                "physics_dt",
+                format!(
+                    "{}s does not match tick_rate_hz {} (expected {:.5}s); set physics_dt = 1 / tick_rate_hz",
+                    physics_dt, tick_rate_hz, expected
+                ),
+            );
+        }
+    }
+
+    /// Terrain chunking and LOD bands, and their fit with the streaming
+    /// window.
+    pub fn check_terrain(&mut self, terrain: &HeightmapTerrain, cfg: &WorldServiceConfig) {
+        let chunk_ok = self.require_positive("chunk_size", terrain.chunk_size);
+        if terrain.base_resolution < 4 {
+            self.fail(
+                "base_resolution",
+                format!("must be at least 4, got {}", terrain.base_resolution),
+            );
+        }
+
+        let window = (2 * cfg.activation_radius + 1) as f32 * cfg.cell_size;
+        if chunk_ok && cfg.cell_size > 0.0 && window < terrain.chunk_size {
+            self.fail(
+                "activation_radius",
+                format!(
+                    "streaming window of {} world units is smaller than one terrain chunk ({}); raise activation_radius or lower chunk_size",
+                    window, terrain.chunk_size
+                ),
+            );
+        }
+
+        if terrain.seed_regions != cfg.seed_regions {
+            self.fail(
+                "seed_regions",
+                "terrain and service config disagree; clients would be sent seeds the server does not generate with",
+            );
+        }
+
+        if terrain.height_scale != cfg.noise.vertical_exaggeration {
+            self.fail(
+                "noise.vertical_exaggeration",
+                format!(
+                    "terrain is scaled by {} but the service config says {}",
+                    terrain.height_scale, cfg.noise.vertical_exaggeration
+                ),
+            );
+        }
+
+        let bands = &terrain.lod_bands;
+        if let Some((i, band)) = bands
+            .iter()
+            .enumerate()
+            .find(|(_, b)| !(b.is_finite() && **b > 0.0))
+        {
+            self.fail(
+                "lod_bands",
+                format!("band {} must be a positive distance, got {}", i, band),
+            );
+        }
+        if let Some(i) = bands.windows(2).position(|w| w[0] >= w[1]) {
+            self.fail(
+                "lod_bands",
+                format!(
+                    "must be strictly increasing, but band {} ({}) >= band {} ({})",
+                    i,
+                    bands[i],
+                    i + 1,
+                    bands[i + 1]
+                ),
+            );
+        }
+        if bands.len() >= u8::BITS as usize || (terrain.base_resolution >> bands.len()) < 4 {
+            self.fail(
+                "lod_bands",
+                format!(
+                    "{} bands would reduce base_resolution {} below 4 samples at the coarsest LOD; drop bands or raise base_resolution",
+                    bands.len(),
+                    terrain.base_resolution
+                ),
+            );
+        }
+
+        if let Some(erosion) = &terrain.erosion {
+            self.require_non_negative("erosion.talus", erosion.talus);
+            self.require_non_negative("erosion.hydraulic_rate", erosion.hydraulic_rate);
+            if !(0.0..=0.25).contains(&erosion.thermal_rate) {
+                self.fail(
+                    "erosion.thermal_rate",
+                    format!("must be between 0 and 0.25, got {}", erosion.thermal_rate),
+                );
+            }
+            if !(0.0..=1.0).contains(&erosion.deposition) {
+                self.fail(
+                    "erosion.deposition",
+                    format!("must be between 0 and 1, got {}", erosion.deposition),
+                );
+            }
+        }
+
+        if let Some(CacheBudget::Chunks(0) | CacheBudget::Bytes(0)) = terrain.cache_budget {
+            self.fail(
+                "chunk_cache_budget",
+                "must be greater than zero (leave unset for an unbounded cache)",
+            );
+        }
+
+        let rules = &terrain.materials;
+        self.require_non_negative("materials.blend", rules.blend);
+        if rules.sand_max >= rules.snow_min {
+            self.fail(
+                "materials.sand_max",
+                format!(
+                    "must be below materials.snow_min ({}), got {}",
+                    rules.snow_min, rules.sand_max
+                ),
+            );
+        }
+        if rules.rock_slope_start >= rules.rock_slope_full {
+            self.fail(
+                "materials.rock_slope_start",
+                format!(
+                    "must be below materials.rock_slope_full ({}), got {}",
+                    rules.rock_slope_full, rules.rock_slope_start
+                ),
+            );
+        }
+
+        if let Some(hydrology) = &terrain.hydrology {
+            self.require_positive("hydrology.river_scale", hydrology.river_scale);
+            self.require_non_negative("hydrology.river_depth", hydrology.river_depth);
+            if !(0.0..=1.0).contains(&hydrology.water_table) {
+                self.fail(
+                    "hydrology.water_table",
+                    format!("must be between 0 and 1, got {}", hydrology.water_table),
+                );
+            }
+            if !(0.0..0.5).contains(&hydrology.river_width) {
+                self.fail(
+                    "hydrology.river_width",
+                    format!(
+                        "must be at least 0 and below 0.5, got {}",
+                        hydrology.river_width
+                    ),
+                );
+            }
+        }
+
+        if terrain.noise.is_empty() {
+            self.fail("noise", "needs at least one layer");
+        }
+        for (i, layer) in terrain.noise.iter().enumerate() {
+            if !(layer.frequency.is_finite() && layer.frequency > 0.0) {
+                self.fail(
+                    "noise",
+                    format!(
+                        "layer {} frequency must be positive, got {}",
+                        i, layer.frequency
+                    ),
+                );
+            }
+            if !layer.weight.is_finite() {
+                self.fail(
+                    "noise",
+                    format!("layer {} weight must be finite, got {}", i, layer.weight),
+                );
+            }
+        }
+        let biomes = &terrain.biomes;
+        if biomes.is_empty() {
+            self.fail("biomes", "needs at least one band");
+        }
+        if let Some(i) = biomes.windows(2).position(|w| w[0].below >= w[1].below) {
+            self.fail(
+                "biomes",
+                format!(
+                    "must be strictly increasing, but '{}' ({}) >= '{}' ({})",
+                    biomes[i].name,
+                    biomes[i].below,
+                    biomes[i + 1].name,
+                    biomes[i + 1].below
+                ),
+            );
+        }
+    }
+
+    /// Structure scatter table of a [`WorldGenConfig`] (its terrain settings
+    /// are covered by [`check_terrain`](Self::check_terrain)).
+    pub fn check_worldgen(&mut self, gen: &WorldGenConfig) {
+        if let Some(level) = gen.sea_level {
+            if !level.is_finite() {
+                self.fail(
+                    "sea_level",
+                    format!("must be a finite number, got {}", level),
+                );
+            }
+        }
+        self.require_non_negative("structure_radius", gen.structure_radius as f32);
+        let known: HashSet<&str> = gen.biomes.iter().map(|b| b.name.as_str()).collect();
+        for s in &gen.structures {
+            let key = format!("structures.{}", s.type_id);
+            self.require_non_negative(&key, s.per_chunk);
+            self.require_positive(&key, s.radius);
+            if let Some(max) = s.max_slope {
+                self.require_non_negative(&key, max);
+            }
+            if let Some(biome) = s.biomes.iter().find(|b| !known.contains(b.as_str())) {
+                self.fail(&key, format!("unknown biome '{}'", biome));
+            }
+        }
+        for p in &gen.population {
+            let key = format!("population.{}", p.archetype);
+            self.require_non_negative(&key, p.per_km2);
+            if let Some(hours) = p.hours {
+                if hours.iter().any(|h| !(0.0..=24.0).contains(h)) {
+                    self.fail(
+                        &key,
+                        format!("hours must be within 0..=24, got {:?}", hours),
+                    );
+                }
+            }
+            if let Some(biome) = p.biomes.iter().find(|b| !known.contains(b.as_str())) {
+                self.fail(&key, format!("unknown biome '{}'", biome));
+            }
+        }
+        let mut spawners = HashSet::new();
+        for s in &gen.spawners {
+            let key = format!("spawners.{}", s.id);
+            if !spawners.insert(s.id.as_str()) {
+                self.fail(&key, "is defined more than once");
+            }
+            if s.id.is_empty() {
+                self.fail(&key, "id must not be empty");
+            }
+            if s.archetype.is_empty() || s.archetype == PARTICIPANT_ARCHETYPE {
+                self.fail(
+                    &key,
+                    format!("archetype must be set and not '{}'", PARTICIPANT_ARCHETYPE),
+                );
+            }
+            if !(s.x.is_finite() && s.y.is_finite()) {
+                self.fail(&key, "position must be finite");
+            }
+            self.require_non_negative(&key, s.radius);
+            self.require_non_negative(&key, s.respawn_s);
+            if s.max_population == 0 {
+                self.fail(&key, "max_population must be at least 1");
+            }
+            if !s.behaviour.as_ref().is_none_or(Behaviour::is_valid) {
+                self.fail(&key, "behaviour values must be finite and not negative");
+            }
+        }
+    }
+
+    /// The session names every subject (`world.{session}.…`).
+    pub fn check_session(&mut self, cfg: &WorldBusConfig) {
+        self.require_subject_token("session", &cfg.session);
+    }
+
+    /// How long snapshot replies are shared between late joiners, and how
+    /// large their pages get.
+    pub fn check_snapshot_cache(&mut self, cfg: &WorldBusConfig) {
+        self.require_non_negative("snapshot_cache_s", cfg.snapshot_cache_s);
+        if cfg.snapshot_page_bytes == 0 {
+            self.fail("snapshot_page_bytes", "must be at least 1");
+        }
+    }
+
+    /// Standby and mirroring settings.  Both sides of a failover pair share
+    /// the checkpoint directory, so either role needs one.
+    pub fn check_failover(&mut self, cfg: &WorldBusConfig, checkpoint_dir: Option<&Path>) {
+        let heartbeat_ok = self.require_positive("heartbeat_interval_s", cfg.heartbeat_interval_s);
+        let timeout_ok = self.require_positive("failover_timeout_s", cfg.failover_timeout_s);
+        self.require_non_negative("mirror_interval_s", cfg.mirror_interval_s);
+
+        if heartbeat_ok && timeout_ok && cfg.failover_timeout_s <= cfg.heartbeat_interval_s {
+            self.fail(
+                "failover_timeout_s",
+                format!(
+                    "{}s must be longer than heartbeat_interval_s ({}s) or the standby takes over between heartbeats",
+                    cfg.failover_timeout_s, cfg.heartbeat_interval_s
+                ),
+            );
+        }
+        if checkpoint_dir.is_none() {
+            if cfg.standby {
+                self.fail(
+                    "standby",
+                    "requires checkpoint_dir to restore the mirror from",
+                );
+            } else if cfg.mirror_interval_s > 0.0 {
+                self.fail(
+                    "mirror_interval_s",
+                    "requires checkpoint_dir to write the mirror to",
+                );
+            }
+        }
+    }
+
+    /// Create `dir` if needed and prove a file can be written inside it.
+    pub fn check_writable_dir(&mut self, key: &str, dir: &Path) {
+        let probe = dir.join(".janet-world-write-probe");
+        let result = fs::create_dir_all(dir)
+            .and_then(|()| fs::write(&probe, b"ok"))
+            .and_then(|()| fs::remove_file(&probe));
+        if let Err(e) = result {
+            self.fail(
+                key,
+                format!("directory {} is not writable: {}", dir.display(), e),
+            );
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Procedural vegetation: trees and rocks scattered over a cell as it
+//! activates, at `WorldServiceConfig::tree_density`.
+//!
+//! Every `tile_size_m` tile of the cell is a candidate spot, jittered inside
+//! the tile, that holds something with probability `tree_density`.  Spots
+//! in a wooded biome ([`TREE_BIOMES`], or anywhere on backends without
+//! biomes) no steeper than [`TREE_MAX_SLOPE`] get a tree; other spots up
+//! to [`ROCK_MAX_SLOPE`] get a rock.  Flooded spots, holes and ground below
+//! the sea stay bare.
+//!
+//! Placement is a pure function of the world seed and the cell, so a cell
+//! that deactivates and comes back gets the same ids at the same spots.
+
+use crate::terrain::TerrainSource;
+use crate::types::{Vec3, WorldObject, WorldServiceConfig};
+use crate::worldgen::unit;
+use janet_operations::physics::types::ColliderShape;
+use std::collections::HashMap;
+
+pub const TREE_KIND: &str = "props/tree";
+pub const ROCK_KIND: &str = "props/rock";
+
+/// Biomes trees grow in.
+pub const TREE_BIOMES: &[&str] = &["grass", "forest", "swamp"];
+
+/// Steepest ground (rise over run) a tree stands on.
+pub const TREE_MAX_SLOPE: f32 = 0.6;
+
+/// Steepest ground a rock rests on.
+pub const ROCK_MAX_SLOPE: f32 = 1.5;
+
+const TREE_RADIUS: f32 = 0.4;
+
+/// Trees and rocks of cell column `(cx, cy)`.  `buildable(x, y, max_slope)`
+/// decides whether a spot can hold anything at all.
+pub fn scatter(
+    config: &WorldServiceConfig,
+    terrain: &dyn TerrainSource,
+    cx: i32,
+    cy: i32,
+    buildable: impl Fn(f32, f32, f32) -> bool,
+) -> Vec<WorldObject> {
+    if config.tree_density <= 0.0 || config.tile_size_m <= 0.0 {
+        return Vec::new();
+    }
+    let tiles = (config.cell_size / config.tile_size_m).round().max(1.0) as usize;
+    let tile = config.cell_size / tiles as f32;
+    let mut out = Vec::new();
+    for i in 0..tiles * tiles {
+        let key = format!("{}:vegetation:{}:{}:{}", config.world_seed, cx, cy, i);
+        if unit(&key, 0) >= config.tree_density {
+            continue;
+        }
+        let x = cx as f32 * config.cell_size + ((i % tiles) as f32 + unit(&key, 1)) * tile;
+        let y = cy as f32 * config.cell_size + ((i / tiles) as f32 + unit(&key, 2)) * tile;
+        let biome = terrain.biome(x, y);
+        let wooded = biome.is_none_or(|b| TREE_BIOMES.contains(&b));
+
+        let (kind, radius) = if wooded && buildable(x, y, TREE_MAX_SLOPE) {
+            (TREE_KIND, TREE_RADIUS)
+        } else if buildable(x, y, ROCK_MAX_SLOPE) {
+            (ROCK_KIND, 0.3 + 0.7 * unit(&key, 3))
+        } else {
+            continue;
+        };
+        let mut properties = HashMap::new();
+        if let Some(biome) = biome {
+            properties.insert("biome".into(), serde_json::json!(biome));
+        }
+        out.push(WorldObject {
+            id: format!("vegetation:{}:{}:{}", cx, cy, i),
+            kind: kind.to_string(),
+            position: Vec3::new(x, y, terrain.height_at(x, y)),
+            collider: ColliderShape::Circle { radius },
+            properties,
+        });
+    }
+    out
+}
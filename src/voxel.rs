@@ -0,0 +1,235 @@
+//! Sparse voxel overrides for caves and overhangs.
+//!
+//! A heightmap has one surface per `(x, y)`, so it can't express a tunnel
+//! or a ledge.  [`VoxelLayer`] stores per-chunk dense blocks of override
+//! cells on top of it: [`VOXEL_INHERIT`] defers to the heightmap,
+//! [`VOXEL_AIR`] carves, and any higher value is solid with that material.
+//! Only chunks that were actually edited have a block.
+//!
+//! Every chunk block spans the layer's full vertical range
+//! (`min_z..max_z`), which keeps indexing trivial and the wire format
+//! fixed-size; [`VoxelCells`] run-length encoding makes the mostly-inherit
+//! blocks cheap to send.
+
+use crate::protocol::{ChunkVoxels, VoxelCells, VOXEL_AIR, VOXEL_INHERIT};
+use crate::types::Vec3;
+use std::collections::HashMap;
+
+/// One chunk's override cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoxelChunk {
+    /// Cells per side `[nx, ny, nz]`.
+    pub dims: [usize; 3],
+    /// Override values, indexed `(iz * ny + iy) * nx + ix`.
+    pub cells: Vec<u8>,
+}
+
+impl VoxelChunk {
+    fn new(dims: [usize; 3]) -> Self {
+        Self {
+            dims,
+            cells: vec![VOXEL_INHERIT; dims[0] * dims[1] * dims[2]],
+        }
+    }
+
+    fn index(&self, ix: usize, iy: usize, iz: usize) -> usize {
+        (iz * self.dims[1] + iy) * self.dims[0] + ix
+    }
+
+    pub fn get(&self, ix: usize, iy: usize, iz: usize) -> u8 {
+        self.cells[self.index(ix, iy, iz)]
+    }
+
+    /// `true` if every cell defers to the heightmap.
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().all(|&c| c == VOXEL_INHERIT)
+    }
+}
+
+/// World-space axis-aligned box of merged solid voxels (collider input).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoxelBox {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+pub struct VoxelLayer {
+    pub chunk_size: f32,
+    pub voxel_size: f32,
+    pub min_z: f32,
+    pub max_z: f32,
+    chunks: HashMap<(i32, i32), VoxelChunk>,
+}
+
+impl VoxelLayer {
+    /// Empty layer covering heights `min_z..max_z` with cubes of
+    /// `voxel_size` (chunk sides are rounded up to whole voxels).
+    pub fn new(chunk_size: f32, voxel_size: f32, min_z: f32, max_z: f32) -> Self {
+        Self {
+            chunk_size,
+            voxel_size,
+            min_z,
+            max_z,
+            chunks: HashMap::new(),
+        }
+    }
+
+    fn dims(&self) -> [usize; 3] {
+        let n = (self.chunk_size / self.voxel_size).ceil().max(1.0) as usize;
+        let nz = ((self.max_z - self.min_z) / self.voxel_size)
+            .ceil()
+            .max(1.0) as usize;
+        [n, n, nz]
+    }
+
+    /// Chunk and cell holding world point `(x, y, z)`, if inside the
+    /// vertical range.
+    fn locate(&self, x: f32, y: f32, z: f32) -> Option<((i32, i32), [usize; 3])> {
+        if !(self.min_z..self.max_z).contains(&z) {
+            return None;
+        }
+        let cx = (x / self.chunk_size).floor() as i32;
+        let cy = (y / self.chunk_size).floor() as i32;
+        let [nx, ny, nz] = self.dims();
+        let local = |v: f32, origin: f32, n: usize| {
+            (((v - origin) / self.voxel_size).floor() as usize).min(n - 1)
+        };
+        Some((
+            (cx, cy),
+            [
+                local(x, cx as f32 * self.chunk_size, nx),
+                local(y, cy as f32 * self.chunk_size, ny),
+                local(z, self.min_z, nz),
+            ],
+        ))
+    }
+
+    /// Override value at a world point (`VOXEL_INHERIT` where unset).
+    pub fn get(&self, x: f32, y: f32, z: f32) -> u8 {
+        self.locate(x, y, z)
+            .and_then(|(key, [ix, iy, iz])| Some(self.chunks.get(&key)?.get(ix, iy, iz)))
+            .unwrap_or(VOXEL_INHERIT)
+    }
+
+    /// Set the cell containing a world point (ignored outside the vertical
+    /// range).
+    pub fn set(&mut self, x: f32, y: f32, z: f32, value: u8) {
+        let Some((key, [ix, iy, iz])) = self.locate(x, y, z) else {
+            return;
+        };
+        let dims = self.dims();
+        let chunk = self
+            .chunks
+            .entry(key)
+            .or_insert_with(|| VoxelChunk::new(dims));
+        let i = chunk.index(ix, iy, iz);
+        chunk.cells[i] = value;
+    }
+
+    /// Set every cell whose centre lies within `radius` of `center` (use
+    /// [`VOXEL_AIR`] to dig a cave, a material id to build an overhang).
+    pub fn fill_sphere(&mut self, center: Vec3, radius: f32, value: u8) {
+        let s = self.voxel_size;
+        let steps = (radius / s).ceil() as i32;
+        let snap = |v: f32| ((v / s).floor() + 0.5) * s;
+        let (ox, oy, oz) = (snap(center.x), snap(center.y), snap(center.z));
+        for k in -steps..=steps {
+            for j in -steps..=steps {
+                for i in -steps..=steps {
+                    let (x, y, z) = (ox + i as f32 * s, oy + j as f32 * s, oz + k as f32 * s);
+                    let (dx, dy, dz) = (x - center.x, y - center.y, z - center.z);
+                    if dx * dx + dy * dy + dz * dz <= radius * radius {
+                        self.set(x, y, z, value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Override block for chunk `(cx, cy)`, if it has any non-inherit cell.
+    pub fn chunk(&self, cx: i32, cy: i32) -> Option<&VoxelChunk> {
+        self.chunks.get(&(cx, cy)).filter(|c| !c.is_empty())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.values().all(VoxelChunk::is_empty)
+    }
+
+    /// `world.chunk.voxels` payload for chunk `(cx, cy)`.
+    pub fn chunk_event(&self, cx: i32, cy: i32) -> Option<ChunkVoxels> {
+        let chunk = self.chunk(cx, cy)?;
+        Some(ChunkVoxels {
+            chunk_id: format!("{}:{}", cx, cy),
+            cx,
+            cy,
+            origin_z: self.min_z,
+            voxel_size: self.voxel_size,
+            dims: chunk.dims.map(|d| d as u32),
+            cells: VoxelCells::encode(&chunk.cells),
+        })
+    }
+
+    /// Solid cells of chunk `(cx, cy)` merged into boxes: runs along x,
+    /// then identical runs stacked along y, one layer at a time.
+    pub fn solid_boxes(&self, cx: i32, cy: i32) -> Vec<VoxelBox> {
+        let Some(chunk) = self.chunk(cx, cy) else {
+            return Vec::new();
+        };
+        let [nx, ny, nz] = chunk.dims;
+        let solid = |ix: usize, iy: usize, iz: usize| chunk.get(ix, iy, iz) > VOXEL_AIR;
+        let origin = Vec3::new(
+            cx as f32 * self.chunk_size,
+            cy as f32 * self.chunk_size,
+            self.min_z,
+        );
+        let s = self.voxel_size;
+
+        let mut boxes = Vec::new();
+        let mut used = vec![false; chunk.cells.len()];
+        for iz in 0..nz {
+            for iy in 0..ny {
+                let mut ix = 0;
+                while ix < nx {
+                    if used[chunk.index(ix, iy, iz)] || !solid(ix, iy, iz) {
+                        ix += 1;
+                        continue;
+                    }
+                    let mut x_end = ix;
+                    while x_end + 1 < nx
+                        && solid(x_end + 1, iy, iz)
+                        && !used[chunk.index(x_end + 1, iy, iz)]
+                    {
+                        x_end += 1;
+                    }
+                    let mut y_end = iy;
+                    while y_end + 1 < ny
+                        && (ix..=x_end).all(|x| {
+                            solid(x, y_end + 1, iz) && !used[chunk.index(x, y_end + 1, iz)]
+                        })
+                    {
+                        y_end += 1;
+                    }
+                    for y in iy..=y_end {
+                        for x in ix..=x_end {
+                            used[chunk.index(x, y, iz)] = true;
+                        }
+                    }
+                    boxes.push(VoxelBox {
+                        min: Vec3::new(
+                            origin.x + ix as f32 * s,
+                            origin.y + iy as f32 * s,
+                            origin.z + iz as f32 * s,
+                        ),
+                        max: Vec3::new(
+                            origin.x + (x_end + 1) as f32 * s,
+                            origin.y + (y_end + 1) as f32 * s,
+                            origin.z + (iz + 1) as f32 * s,
+                        ),
+                    });
+                    ix = x_end + 1;
+                }
+            }
+        }
+        boxes
+    }
+}
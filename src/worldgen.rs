@@ -0,0 +1,368 @@
+//! Declarative world generation settings.
+//!
+//! [`WorldGenConfig`] gathers everything a designer tunes about a world's
+//! shape — noise layers, biome bands, sea level, splat rules, hydrology and
+//! structure density — in one TOML file, so a world can be reshaped without
+//! recompiling the server.  [`HeightmapTerrain::from_config`] builds the
+//! terrain from it; [`WorldGenConfig::scatter_structures`] places the
+//! static structures.
+//!
+//! ```toml
+//! sea_level = 0.2
+//!
+//! [[noise]]
+//! weight = 0.7
+//! frequency = 0.02
+//! salt = 1
+//!
+//! [[noise]]
+//! weight = 0.3
+//! frequency = 0.12
+//! salt = 2
+//!
+//! [[biomes]]
+//! name = "water"
+//! below = 0.2
+//!
+//! [[biomes]]
+//! name = "grass"
+//! below = 1.0
+//!
+//! [[structures]]
+//! type_id = "props/tree"
+//! biomes = ["grass"]
+//! per_chunk = 2.5
+//! radius = 0.5
+//! max_slope = 0.8
+//!
+//! [[population]]
+//! archetype = "creature/wolf"
+//! biomes = ["forest"]
+//! per_km2 = 4.0
+//! hours = [20.0, 6.0]
+//!
+//! [[spawners]]
+//! id = "wolf-den"
+//! archetype = "creature/wolf"
+//! x = 120.0
+//! y = -40.0
+//! radius = 15.0
+//! max_population = 3
+//! respawn_s = 60.0
+//! behaviour = { kind = "wander", radius = 20.0, speed = 1.5 }
+//! ```
+//!
+//! Omitted keys keep the built-in generator, so an empty file reproduces the
+//! default world exactly.
+
+use crate::behaviour::Behaviour;
+use crate::structure::StructureInstance;
+use crate::terrain::{HeightmapTerrain, TerrainSource};
+use crate::types::{HydrologyConfig, MaterialRules, NoiseParams, Vec3};
+use janet_operations::physics::types::ColliderShape;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WorldGenError {
+    #[error("failed to load world generation config: {0}")]
+    Load(#[from] config::ConfigError),
+}
+
+/// One octave of value noise.  Heights are the weighted sum of every layer,
+/// clamped to `0..=1`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NoiseLayer {
+    pub weight: f64,
+    /// Noise cells per world unit (higher = finer detail).
+    pub frequency: f64,
+    /// Mixed into the seed so layers are independent.
+    pub salt: u64,
+}
+
+/// The canonical three-octave generator shared with the Python world.
+pub fn default_noise_layers() -> Vec<NoiseLayer> {
+    [
+        (0.50, 0.04, 0x1111),
+        (0.30, 0.10, 0x2222),
+        (0.20, 0.25, 0x3333),
+    ]
+    .into_iter()
+    .map(|(weight, frequency, salt)| NoiseLayer {
+        weight,
+        frequency,
+        salt,
+    })
+    .collect()
+}
+
+/// `layers` reshaped by `params` (see [`NoiseParams`]).
+pub fn apply_noise_params(layers: &[NoiseLayer], params: &NoiseParams) -> Vec<NoiseLayer> {
+    let mut layers = layers.to_vec();
+    if params.octaves > 0 {
+        let octaves = params.octaves as usize;
+        layers.truncate(octaves);
+        while let Some(&last) = layers.last().filter(|_| layers.len() < octaves) {
+            layers.push(NoiseLayer {
+                weight: last.weight * 0.5,
+                frequency: last.frequency * 2.0,
+                salt: last.salt.wrapping_add(0x1111),
+            });
+        }
+    }
+    for l in &mut layers {
+        l.weight *= params.amplitude;
+        l.frequency *= params.frequency;
+    }
+    layers
+}
+
+/// A biome covering generated heights from the previous band up to
+/// `below` (the last band also takes anything higher).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BiomeBand {
+    pub name: String,
+    pub below: f32,
+}
+
+/// The canonical terrain classes (see `environment` for their tints).
+pub fn default_biomes() -> Vec<BiomeBand> {
+    [
+        ("water", 0.18),
+        ("sand", 0.28),
+        ("swamp", 0.32),
+        ("grass", 0.58),
+        ("forest", 0.72),
+        ("rock", 0.84),
+        ("snow", 0.94),
+        ("desert", 1.0),
+    ]
+    .into_iter()
+    .map(|(name, below)| BiomeBand {
+        name: name.to_string(),
+        below,
+    })
+    .collect()
+}
+
+/// How many structures of one type to scatter per terrain chunk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructureDensity {
+    /// Asset / scene path sent to clients.
+    pub type_id: String,
+    /// Biomes the structure may stand in (empty = any).
+    #[serde(default)]
+    pub biomes: Vec<String>,
+    /// Candidate spots per chunk; a fractional part is a chance of one
+    /// more.  Candidates outside `biomes` are dropped.
+    pub per_chunk: f32,
+    /// Collider radius.
+    #[serde(default = "default_structure_radius")]
+    pub radius: f32,
+    /// Steepest ground (rise over run) the structure may stand on; spots
+    /// that are steeper, flooded or in a hole are dropped.  `None` = any.
+    #[serde(default)]
+    pub max_slope: Option<f32>,
+}
+
+fn default_structure_radius() -> f32 {
+    0.5
+}
+
+/// Ambient entities of one archetype kept stocked in active regions (see
+/// [`population`](crate::population)).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PopulationRule {
+    /// Archetype of the spawned entities (e.g. "creature/wolf").
+    pub archetype: String,
+    /// Biomes the entities live in (empty = any).
+    #[serde(default)]
+    pub biomes: Vec<String>,
+    /// Target density over the active area of those biomes.
+    pub per_km2: f32,
+    /// `[from, to)` hours on the 24-hour world-clock dial the rule applies
+    /// in, wrapping past midnight (`[20, 6]` = night only).  Outside it the
+    /// entities are despawned.  `None` = always.
+    #[serde(default)]
+    pub hours: Option<[f32; 2]>,
+}
+
+impl PopulationRule {
+    /// `true` if the rule applies at `hour` (`0..24`).
+    pub fn active_at(&self, hour: f32) -> bool {
+        match self.hours {
+            None => true,
+            Some([from, to]) if from <= to => from <= hour && hour < to,
+            Some([from, to]) => hour >= from || hour < to,
+        }
+    }
+
+    /// `true` if entities of this rule may live in `biome`.
+    pub fn allows(&self, biome: &str) -> bool {
+        self.biomes.is_empty() || self.biomes.iter().any(|b| b == biome)
+    }
+}
+
+/// Server entities of one archetype kept alive around a spot while its
+/// cell is active (see [`spawner`](crate::spawner)).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpawnerDef {
+    /// Unique name; spawned entity ids are `spawner:{id}:{serial}`.
+    pub id: String,
+    /// Archetype of the spawned entities (e.g. "vehicle/cart").
+    pub archetype: String,
+    pub x: f32,
+    pub y: f32,
+    /// Entities spawn within this distance of `(x, y)` (`0` = on the spot).
+    #[serde(default)]
+    pub radius: f32,
+    /// Most entities alive at once.
+    #[serde(default = "default_max_population")]
+    pub max_population: usize,
+    /// Seconds before an entity that went away is replaced.
+    #[serde(default)]
+    pub respawn_s: f32,
+    /// Components every spawned entity starts with.
+    #[serde(default)]
+    pub components: BTreeMap<String, serde_json::Value>,
+    /// Steering of the spawned entities.
+    #[serde(default)]
+    pub behaviour: Option<Behaviour>,
+}
+
+fn default_max_population() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldGenConfig {
+    pub seed: u64,
+    /// World-space side of a terrain chunk.
+    pub chunk_size: f32,
+    /// Samples per chunk side at LOD 0.
+    pub base_resolution: usize,
+    pub noise: Vec<NoiseLayer>,
+    /// Biome bands by generated height, ascending.
+    pub biomes: Vec<BiomeBand>,
+    /// Global ocean height (`WorldServiceConfig::sea_level`), in generated
+    /// height units like the biome bands: the server scales it by
+    /// `NoiseParams::vertical_exaggeration`.
+    pub sea_level: Option<f32>,
+    pub materials: MaterialRules,
+    pub hydrology: Option<HydrologyConfig>,
+    pub structures: Vec<StructureDensity>,
+    /// Half-width, in chunks, of the square around the origin populated
+    /// with `structures` at startup (`0` = none).  Structures are static,
+    /// so only a bounded area is scattered.
+    pub structure_radius: i32,
+    /// Ambient wildlife per biome.
+    pub population: Vec<PopulationRule>,
+    /// Entities kept alive at fixed spots.
+    pub spawners: Vec<SpawnerDef>,
+}
+
+impl Default for WorldGenConfig {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            chunk_size: 40.0,
+            base_resolution: 64,
+            noise: default_noise_layers(),
+            biomes: default_biomes(),
+            sea_level: None,
+            materials: MaterialRules::default(),
+            hydrology: None,
+            structures: Vec::new(),
+            structure_radius: 0,
+            population: Vec::new(),
+            spawners: Vec::new(),
+        }
+    }
+}
+
+impl WorldGenConfig {
+    /// Read a TOML file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WorldGenError> {
+        let source = config::File::from(path.as_ref()).format(config::FileFormat::Toml);
+        Ok(config::Config::builder()
+            .add_source(source)
+            .build()?
+            .try_deserialize()?)
+    }
+
+    /// Parse TOML text.
+    pub fn from_toml(text: &str) -> Result<Self, WorldGenError> {
+        let source = config::File::from_str(text, config::FileFormat::Toml);
+        Ok(config::Config::builder()
+            .add_source(source)
+            .build()?
+            .try_deserialize()?)
+    }
+
+    /// Structures for chunk `(cx, cy)` of `terrain`, standing on the ground.
+    ///
+    /// Placement is a pure function of the seed, the chunk and the table,
+    /// so every run scatters the same ids at the same spots.
+    pub fn scatter_structures(
+        &self,
+        terrain: &HeightmapTerrain,
+        cx: i32,
+        cy: i32,
+    ) -> Vec<StructureInstance> {
+        let mut out = Vec::new();
+        for density in &self.structures {
+            let whole = density.per_chunk.max(0.0).floor() as usize;
+            let extra = {
+                let key = format!("{}:{}:{}:{}:count", terrain.seed, density.type_id, cx, cy);
+                (unit(&key, 0) < density.per_chunk.fract()) as usize
+            };
+            for i in 0..whole + extra {
+                let key = format!("{}:{}:{}:{}:{}", terrain.seed, density.type_id, cx, cy, i);
+                let x = (cx as f32 + unit(&key, 0)) * terrain.chunk_size;
+                let y = (cy as f32 + unit(&key, 1)) * terrain.chunk_size;
+                let biome = terrain.biome_at(x, y);
+                if !density.biomes.is_empty() && !density.biomes.iter().any(|b| b == biome) {
+                    continue;
+                }
+                if density
+                    .max_slope
+                    .is_some_and(|max| !terrain.is_buildable(x, y, max))
+                {
+                    continue;
+                }
+                let mut s = StructureInstance::new(
+                    format!("{}:{}:{}:{}", density.type_id, cx, cy, i),
+                    Vec3::new(x, y, terrain.height_at(x, y)),
+                    ColliderShape::Circle {
+                        radius: density.radius,
+                    },
+                );
+                s.metadata
+                    .insert("type_id".into(), serde_json::json!(density.type_id));
+                s.metadata.insert("biome".into(), serde_json::json!(biome));
+                out.push(s);
+            }
+        }
+        out
+    }
+
+    /// [`scatter_structures`](Self::scatter_structures) over the
+    /// `structure_radius` square.
+    pub fn scatter_all(&self, terrain: &HeightmapTerrain) -> Vec<StructureInstance> {
+        let r = self.structure_radius.max(0);
+        (-r..r)
+            .flat_map(|cy| (-r..r).map(move |cx| (cx, cy)))
+            .flat_map(|(cx, cy)| self.scatter_structures(terrain, cx, cy))
+            .collect()
+    }
+}
+
+/// Uniform `0..1` value number `k` (0..8) of `key`'s hash.
+pub(crate) fn unit(key: &str, k: usize) -> f32 {
+    let digest = md5::compute(key.as_bytes());
+    let v = u16::from_le_bytes([digest.0[2 * k], digest.0[2 * k + 1]]);
+    v as f32 / 65536.0
+}
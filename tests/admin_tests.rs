@@ -0,0 +1,241 @@
+//! Admin command tests
+
+#[cfg(test)]
+mod tests {
+    use janet_operations::physics::types::ColliderShape;
+    use janet_operations::physics::{types::PhysicsRegistryConfig, PhysicsRegistry};
+    use janet_world::{
+        admin,
+        protocol::{AdminAction, CmdAdmin, StructureUpdate, Weather, WorldSnapshot},
+        scheduler::EventScheduler,
+        service::WorldService,
+        structure::{StructureInstance, World},
+        terrain::HeightmapTerrain,
+        types::{Vec3, WorldServiceConfig},
+    };
+    use parking_lot::RwLock;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    fn make_service(checkpoint_dir: Option<PathBuf>) -> WorldService {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let world = Arc::new(World::new(terrain));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            physics_dt: 1.0 / 30.0,
+            checkpoint_dir,
+            ..Default::default()
+        };
+        WorldService::new(config, physics, world)
+    }
+
+    // -----------------------------------------------------------------------
+    // Wire format
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn admin_request_uses_action_tag() {
+        let req: CmdAdmin = serde_json::from_value(serde_json::json!({
+            "action": "set_tick_rate",
+            "hz": 20.0,
+            "token": "s3cret"
+        }))
+        .expect("parse");
+        assert_eq!(req.action, AdminAction::SetTickRate { hz: 20.0 });
+        assert_eq!(req.token.as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn token_is_required_only_when_configured() {
+        assert!(admin::authorised(None, None));
+        assert!(admin::authorised(Some("a"), Some("a")));
+        assert!(!admin::authorised(Some("a"), Some("b")));
+        assert!(!admin::authorised(Some("a"), None));
+    }
+
+    // -----------------------------------------------------------------------
+    // Actions
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn stats_reply_carries_data() {
+        let mut svc = make_service(None);
+        let reply = admin::execute(&mut svc, AdminAction::Stats);
+        assert!(reply.ok);
+        assert_eq!(reply.data["tracked_participants"], 0);
+    }
+
+    #[test]
+    fn kick_removes_known_participant() {
+        let mut svc = make_service(None);
+        svc.register_participant("bob".into(), Vec3::zero());
+
+        let kick = || AdminAction::Kick {
+            participant_id: "bob".into(),
+        };
+        assert!(admin::execute(&mut svc, kick()).ok);
+        assert_eq!(svc.participant_count(), 0);
+        assert!(!admin::execute(&mut svc, kick()).ok);
+    }
+
+    #[test]
+    fn set_tick_rate_updates_physics_dt() {
+        let mut svc = make_service(None);
+        assert!(admin::execute(&mut svc, AdminAction::SetTickRate { hz: 20.0 }).ok);
+        assert!((svc.tick_rate_hz() - 20.0).abs() < 1e-3);
+        assert!(!admin::execute(&mut svc, AdminAction::SetTickRate { hz: 0.0 }).ok);
+    }
+
+    #[test]
+    fn set_timescale_scales_simulated_steps() {
+        let mut svc = make_service(None);
+        svc.register_participant("bob".into(), Vec3::zero());
+        assert!(admin::execute(&mut svc, AdminAction::SetTimescale { scale: 0.5 }).ok);
+        assert_eq!(svc.timescale(), 0.5);
+        assert!((svc.tick_rate_hz() - 30.0).abs() < 1e-3);
+
+        // 3 m/s for half of a 1/30 s tick.
+        svc.apply_move_action("bob", 3.0, 0.0, 0.0).unwrap();
+        let x = svc.participant_position("bob").unwrap().x;
+        assert!((x - 0.05).abs() < 1e-5, "{}", x);
+
+        for scale in [0.0, 100.0, f32::NAN] {
+            assert!(!admin::execute(&mut svc, AdminAction::SetTimescale { scale }).ok);
+        }
+        assert_eq!(svc.timescale(), 0.5);
+    }
+
+    #[test]
+    fn pregenerate_counts_chunks() {
+        let mut svc = make_service(None);
+        let reply = admin::execute(
+            &mut svc,
+            AdminAction::Pregenerate {
+                cx: 0,
+                cy: 0,
+                radius: 1,
+            },
+        );
+        assert_eq!(reply.data["chunks"], 9);
+    }
+
+    #[test]
+    fn checkpoint_writes_snapshot_json() {
+        let dir = std::env::temp_dir().join(format!("janet_world_ckpt_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut svc = make_service(Some(dir.clone()));
+        svc.register_participant("alice".into(), Vec3::new(1.0, 2.0, 0.0));
+
+        assert!(admin::execute(&mut svc, AdminAction::Save).ok);
+        let saved: WorldSnapshot =
+            serde_json::from_slice(&std::fs::read(dir.join("latest.json")).unwrap()).unwrap();
+        assert_eq!(saved.entities.len(), 1);
+
+        let bad = AdminAction::Checkpoint {
+            name: "../escape".into(),
+        };
+        assert!(!admin::execute(&mut svc, bad).ok);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn checkpoint_without_dir_fails_cleanly() {
+        let mut svc = make_service(None);
+        let reply = admin::execute(&mut svc, AdminAction::Save);
+        assert!(!reply.ok);
+        assert!(
+            reply.message.contains("checkpoint_dir"),
+            "{}",
+            reply.message
+        );
+    }
+
+    #[test]
+    fn triggered_event_sets_weather_and_spawns_until_ended() {
+        let mut svc = make_service(None);
+        let schedule = serde_json::from_value(serde_json::json!({ "events": [
+            { "event_id": "raid", "kind": "invasion", "x": 0, "y": 0, "weather": "storm",
+              "entities": [{ "entity_id": "raider-1", "archetype": "creature/wolf",
+                             "x": 1, "y": 2, "z": 0, "rotation_y": 0 }] },
+        ] }))
+        .unwrap();
+        svc.set_event_schedule(EventScheduler::new(schedule).unwrap());
+        let before = svc.environment().weather();
+
+        let list = admin::execute(&mut svc, AdminAction::Events);
+        assert_eq!(list.data["events"][0]["active"], false);
+
+        let trigger = AdminAction::TriggerEvent {
+            event_id: "raid".into(),
+        };
+        assert!(admin::execute(&mut svc, trigger).ok);
+        assert_eq!(svc.environment().weather(), Weather::Storm);
+        assert_eq!(svc.participant_count(), 1);
+        assert_eq!(svc.build_snapshot("s").active_events.len(), 1);
+
+        let end = || AdminAction::EndEvent {
+            event_id: "raid".into(),
+        };
+        assert!(admin::execute(&mut svc, end()).ok);
+        assert_eq!(svc.environment().weather(), before);
+        assert_eq!(svc.participant_count(), 0);
+        assert!(!admin::execute(&mut svc, end()).ok);
+    }
+
+    #[test]
+    fn update_structure_moves_and_merges_metadata() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let mut world = World::new(terrain);
+        let mut rock = StructureInstance::new(
+            "rock",
+            Vec3::new(1.0, 2.0, 0.0),
+            ColliderShape::Circle { radius: 0.5 },
+        );
+        rock.metadata
+            .insert("type_id".into(), serde_json::json!("props/rock"));
+        rock.metadata
+            .insert("owner".into(), serde_json::json!("alice"));
+        world.structures.insert(rock).unwrap();
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let mut svc = WorldService::new(WorldServiceConfig::default(), physics, Arc::new(world));
+
+        let update = |structure_id: &str, update: serde_json::Value| AdminAction::UpdateStructure {
+            structure_id: structure_id.into(),
+            update: serde_json::from_value::<StructureUpdate>(update).unwrap(),
+        };
+        let reply = admin::execute(
+            &mut svc,
+            update(
+                "rock",
+                serde_json::json!({ "x": 40.0, "rotation_y": 1.0,
+                                    "metadata": { "owner": null, "mossy": true } }),
+            ),
+        );
+        assert!(reply.ok, "{}", reply.message);
+        assert_eq!(reply.data["x"], 40.0);
+        let rock = svc.world().structures.get("rock").unwrap();
+        assert_eq!(rock.position, Vec3::new(40.0, 2.0, 0.0));
+        assert_eq!(rock.rotation_y, 1.0);
+        assert!(!rock.metadata.contains_key("owner"));
+        assert_eq!(rock.metadata["mossy"], true);
+        assert!(svc
+            .world()
+            .structures
+            .query_radius(1.0, 2.0, 0.1)
+            .is_empty());
+
+        // Removing type_id would leave an invalid structure behind.
+        let untyped = update(
+            "rock",
+            serde_json::json!({ "metadata": { "type_id": null } }),
+        );
+        assert!(!admin::execute(&mut svc, untyped).ok);
+        assert!(svc.world().structures.get("rock").unwrap().meta().is_ok());
+        assert!(!admin::execute(&mut svc, update("nope", serde_json::json!({}))).ok);
+    }
+}
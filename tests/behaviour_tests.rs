@@ -0,0 +1,75 @@
+//! Behaviour steering tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::behaviour::{Behaviour, Steering, WAYPOINT_REACHED};
+
+    fn speed(v: [f32; 2]) -> f32 {
+        v[0].hypot(v[1])
+    }
+
+    /// Walk `steps` seconds of `behaviour` from `pos`, returning where the
+    /// entity ends up and the furthest it got from `home`.
+    fn walk(behaviour: &Behaviour, mut pos: [f32; 2], home: [f32; 2], steps: usize) -> f32 {
+        let mut steering = Steering::default();
+        let mut furthest: f32 = 0.0;
+        for _ in 0..steps {
+            let v = behaviour.steer(&mut steering, pos, home, None, "seed:npc");
+            pos = [pos[0] + v[0] * 0.1, pos[1] + v[1] * 0.1];
+            furthest = furthest.max((pos[0] - home[0]).hypot(pos[1] - home[1]));
+        }
+        furthest
+    }
+
+    #[test]
+    fn wandering_stays_near_home_and_is_deterministic() {
+        let wander = Behaviour::Wander {
+            radius: 5.0,
+            speed: 2.0,
+        };
+        let home = [100.0, -40.0];
+        assert!(walk(&wander, home, home, 2000) <= 5.0 + WAYPOINT_REACHED);
+
+        let mut a = Steering::default();
+        let mut b = Steering::default();
+        let va = wander.steer(&mut a, home, home, None, "seed:npc");
+        let vb = wander.steer(&mut b, home, home, None, "seed:npc");
+        assert_eq!((va, &a), (vb, &b));
+        assert!((speed(va) - 2.0).abs() < 1e-4 || va == [0.0, 0.0]);
+    }
+
+    #[test]
+    fn fleeing_runs_directly_away() {
+        let flee = Behaviour::Flee {
+            range: 10.0,
+            speed: 3.0,
+        };
+        let mut steering = Steering::default();
+        let v = flee.steer(&mut steering, [0.0, 0.0], [0.0, 0.0], Some([0.0, 4.0]), "k");
+        assert!(v[0].abs() < 1e-4 && (v[1] + 3.0).abs() < 1e-4);
+        assert_eq!(
+            flee.steer(&mut steering, [0.0, 0.0], [0.0, 0.0], None, "k"),
+            [0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn pursuit_stops_short_of_the_target() {
+        let pursue: Behaviour = serde_json::from_value(serde_json::json!({
+            "kind": "pursue", "range": 20.0, "speed": 5.0, "stop_distance": 2.0
+        }))
+        .unwrap();
+        let mut steering = Steering::default();
+        let v = pursue.steer(&mut steering, [0.0, 0.0], [0.0, 0.0], Some([6.0, 8.0]), "k");
+        assert!((v[0] - 3.0).abs() < 1e-4 && (v[1] - 4.0).abs() < 1e-4);
+        let close = pursue.steer(&mut steering, [5.0, 7.0], [0.0, 0.0], Some([6.0, 8.0]), "k");
+        assert_eq!(close, [0.0, 0.0]);
+
+        assert!(pursue.is_valid());
+        assert!(!Behaviour::Flee {
+            range: f32::NAN,
+            speed: 1.0
+        }
+        .is_valid());
+    }
+}
@@ -0,0 +1,29 @@
+//! Background chunk worker tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::chunk_workers::ChunkWorkers;
+    use janet_world::types::CellCoord;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn panicking_job_still_reports_its_cell() {
+        let workers = ChunkWorkers::new(1, |coord, _lod| {
+            if coord.x == 0 {
+                panic!("generation failed");
+            }
+        });
+        workers.submit(CellCoord::new(0, 0, 0), 0);
+        workers.submit(CellCoord::new(1, 0, 0), 0);
+
+        // The lone worker survives the panic and gets to the next cell.
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut done = Vec::new();
+        while done.len() < 2 {
+            assert!(Instant::now() < deadline, "worker wedged: {:?}", done);
+            done.extend(workers.completed());
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(done, [CellCoord::new(0, 0, 0), CellCoord::new(1, 0, 0)]);
+    }
+}
@@ -0,0 +1,37 @@
+//! Payload encoding tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::codec::{decode, decode_heights, encode, encode_heights};
+
+    #[test]
+    fn bytes_round_trip_at_every_padding_length() {
+        for len in 0..8 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37) as u8).collect();
+            let text = encode(&bytes).unwrap();
+            assert!(text.len().is_multiple_of(4), "padded: {}", text);
+            assert_eq!(decode(&text).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn heights_round_trip_bit_exactly() {
+        let heights = vec![0.0, -1.5, 3.25, f32::MAX, f32::MIN_POSITIVE, 1e-3];
+        let text = encode_heights(&heights).unwrap();
+        assert_eq!(decode_heights(&text).unwrap(), heights);
+    }
+
+    #[test]
+    fn repetitive_grids_compress() {
+        let heights = vec![12.5f32; 64 * 64];
+        assert!(encode_heights(&heights).unwrap().len() < heights.len());
+    }
+
+    #[test]
+    fn corrupt_input_is_an_error() {
+        assert!(decode("not*base64").is_err());
+        assert!(decode("AAAA").is_err());
+        // Valid gzip, but three bytes is not a whole f32.
+        assert!(decode_heights(&encode(&[1, 2, 3]).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+//! Entity collision event tests
+
+#[cfg(test)]
+mod tests {
+    use janet_operations::physics::types::ContactEvent;
+    use janet_world::collision::entity_collisions;
+    use janet_world::protocol::subjects;
+    use janet_world::types::Vec3;
+    use std::collections::HashMap;
+
+    fn contact(a: &str, b: &str, impulse: f32) -> ContactEvent {
+        ContactEvent {
+            body_a: a.to_string(),
+            body_b: b.to_string(),
+            impulse,
+            point: (1.0, 2.0),
+        }
+    }
+
+    #[test]
+    fn contacts_involving_entities_become_collisions() {
+        let positions = HashMap::from([
+            ("alice".to_string(), Vec3::new(0.0, 0.0, 3.0)),
+            ("bob".to_string(), Vec3::new(1.0, 0.0, 4.0)),
+        ]);
+        let collisions = entity_collisions(
+            vec![
+                contact("structure.hut", "terrain.0.0", 9.0),
+                contact("structure.hut", "bob", 2.5),
+                contact("bob", "alice", 1.0),
+            ],
+            |id| positions.get(id).copied(),
+        );
+        let pairs: Vec<_> = collisions
+            .iter()
+            .map(|c| (c.entity_a.as_str(), c.entity_b.as_str(), c.impulse))
+            .collect();
+        assert_eq!(
+            pairs,
+            [("alice", "bob", 1.0), ("bob", "structure.hut", 2.5)]
+        );
+        // The contact point, at entity_a's height.
+        assert_eq!(
+            (collisions[1].x, collisions[1].y, collisions[1].z),
+            (1.0, 2.0, 4.0)
+        );
+        assert_eq!(subjects::ENTITY_COLLISION, "world.entity.collision");
+    }
+}
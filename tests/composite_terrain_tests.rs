@@ -0,0 +1,79 @@
+//! Flat and composite terrain tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::composite_terrain::{CompositeTerrain, TerrainLayer};
+    use janet_world::flat_terrain::FlatTerrain;
+    use janet_world::terrain::{HeightmapTerrain, TerrainSource};
+    use janet_world::types::Vec3;
+    use std::sync::Arc;
+
+    fn flat(height: f32) -> Arc<dyn TerrainSource> {
+        Arc::new(FlatTerrain::new(height))
+    }
+
+    // -----------------------------------------------------------------------
+    // Flat
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn flat_terrain_is_level_and_streamable() {
+        let t = FlatTerrain::new(3.0).with_chunk_size(16.0);
+        assert_eq!(t.height_at(-100.0, 250.0), 3.0);
+        assert_eq!(t.normal_at(7.0, 7.0), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            t.chunk_descriptor(2, -1, 0).expect("chunked").chunk_size,
+            16.0
+        );
+        assert!(t.collider_for_chunk(0, 0, 0).is_some());
+
+        let hit = t
+            .raycast(Vec3::new(0.0, 0.0, 10.0), Vec3::new(0.0, 0.0, -1.0), 20.0)
+            .expect("ray reaches the floor");
+        assert!((hit.position.z - 3.0).abs() < 1e-3);
+    }
+
+    // -----------------------------------------------------------------------
+    // Composite
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn add_layers_sum_with_their_weights() {
+        let t = CompositeTerrain::new(32.0)
+            .with_layer(TerrainLayer::add(flat(10.0), 1.0))
+            .with_layer(TerrainLayer::add(flat(4.0), 0.25));
+        assert!((t.height_at(5.0, 5.0) - 11.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn mix_layers_pull_towards_their_height() {
+        let half = CompositeTerrain::new(32.0)
+            .with_layer(TerrainLayer::add(flat(10.0), 1.0))
+            .with_layer(TerrainLayer::mix(flat(2.0), 0.5));
+        assert!((half.height_at(0.0, 0.0) - 6.0).abs() < 1e-5);
+
+        let replaced = CompositeTerrain::new(32.0)
+            .with_layer(TerrainLayer::add(flat(10.0), 1.0))
+            .with_layer(TerrainLayer::mix(flat(2.0), 1.0));
+        assert!((replaced.height_at(0.0, 0.0) - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn bounded_override_only_touches_its_rectangle() {
+        let noise: Arc<dyn TerrainSource> = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let t = CompositeTerrain::new(64.0)
+            .with_layer(TerrainLayer::add(noise.clone(), 1.0))
+            .with_layer(TerrainLayer::mix(flat(0.5), 1.0).within(0.0, 0.0, 20.0, 20.0));
+
+        assert_eq!(t.height_at(10.0, 10.0), 0.5, "town square is flattened");
+        assert_eq!(t.height_at(30.0, 10.0), noise.height_at(30.0, 10.0));
+        assert_eq!(t.normal_at(10.0, 10.0), Vec3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn empty_composite_is_sea_level() {
+        let t = CompositeTerrain::new(32.0);
+        assert_eq!(t.height_at(1.0, 2.0), 0.0);
+        assert_eq!(t.chunk_descriptor(0, 0, 0).expect("chunked").seed, 0);
+    }
+}
@@ -0,0 +1,122 @@
+//! Operator console tests
+
+#[cfg(test)]
+mod tests {
+    use janet_operations::physics::{types::PhysicsRegistryConfig, PhysicsRegistry};
+    use janet_world::{
+        console::{self, ConsoleCommand, ConsoleError, TimeSpec},
+        service::WorldService,
+        structure::World,
+        terrain::HeightmapTerrain,
+        types::{CellCoord, Vec3, WorldServiceConfig},
+    };
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+
+    fn make_service() -> WorldService {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let world = Arc::new(World::new(terrain));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            cell_size: 10.0,
+            activation_radius: 0,
+            ..Default::default()
+        };
+        WorldService::new(config, physics, world)
+    }
+
+    // -----------------------------------------------------------------------
+    // Parsing
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn parses_every_command() {
+        assert_eq!(console::parse("help").unwrap(), ConsoleCommand::Help);
+        assert_eq!(
+            console::parse("tp alice 1 2.5").unwrap(),
+            ConsoleCommand::Teleport {
+                id: "alice".into(),
+                position: Vec3::new(1.0, 2.5, 0.0),
+            }
+        );
+        assert_eq!(
+            console::parse("TIME 06:30").unwrap(),
+            ConsoleCommand::Time(Some(TimeSpec::Clock {
+                hours: 6,
+                minutes: 30
+            }))
+        );
+        assert_eq!(
+            console::parse("dump -1 3").unwrap(),
+            ConsoleCommand::Dump(CellCoord::new(-1, 3, 0))
+        );
+        assert_eq!(
+            console::parse("dump -1 3 2").unwrap(),
+            ConsoleCommand::Dump(CellCoord::new(-1, 3, 2))
+        );
+    }
+
+    #[test]
+    fn parse_errors_are_descriptive() {
+        assert_eq!(console::parse("   "), Err(ConsoleError::Empty));
+        assert!(matches!(
+            console::parse("nuke everything"),
+            Err(ConsoleError::UnknownCommand(_))
+        ));
+        assert!(matches!(
+            console::parse("spawn bob 1"),
+            Err(ConsoleError::Usage(_))
+        ));
+        assert_eq!(
+            console::parse("tp bob x 1"),
+            Err(ConsoleError::BadNumber("x".into()))
+        );
+        assert!(matches!(
+            console::parse("time 25:00"),
+            Err(ConsoleError::BadTime(_))
+        ));
+    }
+
+    // -----------------------------------------------------------------------
+    // Execution
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn spawn_then_teleport() {
+        let mut svc = make_service();
+        console::run(&mut svc, "spawn npc-1 5 5").unwrap();
+        assert_eq!(
+            console::run(&mut svc, "spawn npc-1 0 0"),
+            Err(ConsoleError::AlreadyExists("npc-1".into()))
+        );
+
+        console::run(&mut svc, "tp npc-1 25 35").unwrap();
+        assert_eq!(
+            svc.participant_position("npc-1"),
+            Some(Vec3::new(25.0, 35.0, 0.0))
+        );
+        assert_eq!(
+            console::run(&mut svc, "tp ghost 0 0"),
+            Err(ConsoleError::UnknownEntity("ghost".into()))
+        );
+    }
+
+    #[test]
+    fn time_clock_scales_to_day_length() {
+        let mut svc = make_service();
+        console::run(&mut svc, "time 12:00").unwrap();
+        let day = svc.environment().day_length_s();
+        assert!((svc.environment().time_of_day_s() - day * 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn dump_lists_participants_in_cell() {
+        let mut svc = make_service();
+        svc.register_participant("alice".into(), Vec3::new(15.0, 25.0, 0.0));
+        let out = console::run(&mut svc, "dump 1 2").unwrap();
+        assert!(out.contains("participants: alice"), "{}", out);
+        assert!(out.contains("inactive"), "{}", out);
+    }
+}
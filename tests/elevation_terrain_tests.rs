@@ -0,0 +1,283 @@
+//! Real-world elevation terrain tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::elevation_terrain::{
+        ElevationConfig, ElevationError, ElevationTerrain, ElevationTile, GeoCrs,
+    };
+    use janet_world::flat_terrain::FlatTerrain;
+    use janet_world::terrain::TerrainSource;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    const FALLBACK: f32 = -7.0;
+
+    fn fallback() -> Arc<dyn TerrainSource> {
+        Arc::new(FlatTerrain::new(FALLBACK))
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "janet_world_elevation_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // -----------------------------------------------------------------------
+    // SRTM
+    // -----------------------------------------------------------------------
+
+    /// 3x3 one-degree tile (0.5° spacing), row 0 on the northern edge.
+    fn write_hgt(dir: &std::path::Path, name: &str, samples: [i16; 9]) {
+        let bytes: Vec<u8> = samples.iter().flat_map(|v| v.to_be_bytes()).collect();
+        std::fs::write(dir.join(name), bytes).unwrap();
+    }
+
+    #[test]
+    fn srtm_tiles_load_on_demand_and_fall_back_outside_and_at_voids() {
+        let dir = temp_dir("srtm");
+        write_hgt(
+            &dir,
+            "N00E000.hgt",
+            [-32768, 100, 100, 100, 200, 100, 100, 100, 100],
+        );
+        let terrain = ElevationTerrain::open(
+            ElevationConfig {
+                crs: GeoCrs::Geographic,
+                origin_x: 0.5,
+                origin_y: 0.5,
+                metres_per_unit: 1000.0,
+                vertical_scale: 0.5,
+                vertical_offset: 1.0,
+                srtm_dir: Some(dir.clone()),
+                ..Default::default()
+            },
+            fallback(),
+        )
+        .expect("open");
+        assert_eq!(terrain.srtm_tiles_cached(), 0);
+
+        // World origin sits on the centre sample (200 m).
+        assert_eq!(terrain.height_at(0.0, 0.0), 1.0 + 0.5 * 200.0);
+        assert_eq!(terrain.srtm_tiles_cached(), 1);
+
+        // ~55 km north-west is next to the void in the corner.
+        assert_eq!(terrain.height_at(-55.0, 55.0), FALLBACK);
+        // Outside the tile (and no file for the next square).
+        assert_eq!(terrain.height_at(0.0, 200.0), FALLBACK);
+        assert_eq!(terrain.srtm_tiles_cached(), 2);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn srtm_cache_drops_the_least_recently_used_square() {
+        let dir = temp_dir("srtm_lru");
+        write_hgt(&dir, "N00E000.hgt", [100; 9]);
+        write_hgt(&dir, "N01E000.hgt", [300; 9]);
+        let terrain = ElevationTerrain::open(
+            ElevationConfig {
+                crs: GeoCrs::Geographic,
+                origin_x: 0.5,
+                origin_y: 0.5,
+                metres_per_unit: 1000.0,
+                srtm_dir: Some(dir.clone()),
+                srtm_cache_tiles: 1,
+                ..Default::default()
+            },
+            fallback(),
+        )
+        .expect("open");
+
+        assert_eq!(terrain.height_at(0.0, 0.0), 100.0);
+        // ~111 km north is the next square up.
+        assert_eq!(terrain.height_at(0.0, 111.0), 300.0);
+        assert_eq!(terrain.srtm_tiles_cached(), 1);
+        // The first square loads again on its next use.
+        assert_eq!(terrain.height_at(0.0, 0.0), 100.0);
+        assert_eq!(terrain.srtm_tiles_cached(), 1);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn srtm_names_place_southern_and_western_tiles() {
+        let dir = temp_dir("srtm_name");
+        write_hgt(&dir, "S01W002.hgt", [0; 9]);
+        let tile = ElevationTile::read_hgt(dir.join("S01W002.hgt")).expect("tile");
+        assert_eq!((tile.origin_x, tile.origin_y), (-2.0, 0.0));
+        assert_eq!(tile.pixel_width, 0.5);
+
+        write_hgt(&dir, "tile.hgt", [0; 9]);
+        assert!(matches!(
+            ElevationTile::read_hgt(dir.join("tile.hgt")),
+            Err(ElevationError::SrtmName(_))
+        ));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    // -----------------------------------------------------------------------
+    // GeoTIFF
+    // -----------------------------------------------------------------------
+
+    enum Value {
+        Short(Vec<u16>),
+        Long(Vec<u32>),
+        Double(Vec<f64>),
+        Ascii(&'static str),
+    }
+
+    /// Minimal little-endian TIFF: one IFD, then every out-of-line value.
+    fn tiff(mut entries: Vec<(u16, Value)>, pixels: &[u8]) -> Vec<u8> {
+        entries.sort_by_key(|(tag, _)| *tag);
+        let ifd_len = 2 + entries.len() * 12 + 4;
+        let mut out = b"II".to_vec();
+        out.extend(42u16.to_le_bytes());
+        out.extend(8u32.to_le_bytes());
+        out.extend((entries.len() as u16).to_le_bytes());
+
+        let mut extra = Vec::new();
+        for (tag, value) in &entries {
+            let (field_type, count, bytes): (u16, usize, Vec<u8>) = match value {
+                Value::Short(v) => (3, v.len(), v.iter().flat_map(|x| x.to_le_bytes()).collect()),
+                Value::Long(v) => (4, v.len(), v.iter().flat_map(|x| x.to_le_bytes()).collect()),
+                Value::Double(v) => (
+                    12,
+                    v.len(),
+                    v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+                ),
+                Value::Ascii(s) => (2, s.len() + 1, format!("{}\0", s).into_bytes()),
+            };
+            out.extend(tag.to_le_bytes());
+            out.extend(field_type.to_le_bytes());
+            out.extend((count as u32).to_le_bytes());
+            if bytes.len() <= 4 {
+                let mut inline = bytes.clone();
+                inline.resize(4, 0);
+                out.extend(inline);
+            } else {
+                out.extend(((8 + ifd_len + extra.len()) as u32).to_le_bytes());
+                extra.extend(bytes);
+            }
+        }
+        out.extend(0u32.to_le_bytes());
+        let pixel_offset = out.len() + extra.len();
+        out.extend(extra);
+        // Pixels follow the IFD data; point StripOffsets at them.
+        let strip = out
+            .windows(2)
+            .skip(10)
+            .step_by(12)
+            .position(|w| w == 273u16.to_le_bytes())
+            .expect("strip offsets entry");
+        let at = 10 + strip * 12 + 8;
+        out[at..at + 4].copy_from_slice(&(pixel_offset as u32).to_le_bytes());
+        out.extend_from_slice(pixels);
+        out
+    }
+
+    /// 2x2 float32 projected raster, 10 m pixels, north-west corner at
+    /// (1000, 2000): centres at x 1005/1015, y 1995/1985.
+    fn projected_tiff(compression: u16, nodata: Option<&'static str>) -> Vec<u8> {
+        let pixels: Vec<u8> = [10.0f32, 20.0, 30.0, 40.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let mut entries = vec![
+            (256, Value::Short(vec![2])),
+            (257, Value::Short(vec![2])),
+            (258, Value::Short(vec![32])),
+            (259, Value::Short(vec![compression])),
+            (273, Value::Long(vec![0])),
+            (277, Value::Short(vec![1])),
+            (279, Value::Long(vec![pixels.len() as u32])),
+            (339, Value::Short(vec![3])),
+            (33550, Value::Double(vec![10.0, 10.0, 0.0])),
+            (
+                33922,
+                Value::Double(vec![0.0, 0.0, 0.0, 1000.0, 2000.0, 0.0]),
+            ),
+            // Version header, then ModelType = projected, RasterType = area.
+            (
+                34735,
+                Value::Short(vec![1, 1, 0, 2, 1024, 0, 1, 1, 1025, 0, 1, 1]),
+            ),
+        ];
+        if let Some(nodata) = nodata {
+            entries.push((42113, Value::Ascii(nodata)));
+        }
+        tiff(entries, &pixels)
+    }
+
+    fn projected_config(dir: &std::path::Path) -> ElevationConfig {
+        ElevationConfig {
+            crs: GeoCrs::Projected,
+            origin_x: 1005.0,
+            origin_y: 1995.0,
+            geotiffs: vec![dir.join("dem.tif")],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn geotiff_heights_are_bilinear_in_projected_metres() {
+        let dir = temp_dir("geotiff");
+        std::fs::write(dir.join("dem.tif"), projected_tiff(1, None)).unwrap();
+        let terrain = ElevationTerrain::open(projected_config(&dir), fallback()).expect("open");
+
+        assert_eq!(terrain.height_at(0.0, 0.0), 10.0);
+        assert_eq!(terrain.height_at(10.0, 0.0), 20.0);
+        assert_eq!(terrain.height_at(0.0, -10.0), 30.0);
+        assert_eq!(terrain.height_at(5.0, -5.0), 25.0);
+        assert_eq!(terrain.height_at(30.0, 0.0), FALLBACK);
+        assert!(terrain.chunk_descriptor(0, 0, 0).is_some());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn geotiff_nodata_pixels_use_the_fallback() {
+        let dir = temp_dir("geotiff_nodata");
+        std::fs::write(dir.join("dem.tif"), projected_tiff(1, Some("40"))).unwrap();
+        let terrain = ElevationTerrain::open(projected_config(&dir), fallback()).expect("open");
+
+        assert_eq!(terrain.height_at(0.0, 0.0), 10.0);
+        assert_eq!(terrain.height_at(10.0, -10.0), FALLBACK);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn compressed_geotiffs_are_rejected() {
+        let dir = temp_dir("geotiff_lzw");
+        std::fs::write(dir.join("dem.tif"), projected_tiff(5, None)).unwrap();
+        assert!(matches!(
+            ElevationTerrain::open(projected_config(&dir), fallback()),
+            Err(ElevationError::Unsupported(_))
+        ));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn tiles_in_another_crs_are_rejected() {
+        let tile =
+            ElevationTile::from_samples(GeoCrs::Geographic, 2, 2, 0.0, 1.0, 1.0, 1.0, vec![0.0; 4])
+                .unwrap();
+        let terrain = ElevationTerrain::open(
+            ElevationConfig {
+                crs: GeoCrs::Projected,
+                ..Default::default()
+            },
+            fallback(),
+        )
+        .unwrap();
+        assert!(matches!(
+            terrain.with_tile(tile),
+            Err(ElevationError::CrsMismatch { .. })
+        ));
+    }
+}
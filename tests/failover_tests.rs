@@ -0,0 +1,123 @@
+//! Warm standby / failover tests
+
+#[cfg(test)]
+mod tests {
+    use janet_operations::physics::{types::PhysicsRegistryConfig, PhysicsRegistry};
+    use janet_world::{
+        failover::{HeartbeatMonitor, MIRROR_CHECKPOINT},
+        protocol::{EntityMeta, WorldHeartbeat},
+        service::WorldService,
+        structure::World,
+        terrain::{HeightmapTerrain, TerrainSource},
+        types::{Vec3, WorldServiceConfig},
+    };
+    use parking_lot::RwLock;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn beat(id: &str) -> WorldHeartbeat {
+        WorldHeartbeat {
+            instance_id: id.to_string(),
+            frame: 1,
+            timescale: 1.0,
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Heartbeat monitor
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn silent_primary_is_lost_after_the_timeout() {
+        let start = Instant::now();
+        let monitor = HeartbeatMonitor::new(TIMEOUT, start);
+        assert!(!monitor.primary_lost(start + Duration::from_secs(4)));
+        assert!(monitor.primary_lost(start + TIMEOUT));
+        assert_eq!(monitor.primary(), None);
+    }
+
+    #[test]
+    fn heartbeats_reset_the_timer_and_name_the_primary() {
+        let start = Instant::now();
+        let mut monitor = HeartbeatMonitor::new(TIMEOUT, start);
+        monitor.observe(&beat("world-a"), start + Duration::from_secs(4));
+
+        assert!(!monitor.primary_lost(start + Duration::from_secs(8)));
+        assert!(monitor.primary_lost(start + Duration::from_secs(9)));
+        assert_eq!(monitor.primary(), Some("world-a"));
+    }
+
+    #[test]
+    fn late_delivered_heartbeat_does_not_rewind_the_timer() {
+        let start = Instant::now();
+        let mut monitor = HeartbeatMonitor::new(TIMEOUT, start);
+        monitor.observe(&beat("world-a"), start + Duration::from_secs(3));
+        monitor.observe(&beat("world-a"), start + Duration::from_secs(1));
+        assert!(!monitor.primary_lost(start + Duration::from_secs(7)));
+    }
+
+    // -----------------------------------------------------------------------
+    // Mirror restore
+    // -----------------------------------------------------------------------
+
+    fn make_service(checkpoint_dir: PathBuf) -> (WorldService, Arc<HeightmapTerrain>) {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let world = Arc::new(World::new(terrain.clone()));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            physics_dt: 1.0 / 30.0,
+            checkpoint_dir: Some(checkpoint_dir),
+            ..Default::default()
+        };
+        (WorldService::new(config, physics, world), terrain)
+    }
+
+    #[test]
+    fn standby_restores_the_primary_mirror() {
+        let dir = std::env::temp_dir().join(format!("janet_world_mirror_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let (mut primary, primary_terrain) = make_service(dir.clone());
+        primary.register_participant("alice".into(), Vec3::new(3.0, 4.0, 0.0));
+        primary
+            .set_entity_meta(EntityMeta {
+                entity_id: "alice".to_string(),
+                name: Some("Alice".to_string()),
+                health: Some(0.5),
+                status: vec![],
+                afk: false,
+            })
+            .expect("known entity");
+        primary
+            .deform_terrain(Vec3::new(32.0, 32.0, 0.0), 8.0, -2.0)
+            .expect("heightmap terrain is deformable");
+        primary.write_checkpoint(MIRROR_CHECKPOINT).expect("mirror");
+
+        let (mut standby, standby_terrain) = make_service(dir.clone());
+        let mirror = standby.read_checkpoint(MIRROR_CHECKPOINT).expect("read");
+        standby.restore_snapshot(&mirror);
+
+        assert!(standby.entity_meta("alice").is_some());
+        let restored = standby.build_snapshot("test");
+        assert_eq!(restored.entities.len(), 1);
+        assert_eq!(restored.entities[0].x, 3.0);
+        assert_eq!(restored.terrain_modifications.len(), 1);
+        assert_eq!(
+            standby_terrain.height_at(32.0, 32.0),
+            primary_terrain.height_at(32.0, 32.0)
+        );
+
+        // The next edit continues the primary's revision sequence.
+        let next = standby
+            .deform_terrain(Vec3::new(0.0, 0.0, 0.0), 4.0, 1.0)
+            .expect("deformable");
+        assert_eq!(next.revision, 2);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,59 @@
+//! Designer height override store tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::height_overrides::HeightOverrides;
+    use std::path::PathBuf;
+
+    fn override_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("janet_world_hov_{}_{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn deltas_round_trip_through_disk() {
+        let dir = override_dir("roundtrip");
+        let mut store = HeightOverrides::open(&dir).unwrap();
+        store.set(-3, 7, [((0, 0), 1.5), ((31, 2), -0.25)]).unwrap();
+        store.set(0, 0, [((4, 4), 9.0)]).unwrap();
+        assert!(dir.join("-3_7.hov").exists());
+
+        let reopened = HeightOverrides::open(&dir).unwrap();
+        assert_eq!(reopened.len(), 3);
+        assert_eq!(reopened.get(-3, 7, 31, 2), Some(-0.25));
+        assert_eq!(reopened.get(0, 0, 4, 4), Some(9.0));
+        assert_eq!(reopened.get(0, 0, 0, 0), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn clearing_the_last_delta_removes_the_chunk_file() {
+        let dir = override_dir("clear");
+        let mut store = HeightOverrides::open(&dir).unwrap();
+        store.set(1, 1, [((2, 3), 4.0)]).unwrap();
+        store.set(1, 1, [((2, 3), 0.0)]).unwrap();
+        assert!(store.is_empty());
+        assert!(store.chunk(1, 1).is_none());
+        assert!(!dir.join("1_1.hov").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn malformed_files_fail_to_open() {
+        let dir = override_dir("malformed");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("0_0.hov"), b"JWHO\x01\x05\x00\x00\x00").unwrap();
+        assert!(HeightOverrides::open(&dir).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn in_memory_store_needs_no_directory() {
+        let mut store = HeightOverrides::new();
+        store.set(0, 0, [((1, 1), 2.0)]).unwrap();
+        assert!(store.dir().is_none());
+        assert_eq!(store.get(0, 0, 1, 1), Some(2.0));
+    }
+}
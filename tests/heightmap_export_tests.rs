@@ -0,0 +1,138 @@
+//! Heightmap export tests
+
+#[cfg(test)]
+mod tests {
+    use janet_operations::physics::{types::PhysicsRegistryConfig, PhysicsRegistry};
+    use janet_world::codec::base64_decode;
+    use janet_world::heightmap_export::{encode, normalise};
+    use janet_world::image_terrain::{ImageTerrain, ImageTerrainConfig};
+    use janet_world::protocol::{CmdTerrainExport, HeightmapFormat};
+    use janet_world::service::{WorldService, MAX_EXPORT_SAMPLES};
+    use janet_world::structure::World;
+    use janet_world::terrain::{HeightmapTerrain, TerrainSource};
+    use janet_world::types::WorldServiceConfig;
+    use parking_lot::RwLock;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    fn make_service(export_dir: Option<PathBuf>) -> (WorldService, Arc<HeightmapTerrain>) {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let world = Arc::new(World::new(terrain.clone()));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            export_dir,
+            ..Default::default()
+        };
+        (WorldService::new(config, physics, world), terrain)
+    }
+
+    fn region(min: [f32; 2], max: [f32; 2]) -> CmdTerrainExport {
+        CmdTerrainExport {
+            min_x: min[0],
+            min_y: min[1],
+            max_x: max[0],
+            max_y: max[1],
+            spacing: None,
+            format: HeightmapFormat::Png,
+            name: None,
+            token: None,
+        }
+    }
+
+    #[test]
+    fn normalise_spans_the_full_sample_range() {
+        let n = normalise(&[2.0, 4.0, 3.0]);
+        assert_eq!((n.min_height, n.max_height), (2.0, 4.0));
+        assert_eq!(n.samples, vec![0, u16::MAX, 32768]);
+
+        let flat = normalise(&[5.0; 4]);
+        assert_eq!((flat.min_height, flat.max_height), (5.0, 5.0));
+        assert_eq!(flat.samples, vec![0; 4]);
+    }
+
+    #[test]
+    fn raw_is_little_endian_u16() {
+        let bytes = encode(2, 1, &[1, 0x0203], HeightmapFormat::Raw).unwrap();
+        assert_eq!(bytes, vec![1, 0, 3, 2]);
+        assert!(encode(3, 3, &[0; 4], HeightmapFormat::Png).is_err());
+    }
+
+    #[test]
+    fn inline_export_covers_the_region_inclusively() {
+        let (svc, terrain) = make_service(None);
+        let mut cmd = region([-10.0, 0.0], [10.0, 6.0]);
+        cmd.format = HeightmapFormat::Raw;
+        let export = svc.export_terrain(&cmd).unwrap();
+        // Default spacing is the 2 m tile size.
+        assert_eq!((export.width, export.height, export.spacing), (11, 4, 2.0));
+        assert!(export.path.is_none());
+
+        let bytes = base64_decode(export.data.as_deref().unwrap()).unwrap();
+        assert_eq!(bytes.len(), 11 * 4 * 2);
+        let sample = |col: usize, row: usize| {
+            let i = (row * 11 + col) * 2;
+            u16::from_le_bytes([bytes[i], bytes[i + 1]]) as f32 / u16::MAX as f32
+        };
+        let range = export.max_height - export.min_height;
+        let expected = terrain.height_at(-10.0 + 3.0 * 2.0, 2.0 * 2.0);
+        let got = export.min_height + sample(3, 2) * range;
+        assert!((got - expected).abs() <= range / u16::MAX as f32);
+    }
+
+    #[test]
+    fn named_png_export_loads_back_as_image_terrain() {
+        let dir = std::env::temp_dir().join(format!("janet_world_export_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let (svc, terrain) = make_service(Some(dir.clone()));
+        let mut cmd = region([0.0, 0.0], [40.0, 40.0]);
+        cmd.name = Some("spawn".into());
+        let export = svc.export_terrain(&cmd).unwrap();
+        assert!(export.data.is_none());
+        let path = dir.join("spawn.png");
+        assert_eq!(export.path.as_deref(), Some(path.to_str().unwrap()));
+
+        let loaded = ImageTerrain::load(
+            &path,
+            ImageTerrainConfig {
+                metres_per_pixel: export.spacing,
+                min_height: export.min_height,
+                max_height: export.max_height,
+                origin_x: export.min_x,
+                origin_y: export.min_y,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let tolerance = (export.max_height - export.min_height) / u16::MAX as f32 + 1e-4;
+        for (x, y) in [(0.0, 0.0), (12.0, 30.0), (40.0, 40.0)] {
+            let (a, b) = (loaded.height_at(x, y), terrain.height_at(x, y));
+            assert!((a - b).abs() <= tolerance, "({}, {}): {} vs {}", x, y, a, b);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn bad_exports_are_rejected() {
+        let (svc, _) = make_service(None);
+        let side = (MAX_EXPORT_SAMPLES as f32).sqrt() * 2.0;
+        assert!(svc
+            .export_terrain(&region([0.0, 0.0], [side, side]))
+            .is_err());
+        assert!(svc.export_terrain(&region([5.0, 0.0], [0.0, 5.0])).is_err());
+
+        let mut cmd = region([0.0, 0.0], [4.0, 4.0]);
+        cmd.spacing = Some(0.0);
+        assert!(svc.export_terrain(&cmd).is_err());
+
+        cmd.spacing = None;
+        cmd.name = Some("heights".into());
+        let err = svc.export_terrain(&cmd).unwrap_err();
+        assert!(err.to_string().contains("export_dir"), "{}", err);
+
+        let (svc, _) = make_service(Some(std::env::temp_dir()));
+        cmd.name = Some("../escape".into());
+        assert!(svc.export_terrain(&cmd).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+//! Cell hibernation store tests
+
+#[cfg(test)]
+mod tests {
+    use janet_operations::physics::types::ColliderShape;
+    use janet_world::hibernation::HibernationStore;
+    use janet_world::types::{CellCoord, Vec3, WorldObject};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn hibernation_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "janet_world_hibernation_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn tree(id: &str, x: f32) -> WorldObject {
+        WorldObject {
+            id: id.to_string(),
+            kind: "tree".to_string(),
+            position: Vec3::new(x, 2.0, 0.0),
+            collider: ColliderShape::Circle { radius: 0.5 },
+            properties: HashMap::from([("felled".to_string(), serde_json::json!(true))]),
+        }
+    }
+
+    #[test]
+    fn cells_round_trip_through_disk() {
+        let dir = hibernation_dir("roundtrip");
+        let coord = CellCoord::new(-2, 5, 1);
+        let mut store = HibernationStore::open(&dir).unwrap();
+        store.put(coord, &[tree("vegetation:-2:5:0", 3.5)]).unwrap();
+        assert!(dir.join("-2_5_1.json").exists());
+
+        let reopened = HibernationStore::open(&dir).unwrap();
+        assert_eq!(reopened.len(), 1);
+        let objects = reopened.get(coord).unwrap().expect("hibernated");
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].id, "vegetation:-2:5:0");
+        assert_eq!(objects[0].position, Vec3::new(3.5, 2.0, 0.0));
+        assert_eq!(objects[0].properties["felled"], serde_json::json!(true));
+        assert!(reopened.get(CellCoord::new(0, 0, 0)).unwrap().is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn removing_a_cell_deletes_its_file() {
+        let dir = hibernation_dir("remove");
+        let coord = CellCoord::new(1, 1, 0);
+        let mut store = HibernationStore::open(&dir).unwrap();
+        store.put(coord, &[tree("a", 0.0)]).unwrap();
+        store.put(coord, &[tree("a", 1.0), tree("b", 2.0)]).unwrap();
+        assert_eq!(store.get(coord).unwrap().unwrap().len(), 2);
+
+        store.remove(coord).unwrap();
+        assert!(store.is_empty());
+        assert!(!dir.join("1_1_0.json").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn in_memory_store_needs_no_directory() {
+        let mut store = HibernationStore::new();
+        store
+            .put(CellCoord::new(0, 0, 0), &[tree("a", 0.0)])
+            .unwrap();
+        assert!(store.dir().is_none());
+        assert!(store.contains(CellCoord::new(0, 0, 0)));
+    }
+}
@@ -0,0 +1,138 @@
+//! Image-backed terrain tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::image_terrain::{ImageTerrain, ImageTerrainConfig, ImageTerrainError};
+    use janet_world::terrain::{CacheBudget, TerrainSource};
+    use std::path::PathBuf;
+
+    fn config() -> ImageTerrainConfig {
+        ImageTerrainConfig {
+            metres_per_pixel: 1.0,
+            min_height: 10.0,
+            max_height: 20.0,
+            chunk_size: 4.0,
+            base_resolution: 4,
+            ..Default::default()
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("janet_world_{}_{}", std::process::id(), name))
+    }
+
+    /// 2x2 ramp: 0 at the origin, 1 at the far corner.
+    fn ramp_u16() -> Vec<u16> {
+        vec![0, u16::MAX / 2, u16::MAX / 2, u16::MAX]
+    }
+
+    // -----------------------------------------------------------------------
+    // Sampling
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn samples_map_onto_vertical_range() {
+        let t = ImageTerrain::from_samples(2, 2, vec![0.0, 0.5, 0.5, 1.0], config()).unwrap();
+        assert!((t.height_at(0.0, 0.0) - 10.0).abs() < 1e-4);
+        assert!((t.height_at(1.0, 1.0) - 20.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn height_is_interpolated_between_pixels() {
+        let t = ImageTerrain::from_samples(2, 2, vec![0.0, 0.5, 0.5, 1.0], config()).unwrap();
+        let mid = t.height_at(0.5, 0.5);
+        assert!((mid - 15.0).abs() < 1e-4, "got {}", mid);
+    }
+
+    #[test]
+    fn queries_outside_image_clamp_to_edge() {
+        let t = ImageTerrain::from_samples(2, 2, vec![0.0, 0.5, 0.5, 1.0], config()).unwrap();
+        assert!((t.height_at(-50.0, -50.0) - 10.0).abs() < 1e-4);
+        assert!((t.height_at(500.0, 500.0) - 20.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn mismatched_dimensions_are_rejected() {
+        let result = ImageTerrain::from_samples(3, 3, vec![0.0; 4], config());
+        assert!(matches!(result, Err(ImageTerrainError::Dimensions { .. })));
+    }
+
+    // -----------------------------------------------------------------------
+    // File formats
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn load_square_raw_heightmap() {
+        let path = temp_path("square.r16");
+        let bytes: Vec<u8> = ramp_u16().iter().flat_map(|v| v.to_le_bytes()).collect();
+        std::fs::write(&path, bytes).unwrap();
+
+        let t = ImageTerrain::load(&path, config()).expect("raw should load");
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!((t.width(), t.height()), (2, 2));
+        assert!((t.height_at(1.0, 1.0) - 20.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn load_png_heightmap() {
+        let path = temp_path("ramp.png");
+        let img = image::ImageBuffer::<image::Luma<u16>, _>::from_raw(2, 2, ramp_u16()).unwrap();
+        img.save(&path).unwrap();
+
+        let t = ImageTerrain::load(&path, config()).expect("png should load");
+        std::fs::remove_file(&path).ok();
+
+        assert!((t.height_at(0.0, 0.0) - 10.0).abs() < 1e-4);
+        assert!((t.height_at(1.0, 1.0) - 20.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn unsupported_extension_is_rejected() {
+        let result = ImageTerrain::load("heights.bmp", config());
+        assert!(matches!(
+            result,
+            Err(ImageTerrainError::UnsupportedFormat(_))
+        ));
+    }
+
+    // -----------------------------------------------------------------------
+    // Chunking
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn chunk_grid_matches_heightmap_layout() {
+        let t = ImageTerrain::from_samples(2, 2, vec![0.0, 0.5, 0.5, 1.0], config()).unwrap();
+        let chunk = t.get_or_generate_chunk(0, 0, 0);
+        assert_eq!(chunk.resolution, 4);
+        assert_eq!(chunk.heights.len(), 16);
+        assert!((chunk.heights[0] - 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn heightfield_collider_covers_chunk() {
+        use janet_operations::physics::types::ColliderShape;
+        let t = ImageTerrain::from_samples(2, 2, vec![0.0; 4], config()).unwrap();
+        match t.heightfield_collider_for_chunk(0, 0, 0) {
+            ColliderShape::Box { width, height } => {
+                assert!((width - 4.0).abs() < 1e-4);
+                assert!((height - 4.0).abs() < 1e-4);
+            }
+            _ => panic!("Expected ColliderShape::Box for placeholder terrain"),
+        }
+    }
+
+    #[test]
+    fn chunk_cache_evicts_least_recently_used() {
+        let t = ImageTerrain::from_samples(2, 2, vec![0.0; 4], config())
+            .unwrap()
+            .with_cache_budget(CacheBudget::Chunks(2));
+        t.warm_chunk(0, 0, 0);
+        t.warm_chunk(1, 0, 0);
+        t.get_or_generate_chunk(0, 0, 0);
+        t.warm_chunk(2, 0, 0);
+        assert!(t.is_chunk_cached(0, 0, 0));
+        assert!(!t.is_chunk_cached(1, 0, 0));
+        assert!(t.is_chunk_cached(2, 0, 0));
+    }
+}
@@ -0,0 +1,171 @@
+//! Structure interest tracker tests
+
+#[cfg(test)]
+mod tests {
+    use janet_operations::physics::types::ColliderShape;
+    use janet_world::interest::{CellInterest, InterestTracker};
+    use janet_world::structure::{StreamTier, StructureInstance, StructureRegistry};
+    use janet_world::types::Vec3;
+
+    const CELL: f32 = 10.0;
+
+    /// Two structures 100 units apart (radius 5 each).
+    fn registry() -> StructureRegistry {
+        let mut registry = StructureRegistry::new();
+        for (id, x) in [("rock-west", 5.0), ("rock-east", 105.0)] {
+            let mut s = StructureInstance::new(
+                id,
+                Vec3::new(x, 5.0, 0.0),
+                ColliderShape::Box {
+                    width: 1.0,
+                    height: 1.0,
+                },
+            );
+            s.metadata
+                .insert("type_id".into(), serde_json::json!("props/rock"));
+            registry.insert(s).unwrap();
+        }
+        registry
+    }
+
+    fn at(x: f32) -> Vec3 {
+        Vec3::new(x, 5.0, 0.0)
+    }
+
+    #[test]
+    fn first_update_loads_structures_in_the_window() {
+        let registry = registry();
+        let mut t = InterestTracker::new();
+
+        let change = t
+            .update("alice", at(5.0), CELL, 1, &registry)
+            .expect("load");
+        assert_eq!(change.participant_id, "alice");
+        assert_eq!(change.loaded.len(), 1);
+        assert_eq!(change.loaded[0].structure_id, "rock-west");
+        assert_eq!(change.loaded[0].type_id, "props/rock");
+        assert!(change.unloaded.is_empty());
+        assert!(t.in_scope("alice", "rock-west"));
+        assert!(!t.in_scope("alice", "rock-east"));
+    }
+
+    #[test]
+    fn staying_in_the_same_cell_sends_nothing() {
+        let registry = registry();
+        let mut t = InterestTracker::new();
+        t.update("alice", at(5.0), CELL, 1, &registry);
+        assert!(t.update("alice", at(6.0), CELL, 1, &registry).is_none());
+    }
+
+    #[test]
+    fn leaving_the_window_unloads_for_that_participant_only() {
+        let registry = registry();
+        let mut t = InterestTracker::new();
+        t.update("alice", at(5.0), CELL, 1, &registry);
+        t.update("bob", at(5.0), CELL, 1, &registry);
+
+        let change = t
+            .update("alice", at(105.0), CELL, 1, &registry)
+            .expect("scope change");
+        assert_eq!(change.unloaded, vec!["rock-west".to_string()]);
+        assert_eq!(change.loaded[0].structure_id, "rock-east");
+        assert!(!t.in_scope("alice", "rock-west"));
+        assert!(t.in_scope("bob", "rock-west"));
+
+        // Coming back loads it again.
+        let change = t
+            .update("alice", at(5.0), CELL, 1, &registry)
+            .expect("scope change");
+        assert_eq!(change.loaded[0].structure_id, "rock-west");
+        assert_eq!(change.unloaded, vec!["rock-east".to_string()]);
+    }
+
+    #[test]
+    fn removed_participants_start_from_an_empty_scope() {
+        let registry = registry();
+        let mut t = InterestTracker::new();
+        t.update("alice", at(5.0), CELL, 1, &registry);
+        t.remove("alice");
+        assert!(!t.in_scope("alice", "rock-west"));
+
+        let change = t
+            .update("alice", at(5.0), CELL, 1, &registry)
+            .expect("reload");
+        assert_eq!(change.loaded.len(), 1);
+    }
+
+    #[test]
+    fn detail_structures_load_within_their_tier_radius() {
+        let mut registry = registry();
+        let mut pebble = StructureInstance::new(
+            "pebble",
+            Vec3::new(30.0, 5.0, 0.0),
+            ColliderShape::Circle { radius: 0.2 },
+        );
+        pebble.tier = StreamTier::Detail;
+        pebble
+            .metadata
+            .insert("type_id".into(), serde_json::json!("props/pebble"));
+        registry.insert(pebble).unwrap();
+        let mut t = InterestTracker::with_tier_radii(2, 1);
+
+        // Three cells away: inside the window, outside the detail radius.
+        let change = t
+            .update("alice", at(5.0), CELL, 3, &registry)
+            .expect("load");
+        assert_eq!(change.loaded.len(), 1);
+        assert_eq!(change.loaded[0].structure_id, "rock-west");
+
+        let change = t
+            .update("alice", at(15.0), CELL, 3, &registry)
+            .expect("closer");
+        assert_eq!(change.loaded.len(), 1);
+        assert_eq!(change.loaded[0].structure_id, "pebble");
+        assert!(change.unloaded.is_empty());
+
+        // An uncapped tracker loads it with everything else.
+        let change = InterestTracker::new()
+            .update("bob", at(5.0), CELL, 3, &registry)
+            .expect("load");
+        assert_eq!(change.loaded.len(), 2);
+    }
+
+    #[test]
+    fn cell_interest_follows_the_view_window() {
+        let mut cells = CellInterest::new(64.0);
+        let first = cells
+            .update("alice", Vec3::new(10.0, 10.0, 0.0), 20.0)
+            .expect("subscribe");
+        assert_eq!(first.cell_size, 64.0);
+        assert_eq!(
+            first.subscribe,
+            [
+                "world.cell.-1.-1.>",
+                "world.cell.-1.0.>",
+                "world.cell.0.-1.>",
+                "world.cell.0.0.>",
+            ]
+        );
+        assert!(first.unsubscribe.is_empty());
+        assert!(cells.subscribed("alice", (-1, 0)));
+
+        // Same window, nothing to change.
+        assert!(cells
+            .update("alice", Vec3::new(12.0, 15.0, 0.0), 20.0)
+            .is_none());
+
+        // Far enough east that the west column drops out.
+        let moved = cells
+            .update("alice", Vec3::new(30.0, 10.0, 0.0), 20.0)
+            .expect("diff");
+        assert!(moved.subscribe.is_empty());
+        assert_eq!(
+            moved.unsubscribe,
+            ["world.cell.-1.-1.>", "world.cell.-1.0.>"]
+        );
+        assert!(!cells.subscribed("alice", (-1, 0)));
+
+        cells.remove("alice");
+        assert!(!cells.subscribed("alice", (0, 0)));
+    }
+}
@@ -0,0 +1,104 @@
+//! Event fan-out metrics tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::metrics::{
+        DropCounters, FanoutMetrics, RollingHistogram, DROP_TRACKED_PARTICIPANTS,
+    };
+    use janet_world::protocol::ChunkDeactivated;
+    use janet_world::service::TickEvents;
+    use janet_world::types::DropReason;
+
+    // -----------------------------------------------------------------------
+    // Rolling histogram
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn empty_histogram_summarises_to_zero() {
+        let h = RollingHistogram::new(4);
+        assert!(h.is_empty());
+        assert_eq!(h.summary(), Default::default());
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let mut h = RollingHistogram::new(100);
+        for count in 1..=100 {
+            h.record(count);
+        }
+        let s = h.summary();
+        assert_eq!(s.last, 100);
+        assert_eq!(s.p50, 50);
+        assert_eq!(s.p95, 95);
+        assert_eq!(s.max, 100);
+        assert!((s.mean - 50.5).abs() < 1e-3);
+        assert_eq!(s.total, 5050);
+    }
+
+    #[test]
+    fn old_ticks_roll_out_of_the_window() {
+        let mut h = RollingHistogram::new(3);
+        for count in [50, 1, 2, 3] {
+            h.record(count);
+        }
+        let s = h.summary();
+        assert_eq!(h.len(), 3);
+        assert_eq!(s.last, 3);
+        assert_eq!(s.max, 3, "the spike has left the window");
+        // The running total still counts it.
+        assert_eq!(s.total, 56);
+    }
+
+    // -----------------------------------------------------------------------
+    // Fan-out
+    // -----------------------------------------------------------------------
+
+    fn deactivation(cx: i32) -> ChunkDeactivated {
+        ChunkDeactivated {
+            chunk_id: format!("{}:0", cx),
+        }
+    }
+
+    #[test]
+    fn fanout_counts_each_event_kind() {
+        let mut metrics = FanoutMetrics::new(8);
+        let events = TickEvents {
+            deactivated: (0..3).map(deactivation).collect(),
+            ..Default::default()
+        };
+        metrics.record(&events);
+        metrics.record(&TickEvents::default());
+
+        let stats = metrics.summary();
+        assert_eq!(stats.window_ticks, 2);
+        assert_eq!(stats.deactivated.max, 3);
+        assert_eq!(stats.deactivated.last, 0);
+        assert_eq!(stats.deactivated.total, 3);
+        assert_eq!(stats.activated.total, 0);
+        assert_eq!(stats.total.max, 3);
+        assert_eq!(stats.transforms.total, 0);
+    }
+
+    // -----------------------------------------------------------------------
+    // Dropped intents
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn drops_past_the_tracked_senders_share_one_bucket() {
+        let mut drops = DropCounters::default();
+        for i in 0..DROP_TRACKED_PARTICIPANTS + 2 {
+            let id = format!("p{}", i);
+            drops.record("action.move", Some(&id), DropReason::Invalid, "unknown");
+        }
+        drops.record("action.move", Some("p0"), DropReason::ChannelFull, "full");
+        drops.record("world.cmd.admin", None, DropReason::Invalid, "token");
+
+        let stats = drops.summary();
+        assert_eq!(stats.total, DROP_TRACKED_PARTICIPANTS as u64 + 4);
+        assert_eq!(stats.by_participant.len(), DROP_TRACKED_PARTICIPANTS + 1);
+        assert_eq!(stats.by_participant["*"], 2);
+        assert_eq!(stats.by_participant["p0"], 2);
+        assert_eq!(stats.by_reason[&DropReason::ChannelFull], 1);
+        assert_eq!(stats.by_kind["world.cmd.admin"], 1);
+    }
+}
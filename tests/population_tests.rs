@@ -0,0 +1,134 @@
+//! Ambient population tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::population::{AmbientPopulation, PopulationCell};
+    use janet_world::worldgen::PopulationRule;
+
+    /// 100 m cells: one per hectare, so 100 cells make a km².
+    const CELL: f32 = 100.0;
+
+    fn wolves(per_km2: f32, hours: Option<[f32; 2]>) -> PopulationRule {
+        PopulationRule {
+            archetype: "creature/wolf".into(),
+            biomes: vec!["forest".into()],
+            per_km2,
+            hours,
+        }
+    }
+
+    /// `n` cells in a row of `biome`, starting at column `x0`.
+    fn cells(x0: i32, n: i32, biome: &str) -> Vec<PopulationCell> {
+        (x0..x0 + n)
+            .map(|cx| PopulationCell {
+                cx,
+                cy: 0,
+                biome: biome.into(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stocks_habitat_to_the_target_density() {
+        let mut pop = AmbientPopulation::new(vec![wolves(4.0, None)], CELL, 42);
+        let mut region = cells(0, 100, "forest");
+        region.extend(cells(100, 100, "dunes"));
+
+        let change = pop.evaluate(&region, 12.0);
+        assert_eq!(change.spawned.len(), 4);
+        assert!(change.despawned.is_empty());
+        for s in &change.spawned {
+            assert_eq!(s.archetype, "creature/wolf");
+            assert_eq!(s.biome, "forest");
+            assert!((0.0..100.0 * CELL).contains(&s.x), "{:?}", s);
+            assert!((0.0..CELL).contains(&s.y), "{:?}", s);
+            assert!(pop.contains(&s.entity_id));
+        }
+
+        // Already stocked: nothing to do.
+        assert_eq!(pop.evaluate(&region, 12.0), Default::default());
+    }
+
+    #[test]
+    fn fractional_targets_do_not_flap() {
+        // 2.4 wolves over 60 cells rounds to 2; 2.8 over 70 to 3.
+        let mut pop = AmbientPopulation::new(vec![wolves(4.0, None)], CELL, 42);
+        let mut spawned = pop.evaluate(&cells(0, 60, "forest"), 12.0).spawned;
+        assert_eq!(spawned.len(), 2);
+        spawned.extend(pop.evaluate(&cells(0, 70, "forest"), 12.0).spawned);
+        assert_eq!(spawned.len(), 3);
+
+        // Back to 2.4: a third wolf is within the ceiling, so only one
+        // standing in a dropped column goes.
+        let mut outside: Vec<_> = spawned
+            .iter()
+            .filter(|s| s.x >= 60.0 * CELL)
+            .map(|s| s.entity_id.clone())
+            .collect();
+        outside.sort();
+        let change = pop.evaluate(&cells(0, 60, "forest"), 12.0);
+        assert_eq!(change.despawned, outside);
+        assert_eq!(pop.stats().total, 3 - outside.len());
+        // Nor does the next pass trim it.
+        assert!(pop
+            .evaluate(&cells(0, 60, "forest"), 12.0)
+            .despawned
+            .is_empty());
+    }
+
+    #[test]
+    fn lost_habitat_and_closed_hours_despawn() {
+        let mut pop = AmbientPopulation::new(vec![wolves(10.0, Some([20.0, 6.0]))], CELL, 7);
+        let region = cells(0, 100, "forest");
+        assert!(pop.evaluate(&region, 14.0).spawned.is_empty());
+
+        let spawned = pop.evaluate(&region, 23.0).spawned;
+        assert_eq!(spawned.len(), 10);
+        // Still night after midnight.
+        assert_eq!(pop.evaluate(&region, 2.0), Default::default());
+
+        // Dawn removes the whole pack.
+        let mut gone = pop.evaluate(&region, 6.0).despawned;
+        gone.sort();
+        let mut ids: Vec<_> = spawned.into_iter().map(|s| s.entity_id).collect();
+        ids.sort();
+        assert_eq!(gone, ids);
+        assert_eq!(pop.stats().total, 0);
+
+        // Columns leaving the region take their wolves with them.
+        pop.evaluate(&region, 22.0);
+        let change = pop.evaluate(&[], 22.0);
+        assert_eq!(change.despawned.len(), 10);
+        assert!(change.spawned.is_empty());
+    }
+
+    #[test]
+    fn stats_count_by_biome_and_archetype() {
+        let crabs = PopulationRule {
+            archetype: "creature/crab".into(),
+            biomes: vec![],
+            per_km2: 1.0,
+            hours: None,
+        };
+        let mut pop = AmbientPopulation::new(vec![wolves(2.0, None), crabs], CELL, 42);
+        let mut region = cells(0, 100, "forest");
+        region.extend(cells(100, 100, "beach"));
+        pop.evaluate(&region, 0.0);
+
+        let stats = pop.stats();
+        assert_eq!(stats.total, 4);
+        assert_eq!(stats.by_archetype["creature/wolf"], 2);
+        assert_eq!(stats.by_archetype["creature/crab"], 2);
+        assert_eq!(stats.by_biome.values().sum::<usize>(), 4);
+        assert!(stats.by_biome["forest"] >= 2);
+
+        let crab = pop
+            .ids()
+            .find(|id| id.contains("crab"))
+            .unwrap()
+            .to_string();
+        assert_eq!(pop.archetype(&crab), Some("creature/crab"));
+        pop.remove(&crab);
+        assert_eq!(pop.stats().by_archetype["creature/crab"], 1);
+    }
+}
@@ -1,7 +1,15 @@
 //! Protocol/config compatibility tests for janet-world expansion fields.
 
-use janet_world::protocol::ChunkActivated;
-use janet_world::types::WorldServiceConfig;
+use janet_world::protocol::{
+    subjects, AdminAction, ChunkActivated, ChunkData, ChunkEdge, ChunkHoles, ChunkVoxels, CmdAdmin,
+    CmdHeights, CmdRaycast, CmdRequestSnapshot, CmdSetHeights, EntityEmote, EntityHandle,
+    EntityMeta, EntitySpawned, EntityTransform, EntityTransformBatch, HeightSamples,
+    IntentPlaceStructure, InteractionResult, InteractionTarget, InterestSubjects, NavChangeCause,
+    NavInvalidated, ParticipantJoined, PickHit, PickTarget, RaycastHit, ScriptedEvent,
+    StructureSpawned, StructureStateChanged, StructureUpdated, TerrainModified, VoxelCells,
+    WorldCmdError, WorldCmdErrorCode, WorldHeartbeat, WorldSnapshot, TERRAIN_VERSION, UNIT_SCALE,
+};
+use janet_world::types::{EdgeFill, HydrologyConfig, MaterialRules, WorldServiceConfig};
 
 #[test]
 fn world_service_config_defaults_tile_size_m_to_two_metres() {
@@ -21,10 +29,18 @@ fn chunk_activated_deserializes_legacy_payload_with_defaults() {
         "chunk_size": 32.0
     });
 
-    let parsed: ChunkActivated = serde_json::from_value(legacy).expect("legacy payload should parse");
+    let parsed: ChunkActivated =
+        serde_json::from_value(legacy).expect("legacy payload should parse");
 
     assert_eq!(parsed.tile_resolution, 2.0);
     assert_eq!(parsed.terrain_algo_version, "md5_value_noise_v1");
+    assert_eq!(parsed.terrain_version, 1);
+    assert_eq!(parsed.priority, 0);
+    assert_eq!(parsed.hydrology, None);
+    assert_eq!(parsed.materials, MaterialRules::default());
+    assert_eq!(parsed.holes, None);
+    assert_eq!(parsed.checksum, None);
+    assert_eq!(parsed.edge, None);
 }
 
 #[test]
@@ -37,15 +53,668 @@ fn chunk_activated_roundtrip_preserves_expanded_fields() {
         terrain_seed: 1337,
         tile_resolution: 1.5,
         terrain_algo_version: "custom_algo_v2".to_string(),
+        terrain_version: 7,
         lod: 1,
         chunk_size: 64.0,
+        priority: 3,
+        hydrology: Some(HydrologyConfig::default()),
+        materials: MaterialRules {
+            snow_min: 0.9,
+            ..Default::default()
+        },
+        shoreline: vec![[0.0, 1.0, 2.0, 3.0]],
+        holes: Some(ChunkHoles {
+            cell_size: 1.0,
+            cells: vec![[3, 4]],
+        }),
+        checksum: Some(u64::MAX),
+        edge: Some(ChunkEdge {
+            fill: EdgeFill::Cliff,
+            height: 40.0,
+            inward: [-1, 0],
+        }),
     };
 
     let v = serde_json::to_value(&payload).expect("serialize");
+    assert_eq!(v["edge"]["fill"], "cliff");
     let reparsed: ChunkActivated = serde_json::from_value(v).expect("deserialize");
 
     assert_eq!(reparsed.chunk_id, "1:2");
     assert_eq!(reparsed.tile_resolution, 1.5);
     assert_eq!(reparsed.terrain_algo_version, "custom_algo_v2");
+    assert_eq!(reparsed.terrain_version, 7);
     assert_eq!(reparsed.lod, 1);
+    assert_eq!(reparsed.priority, 3);
+    assert_eq!(reparsed.hydrology, Some(HydrologyConfig::default()));
+    assert_eq!(reparsed.materials.snow_min, 0.9);
+    assert_eq!(reparsed.shoreline, vec![[0.0, 1.0, 2.0, 3.0]]);
+    assert_eq!(reparsed.holes.expect("holes").cells, vec![[3, 4]]);
+    assert_eq!(reparsed.checksum, Some(u64::MAX));
+    assert_eq!(reparsed.edge.expect("edge").inward, [-1, 0]);
+}
+
+#[test]
+fn world_snapshot_without_environment_still_parses() {
+    let legacy = serde_json::json!({
+        "active_chunks": [],
+        "structures": [],
+        "entities": []
+    });
+
+    let parsed: WorldSnapshot =
+        serde_json::from_value(legacy).expect("legacy snapshot should parse");
+    assert!(parsed.environment.is_none());
+    assert!(parsed.entity_meta.is_empty());
+    assert_eq!(parsed.terrain_version, 1);
+}
+
+fn structure(id: &str, type_id: &str) -> StructureSpawned {
+    StructureSpawned {
+        structure_id: id.to_string(),
+        type_id: type_id.to_string(),
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+        rotation_y: 0.5,
+        pitch: 0.0,
+        roll: 0.0,
+        scale: UNIT_SCALE,
+        state: None,
+        metadata: serde_json::Value::Null,
+    }
+}
+
+#[test]
+fn compact_snapshot_interns_repeated_strings() {
+    let snapshot = WorldSnapshot {
+        active_chunks: vec![],
+        structures: vec![
+            structure("rock-1", "props/rock"),
+            structure("rock-2", "props/rock"),
+        ],
+        entities: vec![EntitySpawned {
+            entity_id: "wolf-1".to_string(),
+            archetype: "creature/wolf".to_string(),
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            rotation_y: 0.0,
+            scale: 1.5,
+            bounding_radius: 0.75,
+            metadata: serde_json::Value::Null,
+        }],
+        environment: None,
+        sea_level: None,
+        terrain_version: TERRAIN_VERSION,
+        terrain_modifications: vec![],
+        chunk_voxels: vec![],
+        chunk_data: vec![],
+        entity_meta: vec![],
+        entity_handles: vec![],
+        active_events: vec![],
+    };
+
+    let compact = snapshot.compact();
+    // rock-1, props/rock, rock-2, wolf-1, creature/wolf
+    assert_eq!(compact.string_table.len(), 5);
+    assert_eq!(compact.structures[0].type_id, compact.structures[1].type_id);
+
+    let expanded = compact.expand().expect("indices should resolve");
+    assert_eq!(expanded.structures[1].structure_id, "rock-2");
+    assert_eq!(expanded.structures[1].type_id, "props/rock");
+    assert_eq!(expanded.entities[0].archetype, "creature/wolf");
+    assert_eq!(expanded.entities[0].scale, 1.5);
+    assert_eq!(expanded.entities[0].bounding_radius, 0.75);
+}
+
+#[test]
+fn entity_size_defaults_for_older_servers() {
+    let json =
+        r#"{"entity_id":"wolf-1","archetype":"creature/wolf","x":0,"y":0,"z":0,"rotation_y":0}"#;
+    let e: EntitySpawned = serde_json::from_str(json).unwrap();
+    assert_eq!(e.scale, 1.0);
+    assert_eq!(e.bounding_radius, 0.0);
+}
+
+#[test]
+fn compact_snapshot_with_bad_index_fails_to_expand() {
+    let mut compact = WorldSnapshot {
+        active_chunks: vec![],
+        structures: vec![structure("a", "b")],
+        entities: vec![],
+        environment: None,
+        sea_level: None,
+        terrain_version: TERRAIN_VERSION,
+        terrain_modifications: vec![],
+        chunk_voxels: vec![],
+        chunk_data: vec![],
+        entity_meta: vec![],
+        entity_handles: vec![],
+        active_events: vec![],
+    }
+    .compact();
+    compact.structures[0].type_id = 99;
+    assert!(compact.expand().is_none());
+}
+
+#[test]
+fn snapshot_request_defaults_to_protocol_version_one() {
+    let req: CmdRequestSnapshot =
+        serde_json::from_value(serde_json::json!({"x": 0.0, "y": 0.0, "z": 0.0, "radius": 50.0}))
+            .expect("parse");
+    assert_eq!(req.protocol_version, 1);
+}
+
+#[test]
+fn compact_snapshot_carries_terrain_modifications() {
+    let snapshot = WorldSnapshot {
+        active_chunks: vec![],
+        structures: vec![],
+        entities: vec![],
+        environment: None,
+        sea_level: None,
+        terrain_version: TERRAIN_VERSION,
+        terrain_modifications: vec![TerrainModified {
+            revision: 1,
+            center_x: 4.0,
+            center_y: -2.0,
+            radius: 3.0,
+            delta: -1.5,
+            chunk_ids: vec!["0:-1".to_string(), "0:0".to_string()],
+            hole: false,
+        }],
+        chunk_voxels: vec![],
+        chunk_data: vec![],
+        entity_meta: vec![],
+        entity_handles: vec![],
+        active_events: vec![],
+    };
+
+    let expanded = snapshot.compact().expand().expect("indices should resolve");
+    assert_eq!(expanded.terrain_modifications.len(), 1);
+    assert_eq!(
+        expanded.terrain_modifications[0].chunk_ids,
+        vec!["0:-1", "0:0"]
+    );
+    assert_eq!(expanded.terrain_modifications[0].delta, -1.5);
+    assert_eq!(expanded.terrain_version, TERRAIN_VERSION);
+}
+
+#[test]
+fn entity_meta_fields_are_optional() {
+    let meta: EntityMeta =
+        serde_json::from_value(serde_json::json!({"entity_id": "wolf-1"})).expect("parse");
+    assert_eq!(meta.name, None);
+    assert_eq!(meta.health, None);
+    assert!(meta.status.is_empty());
+}
+
+#[test]
+fn transform_omits_intent_seq_until_acknowledged() {
+    let mut transform = EntityTransform {
+        entity_id: "alice".into(),
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+        rotation_y: 0.0,
+        vx: 0.0,
+        vy: 0.0,
+        vz: 0.0,
+        dt: 0.0,
+        last_intent_seq: None,
+    };
+    let v = serde_json::to_value(&transform).expect("serialize");
+    assert!(v.get("last_intent_seq").is_none());
+
+    transform.last_intent_seq = Some(12);
+    let v = serde_json::to_value(&transform).expect("serialize");
+    assert_eq!(v["last_intent_seq"], 12);
+}
+
+#[test]
+fn transform_batch_addresses_entities_by_handle() {
+    let transform = EntityTransform {
+        entity_id: "alice".into(),
+        x: 1.0,
+        y: 2.0,
+        z: 0.0,
+        rotation_y: 0.0,
+        vx: 0.5,
+        vy: 0.0,
+        vz: 0.0,
+        dt: 0.0,
+        last_intent_seq: Some(3),
+    };
+    let batch = EntityTransformBatch {
+        transforms: vec![transform.with_handle(7)],
+        ..Default::default()
+    };
+
+    let v = serde_json::to_value(&batch).expect("serialize");
+    assert_eq!(v["transforms"][0]["handle"], 7);
+    assert!(v["transforms"][0].get("entity_id").is_none());
+    assert!(v.get("deltas").is_none() && v.get("seq").is_none() && v.get("keyframe").is_none());
+
+    let back: EntityTransformBatch = serde_json::from_value(v).expect("deserialize");
+    assert_eq!(back, batch);
+    assert_eq!(back.transforms[0].x, 1.0);
+    assert_eq!(back.transforms[0].last_intent_seq, Some(3));
+}
+
+#[test]
+fn snapshot_without_entity_handles_still_parses() {
+    let handle = EntityHandle {
+        entity_id: "alice".into(),
+        handle: 4,
+    };
+    let v = serde_json::to_value(&handle).expect("serialize");
+    assert_eq!(v, serde_json::json!({"entity_id": "alice", "handle": 4}));
+
+    let snapshot: WorldSnapshot = serde_json::from_value(serde_json::json!({
+        "active_chunks": [],
+        "structures": [],
+        "entities": []
+    }))
+    .expect("legacy snapshot");
+    assert!(snapshot.entity_handles.is_empty());
+}
+
+#[test]
+fn sparse_voxels_are_run_length_encoded() {
+    let mut cells = vec![0u8; 4096];
+    cells[100..110].fill(1);
+    let encoded = VoxelCells::encode(&cells);
+    assert_eq!(encoded, VoxelCells::Rle(vec![[0, 100], [1, 10], [0, 3986]]));
+    assert_eq!(encoded.decode(4096), Some(cells));
+    assert_eq!(encoded.decode(4000), None);
+
+    let noisy: Vec<u8> = (0..64).map(|i| (i % 3) as u8).collect();
+    assert_eq!(VoxelCells::encode(&noisy), VoxelCells::Dense(noisy.clone()));
+
+    let msg = ChunkVoxels {
+        chunk_id: "0:0".into(),
+        cx: 0,
+        cy: 0,
+        origin_z: -8.0,
+        voxel_size: 1.0,
+        dims: [4, 4, 4],
+        cells: VoxelCells::Rle(vec![[0, 64]]),
+    };
+    let v = serde_json::to_value(&msg).expect("serialize");
+    assert_eq!(
+        v["cells"],
+        serde_json::json!({"encoding": "rle", "data": [[0, 64]]})
+    );
+    let back: ChunkVoxels = serde_json::from_value(v).expect("deserialize");
+    assert_eq!(back, msg);
+}
+
+#[test]
+fn chunk_data_is_optional_in_snapshots() {
+    let v = serde_json::json!({"active_chunks": [], "structures": [], "entities": []});
+    let snapshot: WorldSnapshot = serde_json::from_value(v).expect("parse");
+    assert!(snapshot.chunk_data.is_empty());
+    let out = serde_json::to_value(&snapshot).expect("serialize");
+    assert!(out.get("chunk_data").is_none());
+
+    let data = ChunkData {
+        chunk_id: "1:2".into(),
+        cx: 1,
+        cy: 2,
+        lod: 0,
+        resolution: 4,
+        cell_size: 8.0,
+        data: "H4sIAAAAAAAA/wMAAAAAAAAAAAA=".into(),
+    };
+    let back: ChunkData =
+        serde_json::from_value(serde_json::to_value(&data).expect("serialize")).expect("parse");
+    assert_eq!(back, data);
+}
+
+#[test]
+fn height_query_normals_are_opt_in() {
+    let cmd: CmdHeights =
+        serde_json::from_value(serde_json::json!({"points": [[1.0, 2.0]]})).expect("parse");
+    assert!(!cmd.normals);
+
+    let reply = HeightSamples {
+        heights: vec![0.5],
+        normals: vec![],
+    };
+    let v = serde_json::to_value(&reply).expect("serialize");
+    assert!(v.get("normals").is_none());
+}
+
+#[test]
+fn set_heights_token_is_optional() {
+    let cmd: CmdSetHeights =
+        serde_json::from_value(serde_json::json!({"samples": [[1.0, 2.0, 3.5]]})).expect("parse");
+    assert_eq!(cmd.samples, vec![[1.0, 2.0, 3.5]]);
+    assert!(cmd.token.is_none());
+    let v = serde_json::to_value(&cmd).expect("serialize");
+    assert!(v.get("token").is_none());
+}
+
+#[test]
+fn nav_invalidation_cause_is_snake_case() {
+    let msg = NavInvalidated {
+        revision: 3,
+        min_x: -4.0,
+        min_y: 0.0,
+        max_x: 4.0,
+        max_y: 8.0,
+        chunk_ids: vec!["-1:0".to_string(), "0:0".to_string()],
+        cause: NavChangeCause::StructureRemoved,
+    };
+    let v = serde_json::to_value(&msg).expect("serialize");
+    assert_eq!(v["cause"], "structure_removed");
+    let back: NavInvalidated = serde_json::from_value(v).expect("deserialize");
+    assert_eq!(back, msg);
+}
+
+#[test]
+fn entity_emote_omits_missing_ids() {
+    let msg = EntityEmote {
+        entity_id: "alice".to_string(),
+        emote_id: None,
+        sound_id: Some("sfx/laugh".to_string()),
+        x: 1.0,
+        y: 2.0,
+        z: 0.0,
+        listeners: vec!["alice".to_string(), "bob".to_string()],
+    };
+    let v = serde_json::to_value(&msg).expect("serialize");
+    assert!(v.get("emote_id").is_none());
+    let back: EntityEmote = serde_json::from_value(v).expect("deserialize");
+    assert_eq!(back, msg);
+}
+
+#[test]
+fn subjects_are_scoped_by_session_then_shard() {
+    assert_eq!(
+        subjects::session(subjects::CHUNK_ACTIVATED, "alpha"),
+        "world.alpha.chunk.activated"
+    );
+    assert_eq!(
+        subjects::session(subjects::INTENT_MOVE, "alpha"),
+        "intent.alpha.move"
+    );
+
+    let scoped = subjects::Namespace::new(Some("alpha"), Some("east"));
+    assert_eq!(
+        scoped.subject(subjects::CHUNK_ACTIVATED),
+        "world.alpha.shard.east.chunk.activated"
+    );
+    assert_eq!(
+        scoped.shared(subjects::SHARD_HANDOFF),
+        "world.alpha.shard.handoff"
+    );
+
+    let legacy = subjects::Namespace::default();
+    assert_eq!(legacy.subject(subjects::ACTION_MOVE), "action.move");
+}
+
+#[test]
+fn scripted_event_omits_empty_payloads() {
+    let json = r#"{"event_id":"drizzle","kind":"weather_front","x":0,"y":0,"weather":"rain"}"#;
+    let event: ScriptedEvent = serde_json::from_str(json).unwrap();
+    assert_eq!(event.duration_s, 0.0);
+    assert!(event.structures.is_empty());
+
+    let value = serde_json::to_value(&event).unwrap();
+    assert_eq!(value["weather"], "rain");
+    assert!(value.get("structures").is_none());
+    assert!(value.get("entities").is_none());
+    assert_eq!(subjects::EVENT_STARTED, "world.event.started");
+}
+
+#[test]
+fn participant_joined_defaults_to_a_fresh_entity() {
+    let joined: ParticipantJoined = serde_json::from_value(serde_json::json!({
+        "id": "alice",
+        "resume_token": "abc"
+    }))
+    .expect("deserialize");
+    assert!(!joined.resumed);
+    assert_eq!(joined.resume_token, "abc");
+}
+
+#[test]
+fn pick_hit_tags_its_target_kind() {
+    let hit = PickHit {
+        target: PickTarget::Entity {
+            entity_id: "bob".into(),
+        },
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+        distance: 4.0,
+    };
+    let v = serde_json::to_value(&hit).expect("serialize");
+    assert_eq!(
+        v,
+        serde_json::json!({"kind": "entity", "entity_id": "bob", "x": 1.0, "y": 2.0, "z": 3.0, "distance": 4.0})
+    );
+    assert_eq!(
+        serde_json::from_value::<PickHit>(v).expect("deserialize"),
+        hit
+    );
+
+    let terrain: PickHit = serde_json::from_value(serde_json::json!({
+        "kind": "terrain", "x": 0.0, "y": 0.0, "z": 0.0, "distance": 1.0
+    }))
+    .expect("terrain hit");
+    assert_eq!(terrain.target, PickTarget::Terrain);
+}
+
+#[test]
+fn command_errors_round_trip_through_the_reply_message() {
+    let error = WorldCmdError::new(WorldCmdErrorCode::LimitExceeded, "too many points")
+        .with_details(serde_json::json!({"max": 1024}));
+    let text = error.encode();
+    let v: serde_json::Value = serde_json::from_str(&text).expect("json");
+    assert_eq!(
+        v,
+        serde_json::json!({"code": "limit_exceeded", "message": "too many points", "details": {"max": 1024}})
+    );
+    assert_eq!(WorldCmdError::decode(&text), error);
+
+    let bare = WorldCmdError::new(WorldCmdErrorCode::Unauthorized, "admin token rejected");
+    assert!(!bare.encode().contains("details"));
+
+    // Free-text failures from older servers.
+    let legacy = WorldCmdError::decode("emote failed: Unknown entity 'x'");
+    assert_eq!(legacy.code, WorldCmdErrorCode::Failed);
+    assert_eq!(legacy.message, "emote failed: Unknown entity 'x'");
+}
+
+#[test]
+fn place_structure_intent_defaults_rotation() {
+    let intent: IntentPlaceStructure = serde_json::from_value(serde_json::json!({
+        "type_id": "props/crate", "x": 1.0, "y": 2.0, "z": 3.0
+    }))
+    .expect("intent");
+    assert_eq!(intent.rotation_y, 0.0);
+    assert_eq!(subjects::INTENT_PLACE_STRUCTURE, "intent.place_structure");
+    assert_eq!(subjects::ACTION_PLACE_STRUCTURE, "action.place_structure");
+}
+
+#[test]
+fn heartbeat_defaults_timescale_to_real_time() {
+    let legacy: WorldHeartbeat =
+        serde_json::from_value(serde_json::json!({"instance_id": "world", "frame": 7}))
+            .expect("heartbeat");
+    assert_eq!(legacy.timescale, 1.0);
+}
+
+#[test]
+fn structures_default_to_yaw_only_orientation() {
+    let legacy: StructureSpawned = serde_json::from_value(serde_json::json!({
+        "structure_id": "hut", "type_id": "buildings/hut",
+        "x": 1.0, "y": 2.0, "z": 3.0, "rotation_y": 0.5
+    }))
+    .expect("legacy structure");
+    assert_eq!(legacy, structure("hut", "buildings/hut"));
+    // Identity pitch, roll and scale stay off the wire.
+    let v = serde_json::to_value(&legacy).expect("serialize");
+    assert!(v.get("pitch").is_none() && v.get("roll").is_none() && v.get("scale").is_none());
+
+    let ramp = StructureSpawned {
+        pitch: 0.3,
+        scale: [2.0, 1.0, 0.5],
+        ..structure("ramp", "props/ramp")
+    };
+    let v = serde_json::to_value(&ramp).expect("serialize");
+    assert_eq!(v["scale"], serde_json::json!([2.0, 1.0, 0.5]));
+    assert!(v.get("roll").is_none());
+    assert_eq!(
+        serde_json::from_value::<StructureSpawned>(v).expect("deserialize"),
+        ramp
+    );
+}
+
+#[test]
+fn structure_state_changes_round_trip() {
+    let changed = StructureStateChanged {
+        structure_id: "gate".into(),
+        previous: "closed".into(),
+        state: "open".into(),
+        verb: "open".into(),
+        actor_id: None,
+    };
+    let v = serde_json::to_value(&changed).expect("serialize");
+    assert!(v.get("actor_id").is_none());
+    assert_eq!(
+        serde_json::from_value::<StructureStateChanged>(v).expect("deserialize"),
+        changed
+    );
+
+    // Only interactive structures carry a state.
+    let plain = serde_json::to_value(structure("hut", "buildings/hut")).expect("serialize");
+    assert!(plain.get("state").is_none());
+    let gate = StructureSpawned {
+        state: Some("open".into()),
+        ..structure("gate", "props/gate")
+    };
+    let v = serde_json::to_value(&gate).expect("serialize");
+    assert_eq!(v["state"], "open");
+    assert_eq!(
+        subjects::STRUCTURE_STATE_CHANGED,
+        "world.structure.state_changed"
+    );
+}
+
+#[test]
+fn structure_updates_share_the_spawned_layout() {
+    let updated = StructureUpdated {
+        structure: structure("rock", "props/rock"),
+    };
+    let v = serde_json::to_value(&updated).expect("serialize");
+    assert_eq!(v["structure_id"], "rock");
+    assert_eq!(v["x"], 1.0);
+    assert!(v.get("structure").is_none());
+    assert_eq!(
+        serde_json::from_value::<StructureUpdated>(v).expect("deserialize"),
+        updated
+    );
+
+    let cmd: CmdAdmin = serde_json::from_value(serde_json::json!({
+        "action": "update_structure",
+        "structure_id": "rock",
+        "update": { "x": 4.0, "metadata": { "owner": null } }
+    }))
+    .expect("parse");
+    let AdminAction::UpdateStructure {
+        structure_id,
+        update,
+    } = cmd.action
+    else {
+        panic!("wrong action");
+    };
+    assert_eq!(structure_id, "rock");
+    assert_eq!((update.x, update.y), (Some(4.0), None));
+    assert!(update.metadata["owner"].is_null());
+    assert_eq!(subjects::STRUCTURE_UPDATED, "world.structure.updated");
+}
+
+#[test]
+fn interaction_results_omit_what_they_lack() {
+    let failed = InteractionResult {
+        actor_id: "alice".into(),
+        target_id: "nobody".into(),
+        target_kind: None,
+        verb: None,
+        success: false,
+        error: Some("Unknown entity 'nobody'".into()),
+        data: serde_json::Value::Null,
+    };
+    let v = serde_json::to_value(&failed).expect("serialize");
+    assert_eq!(
+        v,
+        serde_json::json!({
+            "actor_id": "alice", "target_id": "nobody", "success": false,
+            "error": "Unknown entity 'nobody'"
+        })
+    );
+
+    let talked = InteractionResult {
+        target_kind: Some(InteractionTarget::Entity),
+        success: true,
+        error: None,
+        data: serde_json::json!({ "line": "hello" }),
+        ..failed
+    };
+    let v = serde_json::to_value(&talked).expect("serialize");
+    assert_eq!(v["target_kind"], "entity");
+    assert_eq!(
+        serde_json::from_value::<InteractionResult>(v).expect("deserialize"),
+        talked
+    );
+    assert_eq!(subjects::INTERACTION_RESULT, "world.interaction.result");
+}
+
+#[test]
+fn cell_subjects_scope_by_interest_cell() {
+    assert_eq!(
+        subjects::cell(3, -1, subjects::ENTITY_TRANSFORM),
+        "world.cell.3.-1.entity.transform"
+    );
+    assert_eq!(subjects::cell_of(-0.5, 127.9, 64.0), (-1, 1));
+    assert_eq!(subjects::INTEREST_SUBJECTS, "world.interest.subjects");
+
+    let msg: InterestSubjects = serde_json::from_value(serde_json::json!({
+        "participant_id": "alice",
+        "cell_size": 64.0,
+        "subscribe": ["world.cell.0.0.>"],
+    }))
+    .expect("deserialize");
+    assert!(msg.unsubscribe.is_empty());
+}
+
+#[test]
+fn raycast_hits_tag_their_target_and_keep_the_ray_hit_fields() {
+    let cmd: CmdRaycast = serde_json::from_value(serde_json::json!({
+        "x": 0.0, "y": 0.0, "z": 5.0, "dx": 1.0, "dy": 0.0, "dz": 0.0, "max_dist": 10.0
+    }))
+    .expect("old raycast payload");
+    assert!(cmd.exclude.is_none());
+    assert!(!cmd.terrain_only);
+
+    let hit = RaycastHit {
+        target: PickTarget::Structure {
+            structure_id: "hut".into(),
+        },
+        position: janet_world::types::Vec3::new(1.0, 2.0, 3.0),
+        normal: janet_world::types::Vec3::new(0.0, -1.0, 0.0),
+        distance: 4.0,
+    };
+    let v = serde_json::to_value(&hit).expect("serialize");
+    assert_eq!(v["kind"], "structure");
+    assert_eq!(v["structure_id"], "hut");
+    assert_eq!(v["normal"]["y"], -1.0);
+    assert_eq!(v["distance"], 4.0);
+    assert_eq!(
+        serde_json::from_value::<RaycastHit>(v).expect("deserialize"),
+        hit
+    );
 }
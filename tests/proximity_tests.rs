@@ -0,0 +1,99 @@
+//! Proximity tracker tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::proximity::ProximityTracker;
+    use janet_world::types::Vec3;
+    use std::collections::HashMap;
+
+    fn positions(entries: &[(&str, f32, f32)]) -> HashMap<String, Vec3> {
+        entries
+            .iter()
+            .map(|(id, x, y)| (id.to_string(), Vec3::new(*x, *y, 0.0)))
+            .collect()
+    }
+
+    #[test]
+    fn pair_entering_range_emits_single_event() {
+        let mut t = ProximityTracker::new(10.0, 0);
+
+        let (entered, exited) = t.update(1, &positions(&[("bob", 0.0, 0.0), ("alice", 5.0, 0.0)]));
+        assert_eq!(entered.len(), 1);
+        assert!(exited.is_empty());
+        assert_eq!(entered[0].participant_a, "alice");
+        assert_eq!(entered[0].participant_b, "bob");
+
+        // Still in range → no repeat.
+        let (entered, _) = t.update(2, &positions(&[("bob", 0.0, 0.0), ("alice", 6.0, 0.0)]));
+        assert!(entered.is_empty());
+    }
+
+    #[test]
+    fn neighbouring_buckets_are_checked() {
+        let mut t = ProximityTracker::new(10.0, 0);
+        // Straddle a bucket boundary.
+        let (entered, _) = t.update(1, &positions(&[("a", 10.5, 0.0), ("b", 12.0, 0.0)]));
+        assert_eq!(entered.len(), 1);
+        let (entered, _) = t.update(2, &positions(&[("c", -1.0, -1.0), ("d", 1.0, 1.0)]));
+        assert_eq!(entered.len(), 1);
+    }
+
+    #[test]
+    fn hysteresis_delays_exit() {
+        let mut t = ProximityTracker::new(10.0, 0);
+        t.update(1, &positions(&[("a", 0.0, 0.0), ("b", 9.0, 0.0)]));
+
+        // Just outside radius but inside exit band → still together.
+        let (_, exited) = t.update(2, &positions(&[("a", 0.0, 0.0), ("b", 10.5, 0.0)]));
+        assert!(exited.is_empty());
+
+        let (_, exited) = t.update(3, &positions(&[("a", 0.0, 0.0), ("b", 30.0, 0.0)]));
+        assert_eq!(exited.len(), 1);
+        assert_eq!(t.in_range_count(), 0);
+    }
+
+    #[test]
+    fn cooldown_rate_limits_transitions() {
+        let mut t = ProximityTracker::new(10.0, 5);
+        t.update(1, &positions(&[("a", 0.0, 0.0), ("b", 1.0, 0.0)]));
+
+        // Leaves immediately, but the pair changed state too recently.
+        let (_, exited) = t.update(2, &positions(&[("a", 0.0, 0.0), ("b", 50.0, 0.0)]));
+        assert!(exited.is_empty());
+
+        let (_, exited) = t.update(6, &positions(&[("a", 0.0, 0.0), ("b", 50.0, 0.0)]));
+        assert_eq!(exited.len(), 1);
+    }
+
+    #[test]
+    fn departed_participant_exits_immediately() {
+        let mut t = ProximityTracker::new(10.0, 100);
+        t.update(1, &positions(&[("a", 0.0, 0.0), ("b", 1.0, 0.0)]));
+
+        let (_, exited) = t.update(2, &positions(&[("a", 0.0, 0.0)]));
+        assert_eq!(exited.len(), 1);
+    }
+
+    #[test]
+    fn zero_radius_disables_tracking() {
+        let mut t = ProximityTracker::new(0.0, 0);
+        let (entered, exited) = t.update(1, &positions(&[("a", 0.0, 0.0), ("b", 0.0, 0.0)]));
+        assert!(entered.is_empty() && exited.is_empty());
+    }
+
+    #[test]
+    fn bounding_radii_bring_large_entities_into_range_sooner() {
+        // Centres 25 apart: out of range as points, a 9-unit gap for two
+        // radius-8 giants (even across buckets sized for points).
+        let giants = |_: &str| 8.0;
+        let mut points = ProximityTracker::new(10.0, 0);
+        let (entered, _) = points.update(1, &positions(&[("a", 0.0, 0.0), ("b", 25.0, 0.0)]));
+        assert!(entered.is_empty());
+
+        let mut sized = ProximityTracker::new(10.0, 0);
+        let (entered, _) =
+            sized.update_sized(1, &positions(&[("a", 0.0, 0.0), ("b", 25.0, 0.0)]), giants);
+        assert_eq!(entered.len(), 1);
+        assert!((entered[0].distance - 9.0).abs() < 1e-4);
+    }
+}
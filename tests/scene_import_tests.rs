@@ -0,0 +1,139 @@
+//! Scene import tests
+
+#[cfg(test)]
+mod tests {
+    use janet_operations::physics::types::ColliderShape;
+    use janet_world::scene_import::{from_gltf, from_scene_list, import_scene, SceneImportError};
+    use janet_world::structure::StructureMetaError;
+    use janet_world::types::Vec3;
+    use std::f32::consts::FRAC_PI_2;
+
+    /// Quaternion of `angle` radians about glTF's up axis.
+    fn yaw(angle: f32) -> [f32; 4] {
+        [0.0, (angle / 2.0).sin(), 0.0, (angle / 2.0).cos()]
+    }
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            (actual.x - expected.x).abs() < 1e-4
+                && (actual.y - expected.y).abs() < 1e-4
+                && (actual.z - expected.z).abs() < 1e-4,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    fn assert_box(shape: &ColliderShape, width: f32, height: f32) {
+        match shape {
+            ColliderShape::Box {
+                width: w,
+                height: h,
+            } => assert!(
+                (w - width).abs() < 1e-4 && (h - height).abs() < 1e-4,
+                "{}x{} != {}x{}",
+                w,
+                h,
+                width,
+                height
+            ),
+            other => panic!("expected a box, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn gltf_mesh_nodes_become_structures() {
+        let gltf = serde_json::json!({
+            "scene": 0,
+            "scenes": [{ "nodes": [0, 2] }],
+            "nodes": [
+                { "name": "village", "translation": [10.0, 0.0, -5.0],
+                  "rotation": yaw(FRAC_PI_2), "children": [1] },
+                { "name": "hut-1", "mesh": 0, "translation": [2.0, 1.0, 0.0],
+                  "extras": { "type_id": "buildings/hut", "tags": ["shelter"], "owner": "alice" } },
+                { "mesh": 1, "scale": [2.0, 2.0, 2.0] },
+            ],
+            "meshes": [
+                { "name": "hut", "primitives": [{ "attributes": { "POSITION": 0 } }] },
+                { "name": "props/rock", "primitives": [{ "attributes": { "POSITION": 1 } }] },
+            ],
+            "accessors": [
+                { "min": [-1.0, 0.0, -0.5], "max": [1.0, 2.0, 0.5] },
+                { "min": [-0.5, 0.0, -0.5], "max": [0.5, 1.0, 0.5] },
+            ],
+        });
+        let structures = from_gltf(&serde_json::to_vec(&gltf).unwrap()).unwrap();
+        assert_eq!(structures.len(), 2);
+
+        // The parent's turn carries the child's offset with it.
+        let hut = &structures[0];
+        assert_eq!(hut.id, "hut-1");
+        assert_near(hut.position, Vec3::new(10.0, 7.0, 1.0));
+        assert!((hut.rotation_y - FRAC_PI_2).abs() < 1e-4);
+        assert!(hut.pitch.abs() < 1e-4 && hut.roll.abs() < 1e-4);
+        assert_box(&hut.collider, 2.0, 1.0);
+        assert_eq!(hut.tags, ["shelter"]);
+        assert_eq!(hut.metadata["type_id"], "buildings/hut");
+        assert_eq!(hut.metadata["owner"], "alice");
+        assert!(!hut.metadata.contains_key("tags"));
+
+        let rock = &structures[1];
+        assert_eq!(rock.id, "node-2");
+        assert_eq!(rock.metadata["type_id"], "props/rock");
+        assert_eq!(rock.scale, [2.0, 2.0, 2.0]);
+        assert_box(&rock.collider, 2.0, 2.0);
+    }
+
+    #[test]
+    fn scene_lists_use_the_gltf_conventions() {
+        let list = serde_json::json!({
+            "nodes": [
+                { "name": "wall", "type_id": "buildings/wall",
+                  "translation": [0.0, 1.5, -4.0], "rotation": yaw(FRAC_PI_2),
+                  "size": [6.0, 3.0, 0.5], "tags": ["barrier"] },
+            ],
+        });
+        let structures = from_scene_list(&serde_json::to_vec(&list).unwrap()).unwrap();
+        let wall = &structures[0];
+        assert_near(wall.position, Vec3::new(0.0, 4.0, 1.5));
+        assert!((wall.rotation_y - FRAC_PI_2).abs() < 1e-4);
+        assert_box(&wall.collider, 6.0, 0.5);
+        assert_eq!(wall.tags, ["barrier"]);
+        assert_eq!(wall.metadata["type_id"], "buildings/wall");
+    }
+
+    #[test]
+    fn bad_scenes_are_rejected() {
+        let node = serde_json::json!({ "name": "a", "type_id": "x", "size": [1.0, 1.0, 1.0] });
+        let dup = from_scene_list(
+            &serde_json::to_vec(&serde_json::json!({ "nodes": [node, node] })).unwrap(),
+        );
+        assert!(matches!(dup, Err(SceneImportError::Duplicate(id)) if id == "a"));
+
+        // Neither extras nor the mesh name give a type_id.
+        let untyped = serde_json::json!({
+            "nodes": [{ "name": "blob", "mesh": 0 }],
+            "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+            "accessors": [{ "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 1.0] }],
+        });
+        assert!(matches!(
+            from_gltf(&serde_json::to_vec(&untyped).unwrap()),
+            Err(SceneImportError::Metadata(StructureMetaError::MissingTypeId(id))) if id == "blob"
+        ));
+
+        let unbounded = serde_json::json!({
+            "nodes": [{ "name": "blob", "mesh": 0 }],
+            "meshes": [{ "name": "x", "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+            "accessors": [{}],
+        });
+        assert!(matches!(
+            from_gltf(&serde_json::to_vec(&unbounded).unwrap()),
+            Err(SceneImportError::Gltf(_))
+        ));
+
+        assert!(matches!(
+            import_scene("village.glb"),
+            Err(SceneImportError::Unsupported(_))
+        ));
+    }
+}
@@ -0,0 +1,169 @@
+//! Scripted event scheduler tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::protocol::ScriptedEventEnded;
+    use janet_world::scheduler::{
+        ClockTime, EventChange, EventSchedule, EventScheduler, ScheduleError, SchedulerClock,
+    };
+
+    const DAY: f32 = 1200.0;
+
+    fn scheduler(events: serde_json::Value) -> Result<EventScheduler, ScheduleError> {
+        let schedule: EventSchedule =
+            serde_json::from_value(serde_json::json!({ "events": events })).unwrap();
+        EventScheduler::new(schedule)
+    }
+
+    /// Clock at `elapsed_s` into a world whose day started at midnight.
+    fn clock(elapsed_s: f64) -> SchedulerClock {
+        SchedulerClock {
+            elapsed_s,
+            time_of_day_s: (elapsed_s as f32).rem_euclid(DAY),
+            day_length_s: DAY,
+            wall_clock_s: 1_700_000_000.0 + elapsed_s,
+        }
+    }
+
+    fn started(changes: &[EventChange]) -> Vec<&str> {
+        changes
+            .iter()
+            .filter_map(|c| match c {
+                EventChange::Started(e) => Some(e.event_id.as_str()),
+                EventChange::Ended(_) => None,
+            })
+            .collect()
+    }
+
+    // -----------------------------------------------------------------------
+    // Definitions
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn clock_times_parse_and_round_trip() {
+        let t: ClockTime = serde_json::from_str("\"22:30\"").unwrap();
+        assert_eq!(t.day_fraction(), 22.5 / 24.0);
+        assert_eq!(serde_json::to_string(&t).unwrap(), "\"22:30\"");
+        let t: ClockTime = serde_json::from_str("\"06:00:15\"").unwrap();
+        assert_eq!(t.to_string(), "06:00:15");
+
+        for bad in ["\"24:00\"", "\"12:60\"", "\"noon\"", "\"12\""] {
+            assert!(serde_json::from_str::<ClockTime>(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        let dup = scheduler(serde_json::json!([
+            { "event_id": "a", "kind": "x", "x": 0, "y": 0 },
+            { "event_id": "a", "kind": "y", "x": 0, "y": 0 },
+        ]));
+        assert!(matches!(dup, Err(ScheduleError::Duplicate(id)) if id == "a"));
+
+        let period = scheduler(serde_json::json!([
+            { "event_id": "a", "kind": "x", "x": 0, "y": 0, "trigger": { "every_s": 0 } },
+        ]));
+        assert!(matches!(period, Err(ScheduleError::Invalid { .. })));
+
+        let duration = scheduler(serde_json::json!([
+            { "event_id": "a", "kind": "x", "x": 0, "y": 0, "duration_s": -1 },
+        ]));
+        assert!(matches!(duration, Err(ScheduleError::Invalid { .. })));
+    }
+
+    // -----------------------------------------------------------------------
+    // Triggers
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn game_time_fires_once_per_day_when_passed() {
+        // 18:00 on a 1200 s day is 900 s in.
+        let mut s = scheduler(serde_json::json!([
+            { "event_id": "dusk", "kind": "x", "x": 0, "y": 0,
+              "trigger": { "game_time": "18:00" } },
+        ]))
+        .unwrap();
+
+        // The first poll only starts the clocks, even past the trigger.
+        assert!(s.poll(clock(950.0)).is_empty());
+        assert!(started(&s.poll(clock(1000.0))).is_empty());
+        // Wraps past midnight without firing, then fires the next evening.
+        assert!(started(&s.poll(clock(1300.0))).is_empty());
+        assert_eq!(started(&s.poll(clock(2100.0))), ["dusk"]);
+        assert!(started(&s.poll(clock(2110.0))).is_empty());
+    }
+
+    #[test]
+    fn game_time_fires_across_midnight() {
+        let mut s = scheduler(serde_json::json!([
+            { "event_id": "midnight", "kind": "x", "x": 0, "y": 0,
+              "trigger": { "game_time": "00:00:30" } },
+        ]))
+        .unwrap();
+        s.poll(clock(1190.0));
+        assert_eq!(started(&s.poll(clock(1210.0))), ["midnight"]);
+    }
+
+    #[test]
+    fn every_s_repeats_and_disabled_events_wait() {
+        let mut s = scheduler(serde_json::json!([
+            { "event_id": "drizzle", "kind": "weather_front", "x": 0, "y": 0,
+              "trigger": { "every_s": 100 } },
+            { "event_id": "off", "kind": "x", "x": 0, "y": 0, "enabled": false,
+              "trigger": { "every_s": 100 } },
+        ]))
+        .unwrap();
+        s.poll(clock(0.0));
+        assert!(started(&s.poll(clock(99.0))).is_empty());
+        assert_eq!(started(&s.poll(clock(100.0))), ["drizzle"]);
+        assert!(started(&s.poll(clock(150.0))).is_empty());
+
+        s.set_enabled("off", true).unwrap();
+        assert_eq!(started(&s.poll(clock(200.0))), ["drizzle", "off"]);
+    }
+
+    // -----------------------------------------------------------------------
+    // Duration and manual control
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn events_end_after_their_duration() {
+        let mut s = scheduler(serde_json::json!([
+            { "event_id": "raid", "kind": "invasion", "x": 5, "y": 5, "duration_s": 30 },
+        ]))
+        .unwrap();
+        s.poll(clock(0.0));
+        let raid = s.trigger("raid", 10.0).unwrap();
+        assert_eq!(raid.kind, "invasion");
+        assert!(s.is_active("raid"));
+        assert_eq!(s.active().count(), 1);
+
+        assert!(s.poll(clock(39.0)).is_empty());
+        assert_eq!(
+            s.poll(clock(40.0)),
+            [EventChange::Ended(ScriptedEventEnded {
+                event_id: "raid".into(),
+                cancelled: false,
+            })]
+        );
+        assert!(!s.is_active("raid"));
+    }
+
+    #[test]
+    fn manual_end_cancels_active_events_only() {
+        let mut s = scheduler(serde_json::json!([
+            { "event_id": "raid", "kind": "invasion", "x": 0, "y": 0, "duration_s": 30 },
+        ]))
+        .unwrap();
+        assert!(matches!(s.end("raid"), Err(ScheduleError::NotActive(_))));
+        assert!(matches!(
+            s.trigger("nope", 0.0),
+            Err(ScheduleError::Unknown(_))
+        ));
+
+        s.trigger("raid", 0.0).unwrap();
+        let ended = s.end("raid").unwrap();
+        assert!(ended.cancelled);
+        assert!(!s.is_active("raid"));
+    }
+}
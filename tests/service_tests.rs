@@ -2,16 +2,46 @@
 
 #[cfg(test)]
 mod tests {
-    use janet_operations::physics::{types::PhysicsRegistryConfig, PhysicsRegistry};
+    use janet_operations::physics::{
+        types::{
+            BodyParams, ColliderShape, OntologyId, PhysicsRegistryConfig, Rapier2DConfig,
+            SimulationMetadata, SimulationType, Tier, Transform,
+        },
+        PhysicsRegistry, Rapier2DSimulation,
+    };
     use janet_world::{
-        service::WorldService,
-        structure::World,
-        terrain::HeightmapTerrain,
-        types::{Vec3, WorldServiceConfig},
+        behaviour::Behaviour,
+        entity::{EntityError, EntitySpec},
+        hibernation::HibernationStore,
+        interaction::Interaction,
+        protocol::{
+            CmdEmote, CmdStructures, EntityMeta, IntentInteract, IntentMove, IntentPlaceStructure,
+            InteractionTarget, NavChangeCause, PickTarget, StructureUpdate, Weather,
+        },
+        service::{
+            InteractError, PlacementError, WorldService, MAX_DEFORM_RADIUS,
+            MAX_HEIGHT_QUERY_POINTS, MAX_SET_HEIGHT_SAMPLES,
+        },
+        structure::{StructureInstance, World},
+        terrain::{ChunkDescriptor, HeightmapTerrain, TerrainSource},
+        types::{
+            AfkConfig, BuildableStructure, CellCoord, CellRegion, DropReason, EdgeFill, Vec3,
+            WorldBounds, WorldServiceConfig,
+        },
     };
-    use parking_lot::RwLock;
+    use parking_lot::{Mutex, RwLock};
+    use std::any::Any;
+    use std::collections::{HashMap, HashSet};
     use std::sync::Arc;
 
+    /// A `buildings/hut` with a circle collider.
+    fn structure(id: &str, position: Vec3, radius: f32) -> StructureInstance {
+        let mut s = StructureInstance::new(id, position, ColliderShape::Circle { radius });
+        s.metadata
+            .insert("type_id".into(), serde_json::json!("buildings/hut"));
+        s
+    }
+
     fn make_service(radius: i32) -> WorldService {
         let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
         let world = Arc::new(World::new(terrain));
@@ -46,6 +76,97 @@ mod tests {
         assert_eq!(svc.participant_count(), 0);
     }
 
+    /// Departed participants can resume for 3 ticks (0.1 s at 30 Hz).
+    fn make_resume_service(resume_grace_s: f32) -> WorldService {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let world = Arc::new(World::new(terrain));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            physics_dt: 1.0 / 30.0,
+            resume_grace_s,
+            ..Default::default()
+        };
+        WorldService::new(config, physics, world)
+    }
+
+    #[test]
+    fn reconnect_with_resume_token_keeps_the_entity() {
+        let mut svc = make_resume_service(0.1);
+        let joined = svc.join_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0), None);
+        assert!(!joined.resumed);
+        svc.apply_move_action("alice", 2.0, 0.0, 0.0)
+            .expect("known participant");
+        let moved = svc.participant_position("alice").expect("alice");
+        let handle = svc.entity_handle("alice");
+
+        svc.participant_left("alice");
+        assert!(svc.is_departed("alice"));
+        assert_eq!(svc.stats().departed_participants, 1);
+        let _ = svc.tick();
+
+        // A new connection id, somewhere else: the old entity comes back.
+        let rejoined = svc.join_participant(
+            "alice-2".into(),
+            Vec3::new(50.0, 50.0, 0.0),
+            Some(&joined.resume_token),
+        );
+        assert!(rejoined.resumed);
+        assert_eq!(rejoined.id, "alice");
+        assert_ne!(rejoined.resume_token, joined.resume_token);
+        assert_eq!(svc.participant_position("alice"), Some(moved));
+        assert_eq!(svc.entity_handle("alice"), handle);
+        assert_eq!(svc.participant_position("alice-2"), None);
+        assert!(!svc.is_departed("alice"));
+
+        // Tokens are single use.
+        let again = svc.join_participant(
+            "alice-3".into(),
+            Vec3::new(0.0, 0.0, 0.0),
+            Some(&joined.resume_token),
+        );
+        assert!(!again.resumed);
+        assert_eq!(again.id, "alice-3");
+    }
+
+    #[test]
+    fn departed_participants_expire_after_the_grace_window() {
+        let mut svc = make_resume_service(0.1);
+        let joined = svc.join_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0), None);
+        svc.participant_left("alice");
+        // Ticks fail without a physics simulation; expiry runs before
+        // streaming, so it still happens.
+        for _ in 0..2 {
+            let _ = svc.tick();
+        }
+        assert_eq!(svc.participant_count(), 1);
+
+        let _ = svc.tick();
+        assert_eq!(svc.participant_count(), 0);
+        assert_eq!(svc.stats().departed_participants, 0);
+
+        let late = svc.join_participant(
+            "alice".into(),
+            Vec3::new(5.0, 0.0, 0.0),
+            Some(&joined.resume_token),
+        );
+        assert!(!late.resumed);
+        assert_eq!(
+            svc.participant_position("alice"),
+            Some(Vec3::new(5.0, 0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn leaving_without_a_grace_window_removes_the_entity() {
+        let mut svc = make_resume_service(0.0);
+        svc.join_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0), None);
+        svc.participant_left("alice");
+        assert_eq!(svc.participant_count(), 0);
+        assert!(!svc.is_departed("alice"));
+    }
+
     // -----------------------------------------------------------------------
     // Stats
     // -----------------------------------------------------------------------
@@ -77,6 +198,22 @@ mod tests {
         assert_eq!(stats_after.total_ticks, 1);
     }
 
+    #[test]
+    fn stats_report_per_tick_event_fanout() {
+        let mut svc = make_service(0);
+        svc.tick().expect("tick");
+        svc.tick().expect("tick");
+
+        let fanout = svc.stats().fanout;
+        assert_eq!(fanout.window_ticks, 2);
+        // Environment goes out on the first tick only.
+        assert_eq!(fanout.environment.last, 0);
+        assert_eq!(fanout.environment.max, 1);
+        assert_eq!(fanout.environment.total, 1);
+        assert_eq!(fanout.total.total, 1);
+        assert_eq!(fanout.activated.total, 0);
+    }
+
     // -----------------------------------------------------------------------
     // Determinism – two services with identical seeds produce identical cell sets
     // -----------------------------------------------------------------------
@@ -94,6 +231,7 @@ mod tests {
     // -----------------------------------------------------------------------
 
     #[test]
+    #[allow(clippy::neg_multiply)]
     fn apply_move_action_updates_position_with_fallback_integration() {
         let mut svc = make_service(2);
         svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
@@ -115,10 +253,2160 @@ mod tests {
         assert!((alice.y - (-1.0 * dt)).abs() < 1e-6);
     }
 
+    #[test]
+    fn acknowledged_intent_seq_never_goes_backwards() {
+        let mut svc = make_service(1);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!(svc.last_intent_seq("alice"), None);
+
+        svc.acknowledge_intent("alice", 7);
+        svc.acknowledge_intent("alice", 5);
+        assert_eq!(svc.last_intent_seq("alice"), Some(7));
+
+        svc.acknowledge_intent("ghost", 1);
+        assert_eq!(svc.last_intent_seq("ghost"), None);
+
+        svc.unregister_participant("alice");
+        assert_eq!(svc.last_intent_seq("alice"), None);
+    }
+
+    #[test]
+    fn entity_handles_are_stable_and_never_reused() {
+        let mut svc = make_service(1);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        svc.register_participant("bob".into(), Vec3::new(5.0, 0.0, 0.0));
+        assert_eq!(svc.entity_handle("alice"), Some(0));
+        assert_eq!(svc.entity_handle("bob"), Some(1));
+
+        // Re-registering moves the participant but keeps its handle.
+        svc.register_participant("bob".into(), Vec3::new(6.0, 0.0, 0.0));
+        assert_eq!(svc.entity_handle("bob"), Some(1));
+
+        svc.unregister_participant("alice");
+        assert_eq!(svc.entity_handle("alice"), None);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!(svc.entity_handle("alice"), Some(2));
+
+        let handles: Vec<_> = svc
+            .build_snapshot("test")
+            .entity_handles
+            .into_iter()
+            .map(|h| (h.entity_id.to_string(), h.handle))
+            .collect();
+        assert_eq!(
+            handles,
+            vec![("bob".to_string(), 1), ("alice".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn sea_level_blocks_walking_downhill_into_the_sea() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        // A spot where the ground falls away to the east.
+        let h = |x: f32| terrain.height_at(x, 0.0);
+        let x = (0..256)
+            .map(|i| i as f32)
+            .find(|&x| h(x - 1.0) >= h(x) && h(x + 1.0) < h(x))
+            .expect("slope");
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            activation_radius: 1,
+            physics_dt: 1.0 / 30.0,
+            sea_level: Some(h(x)),
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(config, physics, Arc::new(World::new(terrain.clone())));
+        svc.register_participant("alice".into(), Vec3::new(x, 0.0, 0.0));
+
+        // 30 units/s for one 1/30 s step: one unit east (into the sea).
+        svc.apply_move_action("alice", 30.0, 0.0, 0.0)
+            .expect("move");
+        assert_eq!(svc.participant_position("alice").unwrap().x, x);
+
+        svc.apply_move_action("alice", -30.0, 0.0, 0.0)
+            .expect("move");
+        assert!((svc.participant_position("alice").unwrap().x - (x - 1.0)).abs() < 1e-4);
+        assert_eq!(svc.build_snapshot("test").sea_level, Some(h(x)));
+    }
+
+    #[test]
+    fn world_bounds_block_walking_off_the_edge() {
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            cell_size: 10.0,
+            physics_dt: 1.0 / 30.0,
+            world_bounds: Some(WorldBounds {
+                cells: CellRegion::around(0, 0, 1),
+                fill: EdgeFill::Cliff,
+                height: 40.0,
+            }),
+            ..Default::default()
+        };
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let mut svc = WorldService::new(config, physics, Arc::new(World::new(terrain)));
+        svc.register_participant("alice".into(), Vec3::new(19.5, 5.0, 0.0));
+
+        // One unit east would leave column 1; north stays inside.
+        svc.apply_move_action("alice", 30.0, 30.0, 0.0)
+            .expect("move");
+        let pos = svc.participant_position("alice").unwrap();
+        assert_eq!(pos.x, 19.5);
+        assert!((pos.y - 6.0).abs() < 1e-4);
+
+        // Someone already outside (restored, teleported) may walk back in.
+        svc.register_participant("bob".into(), Vec3::new(25.0, 5.0, 0.0));
+        svc.apply_move_action("bob", -30.0, 0.0, 0.0).expect("move");
+        assert!((svc.participant_position("bob").unwrap().x - 24.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn ground_below_the_sea_is_not_buildable() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let h = terrain.height_at(5.0, 5.0);
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let service = |sea_level| {
+            let config = WorldServiceConfig {
+                sea_level,
+                ..Default::default()
+            };
+            WorldService::new(
+                config,
+                physics.clone(),
+                Arc::new(World::new(terrain.clone())),
+            )
+        };
+        assert!(service(None).is_buildable(5.0, 5.0, f32::INFINITY));
+        assert!(service(Some(h)).is_buildable(5.0, 5.0, f32::INFINITY));
+        assert!(!service(Some(h + 0.01)).is_buildable(5.0, 5.0, f32::INFINITY));
+    }
+
     #[test]
     fn apply_move_action_rejects_unknown_participant() {
         let mut svc = make_service(2);
         let result = svc.apply_move_action("missing", 1.0, 0.0, 0.0);
         assert!(result.is_err());
     }
+
+    // -----------------------------------------------------------------------
+    // Streaming cadence
+    // -----------------------------------------------------------------------
+
+    fn make_throttled_service(stream_interval_s: f32) -> WorldService {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            activation_radius: 1,
+            physics_dt: 1.0 / 30.0,
+            stream_interval_s,
+            ..Default::default()
+        };
+        WorldService::new(config, physics, Arc::new(World::new(terrain)))
+    }
+
+    /// A registry with a Rapier simulation, as the server binary sets up.
+    fn rapier_physics() -> Arc<RwLock<PhysicsRegistry>> {
+        let mut registry = PhysicsRegistry::new(PhysicsRegistryConfig::default());
+        let metadata = SimulationMetadata {
+            id: "world-test".to_string(),
+            mandate_id: "_world_test".to_string(),
+            ontology: OntologyId::Custom {
+                id: "Rapier2D".to_string(),
+            },
+            tier: Tier::Decidable,
+            overlays: vec![],
+            simulation_type: SimulationType::Rapier2D,
+            created_at_frame: 0,
+            name: "World Physics".to_string(),
+            description: None,
+            generator_id: None,
+        };
+        registry.set_default_simulation(Box::new(Rapier2DSimulation::new(
+            metadata,
+            Rapier2DConfig::default(),
+        )));
+        Arc::new(RwLock::new(registry))
+    }
+
+    fn body_position(physics: &RwLock<PhysicsRegistry>, id: &str) -> Option<(f32, f32)> {
+        let registry = physics.read();
+        let sim = registry.default_simulation()?;
+        sim.get_transform(id).ok().map(|t| t.position)
+    }
+
+    #[test]
+    fn stream_interval_is_whole_physics_ticks() {
+        assert_eq!(make_throttled_service(0.0).stream_interval_ticks(), 1);
+        assert_eq!(make_throttled_service(0.1).stream_interval_ticks(), 3);
+        assert_eq!(make_throttled_service(1.0).stream_interval_ticks(), 30);
+    }
+
+    #[test]
+    fn physics_ticks_between_streaming_passes_carry_velocity() {
+        let mut svc = make_throttled_service(1.0);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        // The join streams right away (which needs a physics simulation).
+        assert!(svc.tick().is_err());
+        let events = svc.tick().expect("physics tick skips streaming");
+        assert!(events.activated.is_empty());
+        assert_eq!(events.entity_transforms[0].vx, 0.0);
+
+        // 3 units/s for one 1/30 s step, then a physics-only tick.
+        svc.apply_move_action("alice", 3.0, 0.0, 0.0).expect("move");
+        let events = svc.tick().expect("tick");
+        let t = &events.entity_transforms[0];
+        assert!((t.x - 0.1).abs() < 1e-5);
+        assert!((t.vx - 3.0).abs() < 1e-3);
+        assert_eq!(t.vy, 0.0);
+        assert_eq!(t.dt, 1.0 / 30.0);
+
+        // Standing still: zero velocity on the next step.
+        let events = svc.tick().expect("tick");
+        assert_eq!(events.entity_transforms[0].vx, 0.0);
+    }
+
+    #[test]
+    fn idle_entities_only_publish_keep_alives() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            activation_radius: 1,
+            physics_dt: 1.0 / 30.0,
+            stream_interval_s: 1.0,
+            transform_keepalive_s: 0.2,
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(config, physics, Arc::new(World::new(terrain)));
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        assert!(svc.tick().is_err());
+        let events = svc.tick().expect("physics tick skips streaming");
+        assert_eq!(events.entity_transforms.len(), 1);
+
+        // Standing still: nothing but keep-alives (0.2 s = 6 ticks).
+        let mut published = Vec::new();
+        for tick in 1..=12 {
+            if !svc.tick().expect("tick").entity_transforms.is_empty() {
+                published.push(tick);
+            }
+        }
+        assert_eq!(published, [6, 12]);
+
+        // Moving: every tick, then once more as it stops.
+        svc.apply_move_action("alice", 3.0, 0.0, 0.0).expect("move");
+        assert_eq!(svc.tick().expect("tick").entity_transforms.len(), 1);
+        let events = svc.tick().expect("tick");
+        assert_eq!(events.entity_transforms[0].vx, 0.0);
+        assert!(svc.tick().expect("tick").entity_transforms.is_empty());
+
+        // Below the position epsilon (1 cm) doesn't count as moving.
+        svc.apply_move_action("alice", 0.15, 0.0, 0.0)
+            .expect("move");
+        assert!(svc.tick().expect("tick").entity_transforms.is_empty());
+    }
+
+    #[test]
+    fn teleports_snap_without_velocity() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let config = WorldServiceConfig {
+            activation_radius: 1,
+            physics_dt: 1.0 / 30.0,
+            ..Default::default()
+        };
+        let physics = rapier_physics();
+        let mut svc = WorldService::new(config, physics.clone(), Arc::new(World::new(terrain)));
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        svc.tick().expect("tick");
+
+        let target = Vec3::new(40.0, -25.0, 3.0);
+        let transform = svc.teleport("alice", target).expect("teleport");
+        assert_eq!((transform.x, transform.y, transform.z), (40.0, -25.0, 3.0));
+        assert_eq!((transform.vx, transform.vy, transform.vz), (0.0, 0.0, 0.0));
+        assert_eq!(svc.participant_position("alice"), Some(target));
+
+        // A tick later the body is there and the height is kept.
+        svc.tick().expect("tick");
+        assert_eq!(svc.participant_position("alice"), Some(target));
+        assert_eq!(body_position(&physics, "alice"), Some((40.0, -25.0)));
+
+        assert!(svc.teleport("ghost", target).is_err());
+        assert!(svc
+            .teleport("alice", Vec3::new(f32::NAN, 0.0, 0.0))
+            .is_err());
+        assert_eq!(svc.stats().drops.total, 2);
+        assert_eq!(svc.participant_position("alice"), Some(target));
+    }
+
+    #[test]
+    fn view_radius_is_per_participant() {
+        let mut svc = make_service(2);
+        assert_eq!(svc.interest_cell_size(), None);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+
+        svc.set_view_radius("alice", 48.0).expect("view radius");
+        assert_eq!(svc.view_radius("alice"), Some(48.0));
+        assert!(svc.set_view_radius("alice", -1.0).is_err());
+        assert!(svc.set_view_radius("ghost", 10.0).is_err());
+        assert_eq!(svc.stats().drops.total, 2);
+        assert_eq!(svc.view_radius("alice"), Some(48.0));
+
+        svc.unregister_participant("alice");
+        assert_eq!(svc.view_radius("alice"), None);
+    }
+
+    #[test]
+    fn move_intents_are_held_until_the_next_one() {
+        let mut svc = make_throttled_service(1.0);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        assert!(svc.tick().is_err());
+        svc.tick().expect("physics tick skips streaming");
+
+        // A long direction is shortened to max_move_speed (5 m/s).
+        let intent = |dx, dy, seq| IntentMove {
+            dx,
+            dy,
+            dz: 0.0,
+            seq: Some(seq),
+        };
+        svc.set_move_intent("alice", &intent(0.0, 10.0, 7))
+            .expect("intent");
+        svc.tick().expect("tick");
+        svc.tick().expect("tick");
+        let pos = svc.participant_position("alice").unwrap();
+        assert!((pos.y - 2.0 * 5.0 / 30.0).abs() < 1e-4, "{:?}", pos);
+        // Without a body too, the ground decides the height.
+        let terrain = HeightmapTerrain::new(42, 64.0, 16);
+        assert_eq!(pos.z, terrain.height_at(pos.x, pos.y));
+        assert_eq!(svc.last_intent_seq("alice"), Some(7));
+
+        svc.set_move_intent("alice", &intent(0.0, 0.0, 8))
+            .expect("intent");
+        svc.tick().expect("tick");
+        let stopped = svc.participant_position("alice").unwrap();
+        svc.tick().expect("tick");
+        assert_eq!(svc.participant_position("alice"), Some(stopped));
+
+        assert!(svc.set_move_intent("bob", &intent(1.0, 0.0, 1)).is_err());
+        assert!(svc
+            .set_move_intent("alice", &intent(f32::NAN, 0.0, 9))
+            .is_err());
+        assert_eq!(svc.last_intent_seq("alice"), Some(8));
+    }
+
+    #[test]
+    fn walking_into_someone_reports_the_physics_contact() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let config = WorldServiceConfig {
+            activation_radius: 1,
+            physics_dt: 1.0 / 30.0,
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(config, rapier_physics(), Arc::new(World::new(terrain)));
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        svc.register_participant("bob".into(), Vec3::new(4.0, 0.0, 0.0));
+        svc.set_move_intent(
+            "alice",
+            &IntentMove {
+                dx: 1.0,
+                dy: 0.0,
+                dz: 0.0,
+                seq: None,
+            },
+        )
+        .expect("intent");
+
+        let mut hits = Vec::new();
+        for _ in 0..30 {
+            let events = svc.tick().expect("tick");
+            hits.extend(
+                events
+                    .collisions
+                    .into_iter()
+                    .filter(|c| (c.entity_a.as_str(), c.entity_b.as_str()) == ("alice", "bob")),
+            );
+        }
+        assert_eq!(hits.len(), 1, "{:?}", hits);
+        assert!(hits[0].impulse > 0.0, "{:?}", hits[0]);
+    }
+
+    #[test]
+    fn walking_follows_the_ground_and_moves_the_body() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let physics = rapier_physics();
+        let config = WorldServiceConfig {
+            activation_radius: 1,
+            physics_dt: 1.0 / 30.0,
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(
+            config,
+            physics.clone(),
+            Arc::new(World::new(terrain.clone())),
+        );
+        let start = terrain.height_at(0.0, 0.0);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, start));
+        assert_eq!(body_position(&physics, "alice"), Some((0.0, 0.0)));
+        svc.tick().expect("streams with a simulation");
+
+        let intent = |dx, dy| IntentMove {
+            dx,
+            dy,
+            dz: 0.0,
+            seq: None,
+        };
+        svc.set_move_intent("alice", &intent(1.0, 0.0))
+            .expect("intent");
+        for _ in 0..60 {
+            svc.tick().expect("tick");
+            let pos = svc.participant_position("alice").unwrap();
+            assert_eq!(pos.z, terrain.height_at(pos.x, pos.y), "{:?}", pos);
+            assert_eq!(body_position(&physics, "alice"), Some((pos.x, pos.y)));
+        }
+        let pos = svc.participant_position("alice").unwrap();
+        assert!((pos.x - 10.0).abs() < 1e-3, "{:?}", pos);
+        // The ground there isn't where alice started.
+        assert!((pos.z - start).abs() > 0.01, "{} vs {}", pos.z, start);
+
+        // A body the simulation pushed elsewhere lands on the ground there.
+        svc.set_move_intent("alice", &intent(0.0, 0.0))
+            .expect("intent");
+        svc.tick().expect("tick");
+        physics
+            .write()
+            .default_simulation_mut()
+            .unwrap()
+            .set_transform(
+                "alice",
+                Transform {
+                    position: (-6.0, 4.0),
+                    rotation: 0.0,
+                },
+            )
+            .unwrap();
+        svc.tick().expect("tick");
+        assert_eq!(
+            svc.participant_position("alice"),
+            Some(Vec3::new(-6.0, 4.0, terrain.height_at(-6.0, 4.0)))
+        );
+
+        svc.unregister_participant("alice");
+        assert_eq!(body_position(&physics, "alice"), None);
+    }
+
+    // -----------------------------------------------------------------------
+    // Environment
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn first_tick_publishes_environment_then_throttles() {
+        let mut svc = make_service(0);
+        let first = svc.tick().expect("tick");
+        assert!(first.environment.is_some());
+
+        let second = svc.tick().expect("tick");
+        assert!(second.environment.is_none());
+    }
+
+    #[test]
+    fn weather_change_publishes_immediately() {
+        let mut svc = make_service(0);
+        svc.tick().expect("tick");
+
+        svc.set_weather(Weather::Fog);
+        let events = svc.tick().expect("tick");
+        let env = events.environment.expect("weather change should publish");
+        assert_eq!(env.weather, Weather::Fog);
+        assert!(env.fog_density > 0.0);
+    }
+
+    #[test]
+    fn snapshot_includes_environment() {
+        let mut svc = make_service(0);
+        svc.set_time_of_day(WorldServiceConfig::default().day_length_s * 0.5);
+        let env = svc
+            .build_snapshot("test")
+            .environment
+            .expect("snapshot should carry environment");
+        assert!((env.sun_angle_deg - 90.0).abs() < 1e-3, "noon sun overhead");
+    }
+
+    // -----------------------------------------------------------------------
+    // Height queries
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn height_queries_follow_the_terrain_in_request_order() {
+        let svc = make_service(0);
+        let terrain = HeightmapTerrain::new(42, 64.0, 16);
+        let points = [[3.0, 4.0], [-20.0, 7.5], [100.0, -3.0]];
+
+        let samples = svc.sample_heights(&points, false).expect("within cap");
+        let expected: Vec<f32> = points
+            .iter()
+            .map(|&[x, y]| terrain.height_at(x, y))
+            .collect();
+        assert_eq!(samples.heights, expected);
+        assert!(samples.normals.is_empty());
+
+        let samples = svc.sample_heights(&points, true).expect("within cap");
+        assert_eq!(samples.normals.len(), 3);
+        for [x, y, z] in samples.normals {
+            assert!(((x * x + y * y + z * z).sqrt() - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn pick_returns_the_nearest_entity_structure_or_terrain() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let ground = terrain.height_at(40.0, 0.0);
+        let mut world = World::new(terrain.clone());
+        world
+            .structures
+            .insert(structure("hut", Vec3::new(40.0, 0.0, ground), 2.0))
+            .unwrap();
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let mut svc = WorldService::new(WorldServiceConfig::default(), physics, Arc::new(world));
+        let ground_at_origin = terrain.height_at(0.0, 0.0);
+        svc.register_participant("bob".into(), Vec3::new(0.0, 0.0, ground_at_origin + 1.0));
+
+        // Straight down onto bob: his bounding sphere is above the ground.
+        let top = Vec3::new(0.0, 0.0, ground_at_origin + 100.0);
+        let down = Vec3::new(0.0, 0.0, -2.0);
+        let hit = svc.pick(top, down, 200.0, None).expect("bob");
+        assert_eq!(
+            hit.target,
+            PickTarget::Entity {
+                entity_id: "bob".into()
+            }
+        );
+        assert!((hit.distance - 98.5).abs() < 1e-3, "{}", hit.distance);
+
+        let hit = svc.pick(top, down, 200.0, Some("bob")).expect("ground");
+        assert_eq!(hit.target, PickTarget::Terrain);
+        assert!((hit.z - ground_at_origin).abs() < 0.01);
+
+        let hut = svc
+            .pick(Vec3::new(40.0, 0.0, ground + 100.0), down, 200.0, None)
+            .expect("hut");
+        assert_eq!(
+            hut.target,
+            PickTarget::Structure {
+                structure_id: "hut".into()
+            }
+        );
+        assert!(svc.pick(top, down, 50.0, None).is_none());
+    }
+
+    #[test]
+    fn raycast_reports_what_it_hit_with_the_normal() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let ground = terrain.height_at(0.0, 0.0);
+        // An 8 x 1 wall turned a quarter: it runs north-south.
+        let wall_ground = terrain.height_at(30.0, 30.0);
+        let mut wall = StructureInstance::new(
+            "wall",
+            Vec3::new(30.0, 30.0, wall_ground),
+            ColliderShape::Box {
+                width: 8.0,
+                height: 1.0,
+            },
+        );
+        wall.rotation_y = std::f32::consts::FRAC_PI_2;
+        wall.metadata
+            .insert("type_id".into(), serde_json::json!("buildings/wall"));
+        let mut world = World::new(terrain.clone());
+        world.structures.insert(wall).unwrap();
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let mut svc = WorldService::new(WorldServiceConfig::default(), physics, Arc::new(world));
+        svc.register_participant("bob".into(), Vec3::new(0.0, 0.0, ground + 1.0));
+
+        // Sideways into bob: the normal faces back along the ray.
+        let from = Vec3::new(-20.0, 0.0, ground + 1.0);
+        let east = Vec3::new(1.0, 0.0, 0.0);
+        let hit = svc
+            .raycast_world(from, east, 100.0, None, false)
+            .expect("bob");
+        assert_eq!(
+            hit.target,
+            PickTarget::Entity {
+                entity_id: "bob".into()
+            }
+        );
+        assert!((hit.normal.x + 1.0).abs() < 1e-4, "{:?}", hit.normal);
+        assert!((hit.position.x + 0.5).abs() < 1e-3, "{:?}", hit.position);
+
+        // Straight down onto bob's head, or past him to the ground when he
+        // is excluded or only the ground counts.
+        let top = Vec3::new(0.0, 0.0, ground + 100.0);
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        let hit = svc
+            .raycast_world(top, down, 200.0, None, false)
+            .expect("bob");
+        assert_eq!(
+            hit.target,
+            PickTarget::Entity {
+                entity_id: "bob".into()
+            }
+        );
+        assert!((hit.position.z - (ground + 1.5)).abs() < 1e-3);
+        for (exclude, terrain_only) in [(Some("bob"), false), (None, true)] {
+            let hit = svc
+                .raycast_world(top, down, 200.0, exclude, terrain_only)
+                .expect("ground");
+            assert_eq!(hit.target, PickTarget::Terrain);
+            assert!((hit.position.z - ground).abs() < 0.01);
+            assert!(hit.normal.z > 0.0);
+        }
+
+        // The wall is hit on its turned box, not its bounding sphere: on
+        // top where it runs, through to the ground beside it.
+        let wall_hit = PickTarget::Structure {
+            structure_id: "wall".into(),
+        };
+        let hit = svc
+            .raycast_world(
+                Vec3::new(30.0, 33.0, wall_ground + 50.0),
+                down,
+                100.0,
+                None,
+                false,
+            )
+            .expect("wall top");
+        assert_eq!(hit.target, wall_hit);
+        assert!((hit.position.z - (wall_ground + 5.0)).abs() < 1e-3);
+        assert_eq!(hit.normal, Vec3::new(0.0, 0.0, 1.0));
+        let beside = Vec3::new(33.0, 30.0, wall_ground + 50.0);
+        let hit = svc
+            .raycast_world(beside, down, 100.0, None, false)
+            .expect("ground beside the wall");
+        assert_eq!(hit.target, PickTarget::Terrain);
+
+        // Into its long side, heading north.
+        let north = Vec3::new(0.0, 1.0, 0.0);
+        let hit = svc
+            .raycast_world(
+                Vec3::new(30.0, 25.0, wall_ground + 2.0),
+                north,
+                10.0,
+                None,
+                false,
+            )
+            .expect("wall side");
+        assert_eq!(hit.target, wall_hit);
+        assert!((hit.position.y - 26.0).abs() < 1e-3, "{:?}", hit.position);
+        assert!((hit.normal.y + 1.0).abs() < 1e-4, "{:?}", hit.normal);
+    }
+
+    #[test]
+    fn raycast_hits_vegetation_colliders() {
+        let mut svc = wooded_hut_service(rapier_physics());
+        svc.register_participant("alice".into(), Vec3::new(2.0, 4.0, 0.0));
+        let events = svc.tick().expect("tick");
+        let tree = events
+            .structures_spawned
+            .iter()
+            .find(|s| (s.x - 2.0).hypot(s.y - 4.0) > 3.0)
+            .expect("a tree away from the hut");
+
+        let top = Vec3::new(tree.x, tree.y, tree.z + 50.0);
+        let hit = svc
+            .raycast_world(top, Vec3::new(0.0, 0.0, -1.0), 100.0, None, false)
+            .expect("tree");
+        assert_eq!(
+            hit.target,
+            PickTarget::Structure {
+                structure_id: tree.structure_id.clone()
+            }
+        );
+        assert!(hit.position.z > tree.z);
+    }
+
+    #[test]
+    fn cells_list_the_structures_overlapping_them() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let mut world = World::new(terrain);
+        // Bounds reach 5 m: straddles cells (0, 0) and (1, 0), short of
+        // (0, 2).
+        world
+            .structures
+            .insert(structure("wall", Vec3::new(10.0, 5.0, 0.0), 1.0))
+            .unwrap();
+        world
+            .structures
+            .insert(structure("hut", Vec3::new(2.0, 4.0, 0.0), 1.0))
+            .unwrap();
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let svc = WorldService::new(WorldServiceConfig::default(), physics, Arc::new(world));
+
+        assert_eq!(
+            svc.cell_info(CellCoord::new(0, 0, 0)).structures,
+            ["hut", "wall"]
+        );
+        assert_eq!(svc.cell_info(CellCoord::new(1, 0, 0)).structures, ["wall"]);
+        assert!(svc.cell_structures(CellCoord::new(0, 2, 0)).is_empty());
+    }
+
+    #[test]
+    fn failed_activation_leaves_no_bodies_behind() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let mut world = World::new(terrain);
+        world
+            .structures
+            .insert(structure("hut", Vec3::new(2.0, 4.0, 0.0), 1.0))
+            .unwrap();
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            activation_radius: 0,
+            tree_density: 1.0,
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(config, physics, Arc::new(world));
+        svc.register_participant("alice".into(), Vec3::new(2.0, 4.0, 0.0));
+
+        // No simulation to register with: the cell stays inactive with
+        // nothing recorded against it, on every retry.
+        for _ in 0..2 {
+            assert!(svc.tick().is_err());
+            let info = svc.cell_info(CellCoord::new(0, 0, 0));
+            assert!(!info.active);
+            assert!(info.terrain_bodies.is_empty());
+            assert!(info.objects.is_empty());
+            assert_eq!(info.structures, ["hut"]);
+        }
+    }
+
+    /// A service over one hut at the origin cell, densely wooded, streaming
+    /// only the cell a participant stands in.
+    fn wooded_hut_service(physics: Arc<RwLock<PhysicsRegistry>>) -> WorldService {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let mut world = World::new(terrain);
+        world
+            .structures
+            .insert(structure("hut", Vec3::new(2.0, 4.0, 0.0), 1.0))
+            .unwrap();
+        let config = WorldServiceConfig {
+            activation_radius: 0,
+            tree_density: 1.0,
+            physics_dt: 1.0 / 30.0,
+            ..Default::default()
+        };
+        WorldService::new(config, physics, Arc::new(world))
+    }
+
+    /// Every body the origin cell of a `wooded_hut_service` registers.
+    fn origin_cell_bodies(svc: &WorldService) -> Vec<String> {
+        let info = svc.cell_info(CellCoord::new(0, 0, 0));
+        let mut ids = info.objects;
+        ids.extend(info.terrain_bodies);
+        ids.push("structure.hut".to_string());
+        ids
+    }
+
+    #[test]
+    fn deactivation_unregisters_every_cell_body() {
+        let physics = rapier_physics();
+        let mut svc = wooded_hut_service(physics.clone());
+        svc.register_participant("alice".into(), Vec3::new(2.0, 4.0, 0.0));
+        svc.tick().expect("tick");
+        let bodies = origin_cell_bodies(&svc);
+        assert!(
+            bodies.len() > 2,
+            "terrain, vegetation and the hut: {:?}",
+            bodies
+        );
+        for id in &bodies {
+            assert!(body_position(&physics, id).is_some(), "{} registered", id);
+        }
+
+        svc.unregister_participant("alice");
+        svc.tick().expect("tick");
+        assert!(!svc.cell_info(CellCoord::new(0, 0, 0)).active);
+        let left: Vec<_> = bodies
+            .iter()
+            .map(String::as_str)
+            .chain(["alice"])
+            .filter(|id| body_position(&physics, id).is_some())
+            .collect();
+        assert!(left.is_empty(), "bodies left behind: {:?}", left);
+    }
+
+    #[test]
+    fn clashing_body_rolls_back_the_cell() {
+        // The bodies a clean activation registers.
+        let mut clean = wooded_hut_service(rapier_physics());
+        clean.register_participant("alice".into(), Vec3::new(2.0, 4.0, 0.0));
+        clean.tick().expect("tick");
+        let bodies = origin_cell_bodies(&clean);
+
+        let physics = rapier_physics();
+        physics
+            .write()
+            .default_simulation_mut()
+            .unwrap()
+            .register_body(
+                "structure.hut".to_string(),
+                BodyParams::Static {
+                    shape: ColliderShape::Circle { radius: 1.0 },
+                    position: (50.0, 50.0),
+                    rotation: 0.0,
+                },
+            )
+            .unwrap();
+        let mut svc = wooded_hut_service(physics.clone());
+        svc.register_participant("alice".into(), Vec3::new(2.0, 4.0, 0.0));
+        assert!(svc.tick().is_err());
+        assert!(!svc.cell_info(CellCoord::new(0, 0, 0)).active);
+
+        // Everything registered before the clash is gone again; the body
+        // that was already there is left alone.
+        let left: Vec<_> = bodies
+            .iter()
+            .filter(|id| *id != "structure.hut")
+            .filter(|id| body_position(&physics, id).is_some())
+            .collect();
+        assert!(left.is_empty(), "bodies left behind: {:?}", left);
+        assert_eq!(body_position(&physics, "structure.hut"), Some((50.0, 50.0)));
+    }
+
+    #[test]
+    fn changed_objects_come_back_when_their_cell_wakes() {
+        let dir = std::env::temp_dir().join(format!(
+            "janet_world_service_hibernation_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let start = || {
+            let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+            let config = WorldServiceConfig {
+                activation_radius: 0,
+                tree_density: 1.0,
+                physics_dt: 1.0 / 30.0,
+                ..Default::default()
+            };
+            let mut svc =
+                WorldService::new(config, rapier_physics(), Arc::new(World::new(terrain)));
+            svc.set_hibernation_store(HibernationStore::open(&dir).unwrap());
+            svc
+        };
+        let home = CellCoord::new(0, 0, 0);
+        let spawned_at = |events: &janet_world::service::TickEvents, id: &str| {
+            events
+                .structures_spawned
+                .iter()
+                .find(|s| s.structure_id == id)
+                .map(|s| (s.x, s.y, s.metadata.clone()))
+        };
+
+        let mut svc = start();
+        svc.register_participant("alice".into(), Vec3::new(5.0, 5.0, 0.0));
+        svc.tick().expect("tick");
+        let id = svc.cell_info(home).objects[0].clone();
+        let update = StructureUpdate {
+            x: Some(4.5),
+            y: Some(4.5),
+            metadata: [("felled".to_string(), serde_json::json!(true))]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        svc.update_structure(&id, &update).expect("update");
+
+        // Walk away and back: the cell hibernates and wakes with the change.
+        svc.register_participant("alice".into(), Vec3::new(500.0, 500.0, 0.0));
+        svc.tick().expect("tick");
+        assert!(!svc.cell_info(home).active);
+        svc.register_participant("alice".into(), Vec3::new(5.0, 5.0, 0.0));
+        let events = svc.tick().expect("tick");
+        let (x, y, metadata) = spawned_at(&events, &id).expect("object respawned");
+        assert_eq!((x, y), (4.5, 4.5));
+        assert_eq!(metadata["felled"], serde_json::json!(true));
+        assert!(svc.hibernation_store().contains(home));
+
+        // A restart while the cell is active keeps the change too.
+        drop(svc);
+        let mut svc = start();
+        svc.register_participant("alice".into(), Vec3::new(5.0, 5.0, 0.0));
+        let events = svc.tick().expect("tick");
+        let (x, y, _) = spawned_at(&events, &id).expect("object spawned");
+        assert_eq!((x, y), (4.5, 4.5));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn world_state_survives_a_restart() {
+        let restart = || {
+            let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+            let mut world = World::new(terrain);
+            world
+                .structures
+                .insert(structure("hut", Vec3::new(2.0, 4.0, 0.0), 1.0))
+                .unwrap();
+            let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+                PhysicsRegistryConfig::default(),
+            )));
+            WorldService::new(WorldServiceConfig::default(), physics, Arc::new(world))
+        };
+        let path =
+            std::env::temp_dir().join(format!("janet_world_state_{}.json", std::process::id()));
+
+        let mut svc = restart();
+        let moved = StructureUpdate {
+            x: Some(6.0),
+            ..Default::default()
+        };
+        svc.update_structure("hut", &moved).expect("update");
+        svc.spawn_entity(EntitySpec {
+            entity_id: "cart-1".into(),
+            archetype: "vehicle/cart".into(),
+            x: 5.0,
+            y: 5.0,
+            z: Some(0.0),
+            components: [("cargo".to_string(), serde_json::json!("grain"))].into(),
+            ..Default::default()
+        })
+        .expect("spawn");
+        svc.register_participant("alice".into(), Vec3::new(3.0, 4.0, 0.0));
+        svc.save_state(&path).expect("save");
+
+        let mut svc = restart();
+        svc.load_state(&path).expect("load");
+        std::fs::remove_file(&path).ok();
+        assert_eq!(svc.world().structures.get("hut").unwrap().position.x, 6.0);
+        assert_eq!(svc.entity("cart-1").unwrap().components["cargo"], "grain");
+        assert_eq!(
+            svc.participant_position("cart-1"),
+            Some(Vec3::new(5.0, 5.0, 0.0))
+        );
+
+        // Alice isn't back until she joins, then she's where she left.
+        assert!(svc.participant_position("alice").is_none());
+        assert_eq!(
+            svc.last_known_position("alice"),
+            Some(Vec3::new(3.0, 4.0, 0.0))
+        );
+        svc.join_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0), None);
+        assert_eq!(
+            svc.participant_position("alice"),
+            Some(Vec3::new(3.0, 4.0, 0.0))
+        );
+        assert_eq!(svc.last_known_position("alice"), None);
+
+        assert!(svc.load_state(&path).is_err(), "already removed");
+    }
+
+    fn place(type_id: &str, x: f32, y: f32) -> IntentPlaceStructure {
+        IntentPlaceStructure {
+            type_id: type_id.into(),
+            x,
+            y,
+            z: 0.0,
+            rotation_y: 0.5,
+        }
+    }
+
+    #[test]
+    fn placed_structures_are_validated_and_registered() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let buildable = BuildableStructure {
+            collider: ColliderShape::Circle { radius: 1.0 },
+            max_slope: None,
+        };
+        let config = WorldServiceConfig {
+            buildables: HashMap::from([
+                ("props/crate".to_string(), buildable.clone()),
+                // Registries refuse an empty type_id.
+                (String::new(), buildable),
+            ]),
+            build_reach: 8.0,
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(config, physics, Arc::new(World::new(terrain.clone())));
+        svc.register_participant("alice".into(), Vec3::new(2.0, 2.0, 0.0));
+
+        assert_eq!(
+            svc.place_structure("bob", place("props/crate", 4.0, 2.0)),
+            Err(PlacementError::UnknownEntity("bob".into()))
+        );
+        assert_eq!(
+            svc.place_structure("alice", place("props/tower", 4.0, 2.0)),
+            Err(PlacementError::NotBuildable("props/tower".into()))
+        );
+        assert!(matches!(
+            svc.place_structure("alice", place("props/crate", 20.0, 2.0)),
+            Err(PlacementError::OutOfReach { .. })
+        ));
+        for bad in [
+            place("props/crate", f32::NAN, 2.0),
+            IntentPlaceStructure {
+                rotation_y: f32::INFINITY,
+                ..place("props/crate", 4.0, 2.0)
+            },
+        ] {
+            assert_eq!(
+                svc.place_structure("alice", bad),
+                Err(PlacementError::NonFinite)
+            );
+        }
+        assert_eq!(
+            svc.place_structure("alice", place("", 2.0, 6.0)),
+            Err(PlacementError::InvalidMetadata {
+                key: "type_id",
+                expected: "a non-empty string",
+            })
+        );
+        assert_eq!(
+            svc.place_structure("alice", place("props/crate", 2.5, 2.0)),
+            Err(PlacementError::Overlaps("alice".into()))
+        );
+
+        let spawned = svc
+            .place_structure("alice", place("props/crate", 5.0, 2.0))
+            .expect("placed");
+        assert_eq!(spawned.type_id, "props/crate");
+        assert_eq!(spawned.rotation_y, 0.5);
+        assert_eq!(spawned.z, terrain.height_at(5.0, 2.0));
+        assert_eq!(spawned.metadata["owner"], "alice");
+        assert_eq!(
+            svc.cell_info(CellCoord::new(0, 0, 0)).structures,
+            vec![spawned.structure_id.clone()]
+        );
+        assert_eq!(
+            svc.place_structure("alice", place("props/crate", 6.5, 2.0)),
+            Err(PlacementError::Overlaps(spawned.structure_id))
+        );
+    }
+
+    #[test]
+    fn placements_on_vegetation_are_refused() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let config = WorldServiceConfig {
+            activation_radius: 0,
+            tree_density: 1.0,
+            buildables: HashMap::from([(
+                "props/crate".to_string(),
+                BuildableStructure {
+                    collider: ColliderShape::Circle { radius: 1.0 },
+                    max_slope: None,
+                },
+            )]),
+            build_reach: 8.0,
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(config, rapier_physics(), Arc::new(World::new(terrain)));
+        svc.register_participant("alice".into(), Vec3::new(2.0, 2.0, 0.0));
+        svc.tick().expect("tick");
+        let tree = svc.cell_info(CellCoord::new(0, 0, 0)).objects[0].clone();
+        let update = StructureUpdate {
+            x: Some(6.0),
+            y: Some(2.0),
+            ..Default::default()
+        };
+        svc.update_structure(&tree, &update).expect("update");
+
+        assert_eq!(
+            svc.place_structure("alice", place("props/crate", 6.5, 2.0)),
+            Err(PlacementError::Overlaps(tree))
+        );
+    }
+
+    #[test]
+    fn server_entities_spawn_move_and_despawn() {
+        let mut svc = make_service(1);
+        let spec = || EntitySpec {
+            entity_id: "cart-1".into(),
+            archetype: "vehicle/cart".into(),
+            x: 5.0,
+            y: 5.0,
+            z: Some(0.0),
+            velocity: [3.0, 0.0],
+            components: [("cargo".to_string(), serde_json::json!("grain"))].into(),
+            ..Default::default()
+        };
+        let spawned = svc.spawn_entity(spec()).expect("spawn");
+        assert_eq!(spawned.metadata["cargo"], "grain");
+        assert_eq!(
+            svc.spawn_entity(spec()),
+            Err(EntityError::Exists("cart-1".into()))
+        );
+        let participant = EntitySpec {
+            entity_id: "npc".into(),
+            archetype: "participant".into(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            svc.spawn_entity(participant),
+            Err(EntityError::Invalid { .. })
+        ));
+
+        // Announced once, then carried along by its velocity.
+        let events = svc.tick().expect("tick");
+        assert_eq!(events.entities_spawned, [spawned]);
+        assert!(events.activated.is_empty(), "entities don't stream terrain");
+        assert!(svc.participant_position("cart-1").unwrap().x > 5.0);
+        assert!(svc.tick().expect("tick").entities_spawned.is_empty());
+
+        let snapshot = svc.build_snapshot("s");
+        let cart = snapshot
+            .entities
+            .iter()
+            .find(|e| e.entity_id == "cart-1")
+            .unwrap();
+        assert_eq!(cart.archetype, "vehicle/cart");
+        assert_eq!(cart.metadata["cargo"], "grain");
+
+        svc.entity_mut("cart-1").unwrap().velocity = [0.0, 0.0];
+        let stopped = svc.participant_position("cart-1").unwrap();
+        svc.tick().expect("tick");
+        assert_eq!(svc.participant_position("cart-1"), Some(stopped));
+
+        svc.despawn_entity("cart-1").expect("despawn");
+        assert_eq!(svc.entity_count(), 0);
+        assert!(svc.participant_position("cart-1").is_none());
+        let removed = svc.tick().expect("tick").entities_removed;
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].entity_id, "cart-1");
+        assert!(matches!(
+            svc.despawn_entity("cart-1"),
+            Err(EntityError::Unknown(_))
+        ));
+    }
+
+    #[test]
+    fn fleeing_entities_run_from_nearby_participants() {
+        let mut svc = make_throttled_service(1.0);
+        svc.spawn_entity(EntitySpec {
+            entity_id: "deer".into(),
+            archetype: "creature/deer".into(),
+            x: 10.0,
+            y: 10.0,
+            z: Some(0.0),
+            behaviour: Some(Behaviour::Flee {
+                range: 8.0,
+                speed: 4.0,
+            }),
+            ..Default::default()
+        })
+        .expect("spawn");
+
+        // Nobody close: the deer grazes.
+        svc.tick().expect("tick");
+        assert_eq!(svc.entity("deer").unwrap().velocity, [0.0, 0.0]);
+
+        svc.register_participant("alice".into(), Vec3::new(6.0, 10.0, 0.0));
+        // The join streams right away (which needs a physics simulation).
+        assert!(svc.tick().is_err());
+        svc.tick().expect("physics tick skips streaming");
+        let velocity = svc.entity("deer").unwrap().velocity;
+        assert!(velocity[0] > 0.0 && velocity[1].abs() < 1e-4);
+        assert!(svc.participant_position("deer").unwrap().x > 10.0);
+
+        assert!(matches!(
+            svc.spawn_entity(EntitySpec {
+                entity_id: "hare".into(),
+                archetype: "creature/hare".into(),
+                behaviour: Some(Behaviour::Wander {
+                    radius: 5.0,
+                    speed: -1.0,
+                }),
+                ..Default::default()
+            }),
+            Err(EntityError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn tagged_structures_come_back_nearest_first() {
+        let terrain = Arc::new(janet_world::flat_terrain::FlatTerrain::new(0.0));
+        let mut world = World::new(terrain);
+        for (id, x) in [("far", 40.0), ("near", 5.0), ("mid", 20.0), ("other", 1.0)] {
+            let mut s = structure(id, Vec3::new(x, 0.0, 0.0), 1.0);
+            if id != "other" {
+                s.tags = vec!["campfire".into()];
+            }
+            world.structures.insert(s).unwrap();
+        }
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let svc = WorldService::new(WorldServiceConfig::default(), physics, Arc::new(world));
+        let query = |cmd: serde_json::Value| {
+            let cmd: CmdStructures = serde_json::from_value(cmd).unwrap();
+            let reply = svc.structures_by_tag(&cmd);
+            let ids: Vec<String> = reply
+                .structures
+                .into_iter()
+                .map(|s| s.structure_id)
+                .collect();
+            (ids, reply.truncated)
+        };
+
+        let (ids, truncated) = query(serde_json::json!({ "tag": "campfire", "near": [0.0, 0.0] }));
+        assert_eq!(ids, ["near", "mid", "far"]);
+        assert!(!truncated);
+
+        let (ids, truncated) = query(serde_json::json!({
+            "tag": "campfire", "near": [45.0, 0.0], "limit": 1
+        }));
+        assert_eq!(ids, ["far"]);
+        assert!(truncated);
+
+        let (ids, _) = query(serde_json::json!({
+            "tag": "campfire", "rect": [0.0, -5.0, 25.0, 5.0]
+        }));
+        assert_eq!(ids, ["mid", "near"]);
+    }
+
+    #[test]
+    fn interactive_structures_move_between_states() {
+        let terrain = Arc::new(janet_world::flat_terrain::FlatTerrain::new(0.0));
+        let mut world = World::new(terrain);
+        let mut gate = structure("gate", Vec3::new(4.0, 0.0, 0.0), 2.0);
+        gate.states = Some(
+            serde_json::from_value(serde_json::json!({
+                "state": "closed",
+                "states": {
+                    "closed": {
+                        "collider": { "Box": { "width": 4.0, "height": 0.5 } },
+                        "transitions": { "open": "open", "lock": "locked" }
+                    },
+                    "open": { "transitions": { "close": "closed" } },
+                    "locked": {
+                        "collider": { "Box": { "width": 4.0, "height": 0.5 } }
+                    }
+                }
+            }))
+            .unwrap(),
+        );
+        world.structures.insert(gate).unwrap();
+        world
+            .structures
+            .insert(structure("hut", Vec3::new(-4.0, 0.0, 0.0), 2.0))
+            .unwrap();
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let mut svc = WorldService::new(WorldServiceConfig::default(), physics, Arc::new(world));
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        svc.register_participant("bob".into(), Vec3::new(30.0, 0.0, 0.0));
+        let interact = |target: &str, verb: Option<&str>| IntentInteract {
+            target_id: target.into(),
+            verb: verb.map(str::to_string),
+        };
+
+        assert_eq!(
+            svc.interact("carol", &interact("gate", None)),
+            Err(InteractError::UnknownEntity("carol".into()))
+        );
+        assert_eq!(
+            svc.interact("alice", &interact("hut", None)),
+            Err(InteractError::NotInteractive("hut".into()))
+        );
+        assert!(matches!(
+            svc.interact("bob", &interact("gate", Some("open"))),
+            Err(InteractError::OutOfReach { .. })
+        ));
+        // Two ways out of `closed`: the verb is required.
+        assert!(matches!(
+            svc.interact("alice", &interact("gate", None)),
+            Err(InteractError::NoTransition { state, .. }) if state == "closed"
+        ));
+
+        let changed = svc
+            .interact("alice", &interact("gate", Some("open")))
+            .expect("open");
+        assert_eq!(
+            (changed.previous.as_str(), changed.state.as_str()),
+            ("closed", "open")
+        );
+        assert_eq!(changed.actor_id.as_deref(), Some("alice"));
+        let gate = svc.cell_structures(CellCoord::new(0, 0, 0))[0].clone();
+        assert_eq!(gate.to_spawned().state.as_deref(), Some("open"));
+        assert!(gate.body_collider().is_none());
+
+        // `open` has a single transition, taken without a verb.
+        let changed = svc
+            .interact("alice", &interact("gate", None))
+            .expect("close");
+        assert_eq!(
+            (changed.verb.as_str(), changed.state.as_str()),
+            ("close", "closed")
+        );
+    }
+
+    #[test]
+    fn interaction_intents_dispatch_by_verb_and_target_kind() {
+        let terrain = Arc::new(janet_world::flat_terrain::FlatTerrain::new(0.0));
+        let mut world = World::new(terrain);
+        world
+            .structures
+            .insert(structure("hut", Vec3::new(-4.0, 0.0, 0.0), 2.0))
+            .unwrap();
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let mut svc = WorldService::new(WorldServiceConfig::default(), physics, Arc::new(world));
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        svc.register_participant("bob".into(), Vec3::new(30.0, 0.0, 0.0));
+        svc.spawn_entity(EntitySpec {
+            entity_id: "merchant".into(),
+            archetype: "npc/merchant".into(),
+            x: 2.0,
+            z: Some(0.0),
+            ..Default::default()
+        })
+        .expect("spawn");
+        svc.register_interaction_handler("talk", InteractionTarget::Entity, |i: &Interaction| {
+            Ok(serde_json::json!({ "greeting": format!("{} hails {}", i.target_type, i.actor_id) }))
+        });
+        svc.register_interaction_handler("use", InteractionTarget::Structure, |i: &Interaction| {
+            Err(format!("The {} is locked", i.target_type))
+        });
+        let interact = |target: &str, verb: Option<&str>| IntentInteract {
+            target_id: target.into(),
+            verb: verb.map(str::to_string),
+        };
+
+        let talk = svc.resolve_interaction("alice", &interact("merchant", Some("talk")));
+        assert!(talk.success, "{:?}", talk.error);
+        assert_eq!(talk.target_kind, Some(InteractionTarget::Entity));
+        assert_eq!(talk.data["greeting"], "npc/merchant hails alice");
+
+        // No verb means `use`; the handler's refusal is the error shown.
+        let locked = svc.resolve_interaction("alice", &interact("hut", None));
+        assert!(!locked.success);
+        assert_eq!(locked.target_kind, Some(InteractionTarget::Structure));
+        assert_eq!(locked.error.as_deref(), Some("The buildings/hut is locked"));
+
+        let failures = [
+            ("alice", interact("merchant", Some("trade"))),
+            ("bob", interact("merchant", Some("talk"))),
+            ("alice", interact("hut", Some("open"))),
+            ("alice", interact("nobody", Some("talk"))),
+        ];
+        for (actor, intent) in &failures {
+            let result = svc.resolve_interaction(actor, intent);
+            assert!(!result.success && result.error.is_some(), "{:?}", intent);
+        }
+        assert_eq!(svc.stats().drops.total, 4);
+
+        // Results go out with the next tick, failures included.
+        let mut quiet = make_service(0);
+        let result = quiet.resolve_interaction("ghost", &interact("hut", None));
+        assert_eq!(result.error.as_deref(), Some("Unknown entity 'ghost'"));
+        assert_eq!(quiet.tick().expect("tick").interaction_results, [result]);
+    }
+
+    #[test]
+    fn height_queries_over_the_cap_are_rejected() {
+        let svc = make_service(0);
+        let points = vec![[0.0, 0.0]; MAX_HEIGHT_QUERY_POINTS + 1];
+        assert!(svc.sample_heights(&points, false).is_err());
+        assert!(svc
+            .sample_heights(&points[..MAX_HEIGHT_QUERY_POINTS], false)
+            .is_ok());
+    }
+
+    #[test]
+    fn set_terrain_heights_moves_the_ground_and_bumps_the_revision() {
+        let mut svc = make_service(0);
+        let chunk_ids = svc.set_terrain_heights(&[[32.0, 32.0, 25.0]]).unwrap();
+        assert_eq!(chunk_ids, vec!["0:0".to_string()]);
+        assert_eq!(svc.terrain_revision(), 1);
+        let samples = svc.sample_heights(&[[32.0, 32.0]], false).unwrap();
+        assert!((samples.heights[0] - 25.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn set_terrain_heights_rejects_bad_requests() {
+        let mut svc = make_service(0);
+        let samples = vec![[0.0, 0.0, 1.0]; MAX_SET_HEIGHT_SAMPLES + 1];
+        assert!(svc.set_terrain_heights(&samples).is_err());
+        assert!(svc.set_terrain_heights(&[[0.0, f32::NAN, 1.0]]).is_err());
+        assert_eq!(svc.terrain_revision(), 0);
+
+        let terrain = Arc::new(janet_world::flat_terrain::FlatTerrain::new(0.0));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let mut flat = WorldService::new(
+            WorldServiceConfig::default(),
+            physics,
+            Arc::new(World::new(terrain)),
+        );
+        let err = flat.set_terrain_heights(&[[0.0, 0.0, 1.0]]).unwrap_err();
+        assert!(err.to_string().contains("does not support"), "{}", err);
+    }
+
+    // -----------------------------------------------------------------------
+    // Terrain deformation
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn deform_terrain_queues_event_for_next_tick_and_snapshot() {
+        let mut svc = make_service(0);
+        let ev = svc
+            .deform_terrain(Vec3::new(32.0, 32.0, 0.0), 8.0, -2.0)
+            .expect("heightmap terrain is deformable");
+        assert_eq!(ev.revision, 1);
+        assert_eq!(ev.chunk_ids, vec!["0:0"]);
+
+        let events = svc.tick().expect("tick");
+        assert_eq!(events.terrain_modified.len(), 1);
+        assert!(svc.tick().expect("tick").terrain_modified.is_empty());
+
+        let snapshot = svc.build_snapshot("test");
+        assert_eq!(snapshot.terrain_modifications.len(), 1);
+    }
+
+    #[test]
+    fn deform_terrain_rejects_bad_requests() {
+        let mut svc = make_service(0);
+        let center = Vec3::new(32.0, 32.0, 0.0);
+        assert!(svc
+            .deform_terrain(center, MAX_DEFORM_RADIUS + 1.0, -2.0)
+            .is_err());
+        assert!(svc.deform_terrain(center, f32::INFINITY, -2.0).is_err());
+        assert!(svc.deform_terrain(center, f32::NAN, -2.0).is_err());
+        assert!(svc.deform_terrain(center, 8.0, f32::NAN).is_err());
+        assert!(svc
+            .deform_terrain(Vec3::new(f32::NAN, 32.0, 0.0), 8.0, -2.0)
+            .is_err());
+        assert!(svc.tick().expect("tick").terrain_modified.is_empty());
+        let ev = svc.deform_terrain(center, MAX_DEFORM_RADIUS, -2.0).unwrap();
+        assert_eq!(ev.revision, 1);
+    }
+
+    #[test]
+    fn terrain_edits_invalidate_nav_over_their_footprint() {
+        let mut svc = make_service(0);
+        svc.deform_terrain(Vec3::new(60.0, 20.0, 0.0), 8.0, -2.0)
+            .expect("deformable");
+        svc.cut_terrain_holes(Vec3::new(20.0, 20.0, 0.0), 2.0)
+            .expect("holes");
+
+        let events = svc.tick().expect("tick");
+        let [dig, hole] = &events.nav_invalidated[..] else {
+            panic!("expected two invalidations");
+        };
+        assert_eq!(dig.cause, NavChangeCause::TerrainDeformed);
+        assert_eq!((dig.min_x, dig.max_x), (52.0, 68.0));
+        assert_eq!(dig.chunk_ids, vec!["0:0", "1:0"]);
+        assert_eq!(hole.cause, NavChangeCause::TerrainHole);
+        assert_eq!(hole.revision, 2);
+        assert!(svc.tick().expect("tick").nav_invalidated.is_empty());
+    }
+
+    #[test]
+    fn structure_changes_invalidate_nav_through_the_hook() {
+        let mut svc = make_service(0);
+        let ev = svc.invalidate_nav([-1.0, 0.0], [1.0, 2.0], NavChangeCause::StructurePlaced);
+        assert_eq!(ev.revision, 1);
+        assert_eq!(ev.chunk_ids, vec!["-1:0", "0:0"]);
+        assert_eq!(svc.tick().expect("tick").nav_invalidated, vec![ev]);
+    }
+
+    // -----------------------------------------------------------------------
+    // Background chunk generation
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn workers_generate_chunks_off_tick() {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let world = Arc::new(World::new(terrain.clone()));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            cell_size: 10.0,
+            activation_radius: 1,
+            generation_workers: 2,
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(config, physics, world);
+        svc.register_participant("alice".into(), Vec3::new(5.0, 5.0, 0.0));
+
+        // Nothing is generated yet, so the tick queues every cell instead of
+        // activating (or blocking on) any of them.
+        let events = svc.tick().expect("tick");
+        assert!(events.activated.is_empty());
+        assert_eq!(svc.stats().pending_chunks, 9);
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !(-1..=1).all(|x| (-1..=1).all(|y| terrain.is_cached(x, y, 0))) {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "workers never finished"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn pending_cells_are_dropped_when_nobody_needs_them() {
+        let config = WorldServiceConfig {
+            activation_radius: 1,
+            generation_workers: 1,
+            ..Default::default()
+        };
+        let world = Arc::new(World::new(Arc::new(HeightmapTerrain::new(42, 64.0, 16))));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let mut svc = WorldService::new(config, physics, world);
+        svc.register_participant("alice".into(), Vec3::new(5.0, 5.0, 0.0));
+        svc.tick().expect("tick");
+
+        svc.unregister_participant("alice");
+        svc.tick().expect("tick");
+        assert_eq!(svc.stats().pending_chunks, 0);
+    }
+
+    // -----------------------------------------------------------------------
+    // Custom terrain backends (streamed through the trait, no downcasts)
+    // -----------------------------------------------------------------------
+
+    /// Flat chunked terrain that records which chunks were generated.
+    #[derive(Default)]
+    struct TiledTerrain {
+        warmed: Mutex<HashSet<(i32, i32)>>,
+    }
+
+    impl TerrainSource for TiledTerrain {
+        fn height_at(&self, _x: f32, _y: f32) -> f32 {
+            1.0
+        }
+
+        fn normal_at(&self, _x: f32, _y: f32) -> Vec3 {
+            Vec3::new(0.0, 0.0, 1.0)
+        }
+
+        fn chunk_descriptor(&self, _cx: i32, _cy: i32, _lod: u8) -> Option<ChunkDescriptor> {
+            Some(ChunkDescriptor {
+                seed: 7,
+                chunk_size: 32.0,
+                hydrology: None,
+                materials: Default::default(),
+            })
+        }
+
+        fn warm_chunk(&self, cx: i32, cy: i32, _lod: u8) {
+            self.warmed.lock().insert((cx, cy));
+        }
+
+        fn is_chunk_cached(&self, cx: i32, cy: i32, _lod: u8) -> bool {
+            self.warmed.lock().contains(&(cx, cy))
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn make_tiled_service(workers: usize) -> (WorldService, Arc<TiledTerrain>) {
+        let terrain = Arc::new(TiledTerrain::default());
+        let world = Arc::new(World::new(terrain.clone()));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            activation_radius: 1,
+            generation_workers: workers,
+            ..Default::default()
+        };
+        (WorldService::new(config, physics, world), terrain)
+    }
+
+    #[test]
+    fn custom_backend_chunks_are_generated_off_tick() {
+        let (mut svc, terrain) = make_tiled_service(2);
+        svc.register_participant("alice".into(), Vec3::new(5.0, 5.0, 0.0));
+        let events = svc.tick().expect("tick");
+        assert!(events.activated.is_empty());
+        assert_eq!(svc.stats().pending_chunks, 9);
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while terrain.warmed.lock().len() < 9 {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "workers never finished"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+
+    /// Chunked terrain with one LOD band at 15 units; records the LOD each
+    /// chunk was generated at.
+    #[derive(Default)]
+    struct BandedTerrain {
+        warmed: Mutex<HashMap<(i32, i32), u8>>,
+    }
+
+    impl TerrainSource for BandedTerrain {
+        fn height_at(&self, _x: f32, _y: f32) -> f32 {
+            0.0
+        }
+
+        fn normal_at(&self, _x: f32, _y: f32) -> Vec3 {
+            Vec3::new(0.0, 0.0, 1.0)
+        }
+
+        fn lod_for_distance(&self, distance: f32) -> u8 {
+            (distance >= 15.0) as u8
+        }
+
+        fn warm_chunk(&self, cx: i32, cy: i32, lod: u8) {
+            self.warmed.lock().insert((cx, cy), lod);
+        }
+
+        fn is_chunk_cached(&self, cx: i32, cy: i32, lod: u8) -> bool {
+            self.warmed.lock().get(&(cx, cy)) == Some(&lod)
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn chunks_are_requested_at_the_lod_for_their_distance() {
+        let terrain = Arc::new(BandedTerrain::default());
+        let world = Arc::new(World::new(terrain.clone()));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            cell_size: 10.0,
+            activation_radius: 2,
+            generation_workers: 1,
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(config, physics, world);
+        svc.register_participant("alice".into(), Vec3::new(5.0, 5.0, 0.0));
+        svc.tick().expect("tick");
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while terrain.warmed.lock().len() < 25 {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "workers never finished"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        let warmed = terrain.warmed.lock();
+        assert_eq!(warmed[&(0, 0)], 0);
+        assert_eq!(warmed[&(1, -1)], 0);
+        assert_eq!(warmed[&(2, 0)], 1);
+        assert_eq!(warmed[&(-2, -2)], 1);
+    }
+
+    #[test]
+    fn spawn_points_pregenerate_at_their_streaming_lods() {
+        let terrain = Arc::new(BandedTerrain::default());
+        let world = Arc::new(World::new(terrain.clone()));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            cell_size: 10.0,
+            activation_radius: 2,
+            generation_workers: 2,
+            spawn_points: vec![[5.0, 5.0], [15.0, 5.0]],
+            ..Default::default()
+        };
+        let svc = WorldService::new(config, physics, world);
+
+        // Two overlapping 5x5 squares; columns where they disagree on the
+        // LOD are generated at both (the terrain keeps the last).
+        let report = svc.pregenerate_spawn_points();
+        assert_eq!(report.generated, report.chunks);
+        assert_eq!(terrain.warmed.lock().len(), 30);
+        assert_eq!(terrain.warmed.lock()[&(-2, 0)], 1);
+        assert_eq!(terrain.warmed.lock()[&(3, 0)], 1);
+
+        // Everything is cached now.
+        let near_both = CellRegion {
+            min_cx: 0,
+            min_cy: -1,
+            max_cx: 1,
+            max_cy: 1,
+        };
+        let again = svc.pregenerate(near_both, &[0]);
+        assert_eq!((again.chunks, again.generated), (6, 0));
+    }
+
+    /// Terrain rising one unit per unit of `x`; records warmed chunks.
+    #[derive(Default)]
+    struct RampTerrain {
+        warmed: Mutex<HashSet<(i32, i32)>>,
+    }
+
+    impl TerrainSource for RampTerrain {
+        fn height_at(&self, x: f32, _y: f32) -> f32 {
+            x
+        }
+
+        fn normal_at(&self, _x: f32, _y: f32) -> Vec3 {
+            Vec3::new(-1.0, 0.0, 1.0)
+        }
+
+        fn warm_chunk(&self, cx: i32, cy: i32, _lod: u8) {
+            self.warmed.lock().insert((cx, cy));
+        }
+
+        fn is_chunk_cached(&self, cx: i32, cy: i32, _lod: u8) -> bool {
+            self.warmed.lock().contains(&(cx, cy))
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn vertical_streaming_skips_columns_far_above_or_below() {
+        let terrain = Arc::new(RampTerrain::default());
+        let world = Arc::new(World::new(terrain.clone()));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            cell_size: 10.0,
+            activation_radius: 2,
+            generation_workers: 1,
+            vertical_cell_size: 10.0,
+            vertical_activation_radius: 1,
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(config, physics, world);
+        // Column centres sit at x = 5, 15, ..., so column cx is in layer cx.
+        assert_eq!(svc.column_cell(3, -1), CellCoord::new(3, -1, 3));
+        assert_eq!(svc.column_cell(-2, 0), CellCoord::new(-2, 0, -2));
+
+        // Layer 1: columns cx = 0..=2 are within one layer.
+        svc.register_participant("alice".into(), Vec3::new(15.0, 5.0, 12.0));
+        svc.tick().expect("tick");
+        assert_eq!(svc.stats().pending_chunks, 15);
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while terrain.warmed.lock().len() < 15 {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "workers never finished"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        let columns: HashSet<i32> = terrain.warmed.lock().iter().map(|&(cx, _)| cx).collect();
+        assert_eq!(columns, HashSet::from([0, 1, 2]));
+    }
+
+    #[test]
+    fn participants_at_altitude_stream_the_layers_around_them() {
+        let terrain = Arc::new(RampTerrain::default());
+        let config = WorldServiceConfig {
+            cell_size: 10.0,
+            activation_radius: 2,
+            vertical_cell_size: 10.0,
+            vertical_activation_radius: 1,
+            physics_dt: 1.0 / 30.0,
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(config, rapier_physics(), Arc::new(World::new(terrain)));
+        // Flying 20 units over column 0 (layer 0), in layer 2.
+        svc.register_participant("alice".into(), Vec3::new(5.0, 5.0, 25.0));
+        for _ in 0..3 {
+            svc.tick().expect("tick");
+        }
+        assert_eq!(svc.participant_position("alice").map(|p| p.z), Some(25.0));
+        let active = |cx| svc.cell_info(svc.column_cell(cx, 0)).active;
+        assert!(active(2) && active(1));
+        assert!(!active(0) && !active(-1));
+    }
+
+    #[test]
+    fn steered_entities_follow_the_ground() {
+        let terrain = Arc::new(RampTerrain::default());
+        let config = WorldServiceConfig {
+            cell_size: 10.0,
+            activation_radius: 1,
+            physics_dt: 1.0 / 30.0,
+            ..Default::default()
+        };
+        let physics = rapier_physics();
+        let mut svc = WorldService::new(config, physics.clone(), Arc::new(World::new(terrain)));
+        svc.spawn_entity(EntitySpec {
+            entity_id: "deer".into(),
+            archetype: "creature/deer".into(),
+            x: 10.0,
+            y: 5.0,
+            behaviour: Some(Behaviour::Flee {
+                range: 8.0,
+                speed: 4.0,
+            }),
+            ..Default::default()
+        })
+        .expect("spawn");
+        svc.register_participant("alice".into(), Vec3::new(6.0, 5.0, 6.0));
+
+        // The deer runs east, up the ramp (height = x), feet on the ground.
+        for _ in 0..30 {
+            svc.tick().expect("tick");
+            let pos = svc.participant_position("deer").unwrap();
+            assert!((pos.z - pos.x).abs() < 1e-4, "{:?}", pos);
+        }
+        let pos = svc.participant_position("deer").unwrap();
+        assert!(pos.x > 13.0, "{:?}", pos);
+        assert_eq!(body_position(&physics, "deer"), Some((pos.x, pos.y)));
+    }
+
+    #[test]
+    fn flat_streaming_ignores_altitude() {
+        let mut svc = make_service(1);
+        assert_eq!(svc.column_cell(4, 4), CellCoord::new(4, 4, 0));
+        svc.register_participant("alice".into(), Vec3::new(5.0, 5.0, 1.0e4));
+        assert_eq!(
+            svc.cell_info(CellCoord::new(0, 0, 0)).participants,
+            vec!["alice"]
+        );
+    }
+
+    #[test]
+    fn custom_backend_pregenerates_but_cannot_be_deformed() {
+        let (mut svc, terrain) = make_tiled_service(0);
+        let report = svc.pregenerate(CellRegion::around(0, 0, 1), &[0]);
+        assert_eq!((report.chunks, report.generated), (9, 9));
+        assert_eq!(terrain.warmed.lock().len(), 9);
+        assert!(svc
+            .deform_terrain(Vec3::new(0.0, 0.0, 0.0), 4.0, 1.0)
+            .is_err());
+        assert!(svc
+            .cut_terrain_holes(Vec3::new(0.0, 0.0, 0.0), 4.0)
+            .is_err());
+    }
+
+    #[test]
+    fn terrain_holes_split_the_cell_collider_around_them() {
+        let physics = rapier_physics();
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let config = WorldServiceConfig {
+            cell_size: 64.0,
+            activation_radius: 0,
+            ..Default::default()
+        };
+        let mut svc = WorldService::new(config, physics.clone(), Arc::new(World::new(terrain)));
+        svc.register_participant("alice".into(), Vec3::new(40.0, 40.0, 0.0));
+        svc.tick().expect("tick");
+        let origin = CellCoord::new(0, 0, 0);
+        assert_eq!(svc.cell_info(origin).terrain_bodies, ["terrain.0.0"]);
+        assert_eq!(body_position(&physics, "terrain.0.0"), Some((32.0, 32.0)));
+
+        // LOD 0 cells are 4 units; only cell (1, 1) is cut.
+        svc.cut_terrain_holes(Vec3::new(6.0, 6.0, 0.0), 1.0)
+            .expect("holes");
+        let ids = svc.cell_info(origin).terrain_bodies;
+        assert_eq!(
+            ids,
+            [
+                "terrain.0.0.0",
+                "terrain.0.0.1",
+                "terrain.0.0.2",
+                "terrain.0.0.3"
+            ]
+        );
+        assert_eq!(body_position(&physics, "terrain.0.0"), None);
+        assert_eq!(body_position(&physics, "terrain.0.0.1"), Some((2.0, 6.0)));
+        assert_eq!(body_position(&physics, "terrain.0.0.2"), Some((36.0, 6.0)));
+
+        assert!(svc
+            .cut_terrain_holes(Vec3::new(6.0, 6.0, 0.0), MAX_DEFORM_RADIUS + 1.0)
+            .is_err());
+        assert!(svc
+            .cut_terrain_holes(Vec3::new(f32::NAN, 6.0, 0.0), 1.0)
+            .is_err());
+        assert!(svc
+            .cut_terrain_holes(Vec3::new(6.0, 6.0, 0.0), f32::INFINITY)
+            .is_err());
+
+        svc.unregister_participant("alice");
+        svc.tick().expect("tick");
+        for id in ids {
+            assert_eq!(body_position(&physics, &id), None, "{} left behind", id);
+        }
+    }
+
+    #[test]
+    fn terrain_holes_are_logged_and_replayed_on_restore() {
+        let mut svc = make_service(1);
+        let ev = svc
+            .cut_terrain_holes(Vec3::new(5.0, 5.0, 0.0), 3.0)
+            .expect("heightmap supports holes");
+        assert!(ev.hole);
+        assert_eq!(ev.delta, 0.0);
+        assert_eq!(ev.chunk_ids, vec!["0:0".to_string()]);
+        let snapshot = svc.build_snapshot("test");
+        assert!(snapshot.terrain_modifications[0].hole);
+
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let world = Arc::new(World::new(terrain.clone()));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let mut restored = WorldService::new(WorldServiceConfig::default(), physics, world);
+        assert!(!terrain.is_hole(5.0, 5.0));
+        restored.restore_snapshot(&snapshot);
+        assert!(terrain.is_hole(5.0, 5.0));
+        assert_eq!(
+            terrain.height_at(5.0, 5.0),
+            HeightmapTerrain::new(42, 64.0, 16).height_at(5.0, 5.0)
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Entity display data
+    // -----------------------------------------------------------------------
+
+    fn meta(id: &str, health: f32) -> EntityMeta {
+        EntityMeta {
+            entity_id: id.to_string(),
+            name: Some("Alice".to_string()),
+            health: Some(health),
+            status: vec!["afk".to_string()],
+            afk: false,
+        }
+    }
+
+    #[test]
+    fn entity_meta_requires_a_known_entity() {
+        let mut svc = make_service(1);
+        assert!(svc.set_entity_meta(meta("ghost", 1.0)).is_err());
+    }
+
+    #[test]
+    fn entity_meta_is_clamped_and_included_in_snapshot() {
+        let mut svc = make_service(1);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        let stored = svc
+            .set_entity_meta(meta("alice", 1.7))
+            .expect("known entity");
+        assert_eq!(stored.health, Some(1.0));
+
+        let snapshot = svc.build_snapshot("test");
+        assert_eq!(snapshot.entity_meta, vec![stored]);
+    }
+
+    #[test]
+    fn leaving_clears_entity_meta() {
+        let mut svc = make_service(1);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        svc.set_entity_meta(meta("alice", 0.5))
+            .expect("known entity");
+        svc.unregister_participant("alice");
+
+        assert!(svc.entity_meta("alice").is_none());
+        assert!(svc.tick().expect("tick").entity_meta.is_empty());
+    }
+
+    // -----------------------------------------------------------------------
+    // Emotes
+    // -----------------------------------------------------------------------
+
+    fn wave(id: &str) -> CmdEmote {
+        CmdEmote {
+            entity_id: id.to_string(),
+            emote_id: Some("wave".to_string()),
+            sound_id: None,
+        }
+    }
+
+    #[test]
+    fn emotes_reach_only_participants_within_hearing_range() {
+        let mut svc = make_service(1);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        svc.register_participant("bob".into(), Vec3::new(25.0, 0.0, 0.0));
+        svc.register_participant("carol".into(), Vec3::new(0.0, 45.0, 0.0));
+        // Walked out of earshot after joining.
+        svc.register_participant("dave".into(), Vec3::new(10.0, 10.0, 0.0));
+        svc.register_participant("dave".into(), Vec3::new(80.0, 0.0, 0.0));
+
+        let emote = svc.emote(wave("alice")).expect("known entity");
+        assert_eq!(emote.listeners, vec!["alice", "bob"]);
+        assert_eq!((emote.x, emote.y), (0.0, 0.0));
+
+        svc.unregister_participant("bob");
+        let emote = svc.emote(wave("carol")).expect("known entity");
+        assert_eq!(emote.listeners, vec!["carol"]);
+    }
+
+    #[test]
+    fn emotes_are_rate_limited_per_entity() {
+        let mut svc = make_service(1);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        svc.register_participant("bob".into(), Vec3::new(1.0, 0.0, 0.0));
+
+        assert_eq!(svc.emote_cooldown_s("alice"), 0.0);
+        svc.emote(wave("alice")).expect("first emote");
+        assert!(svc.emote_cooldown_s("alice") > 0.0);
+        assert!(svc.emote(wave("alice")).is_err());
+        svc.emote(wave("bob"))
+            .expect("other entities are unaffected");
+        assert!(svc
+            .emote(CmdEmote {
+                emote_id: None,
+                ..wave("bob")
+            })
+            .is_err());
+        assert!(svc.emote(wave("ghost")).is_err());
+    }
+
+    #[test]
+    fn rejected_intents_are_counted_by_reason_kind_and_sender() {
+        let mut svc = make_service(1);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+
+        svc.emote(wave("alice")).expect("first emote");
+        assert!(svc.emote(wave("alice")).is_err());
+        assert!(svc.emote(wave("alice")).is_err());
+        assert!(svc.apply_move_action("ghost", 1.0, 0.0, 0.0).is_err());
+
+        let drops = svc.stats().drops;
+        assert_eq!(drops.total, 3);
+        assert_eq!(drops.by_reason[&DropReason::RateLimited], 2);
+        assert_eq!(drops.by_reason[&DropReason::Invalid], 1);
+        assert_eq!(drops.by_kind["world.cmd.emote"], 2);
+        assert_eq!(drops.by_kind["action.move"], 1);
+        assert_eq!(drops.by_participant["alice"], 2);
+        assert_eq!(drops.by_participant["ghost"], 1);
+    }
+
+    // -----------------------------------------------------------------------
+    // Entity size
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn snapshot_entities_carry_scale_and_bounding_radius() {
+        let mut svc = make_service(1);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        svc.register_participant("bob".into(), Vec3::new(5.0, 0.0, 0.0));
+        svc.set_entity_scale("bob", 3.0).expect("known entity");
+
+        let snapshot = svc.build_snapshot("test");
+        let bob = snapshot
+            .entities
+            .iter()
+            .find(|e| e.entity_id == "bob")
+            .expect("bob");
+        assert_eq!(bob.scale, 3.0);
+        assert!((bob.bounding_radius - 1.5).abs() < 1e-5);
+        assert!((svc.bounding_radius("alice") - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn invalid_scales_are_rejected() {
+        let mut svc = make_service(1);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        assert!(svc.set_entity_scale("alice", 0.0).is_err());
+        assert!(svc.set_entity_scale("alice", f32::NAN).is_err());
+        assert!(svc.set_entity_scale("ghost", 2.0).is_err());
+        assert_eq!(svc.entity_scale("alice"), 1.0);
+    }
+
+    #[test]
+    fn interaction_range_is_measured_between_bounding_circles() {
+        let mut svc = make_service(1);
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        svc.register_participant("troll".into(), Vec3::new(4.0, 0.0, 0.0));
+        // 4 apart, radii 0.5 + 0.5.
+        assert!((svc.surface_distance("alice", "troll").unwrap() - 3.0).abs() < 1e-5);
+        assert!(!svc.in_interaction_range("alice", "troll", 2.0));
+
+        svc.set_entity_scale("troll", 4.0).expect("known entity");
+        assert!(svc.in_interaction_range("alice", "troll", 2.0));
+        assert!(!svc.in_interaction_range("alice", "ghost", 100.0));
+    }
+
+    // -----------------------------------------------------------------------
+    // AFK
+    // -----------------------------------------------------------------------
+
+    /// AFK after 3 ticks (0.1 s at 30 Hz).
+    fn make_afk_service() -> WorldService {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let world = Arc::new(World::new(terrain));
+        let physics = Arc::new(RwLock::new(PhysicsRegistry::new(
+            PhysicsRegistryConfig::default(),
+        )));
+        let config = WorldServiceConfig {
+            physics_dt: 1.0 / 30.0,
+            afk: Some(AfkConfig {
+                timeout_s: 0.1,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        WorldService::new(config, physics, world)
+    }
+
+    #[test]
+    fn idle_participants_are_flagged_afk() {
+        let mut svc = make_afk_service();
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        // Ticks fail without a physics simulation; AFK is decided before
+        // streaming, so the flag still updates.
+        for _ in 0..2 {
+            let _ = svc.tick();
+        }
+        assert!(!svc.is_afk("alice"));
+
+        let _ = svc.tick();
+        assert!(svc.is_afk("alice"));
+        assert!(svc.entity_meta("alice").expect("afk meta").afk);
+        assert_eq!(svc.stats().afk_participants, 1);
+    }
+
+    #[test]
+    fn next_intent_restores_full_streaming() {
+        let mut svc = make_afk_service();
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        for _ in 0..3 {
+            let _ = svc.tick();
+        }
+        assert!(svc.is_afk("alice"));
+
+        svc.apply_move_action("alice", 1.0, 0.0, 0.0)
+            .expect("known participant");
+        assert!(!svc.is_afk("alice"));
+        assert!(!svc.entity_meta("alice").expect("meta").afk);
+        assert_eq!(svc.stats().afk_participants, 0);
+    }
+
+    #[test]
+    fn game_meta_cannot_clear_the_afk_flag() {
+        let mut svc = make_afk_service();
+        svc.register_participant("alice".into(), Vec3::new(0.0, 0.0, 0.0));
+        for _ in 0..3 {
+            let _ = svc.tick();
+        }
+        let stored = svc
+            .set_entity_meta(meta("alice", 1.0))
+            .expect("known entity");
+        assert!(stored.afk);
+    }
 }
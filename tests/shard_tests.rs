@@ -0,0 +1,214 @@
+//! Region sharding tests
+
+#[cfg(test)]
+mod tests {
+    use janet_operations::physics::{
+        types::{
+            OntologyId, PhysicsRegistryConfig, Rapier2DConfig, SimulationMetadata, SimulationType,
+            Tier,
+        },
+        PhysicsRegistry, Rapier2DSimulation,
+    };
+    use janet_world::{
+        entity::EntitySpec,
+        protocol::{subjects, EntityMeta, ShardHandoffAck},
+        service::WorldService,
+        shard::ShardMap,
+        structure::World,
+        terrain::HeightmapTerrain,
+        types::{ShardConfig, ShardRegion, Vec3, WorldServiceConfig},
+    };
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+
+    /// West (`rx = -1`) belongs to shard "west", east (`rx = 0`) to "east";
+    /// everything else is unassigned.
+    fn shard_config(shard_id: &str) -> ShardConfig {
+        ShardConfig {
+            shard_id: shard_id.to_string(),
+            region_size: 100.0,
+            regions: vec![
+                ShardRegion {
+                    rx: -1,
+                    ry: 0,
+                    shard_id: "west".to_string(),
+                },
+                ShardRegion {
+                    rx: 0,
+                    ry: 0,
+                    shard_id: "east".to_string(),
+                },
+            ],
+            handoff_timeout_s: 0.1,
+        }
+    }
+
+    fn make_service(shard_id: &str) -> WorldService {
+        let terrain = Arc::new(HeightmapTerrain::new(42, 64.0, 16));
+        let world = Arc::new(World::new(terrain));
+        let mut registry = PhysicsRegistry::new(PhysicsRegistryConfig::default());
+        let metadata = SimulationMetadata {
+            id: format!("world-{}", shard_id),
+            mandate_id: "_world_test".to_string(),
+            ontology: OntologyId::Custom {
+                id: "Rapier2D".to_string(),
+            },
+            tier: Tier::Decidable,
+            overlays: vec![],
+            simulation_type: SimulationType::Rapier2D,
+            created_at_frame: 0,
+            name: "World Physics".to_string(),
+            description: None,
+            generator_id: None,
+        };
+        registry.set_default_simulation(Box::new(Rapier2DSimulation::new(
+            metadata,
+            Rapier2DConfig::default(),
+        )));
+        let physics = Arc::new(RwLock::new(registry));
+        let config = WorldServiceConfig {
+            physics_dt: 1.0 / 30.0,
+            activation_radius: 1,
+            shard: Some(shard_config(shard_id)),
+            ..Default::default()
+        };
+        WorldService::new(config, physics, world)
+    }
+
+    // -----------------------------------------------------------------------
+    // Shard map
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn owners_are_looked_up_by_super_region() {
+        let map = ShardMap::new(&shard_config("west"));
+        assert_eq!(map.region_of(Vec3::new(-0.5, 10.0, 0.0)), (-1, 0));
+        assert_eq!(map.owner_at(Vec3::new(-0.5, 10.0, 0.0)), Some("west"));
+        assert_eq!(map.owner_at(Vec3::new(0.5, 10.0, 0.0)), Some("east"));
+        assert_eq!(map.owner_at(Vec3::new(0.5, 150.0, 0.0)), None);
+
+        assert_eq!(map.handoff_target(Vec3::new(-50.0, 10.0, 0.0)), None);
+        assert_eq!(map.handoff_target(Vec3::new(50.0, 10.0, 0.0)), Some("east"));
+        assert_eq!(map.handoff_target(Vec3::new(50.0, 150.0, 0.0)), None);
+    }
+
+    #[test]
+    fn subjects_are_namespaced_by_shard() {
+        assert_eq!(
+            subjects::sharded(subjects::CHUNK_ACTIVATED, "east"),
+            "world.shard.east.chunk.activated"
+        );
+        assert_eq!(
+            subjects::sharded(subjects::ACTION_MOVE, "east"),
+            "action.shard.east.move"
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Handoff
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn crossing_the_border_hands_the_participant_over() {
+        let mut west = make_service("west");
+        let mut east = make_service("east");
+        west.register_participant("alice".into(), Vec3::new(-10.0, 10.0, 0.0));
+        west.register_participant("bob".into(), Vec3::new(-20.0, 10.0, 0.0));
+        west.set_entity_meta(EntityMeta {
+            entity_id: "alice".to_string(),
+            name: Some("Alice".to_string()),
+            health: Some(0.75),
+            status: vec![],
+            afk: false,
+        })
+        .expect("known entity");
+        west.acknowledge_intent("alice", 9);
+
+        let mut handoffs = Vec::new();
+        west.collect_handoffs(&mut handoffs);
+        assert!(handoffs.is_empty());
+
+        west.register_participant("alice".into(), Vec3::new(10.0, 10.0, 0.0));
+        west.collect_handoffs(&mut handoffs);
+        assert_eq!(handoffs.len(), 1);
+        let handoff = &handoffs[0];
+        assert_eq!(
+            (handoff.from_shard.as_str(), handoff.to_shard.as_str()),
+            ("west", "east")
+        );
+        assert_eq!(handoff.last_intent_seq, Some(9));
+
+        // West keeps alice (and doesn't offer her again) until east answers.
+        assert_eq!(west.participant_count(), 2);
+        assert_eq!(west.pending_handoff_count(), 1);
+        let mut again = Vec::new();
+        west.collect_handoffs(&mut again);
+        assert!(again.is_empty());
+
+        // Only the addressed shard takes the participant.
+        assert!(!make_service("west").accept_handoff(handoff));
+        assert!(east.accept_handoff(handoff));
+        assert_eq!(
+            east.participant_position("alice"),
+            Some(Vec3::new(10.0, 10.0, 0.0))
+        );
+        assert_eq!(east.entity_meta("alice").and_then(|m| m.health), Some(0.75));
+        assert_eq!(east.last_intent_seq("alice"), Some(9));
+
+        // The ack drops alice from west, once.
+        let ack = ShardHandoffAck::new(handoff);
+        assert!(!east.complete_handoff(&ack));
+        assert!(west.complete_handoff(&ack));
+        assert_eq!(west.participant_count(), 1);
+        assert!(west.participant_position("alice").is_none());
+        assert_eq!(west.pending_handoff_count(), 0);
+        assert!(!west.complete_handoff(&ack));
+        assert_eq!(subjects::SHARD_HANDOFF_ACK, "world.shard.handoff_ack");
+    }
+
+    #[test]
+    fn unacknowledged_handoffs_fall_back_and_retry() {
+        let mut west = make_service("west");
+        west.register_participant("alice".into(), Vec3::new(10.0, 10.0, 0.0));
+        let events = west.tick().expect("tick");
+        assert_eq!(events.handoffs.len(), 1);
+
+        // Nobody answers: alice stays simulated here, then is offered again
+        // once the 0.1s timeout (3 ticks) has passed.
+        let offered: Vec<usize> = (0..3)
+            .map(|_| west.tick().expect("tick").handoffs.len())
+            .collect();
+        assert_eq!(offered, [0, 0, 1]);
+        assert_eq!(west.participant_count(), 1);
+        assert!(west.participant_position("alice").is_some());
+    }
+
+    #[test]
+    fn server_entities_stay_with_their_shard() {
+        let mut west = make_service("west");
+        west.spawn_entity(EntitySpec {
+            entity_id: "cart-1".into(),
+            archetype: "vehicle/cart".into(),
+            x: 10.0,
+            y: 10.0,
+            z: Some(0.0),
+            ..Default::default()
+        })
+        .expect("spawn");
+        let mut handoffs = Vec::new();
+        west.collect_handoffs(&mut handoffs);
+        assert!(handoffs.is_empty());
+        assert_eq!(west.pending_handoff_count(), 0);
+        assert!(west.participant_position("cart-1").is_some());
+    }
+
+    #[test]
+    fn unassigned_regions_never_trigger_a_handoff() {
+        let mut west = make_service("west");
+        west.register_participant("alice".into(), Vec3::new(500.0, 500.0, 0.0));
+        let mut handoffs = Vec::new();
+        west.collect_handoffs(&mut handoffs);
+        assert!(handoffs.is_empty());
+        assert_eq!(west.participant_count(), 1);
+    }
+}
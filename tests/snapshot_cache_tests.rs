@@ -0,0 +1,105 @@
+//! Snapshot reply cache tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::protocol::{EncodedSnapshot, SnapshotEncoding, WorldSnapshot};
+    use janet_world::snapshot_cache::{decode, SnapshotCache};
+    use std::cell::Cell;
+
+    fn snapshot(sea_level: f32) -> WorldSnapshot {
+        serde_json::from_value(serde_json::json!({
+            "active_chunks": [],
+            "structures": [],
+            "entities": [],
+            "sea_level": sea_level,
+        }))
+        .unwrap()
+    }
+
+    // -----------------------------------------------------------------------
+    // Freshness
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn requests_within_max_age_share_one_build() {
+        let mut cache = SnapshotCache::new(5);
+        let builds = Cell::new(0);
+        let build = || {
+            builds.set(builds.get() + 1);
+            snapshot(1.0)
+        };
+
+        let first = cache.get_or_build(10, 0, false, build);
+        let again = cache.get_or_build(15, 0, false, build);
+        assert_eq!(builds.get(), 1);
+        assert_eq!(again.frame, 10);
+        assert_eq!(first.value, again.value);
+
+        let stale = cache.get_or_build(16, 0, false, build);
+        assert_eq!(builds.get(), 2);
+        assert_eq!(stale.frame, 16);
+    }
+
+    #[test]
+    fn terrain_edit_or_rewound_frame_forces_a_rebuild() {
+        let mut cache = SnapshotCache::new(100);
+        let builds = Cell::new(0);
+        let build = || {
+            builds.set(builds.get() + 1);
+            snapshot(1.0)
+        };
+
+        cache.get_or_build(10, 0, false, build);
+        cache.get_or_build(11, 1, false, build);
+        assert_eq!(builds.get(), 2);
+        // A restored checkpoint can put the tick counter behind the cache.
+        cache.get_or_build(3, 1, false, build);
+        assert_eq!(builds.get(), 3);
+    }
+
+    #[test]
+    fn full_and_compact_replies_are_cached_separately() {
+        let mut cache = SnapshotCache::new(5);
+        let full = cache.get_or_build(1, 0, false, || snapshot(1.0));
+        let compact = cache.get_or_build(1, 0, true, || snapshot(2.0));
+        assert!(!full.compact && compact.compact);
+        let value = compact.value.as_ref().unwrap();
+        assert!(value.get("string_table").is_some());
+        assert_eq!(value["sea_level"], 2.0);
+
+        cache.clear();
+        let rebuilt = cache.get_or_build(1, 0, false, || snapshot(3.0));
+        assert_eq!(rebuilt.value.as_ref().unwrap()["sea_level"], 3.0);
+    }
+
+    // -----------------------------------------------------------------------
+    // Encoding
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn gzip_reply_decodes_to_the_plain_reply() {
+        let mut cache = SnapshotCache::new(0);
+        let cached = cache.get_or_build(7, 0, false, || snapshot(4.5));
+        let encoded: EncodedSnapshot = serde_json::from_value(cached.encoded().unwrap()).unwrap();
+        assert_eq!(encoded.frame, 7);
+        assert_eq!(encoded.encoding, SnapshotEncoding::Gzip);
+        assert!(!encoded.compact);
+        assert_eq!(Some(decode(&encoded).unwrap()), cached.value);
+    }
+
+    #[test]
+    fn corrupt_encoded_data_is_an_error() {
+        let encoded = EncodedSnapshot {
+            frame: 0,
+            encoding: SnapshotEncoding::Gzip,
+            compact: false,
+            data: "not*base64".into(),
+        };
+        assert!(decode(&encoded).is_err());
+        let encoded = EncodedSnapshot {
+            data: "AAAA".into(),
+            ..encoded
+        };
+        assert!(decode(&encoded).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+//! Paged snapshot delivery tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::protocol::{subjects, SnapshotEncoding, SnapshotEnd};
+    use janet_world::snapshot_paging::{paginate, SnapshotAssembler, SnapshotPageError};
+
+    fn reply() -> serde_json::Value {
+        serde_json::json!({
+            "structures": [{ "structure_id": "hut \"north\"", "metadata": { "sign": "Café ☕" } }],
+            "entities": [],
+        })
+    }
+
+    #[test]
+    fn pages_reassemble_in_any_order() {
+        let json = serde_json::to_string(&reply()).unwrap();
+        let paged = paginate("cmd-1", 42, false, None, &json, 16);
+        assert!(paged.pages.len() > 4);
+        assert_eq!(paged.begin.total_pages, paged.pages.len() as u32);
+        assert_eq!(paged.begin.total_bytes, json.len());
+        for page in &paged.pages {
+            assert_eq!(page.total, paged.begin.total_pages);
+            // The limit holds for the escaped page, as it goes on the wire.
+            let escaped = serde_json::to_string(&page.data).unwrap();
+            assert!(escaped.len() - 2 <= 16, "{:?}", page.data);
+        }
+
+        let mut assembler = SnapshotAssembler::new();
+        assembler.expect("cmd-1");
+        for page in paged.pages.iter().rev() {
+            assembler.page(page);
+        }
+        assembler.begin(&paged.begin);
+        let assembled = assembler.end(&paged.end).unwrap().expect("expected");
+        assert_eq!(assembled.value, reply());
+        assert_eq!(assembled.begin.frame, 42);
+        assert!(!assembler.is_pending("cmd-1"));
+    }
+
+    #[test]
+    fn tiny_pages_still_hold_a_character() {
+        let paged = paginate("cmd-1", 0, true, Some(SnapshotEncoding::Gzip), "\"☕\"", 1);
+        let data: Vec<_> = paged.pages.iter().map(|p| p.data.as_str()).collect();
+        assert_eq!(data, ["\"", "☕", "\""]);
+        assert!(paged.begin.compact);
+        assert_eq!(paged.begin.encoding, Some(SnapshotEncoding::Gzip));
+    }
+
+    #[test]
+    fn incomplete_and_unexpected_snapshots() {
+        let json = serde_json::to_string(&reply()).unwrap();
+        let paged = paginate("cmd-1", 0, false, None, &json, 32);
+        let mut assembler = SnapshotAssembler::new();
+
+        // Someone else's snapshot.
+        assembler.begin(&paged.begin);
+        assembler.page(&paged.pages[0]);
+        assert_eq!(assembler.end(&paged.end), Ok(None));
+
+        assembler.expect("cmd-1");
+        assembler.begin(&paged.begin);
+        for page in &paged.pages[1..] {
+            assembler.page(page);
+        }
+        assert_eq!(
+            assembler.end(&paged.end),
+            Err(SnapshotPageError::Incomplete {
+                snapshot_id: "cmd-1".into(),
+                missing: 1,
+                total: paged.begin.total_pages,
+            })
+        );
+
+        assembler.expect("cmd-2");
+        let end = SnapshotEnd {
+            snapshot_id: "cmd-2".into(),
+            total_pages: 0,
+        };
+        assert!(matches!(
+            assembler.end(&end),
+            Err(SnapshotPageError::NotBegun(id)) if id == "cmd-2"
+        ));
+        assert_eq!(subjects::SNAPSHOT_PAGE, "world.snapshot.page");
+    }
+}
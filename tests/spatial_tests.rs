@@ -0,0 +1,46 @@
+//! Participant position grid tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::spatial::SpatialGrid;
+    use janet_world::types::Vec3;
+    use std::sync::Arc;
+
+    fn ids(grid: &SpatialGrid, x: f32, y: f32, radius: f32) -> Vec<String> {
+        let mut out: Vec<String> = grid
+            .candidates(x, y, radius)
+            .into_iter()
+            .map(|id| id.to_string())
+            .collect();
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn candidates_cover_the_buckets_the_circle_overlaps() {
+        let mut grid = SpatialGrid::new(10.0);
+        for (id, x) in [("a", 1.0), ("b", 15.0), ("c", 45.0), ("d", -3.0)] {
+            grid.update(&Arc::from(id), Vec3::new(x, 1.0, 0.0));
+        }
+        assert_eq!(ids(&grid, 5.0, 5.0, 4.0), vec!["a"]);
+        assert_eq!(ids(&grid, 5.0, 5.0, 6.0), vec!["a", "b", "d"]);
+        // Wider than the populated area.
+        assert_eq!(ids(&grid, 0.0, 0.0, 1000.0), vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn moves_and_removals_update_the_buckets() {
+        let mut grid = SpatialGrid::new(10.0);
+        let a: Arc<str> = Arc::from("a");
+        grid.update(&a, Vec3::new(1.0, 1.0, 0.0));
+        grid.update(&a, Vec3::new(2.0, 2.0, 0.0));
+        grid.update(&a, Vec3::new(55.0, 1.0, 0.0));
+        assert_eq!(grid.len(), 1);
+        assert!(ids(&grid, 5.0, 5.0, 4.0).is_empty());
+        assert_eq!(ids(&grid, 55.0, 5.0, 4.0), vec!["a"]);
+
+        grid.remove("a");
+        assert!(grid.is_empty());
+        assert!(ids(&grid, 55.0, 5.0, 4.0).is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+//! Spawner tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::spawner::Spawners;
+    use janet_world::worldgen::{SpawnerDef, WorldGenConfig};
+
+    fn den(max_population: usize, respawn_s: f32) -> SpawnerDef {
+        SpawnerDef {
+            id: "den".into(),
+            archetype: "creature/wolf".into(),
+            x: 100.0,
+            y: 50.0,
+            radius: 10.0,
+            max_population,
+            respawn_s,
+            components: [("pack".to_string(), serde_json::json!("grey"))].into(),
+            behaviour: None,
+        }
+    }
+
+    fn spawned_ids(spawners: &mut Spawners, now_s: f64, active: bool) -> Vec<String> {
+        let change = spawners.evaluate(now_s, |_, _| active);
+        change.spawned.into_iter().map(|s| s.entity_id).collect()
+    }
+
+    #[test]
+    fn active_spawners_fill_up_within_their_radius() {
+        let mut spawners = Spawners::new(vec![den(3, 0.0)], 42);
+        let change = spawners.evaluate(0.0, |_, _| true);
+        assert_eq!(change.spawned.len(), 3);
+        assert!(change.despawned.is_empty());
+        for s in &change.spawned {
+            assert_eq!(s.archetype, "creature/wolf");
+            assert_eq!(s.components["pack"], "grey");
+            assert!((s.x - 100.0).hypot(s.y - 50.0) <= 10.0, "{:?}", s);
+            assert!(spawners.contains(&s.entity_id));
+        }
+        assert_eq!(spawners.population("den"), 3);
+
+        // Full: nothing to do.
+        assert_eq!(spawners.evaluate(1.0, |_, _| true), Default::default());
+
+        // The same seed places the same entities at the same spots.
+        let again = Spawners::new(vec![den(3, 0.0)], 42).evaluate(0.0, |_, _| true);
+        assert_eq!(again.spawned, change.spawned);
+    }
+
+    #[test]
+    fn lost_entities_respawn_after_the_timer() {
+        let mut spawners = Spawners::new(vec![den(2, 30.0)], 42);
+        let ids = spawned_ids(&mut spawners, 0.0, true);
+        spawners.forget(&ids[0], 10.0);
+        assert_eq!(spawners.population("den"), 1);
+
+        assert!(spawned_ids(&mut spawners, 39.0, true).is_empty());
+        assert_eq!(spawned_ids(&mut spawners, 40.0, true), ["spawner:den:2"]);
+    }
+
+    #[test]
+    fn deactivation_despawns_and_reactivation_refills() {
+        let mut spawners = Spawners::new(vec![den(2, 300.0)], 42);
+        let ids = spawned_ids(&mut spawners, 0.0, true);
+        spawners.forget(&ids[0], 1.0);
+
+        let change = spawners.evaluate(2.0, |_, _| false);
+        assert!(change.spawned.is_empty());
+        assert_eq!(change.despawned, [ids[1].clone()]);
+        assert_eq!(spawners.population("den"), 0);
+        assert_eq!(spawners.ids().count(), 0);
+
+        // Pending respawns are dropped: the spawner refills at once.
+        assert_eq!(spawned_ids(&mut spawners, 3.0, true).len(), 2);
+    }
+
+    #[test]
+    fn spawners_load_from_world_gen_toml() {
+        let gen = WorldGenConfig::from_toml(
+            r#"
+            [[spawners]]
+            id = "depot"
+            archetype = "vehicle/cart"
+            x = 10.0
+            y = -4.0
+            "#,
+        )
+        .unwrap();
+        let depot = &gen.spawners[0];
+        assert_eq!(depot.archetype, "vehicle/cart");
+        assert_eq!(
+            (depot.radius, depot.max_population, depot.respawn_s),
+            (0.0, 1, 0.0)
+        );
+    }
+}
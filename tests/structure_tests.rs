@@ -0,0 +1,396 @@
+//! Structure registry tests
+
+#[cfg(test)]
+mod tests {
+    use janet_operations::physics::types::ColliderShape;
+    use janet_world::structure::{
+        StreamTier, StructureCatalog, StructureFileError, StructureInstance, StructureMeta,
+        StructureMetaError, StructureRegistry, StructureState, StructureStates,
+        STRUCTURE_FILE_VERSION,
+    };
+    use janet_world::types::Vec3;
+    use std::collections::BTreeMap;
+
+    fn structure(id: &str, x: f32, y: f32, radius: f32) -> StructureInstance {
+        let mut s =
+            StructureInstance::new(id, Vec3::new(x, y, 0.0), ColliderShape::Circle { radius });
+        s.bounds_radius = radius;
+        s.metadata
+            .insert("type_id".into(), serde_json::json!("props/rock"));
+        s
+    }
+
+    fn ids(found: Vec<&StructureInstance>) -> Vec<String> {
+        let mut out: Vec<String> = found.into_iter().map(|s| s.id.clone()).collect();
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn rect_queries_match_a_linear_scan() {
+        let mut registry = StructureRegistry::with_bucket_size(10.0);
+        let mut all = Vec::new();
+        for i in 0..200 {
+            let (x, y) = ((i * 37 % 300) as f32 - 150.0, (i * 53 % 300) as f32 - 150.0);
+            let radius = 0.5 + (i % 7) as f32 * 4.0;
+            registry
+                .insert(structure(&format!("s{}", i), x, y, radius))
+                .unwrap();
+            all.push((format!("s{}", i), x, y, radius));
+        }
+
+        for (min_x, min_y, max_x, max_y) in [
+            (-5.0, -5.0, 5.0, 5.0),
+            (12.5, -80.0, 47.0, -20.0),
+            (-1000.0, -1000.0, 1000.0, 1000.0),
+            (f32::MIN, f32::MIN, f32::MAX, f32::MAX),
+            (400.0, 400.0, 410.0, 410.0),
+        ] {
+            let mut expected: Vec<String> = all
+                .iter()
+                .filter(|(_, x, y, r)| {
+                    x + r >= min_x && x - r <= max_x && y + r >= min_y && y - r <= max_y
+                })
+                .map(|(id, ..)| id.clone())
+                .collect();
+            expected.sort();
+            assert_eq!(
+                ids(registry.query_rect(min_x, min_y, max_x, max_y)),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn structures_spanning_buckets_are_reported_once() {
+        let mut registry = StructureRegistry::with_bucket_size(10.0);
+        registry.insert(structure("hall", 0.0, 0.0, 25.0)).unwrap();
+        assert_eq!(ids(registry.query_rect(-30.0, -30.0, 30.0, 30.0)), ["hall"]);
+        assert_eq!(ids(registry.query_rect(15.0, 15.0, 16.0, 16.0)), ["hall"]);
+        assert_eq!(ids(registry.query_radius(30.0, 0.0, 6.0)), ["hall"]);
+        assert!(registry.query_radius(30.0, 30.0, 6.0).is_empty());
+    }
+
+    #[test]
+    fn reinserting_and_removing_update_the_buckets() {
+        let mut registry = StructureRegistry::with_bucket_size(10.0);
+        registry.insert(structure("rock", 5.0, 5.0, 1.0)).unwrap();
+        registry.insert(structure("rock", 105.0, 5.0, 1.0)).unwrap();
+        assert_eq!(registry.len(), 1);
+        assert!(registry.query_rect(0.0, 0.0, 10.0, 10.0).is_empty());
+        assert_eq!(ids(registry.query_rect(100.0, 0.0, 110.0, 10.0)), ["rock"]);
+
+        assert!(registry.remove("rock").is_some());
+        assert!(registry.is_empty());
+        assert!(registry.query_rect(100.0, 0.0, 110.0, 10.0).is_empty());
+    }
+
+    #[test]
+    fn metadata_is_checked_on_insert() {
+        let mut registry = StructureRegistry::new();
+        let mut door = structure("door", 0.0, 0.0, 1.0);
+        door.metadata
+            .insert("verbs".into(), serde_json::json!(["open", "close"]));
+        door.metadata
+            .insert("asset".into(), serde_json::json!("res://props/door.tscn"));
+        door.metadata.insert("colour".into(), serde_json::json!(3));
+        assert_eq!(
+            door.meta().unwrap(),
+            StructureMeta {
+                type_id: "props/rock".into(),
+                asset: Some("res://props/door.tscn".into()),
+                verbs: vec!["open".into(), "close".into()],
+            }
+        );
+        registry.insert(door).unwrap();
+
+        let mut untyped = structure("untyped", 0.0, 0.0, 1.0);
+        untyped.metadata.remove("type_id");
+        assert_eq!(
+            registry.insert(untyped),
+            Err(StructureMetaError::MissingTypeId("untyped".into()))
+        );
+        for (key, value) in [
+            ("type_id", serde_json::json!("")),
+            ("verbs", serde_json::json!([1])),
+            ("asset", serde_json::json!(false)),
+        ] {
+            let mut bad = structure("bad", 0.0, 0.0, 1.0);
+            bad.metadata.insert(key.into(), value);
+            assert!(
+                matches!(registry.insert(bad), Err(StructureMetaError::Invalid { key: k, .. }) if k == key),
+                "{}",
+                key
+            );
+        }
+        assert_eq!(ids(registry.query_radius(0.0, 0.0, 1.0)), ["door"]);
+    }
+
+    #[test]
+    fn states_must_name_defined_states() {
+        let mut registry = StructureRegistry::new();
+        let mut door = structure("door", 0.0, 0.0, 1.0);
+        door.states = Some(StructureStates {
+            state: "closed".into(),
+            states: BTreeMap::from([
+                (
+                    "closed".into(),
+                    StructureState {
+                        collider: Some(ColliderShape::Circle { radius: 1.0 }),
+                        transitions: BTreeMap::from([("open".into(), "ajar".into())]),
+                    },
+                ),
+                ("open".into(), StructureState::default()),
+            ]),
+        });
+        assert_eq!(
+            registry.insert(door.clone()),
+            Err(StructureMetaError::UnknownState {
+                id: "door".into(),
+                state: "ajar".into(),
+            })
+        );
+
+        let states = door.states.as_mut().unwrap();
+        states
+            .states
+            .get_mut("closed")
+            .unwrap()
+            .transitions
+            .insert("open".into(), "open".into());
+        assert_eq!(states.next(None), Some(("open", "open")));
+        assert_eq!(states.next(Some("kick")), None);
+        registry.insert(door).unwrap();
+
+        assert!(registry.set_state("door", "ajar").is_err());
+        assert_eq!(registry.set_state("door", "open").unwrap(), "closed");
+        let door = registry.get("door").unwrap();
+        assert!(door.body_collider().is_none());
+        assert_eq!(door.to_spawned().state.as_deref(), Some("open"));
+        assert!(structure("rock", 0.0, 0.0, 1.0).body_collider().is_some());
+    }
+
+    #[test]
+    fn tag_queries_follow_inserts_and_removals() {
+        let mut registry = StructureRegistry::with_bucket_size(10.0);
+        for (id, x) in [("fire-a", 0.0), ("fire-b", 50.0), ("beacon", 5.0)] {
+            let mut s = structure(id, x, 0.0, 1.0);
+            s.tags = vec![if id == "beacon" {
+                "spawn_beacon"
+            } else {
+                "campfire"
+            }
+            .into()];
+            registry.insert(s).unwrap();
+        }
+        let everywhere = |r: &StructureRegistry, tag: &str| {
+            ids(r.query_by_tag(
+                tag,
+                f32::NEG_INFINITY,
+                f32::NEG_INFINITY,
+                f32::INFINITY,
+                f32::INFINITY,
+            ))
+        };
+        assert_eq!(everywhere(&registry, "campfire"), ["fire-a", "fire-b"]);
+        assert_eq!(
+            ids(registry.query_by_tag("campfire", 40.0, -5.0, 60.0, 5.0)),
+            ["fire-b"]
+        );
+        assert!(everywhere(&registry, "tree").is_empty());
+
+        // Retagging on reinsert and removal keep the index current.
+        let mut fire = structure("fire-b", 50.0, 0.0, 1.0);
+        fire.tags = vec!["embers".into()];
+        registry.insert(fire).unwrap();
+        assert_eq!(everywhere(&registry, "campfire"), ["fire-a"]);
+        registry.remove("fire-b");
+        assert!(everywhere(&registry, "embers").is_empty());
+    }
+
+    fn temp_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "janet_world_structures_{}_{}.json",
+            std::process::id(),
+            name
+        ))
+    }
+
+    #[test]
+    fn structure_files_round_trip() {
+        let mut registry = StructureRegistry::new();
+        let mut hut = StructureInstance::new(
+            "hut",
+            Vec3::new(12.0, -4.5, 3.25),
+            ColliderShape::Box {
+                width: 4.0,
+                height: 3.0,
+            },
+        );
+        hut.bounds_radius = 2.5;
+        hut.pitch = 0.1;
+        hut.tier = StreamTier::Near;
+        hut.scale = [2.0, 2.0, 1.5];
+        hut.metadata
+            .insert("type_id".into(), serde_json::json!("buildings/hut"));
+        hut.metadata
+            .insert("owner".into(), serde_json::json!("alice"));
+        registry.insert(hut).unwrap();
+        registry.insert(structure("rock", 1.0, 2.0, 0.5)).unwrap();
+
+        let path = temp_file("round_trip");
+        registry.save_to_file(&path).unwrap();
+        let saved: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(saved["version"], STRUCTURE_FILE_VERSION);
+        assert_eq!(saved["structures"][0]["id"], "hut");
+        assert_eq!(saved["structures"][0]["type_id"], "buildings/hut");
+        assert_eq!(saved["structures"][0]["metadata"]["owner"], "alice");
+        assert_eq!(saved["structures"][1]["id"], "rock");
+        assert_eq!(saved["structures"][0]["tier"], "near");
+        assert!(saved["structures"][1].get("scale").is_none());
+        assert!(saved["structures"][1].get("tier").is_none());
+
+        let mut loaded = StructureRegistry::with_bucket_size(10.0);
+        assert_eq!(loaded.load_from_file(&path).unwrap(), 2);
+        std::fs::remove_file(&path).unwrap();
+        let hut = loaded.get("hut").expect("hut");
+        assert_eq!(hut.position, Vec3::new(12.0, -4.5, 3.25));
+        assert_eq!(hut.bounds_radius, 2.5);
+        assert_eq!(
+            (hut.pitch, hut.roll, hut.scale),
+            (0.1, 0.0, [2.0, 2.0, 1.5])
+        );
+        assert_eq!(hut.tier, StreamTier::Near);
+        assert_eq!(hut.to_spawned().type_id, "buildings/hut");
+        assert_eq!(hut.metadata["owner"], "alice");
+        assert_eq!(ids(loaded.query_radius(1.0, 2.0, 0.1)), ["rock"]);
+    }
+
+    #[test]
+    fn legacy_metadata_tags_load_into_the_tag_index() {
+        let path = temp_file("legacy_tags");
+        let file = serde_json::json!({
+            "version": STRUCTURE_FILE_VERSION,
+            "structures": [{
+                "id": "fire",
+                "type_id": "props/campfire",
+                "position": { "x": 3.0, "y": 0.0, "z": 0.0 },
+                "collider": { "Circle": { "radius": 1.0 } },
+                "metadata": { "tags": ["campfire"] },
+            }],
+        });
+        std::fs::write(&path, serde_json::to_vec(&file).unwrap()).unwrap();
+        let mut registry = StructureRegistry::new();
+        assert_eq!(registry.load_from_file(&path).unwrap(), 1);
+        std::fs::remove_file(&path).unwrap();
+
+        let fire = registry.get("fire").expect("fire");
+        assert_eq!(fire.tags, ["campfire"]);
+        assert!(!fire.metadata.contains_key("tags"));
+        assert_eq!(
+            ids(registry.query_by_tag("campfire", 0.0, -1.0, 5.0, 1.0)),
+            ["fire"]
+        );
+    }
+
+    #[test]
+    fn invalid_structure_files_insert_nothing() {
+        let path = temp_file("invalid");
+        let rock = serde_json::json!({
+            "id": "rock",
+            "type_id": "props/rock",
+            "position": { "x": 0.0, "y": 0.0, "z": 0.0 },
+            "collider": { "Circle": { "radius": 1.0 } },
+        });
+        let mut registry = StructureRegistry::new();
+
+        std::fs::write(
+            &path,
+            serde_json::json!({ "version": 1, "structures": [rock, rock] }).to_string(),
+        )
+        .unwrap();
+        assert!(matches!(
+            registry.load_from_file(&path),
+            Err(StructureFileError::Duplicate(id)) if id == "rock"
+        ));
+
+        let mut untyped = rock.clone();
+        untyped["id"] = serde_json::json!("stone");
+        untyped.as_object_mut().unwrap().remove("type_id");
+        std::fs::write(
+            &path,
+            serde_json::json!({ "version": 1, "structures": [rock, untyped] }).to_string(),
+        )
+        .unwrap();
+        assert!(matches!(
+            registry.load_from_file(&path),
+            Err(StructureFileError::Metadata(StructureMetaError::MissingTypeId(id))) if id == "stone"
+        ));
+
+        std::fs::write(
+            &path,
+            serde_json::json!({ "version": 99, "structures": [rock] }).to_string(),
+        )
+        .unwrap();
+        assert!(matches!(
+            registry.load_from_file(&path),
+            Err(StructureFileError::Version(99))
+        ));
+        std::fs::remove_file(&path).unwrap();
+        assert!(registry.is_empty());
+        assert!(matches!(
+            registry.load_from_file(&path),
+            Err(StructureFileError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn prefabs_fill_in_collider_bounds_and_metadata() {
+        let catalog = StructureCatalog::from_toml(
+            r#"
+            [prefabs."buildings/hut"]
+            collider = { Box = { width = 6.0, height = 8.0 } }
+            tags = ["shelter", "wood"]
+            metadata = { asset = "res://buildings/hut.tscn" }
+
+            [prefabs."props/barrel"]
+            collider = { Circle = { radius = 0.4 } }
+            bounds_radius = 1.0
+            "#,
+        )
+        .unwrap();
+
+        let hut = StructureInstance::from_prefab(
+            &catalog,
+            "buildings/hut",
+            "hut-1",
+            Vec3::new(1.0, 2.0, 3.0),
+        )
+        .expect("hut prefab");
+        assert_eq!(
+            hut.collider,
+            ColliderShape::Box {
+                width: 6.0,
+                height: 8.0
+            }
+        );
+        assert_eq!(hut.bounds_radius, 5.0);
+        assert_eq!(hut.to_spawned().type_id, "buildings/hut");
+        assert_eq!(hut.tags, ["shelter", "wood"]);
+        assert_eq!(
+            hut.to_spawned().metadata["tags"],
+            serde_json::json!(["shelter", "wood"])
+        );
+        assert_eq!(hut.metadata["asset"], "res://buildings/hut.tscn");
+
+        let barrel =
+            StructureInstance::from_prefab(&catalog, "props/barrel", "b", Vec3::zero()).unwrap();
+        assert_eq!(barrel.bounds_radius, 1.0);
+        assert!(barrel.tags.is_empty());
+        assert!(barrel.to_spawned().metadata.get("tags").is_none());
+        assert!(
+            StructureInstance::from_prefab(&catalog, "props/tent", "t", Vec3::zero()).is_none()
+        );
+    }
+}
@@ -2,7 +2,13 @@
 
 #[cfg(test)]
 mod tests {
-    use janet_world::terrain::{HeightmapTerrain, TerrainSource};
+    use janet_operations::physics::types::ColliderShape;
+    use janet_world::flat_terrain::FlatTerrain;
+    use janet_world::protocol::ChunkHoles;
+    use janet_world::terrain::{
+        heights_checksum, solid_footprint, CacheBudget, HeightChunk, HeightmapTerrain,
+        Interpolation, TerrainSource, REGION_STATS_SAMPLES,
+    };
 
     fn make_terrain(seed: u64) -> HeightmapTerrain {
         HeightmapTerrain::new(seed, 64.0, 32)
@@ -42,6 +48,7 @@ mod tests {
     // -----------------------------------------------------------------------
 
     #[test]
+    #[allow(clippy::manual_range_contains)]
     fn height_within_expected_range() {
         let t = make_terrain(42);
         for x in [-100, 0, 100, 500] {
@@ -59,6 +66,54 @@ mod tests {
         }
     }
 
+    // -----------------------------------------------------------------------
+    // Interpolation
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn height_matches_grid_sample_exactly_on_grid_points() {
+        let t = make_terrain(42);
+        let chunk = t.get_or_generate_chunk(0, 0, 0);
+        // 64 m chunk / 32 samples → 2 m spacing; (3, 5) is world (6, 10).
+        let expected = chunk.heights[5 * chunk.resolution + 3];
+        assert!((t.height_at(6.0, 10.0) - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bilinear_midpoint_is_average_of_neighbours() {
+        let t = make_terrain(42);
+        let a = t.height_at(6.0, 10.0);
+        let b = t.height_at(8.0, 10.0);
+        let mid = t.height_at(7.0, 10.0);
+        assert!((mid - (a + b) / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn height_is_continuous_across_chunk_edges() {
+        for interpolation in [Interpolation::Bilinear, Interpolation::Bicubic] {
+            let t = make_terrain(42).with_interpolation(interpolation);
+            for y in [0.5f32, 17.3, 40.0] {
+                let left = t.height_at(64.0 - 1e-3, y);
+                let right = t.height_at(64.0 + 1e-3, y);
+                assert!(
+                    (left - right).abs() < 1e-3,
+                    "{:?} discontinuity at x=64, y={}: {} vs {}",
+                    interpolation,
+                    y,
+                    left,
+                    right
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bicubic_passes_through_grid_samples() {
+        let linear = make_terrain(42);
+        let cubic = make_terrain(42).with_interpolation(Interpolation::Bicubic);
+        assert!((linear.height_at(6.0, 10.0) - cubic.height_at(6.0, 10.0)).abs() < 1e-5);
+    }
+
     // -----------------------------------------------------------------------
     // Normals
     // -----------------------------------------------------------------------
@@ -67,8 +122,52 @@ mod tests {
     fn normal_has_nonzero_z() {
         let t = make_terrain(42);
         let n = t.normal_at(30.0, 30.0);
-        // Z is always 2*eps = 1.0 in the current FD implementation
         assert!(n.z > 0.0, "normal Z should be positive, got {:?}", n);
+        let len = (n.x * n.x + n.y * n.y + n.z * n.z).sqrt();
+        assert!(
+            (len - 1.0).abs() < 1e-5,
+            "normal should be unit, got {}",
+            len
+        );
+    }
+
+    /// Finite-difference normal over a tiny step, for comparison.
+    fn numeric_normal(t: &HeightmapTerrain, x: f32, y: f32) -> Vec3 {
+        let eps = 1e-3;
+        let dx = (t.height_at(x + eps, y) - t.height_at(x - eps, y)) / (2.0 * eps);
+        let dy = (t.height_at(x, y + eps) - t.height_at(x, y - eps)) / (2.0 * eps);
+        let len = (dx * dx + dy * dy + 1.0).sqrt();
+        Vec3::new(-dx / len, -dy / len, 1.0 / len)
+    }
+
+    #[test]
+    fn analytical_normals_match_the_surface_slope() {
+        for interpolation in [Interpolation::Bilinear, Interpolation::Bicubic] {
+            let t = make_terrain(42).with_interpolation(interpolation);
+            for (x, y) in [(1.3, 2.7), (17.9, 5.2), (-8.4, 33.1)] {
+                let a = t.normal_at(x, y);
+                let n = numeric_normal(&t, x, y);
+                assert!(
+                    (a.x - n.x).abs() < 1e-2 && (a.y - n.y).abs() < 1e-2,
+                    "{:?} at ({}, {}): {:?} vs {:?}",
+                    interpolation,
+                    x,
+                    y,
+                    a,
+                    n
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bicubic_normals_are_continuous_across_grid_lines() {
+        let t = make_terrain(42).with_interpolation(Interpolation::Bicubic);
+        // LOD 0 samples are 2 units apart (64 / 32); x = 12 is a grid line.
+        let left = t.normal_at(12.0 - 1e-3, 4.5);
+        let right = t.normal_at(12.0 + 1e-3, 4.5);
+        assert!((left.x - right.x).abs() < 1e-3, "{:?} vs {:?}", left, right);
+        assert!((left.y - right.y).abs() < 1e-3, "{:?} vs {:?}", left, right);
     }
 
     // -----------------------------------------------------------------------
@@ -106,6 +205,65 @@ mod tests {
         assert_eq!(t.lod_for_distance(350.0), 2);
     }
 
+    /// Row 0 (`col = false`) or column 0 of a chunk, as a list of heights.
+    fn border(chunk: &HeightChunk, col: bool) -> Vec<f32> {
+        (0..chunk.resolution)
+            .map(|i| chunk.heights[if col { i * chunk.resolution } else { i }])
+            .collect()
+    }
+
+    /// Fine border vertices must lie on the coarse border polyline: equal
+    /// where they coincide, linearly interpolated in between.
+    fn assert_on_polyline(fine: &[f32], coarse: &[f32], next_corner: f32) {
+        let step = fine.len() / coarse.len();
+        for (i, &h) in fine.iter().enumerate() {
+            let (k, t) = (i / step, (i % step) as f32 / step as f32);
+            let b = coarse.get(k + 1).copied().unwrap_or(next_corner);
+            let expected = coarse[k] + (b - coarse[k]) * t;
+            assert!(
+                (h - expected).abs() < 1e-5,
+                "vertex {}: {} vs {}",
+                i,
+                h,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn chunk_borders_match_across_mixed_lods() {
+        // Erosion output differs per LOD; stitched borders must not.
+        let t = make_terrain(42).with_erosion(ErosionConfig::default());
+        t.deform(64.0, 20.0, 6.0, 1.5);
+        for lod in 0..=2 {
+            for (cx, cy) in [(0, 0), (1, 0), (0, 1)] {
+                let fine = t.get_or_generate_chunk(cx, cy, 0);
+                let coarse = t.get_or_generate_chunk(cx, cy, lod);
+                for col in [false, true] {
+                    let (dx, dy) = if col { (0, 1) } else { (1, 0) };
+                    // The far end of the border is the next chunk's corner.
+                    let corner = t.get_or_generate_chunk(cx + dx, cy + dy, lod).heights[0];
+                    assert_on_polyline(&border(&fine, col), &border(&coarse, col), corner);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn neighbours_at_different_lods_share_the_border() {
+        let t = make_terrain(42);
+        // Chunk (0,0) meshed at LOD 2 closes its +x edge against chunk
+        // (1,0)'s column 0, which may be at LOD 0: its coarse vertices are
+        // the same samples.
+        let near = t.get_or_generate_chunk(1, 0, 0);
+        let far = t.get_or_generate_chunk(1, 0, 2);
+        let step = near.resolution / far.resolution;
+        for (i, &h) in border(&far, true).iter().enumerate() {
+            assert_eq!(near.heights[i * step * near.resolution], h);
+        }
+        assert_eq!(t.border_resolution(), 8);
+    }
+
     // -----------------------------------------------------------------------
     // Eviction
     // -----------------------------------------------------------------------
@@ -125,6 +283,69 @@ mod tests {
         let _ = t.get_or_generate_chunk(5, 5, 0);
     }
 
+    #[test]
+    fn cache_budget_evicts_least_recently_used() {
+        let t = make_terrain(42).with_cache_budget(CacheBudget::Chunks(2));
+        t.get_or_generate_chunk(0, 0, 0);
+        t.get_or_generate_chunk(1, 0, 0);
+        t.get_or_generate_chunk(0, 0, 0); // (1, 0) is now least recent
+        t.get_or_generate_chunk(2, 0, 0);
+
+        assert!(t.is_cached(0, 0, 0));
+        assert!(!t.is_cached(1, 0, 0));
+        assert!(t.is_cached(2, 0, 0));
+
+        let stats = t.cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 3, 1));
+        assert_eq!(stats.chunks, 2);
+    }
+
+    #[test]
+    fn byte_budget_bounds_cache_size() {
+        let chunk_bytes = make_terrain(42)
+            .get_or_generate_chunk(0, 0, 0)
+            .memory_bytes();
+        let t = make_terrain(42).with_cache_budget(CacheBudget::Bytes(chunk_bytes * 3));
+        for x in 0..10 {
+            t.get_or_generate_chunk(x, 0, 0);
+        }
+        let stats = t.cache_stats();
+        assert_eq!(stats.chunks, 3);
+        assert!(stats.bytes <= chunk_bytes * 3);
+        assert_eq!(stats.evictions, 7);
+    }
+
+    #[test]
+    fn distance_eviction_is_counted() {
+        let t = make_terrain(42);
+        for x in 0..4 {
+            t.get_or_generate_chunk(x, 0, 0);
+        }
+        t.evict_distant_chunks(0, 0, 1);
+        assert_eq!(t.cache_stats().evictions, 2);
+        assert_eq!(t.cache_stats().chunks, 2);
+    }
+
+    #[test]
+    fn generation_is_profiled() {
+        let t = make_terrain(42).with_slow_generation_ms(f32::MIN_POSITIVE);
+        assert_eq!(t.cache_stats().hit_rate, 0.0);
+        t.get_or_generate_chunk(0, 0, 0);
+        t.get_or_generate_chunk(1, 0, 0);
+        t.get_or_generate_chunk(0, 0, 0);
+        t.get_or_generate_chunk(0, 0, 0);
+
+        let stats = t.cache_stats();
+        assert_eq!(stats.hit_rate, 0.5);
+        assert!(stats.generation_ms_mean > 0.0);
+        assert!(stats.generation_ms_max >= stats.generation_ms_mean);
+        assert_eq!(stats.slow_generations, 2);
+
+        let quiet = make_terrain(42).with_slow_generation_ms(0.0);
+        quiet.get_or_generate_chunk(0, 0, 0);
+        assert_eq!(quiet.cache_stats().slow_generations, 0);
+    }
+
     // -----------------------------------------------------------------------
     // Heightfield collider shape
     // -----------------------------------------------------------------------
@@ -144,5 +365,689 @@ mod tests {
         }
     }
 
+    // -----------------------------------------------------------------------
+    // Deformation
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn deform_digs_at_centre_and_leaves_outside_untouched() {
+        let t = make_terrain(42);
+        let centre_before = t.height_at(32.0, 32.0);
+        let far_before = t.height_at(200.0, 200.0);
+
+        let chunks = t.deform(32.0, 32.0, 8.0, -3.0);
+        assert_eq!(chunks, vec![(0, 0)]);
+        assert!((t.height_at(32.0, 32.0) - (centre_before - 3.0)).abs() < 1e-4);
+        assert_eq!(t.height_at(200.0, 200.0), far_before);
+    }
+
+    #[test]
+    fn deform_refreshes_cached_chunks() {
+        let t = make_terrain(42);
+        let before = t.get_or_generate_chunk(0, 0, 0);
+        t.deform(32.0, 32.0, 8.0, 2.0);
+        let after = t.get_or_generate_chunk(0, 0, 0);
+        assert!(!std::sync::Arc::ptr_eq(&before, &after));
+        assert!(after.heights.iter().sum::<f32>() > before.heights.iter().sum::<f32>());
+    }
+
+    #[test]
+    fn deform_across_chunk_edge_reports_both_chunks() {
+        let t = make_terrain(42);
+        let chunks = t.deform(0.0, 10.0, 5.0, 1.0);
+        assert_eq!(chunks, vec![(-1, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn set_heights_pins_the_nearest_grid_sample() {
+        let t = make_terrain(42);
+        let neighbour = t.height_at(36.0, 32.0);
+        // (32.4, 31.7) snaps to the LOD 0 sample at (32, 32).
+        let chunks = t.set_heights(&[[32.4, 31.7, 50.0]]).unwrap();
+        assert_eq!(chunks, vec![(0, 0)]);
+        assert!((t.height_at(32.0, 32.0) - 50.0).abs() < 1e-4);
+        assert_eq!(t.height_at(36.0, 32.0), neighbour);
+        assert_eq!(t.height_override_count(), 1);
+        assert!(!t.client_regenerable(0, 0));
+        assert!(t.client_regenerable(1, 0));
+
+        // Runtime deformation stacks on top of the designer height.
+        t.deform(32.0, 32.0, 4.0, -2.0);
+        assert!((t.height_at(32.0, 32.0) - 48.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn set_heights_back_to_generated_clears_the_override() {
+        let t = make_terrain(42);
+        let generated = t.height_at(10.0, 10.0);
+        t.set_heights(&[[10.0, 10.0, generated + 5.0]]).unwrap();
+        t.set_heights(&[[10.0, 10.0, generated]]).unwrap();
+        assert_eq!(t.height_override_count(), 0);
+        assert!(t.client_regenerable(0, 0));
+        assert!((t.height_at(10.0, 10.0) - generated).abs() < 1e-4);
+    }
+
+    #[test]
+    fn set_heights_on_chunk_edge_reports_both_chunks() {
+        let t = make_terrain(42);
+        let chunks = t.set_heights(&[[64.0, 10.0, 3.0]]).unwrap();
+        assert_eq!(chunks, vec![(0, 0), (1, 0)]);
+        // Borders stay stitched: both sides of the seam agree.
+        let left = t.height_at(64.0 - 1e-3, 10.0);
+        let right = t.height_at(64.0 + 1e-3, 10.0);
+        assert!((left - right).abs() < 1e-2, "{} vs {}", left, right);
+    }
+
+    #[test]
+    fn height_overrides_survive_a_restart() {
+        let dir =
+            std::env::temp_dir().join(format!("janet_world_overrides_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let first = make_terrain(42).with_height_overrides(&dir).unwrap();
+        first.set_heights(&[[-20.0, 6.0, 40.0]]).unwrap();
+
+        let second = make_terrain(42).with_height_overrides(&dir).unwrap();
+        assert_eq!(second.height_override_count(), 1);
+        assert!((second.height_at(-20.0, 6.0) - 40.0).abs() < 1e-4);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn zero_delta_deform_is_a_no_op() {
+        let t = make_terrain(42);
+        assert!(t.deform(0.0, 0.0, 5.0, 0.0).is_empty());
+        assert!(t.deform(0.0, 0.0, 0.0, 1.0).is_empty());
+    }
+
+    // -----------------------------------------------------------------------
+    // Holes
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn holes_open_the_ground_without_moving_it() {
+        let t = make_terrain(42);
+        let before = t.height_at(5.0, 5.0);
+        // LOD 0 cells are 2 units; only cell (2, 2) has its centre in range.
+        assert_eq!(t.cut_holes(5.0, 5.0, 1.5), vec![(0, 0)]);
+        assert!(t.cut_holes(5.0, 5.0, 1.5).is_empty(), "already cut");
+
+        let holes = t.chunk_holes(0, 0).expect("holes");
+        assert_eq!(holes.cell_size, 2.0);
+        assert_eq!(holes.cells, vec![[2, 2]]);
+        assert!(t.chunk_holes(1, 0).is_none());
+
+        assert_eq!(t.height_at(5.0, 5.0), before);
+        assert!(t.is_hole(4.1, 5.9));
+        assert!(!t.is_hole(6.5, 5.0));
+        assert!(!t.solid_at(5.0, 5.0, before - 1.0));
+        assert!(t.solid_at(7.0, 5.0, t.height_at(7.0, 5.0) - 1.0));
+
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        assert!(t.raycast(Vec3::new(5.0, 5.0, 10.0), down, 20.0).is_none());
+        assert!(t.raycast(Vec3::new(7.0, 5.0, 10.0), down, 20.0).is_some());
+    }
+
+    #[test]
+    fn holes_index_negative_cells_from_their_chunk_corner() {
+        let t = make_terrain(42);
+        assert_eq!(t.cut_holes(-1.0, -1.0, 0.5), vec![(-1, -1)]);
+        assert_eq!(t.chunk_holes(-1, -1).expect("holes").cells, vec![[31, 31]]);
+    }
+
+    #[test]
+    fn solid_footprint_leaves_hole_cells_out() {
+        let holes = ChunkHoles {
+            cell_size: 2.0,
+            cells: vec![[1, 2]],
+        };
+        let boxes: Vec<_> = solid_footprint(8.0, &holes)
+            .into_iter()
+            .map(|(shape, centre)| match shape {
+                ColliderShape::Box { width, height } => (width, height, centre),
+                other => panic!("expected a box, got {:?}", other),
+            })
+            .collect();
+        // Rows above the hole, the runs either side of it, the row below.
+        assert_eq!(
+            boxes,
+            [
+                (8.0, 4.0, (4.0, 2.0)),
+                (2.0, 2.0, (1.0, 5.0)),
+                (4.0, 2.0, (6.0, 5.0)),
+                (8.0, 2.0, (4.0, 7.0)),
+            ]
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Chunk checksums
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn heights_checksum_is_fnv1a_over_little_endian_floats() {
+        assert_eq!(heights_checksum(&[]), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(heights_checksum(&[1.0]), 0x4b72_477f_9c5c_2f98);
+    }
+
+    #[test]
+    fn chunk_checksums_follow_seed_lod_and_edits() {
+        let t = make_terrain(42);
+        let sum = t
+            .chunk_checksum(0, 0, 0)
+            .expect("heightmaps have checksums");
+        assert_eq!(make_terrain(42).chunk_checksum(0, 0, 0), Some(sum));
+        assert_ne!(make_terrain(7).chunk_checksum(0, 0, 0), Some(sum));
+        assert_ne!(t.chunk_checksum(0, 0, 1), Some(sum));
+
+        t.deform(32.0, 32.0, 8.0, 2.0);
+        assert_ne!(t.chunk_checksum(0, 0, 0), Some(sum));
+        assert_eq!(FlatTerrain::new(0.0).chunk_checksum(0, 0, 0), None);
+    }
+
+    #[test]
+    fn only_uneroded_heightmaps_are_client_regenerable() {
+        assert!(make_terrain(42).client_regenerable(0, 0));
+        assert!(!make_terrain(42)
+            .with_erosion(ErosionConfig::default())
+            .client_regenerable(0, 0));
+        assert!(!FlatTerrain::new(0.0).client_regenerable(0, 0));
+    }
+
+    // -----------------------------------------------------------------------
+    // Placement queries
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn buildable_ground_is_dry_solid_and_gentle_enough() {
+        let t = make_terrain(42);
+        let slope = t.slope_at(5.0, 5.0);
+        assert!(slope > 0.0);
+        assert!(t.is_buildable(5.0, 5.0, slope + 1e-4));
+        assert!(!t.is_buildable(5.0, 5.0, slope - 1e-4));
+
+        t.cut_holes(5.0, 5.0, 1.5);
+        assert!(!t.is_buildable(5.0, 5.0, f32::INFINITY));
+
+        let flooded = make_terrain(42).with_hydrology(HydrologyConfig {
+            water_table: 1.0,
+            ..Default::default()
+        });
+        assert!(!flooded.is_buildable(5.0, 5.0, f32::INFINITY));
+    }
+
+    #[test]
+    fn region_stats_summarise_the_sampled_grid() {
+        let t = make_terrain(42);
+        let stats = t.sample_region_stats([0.0, 0.0], [30.0, 30.0]);
+        assert_eq!(stats.samples, REGION_STATS_SAMPLES * REGION_STATS_SAMPLES);
+        assert_eq!(
+            stats.min_height.min(t.height_at(0.0, 0.0)),
+            stats.min_height
+        );
+        assert_eq!(
+            stats.max_height.max(t.height_at(30.0, 30.0)),
+            stats.max_height
+        );
+        assert!(stats.min_height <= stats.mean_height && stats.mean_height <= stats.max_height);
+        assert!(stats.max_slope >= t.slope_at(0.0, 0.0));
+        assert_eq!((stats.water_fraction, stats.hole_fraction), (0.0, 0.0));
+
+        let flooded = make_terrain(42).with_hydrology(HydrologyConfig {
+            water_table: 1.0,
+            ..Default::default()
+        });
+        let stats = flooded.sample_region_stats([0.0, 0.0], [30.0, 30.0]);
+        assert_eq!(stats.water_fraction, 1.0);
+
+        let flat = FlatTerrain::new(3.0).sample_region_stats([-5.0, -5.0], [5.0, 5.0]);
+        assert_eq!(
+            (flat.min_height, flat.max_height, flat.mean_height),
+            (3.0, 3.0, 3.0)
+        );
+        assert_eq!(flat.max_slope, 0.0);
+    }
+
+    // -----------------------------------------------------------------------
+    // Disk chunk store
+    // -----------------------------------------------------------------------
+
+    fn store_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "janet_world_chunks_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn chunk_store_writes_back_and_reads_through() {
+        let dir = store_dir("roundtrip");
+        let first = make_terrain(42).with_chunk_store(&dir).unwrap();
+        let generated = first.get_or_generate_chunk(1, -2, 0);
+        assert!(
+            dir.join("1_-2_0.chunk").exists(),
+            "chunk should be written back"
+        );
+
+        // Fresh terrain (empty memory cache) reads the stored chunk.
+        let second = make_terrain(42).with_chunk_store(&dir).unwrap();
+        let loaded = second.get_or_generate_chunk(1, -2, 0);
+        assert_eq!(generated.heights, loaded.heights);
+        assert_eq!(generated.resolution, loaded.resolution);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn chunk_store_ignores_chunks_from_other_seeds() {
+        let dir = store_dir("seed");
+        let a = make_terrain(1).with_chunk_store(&dir).unwrap();
+        a.get_or_generate_chunk(0, 0, 0);
+
+        let b = make_terrain(999999).with_chunk_store(&dir).unwrap();
+        let chunk = b.get_or_generate_chunk(0, 0, 0);
+        let fresh = make_terrain(999999).get_or_generate_chunk(0, 0, 0);
+        assert_eq!(chunk.heights, fresh.heights);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn corrupt_chunk_file_is_regenerated() {
+        let dir = store_dir("corrupt");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("0_0_0.chunk"), b"garbage").unwrap();
+
+        let t = make_terrain(42).with_chunk_store(&dir).unwrap();
+        let chunk = t.get_or_generate_chunk(0, 0, 0);
+        assert_eq!(chunk.heights.len(), chunk.resolution * chunk.resolution);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn chunk_store_keeps_eroded_and_plain_chunks_apart() {
+        let dir = store_dir("erosion");
+        let plain = make_terrain(42).with_chunk_store(&dir).unwrap();
+        plain.get_or_generate_chunk(0, 0, 0);
+
+        let eroded = make_terrain(42)
+            .with_erosion(ErosionConfig::default())
+            .with_chunk_store(&dir)
+            .unwrap();
+        let chunk = eroded.get_or_generate_chunk(0, 0, 0);
+        let fresh = make_terrain(42)
+            .with_erosion(ErosionConfig::default())
+            .get_or_generate_chunk(0, 0, 0);
+        assert_eq!(chunk.heights, fresh.heights);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    // -----------------------------------------------------------------------
+    // Erosion
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn erosion_changes_terrain_deterministically() {
+        let plain = make_terrain(42).get_or_generate_chunk(0, 0, 0);
+        let a = make_terrain(42)
+            .with_erosion(ErosionConfig::default())
+            .get_or_generate_chunk(0, 0, 0);
+        let b = make_terrain(42)
+            .with_erosion(ErosionConfig::default())
+            .get_or_generate_chunk(0, 0, 0);
+        assert_eq!(a.heights, b.heights);
+        assert_ne!(a.heights, plain.heights);
+    }
+
+    #[test]
+    fn zero_iterations_is_a_no_op() {
+        let plain = make_terrain(42).get_or_generate_chunk(3, 1, 0);
+        let eroded = make_terrain(42)
+            .with_erosion(ErosionConfig {
+                iterations: 0,
+                ..Default::default()
+            })
+            .get_or_generate_chunk(3, 1, 0);
+        assert_eq!(plain.heights, eroded.heights);
+    }
+
+    #[test]
+    fn erosion_is_seamless_across_chunk_boundaries() {
+        // Same sample spacing, chunks twice as large: the big chunk must
+        // equal the four small chunks it covers.
+        // (Border stitching is per chunk size, so it is off here.)
+        let small = HeightmapTerrain::new(7, 32.0, 16)
+            .with_erosion(ErosionConfig::default())
+            .with_border_stitching(false);
+        let big = HeightmapTerrain::new(7, 64.0, 32)
+            .with_erosion(ErosionConfig::default())
+            .with_border_stitching(false);
+        let whole = big.get_or_generate_chunk(0, 0, 0);
+
+        for (cx, cy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let part = small.get_or_generate_chunk(cx, cy, 0);
+            for row in 0..16 {
+                for col in 0..16 {
+                    let w = whole.heights[(cy as usize * 16 + row) * 32 + cx as usize * 16 + col];
+                    assert_eq!(part.heights[row * 16 + col], w, "chunk ({}, {})", cx, cy);
+                }
+            }
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Hydrology
+    // -----------------------------------------------------------------------
+
+    /// First point along `y = 8` on a river centre line (off the chunk
+    /// border, which is stitched at the coarsest LOD's spacing).
+    fn river_point(seed: u64, cfg: &HydrologyConfig) -> (f32, f32) {
+        (0..5000)
+            .map(|i| (i as f32 * 0.5, 8.0))
+            .find(|&(x, y)| hydrology::river_factor(cfg, seed, x, y) > 0.99)
+            .expect("no river within 2500 units")
+    }
+
+    #[test]
+    fn hydrology_only_ever_lowers_terrain() {
+        let plain = make_terrain(42).get_or_generate_chunk(0, 0, 0);
+        let carved = make_terrain(42)
+            .with_hydrology(HydrologyConfig::default())
+            .get_or_generate_chunk(0, 0, 0);
+        for (c, p) in carved.heights.iter().zip(&plain.heights) {
+            assert!(c <= p);
+        }
+    }
+
+    #[test]
+    fn rivers_are_carved_and_hold_water() {
+        let cfg = HydrologyConfig::default();
+        let (x, y) = river_point(42, &cfg);
+        let plain = make_terrain(42);
+        let terrain = make_terrain(42).with_hydrology(cfg);
+
+        let bed = terrain.height_at(x, y);
+        assert!(bed < plain.height_at(x, y));
+        let level = terrain
+            .water_level_at(x, y)
+            .expect("river should hold water");
+        assert!(level > bed);
+        assert_eq!(plain.water_level_at(x, y), None);
+    }
+
+    #[test]
+    fn land_above_the_water_table_is_dry() {
+        let cfg = HydrologyConfig {
+            water_table: 0.0,
+            river_width: 0.0,
+            ..Default::default()
+        };
+        let terrain = make_terrain(42).with_hydrology(cfg);
+        assert_eq!(terrain.water_level_at(12.0, 34.0), None);
+    }
+
+    #[test]
+    fn lakes_fill_to_the_water_table() {
+        let cfg = HydrologyConfig {
+            water_table: 1.0,
+            ..Default::default()
+        };
+        let terrain = make_terrain(42).with_hydrology(cfg);
+        for (x, y) in [(0.0, 0.0), (17.0, -40.0), (300.0, 5.5)] {
+            assert_eq!(terrain.water_level_at(x, y), Some(1.0));
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Materials
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn cached_chunks_carry_normalised_material_weights() {
+        let chunk = make_terrain(42).get_or_generate_chunk(0, 0, 0);
+        assert_eq!(chunk.materials.len(), chunk.heights.len());
+        for w in &chunk.materials {
+            let sum = w.grass + w.rock + w.sand + w.snow;
+            assert!((sum - 1.0).abs() < 1e-4, "weights sum to {}", sum);
+        }
+    }
+
+    #[test]
+    fn material_weights_follow_height_and_slope() {
+        let rules = MaterialRules::default();
+        assert_eq!(rules.weights(0.05, 0.0).sand, 1.0);
+        assert_eq!(rules.weights(0.5, 0.0).grass, 1.0);
+        assert_eq!(rules.weights(0.95, 0.0).snow, 1.0);
+        assert_eq!(rules.weights(0.5, 1.0).rock, 1.0);
+    }
+
+    #[test]
+    fn deform_recomputes_material_weights() {
+        let terrain = make_terrain(42);
+        let before = terrain.get_or_generate_chunk(0, 0, 0).materials[16 * 32 + 16];
+        terrain.deform(32.0, 32.0, 6.0, 5.0);
+        let after = terrain.get_or_generate_chunk(0, 0, 0).materials[16 * 32 + 16];
+        assert_ne!(before, after);
+    }
+
+    // -----------------------------------------------------------------------
+    // Raycast
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn downward_ray_hits_the_ground() {
+        let terrain = make_terrain(42);
+        let ground = terrain.height_at(20.0, 30.0);
+        let hit = terrain
+            .raycast(
+                Vec3::new(20.0, 30.0, ground + 10.0),
+                Vec3::new(0.0, 0.0, -1.0),
+                50.0,
+            )
+            .expect("should hit");
+
+        assert!((hit.position.z - ground).abs() < 1e-3);
+        assert!((hit.distance - 10.0).abs() < 1e-2);
+        let n = hit.normal;
+        assert!(((n.x * n.x + n.y * n.y + n.z * n.z).sqrt() - 1.0).abs() < 1e-4);
+        assert!(n.z > 0.0);
+    }
+
+    #[test]
+    fn rays_that_stay_above_ground_miss() {
+        let terrain = make_terrain(42);
+        let start = Vec3::new(0.0, 0.0, terrain.height_at(0.0, 0.0) + 1.0);
+        assert!(terrain
+            .raycast(start, Vec3::new(0.0, 0.0, 1.0), 100.0)
+            .is_none());
+        assert!(terrain.raycast(start, Vec3::zero(), 100.0).is_none());
+        // Too short to reach the ground.
+        let high = Vec3::new(0.0, 0.0, terrain.height_at(0.0, 0.0) + 20.0);
+        assert!(terrain
+            .raycast(high, Vec3::new(0.0, 0.0, -1.0), 5.0)
+            .is_none());
+    }
+
+    #[test]
+    fn line_of_sight_is_blocked_by_terrain() {
+        let terrain = make_terrain(42);
+        let a = Vec3::new(0.0, 0.0, terrain.height_at(0.0, 0.0) + 1.0);
+        let sky = Vec3::new(40.0, 0.0, a.z + 1_000.0);
+        let buried = Vec3::new(40.0, 0.0, terrain.height_at(40.0, 0.0) - 5.0);
+        assert!(terrain.line_of_sight(a, sky));
+        assert!(!terrain.line_of_sight(a, buried));
+    }
+
+    // -----------------------------------------------------------------------
+    // Shoreline
+    // -----------------------------------------------------------------------
+
+    /// Ground rising one unit per unit of `x`.
+    struct Ramp;
+
+    impl TerrainSource for Ramp {
+        fn height_at(&self, x: f32, _y: f32) -> f32 {
+            x
+        }
+        fn normal_at(&self, _x: f32, _y: f32) -> Vec3 {
+            Vec3::new(-1.0, 0.0, 1.0)
+        }
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    #[test]
+    fn shoreline_follows_the_sea_level_contour() {
+        let segments = shoreline::segments(&Ramp, 0.0, 0.0, 64.0, 10.0);
+        assert_eq!(segments.len(), shoreline::SHORELINE_RESOLUTION);
+        for [x0, y0, x1, y1] in segments {
+            assert!((x0 - 10.0).abs() < 1e-4 && (x1 - 10.0).abs() < 1e-4);
+            assert!((0.0..=64.0).contains(&y0) && (0.0..=64.0).contains(&y1));
+        }
+    }
+
+    #[test]
+    fn chunks_away_from_the_coast_have_no_shoreline() {
+        assert!(shoreline::segments(&Ramp, 64.0, 0.0, 64.0, 10.0).is_empty());
+        assert!(shoreline::segments(&Ramp, -128.0, 0.0, 64.0, 10.0).is_empty());
+    }
+
+    // -----------------------------------------------------------------------
+    // Voxel overrides
+    // -----------------------------------------------------------------------
+
+    /// 1-unit voxels from 64 units below to 64 units above `make_terrain`'s
+    /// surface range.
+    fn voxel_layer() -> VoxelLayer {
+        VoxelLayer::new(64.0, 1.0, -64.0, 64.0)
+    }
+
+    #[test]
+    fn carved_cave_is_hollow_and_rays_pass_through_it() {
+        let ground = make_terrain(42).height_at(20.5, 20.5);
+        let mut voxels = voxel_layer();
+        voxels.fill_sphere(Vec3::new(20.5, 20.5, ground - 6.0), 3.0, VOXEL_AIR);
+        let terrain = make_terrain(42).with_voxels(voxels);
+
+        assert!(terrain.solid_at(20.5, 20.5, ground - 1.0));
+        assert!(!terrain.solid_at(20.5, 20.5, ground - 6.0));
+        assert!(terrain.solid_at(20.5, 20.5, ground - 10.0));
+
+        // Starting inside the cave, a downward ray hits the cave floor.
+        let hit = terrain
+            .raycast(
+                Vec3::new(20.5, 20.5, ground - 6.0),
+                Vec3::new(0.0, 0.0, -1.0),
+                10.0,
+            )
+            .expect("cave floor");
+        assert!((hit.distance - 3.0).abs() < 1.01);
+    }
+
+    #[test]
+    fn overhang_blocks_rays_above_the_surface() {
+        let ground = make_terrain(42).height_at(40.5, 40.5);
+        let mut voxels = voxel_layer();
+        voxels.fill_sphere(Vec3::new(40.5, 40.5, ground + 8.0), 1.5, 2);
+        let terrain = make_terrain(42).with_voxels(voxels);
+
+        let hit = terrain
+            .raycast(
+                Vec3::new(40.5, 40.5, ground + 20.0),
+                Vec3::new(0.0, 0.0, -1.0),
+                40.0,
+            )
+            .expect("overhang");
+        assert!(hit.position.z > ground + 5.0);
+        assert!(!terrain.line_of_sight(
+            Vec3::new(40.5, 40.5, ground + 20.0),
+            Vec3::new(40.5, 40.5, ground + 1.0)
+        ));
+    }
+
+    #[test]
+    fn solid_voxels_merge_into_boxes_and_encode_per_chunk() {
+        let mut voxels = voxel_layer();
+        voxels.fill_sphere(Vec3::new(10.0, 10.0, 0.0), 3.0, 2);
+
+        let solid = voxels
+            .chunk(0, 0)
+            .expect("edited chunk")
+            .cells
+            .iter()
+            .filter(|&&c| c > VOXEL_AIR)
+            .count();
+        let boxes = voxels.solid_boxes(0, 0);
+        assert!(!boxes.is_empty() && boxes.len() < solid);
+        let volume: f32 = boxes
+            .iter()
+            .map(|b| (b.max.x - b.min.x) * (b.max.y - b.min.y) * (b.max.z - b.min.z))
+            .sum();
+        assert_eq!(volume, solid as f32);
+
+        assert!(voxels.chunk_event(1, 0).is_none());
+        let event = voxels.chunk_event(0, 0).expect("event");
+        assert_eq!(event.dims, [64, 64, 128]);
+        let cells = event.cells.decode(64 * 64 * 128).expect("decode");
+        assert_eq!(cells, voxels.chunk(0, 0).unwrap().cells);
+    }
+
+    // -----------------------------------------------------------------------
+    // Seed regions
+    // -----------------------------------------------------------------------
+
+    /// Chunks 0..2 along x (64 units each) generated from seed 7.
+    fn island() -> SeedRegion {
+        SeedRegion {
+            min_x: 0.0,
+            min_y: 0.0,
+            max_x: 128.0,
+            max_y: 64.0,
+            seed: 7,
+        }
+    }
+
+    #[test]
+    fn chunk_seed_follows_the_region_holding_the_chunk_centre() {
+        let t = HeightmapTerrain::new(42, 64.0, 16).with_seed_regions(vec![island()]);
+        assert_eq!(t.chunk_seed(0, 0), 7);
+        assert_eq!(t.chunk_seed(1, 0), 7);
+        assert_eq!(t.chunk_seed(2, 0), 42);
+        assert_eq!(t.chunk_seed(0, -1), 42);
+    }
+
+    #[test]
+    fn region_chunks_match_terrain_generated_from_their_seed() {
+        // Stitched borders reach into the neighbour's corner, which may lie
+        // in another region; compare the generated samples themselves.
+        let t = HeightmapTerrain::new(42, 64.0, 16)
+            .with_seed_regions(vec![island()])
+            .with_border_stitching(false);
+        let island_world = HeightmapTerrain::new(7, 64.0, 16).with_border_stitching(false);
+        let ocean_world = HeightmapTerrain::new(42, 64.0, 16).with_border_stitching(false);
+
+        let inside = t.get_or_generate_chunk(1, 0, 0);
+        assert_eq!(
+            inside.heights,
+            island_world.get_or_generate_chunk(1, 0, 0).heights
+        );
+        let outside = t.get_or_generate_chunk(2, 0, 0);
+        assert_eq!(
+            outside.heights,
+            ocean_world.get_or_generate_chunk(2, 0, 0).heights
+        );
+    }
+
+    use janet_world::erosion::ErosionConfig;
+    use janet_world::hydrology;
+    use janet_world::protocol::VOXEL_AIR;
+    use janet_world::shoreline;
+    use janet_world::types::{HydrologyConfig, MaterialRules, SeedRegion, Vec3};
+    use janet_world::voxel::VoxelLayer;
     use std::sync::Arc;
 }
@@ -0,0 +1,153 @@
+//! Transform batch delta encoding tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::protocol::{EntityTransformBatch, HandleTransform, TRANSFORM_DELTA_QUANTUM};
+    use janet_world::transform_delta::{TransformDecoder, TransformEncoder};
+
+    fn transform(handle: u32, x: f32, y: f32) -> HandleTransform {
+        HandleTransform {
+            handle,
+            x,
+            y,
+            z: 1.5,
+            rotation_y: 0.25,
+            vx: 0.0,
+            vy: 0.0,
+            vz: 0.0,
+            dt: 1.0 / 30.0,
+            last_intent_seq: None,
+        }
+    }
+
+    fn batch(transforms: Vec<HandleTransform>) -> EntityTransformBatch {
+        EntityTransformBatch {
+            transforms,
+            ..Default::default()
+        }
+    }
+
+    /// Encode `transforms` as the next batch, returning what went out.
+    fn encode(
+        enc: &mut TransformEncoder,
+        transforms: Vec<HandleTransform>,
+    ) -> EntityTransformBatch {
+        let mut b = batch(transforms);
+        enc.encode(&mut b);
+        b
+    }
+
+    #[test]
+    fn keyframes_carry_everything_and_deltas_follow() {
+        let mut enc = TransformEncoder::new(3);
+        let first = encode(&mut enc, vec![transform(1, 0.0, 0.0)]);
+        assert_eq!((first.seq, first.keyframe), (Some(1), true));
+        assert_eq!(first.transforms.len(), 1);
+
+        let second = encode(&mut enc, vec![transform(1, 0.5, 0.0)]);
+        assert_eq!((second.seq, second.keyframe), (Some(2), false));
+        assert!(second.transforms.is_empty());
+        assert_eq!(second.deltas[0].dx, 500);
+        assert_eq!(second.deltas[0].dy, 0);
+
+        encode(&mut enc, vec![transform(1, 1.0, 0.0)]);
+        let fourth = encode(&mut enc, vec![transform(1, 1.5, 0.0)]);
+        assert!(fourth.keyframe);
+        assert!(fourth.deltas.is_empty());
+    }
+
+    #[test]
+    fn decoder_reconstructs_within_one_quantum() {
+        let mut enc = TransformEncoder::new(50);
+        let mut dec = TransformDecoder::new();
+        for step in 0..40 {
+            let t = step as f32;
+            let mut truth = transform(4, 100.0 + t * 0.123_456, -7.0 + t * 0.031);
+            truth.vx = 0.123_456 * 30.0;
+            truth.last_intent_seq = Some(step / 3);
+            let out = dec.apply(&encode(&mut enc, vec![truth.clone()]));
+            assert_eq!(out.len(), 1);
+            let got = &out[0];
+            assert!(
+                (got.x - truth.x).abs() <= TRANSFORM_DELTA_QUANTUM,
+                "step {}",
+                step
+            );
+            assert!(
+                (got.y - truth.y).abs() <= TRANSFORM_DELTA_QUANTUM,
+                "step {}",
+                step
+            );
+            assert!((got.vx - truth.vx).abs() <= TRANSFORM_DELTA_QUANTUM);
+            assert_eq!(got.dt, truth.dt);
+            assert_eq!(got.last_intent_seq, truth.last_intent_seq);
+        }
+    }
+
+    #[test]
+    fn resting_entities_cost_only_their_handle() {
+        let mut enc = TransformEncoder::new(10);
+        encode(&mut enc, vec![transform(9, 3.0, 4.0)]);
+        let b = encode(&mut enc, vec![transform(9, 3.0, 4.0)]);
+        let v = serde_json::to_value(&b.deltas[0]).unwrap();
+        assert_eq!(v, serde_json::json!({"handle": 9}));
+    }
+
+    #[test]
+    fn new_entities_between_keyframes_come_in_full() {
+        let mut enc = TransformEncoder::new(10);
+        encode(&mut enc, vec![transform(1, 0.0, 0.0)]);
+        let b = encode(
+            &mut enc,
+            vec![transform(1, 0.1, 0.0), transform(2, 5.0, 5.0)],
+        );
+        assert_eq!(b.transforms.len(), 1);
+        assert_eq!(b.transforms[0].handle, 2);
+        assert_eq!(b.deltas.len(), 1);
+        assert_eq!(b.deltas[0].handle, 1);
+    }
+
+    #[test]
+    fn lost_batches_drop_deltas_until_the_next_keyframe() {
+        let mut enc = TransformEncoder::new(4);
+        let mut dec = TransformDecoder::new();
+        assert_eq!(
+            dec.apply(&encode(&mut enc, vec![transform(1, 0.0, 0.0)]))
+                .len(),
+            1
+        );
+        encode(&mut enc, vec![transform(1, 1.0, 0.0)]); // lost
+        assert!(dec
+            .apply(&encode(&mut enc, vec![transform(1, 2.0, 0.0)]))
+            .is_empty());
+        assert!(dec
+            .apply(&encode(&mut enc, vec![transform(1, 3.0, 0.0)]))
+            .is_empty());
+
+        let out = dec.apply(&encode(&mut enc, vec![transform(1, 4.0, 0.0)]));
+        assert_eq!(out, vec![transform(1, 4.0, 0.0)]);
+        let out = dec.apply(&encode(&mut enc, vec![transform(1, 5.0, 0.0)]));
+        assert!((out[0].x - 5.0).abs() <= TRANSFORM_DELTA_QUANTUM);
+    }
+
+    #[test]
+    fn disabled_encoder_leaves_batches_alone() {
+        let mut enc = TransformEncoder::new(0);
+        encode(&mut enc, vec![transform(1, 0.0, 0.0)]);
+        let b = encode(&mut enc, vec![transform(1, 1.0, 0.0)]);
+        assert_eq!(b, batch(vec![transform(1, 1.0, 0.0)]));
+        assert_eq!(enc.seq(), 0);
+
+        let mut dec = TransformDecoder::new();
+        assert_eq!(dec.apply(&b), b.transforms);
+    }
+
+    #[test]
+    fn empty_batches_do_not_use_a_sequence_number() {
+        let mut enc = TransformEncoder::new(5);
+        let mut empty = EntityTransformBatch::default();
+        enc.encode(&mut empty);
+        assert_eq!(empty.seq, None);
+        assert_eq!(enc.seq(), 0);
+    }
+}
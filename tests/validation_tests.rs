@@ -0,0 +1,481 @@
+//! Startup config validation tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::bus::WorldBusConfig;
+    use janet_world::terrain::HeightmapTerrain;
+    use janet_world::types::{
+        AfkConfig, CellRegion, EdgeFill, HydrologyConfig, NoiseParams, SeedRegion, ShardConfig,
+        ShardRegion, WorldBounds, WorldServiceConfig,
+    };
+    use janet_world::validation::ConfigValidator;
+    use janet_world::worldgen::{
+        BiomeBand, PopulationRule, SpawnerDef, StructureDensity, WorldGenConfig,
+    };
+
+    fn keys(v: &ConfigValidator) -> Vec<&str> {
+        v.errors().iter().map(|e| e.key.as_str()).collect()
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        let cfg = WorldServiceConfig::default();
+        let terrain = HeightmapTerrain::new(42, cfg.cell_size * 4.0, 64);
+
+        let mut v = ConfigValidator::new();
+        v.check_service(&cfg);
+        v.check_tick_rate(30.0, cfg.physics_dt);
+        v.check_terrain(&terrain, &cfg);
+        assert!(v.finish().is_ok());
+    }
+
+    #[test]
+    fn all_problems_are_reported_together() {
+        let cfg = WorldServiceConfig {
+            cell_size: 0.0,
+            activation_radius: -1,
+            physics_dt: f32::NAN,
+            ..Default::default()
+        };
+
+        let mut v = ConfigValidator::new();
+        v.check_service(&cfg);
+        assert_eq!(
+            keys(&v),
+            vec!["cell_size", "physics_dt", "activation_radius"]
+        );
+
+        let message = v.finish().unwrap_err().to_string();
+        assert!(message.contains("3 problem(s)"), "{}", message);
+        assert!(message.contains("cell_size"), "{}", message);
+    }
+
+    #[test]
+    fn vertical_radius_is_only_checked_with_vertical_streaming() {
+        let flat = WorldServiceConfig {
+            vertical_activation_radius: -1,
+            ..Default::default()
+        };
+        let mut v = ConfigValidator::new();
+        v.check_service(&flat);
+        assert!(v.errors().is_empty());
+
+        let mut v = ConfigValidator::new();
+        v.check_service(&WorldServiceConfig {
+            vertical_cell_size: 8.0,
+            ..flat
+        });
+        assert_eq!(keys(&v), vec!["vertical_activation_radius"]);
+    }
+
+    #[test]
+    fn transform_delta_encoding_needs_batched_transforms() {
+        let mut v = ConfigValidator::new();
+        v.check_service(&WorldServiceConfig {
+            transform_keyframe_interval: 30,
+            ..Default::default()
+        });
+        assert_eq!(keys(&v), vec!["transform_keyframe_interval"]);
+
+        let mut v = ConfigValidator::new();
+        v.check_service(&WorldServiceConfig {
+            transform_keyframe_interval: 30,
+            handle_transforms: true,
+            ..Default::default()
+        });
+        assert!(v.errors().is_empty());
+    }
+
+    #[test]
+    fn cell_subjects_need_per_entity_transforms() {
+        let mut v = ConfigValidator::new();
+        v.check_service(&WorldServiceConfig {
+            cell_subjects: true,
+            handle_transforms: true,
+            ..Default::default()
+        });
+        assert_eq!(keys(&v), vec!["cell_subjects"]);
+
+        let mut v = ConfigValidator::new();
+        v.check_service(&WorldServiceConfig {
+            cell_subjects: true,
+            interest_cell_size: 0.0,
+            ..Default::default()
+        });
+        assert_eq!(keys(&v), vec!["interest_cell_size"]);
+    }
+
+    #[test]
+    fn tick_rate_must_match_physics_dt() {
+        let mut v = ConfigValidator::new();
+        v.check_tick_rate(60.0, 1.0 / 30.0);
+        assert_eq!(keys(&v), vec!["physics_dt"]);
+
+        let mut v = ConfigValidator::new();
+        v.check_tick_rate(0.0, 1.0 / 30.0);
+        assert_eq!(keys(&v), vec!["tick_rate_hz"]);
+    }
+
+    #[test]
+    fn streaming_window_must_cover_a_chunk() {
+        let cfg = WorldServiceConfig {
+            cell_size: 10.0,
+            activation_radius: 0,
+            ..Default::default()
+        };
+        let terrain = HeightmapTerrain::new(42, 64.0, 64);
+
+        let mut v = ConfigValidator::new();
+        v.check_terrain(&terrain, &cfg);
+        assert_eq!(keys(&v), vec!["activation_radius"]);
+    }
+
+    #[test]
+    fn lod_bands_must_increase() {
+        let cfg = WorldServiceConfig::default();
+        let terrain = HeightmapTerrain::new(42, 64.0, 64).with_lod_bands(vec![300.0, 100.0]);
+
+        let mut v = ConfigValidator::new();
+        v.check_terrain(&terrain, &cfg);
+        assert_eq!(keys(&v), vec!["lod_bands"]);
+    }
+
+    #[test]
+    fn too_many_lod_bands_for_resolution() {
+        let cfg = WorldServiceConfig::default();
+        let terrain = HeightmapTerrain::new(42, 64.0, 16).with_lod_bands(vec![50.0, 100.0, 200.0]);
+
+        let mut v = ConfigValidator::new();
+        v.check_terrain(&terrain, &cfg);
+        assert_eq!(keys(&v), vec!["lod_bands"]);
+    }
+
+    #[test]
+    fn hydrology_ranges_are_checked() {
+        let cfg = WorldServiceConfig::default();
+        let terrain = HeightmapTerrain::new(42, 64.0, 64).with_hydrology(HydrologyConfig {
+            water_table: 1.5,
+            river_width: 0.5,
+            ..Default::default()
+        });
+
+        let mut v = ConfigValidator::new();
+        v.check_terrain(&terrain, &cfg);
+        assert_eq!(
+            keys(&v),
+            vec!["hydrology.water_table", "hydrology.river_width"]
+        );
+    }
+
+    #[test]
+    fn world_gen_tables_are_checked() {
+        let mut gen = WorldGenConfig {
+            structures: vec![StructureDensity {
+                type_id: "props/cactus".into(),
+                biomes: vec!["dunes".into()],
+                per_chunk: 1.0,
+                radius: 0.5,
+                max_slope: None,
+            }],
+            ..Default::default()
+        };
+        gen.biomes.push(BiomeBand {
+            name: "peak".into(),
+            below: 0.5,
+        });
+        gen.noise.clear();
+        let terrain = HeightmapTerrain::from_config(&gen);
+
+        let mut v = ConfigValidator::new();
+        v.check_terrain(&terrain, &WorldServiceConfig::default());
+        v.check_worldgen(&gen);
+        assert_eq!(keys(&v), vec!["noise", "biomes", "structures.props/cactus"]);
+    }
+
+    #[test]
+    fn spawn_points_must_be_finite() {
+        let cfg = WorldServiceConfig {
+            spawn_points: vec![[0.0, 0.0], [f32::NAN, 4.0]],
+            ..Default::default()
+        };
+        let mut v = ConfigValidator::new();
+        v.check_service(&cfg);
+        assert_eq!(keys(&v), vec!["spawn_points"]);
+    }
+
+    #[test]
+    fn world_bounds_must_be_ordered_and_finite() {
+        let cfg = WorldServiceConfig {
+            world_bounds: Some(WorldBounds {
+                cells: CellRegion {
+                    min_cx: 4,
+                    min_cy: 0,
+                    max_cx: -4,
+                    max_cy: 8,
+                },
+                fill: EdgeFill::Ocean,
+                height: f32::INFINITY,
+            }),
+            ..Default::default()
+        };
+        let mut v = ConfigValidator::new();
+        v.check_service(&cfg);
+        assert_eq!(keys(&v), vec!["world_bounds", "world_bounds.height"]);
+    }
+
+    #[test]
+    fn noise_params_are_checked_and_must_match_the_terrain() {
+        let cfg = WorldServiceConfig {
+            noise: NoiseParams {
+                amplitude: 0.0,
+                octaves: 40,
+                vertical_exaggeration: 20.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let terrain = HeightmapTerrain::new(42, cfg.cell_size * 4.0, 64);
+        let mut v = ConfigValidator::new();
+        v.check_service(&cfg);
+        v.check_terrain(&terrain, &cfg);
+        assert_eq!(
+            keys(&v),
+            vec![
+                "noise.amplitude",
+                "noise.octaves",
+                "noise.vertical_exaggeration"
+            ]
+        );
+
+        let terrain = terrain.with_noise_params(cfg.noise);
+        let mut v = ConfigValidator::new();
+        v.check_terrain(&terrain, &cfg);
+        assert!(v.errors().is_empty());
+    }
+
+    #[test]
+    fn population_rules_are_checked() {
+        let rule = |archetype: &str, biome: &str, per_km2: f32, hours| PopulationRule {
+            archetype: archetype.into(),
+            biomes: vec![biome.into()],
+            per_km2,
+            hours,
+        };
+        let gen = WorldGenConfig {
+            population: vec![
+                rule("creature/wolf", "forest", 4.0, Some([20.0, 6.0])),
+                rule("creature/crab", "reef", 1.0, None),
+                rule("creature/bat", "forest", -1.0, None),
+                rule("creature/owl", "forest", 1.0, Some([22.0, 25.0])),
+            ],
+            ..Default::default()
+        };
+        let mut v = ConfigValidator::new();
+        v.check_worldgen(&gen);
+        assert_eq!(
+            keys(&v),
+            vec![
+                "population.creature/crab",
+                "population.creature/bat",
+                "population.creature/owl"
+            ]
+        );
+    }
+
+    #[test]
+    fn spawners_are_checked() {
+        let spawner = |id: &str, archetype: &str, max_population| SpawnerDef {
+            id: id.into(),
+            archetype: archetype.into(),
+            x: 0.0,
+            y: 0.0,
+            radius: 5.0,
+            max_population,
+            respawn_s: 30.0,
+            components: Default::default(),
+            behaviour: None,
+        };
+        let gen = WorldGenConfig {
+            spawners: vec![
+                spawner("den", "creature/wolf", 3),
+                spawner("den", "creature/deer", 2),
+                spawner("crowd", "participant", 1),
+                spawner("empty", "vehicle/cart", 0),
+                SpawnerDef {
+                    radius: -1.0,
+                    ..spawner("pit", "creature/bat", 1)
+                },
+            ],
+            ..Default::default()
+        };
+        let mut v = ConfigValidator::new();
+        v.check_worldgen(&gen);
+        assert_eq!(
+            keys(&v),
+            vec![
+                "spawners.den",
+                "spawners.crowd",
+                "spawners.empty",
+                "spawners.pit"
+            ]
+        );
+    }
+
+    #[test]
+    fn seed_regions_must_be_valid_and_match_the_terrain() {
+        let region = SeedRegion {
+            min_x: 0.0,
+            min_y: 0.0,
+            max_x: 128.0,
+            max_y: 128.0,
+            seed: 7,
+        };
+        let cfg = WorldServiceConfig {
+            seed_regions: vec![region],
+            ..Default::default()
+        };
+        let terrain = HeightmapTerrain::new(42, cfg.cell_size * 4.0, 64);
+        let mut v = ConfigValidator::new();
+        v.check_terrain(&terrain, &cfg);
+        assert_eq!(keys(&v), vec!["seed_regions"]);
+
+        let terrain = terrain.with_seed_regions(vec![region]);
+        let mut v = ConfigValidator::new();
+        v.check_service(&cfg);
+        v.check_terrain(&terrain, &cfg);
+        assert!(v.errors().is_empty());
+
+        let inverted = WorldServiceConfig {
+            seed_regions: vec![SeedRegion {
+                max_x: -1.0,
+                ..region
+            }],
+            ..Default::default()
+        };
+        let mut v = ConfigValidator::new();
+        v.check_service(&inverted);
+        assert_eq!(keys(&v), vec!["seed_regions"]);
+    }
+
+    #[test]
+    fn shard_config_is_checked() {
+        let region = |rx| ShardRegion {
+            rx,
+            ry: 0,
+            shard_id: "east".to_string(),
+        };
+        let cfg = WorldServiceConfig {
+            shard: Some(ShardConfig {
+                shard_id: "east.1".to_string(),
+                region_size: 0.0,
+                regions: vec![region(0), region(1), region(0)],
+                handoff_timeout_s: 0.0,
+            }),
+            ..Default::default()
+        };
+        let mut v = ConfigValidator::new();
+        v.check_service(&cfg);
+        assert_eq!(
+            keys(&v),
+            vec![
+                "shard.shard_id",
+                "shard.region_size",
+                "shard.handoff_timeout_s",
+                "shard.regions"
+            ]
+        );
+    }
+
+    #[test]
+    fn afk_config_is_checked() {
+        let cfg = WorldServiceConfig {
+            afk: Some(AfkConfig {
+                timeout_s: 0.0,
+                activation_radius: -1,
+                transform_interval_s: 1.0,
+            }),
+            ..Default::default()
+        };
+        let mut v = ConfigValidator::new();
+        v.check_service(&cfg);
+        assert_eq!(keys(&v), vec!["afk.timeout_s", "afk.activation_radius"]);
+    }
+
+    #[test]
+    fn failover_needs_a_checkpoint_dir_and_a_sane_timeout() {
+        let dir = std::env::temp_dir();
+        let mut v = ConfigValidator::new();
+        v.check_failover(&WorldBusConfig::default(), None);
+        assert!(v.errors().is_empty());
+
+        let standby = WorldBusConfig {
+            standby: true,
+            failover_timeout_s: 0.5,
+            ..Default::default()
+        };
+        let mut v = ConfigValidator::new();
+        v.check_failover(&standby, None);
+        assert_eq!(keys(&v), vec!["failover_timeout_s", "standby"]);
+
+        let mirroring = WorldBusConfig {
+            mirror_interval_s: 2.0,
+            ..Default::default()
+        };
+        let mut v = ConfigValidator::new();
+        v.check_failover(&mirroring, None);
+        assert_eq!(keys(&v), vec!["mirror_interval_s"]);
+
+        let mut v = ConfigValidator::new();
+        v.check_failover(&mirroring, Some(&dir));
+        assert!(v.errors().is_empty());
+    }
+
+    #[test]
+    fn session_must_be_a_subject_token() {
+        let mut v = ConfigValidator::new();
+        v.check_session(&WorldBusConfig::default());
+        assert!(v.errors().is_empty());
+
+        let mut v = ConfigValidator::new();
+        v.check_session(&WorldBusConfig {
+            session: "team.a".into(),
+            ..Default::default()
+        });
+        assert_eq!(keys(&v), vec!["session"]);
+    }
+
+    #[test]
+    fn negative_snapshot_cache_age_is_rejected() {
+        let mut v = ConfigValidator::new();
+        v.check_snapshot_cache(&WorldBusConfig {
+            snapshot_cache_s: -1.0,
+            ..Default::default()
+        });
+        assert_eq!(keys(&v), vec!["snapshot_cache_s"]);
+
+        let mut v = ConfigValidator::new();
+        v.check_snapshot_cache(&WorldBusConfig {
+            snapshot_page_bytes: 0,
+            ..Default::default()
+        });
+        assert_eq!(keys(&v), vec!["snapshot_page_bytes"]);
+    }
+
+    #[test]
+    fn unwritable_store_dir_is_rejected() {
+        // A regular file cannot be used as a directory.
+        let file =
+            std::env::temp_dir().join(format!("janet-world-validation-{}", std::process::id()));
+        std::fs::write(&file, b"not a dir").unwrap();
+
+        let mut v = ConfigValidator::new();
+        v.check_writable_dir("chunk_store_dir", &file);
+        assert_eq!(keys(&v), vec!["chunk_store_dir"]);
+
+        let mut v = ConfigValidator::new();
+        v.check_writable_dir("chunk_store_dir", &std::env::temp_dir());
+        assert!(v.errors().is_empty());
+
+        let _ = std::fs::remove_file(file);
+    }
+}
@@ -0,0 +1,68 @@
+//! Procedural vegetation tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::flat_terrain::FlatTerrain;
+    use janet_world::terrain::HeightmapTerrain;
+    use janet_world::types::WorldServiceConfig;
+    use janet_world::vegetation::{scatter, ROCK_KIND, TREE_BIOMES, TREE_KIND};
+
+    /// 10 m cells of 2 m tiles: 25 candidate spots each.
+    fn config(tree_density: f32) -> WorldServiceConfig {
+        WorldServiceConfig {
+            cell_size: 10.0,
+            tile_size_m: 2.0,
+            tree_density,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn placement_is_deterministic_and_stays_in_the_cell() {
+        let terrain = HeightmapTerrain::new(42, 64.0, 16);
+        let a = scatter(&config(0.5), &terrain, 3, -2, |_, _, _| true);
+        let b = scatter(&config(0.5), &terrain, 3, -2, |_, _, _| true);
+        assert!(!a.is_empty());
+        assert_eq!(
+            a.iter().map(|o| (&o.id, o.position)).collect::<Vec<_>>(),
+            b.iter().map(|o| (&o.id, o.position)).collect::<Vec<_>>()
+        );
+        for o in &a {
+            assert!((30.0..40.0).contains(&o.position.x), "{:?}", o.position);
+            assert!((-20.0..-10.0).contains(&o.position.y), "{:?}", o.position);
+            let biome = o.properties["biome"].as_str().expect("biome");
+            assert_eq!(
+                o.kind == TREE_KIND,
+                TREE_BIOMES.contains(&biome),
+                "{}",
+                biome
+            );
+        }
+
+        let other = scatter(&config(0.5), &terrain, 4, -2, |_, _, _| true);
+        assert_ne!(a[0].position, other[0].position);
+    }
+
+    #[test]
+    fn density_is_the_chance_per_tile() {
+        let terrain = FlatTerrain::new(0.0);
+        assert!(scatter(&config(0.0), &terrain, 0, 0, |_, _, _| true).is_empty());
+
+        // No biomes: every spot is wooded.
+        let full = scatter(&config(1.0), &terrain, 0, 0, |_, _, _| true);
+        assert_eq!(full.len(), 25);
+        assert!(full.iter().all(|o| o.kind == TREE_KIND));
+    }
+
+    #[test]
+    fn slope_and_buildability_choose_rocks_or_nothing() {
+        let terrain = FlatTerrain::new(0.0);
+        let steep = scatter(&config(1.0), &terrain, 0, 0, |_, _, max_slope| {
+            max_slope > 1.0
+        });
+        assert_eq!(steep.len(), 25);
+        assert!(steep.iter().all(|o| o.kind == ROCK_KIND));
+
+        assert!(scatter(&config(1.0), &terrain, 0, 0, |_, _, _| false).is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+//! Declarative world generation config tests
+
+#[cfg(test)]
+mod tests {
+    use janet_world::terrain::{HeightmapTerrain, TerrainSource};
+    use janet_world::types::NoiseParams;
+    use janet_world::worldgen::{apply_noise_params, default_noise_layers, WorldGenConfig};
+
+    #[test]
+    fn empty_file_reproduces_the_default_world() {
+        let gen = WorldGenConfig::from_toml("").expect("parse");
+        assert_eq!(gen, WorldGenConfig::default());
+
+        let configured = HeightmapTerrain::from_config(&gen);
+        let built = HeightmapTerrain::new(gen.seed, gen.chunk_size, gen.base_resolution);
+        for (x, y) in [(0.0, 0.0), (13.5, -7.25), (-120.0, 64.0)] {
+            assert_eq!(configured.height_at(x, y), built.height_at(x, y));
+        }
+    }
+
+    #[test]
+    fn toml_sets_noise_biomes_and_sea_level() {
+        let gen = WorldGenConfig::from_toml(
+            r#"
+            sea_level = 0.25
+
+            [[noise]]
+            weight = 0.0
+            frequency = 0.05
+            salt = 7
+
+            [[biomes]]
+            name = "flats"
+            below = 0.5
+
+            [[biomes]]
+            name = "hills"
+            below = 1.0
+            "#,
+        )
+        .expect("parse");
+        assert_eq!(gen.sea_level, Some(0.25));
+        assert_eq!(gen.noise.len(), 1);
+
+        let terrain = HeightmapTerrain::from_config(&gen);
+        assert_eq!(terrain.height_at(31.0, -9.0), 0.0);
+        assert_eq!(terrain.biome_at(31.0, -9.0), "flats");
+    }
+
+    #[test]
+    fn bad_toml_is_an_error() {
+        assert!(WorldGenConfig::from_toml("noise = 3").is_err());
+    }
+
+    #[test]
+    fn structures_scatter_deterministically_within_their_biomes() {
+        let gen = WorldGenConfig::from_toml(
+            r#"
+            structure_radius = 2
+
+            [[structures]]
+            type_id = "props/tree"
+            biomes = ["grass", "forest"]
+            per_chunk = 3.5
+            "#,
+        )
+        .expect("parse");
+        let terrain = HeightmapTerrain::from_config(&gen);
+
+        let first = gen.scatter_all(&terrain);
+        let again = gen.scatter_all(&terrain);
+        assert!(!first.is_empty());
+        assert_eq!(
+            first.iter().map(|s| &s.id).collect::<Vec<_>>(),
+            again.iter().map(|s| &s.id).collect::<Vec<_>>()
+        );
+        for s in &first {
+            let biome = terrain.biome_at(s.position.x, s.position.y);
+            assert!(
+                biome == "grass" || biome == "forest",
+                "{} in {}",
+                s.id,
+                biome
+            );
+            assert_eq!(s.position.z, terrain.height_at(s.position.x, s.position.y));
+            assert_eq!(s.metadata["type_id"], "props/tree");
+        }
+        // Never more than the whole and fractional candidates per chunk.
+        assert!(first.len() <= 4 * 16);
+    }
+
+    #[test]
+    fn structures_skip_ground_steeper_than_their_max_slope() {
+        let table = |max_slope: f32| {
+            WorldGenConfig::from_toml(&format!(
+                r#"
+                structure_radius = 2
+
+                [[structures]]
+                type_id = "props/hut"
+                per_chunk = 4
+                max_slope = {:?}
+                "#,
+                max_slope
+            ))
+            .expect("parse")
+        };
+        let gen = table(0.02);
+        let terrain = HeightmapTerrain::from_config(&gen);
+        let gentle = gen.scatter_all(&terrain);
+        for s in &gentle {
+            assert!(terrain.slope_at(s.position.x, s.position.y) <= 0.02);
+        }
+        assert!(gentle.len() < table(1e3).scatter_all(&terrain).len());
+    }
+
+    #[test]
+    fn noise_params_reshape_the_layers() {
+        let layers = default_noise_layers();
+        let params = |amplitude, frequency, octaves| NoiseParams {
+            amplitude,
+            frequency,
+            octaves,
+            ..Default::default()
+        };
+        assert_eq!(apply_noise_params(&layers, &NoiseParams::default()), layers);
+
+        let gentle = apply_noise_params(&layers, &params(0.5, 0.5, 2));
+        assert_eq!(gentle.len(), 2);
+        assert_eq!(gentle[1].weight, layers[1].weight * 0.5);
+        assert_eq!(gentle[1].frequency, layers[1].frequency * 0.5);
+
+        let rough = apply_noise_params(&layers, &params(1.0, 1.0, 5));
+        assert_eq!(&rough[..3], &layers[..]);
+        assert_eq!(rough[3].frequency, layers[2].frequency * 2.0);
+        assert_eq!(rough[4].weight, layers[2].weight * 0.25);
+        assert_ne!(rough[3].salt, rough[4].salt);
+    }
+
+    #[test]
+    fn vertical_exaggeration_scales_heights_and_splat_bands() {
+        let flat = HeightmapTerrain::new(42, 64.0, 16);
+        let tall = HeightmapTerrain::new(42, 64.0, 16).with_noise_params(NoiseParams {
+            vertical_exaggeration: 50.0,
+            ..Default::default()
+        });
+        for (x, y) in [(0.0, 0.0), (13.5, -7.25), (-120.0, 64.0)] {
+            let (a, b) = (flat.height_at(x, y), tall.height_at(x, y));
+            assert!((b - 50.0 * a).abs() < 1e-3, "{} vs {}", a, b);
+            assert_eq!(flat.biome_at(x, y), tall.biome_at(x, y));
+        }
+        assert_eq!(tall.materials.snow_min, flat.materials.snow_min * 50.0);
+
+        // Clients only regenerate the canonical terrain.
+        assert!(flat.client_regenerable(0, 0));
+        assert!(!tall.client_regenerable(0, 0));
+    }
+}